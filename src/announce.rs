@@ -0,0 +1,55 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+/// Writes a line describing the focus and the selection to a file or FIFO whenever they
+/// change, so that a screen reader can read what the TUI shows.
+pub struct Announcer {
+    sender: Option<mpsc::Sender<String>>,
+    last: String,
+}
+
+impl Announcer {
+    pub fn new(path: Option<&Path>) -> Self {
+        Self {
+            sender: path.map(|path| Self::spawn(path.to_path_buf())),
+            last: String::new(),
+        }
+    }
+
+    /// Writing happens in a thread, since opening a FIFO waits until it has a reader.
+    fn spawn(path: PathBuf) -> mpsc::Sender<String> {
+        let (sender, receiver) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            let mut file = match OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(file) => file,
+                Err(_) => return,
+            };
+            for line in receiver {
+                if writeln!(file, "{}", line)
+                    .and_then(|_| file.flush())
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        });
+        sender
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Writes `description` unless it is what was written last.
+    pub fn announce(&mut self, description: String) {
+        if description == self.last {
+            return;
+        }
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(description.clone());
+        }
+        self.last = description;
+    }
+}
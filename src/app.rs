@@ -1,79 +1,220 @@
-use crate::clipboard::copy_to_clipboard;
+use crate::announce::Announcer;
+use crate::clipboard::copy_in_background;
 use crate::components::{
+    favorites::Favorite,
+    recent_tables::RecentTable,
+    sql_editor::SqlFileAction,
     tab::Tab,
     {
-        command, ConnectionsComponent, DatabasesComponent, ErrorComponent, HelpComponent,
-        PropertiesComponent, RecordTableComponent, SqlEditorComponent, TabComponent,
+        bookmarks::Bookmark, command, command_line, ActivityComponent, BookmarksComponent,
+        CommandLineComponent, ConnectionsComponent, DashboardComponent, DatabasesComponent,
+        ErrorComponent, ExportProgressComponent, FavoritesComponent, HelpComponent, LogsComponent,
+        ObjectSearchComponent, PromptComponent, PropertiesComponent, RecentTablesComponent,
+        RecordTableComponent, RelationsComponent, RolesComponent, SessionSettingsComponent,
+        SqlEditorComponent, StatusBarComponent, TabComponent, TableComponent, TableCursor,
+        ToastComponent, TutorialComponent, TutorialProgress,
     },
 };
 use crate::components::{
-    CommandInfo, Component as _, DrawableComponent as _, EventState, StatefulDrawableComponent,
+    CommandInfo, Component, DrawableComponent as _, EventState, LineCommand,
+    StatefulDrawableComponent,
 };
-use crate::config::Config;
-use crate::database::{MySqlPool, Pool, PostgresPool, SqlitePool};
+use crate::config::{expand_path, Config, KeyConfig, KeyScope};
+use crate::database::{
+    check_setting_name, ddl, is_disconnect, CachedPool, ClickHousePool, ExecuteResult,
+    FailedStatement, HealthCheck, HealthEvent, LibSqlPool, LoggedPool, MetadataCache, MySqlPool,
+    Pool, PostgresPool, QueryLog, QueryTag, RetryPool, SchemaObject, SqlitePool, TaggedPool, Value,
+};
+use crate::diagram::{write_diagram_to_file, DiagramFormat, DiagramTable};
+use crate::event::chord::{format_sequence, Chords};
 use crate::event::Key;
+use crate::export::{format_rows, write_rows_to_file, ExportFormat, ExportJob};
+use crate::favorites::{FavoriteTable, Favorites};
+use crate::history::History;
+use crate::session::{Session, SessionTable};
+use crate::tree::{Database, Table};
 use anyhow::Context;
 use ratatui::layout::Flex;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     Frame,
 };
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub enum Focus {
     DatabaseList,
     Table,
     ConnectionList,
 }
+
+/// Where the user was in a table, kept while the connection is open.
+struct TableMemory {
+    cursor: TableCursor,
+    tab: Tab,
+    property_tab: crate::components::properties::Focus,
+    filter: String,
+    orders: Vec<(usize, bool)>,
+}
+
+/// The number of tables listed by `open_recent_tables`.
+const MAX_RECENT_TABLES: usize = 20;
+
 pub struct App {
     record_table: RecordTableComponent,
     properties: PropertiesComponent,
     sql_editor: SqlEditorComponent,
+    history: History,
     focus: Focus,
     tab: TabComponent,
     help: HelpComponent,
+    logs: LogsComponent,
     databases: DatabasesComponent,
     connections: ConnectionsComponent,
     pool: Option<Box<dyn Pool>>,
+    /// The metadata read through `pool`, cleared on a refresh.
+    metadata_cache: Arc<MetadataCache>,
+    /// Pings the server of `pool` and reconnects when it went away.
+    health: HealthCheck,
     left_main_chunk_percentage: u16,
+    table_memories: HashMap<(String, String), TableMemory>,
+    /// The tables opened on the connection, the latest first.
+    recent_tables: Vec<SessionTable>,
+    recent_table_list: RecentTablesComponent,
+    query_tag: QueryTag,
+    prompt: PromptComponent,
+    /// Asks for the path of the SQL file to open or save.
+    file_prompt: PromptComponent,
+    file_action: Option<SqlFileAction>,
+    export: ExportProgressComponent,
+    command_line: CommandLineComponent,
+    object_search: ObjectSearchComponent,
+    bookmarks: BookmarksComponent,
+    favorites: Favorites,
+    favorite_list: FavoritesComponent,
+    session_settings: SessionSettingsComponent,
+    dashboard: DashboardComponent,
+    activity: ActivityComponent,
+    roles: RolesComponent,
+    relations: RelationsComponent,
+    /// Opens the dashboard once the selected connection is connected to.
+    dashboard_requested: bool,
+    announcer: Announcer,
+    /// The steps of `--tutorial`, shown over everything else.
+    tutorial: Option<TutorialComponent>,
+    /// The name and the masked URL of the connection in use.
+    connection_label: Option<String>,
+    /// How long the last query for the records or in the SQL editor took.
+    query_time: Option<Duration>,
+    chords: Chords,
+    /// The keys of the tree, the tables and the SQL editor.
+    scoped_keys: HashMap<KeyScope, KeyConfig>,
     pub config: Config,
     pub error: ErrorComponent,
+    pub toast: ToastComponent,
 }
 
 impl App {
     pub fn new(config: Config) -> App {
-        Self {
+        let toast = ToastComponent::new();
+        let value_format = config.display.value_format();
+        let mut app = Self {
             config: config.clone(),
             connections: ConnectionsComponent::new(config.key_config.clone(), config.conn),
-            record_table: RecordTableComponent::new(config.key_config.clone()),
-            properties: PropertiesComponent::new(config.key_config.clone()),
-            sql_editor: SqlEditorComponent::new(config.key_config.clone()),
+            record_table: RecordTableComponent::new(config.key_config.scoped(KeyScope::Table)),
+            properties: PropertiesComponent::new(
+                config.key_config.scoped(KeyScope::Table),
+                toast.queue(),
+            ),
+            sql_editor: SqlEditorComponent::new(config.key_config.scoped(KeyScope::Editor))
+                .with_result_history(config.result_history)
+                .with_sql_format(config.sql_format.clone())
+                .with_editor_config(config.editor),
+            history: History::default(),
             tab: TabComponent::new(config.key_config.clone()),
             help: HelpComponent::new(config.key_config.clone()),
-            databases: DatabasesComponent::new(config.key_config.clone()),
+            logs: LogsComponent::new(config.key_config.scoped(KeyScope::Table), config.log_level),
+            databases: DatabasesComponent::new(
+                config.key_config.scoped(KeyScope::Tree),
+                config.display.clone(),
+            ),
+            query_tag: QueryTag::new(&config.query_tag),
+            prompt: PromptComponent::new(config.key_config.clone()),
+            file_prompt: PromptComponent::new(config.key_config.clone()),
+            file_action: None,
+            export: ExportProgressComponent::new(config.key_config.clone()),
+            command_line: CommandLineComponent::new(config.key_config.clone()),
+            object_search: ObjectSearchComponent::new(config.key_config.clone()),
+            bookmarks: BookmarksComponent::new(config.key_config.clone()),
+            favorites: Favorites::default(),
+            favorite_list: FavoritesComponent::new(config.key_config.clone()),
+            recent_table_list: RecentTablesComponent::new(config.key_config.clone()),
+            session_settings: SessionSettingsComponent::new(config.key_config.clone()),
+            dashboard: DashboardComponent::new(config.key_config.clone()),
+            activity: ActivityComponent::new(config.key_config.clone()),
+            roles: RolesComponent::new(config.key_config.clone()),
+            relations: RelationsComponent::new(config.key_config.clone()),
+            dashboard_requested: false,
+            announcer: Announcer::new(config.announce.as_deref()),
+            tutorial: None,
+            connection_label: None,
+            query_time: None,
+            chords: Chords::default(),
+            scoped_keys: [KeyScope::Tree, KeyScope::Table, KeyScope::Editor]
+                .into_iter()
+                .map(|scope| (scope, config.key_config.scoped(scope)))
+                .collect(),
             error: ErrorComponent::new(config.key_config),
+            toast,
             focus: Focus::ConnectionList,
             pool: None,
+            metadata_cache: Arc::new(MetadataCache::default()),
+            health: HealthCheck::default(),
             left_main_chunk_percentage: 15,
-        }
+            table_memories: HashMap::new(),
+            recent_tables: Vec::new(),
+        };
+        app.record_table.table.set_value_format(value_format);
+        app.sql_editor.table_mut().set_value_format(value_format);
+        app
     }
 
     pub fn draw(&mut self, f: &mut Frame) -> anyhow::Result<()> {
+        let screen = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(f.size());
+        self.draw_status_bar(f, screen[1])?;
         if let Focus::ConnectionList = self.focus {
-            match self.connections.draw(
-                f,
-                Layout::default()
-                    .constraints([Constraint::Percentage(100)])
-                    .split(f.size())[0],
-                false,
-            ) {
+            // the log is shown below the connections, e.g. to see why connecting failed
+            let area = if matches!(self.tab.selected_tab, Tab::Logs) {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(screen[0]);
+                self.logs.draw(f, chunks[1], false)?;
+                chunks[0]
+            } else {
+                screen[0]
+            };
+            match self.connections.draw(f, area, false) {
                 Ok(()) => (),
                 Err(e) => {
                     return Err(anyhow::anyhow!(e).context("from: ConnectionsComponent::draw"));
                 }
             }
 
+            self.prompt.draw(f, Rect::default(), false)?;
+            self.command_line.draw(f, Rect::default(), false)?;
+            if let Some(tutorial) = self.tutorial.as_ref() {
+                tutorial.draw(f, Rect::default(), false)?;
+            }
             self.error.draw(f, Rect::default(), false)?;
             self.help.draw(f, Rect::default(), false)?;
+            self.toast.update();
+            self.toast.draw(f, Rect::default(), false)?;
             return Ok(());
         }
 
@@ -83,7 +224,7 @@ impl App {
                 Constraint::Percentage(self.left_main_chunk_percentage),
                 Constraint::Percentage((100_u16).saturating_sub(self.left_main_chunk_percentage)),
             ])
-            .split(f.size());
+            .split(screen[0]);
 
         self.databases
             .draw(f, main_chunks[0], matches!(self.focus, Focus::DatabaseList))?;
@@ -94,6 +235,7 @@ impl App {
             .constraints([Constraint::Length(3), Constraint::Length(5)].as_ref())
             .split(main_chunks[1]);
 
+        self.tab.set_status(self.sql_editor.transaction_status());
         self.tab.draw(f, right_chunks[0], false)?;
 
         match self.tab.selected_tab {
@@ -109,70 +251,336 @@ impl App {
                 self.properties
                     .draw(f, right_chunks[1], matches!(self.focus, Focus::Table))?;
             }
+            Tab::Logs => {
+                self.logs
+                    .draw(f, right_chunks[1], matches!(self.focus, Focus::Table))?;
+            }
+        }
+        self.export.draw(f, Rect::default(), false)?;
+        self.file_prompt.draw(f, Rect::default(), false)?;
+        self.command_line.draw(f, Rect::default(), false)?;
+        self.object_search.draw(f, Rect::default(), false)?;
+        self.bookmarks.draw(f, Rect::default(), false)?;
+        self.favorite_list.draw(f, Rect::default(), false)?;
+        self.recent_table_list.draw(f, Rect::default(), false)?;
+        self.session_settings.draw(f, Rect::default(), false)?;
+        self.dashboard.draw(f, Rect::default(), false)?;
+        self.activity.draw(f, Rect::default(), false)?;
+        self.roles.draw(f, Rect::default(), false)?;
+        self.relations.draw(f, Rect::default(), false)?;
+        if let Some(tutorial) = self.tutorial.as_ref() {
+            tutorial.draw(f, Rect::default(), false)?;
         }
         self.error.draw(f, Rect::default(), false)?;
         self.help.draw(f, Rect::default(), false)?;
+        self.toast.update();
+        self.toast.draw(f, Rect::default(), false)?;
         Ok(())
     }
 
+    fn draw_status_bar(&mut self, f: &mut Frame, area: Rect) -> anyhow::Result<()> {
+        if let Some(query_time) = self.sql_editor.take_query_time() {
+            self.query_time = Some(query_time);
+        }
+        let location =
+            self.record_table
+                .table
+                .table()
+                .map(|(database, table)| match &table.schema {
+                    Some(schema) => format!("{}.{}.{}", database.name, schema, table.name),
+                    None => format!("{}.{}", database.name, table.name),
+                });
+        let rows = match self.tab.selected_tab {
+            _ if self.pool.is_none() => None,
+            Tab::Records if !self.record_table.table.headers.is_empty() => Some((
+                self.record_table.table.row_count(),
+                self.record_table.table.total_row_count,
+            )),
+            Tab::Sql => self.sql_editor.row_counts(),
+            _ => None,
+        };
+        StatusBarComponent::new(
+            self.connection_label
+                .clone()
+                .filter(|_| self.pool.is_some())
+                .map(|label| {
+                    if self.health.is_reconnecting() {
+                        format!("{} (reconnecting…)", label)
+                    } else {
+                        label
+                    }
+                }),
+            location,
+            rows,
+            self.query_time,
+            self.hint(),
+        )
+        .draw(f, area, false)
+    }
+
+    /// Returns the keys where the focus is, which may be bound differently from the global keys.
+    fn key_config(&self) -> &KeyConfig {
+        let scope = match self.focus {
+            Focus::ConnectionList => return &self.config.key_config,
+            Focus::DatabaseList => KeyScope::Tree,
+            Focus::Table if matches!(self.tab.selected_tab, Tab::Sql) => KeyScope::Editor,
+            Focus::Table => KeyScope::Table,
+        };
+        &self.scoped_keys[&scope]
+    }
+
+    /// Returns the keys which do something where the focus is.
+    fn hint(&self) -> String {
+        let k = self.key_config();
+        if self.error.is_visible() {
+            return format!(
+                "close [{}] select [{}/{}] scroll [{}/{}]",
+                k.exit_popup,
+                k.scroll_up,
+                k.scroll_down,
+                k.scroll_up_multiple_lines,
+                k.scroll_down_multiple_lines
+            );
+        }
+        if !self.chords.pending().is_empty() {
+            let next = self
+                .chords
+                .continuations()
+                .into_iter()
+                .map(|(key, action)| format!("{} [{}]", action.replace('_', " "), key))
+                .collect::<Vec<String>>();
+            return format!(
+                "{} …  {}",
+                format_sequence(self.chords.pending()),
+                next.join("  ")
+            );
+        }
+        match self.focus {
+            Focus::ConnectionList => format!(
+                "connect [{}]  dashboard [{}]  help [{}]",
+                k.enter, k.open_dashboard, k.open_help
+            ),
+            Focus::DatabaseList => format!(
+                "open [{}]  filter [{}]  help [{}]",
+                k.enter, k.filter, k.open_help
+            ),
+            Focus::Table => match self.tab.selected_tab {
+                Tab::Records if self.record_table.filter_focused() => {
+                    format!("apply [{}]  cancel [{}]", k.enter, k.exit_popup)
+                }
+                Tab::Sql if !self.sql_editor.is_table_focused() => {
+                    format!("run [{}]  $EDITOR [{}]", k.enter, k.edit_in_external_editor)
+                }
+                Tab::Logs => format!(
+                    "errors/all [{}]  scroll [{}/{}]  newest [{}]",
+                    k.filter, k.scroll_up, k.scroll_down, k.scroll_to_bottom
+                ),
+                _ => format!(
+                    "filter [{}]  sort [{}]  copy [{}]  help [{}]",
+                    k.filter, k.sort_by_column, k.copy, k.open_help
+                ),
+            },
+        }
+    }
+
     fn update_commands(&mut self) {
         self.help.set_cmds(self.commands());
     }
 
     fn commands(&self) -> Vec<CommandInfo> {
         let mut res = vec![
-            CommandInfo::new(command::exit_pop_up(&self.config.key_config)),
-            CommandInfo::new(command::filter(&self.config.key_config)),
-            CommandInfo::new(command::help(&self.config.key_config)),
-            CommandInfo::new(command::toggle_tabs(&self.config.key_config)),
-            CommandInfo::new(command::scroll(&self.config.key_config)),
-            CommandInfo::new(command::scroll_to_top_bottom(&self.config.key_config)),
-            CommandInfo::new(command::scroll_up_down_multiple_lines(
-                &self.config.key_config,
-            )),
-            CommandInfo::new(command::move_focus(&self.config.key_config)),
-            CommandInfo::new(command::extend_or_shorten_widget_width(
-                &self.config.key_config,
-            )),
+            CommandInfo::new(command::exit_pop_up(self.key_config())),
+            CommandInfo::new(command::filter(self.key_config())),
+            CommandInfo::new(command::help(self.key_config())),
+            CommandInfo::new(command::command_line(self.key_config())),
+            CommandInfo::new(command::search_objects(self.key_config())),
+            CommandInfo::new(command::open_bookmarks(self.key_config())),
+            CommandInfo::new(command::toggle_favorite(self.key_config())),
+            CommandInfo::new(command::open_favorites(self.key_config())),
+            CommandInfo::new(command::open_recent_tables(self.key_config())),
+            CommandInfo::new(command::open_errors(self.key_config())),
+            CommandInfo::new(command::session_settings(self.key_config())),
+            CommandInfo::new(command::open_dashboard(self.key_config())),
+            CommandInfo::new(command::open_activity(self.key_config())),
+            CommandInfo::new(command::open_roles(self.key_config())),
+            CommandInfo::new(command::open_relations(self.key_config())),
+            CommandInfo::new(command::toggle_tabs(self.key_config())),
+            CommandInfo::new(command::scroll(self.key_config())),
+            CommandInfo::new(command::scroll_to_top_bottom(self.key_config())),
+            CommandInfo::new(command::scroll_up_down_multiple_lines(self.key_config())),
+            CommandInfo::new(command::move_focus(self.key_config())),
+            CommandInfo::new(command::extend_or_shorten_widget_width(self.key_config())),
+            CommandInfo::new(command::refresh(self.key_config())),
         ];
 
+        for (sequence, action) in self.chords.list() {
+            res.push(CommandInfo::new(command::chord(&sequence, action)));
+        }
+
         self.databases.commands(&mut res);
         self.record_table.commands(&mut res);
         self.properties.commands(&mut res);
+        self.sql_editor.commands(&mut res);
 
         res
     }
 
+    /// Asks for the query tag fields which are not known yet, then connects.
+    async fn connect(&mut self) -> anyhow::Result<()> {
+        match self.query_tag.pending_field() {
+            Some(field) => {
+                self.prompt.open(format!("Query tag: {}", field));
+                Ok(())
+            }
+            None => self.update_databases().await,
+        }
+    }
+
     async fn update_databases(&mut self) -> anyhow::Result<()> {
         if let Some(conn) = self.connections.selected_connection() {
             if let Some(pool) = self.pool.as_ref() {
+                if self.sql_editor.in_transaction() {
+                    self.toast.push("Rolled back the open transaction");
+                }
                 pool.close().await;
             }
 
+            crate::log!(
+                Info,
+                "Connecting to {}",
+                conn.database_url_with_name().unwrap_or_default()
+            );
+            let memory_budget = conn.memory_budget(self.config.memory_budget());
             match conn.database_url() {
                 Ok(url) => {
-                    self.pool = if conn.is_mysql() {
-                        Some(Box::new(
-                            MySqlPool::new(url.as_str(), conn.limit_size, conn.timeout_second)
-                                .await?,
-                        ))
-                    } else if conn.is_postgres() {
-                        Some(Box::new(
-                            PostgresPool::new(url.as_str(), conn.limit_size, conn.timeout_second)
-                                .await?,
-                        ))
+                    let pool: Box<dyn Pool> = if conn.is_mysql() {
+                        Box::new(
+                            MySqlPool::new(
+                                url.as_str(),
+                                conn.limit_size,
+                                conn.timeout_second,
+                                memory_budget,
+                                &conn.session_settings(),
+                            )
+                            .await?,
+                        )
+                    } else if let Some(server) = conn.postgres_server() {
+                        Box::new(
+                            PostgresPool::new(
+                                url.as_str(),
+                                conn.limit_size,
+                                conn.timeout_second,
+                                memory_budget,
+                                &conn.session_settings(),
+                                server,
+                            )
+                            .await?,
+                        )
+                    } else if conn.is_clickhouse() {
+                        Box::new(
+                            ClickHousePool::new(
+                                url.as_str(),
+                                conn.limit_size,
+                                conn.timeout_second,
+                                memory_budget,
+                                &conn.session_settings(),
+                            )
+                            .await?,
+                        )
+                    } else if conn.is_libsql() {
+                        Box::new(
+                            LibSqlPool::new(
+                                url.as_str(),
+                                conn.limit_size,
+                                conn.timeout_second,
+                                memory_budget,
+                                &conn.session_settings(),
+                            )
+                            .await?,
+                        )
                     } else {
-                        Some(Box::new(
-                            SqlitePool::new(url.as_str(), conn.limit_size, conn.timeout_second)
-                                .await?,
-                        ))
+                        Box::new(
+                            SqlitePool::new(
+                                url.as_str(),
+                                conn.limit_size,
+                                conn.timeout_second,
+                                memory_budget,
+                                &conn.session_settings(),
+                                conn.key(),
+                            )
+                            .await?,
+                        )
+                    };
+                    let pool: Box<dyn Pool> =
+                        Box::new(RetryPool::new(pool, conn.retry_count, self.toast.queue()));
+                    let pool: Box<dyn Pool> = match self.query_tag.comment() {
+                        Some(comment) => Box::new(TaggedPool::new(pool, comment)),
+                        None => pool,
+                    };
+                    // the log has the statements as they were written, without the tag
+                    let pool: Box<dyn Pool> = match &self.config.query_log {
+                        Some(log) if log.enabled => Box::new(LoggedPool::new(
+                            pool,
+                            QueryLog::new(log.path.clone(), conn.session_key()),
+                        )),
+                        _ => pool,
                     };
+                    self.metadata_cache = Arc::new(if conn.metadata_cache_on_disk {
+                        MetadataCache::on_disk(&conn.session_key())?
+                    } else {
+                        MetadataCache::default()
+                    });
+                    self.pool = Some(Box::new(CachedPool::new(
+                        pool,
+                        Arc::clone(&self.metadata_cache),
+                    )));
+                    self.connection_label = conn.database_url_with_name().ok();
+                    self.health = HealthCheck::new(conn.keepalive_second);
+                    self.query_time = None;
+                    let capabilities = self.pool.as_ref().unwrap().capabilities();
+                    crate::log!(Info, "Connected to {}", capabilities);
+                    let missing = capabilities.missing();
+                    if !missing.is_empty() {
+                        self.toast.push(format!(
+                            "{} does not support {}",
+                            capabilities,
+                            missing
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
+                    }
                     self.databases
                         .update(conn, self.pool.as_ref().unwrap())
                         .await?;
+                    self.object_search.reset();
+                    self.bookmarks.clear();
+                    self.properties.set_dialect(conn.dialect());
+                    self.activity.set_dialect(conn.dialect());
+                    self.sql_editor.set_dialect(conn.dialect());
+                    self.sql_editor.set_transaction_mode(conn.transaction_mode);
+                    self.sql_editor
+                        .set_auto_limit(conn.auto_limit.then_some(conn.limit_size));
+                    self.sql_editor.set_confirm_destructive(
+                        conn.confirm_destructive
+                            .unwrap_or(self.config.confirm_destructive),
+                    );
+                    self.sql_editor.set_history(
+                        self.history
+                            .sql
+                            .get(&conn.session_key())
+                            .cloned()
+                            .unwrap_or_default(),
+                    );
                     self.focus = Focus::DatabaseList;
                     self.record_table.reset();
                     self.tab.reset();
+                    self.table_memories.clear();
+                    self.recent_tables.clear();
+                    if std::mem::take(&mut self.dashboard_requested) {
+                        self.open_dashboard().await?;
+                    }
                 }
                 Err(e) => {
                     return Err(anyhow::anyhow!(e)).context("from Connection::database_url");
@@ -182,39 +590,91 @@ impl App {
         Ok(())
     }
 
+    /// Fetches the overview of the server of the connection in use and shows it.
+    async fn open_dashboard(&mut self) -> anyhow::Result<()> {
+        if let Some(pool) = self.pool.as_ref() {
+            let overview = pool.get_server_overview().await?;
+            self.dashboard
+                .open(self.connection_label.clone().unwrap_or_default(), overview);
+        }
+        Ok(())
+    }
+
+    async fn open_activity(&mut self) -> anyhow::Result<()> {
+        if let Some(pool) = self.pool.as_ref() {
+            self.activity.open(pool.get_sessions().await?);
+        }
+        Ok(())
+    }
+
+    async fn open_roles(&mut self) -> anyhow::Result<()> {
+        if let Some(pool) = self.pool.as_ref() {
+            self.roles.open(pool.get_roles().await?);
+        }
+        Ok(())
+    }
+
+    /// Draws the foreign keys from and to the table selected in the tree.
+    async fn open_relations(&mut self) -> anyhow::Result<()> {
+        if let (Some(pool), Some((database, table))) =
+            (self.pool.as_ref(), self.databases.tree().selected_table())
+        {
+            let relations = pool.get_relations(&database, &table).await?;
+            self.relations.open(database, table, relations);
+        }
+        Ok(())
+    }
+
+    /// Runs the confirmed statement which ends a session, then lists the sessions again.
+    async fn kill_session(&mut self, statement: String) -> anyhow::Result<()> {
+        let pool = match self.pool.as_ref() {
+            Some(pool) => pool,
+            None => return Ok(()),
+        };
+        let result = pool
+            .execute(&statement)
+            .await
+            .map_err(|e| e.context(FailedStatement(statement.clone())))?;
+        // pg_terminate_backend answers false if the session was gone or not ours to end
+        if let ExecuteResult::Read { rows, .. } = result {
+            if rows.first().and_then(|row| row.first()) == Some(&Value::Bool(false)) {
+                anyhow::bail!("The server did not end the session");
+            }
+        }
+        self.toast.push("Ended the session");
+        self.open_activity().await
+    }
+
     async fn update_record_table(&mut self, hold_cursor_position: bool) -> anyhow::Result<()> {
-        if let Some((database, table)) = self.databases.tree().selected_table() {
+        if let Some((database, table)) = self
+            .databases
+            .tree()
+            .selected_table()
+            .filter(|(_, table)| !table.kind.is_routine())
+        {
+            let started_at = Instant::now();
             let order_query = self.record_table.table.generate_order_query();
-            let (headers, records) = self
-                .pool
-                .as_ref()
-                .unwrap()
-                .get_records(
+            let pool = self.pool.as_ref().unwrap();
+            let (headers, records) = if self.record_table.is_sample() {
+                pool.get_sample(&database, &table, self.record_table.filter.query())
+                    .await?
+            } else {
+                pool.get_records(
                     &database,
                     &table,
                     0,
-                    if self.record_table.filter.input_str().is_empty() {
-                        None
-                    } else {
-                        Some(self.record_table.filter.input_str())
-                    },
+                    self.record_table.filter.query(),
                     order_query,
                 )
-                .await?;
+                .await?
+            };
             let total_row_count = self
                 .pool
                 .as_ref()
                 .unwrap()
-                .get_total_row_count(
-                    &database,
-                    &table,
-                    if self.record_table.filter.input_str().is_empty() {
-                        None
-                    } else {
-                        Some(self.record_table.filter.input_str())
-                    },
-                )
+                .get_total_row_count(&database, &table, self.record_table.filter.query())
                 .await?;
+            self.query_time = Some(started_at.elapsed());
 
             let header_icons = self.record_table.table.generate_header_icons(headers.len());
             self.record_table.update(
@@ -225,21 +685,441 @@ impl App {
                 table.clone(),
                 hold_cursor_position,
             );
+            if let Some(connection) = self.connections.selected_connection() {
+                self.record_table.table.set_page_size(
+                    Some(connection.limit_size).filter(|_| !self.record_table.is_sample()),
+                );
+                let key = History::table_key(&connection.session_key(), &database, &table);
+                self.record_table
+                    .filter
+                    .set_history(self.history.filters.get(&key).cloned().unwrap_or_default());
+            }
+            if self.record_table.is_sample() {
+                // a sample is a single page, so no more rows are loaded on scrolling
+                self.record_table.table.end();
+                self.record_table.table.set_warning(Some(format!(
+                    "random sample, press {} to show the first rows",
+                    self.key_config().toggle_sample
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn table_memory_key(database: &Database, table: &Table) -> (String, String) {
+        (database.name.clone(), table.qualified_name())
+    }
+
+    /// Saves where the user was in the currently opened table.
+    fn remember_table(&mut self) {
+        if let Some((database, table)) = self.record_table.table.table() {
+            self.table_memories.insert(
+                Self::table_memory_key(database, table),
+                TableMemory {
+                    cursor: self.record_table.table.cursor(),
+                    tab: self.tab.selected_tab,
+                    property_tab: self.properties.selected_tab(),
+                    filter: self.record_table.filter.input_str(),
+                    orders: self.record_table.table.orders(),
+                },
+            );
+        }
+    }
+
+    fn restore_table(&mut self, database: &Database, table: &Table) {
+        if let Some(memory) = self
+            .table_memories
+            .get(&Self::table_memory_key(database, table))
+        {
+            self.record_table.table.restore_cursor(memory.cursor);
+            self.tab.selected_tab = memory.tab;
+            self.properties.select_tab(memory.property_tab);
+        }
+    }
+
+    /// Loads the table selected in the tree and moves the focus to it.
+    async fn open_table(&mut self) -> anyhow::Result<()> {
+        if let Some((database, table)) = self.databases.tree().selected_table() {
+            self.remember_table();
+            self.record_table.reset();
+            let recent = SessionTable::new(&database, &table);
+            self.recent_tables.retain(|table| *table != recent);
+            self.recent_tables.insert(0, recent);
+            self.recent_tables.truncate(MAX_RECENT_TABLES);
+            if table.kind.is_routine() {
+                // a routine has no rows, so its source is shown instead
+                self.properties
+                    .update(database, table, self.pool.as_ref().unwrap())
+                    .await?;
+                self.properties
+                    .select_tab(crate::components::properties::Focus::Definition);
+                self.tab.selected_tab = Tab::Properties;
+                self.focus = Focus::Table;
+                return Ok(());
+            }
+            let started_at = Instant::now();
+            let (headers, records) = self
+                .pool
+                .as_ref()
+                .unwrap()
+                .get_records(&database, &table, 0, None, None)
+                .await?;
+            // the total count is informative, so do not block the records on it
+            let total_row_count = self
+                .pool
+                .as_ref()
+                .unwrap()
+                .get_total_row_count(&database, &table, None)
+                .await
+                .ok();
+            self.query_time = Some(started_at.elapsed());
+            self.record_table.update(
+                records,
+                total_row_count,
+                headers,
+                database.clone(),
+                table.clone(),
+                false,
+            );
+            self.properties
+                .update(database.clone(), table.clone(), self.pool.as_ref().unwrap())
+                .await?;
+            self.properties
+                .set_tables(self.databases.tree().table_names(&database.name));
+            self.sync_bookmarks();
+            self.restore_table(&database, &table);
+            self.focus = Focus::Table;
+        }
+        Ok(())
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn set_history(&mut self, history: History) {
+        self.history = history;
+    }
+
+    pub fn set_favorites(&mut self, favorites: Favorites) {
+        self.connections
+            .set_favorites(favorites.connections.clone());
+        self.favorites = favorites;
+    }
+
+    /// Adds the applied filter to the history of the table.
+    fn remember_filter(&mut self) {
+        let input = self.record_table.filter.input_str();
+        if let (Some(connection), Some((database, table))) = (
+            self.connections.selected_connection(),
+            self.record_table.table.table(),
+        ) {
+            let key = History::table_key(&connection.session_key(), database, table);
+            let entries = self.history.filters.entry(key).or_default();
+            History::push(entries, input);
+            self.record_table.filter.set_history(entries.clone());
+        }
+    }
+
+    /// Adds the query which was run to the history of the connection.
+    fn remember_sql(&mut self, query: String) {
+        if let Some(connection) = self.connections.selected_connection() {
+            let entries = self
+                .history
+                .sql
+                .entry(connection.session_key())
+                .or_default();
+            History::push(entries, query);
+            self.sql_editor.set_history(entries.clone());
+        }
+    }
+
+    /// Returns where the user is, to be saved when zhobo quits.
+    pub fn session(&self) -> Session {
+        let connected = self.pool.is_some();
+        Session {
+            connection: self
+                .connections
+                .selected_connection()
+                .filter(|_| connected)
+                .map(|connection| connection.session_key()),
+            expanded: if connected {
+                self.databases.tree().expanded_nodes()
+            } else {
+                Vec::new()
+            },
+            table: self
+                .record_table
+                .table
+                .table()
+                .filter(|_| connected)
+                .map(|(database, table)| SessionTable::new(database, table)),
+            tab: self.tab.selected_tab,
+            sql: self.sql_editor.query(),
+            filter: self.record_table.filter.input_str(),
+            recent_tables: if connected {
+                self.recent_tables.clone()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    /// Reconnects and reopens the table of a saved session.
+    pub async fn restore_session(&mut self, session: Session) -> anyhow::Result<()> {
+        self.sql_editor.set_input(session.sql);
+        let connection = match &session.connection {
+            Some(connection) => connection,
+            None => return Ok(()),
+        };
+        if !self.connections.select_connection(connection) {
+            return Ok(());
+        }
+        self.update_databases().await?;
+        let unloaded = session
+            .expanded
+            .iter()
+            .filter(|node| !node.contains('/') && !self.databases.is_loaded(node))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.load_databases(unloaded).await?;
+        self.databases.tree_mut().expand_nodes(&session.expanded);
+        self.recent_tables = session.recent_tables;
+        if let Some(table) = session.table {
+            if self
+                .select_table(&table.database, table.schema.as_deref(), &table.name)
+                .await?
+            {
+                self.open_table().await?;
+                if !session.filter.is_empty() {
+                    self.record_table.filter.set_input(session.filter);
+                    self.update_record_table(false).await?;
+                }
+                self.tab.selected_tab = session.tab;
+            }
+        }
+        Ok(())
+    }
+
+    /// Tells a screen reader what is focused and selected now, if `announce` is set.
+    pub fn announce(&mut self) {
+        if self.announcer.is_enabled() {
+            let description = self.describe();
+            self.announcer.announce(description);
+        }
+    }
+
+    fn describe(&self) -> String {
+        if self.error.is_visible() {
+            return match self.error.selected() {
+                Some(entry) => format!("error: {}", entry.message),
+                None => "no errors".to_string(),
+            };
+        }
+        match self.focus {
+            Focus::ConnectionList => match self.connections.selected_connection() {
+                Some(conn) => format!(
+                    "connection {}",
+                    conn.database_url_with_name().unwrap_or_default()
+                ),
+                None => "no connections".to_string(),
+            },
+            Focus::DatabaseList => self.databases.describe(),
+            Focus::Table => match self.tab.selected_tab {
+                Tab::Records if self.record_table.filter_focused() => "records filter".to_string(),
+                Tab::Records => format!("records, {}", self.record_table.table.describe()),
+                Tab::Properties => self.properties.describe(),
+                Tab::Sql => self.sql_editor.describe(),
+                Tab::Logs => "logs".to_string(),
+            },
+        }
+    }
+
+    /// Returns true while a script or a query is running, so that the event loop must not wait for input.
+    pub fn is_busy(&self) -> bool {
+        self.sql_editor.is_running_script()
+            || self.sql_editor.is_streaming()
+            || self.export.is_running()
+    }
+
+    /// Runs the next statement of the running script, shows the rows of the running query which
+    /// arrived and writes the next page of the export.
+    pub async fn step(&mut self) -> anyhow::Result<()> {
+        self.sql_editor.step_stream().await?;
+        if let Some(pool) = self.pool.as_ref() {
+            self.sql_editor.step_script(pool.as_ref()).await;
+            if let Some((path, row_count)) = self.export.step(pool.as_ref()).await? {
+                self.toast
+                    .push(format!("Exported {} rows to {}", row_count, path.display()));
+            }
         }
         Ok(())
     }
 
+    /// Returns the SQL to open in an external editor if the editor asked for it.
+    pub fn take_external_edit(&mut self) -> Option<String> {
+        self.sql_editor.take_external_edit()
+    }
+
+    pub fn finish_external_edit(&mut self, query: String) {
+        self.sql_editor.set_input(query);
+    }
+
+    pub fn set_chords(&mut self, chords: Chords) {
+        self.chords = chords;
+    }
+
     pub async fn event(&mut self, key: Key) -> anyhow::Result<EventState> {
+        // every key is text while typing, so none waits for the rest of a chord
+        let keys = if self.is_typing() {
+            self.chords.reset();
+            vec![key]
+        } else {
+            self.chords.feed(key)
+        };
+        let mut state = EventState::Consumed;
+        for key in keys {
+            state = self.key_event(key).await?;
+        }
+        Ok(state)
+    }
+
+    /// Shows an error which happened while handling an event. A lost connection is reconnected
+    /// in the background instead of failing with a popup.
+    pub fn report(&mut self, error: &anyhow::Error) -> anyhow::Result<()> {
+        if self.pool.is_some() && is_disconnect(error) {
+            crate::log!(Error, "{:#}", error);
+            if !self.health.is_reconnecting() {
+                self.toast.push("Lost the connection, reconnecting…");
+            }
+            self.health.lost();
+            return Ok(());
+        }
+        self.error.push(error)
+    }
+
+    /// Pings the server when it is due, and tells when the connection was lost or is back.
+    pub async fn check_health(&mut self) {
+        let pool = match self.pool.as_ref() {
+            Some(pool) => pool,
+            None => return,
+        };
+        let event = self.health.check(pool.as_ref()).await;
+        // a transaction does not survive its connection, and the pool would keep running the
+        // statements on the dead one
+        let lost_transaction = event.is_some() && self.sql_editor.in_transaction();
+        if lost_transaction {
+            let _ = pool.rollback().await;
+            self.sql_editor.forget_transaction();
+        }
+        match event {
+            Some(HealthEvent::Lost(e)) => {
+                crate::log!(Error, "Ping failed: {:#}", e);
+                if lost_transaction {
+                    self.toast
+                        .push("Lost the connection and the open transaction, reconnecting…");
+                } else {
+                    self.toast.push("Lost the connection, reconnecting…");
+                }
+            }
+            Some(HealthEvent::Reconnected) => {
+                if lost_transaction {
+                    self.toast
+                        .push("Reconnected, the open transaction was lost");
+                } else {
+                    self.toast.push("Reconnected");
+                }
+            }
+            None => (),
+        }
+    }
+
+    /// Handles the keys of a chord which waited too long for its next key.
+    pub async fn expire_chord(&mut self) -> anyhow::Result<()> {
+        for key in self.chords.expire() {
+            self.key_event(key).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the keys go to a text field, where no chord applies.
+    fn is_typing(&self) -> bool {
+        if self.prompt.is_visible()
+            || self.file_prompt.is_visible()
+            || self.command_line.is_visible()
+            || self.object_search.is_visible()
+            || self.session_settings.is_visible()
+            || self.dashboard.is_visible()
+            || self.activity.is_visible()
+            || self.roles.is_visible()
+            || self.relations.is_visible()
+            || self.properties.is_editing()
+            || self.help.is_searching()
+        {
+            return true;
+        }
+        match self.focus {
+            Focus::ConnectionList => false,
+            Focus::DatabaseList => !self.databases.tree_focused(),
+            Focus::Table => match self.tab.selected_tab {
+                Tab::Records => {
+                    self.record_table.filter_focused()
+                        || self.record_table.table.is_searching()
+                        || self.record_table.table.is_viewing_cell()
+                }
+                Tab::Sql => {
+                    !self.sql_editor.is_table_focused()
+                        || self.sql_editor.is_searching()
+                        || self.sql_editor.is_filling_parameters()
+                        || self.sql_editor.table().is_viewing_cell()
+                }
+                Tab::Properties | Tab::Logs => false,
+            },
+        }
+    }
+
+    async fn key_event(&mut self, key: Key) -> anyhow::Result<EventState> {
         self.update_commands();
 
-        if self.components_event(key).await?.is_consumed() {
-            return Ok(EventState::Consumed);
+        let state = if self.components_event(key).await?.is_consumed()
+            || self.move_focus(key)?.is_consumed()
+        {
+            EventState::Consumed
+        } else {
+            EventState::NotConsumed
         };
+        self.update_tutorial(key);
+        Ok(state)
+    }
 
-        if self.move_focus(key)?.is_consumed() {
-            return Ok(EventState::Consumed);
+    /// Starts the guided tour of `--tutorial`.
+    pub fn start_tutorial(&mut self) {
+        self.tutorial = Some(TutorialComponent::new(self.config.key_config.clone()));
+    }
+
+    fn update_tutorial(&mut self, key: Key) {
+        let progress = TutorialProgress {
+            key,
+            connected: self.pool.is_some(),
+            table: self
+                .record_table
+                .table
+                .table()
+                .map(|(_, table)| table.name.clone()),
+            filtered: !self.record_table.filter.input_str().is_empty()
+                && !self.record_table.filter_focused(),
+            sorted: self.record_table.table.first_order().is_some(),
+            sql_result: self.sql_editor.has_result(),
+            table_focused: matches!(self.focus, Focus::Table)
+                && match self.tab.selected_tab {
+                    Tab::Records => !self.record_table.filter_focused(),
+                    Tab::Sql => self.sql_editor.is_table_focused(),
+                    Tab::Properties | Tab::Logs => true,
+                },
         };
-        Ok(EventState::NotConsumed)
+        if let Some(tutorial) = self.tutorial.as_mut() {
+            tutorial.update(&progress);
+        }
     }
 
     async fn components_event(&mut self, key: Key) -> anyhow::Result<EventState> {
@@ -247,98 +1127,276 @@ impl App {
             return Ok(EventState::Consumed);
         }
 
-        if !matches!(self.focus, Focus::ConnectionList) && self.help.event(key)?.is_consumed() {
+        if self.export.event(key)?.is_consumed() {
             return Ok(EventState::Consumed);
         }
 
-        match self.focus {
-            Focus::ConnectionList => {
-                if self.connections.event(key)?.is_consumed() {
-                    return Ok(EventState::Consumed);
+        if self.session_settings.is_visible() {
+            self.session_settings.event(key)?;
+            if let Some((name, value)) = self.session_settings.take_change() {
+                self.set_session_setting(&name, value).await?;
+                if let Some(pool) = self.pool.as_ref() {
+                    self.session_settings
+                        .open(pool.get_session_settings().await?);
                 }
+            }
+            return Ok(EventState::Consumed);
+        }
 
-                if key == self.config.key_config.enter {
-                    self.update_databases().await?;
-                    return Ok(EventState::Consumed);
-                }
+        if self.dashboard.is_visible() {
+            self.dashboard.event(key)?;
+            if self.dashboard.take_refresh() {
+                self.open_dashboard().await?;
             }
-            Focus::DatabaseList => {
-                if self.databases.event(key)?.is_consumed() {
-                    return Ok(EventState::Consumed);
-                }
+            return Ok(EventState::Consumed);
+        }
 
-                if key == self.config.key_config.enter && self.databases.tree_focused() {
-                    if let Some((database, table)) = self.databases.tree().selected_table() {
-                        self.record_table.reset();
-                        let (headers, records) = self
-                            .pool
-                            .as_ref()
-                            .unwrap()
-                            .get_records(&database, &table, 0, None, None)
-                            .await?;
-                        let total_row_count = self
-                            .pool
-                            .as_ref()
-                            .unwrap()
-                            .get_total_row_count(&database, &table, None)
-                            .await?;
-                        self.record_table.update(
-                            records,
-                            Some(total_row_count),
-                            headers,
-                            database.clone(),
-                            table.clone(),
-                            false,
-                        );
-                        self.properties
-                            .update(database.clone(), table.clone(), self.pool.as_ref().unwrap())
-                            .await?;
-                        self.focus = Focus::Table;
-                    }
-                    return Ok(EventState::Consumed);
-                }
+        if self.activity.is_visible() {
+            self.activity.event(key)?;
+            if let Some(statement) = self.activity.take_confirmed_kill() {
+                self.kill_session(statement).await?;
             }
-            Focus::Table => {
-                match self.tab.selected_tab {
-                    Tab::Records => {
-                        if self.record_table.event(key)?.is_consumed() {
-                            return Ok(EventState::Consumed);
-                        };
+            if self.activity.take_refresh() {
+                self.open_activity().await?;
+            }
+            return Ok(EventState::Consumed);
+        }
 
-                        if key == self.config.key_config.sort_by_column
-                            && !self.record_table.table.headers.is_empty()
-                        {
-                            self.record_table.table.add_order();
-                            self.update_record_table(true).await?;
-                            return Ok(EventState::Consumed);
-                        };
+        if self.roles.is_visible() {
+            self.roles.event(key)?;
+            if self.roles.take_refresh() {
+                self.open_roles().await?;
+            }
+            return Ok(EventState::Consumed);
+        }
 
-                        if key == self.config.key_config.copy {
-                            if let Some(text) = self.record_table.table.content() {
-                                copy_to_clipboard(text.as_str())?
-                            }
-                        }
+        if self.relations.is_visible() {
+            self.relations.event(key)?;
+            // the graph follows to the chosen table, so that the schema can be walked
+            if let Some(object) = self.relations.take_chosen() {
+                self.jump_to_object(object).await?;
+                self.open_relations().await?;
+            } else if self.relations.take_refresh() {
+                self.open_relations().await?;
+            }
+            return Ok(EventState::Consumed);
+        }
 
-                        if key == self.config.key_config.enter && self.record_table.filter_focused()
-                        {
-                            self.record_table.focus = crate::components::record_table::Focus::Table;
-                            self.update_record_table(false).await?;
-                        }
+        if self.object_search.is_visible() {
+            self.object_search.event(key)?;
+            if let Some(object) = self.object_search.take_chosen() {
+                self.jump_to_object(object).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.bookmarks.is_visible() {
+            self.bookmarks.event(key)?;
+            self.sync_bookmarks();
+            if let Some(bookmark) = self.bookmarks.take_chosen() {
+                self.jump_to_bookmark(bookmark).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.recent_table_list.is_visible() {
+            self.recent_table_list.event(key)?;
+            if let Some(table) = self.recent_table_list.take_chosen() {
+                self.open_recent_table(table).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.favorite_list.is_visible() {
+            self.favorite_list.event(key)?;
+            if let Some(favorite) = self.favorite_list.take_removed() {
+                match favorite {
+                    Favorite::Connection(connection) => {
+                        self.favorites.toggle_connection(&connection);
+                    }
+                    Favorite::Table(table) => {
+                        if let Some(connection) = self.connections.selected_connection() {
+                            self.favorites
+                                .toggle_table(&connection.session_key(), table);
+                        }
+                    }
+                }
+                self.save_favorites()?;
+            }
+            if let Some(favorite) = self.favorite_list.take_chosen() {
+                self.open_favorite(favorite).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.command_line.is_visible() {
+            self.command_line.event(key)?;
+            if let Some(line) = self.command_line.take_submitted() {
+                self.run_line_command(&line).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.file_prompt.is_visible() {
+            self.file_prompt.event(key)?;
+            if let Some(path) = self.file_prompt.take_submitted() {
+                self.finish_sql_file(path)?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if !matches!(self.focus, Focus::ConnectionList)
+            && !self.record_table.table.is_searching()
+            && !self.sql_editor.is_searching()
+            && self.help.event(key)?.is_consumed()
+        {
+            return Ok(EventState::Consumed);
+        }
+
+        match self.focus {
+            Focus::ConnectionList => {
+                if self.prompt.is_visible() {
+                    self.prompt.event(key)?;
+                    if let Some(value) = self.prompt.take_submitted() {
+                        if let Some(field) = self.query_tag.pending_field().map(String::from) {
+                            self.query_tag.set(&field, value);
+                        }
+                        self.connect().await?;
+                    }
+                    return Ok(EventState::Consumed);
+                }
+
+                if self.connections.event(key)?.is_consumed() {
+                    return Ok(EventState::Consumed);
+                }
+
+                if key == self.key_config().enter || key == self.key_config().open_dashboard {
+                    self.dashboard_requested = key == self.key_config().open_dashboard;
+                    self.connect().await?;
+                    return Ok(EventState::Consumed);
+                }
+            }
+            Focus::DatabaseList => {
+                if self.databases.event(key)?.is_consumed() {
+                    if let Some(database) = self.databases.database_to_load() {
+                        self.load_database(&database).await?;
+                    }
+                    let unloaded = self.databases.databases_to_filter();
+                    if !unloaded.is_empty() {
+                        self.load_databases(unloaded).await?;
+                    }
+                    return Ok(EventState::Consumed);
+                }
+
+                if key == self.key_config().enter && self.databases.tree_focused() {
+                    self.open_table().await?;
+                    return Ok(EventState::Consumed);
+                }
+            }
+            Focus::Table => {
+                match self.tab.selected_tab {
+                    Tab::Records => {
+                        if self.record_table.event(key)?.is_consumed() {
+                            if let Some(statement) = self.record_table.take_confirmed_delete() {
+                                self.delete_row(statement).await?;
+                            }
+                            return Ok(EventState::Consumed);
+                        };
+
+                        if key == self.key_config().toggle_sample {
+                            self.record_table.toggle_sample();
+                            self.update_record_table(false).await?;
+                            return Ok(EventState::Consumed);
+                        };
+
+                        if (key == self.key_config().sort_by_column
+                            || key == self.key_config().add_sort_column)
+                            && !self.record_table.table.headers.is_empty()
+                        {
+                            self.record_table
+                                .add_order(key == self.key_config().add_sort_column);
+                            self.update_record_table(true).await?;
+                            return Ok(EventState::Consumed);
+                        };
+
+                        if key == self.key_config().bookmark_row
+                            && !self.record_table.filter_focused()
+                        {
+                            self.toggle_bookmark().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.key_config().delete_row
+                            && !self.record_table.filter_focused()
+                        {
+                            self.confirm_delete_row().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.key_config().copy_as_insert
+                            && !self.record_table.filter_focused()
+                        {
+                            if let Some(text) = self
+                                .connections
+                                .selected_connection()
+                                .and_then(|c| self.record_table.insert_statements(c.dialect()))
+                            {
+                                copy_in_background(text, None, self.toast.queue());
+                            }
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.key_config().copy {
+                            if let Some(text) = self.record_table.table.content() {
+                                copy_in_background(
+                                    text,
+                                    Some(self.record_table.table.selected_cell_count()),
+                                    self.toast.queue(),
+                                );
+                            }
+                        }
+
+                        if key == self.key_config().enter && self.record_table.filter_focused() {
+                            if let Err(error) = self.record_table.filter.validate() {
+                                self.record_table.filter.set_error(error);
+                                return Ok(EventState::Consumed);
+                            }
+                            self.record_table.focus = crate::components::record_table::Focus::Table;
+                            if let Err(error) = self.update_record_table(false).await {
+                                // keep the filter focused so that the user can fix the condition
+                                self.record_table.focus =
+                                    crate::components::record_table::Focus::Filter;
+                                self.record_table.filter.set_error(error.to_string());
+                                return Ok(EventState::Consumed);
+                            }
+                            self.remember_filter();
+                        }
 
                         if self.record_table.table.eod {
                             return Ok(EventState::Consumed);
                         }
 
                         if let Some(index) = self.record_table.table.selected_row.selected() {
-                            let limit_size =
+                            let (limit_size, memory_budget) =
                                 if let Some(connection) = self.connections.selected_connection() {
-                                    connection.limit_size
+                                    (
+                                        connection.limit_size,
+                                        connection.memory_budget(self.config.memory_budget()).bytes,
+                                    )
                                 } else {
-                                    200
+                                    (200, self.config.memory_budget())
                                 };
                             if index.saturating_add(1) % limit_size == 0
-                                && index >= self.record_table.table.rows.len() - 1
+                                && index >= self.record_table.table.row_count() - 1
                             {
+                                if self.record_table.loaded_size() >= memory_budget {
+                                    self.record_table.table.set_warning(Some(format!(
+                                        "stopped at the memory budget, press {} to export all rows",
+                                        self.key_config().export_to_file
+                                    )));
+                                    self.record_table.table.end();
+                                    return Ok(EventState::Consumed);
+                                }
                                 if let Some((database, table)) =
                                     self.databases.tree().selected_table()
                                 {
@@ -349,17 +1407,13 @@ impl App {
                                         .get_records(
                                             &database,
                                             &table,
-                                            index.saturating_add(1) as u16,
-                                            if self.record_table.filter.input_str().is_empty() {
-                                                None
-                                            } else {
-                                                Some(self.record_table.filter.input_str())
-                                            },
+                                            index.saturating_add(1),
+                                            self.record_table.filter.query(),
                                             None,
                                         )
                                         .await?;
                                     if !records.is_empty() {
-                                        self.record_table.table.rows.extend(records);
+                                        self.record_table.extend(records);
                                     } else {
                                         self.record_table.table.end()
                                     }
@@ -375,11 +1429,25 @@ impl App {
                                 .await?
                                 .is_consumed()
                         {
+                            if let Some(query) = self.sql_editor.take_submitted() {
+                                self.remember_sql(query);
+                            }
+                            if let Some(action) = self.sql_editor.take_file_request() {
+                                self.ask_sql_file(action);
+                            }
                             return Ok(EventState::Consumed);
                         };
                     }
+                    Tab::Logs => {
+                        if self.logs.event(key)?.is_consumed() {
+                            return Ok(EventState::Consumed);
+                        }
+                    }
                     Tab::Properties => {
                         if self.properties.event(key)?.is_consumed() {
+                            if let Some(ddl) = self.properties.take_ddl() {
+                                self.execute_ddl(ddl).await?;
+                            }
                             return Ok(EventState::Consumed);
                         };
                     }
@@ -387,6 +1455,82 @@ impl App {
             }
         }
 
+        if key == self.key_config().search_objects {
+            if let Some(pool) = self.pool.as_ref() {
+                if !self.object_search.has_objects() {
+                    self.object_search.set_objects(pool.get_objects().await?);
+                }
+                self.object_search.open();
+                return Ok(EventState::Consumed);
+            }
+        }
+
+        if key == self.key_config().open_errors {
+            self.error.open();
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().open_bookmarks && self.pool.is_some() {
+            self.bookmarks.open();
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().toggle_favorite {
+            self.toggle_favorite()?;
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().open_recent_tables && self.pool.is_some() {
+            self.open_recent_tables();
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().open_favorites {
+            self.open_favorites();
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().open_activity && self.pool.is_some() {
+            self.open_activity().await?;
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().open_roles && self.pool.is_some() {
+            self.open_roles().await?;
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().open_relations && self.pool.is_some() {
+            self.open_relations().await?;
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().open_dashboard && self.pool.is_some() {
+            self.open_dashboard().await?;
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config().session_settings {
+            if let Some(pool) = self.pool.as_ref() {
+                self.session_settings
+                    .open(pool.get_session_settings().await?);
+                return Ok(EventState::Consumed);
+            }
+        }
+
+        if key == self.key_config().command_line {
+            self.command_line.open(self.line_commands());
+            return Ok(EventState::Consumed);
+        }
+
+        if self.refresh(key).await?.is_consumed() {
+            return Ok(EventState::Consumed);
+        };
+
+        if self.export(key).await?.is_consumed() {
+            return Ok(EventState::Consumed);
+        };
+
         if self.extend_or_shorten_widget_width(key)?.is_consumed() {
             return Ok(EventState::Consumed);
         };
@@ -394,6 +1538,807 @@ impl App {
         Ok(EventState::NotConsumed)
     }
 
+    /// Re-runs the query of the focused view, or of every view of the selected table.
+    async fn refresh(&mut self, key: Key) -> anyhow::Result<EventState> {
+        let refresh_all = key == self.key_config().refresh_all;
+        if !refresh_all && key != self.key_config().refresh {
+            return Ok(EventState::NotConsumed);
+        }
+        let pool = match self.pool.as_ref() {
+            Some(pool) => pool,
+            None => return Ok(EventState::NotConsumed),
+        };
+
+        // the selected database, or the whole tree with refresh_all
+        if matches!(self.focus, Focus::DatabaseList) {
+            if let Some(conn) = self.connections.selected_connection() {
+                let database = self
+                    .databases
+                    .tree()
+                    .selected_database()
+                    .filter(|_| !refresh_all)
+                    .map(String::from);
+                match database {
+                    Some(database) => {
+                        self.metadata_cache.clear_database(&database);
+                        self.databases.load_database(conn, pool, &database).await?;
+                    }
+                    None => {
+                        self.metadata_cache.clear();
+                        self.databases.update(conn, pool).await?;
+                    }
+                }
+                self.object_search.reset();
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        let (database, table) = match self.databases.tree().selected_table() {
+            Some(selected) => selected,
+            None => return Ok(EventState::NotConsumed),
+        };
+        self.metadata_cache.clear_table(&database, &table);
+        if refresh_all {
+            self.update_record_table(true).await?;
+            self.properties
+                .update(database, table, self.pool.as_ref().unwrap())
+                .await?;
+            return Ok(EventState::Consumed);
+        }
+        match (&self.focus, &self.tab.selected_tab) {
+            (Focus::Table, Tab::Records) => self.update_record_table(true).await?,
+            (Focus::Table, Tab::Properties) => {
+                self.properties.refresh(database, table, pool).await?
+            }
+            _ => return Ok(EventState::NotConsumed),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    /// Runs a statement built in the properties view and reloads the focused tab.
+    async fn execute_ddl(&mut self, ddl: String) -> anyhow::Result<()> {
+        let pool = match self.pool.as_ref() {
+            Some(pool) => pool,
+            None => return Ok(()),
+        };
+        pool.execute(&ddl).await?;
+        self.toast.push(ddl);
+        if let Some((database, table)) = self.databases.tree().selected_table() {
+            self.properties.refresh(database, table, pool).await?;
+        }
+        Ok(())
+    }
+
+    /// Starts streaming every row of the records or the SQL result into a file.
+    async fn export(&mut self, key: Key) -> anyhow::Result<EventState> {
+        let format = if key == self.key_config().export_to_file {
+            ExportFormat::Csv
+        } else if key == self.key_config().export_to_json {
+            ExportFormat::Json
+        } else if key == self.key_config().export_to_json_lines {
+            ExportFormat::JsonLines
+        } else {
+            return Ok(EventState::NotConsumed);
+        };
+        if !matches!(self.focus, Focus::Table) {
+            return Ok(EventState::NotConsumed);
+        }
+        self.start_export(format, None).await
+    }
+
+    /// Returns a name for a file, the headers and the values of the area selected in the focused
+    /// table.
+    fn selected_area(&self) -> Option<(String, Vec<String>, Vec<Vec<Value>>)> {
+        if !matches!(self.focus, Focus::Table) {
+            return None;
+        }
+        match self.tab.selected_tab {
+            Tab::Records => {
+                let (headers, rows) = self.record_table.table.selected_area_values()?;
+                let (_, table) = self.record_table.table.table()?;
+                Some((table.name.clone(), headers, rows))
+            }
+            Tab::Sql if self.sql_editor.is_table_focused() => {
+                let (headers, rows) = self.sql_editor.selected_area_values()?;
+                Some(("query".to_string(), headers, rows))
+            }
+            _ => None,
+        }
+    }
+
+    /// Asks for the path of the SQL file, starting from the file opened or saved before.
+    fn ask_sql_file(&mut self, action: SqlFileAction) {
+        let label = match action {
+            SqlFileAction::Open => "Open SQL file",
+            SqlFileAction::Save => "Save SQL to file",
+        };
+        let path = self
+            .sql_editor
+            .file()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        self.file_action = Some(action);
+        self.file_prompt.open_with(label.to_string(), path);
+    }
+
+    fn finish_sql_file(&mut self, path: String) -> anyhow::Result<()> {
+        let path = PathBuf::from(path.trim());
+        if path.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let path = expand_path(&path).unwrap_or(path);
+        match self.file_action.take() {
+            Some(SqlFileAction::Open) => {
+                self.sql_editor.open_file(path.clone())?;
+                self.toast.push(format!("Opened {}", path.display()));
+            }
+            Some(SqlFileAction::Save) => {
+                self.sql_editor.save_file(path.clone())?;
+                self.toast.push(format!("Saved {}", path.display()));
+            }
+            None => (),
+        }
+        Ok(())
+    }
+
+    /// Exports the selected cells, or else the records, or the SQL result if the SQL tab is
+    /// showing one.
+    async fn start_export(
+        &mut self,
+        format: ExportFormat,
+        path: Option<PathBuf>,
+    ) -> anyhow::Result<EventState> {
+        // the selected cells are loaded, so they are written at once
+        if let Some((name, headers, rows)) = self.selected_area() {
+            let path = write_rows_to_file(&name, format, &headers, &rows, path)?;
+            self.toast.push(format!(
+                "Exported {} selected rows to {}",
+                rows.len(),
+                path.display()
+            ));
+            return Ok(EventState::Consumed);
+        }
+        let pool = match self.pool.as_ref() {
+            Some(pool) => pool,
+            None => return Ok(EventState::NotConsumed),
+        };
+        let job = match self.tab.selected_tab {
+            Tab::Sql if self.sql_editor.is_table_focused() => {
+                ExportJob::query(format, &self.sql_editor.query(), path)?
+            }
+            Tab::Records => {
+                let (database, table) = match self.databases.tree().selected_table() {
+                    Some(selected) => selected,
+                    None => return Ok(EventState::NotConsumed),
+                };
+                let filter = self.record_table.filter.query();
+                // the progress is shown without a total if the rows cannot be counted
+                let total = pool
+                    .get_total_row_count(&database, &table, filter.clone())
+                    .await
+                    .ok();
+                let orders = self.record_table.table.generate_order_query();
+                ExportJob::records(format, database, table, filter, orders, total, path)?
+            }
+            _ => return Ok(EventState::NotConsumed),
+        };
+        self.export.start(job);
+        Ok(EventState::Consumed)
+    }
+
+    /// Writes the tables of the database of the tree selection with their columns and foreign
+    /// keys as a diagram.
+    async fn write_diagram(
+        &mut self,
+        format: DiagramFormat,
+        path: Option<PathBuf>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.as_ref().context("not connected")?;
+        let tree = self.databases.tree();
+        let name = tree
+            .selected_database()
+            .context("select a database first")?
+            .to_string();
+        let database = Database::new(name.clone(), vec![]);
+        let mut tables = Vec::new();
+        let mut relations = Vec::new();
+        for table in tree.tables(&name) {
+            let columns = pool
+                .get_columns(&database, &table)
+                .await?
+                .iter()
+                .map(|row| {
+                    let mut columns = row.columns().into_iter();
+                    (
+                        columns.next().unwrap_or_default(),
+                        columns.next().unwrap_or_default(),
+                    )
+                })
+                .collect();
+            // each foreign key is read with the table it is defined on
+            relations.extend(
+                pool.get_relations(&database, &table)
+                    .await?
+                    .into_iter()
+                    .filter(|relation| {
+                        relation.schema == table.schema && relation.table == table.name
+                    }),
+            );
+            tables.push(DiagramTable {
+                primary_key: pool.get_primary_key(&database, &table).await?,
+                schema: table.schema,
+                name: table.name,
+                columns,
+            });
+        }
+        let path = write_diagram_to_file(format, &name, &tables, &relations, path)?;
+        self.toast.push(format!(
+            "Wrote the diagram of {} tables and {} foreign keys to {}",
+            tables.len(),
+            relations.len(),
+            path.display()
+        ));
+        Ok(())
+    }
+
+    /// Selects a found object in the tree: a table or view itself, the table of a column, or the
+    /// schema of a routine.
+    async fn jump_to_object(&mut self, object: SchemaObject) -> anyhow::Result<()> {
+        if !self.databases.is_loaded(&object.database) {
+            self.load_database(&object.database).await?;
+        }
+        let tree = self.databases.tree_mut();
+        let found = match (object.kind.as_str(), &object.table) {
+            ("column", Some(table)) => {
+                tree.select_table(&object.database, object.schema.as_deref(), table)
+            }
+            ("table" | "view" | "function" | "procedure" | "sequence", _) => {
+                tree.select_table(&object.database, object.schema.as_deref(), &object.name)
+            }
+            _ => tree.select_node(&object.database, object.schema.as_deref()),
+        };
+        if found {
+            self.focus = Focus::DatabaseList;
+        } else {
+            self.toast
+                .push(format!("{} is not shown in the tree", object.path()));
+        }
+        Ok(())
+    }
+
+    /// Reads the tables of `database` into the tree, e.g. once it is expanded.
+    async fn load_database(&mut self, database: &str) -> anyhow::Result<()> {
+        if let (Some(connection), Some(pool)) =
+            (self.connections.selected_connection(), self.pool.as_ref())
+        {
+            self.databases
+                .load_database(connection, pool, database)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Reads the tables of several databases into the tree at once.
+    async fn load_databases(&mut self, databases: Vec<String>) -> anyhow::Result<()> {
+        if let (Some(connection), Some(pool)) =
+            (self.connections.selected_connection(), self.pool.as_ref())
+        {
+            self.databases
+                .load_databases(connection, pool, databases)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Selects a table in the tree, reading the tables of its database first if they are not
+    /// yet. Returns false if it is not in the tree.
+    async fn select_table(
+        &mut self,
+        database: &str,
+        schema: Option<&str>,
+        table: &str,
+    ) -> anyhow::Result<bool> {
+        if !self.databases.is_loaded(database) {
+            self.load_database(database).await?;
+        }
+        Ok(self
+            .databases
+            .tree_mut()
+            .select_table(database, schema, table))
+    }
+
+    /// Pins the selected connection, or the selected table of the tree, as a favorite, or
+    /// unpins it.
+    fn toggle_favorite(&mut self) -> anyhow::Result<()> {
+        let connection = match self.connections.selected_connection() {
+            Some(connection) => connection.session_key(),
+            None => return Ok(()),
+        };
+        let (label, pinned) = match self.focus {
+            Focus::ConnectionList => (
+                connection.clone(),
+                self.favorites.toggle_connection(&connection),
+            ),
+            Focus::DatabaseList if self.pool.is_some() => {
+                let table = match self.databases.tree().selected_table() {
+                    Some((database, table)) => FavoriteTable::new(&database, &table),
+                    None => return Ok(()),
+                };
+                (
+                    table.path(),
+                    self.favorites.toggle_table(&connection, table),
+                )
+            }
+            _ => return Ok(()),
+        };
+        self.save_favorites()?;
+        self.toast.push(if pinned {
+            format!("Pinned {} to the favorites", label)
+        } else {
+            format!("Unpinned {} from the favorites", label)
+        });
+        Ok(())
+    }
+
+    /// Lists the favorite connections, and the favorite tables of the connection in use.
+    fn open_favorites(&mut self) {
+        let mut favorites: Vec<Favorite> = self
+            .favorites
+            .connections
+            .iter()
+            .cloned()
+            .map(Favorite::Connection)
+            .collect();
+        if let (Some(connection), Some(_)) =
+            (self.connections.selected_connection(), self.pool.as_ref())
+        {
+            favorites.extend(
+                self.favorites
+                    .tables_of(&connection.session_key())
+                    .iter()
+                    .cloned()
+                    .map(Favorite::Table),
+            );
+        }
+        self.favorite_list.open(favorites);
+    }
+
+    async fn open_favorite(&mut self, favorite: Favorite) -> anyhow::Result<()> {
+        match favorite {
+            Favorite::Connection(connection) => {
+                if !self.connections.select_connection(&connection) {
+                    anyhow::bail!("connection `{}` is not found", connection);
+                }
+                self.connect().await?;
+            }
+            Favorite::Table(table) => {
+                if self
+                    .select_table(&table.database, table.schema.as_deref(), &table.name)
+                    .await?
+                {
+                    self.open_table().await?;
+                } else {
+                    self.toast
+                        .push(format!("{} is not shown in the tree", table.path()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists the tables opened on the connection with the filter and the sort they were left
+    /// with.
+    fn open_recent_tables(&mut self) {
+        self.remember_table();
+        let tables = self
+            .recent_tables
+            .iter()
+            .map(|table| {
+                let key = (
+                    table.database.clone(),
+                    match &table.schema {
+                        Some(schema) => format!("{}.{}", schema, table.name),
+                        None => table.name.clone(),
+                    },
+                );
+                let state = match self.table_memories.get(&key) {
+                    Some(memory) => [
+                        Some(memory.filter.as_str())
+                            .filter(|filter| !filter.is_empty())
+                            .map(|filter| format!("WHERE {}", filter)),
+                        Some(memory.orders.len())
+                            .filter(|count| *count > 0)
+                            .map(|count| format!("sorted by {} column(s)", count)),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                    None => String::new(),
+                };
+                RecentTable {
+                    table: table.clone(),
+                    state,
+                }
+            })
+            .collect();
+        self.recent_table_list.open(tables);
+    }
+
+    /// Opens the table again with the filter and the sort it was left with.
+    async fn open_recent_table(&mut self, table: SessionTable) -> anyhow::Result<()> {
+        if !self
+            .select_table(&table.database, table.schema.as_deref(), &table.name)
+            .await?
+        {
+            self.toast
+                .push(format!("{} is not shown in the tree", table.path()));
+            return Ok(());
+        }
+        self.open_table().await?;
+        let memory = self
+            .record_table
+            .table
+            .table()
+            .and_then(|(database, table)| {
+                self.table_memories
+                    .get(&Self::table_memory_key(database, table))
+            });
+        if let Some(memory) =
+            memory.filter(|memory| !memory.filter.is_empty() || !memory.orders.is_empty())
+        {
+            let (filter, orders) = (memory.filter.clone(), memory.orders.clone());
+            self.record_table.filter.set_input(filter);
+            self.record_table.table.set_orders(&orders);
+            self.update_record_table(true).await?;
+        }
+        Ok(())
+    }
+
+    fn save_favorites(&mut self) -> anyhow::Result<()> {
+        self.connections
+            .set_favorites(self.favorites.connections.clone());
+        self.favorites.save()
+    }
+
+    /// Bookmarks the selected row of the records, or removes its bookmark.
+    async fn toggle_bookmark(&mut self) -> anyhow::Result<()> {
+        let (database, table) = match self.record_table.table.table() {
+            Some((database, table)) => (database.clone(), table.clone()),
+            None => return Ok(()),
+        };
+        let primary_key = self
+            .pool
+            .as_ref()
+            .unwrap()
+            .get_primary_key(&database, &table)
+            .await?;
+        if let Some(key) = self.record_table.selected_key(&primary_key) {
+            let bookmark = Bookmark {
+                database: Database::new(database.name, vec![]),
+                table,
+                key,
+            };
+            if self.bookmarks.toggle(bookmark) {
+                self.toast.push("Bookmarked the row");
+            } else {
+                self.toast.push("Removed the bookmark");
+            }
+            self.sync_bookmarks();
+        }
+        Ok(())
+    }
+
+    /// Builds the `DELETE` statement of the selected row from its primary key and asks before
+    /// it is run. A table without a primary key is refused, as any other condition could
+    /// delete more than the row. It is refused too while the SQL editor has a transaction
+    /// open, which the `DELETE` would join while the records are read outside of it.
+    async fn confirm_delete_row(&mut self) -> anyhow::Result<()> {
+        if self.sql_editor.in_transaction() {
+            anyhow::bail!("commit or roll back the open transaction before deleting a row");
+        }
+        let (database, table) = match self.record_table.table.table() {
+            Some((database, table)) => (database.clone(), table.clone()),
+            None => return Ok(()),
+        };
+        let pool = match self.pool.as_ref() {
+            Some(pool) => pool,
+            None => return Ok(()),
+        };
+        let primary_key = pool.get_primary_key(&database, &table).await?;
+        if primary_key.is_empty() {
+            anyhow::bail!(
+                "{} has no primary key, so the row cannot be deleted on its own",
+                table.name
+            );
+        }
+        let key = match self.record_table.selected_key(&primary_key) {
+            Some(key) if key.iter().map(|(column, _)| column).eq(primary_key.iter()) => key,
+            Some(_) => anyhow::bail!("the primary key of {} is not in the records", table.name),
+            None => return Ok(()),
+        };
+        let dialect = match self.connections.selected_connection() {
+            Some(connection) => connection.dialect(),
+            None => return Ok(()),
+        };
+        self.record_table
+            .confirm_delete(ddl::delete_row(dialect, &database, &table, &key));
+        Ok(())
+    }
+
+    async fn delete_row(&mut self, statement: String) -> anyhow::Result<()> {
+        let pool = match self.pool.as_ref() {
+            Some(pool) => pool,
+            None => return Ok(()),
+        };
+        match pool
+            .execute(&statement)
+            .await
+            .map_err(|e| e.context(FailedStatement(statement.clone())))?
+        {
+            ExecuteResult::Write { updated_rows: 1 } => self.toast.push("Deleted the row"),
+            ExecuteResult::Write { updated_rows } => {
+                self.toast.push(format!("Deleted {} rows", updated_rows))
+            }
+            ExecuteResult::Read { .. } => (),
+        }
+        self.update_record_table(true).await
+    }
+
+    /// Marks the bookmarked rows of the open table.
+    fn sync_bookmarks(&mut self) {
+        let bookmarks = match self.record_table.table.table() {
+            Some((database, table)) => self.bookmarks.of_table(database, table).cloned().collect(),
+            None => Vec::new(),
+        };
+        self.record_table.set_bookmarks(bookmarks);
+    }
+
+    /// Opens the table of the bookmark and selects its row. If the filter hides the row or it
+    /// is not loaded yet, the table is filtered down to the row.
+    async fn jump_to_bookmark(&mut self, bookmark: Bookmark) -> anyhow::Result<()> {
+        let is_open = self
+            .record_table
+            .table
+            .table()
+            .is_some_and(|(database, table)| bookmark.is_in(database, table));
+        if !is_open {
+            if !self
+                .select_table(
+                    &bookmark.database.name,
+                    bookmark.table.schema.as_deref(),
+                    &bookmark.table.name,
+                )
+                .await?
+            {
+                self.toast.push(format!(
+                    "{}.{} is not shown in the tree",
+                    bookmark.database.name, bookmark.table.name
+                ));
+                return Ok(());
+            }
+            self.open_table().await?;
+        }
+        self.tab.selected_tab = Tab::Records;
+        self.focus = Focus::Table;
+        self.record_table.focus = crate::components::record_table::Focus::Table;
+        if self.record_table.select_bookmark(&bookmark) {
+            return Ok(());
+        }
+        let dialect = match self.connections.selected_connection() {
+            Some(connection) => connection.dialect(),
+            None => return Ok(()),
+        };
+        self.record_table.filter.set_input(bookmark.filter(dialect));
+        self.update_record_table(false).await?;
+        if !self.record_table.select_bookmark(&bookmark) {
+            self.toast.push("The bookmarked row no longer exists");
+        }
+        Ok(())
+    }
+
+    /// Connects again, e.g. with changed settings of the connection, and reopens the table.
+    async fn reconnect(&mut self) -> anyhow::Result<()> {
+        if self.pool.is_none() {
+            return Ok(());
+        }
+        let table = self.record_table.table.table().cloned();
+        self.update_databases().await?;
+        if let Some((database, table)) = table {
+            if self
+                .select_table(&database.name, table.schema.as_deref(), &table.name)
+                .await?
+            {
+                self.open_table().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies a session setting to every connection of the pool by connecting again.
+    async fn set_session_setting(&mut self, name: &str, value: String) -> anyhow::Result<()> {
+        check_setting_name(name)?;
+        let connection = match self.connections.selected_connection_mut() {
+            Some(connection) => connection,
+            None => anyhow::bail!("no connection is selected"),
+        };
+        connection.session.insert(name.to_string(), value.clone());
+        self.reconnect().await?;
+        self.toast.push(format!("{} = {}", name, value));
+        Ok(())
+    }
+
+    /// Returns the commands of the `:` command line.
+    fn line_commands(&self) -> Vec<LineCommand> {
+        let mut commands = vec![
+            LineCommand::new(
+                "connect",
+                "<connection>",
+                "Connect to a connection by its name",
+            ),
+            LineCommand::new("table", "[database.][schema.]<table>", "Open a table"),
+            LineCommand::new(
+                "export",
+                "csv|json|jsonl [path]",
+                "Export the selected cells, or all rows of the table or the SQL result",
+            ),
+            LineCommand::new(
+                "erd",
+                "dot|mermaid [path]",
+                "Write the tables and foreign keys of the database as a diagram",
+            ),
+            LineCommand::new(
+                "yank",
+                "csv|json|jsonl",
+                "Copy the selected cells with their headers",
+            ),
+            LineCommand::new(
+                "set",
+                "limit <rows> | <setting> <value>",
+                "Change how many rows are fetched at once or a session setting",
+            ),
+        ];
+        self.tab.line_commands(&mut commands);
+        self.sql_editor.line_commands(&mut commands);
+        if let Some(table) = self.shown_table() {
+            table.line_commands(&mut commands);
+        }
+        commands
+    }
+
+    /// Returns the rows of the table or the SQL result in the selected tab.
+    fn shown_table(&self) -> Option<&TableComponent> {
+        match self.tab.selected_tab {
+            Tab::Records => Some(&self.record_table.table),
+            Tab::Sql => Some(self.sql_editor.table()),
+            Tab::Properties | Tab::Logs => None,
+        }
+    }
+
+    fn shown_table_mut(&mut self) -> Option<&mut TableComponent> {
+        match self.tab.selected_tab {
+            Tab::Records => Some(&mut self.record_table.table),
+            Tab::Sql => Some(self.sql_editor.table_mut()),
+            Tab::Properties | Tab::Logs => None,
+        }
+    }
+
+    async fn run_line_command(&mut self, line: &str) -> anyhow::Result<()> {
+        let (name, args) = match command_line::parse(line) {
+            Some(command) => command,
+            None => return Ok(()),
+        };
+        match name {
+            "connect" => {
+                if !self.connections.select_connection(&args.join(" ")) {
+                    anyhow::bail!("connection `{}` is not found", args.join(" "));
+                }
+                self.connect().await?;
+            }
+            "table" => {
+                if self.pool.is_none() {
+                    anyhow::bail!("not connected");
+                }
+                let path = match args.as_slice() {
+                    [path] => path,
+                    _ => anyhow::bail!("usage: table [database.][schema.]<table>"),
+                };
+                if let Some((database, _)) = path.split_once('.') {
+                    if !self.databases.is_loaded(database) {
+                        self.load_database(database).await?;
+                    }
+                }
+                if !self.databases.tree_mut().select_table_path(path) {
+                    anyhow::bail!("table `{}` is not found", path);
+                }
+                self.open_table().await?;
+            }
+            "export" => {
+                let (format, path) = match args.split_first() {
+                    Some((format, path)) => (
+                        ExportFormat::from_name(format),
+                        Some(path.join(" ")).filter(|path| !path.is_empty()),
+                    ),
+                    None => (None, None),
+                };
+                let format = match format {
+                    Some(format) => format,
+                    None => anyhow::bail!("usage: export csv|json|jsonl [path]"),
+                };
+                if !self
+                    .start_export(format, path.map(PathBuf::from))
+                    .await?
+                    .is_consumed()
+                {
+                    anyhow::bail!("open a table or run a query to export");
+                }
+            }
+            "erd" => {
+                let (format, path) = match args.split_first() {
+                    Some((format, path)) => (
+                        DiagramFormat::from_name(format),
+                        Some(path.join(" ")).filter(|path| !path.is_empty()),
+                    ),
+                    None => (None, None),
+                };
+                let format = format.context("usage: erd dot|mermaid [path]")?;
+                self.write_diagram(format, path.map(PathBuf::from)).await?;
+            }
+            "yank" => {
+                let format = match args.as_slice() {
+                    [format] => ExportFormat::from_name(format),
+                    _ => None,
+                }
+                .context("usage: yank csv|json|jsonl")?;
+                let (_, headers, rows) = self.selected_area().with_context(|| {
+                    format!(
+                        "select cells first, e.g. with {}",
+                        self.key_config().extend_selection_by_one_cell_down
+                    )
+                })?;
+                copy_in_background(
+                    format_rows(format, &headers, &rows),
+                    Some(headers.len() * rows.len()),
+                    self.toast.queue(),
+                );
+            }
+            "set" => match args.as_slice() {
+                ["limit", rows] => {
+                    let rows = rows
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|rows| *rows > 0)
+                        .context("the limit must be a positive number")?;
+                    let connection = match self.connections.selected_connection_mut() {
+                        Some(connection) => connection,
+                        None => anyhow::bail!("no connection is selected"),
+                    };
+                    connection.limit_size = rows;
+                    // the pool fetches the pages, so it has to be opened again
+                    self.reconnect().await?;
+                    self.toast.push(format!("Fetching {} rows at a time", rows));
+                }
+                [name, value @ ..] => self.set_session_setting(name, value.join(" ")).await?,
+                _ => anyhow::bail!("usage: set limit <rows> | set <setting> <value>"),
+            },
+            name => {
+                if let Some(table) = self.shown_table_mut() {
+                    if table.run_line_command(name, &args)?.is_consumed() {
+                        return Ok(());
+                    }
+                }
+                let components: [&mut dyn Component; 2] = [&mut self.tab, &mut self.sql_editor];
+                for component in components {
+                    if component.run_line_command(name, &args)?.is_consumed() {
+                        return Ok(());
+                    }
+                }
+                anyhow::bail!("unknown command `{}`", name);
+            }
+        }
+        Ok(())
+    }
+
     fn concat_headers(
         &self,
         headers: Vec<String>,
@@ -446,7 +2391,7 @@ impl App {
     }
 
     fn move_focus(&mut self, key: Key) -> anyhow::Result<EventState> {
-        if key == self.config.key_config.focus_connections {
+        if key == self.key_config().focus_connections {
             self.focus = Focus::ConnectionList;
             return Ok(EventState::Consumed);
         }
@@ -455,19 +2400,19 @@ impl App {
         }
         match self.focus {
             Focus::ConnectionList => {
-                if key == self.config.key_config.enter {
+                if key == self.key_config().enter {
                     self.focus = Focus::DatabaseList;
                     return Ok(EventState::Consumed);
                 }
             }
             Focus::DatabaseList => {
-                if key == self.config.key_config.focus_right && self.databases.tree_focused() {
+                if key == self.key_config().focus_right && self.databases.tree_focused() {
                     self.focus = Focus::Table;
                     return Ok(EventState::Consumed);
                 }
             }
             Focus::Table => {
-                if key == self.config.key_config.focus_left {
+                if key == self.key_config().focus_left {
                     self.focus = Focus::DatabaseList;
                     return Ok(EventState::Consumed);
                 }
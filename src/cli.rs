@@ -7,6 +7,28 @@ use structopt::StructOpt;
 pub struct Cli {
     #[structopt(flatten)]
     pub config: CliConfig,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Store the password of a connection in the OS keyring
+    SetPassword {
+        /// The name of the connection in config.toml
+        connection: String,
+    },
+    /// Write the keymap and display settings in use to a file which can be shared
+    ExportPreset {
+        /// The file to write, e.g. team.ron
+        path: std::path::PathBuf,
+    },
+    /// Use the keymap and display settings of a shared file or URL
+    ImportPreset {
+        /// A path or an http(s) URL
+        source: String,
+    },
 }
 
 pub fn parse() -> Cli {
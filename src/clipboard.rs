@@ -1,8 +1,121 @@
+use crate::components::toast::ToastQueue;
 use anyhow::{anyhow, Result};
+use serde::Deserialize;
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
 use std::ffi::OsStr;
 use std::io::Write;
 use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Where copied text goes.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardBackend {
+    /// pbcopy, clip, or xclip/xsel
+    #[default]
+    System,
+    /// An OSC 52 escape sequence, which the terminal copies even over SSH.
+    Osc52,
+    /// A command which reads the text from its stdin, e.g. `["wl-copy"]`.
+    Command(Vec<String>),
+}
+
+static BACKEND: OnceLock<ClipboardBackend> = OnceLock::new();
+
+/// Sets the backend from the config. It can only be set once.
+pub fn set_backend(backend: ClipboardBackend) {
+    let _ = BACKEND.set(backend);
+}
+
+/// Copies `text` on another thread so that a large copy does not freeze the UI, and reports on
+/// `toast` how much was copied or why it failed. `cells` is the number of cells in `text`.
+pub fn copy_in_background(text: String, cells: Option<usize>, toast: ToastQueue) {
+    let bytes = text.len();
+    let report = move |result: Result<()>| {
+        let message = match result {
+            Ok(()) => copied_message(bytes, cells),
+            Err(e) => format!("Failed to copy: {}", e),
+        };
+        if let Ok(mut queue) = toast.lock() {
+            queue.push(message);
+        }
+    };
+    // the sequence is written to the terminal, where it must not interleave with a redraw
+    if BACKEND.get() == Some(&ClipboardBackend::Osc52) {
+        let result = copy_to_clipboard(&text);
+        return report(result);
+    }
+    std::thread::spawn(move || {
+        let result = copy_to_clipboard(&text);
+        report(result)
+    });
+}
+
+fn copied_message(bytes: usize, cells: Option<usize>) -> String {
+    let size = if bytes < 1024 {
+        format!("{} bytes", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    };
+    match cells {
+        Some(1) => format!("Copied 1 cell ({})", size),
+        Some(cells) => format!("Copied {} cells ({})", cells, size),
+        None => format!("Copied {}", size),
+    }
+}
+
+pub fn copy_to_clipboard(string: &str) -> Result<()> {
+    match BACKEND.get().unwrap_or(&ClipboardBackend::System) {
+        ClipboardBackend::System => copy_to_system_clipboard(string),
+        ClipboardBackend::Osc52 => {
+            let mut stdout = std::io::stdout();
+            stdout
+                .write_all(osc52_sequence(string, std::env::var_os("TMUX").is_some()).as_bytes())?;
+            stdout.flush()?;
+            Ok(())
+        }
+        ClipboardBackend::Command(command) => {
+            let (program, args) = command
+                .split_first()
+                .ok_or_else(|| anyhow!("the clipboard command is empty"))?;
+            let mut command = Command::new(program);
+            command.args(args);
+            execute_copy_command(command, string)
+        }
+    }
+}
+
+/// Returns the escape sequence which asks the terminal to put `text` in the clipboard.
+/// tmux only passes it through to the outer terminal when it is wrapped in a DCS.
+fn osc52_sequence(text: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
 
 fn execute_copy_command(command: Command, text: &str) -> Result<()> {
     let mut command = command;
@@ -40,7 +153,7 @@ fn gen_command(path: impl AsRef<OsStr>, xclip_syntax: bool) -> Command {
 }
 
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
-pub fn copy_to_clipboard(string: &str) -> Result<()> {
+fn copy_to_system_clipboard(string: &str) -> Result<()> {
     use std::path::PathBuf;
     use which::which;
     let (path, xclip_syntax) = which("xclip").ok().map_or_else(
@@ -58,11 +171,57 @@ pub fn copy_to_clipboard(string: &str) -> Result<()> {
 }
 
 #[cfg(target_os = "macos")]
-pub fn copy_to_clipboard(string: &str) -> Result<()> {
+fn copy_to_system_clipboard(string: &str) -> Result<()> {
     execute_copy_command(Command::new("pbcopy"), string)
 }
 
 #[cfg(windows)]
-pub fn copy_to_clipboard(string: &str) -> Result<()> {
+fn copy_to_system_clipboard(string: &str) -> Result<()> {
     execute_copy_command(Command::new("clip"), string)
 }
+
+#[cfg(test)]
+mod test {
+    use super::{base64, copied_message, osc52_sequence, ClipboardBackend};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Config {
+        clipboard: ClipboardBackend,
+    }
+
+    #[test]
+    fn test_deserialize_backend() {
+        let backend = |toml: &str| toml::from_str::<Config>(toml).unwrap().clipboard;
+        assert_eq!(backend(r#"clipboard = "osc52""#), ClipboardBackend::Osc52);
+        assert_eq!(
+            backend(r#"clipboard = { command = ["wl-copy", "-n"] }"#),
+            ClipboardBackend::Command(vec!["wl-copy".to_string(), "-n".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_copied_message() {
+        assert_eq!(copied_message(5, Some(1)), "Copied 1 cell (5 bytes)");
+        assert_eq!(copied_message(2048, Some(12)), "Copied 12 cells (2.0 KB)");
+        assert_eq!(copied_message(3 * 1024 * 1024, None), "Copied 3.0 MB");
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64("héllo".as_bytes()), "aMOpbGxv");
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("foo", false), "\x1b]52;c;Zm9v\x07");
+        assert_eq!(
+            osc52_sequence("foo", true),
+            "\x1bPtmux;\x1b\x1b]52;c;Zm9v\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use super::{Component, ConfirmComponent, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::{ddl::kill_session, format_uptime, Dialect, ServerSession};
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// A popup which lists the sessions of the server with the queries they run, and ends the
+/// selected one after asking.
+pub struct ActivityComponent {
+    sessions: Vec<ServerSession>,
+    selected: usize,
+    dialect: Dialect,
+    confirm: ConfirmComponent,
+    refresh_requested: bool,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl ActivityComponent {
+    const LIST_HEIGHT: u16 = 14;
+    /// A query which runs for longer than this many seconds is highlighted.
+    const LONG_RUNNING: u64 = 60;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            sessions: Vec::new(),
+            selected: 0,
+            dialect: Dialect::MySql,
+            confirm: ConfirmComponent::new(key_config.clone()),
+            refresh_requested: false,
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    /// Shows `sessions`, keeping the selected session if it is still there.
+    pub fn open(&mut self, sessions: Vec<ServerSession>) {
+        let selected = self.sessions.get(self.selected).map(|session| &session.id);
+        self.selected = selected
+            .and_then(|id| sessions.iter().position(|session| &session.id == id))
+            .unwrap_or(0);
+        self.sessions = sessions;
+        self.refresh_requested = false;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns true once if the user asked to list the sessions again.
+    pub fn take_refresh(&mut self) -> bool {
+        std::mem::take(&mut self.refresh_requested)
+    }
+
+    /// Returns the statement which ends the selected session once the user has confirmed it.
+    pub fn take_confirmed_kill(&mut self) -> Option<String> {
+        self.confirm.take_confirmed()
+    }
+
+    fn selected(&self) -> Option<&ServerSession> {
+        self.sessions.get(self.selected)
+    }
+
+    fn kill_selected(&mut self) -> Result<()> {
+        if let Some(session) = self.selected() {
+            let statement = kill_session(self.dialect, &session.id)?;
+            let message = format!("End the session {} of {}?", session.id, session.user);
+            self.confirm.open(message, statement);
+        }
+        Ok(())
+    }
+
+    fn line(session: &ServerSession) -> String {
+        format!(
+            "{:>10} {:<14} {:<14} {:<10} {:>7}  {}",
+            truncate(&session.id, 10),
+            truncate(&session.user, 14),
+            truncate(session.database.as_deref().unwrap_or("-"), 14),
+            truncate(&session.state, 10),
+            session.elapsed.map(format_uptime).unwrap_or_default(),
+            session
+                .query
+                .as_deref()
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        )
+    }
+
+    /// Returns true if the session runs a query for longer than `LONG_RUNNING`.
+    fn is_long_running(session: &ServerSession) -> bool {
+        let idle = session.state.eq_ignore_ascii_case("sleep") || session.state.starts_with("idle");
+        !idle
+            && session
+                .elapsed
+                .is_some_and(|elapsed| elapsed > Self::LONG_RUNNING)
+    }
+}
+
+/// Cuts `text` to `width` characters, marking the cut with an ellipsis.
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+impl DrawableComponent for ActivityComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let width = (f.size().width * 9 / 10).max(60).min(f.size().width);
+            let height = (f.size().height * 4 / 5).max(12).min(f.size().height);
+            let area = Rect::new(
+                (f.size().width - width) / 2,
+                (f.size().height - height) / 2,
+                width,
+                height,
+            );
+            let block = Block::default()
+                .title(format!(
+                    "Activity ({}) [{}] end session [{}] refresh [{}] close",
+                    self.sessions.len(),
+                    self.key_config.delete_row,
+                    self.key_config.refresh,
+                    self.key_config.exit_popup
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1),
+                    Constraint::Length(Self::LIST_HEIGHT),
+                    Constraint::Min(1),
+                ])
+                .split(block.inner(area));
+            let header = Line::from(Span::styled(
+                format!(
+                    "{:>10} {:<14} {:<14} {:<10} {:>7}  {}",
+                    "id", "user", "database", "state", "time", "query"
+                ),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            let offset = self
+                .selected
+                .saturating_sub((Self::LIST_HEIGHT as usize).saturating_sub(1));
+            let list: Vec<Line> = if self.sessions.is_empty() {
+                vec![Line::from(Span::styled(
+                    "No other sessions.",
+                    Style::default().fg(Color::DarkGray),
+                ))]
+            } else {
+                self.sessions
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(Self::LIST_HEIGHT as usize)
+                    .map(|(index, session)| {
+                        let style = if index == self.selected {
+                            Style::default()
+                                .fg(Color::Blue)
+                                .add_modifier(Modifier::BOLD)
+                        } else if Self::is_long_running(session) {
+                            Style::default().fg(Color::Yellow)
+                        } else {
+                            Style::default()
+                        };
+                        Line::from(Span::styled(Self::line(session), style))
+                    })
+                    .collect()
+            };
+            let query = self
+                .selected()
+                .and_then(|session| session.query.clone())
+                .unwrap_or_default();
+
+            f.render_widget(Clear, area);
+            f.render_widget(block, area);
+            f.render_widget(Paragraph::new(header), chunks[0]);
+            f.render_widget(Paragraph::new(list), chunks[1]);
+            f.render_widget(
+                Paragraph::new(query)
+                    .block(Block::default().borders(Borders::TOP).title("Query"))
+                    .wrap(Wrap { trim: false }),
+                chunks[2],
+            );
+            self.confirm.draw(f, area, false)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for ActivityComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if self.confirm.is_visible() {
+            return self.confirm.event(key);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.exit_popup || key == self.key_config.open_activity => {
+                self.hide()
+            }
+            key if key == self.key_config.refresh => self.refresh_requested = true,
+            key if key == self.key_config.delete_row => self.kill_selected()?,
+            key if (key == self.key_config.scroll_down || key == self.key_config.move_down)
+                && self.selected + 1 < self.sessions.len() =>
+            {
+                self.selected += 1;
+            }
+            key if key == self.key_config.scroll_up || key == self.key_config.move_up => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ActivityComponent;
+    use crate::components::Component;
+    use crate::config::KeyConfig;
+    use crate::database::{Dialect, ServerSession};
+
+    fn session(id: &str, state: &str, elapsed: u64) -> ServerSession {
+        ServerSession {
+            id: id.to_string(),
+            user: "app".to_string(),
+            database: Some("shop".to_string()),
+            state: state.to_string(),
+            elapsed: Some(elapsed),
+            query: Some("SELECT *\n  FROM orders".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_activity() {
+        let key_config = KeyConfig::default();
+        let mut activity = ActivityComponent::new(key_config.clone());
+        activity.set_dialect(Dialect::Postgres);
+        activity.open(vec![session("7", "active", 600), session("9", "idle", 5)]);
+        assert!(ActivityComponent::is_long_running(&activity.sessions[0]));
+        assert!(!ActivityComponent::is_long_running(&session(
+            "8", "Sleep", 600
+        )));
+        assert!(ActivityComponent::line(&activity.sessions[0])
+            .ends_with("10m 0s  SELECT * FROM orders"));
+
+        // the selection follows the session when the list is fetched again
+        activity.event(key_config.scroll_down).unwrap();
+        activity.open(vec![session("9", "idle", 6), session("10", "active", 1)]);
+        assert_eq!(activity.selected().unwrap().id, "9");
+
+        // ending a session asks first
+        activity.event(key_config.delete_row).unwrap();
+        assert_eq!(activity.take_confirmed_kill(), None);
+        activity.event(key_config.enter).unwrap();
+        assert_eq!(
+            activity.take_confirmed_kill(),
+            Some("SELECT pg_terminate_backend(9)".to_string())
+        );
+    }
+}
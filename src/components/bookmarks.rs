@@ -0,0 +1,285 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::ddl::key_condition;
+use crate::database::{Dialect, Value};
+use crate::event::Key;
+use crate::tree::{Database, Table};
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// A row remembered by the values of its primary key, or of all its columns if the table has
+/// no primary key, so that it can be found again after the rows are filtered or sorted.
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub database: Database,
+    pub table: Table,
+    pub key: Vec<(String, Value)>,
+}
+
+impl Bookmark {
+    pub fn is_in(&self, database: &Database, table: &Table) -> bool {
+        self.database.name == database.name
+            && self.table.schema == table.schema
+            && self.table.name == table.name
+    }
+
+    /// Returns true if `row`, with the columns `headers`, is the bookmarked row.
+    pub fn matches(&self, headers: &[String], row: &[Value]) -> bool {
+        self.key.iter().all(|(column, value)| {
+            headers
+                .iter()
+                .position(|header| header == column)
+                .and_then(|index| row.get(index))
+                == Some(value)
+        })
+    }
+
+    /// Returns the condition which selects the row, for the filter of the table.
+    pub fn filter(&self, dialect: Dialect) -> String {
+        key_condition(dialect, &self.key)
+    }
+
+    fn label(&self) -> String {
+        let table = match &self.table.schema {
+            Some(schema) => format!("{}.{}.{}", self.database.name, schema, self.table.name),
+            None => format!("{}.{}", self.database.name, self.table.name),
+        };
+        let key = self
+            .key
+            .iter()
+            .map(|(column, value)| format!("{}={}", column, value))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("{}  {}", table, key)
+    }
+}
+
+/// The rows bookmarked in this session, and a popup which lists them.
+pub struct BookmarksComponent {
+    bookmarks: Vec<Bookmark>,
+    selected: usize,
+    chosen: Option<Bookmark>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl BookmarksComponent {
+    const WIDTH: u16 = 80;
+    const HEIGHT: u16 = 16;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            bookmarks: Vec::new(),
+            selected: 0,
+            chosen: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Adds the bookmark, or removes it if the row is already bookmarked. Returns true if it
+    /// was added.
+    pub fn toggle(&mut self, bookmark: Bookmark) -> bool {
+        match self
+            .bookmarks
+            .iter()
+            .position(|b| b.is_in(&bookmark.database, &bookmark.table) && b.key == bookmark.key)
+        {
+            Some(index) => {
+                self.bookmarks.remove(index);
+                false
+            }
+            None => {
+                self.bookmarks.push(bookmark);
+                true
+            }
+        }
+    }
+
+    /// Returns the bookmarks of a table.
+    pub fn of_table<'a>(
+        &'a self,
+        database: &'a Database,
+        table: &'a Table,
+    ) -> impl Iterator<Item = &'a Bookmark> {
+        self.bookmarks
+            .iter()
+            .filter(move |bookmark| bookmark.is_in(database, table))
+    }
+
+    /// Forgets the bookmarks, e.g. when connecting to another server.
+    pub fn clear(&mut self) {
+        self.bookmarks.clear();
+        self.selected = 0;
+    }
+
+    pub fn open(&mut self) {
+        self.chosen = None;
+        self.selected = self.selected.min(self.bookmarks.len().saturating_sub(1));
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the bookmark once the user has picked it.
+    pub fn take_chosen(&mut self) -> Option<Bookmark> {
+        self.chosen.take()
+    }
+}
+
+impl DrawableComponent for BookmarksComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            let height = area.height.saturating_sub(2) as usize;
+            let offset = self.selected.saturating_sub(height.saturating_sub(1));
+            let lines = if self.bookmarks.is_empty() {
+                vec![Line::from(Span::styled(
+                    format!(
+                        "No bookmarks. Press {} on a row to bookmark it.",
+                        self.key_config.bookmark_row
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ))]
+            } else {
+                self.bookmarks
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(height)
+                    .map(|(index, bookmark)| {
+                        Line::from(Span::styled(
+                            bookmark.label(),
+                            if index == self.selected {
+                                Style::default()
+                                    .fg(Color::Blue)
+                                    .add_modifier(Modifier::BOLD)
+                            } else {
+                                Style::default()
+                            },
+                        ))
+                    })
+                    .collect()
+            };
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .title(format!(
+                            "Bookmarks ({}) [{}] open [{}] remove",
+                            self.bookmarks.len(),
+                            self.key_config.enter,
+                            self.key_config.bookmark_row
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                area,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for BookmarksComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.enter => {
+                self.chosen = self.bookmarks.get(self.selected).cloned();
+                self.hide();
+            }
+            key if key == self.key_config.exit_popup || key == self.key_config.open_bookmarks => {
+                self.hide()
+            }
+            key if key == self.key_config.bookmark_row && self.selected < self.bookmarks.len() => {
+                self.bookmarks.remove(self.selected);
+                self.selected = self.selected.min(self.bookmarks.len().saturating_sub(1));
+            }
+            key if (key == self.key_config.scroll_down || key == self.key_config.move_down)
+                && self.selected + 1 < self.bookmarks.len() =>
+            {
+                self.selected += 1;
+            }
+            key if key == self.key_config.scroll_up || key == self.key_config.move_up => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Bookmark, BookmarksComponent};
+    use crate::config::KeyConfig;
+    use crate::database::{Dialect, Value};
+    use crate::tree::{Database, Table, TableKind};
+
+    fn bookmark(id: i64) -> Bookmark {
+        Bookmark {
+            database: Database::new("shop".to_string(), vec![]),
+            table: Table {
+                name: "orders".to_string(),
+                create_time: None,
+                update_time: None,
+                engine: None,
+                schema: None,
+                kind: TableKind::default(),
+                partition_of: None,
+            },
+            key: vec![
+                ("id".to_string(), Value::Int(id)),
+                ("region".to_string(), Value::Text("eu".to_string())),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_bookmark() {
+        let headers = ["region", "name", "id"].map(String::from);
+        let row = |id| vec![Value::Text("eu".to_string()), Value::Null, Value::Int(id)];
+        assert!(bookmark(1).matches(&headers, &row(1)));
+        assert!(!bookmark(1).matches(&headers, &row(2)));
+        assert_eq!(
+            bookmark(1).filter(Dialect::Postgres),
+            r#""id" = 1 AND "region" = 'eu'"#
+        );
+
+        let mut bookmarks = BookmarksComponent::new(KeyConfig::default());
+        assert!(bookmarks.toggle(bookmark(1)));
+        assert!(bookmarks.toggle(bookmark(2)));
+        assert!(!bookmarks.toggle(bookmark(1)));
+        let (database, table) = (bookmark(2).database, bookmark(2).table);
+        assert_eq!(bookmarks.of_table(&database, &table).count(), 1);
+    }
+}
@@ -0,0 +1,320 @@
+use super::json_tree::JsonTree;
+use super::{Component, DrawableComponent, EventState};
+use crate::clipboard::copy_to_clipboard;
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::Value;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// A popup which shows the whole value of a cell, with JSON objects and arrays as a tree.
+pub struct CellViewerComponent {
+    title: String,
+    content: String,
+    tree: Option<JsonTree>,
+    /// The result of copying a path, shown in the title until the next key.
+    message: Option<String>,
+    scroll: u16,
+    /// How far the wrapped content can be scrolled, as measured by the last draw.
+    max_scroll: std::cell::Cell<u16>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl CellViewerComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            title: String::new(),
+            content: String::new(),
+            tree: None,
+            message: None,
+            scroll: 0,
+            max_scroll: std::cell::Cell::new(0),
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, title: String, value: &Value) {
+        self.title = title;
+        self.content = pretty(value);
+        self.tree = json(value).and_then(JsonTree::new);
+        self.message = None;
+        self.scroll = 0;
+        self.max_scroll
+            .set(self.content.lines().count().saturating_sub(1) as u16);
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn scroll_down(&mut self, lines: u16) {
+        self.scroll = self.scroll.saturating_add(lines).min(self.max_scroll.get());
+    }
+
+    fn tree_event(&mut self, key: Key) -> bool {
+        let tree = match self.tree.as_mut() {
+            Some(tree) => tree,
+            None => return false,
+        };
+        if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            tree.next(1);
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            tree.previous(1);
+        } else if key == self.key_config.scroll_down_multiple_lines {
+            tree.next(10);
+        } else if key == self.key_config.scroll_up_multiple_lines {
+            tree.previous(10);
+        } else if key == self.key_config.scroll_to_top {
+            tree.previous(usize::MAX);
+        } else if key == self.key_config.scroll_to_bottom {
+            tree.next(usize::MAX);
+        } else if key == self.key_config.enter {
+            tree.toggle();
+        } else if key == self.key_config.scroll_right {
+            tree.expand();
+        } else if key == self.key_config.scroll_left {
+            tree.collapse();
+        } else if key == self.key_config.copy {
+            if let Some(path) = tree.selected_path() {
+                self.message = Some(match copy_to_clipboard(&path) {
+                    Ok(()) => format!("copied {}", path),
+                    Err(e) => format!("failed to copy: {}", e),
+                });
+            }
+        } else {
+            return false;
+        }
+        true
+    }
+}
+
+impl DrawableComponent for CellViewerComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let size = f.size();
+            let area = Rect::new(
+                size.width / 10,
+                size.height / 10,
+                size.width - size.width / 5,
+                size.height - size.height / 5,
+            );
+            let title = match &self.message {
+                Some(message) => format!("{} - {}", self.title, message),
+                None => self.title.clone(),
+            };
+            let block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick);
+            f.render_widget(Clear, area);
+            let paragraph = match &self.tree {
+                Some(tree) => {
+                    // keeps the selection on the last visible line when moving past it
+                    let height = area.height.saturating_sub(2).max(1) as usize;
+                    let scroll = tree.selection().saturating_sub(height - 1);
+                    Paragraph::new(tree.lines()).scroll((scroll as u16, 0))
+                }
+                None => {
+                    // a long value on one line is wrapped rather than cut at the border
+                    let paragraph =
+                        Paragraph::new(self.content.as_str()).wrap(Wrap { trim: false });
+                    let height = area.height.saturating_sub(2) as usize;
+                    let line_count = paragraph.line_count(area.width.saturating_sub(2));
+                    self.max_scroll
+                        .set(line_count.saturating_sub(height).min(u16::MAX as usize) as u16);
+                    paragraph.scroll((self.scroll.min(self.max_scroll.get()), 0))
+                }
+            };
+            f.render_widget(paragraph.block(block), area);
+        }
+        Ok(())
+    }
+}
+
+impl Component for CellViewerComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        self.message = None;
+        if self.tree_event(key) {
+            return Ok(EventState::Consumed);
+        }
+        if key == self.key_config.exit_popup || key == self.key_config.view_cell {
+            self.hide();
+        } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            self.scroll_down(1);
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.scroll = self.scroll.saturating_sub(1);
+        } else if key == self.key_config.scroll_down_multiple_lines {
+            self.scroll_down(10);
+        } else if key == self.key_config.scroll_up_multiple_lines {
+            self.scroll = self.scroll.saturating_sub(10);
+        } else if key == self.key_config.scroll_to_top {
+            self.scroll = 0;
+        } else if key == self.key_config.scroll_to_bottom {
+            self.scroll_down(u16::MAX);
+        } else if key == self.key_config.exit {
+            return Ok(EventState::NotConsumed);
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+/// Returns the JSON in a JSON column, or in text which looks like a JSON object or array.
+fn json(value: &Value) -> Option<serde_json::Value> {
+    match value {
+        Value::Json(json) => Some(json.clone()),
+        Value::Text(text) => {
+            let trimmed = text.trim();
+            if trimmed.starts_with('{') || trimmed.starts_with('[') {
+                serde_json::from_str(trimmed).ok()
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Formats JSON and XML with indentation and binary data as a hex dump.
+pub fn pretty(value: &Value) -> String {
+    if let Some(json) = json(value) {
+        return serde_json::to_string_pretty(&json).unwrap_or_else(|_| json.to_string());
+    }
+    match value {
+        Value::Bytes(bytes) => hex_dump(bytes),
+        Value::Text(text) => {
+            let trimmed = text.trim();
+            if trimmed.starts_with('<') && trimmed.ends_with('>') {
+                return pretty_xml(trimmed);
+            }
+            text.clone()
+        }
+        value => value.to_string(),
+    }
+}
+
+fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let hex = chunk
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<String>>()
+                .join(" ");
+            let ascii = chunk
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() || *b == b' ' {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            format!("{:08x}  {:<47}  {}", index * 16, hex, ascii)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Puts every tag and text node of `xml` on its own line, indented by its depth.
+fn pretty_xml(xml: &str) -> String {
+    let mut lines = Vec::new();
+    let mut depth = 0_usize;
+    let mut rest = xml;
+    while !rest.is_empty() {
+        let (node, next) = if rest.starts_with('<') {
+            let end = if rest.starts_with("<!--") {
+                rest.find("-->").map(|i| i + 3)
+            } else {
+                rest.find('>').map(|i| i + 1)
+            }
+            .unwrap_or(rest.len());
+            rest.split_at(end)
+        } else {
+            rest.split_at(rest.find('<').unwrap_or(rest.len()))
+        };
+        rest = next;
+        let node = node.trim();
+        if node.is_empty() {
+            continue;
+        }
+        if node.starts_with("</") {
+            depth = depth.saturating_sub(1);
+        }
+        lines.push(format!("{}{}", "  ".repeat(depth), node));
+        if node.starts_with('<')
+            && !node.starts_with("</")
+            && !node.starts_with("<?")
+            && !node.starts_with("<!")
+            && !node.ends_with("/>")
+        {
+            depth += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hex_dump, pretty, pretty_xml};
+    use crate::database::Value;
+
+    #[test]
+    fn test_pretty_json() {
+        assert_eq!(
+            pretty(&Value::Text(r#"{"a":[1,2]}"#.to_string())),
+            "{\n  \"a\": [\n    1,\n    2\n  ]\n}"
+        );
+        assert_eq!(pretty(&Value::Text("{not json".to_string())), "{not json");
+    }
+
+    #[test]
+    fn test_pretty_xml() {
+        assert_eq!(
+            pretty_xml(r#"<?xml version="1.0"?><a><b x="1">text</b><c/><!-- note --></a>"#),
+            [
+                r#"<?xml version="1.0"?>"#,
+                "<a>",
+                r#"  <b x="1">"#,
+                "    text",
+                "  </b>",
+                "  <c/>",
+                "  <!-- note -->",
+                "</a>",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        assert_eq!(
+            hex_dump(b"zhobo\x00"),
+            "00000000  7a 68 6f 62 6f 00                                zhobo."
+        );
+    }
+}
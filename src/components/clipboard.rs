@@ -1,6 +1,7 @@
 use ratatui::{
     layout::{Constraint, Direction, Flex, Layout, Margin, Rect},
     style::{Color, Style},
+    text::Text,
     widgets::{Block, BorderType, Borders, Paragraph, Wrap},
     Frame,
 };
@@ -9,6 +10,7 @@ use crate::{
     config::KeyConfig,
     event::Key,
     tree::{Database, Table as DTable},
+    ui::syntax_text::SyntaxText,
 };
 
 use super::{utils::scroll_vertical::VerticalScroll, EventState, PropertyTrait};
@@ -16,6 +18,7 @@ use super::{utils::scroll_vertical::VerticalScroll, EventState, PropertyTrait};
 pub struct ClipboardComponent {
     table: Option<(Database, DTable)>,
     content: Option<String>,
+    syntax: Option<SyntaxText>,
     key_config: KeyConfig,
     position: u16,
     scroll: VerticalScroll,
@@ -27,6 +30,7 @@ impl ClipboardComponent {
         Self {
             table: None,
             content: None,
+            syntax: None,
             key_config,
             position: 0,
             scroll: VerticalScroll::new(false, false),
@@ -36,6 +40,7 @@ impl ClipboardComponent {
     pub fn reset(&mut self) {
         self.table = None;
         self.content = None;
+        self.syntax = None;
         self.position = 0;
     }
 
@@ -46,13 +51,10 @@ impl ClipboardComponent {
     }
 
     pub fn update(&mut self, content: String, database: Database, table: DTable) {
+        self.syntax = Some(SyntaxText::new(content.clone()));
         self.content = Some(content);
         self.table = Some((database, table));
     }
-
-    pub fn unwrap_content(&self) -> String {
-        self.content.clone().unwrap_or(String::from(""))
-    }
 }
 
 impl PropertyTrait for ClipboardComponent {
@@ -82,7 +84,7 @@ impl PropertyTrait for ClipboardComponent {
             }));
 
         // can scroll = content.height - widget.height
-        let paragraph = Paragraph::new(self.unwrap_content())
+        let paragraph = Paragraph::new(self.syntax.as_ref().map_or_else(Text::default, Text::from))
             .scroll((self.position, 0))
             .wrap(Wrap { trim: false });
 
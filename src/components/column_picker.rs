@@ -0,0 +1,202 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+/// The columns of a result set in display order, each with whether it is shown.
+pub type ColumnLayout = Vec<(usize, bool)>;
+
+pub struct ColumnPickerComponent {
+    headers: Vec<String>,
+    layout: ColumnLayout,
+    selection: usize,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl ColumnPickerComponent {
+    const WIDTH: u16 = 40;
+    const HEIGHT: u16 = 20;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            headers: Vec::new(),
+            layout: Vec::new(),
+            selection: 0,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Sets the columns of a new result set. `layout` is ignored unless it was made for
+    /// the same number of columns.
+    pub fn set_headers(&mut self, headers: Vec<String>, layout: Option<ColumnLayout>) {
+        self.layout = match layout {
+            Some(layout) if layout.len() == headers.len() => layout,
+            _ => (0..headers.len()).map(|index| (index, true)).collect(),
+        };
+        self.headers = headers;
+        self.selection = self.selection.min(self.layout.len().saturating_sub(1));
+    }
+
+    pub fn layout(&self) -> ColumnLayout {
+        self.layout.clone()
+    }
+
+    /// Returns the indexes of the shown columns in display order.
+    pub fn projection(&self) -> Vec<usize> {
+        self.layout
+            .iter()
+            .filter(|(_, visible)| *visible)
+            .map(|(index, _)| *index)
+            .collect()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn toggle(&mut self) {
+        let visible_count = self.layout.iter().filter(|(_, visible)| *visible).count();
+        if let Some((_, visible)) = self.layout.get_mut(self.selection) {
+            // at least one column has to be shown
+            if !*visible || visible_count > 1 {
+                *visible = !*visible;
+            }
+        }
+    }
+
+    fn move_column(&mut self, down: bool) {
+        if down && self.selection + 1 < self.layout.len() {
+            self.layout.swap(self.selection, self.selection + 1);
+            self.selection += 1;
+        } else if !down && self.selection > 0 {
+            self.layout.swap(self.selection, self.selection - 1);
+            self.selection -= 1;
+        }
+    }
+}
+
+impl DrawableComponent for ColumnPickerComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            let items = self
+                .layout
+                .iter()
+                .map(|(index, visible)| {
+                    ListItem::new(format!(
+                        "[{}] {}",
+                        if *visible { "x" } else { " " },
+                        self.headers.get(*index).map_or("", |h| h.as_str())
+                    ))
+                })
+                .collect::<Vec<ListItem>>();
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .title("Columns")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                )
+                .highlight_style(Style::default().bg(Color::Blue));
+            let mut state = ListState::default();
+            state.select(Some(self.selection));
+
+            f.render_widget(Clear, area);
+            f.render_stateful_widget(list, area, &mut state);
+        }
+        Ok(())
+    }
+}
+
+impl Component for ColumnPickerComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::pick_columns(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit_popup || key == self.key_config.open_column_picker {
+            self.hide();
+        } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            self.selection = (self.selection + 1).min(self.layout.len().saturating_sub(1));
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.selection = self.selection.saturating_sub(1);
+        } else if key == self.key_config.enter {
+            self.toggle();
+        } else if key == self.key_config.extend_selection_by_one_cell_down {
+            self.move_column(true);
+        } else if key == self.key_config.extend_selection_by_one_cell_up {
+            self.move_column(false);
+        } else if key == self.key_config.exit {
+            return Ok(EventState::NotConsumed);
+        }
+        // the picker is modal, so the table below must not see the key
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ColumnPickerComponent, KeyConfig};
+
+    fn headers() -> Vec<String> {
+        ["a", "b", "c"].iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn test_toggle_and_move_column() {
+        let mut picker = ColumnPickerComponent::new(KeyConfig::default());
+        picker.set_headers(headers(), None);
+        assert_eq!(picker.projection(), vec![0, 1, 2]);
+
+        picker.toggle();
+        assert_eq!(picker.projection(), vec![1, 2]);
+
+        picker.selection = 2;
+        picker.move_column(false);
+        assert_eq!(picker.projection(), vec![2, 1]);
+        assert_eq!(picker.layout(), vec![(0, false), (2, true), (1, true)]);
+    }
+
+    #[test]
+    fn test_keep_one_column() {
+        let mut picker = ColumnPickerComponent::new(KeyConfig::default());
+        picker.set_headers(vec!["a".to_string()], None);
+        picker.toggle();
+        assert_eq!(picker.projection(), vec![0]);
+    }
+
+    #[test]
+    fn test_ignore_layout_of_other_columns() {
+        let mut picker = ColumnPickerComponent::new(KeyConfig::default());
+        picker.set_headers(headers(), Some(vec![(1, true), (0, false)]));
+        assert_eq!(picker.projection(), vec![0, 1, 2]);
+        picker.set_headers(headers(), Some(vec![(2, true), (0, false), (1, true)]));
+        assert_eq!(picker.projection(), vec![2, 1]);
+    }
+}
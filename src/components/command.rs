@@ -1,9 +1,21 @@
 use crate::config::KeyConfig;
 
-static CMD_GROUP_GENERAL: &str = "-- General --";
-static CMD_GROUP_TABLE: &str = "-- Table --";
-static CMD_GROUP_DATABASES: &str = "-- Databases --";
-static CMD_GROUP_PROPERTIES: &str = "-- Properties --";
+const CMD_GROUP_GENERAL: &str = "-- General --";
+const CMD_GROUP_NAVIGATION: &str = "-- Navigation --";
+const CMD_GROUP_TREE: &str = "-- Tree --";
+const CMD_GROUP_TABLE: &str = "-- Table --";
+const CMD_GROUP_PROPERTIES: &str = "-- Properties --";
+const CMD_GROUP_EDITOR: &str = "-- SQL editor --";
+
+/// The order the groups are shown in the help.
+pub const CMD_GROUPS: [&str; 6] = [
+    CMD_GROUP_GENERAL,
+    CMD_GROUP_NAVIGATION,
+    CMD_GROUP_TREE,
+    CMD_GROUP_TABLE,
+    CMD_GROUP_PROPERTIES,
+    CMD_GROUP_EDITOR,
+];
 
 #[derive(Clone, PartialEq, PartialOrd, Ord, Eq)]
 pub struct CommandText {
@@ -38,7 +50,7 @@ pub fn scroll(key: &KeyConfig) -> CommandText {
             "Scroll up/down/left/right [{},{},{},{}]",
             key.scroll_up, key.scroll_down, key.scroll_left, key.scroll_right
         ),
-        CMD_GROUP_GENERAL,
+        CMD_GROUP_NAVIGATION,
     )
 }
 
@@ -48,7 +60,7 @@ pub fn scroll_up_down_multiple_lines(key: &KeyConfig) -> CommandText {
             "Scroll up/down multiple lines [{},{}]",
             key.scroll_up_multiple_lines, key.scroll_down_multiple_lines,
         ),
-        CMD_GROUP_GENERAL,
+        CMD_GROUP_NAVIGATION,
     )
 }
 
@@ -58,7 +70,7 @@ pub fn scroll_to_top_bottom(key: &KeyConfig) -> CommandText {
             "Scroll to top/bottom [{},{}]",
             key.scroll_to_top, key.scroll_to_bottom,
         ),
-        CMD_GROUP_GENERAL,
+        CMD_GROUP_NAVIGATION,
     )
 }
 
@@ -72,10 +84,30 @@ pub fn move_to_head_tail_of_line(key: &KeyConfig) -> CommandText {
     )
 }
 
+pub fn column_pages(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Move to the previous/next page of columns [{},{}]",
+            key.previous_column_page, key.next_column_page,
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
 pub fn expand_collapse(key: &KeyConfig) -> CommandText {
     CommandText::new(
         format!("Expand/Collapse [{},{}]", key.scroll_right, key.scroll_left,),
-        CMD_GROUP_DATABASES,
+        CMD_GROUP_TREE,
+    )
+}
+
+pub fn toggle_system_objects(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Show/hide the system databases, schemas and tables [{}]",
+            key.toggle_system_objects
+        ),
+        CMD_GROUP_TREE,
     )
 }
 
@@ -89,13 +121,16 @@ pub fn move_focus(key: &KeyConfig) -> CommandText {
             "Move focus to left/right [{},{}]",
             key.focus_left, key.focus_right
         ),
-        CMD_GROUP_GENERAL,
+        CMD_GROUP_NAVIGATION,
     )
 }
 
 pub fn sort_by_column(key: &KeyConfig) -> CommandText {
     CommandText::new(
-        format!("Sort by column [{}]", key.sort_by_column),
+        format!(
+            "Sort by column, or add it to the sort [{},{}]",
+            key.sort_by_column, key.add_sort_column
+        ),
         CMD_GROUP_TABLE,
     )
 }
@@ -129,10 +164,305 @@ pub fn extend_or_shorten_widget_width(key: &KeyConfig) -> CommandText {
             "Extend/shorten widget width to left/right [{},{}]",
             key.extend_or_shorten_widget_width_to_left, key.extend_or_shorten_widget_width_to_right
         ),
+        CMD_GROUP_NAVIGATION,
+    )
+}
+
+pub fn edit_in_external_editor(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Edit SQL in $EDITOR [{}]", key.edit_in_external_editor),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn format_sql(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Format the SQL [{}]", key.format_sql),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn run_statement(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Run the statement under the cursor [{}]", key.run_statement),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn open_or_save_sql_file(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Open/save a SQL file [{},{}]",
+            key.open_sql_file, key.save_sql_file
+        ),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn refresh(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Refresh current view/all views of the table, or the database/tree [{},{}]",
+            key.refresh, key.refresh_all
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn view_cell(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("View the whole value of a cell [{}]", key.view_cell),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn search_in_table(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Search the loaded rows [{}], jump to the next/previous match [{},{}]",
+            key.search_in_table, key.next_match, key.previous_match
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn pin_columns(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Pin the columns up to the selected one while scrolling, or unpin them [{}]",
+            key.pin_columns
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn resize_column(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Widen/narrow the selected column [{},{}], fit it to its values [{}]",
+            key.widen_column, key.narrow_column, key.fit_column
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn toggle_record_view(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Show the selected row as a list of its columns, or the rows [{}]",
+            key.toggle_record_view
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn bookmark_row(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Bookmark/unbookmark the row [{}]", key.bookmark_row),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn copy_as_insert(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Copy the selected rows as INSERT statements [{}]",
+            key.copy_as_insert
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn delete_row(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Delete the row by its primary key [{}]", key.delete_row),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn open_errors(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Show the errors of this session [{}]", key.open_errors),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn open_bookmarks(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("List the bookmarked rows [{}]", key.open_bookmarks),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn toggle_favorite(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Pin/unpin the connection or table as a favorite [{}]",
+            key.toggle_favorite
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn open_recent_tables(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "List the recently opened tables [{}]",
+            key.open_recent_tables
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn open_favorites(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "List the favorite connections and tables [{}]",
+            key.open_favorites
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+/// A chord from key_bind.ron, e.g. `<Space> b`, and the name of the key whose action it runs.
+pub fn chord(sequence: &str, action: &str) -> CommandText {
+    CommandText::new(
+        format!("Run {} [{}]", action.replace('_', " "), sequence),
         CMD_GROUP_GENERAL,
     )
 }
 
+pub fn json_tree(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Expand/collapse JSON in the cell viewer [{},{},{}], copy its path [{}]",
+            key.enter, key.scroll_right, key.scroll_left, key.copy
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn export_to_file(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Export all rows to a CSV file [{}]", key.export_to_file),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn export_to_json(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Export all rows to a JSON [{}] or JSON Lines [{}] file",
+            key.export_to_json, key.export_to_json_lines
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn toggle_sample(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Show a random sample of the rows or the first rows [{}]",
+            key.toggle_sample
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn toggle_stop_on_error(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Toggle stopping a script at a failed statement [{}]",
+            key.toggle_stop_on_error
+        ),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn create_foreign_key(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Create a foreign key [{}]", key.create_foreign_key),
+        CMD_GROUP_PROPERTIES,
+    )
+}
+
+pub fn drop_foreign_key(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Drop the selected foreign key [{}]", key.drop_foreign_key),
+        CMD_GROUP_PROPERTIES,
+    )
+}
+
+pub fn restart_sequence(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Restart the sequence [{}]", key.restart_sequence),
+        CMD_GROUP_PROPERTIES,
+    )
+}
+
+pub fn toggle_transaction_mode(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Run statements in a transaction until commit [{}]",
+            key.toggle_transaction_mode
+        ),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn commit_or_rollback(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Commit/roll back the transaction [{},{}]",
+            key.commit, key.rollback
+        ),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn switch_result_set(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Show the previous/next result set of a script or recent result [{},{}]",
+            key.previous_result_set, key.next_result_set
+        ),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn snapshot_result(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Snapshot the result to compare the next run with, or stop comparing [{}]",
+            key.snapshot_result
+        ),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn toggle_chart(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Show a two-column result as a bar chart, a line chart or a table [{}]",
+            key.toggle_chart
+        ),
+        CMD_GROUP_EDITOR,
+    )
+}
+
+pub fn pick_columns(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Hide/show [{}] and reorder [{},{}] columns [{}]",
+            key.enter,
+            key.extend_selection_by_one_cell_up,
+            key.extend_selection_by_one_cell_down,
+            key.open_column_picker
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
 pub fn tab_records(key: &KeyConfig) -> CommandText {
     CommandText::new(format!("Records [{}]", key.tab_records), CMD_GROUP_TABLE)
 }
@@ -155,6 +485,38 @@ pub fn tab_definition(key: &KeyConfig) -> CommandText {
     )
 }
 
+pub fn tab_dependencies(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Dependencies [{}]", key.tab_dependencies),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn tab_triggers(key: &KeyConfig) -> CommandText {
+    CommandText::new(format!("Triggers [{}]", key.tab_triggers), CMD_GROUP_TABLE)
+}
+
+pub fn tab_statistics(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Statistics [{}]", key.tab_statistics),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn tab_privileges(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Privileges [{}]", key.tab_privileges),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn tab_partitions(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Partitions [{}]", key.tab_partitions),
+        CMD_GROUP_TABLE,
+    )
+}
+
 pub fn tab_foreign_keys(key: &KeyConfig) -> CommandText {
     CommandText::new(
         format!("Foreign keys [{}]", key.tab_foreign_keys),
@@ -170,6 +532,10 @@ pub fn tab_sql_editor(key: &KeyConfig) -> CommandText {
     CommandText::new(format!("SQL [{}]", key.tab_sql_editor), CMD_GROUP_TABLE)
 }
 
+pub fn tab_logs(key: &KeyConfig) -> CommandText {
+    CommandText::new(format!("Logs [{}]", key.tab_logs), CMD_GROUP_TABLE)
+}
+
 pub fn tab_properties(key: &KeyConfig) -> CommandText {
     CommandText::new(
         format!("Properties [{}]", key.tab_properties),
@@ -180,34 +546,112 @@ pub fn tab_properties(key: &KeyConfig) -> CommandText {
 pub fn toggle_tabs(key_config: &KeyConfig) -> CommandText {
     CommandText::new(
         format!(
-            "Tab [{},{},{},{},{},{},{},{}]",
+            "Tab [{},{},{},{},{},{},{},{},{},{},{},{},{},{}]",
             key_config.tab_records,
             key_config.tab_properties,
             key_config.tab_sql_editor,
+            key_config.tab_logs,
             key_config.tab_columns,
             key_config.tab_constraints,
             key_config.tab_foreign_keys,
             key_config.tab_indexes,
-            key_config.tab_definition
+            key_config.tab_definition,
+            key_config.tab_dependencies,
+            key_config.tab_triggers,
+            key_config.tab_statistics,
+            key_config.tab_privileges,
+            key_config.tab_partitions
         ),
-        CMD_GROUP_GENERAL,
+        CMD_GROUP_NAVIGATION,
     )
 }
 
 pub fn toggle_property_tabs(key_config: &KeyConfig) -> CommandText {
     CommandText::new(
         format!(
-            "Tab [{},{},{},{},{}]",
+            "Tab [{},{},{},{},{},{},{},{},{},{}]",
             key_config.tab_columns,
             key_config.tab_constraints,
             key_config.tab_foreign_keys,
             key_config.tab_indexes,
-            key_config.tab_definition
+            key_config.tab_definition,
+            key_config.tab_dependencies,
+            key_config.tab_triggers,
+            key_config.tab_statistics,
+            key_config.tab_privileges,
+            key_config.tab_partitions
         ),
         CMD_GROUP_PROPERTIES,
     )
 }
 
+pub fn command_line(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Run a command [{}]", key_config.command_line),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn search_objects(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Search tables, views, columns and routines [{}]",
+            key_config.search_objects
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn open_dashboard(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Show the version, uptime, connections, cache hit ratios and largest tables of the server [{}]",
+            key_config.open_dashboard
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn open_activity(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "List the sessions of the server and end one [{}]",
+            key_config.open_activity
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn open_roles(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "List the users and roles of the server [{}]",
+            key_config.open_roles
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn open_relations(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Draw the foreign keys from and to the selected table [{}]",
+            key_config.open_relations
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
+pub fn session_settings(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Show and switch the session settings [{}]",
+            key_config.session_settings
+        ),
+        CMD_GROUP_GENERAL,
+    )
+}
+
 pub fn help(key_config: &KeyConfig) -> CommandText {
     CommandText::new(
         format!("Help [{}]", key_config.open_help),
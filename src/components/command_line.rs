@@ -0,0 +1,273 @@
+use super::{compute_character_width, Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+/// A command which can be run from the `:` command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineCommand {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+    /// The values of the argument which the command line completes, e.g. the columns.
+    pub arguments: Vec<String>,
+}
+
+impl LineCommand {
+    pub const fn new(name: &'static str, usage: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            usage,
+            description,
+            arguments: Vec::new(),
+        }
+    }
+
+    pub fn with_arguments(mut self, arguments: Vec<String>) -> Self {
+        self.arguments = arguments;
+        self
+    }
+}
+
+/// Splits a command line into the name of the command and its arguments.
+pub fn parse(line: &str) -> Option<(&str, Vec<&str>)> {
+    let mut words = line.split_whitespace();
+    words.next().map(|name| (name, words.collect()))
+}
+
+/// Returns the longest prefix which the words share, followed by a space if there is only one.
+fn complete_word(words: &[&str]) -> Option<String> {
+    match words {
+        [] => None,
+        [word] => Some(format!("{} ", word)),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.len(), |len, word| {
+                first
+                    .char_indices()
+                    .zip(word.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .map(|((index, a), _)| index + a.len_utf8())
+                    .last()
+                    .unwrap_or_default()
+                    .min(len)
+            });
+            Some(first[..common].to_string())
+        }
+    }
+}
+
+/// A vim-like command line at the bottom of the screen.
+pub struct CommandLineComponent {
+    input: String,
+    commands: Vec<LineCommand>,
+    submitted: Option<String>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl CommandLineComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            input: String::new(),
+            commands: Vec::new(),
+            submitted: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Opens the command line with the commands which can be run at the moment.
+    pub fn open(&mut self, commands: Vec<LineCommand>) {
+        self.commands = commands;
+        self.input.clear();
+        self.submitted = None;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the line once the user has submitted it.
+    pub fn take_submitted(&mut self) -> Option<String> {
+        self.submitted.take()
+    }
+
+    fn matching_commands(&self) -> Vec<&LineCommand> {
+        let name = parse(&self.input).map_or("", |(name, _)| name);
+        self.commands
+            .iter()
+            .filter(|command| command.name.starts_with(name))
+            .collect()
+    }
+
+    /// Returns the command whose argument is being typed, and the argument so far.
+    fn typed_argument(&self) -> Option<(&LineCommand, &str)> {
+        let (name, argument) = self.input.split_once(char::is_whitespace)?;
+        let command = self.commands.iter().find(|command| command.name == name)?;
+        Some((command, argument.trim_start()))
+    }
+
+    fn matching_arguments<'a>(command: &'a LineCommand, argument: &str) -> Vec<&'a str> {
+        command
+            .arguments
+            .iter()
+            .filter(|value| value.starts_with(argument))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Completes the name of the command, or its argument, as far as it is unambiguous.
+    fn complete(&mut self) {
+        if !self.input.contains(char::is_whitespace) {
+            let names = self
+                .matching_commands()
+                .iter()
+                .map(|command| command.name)
+                .collect::<Vec<_>>();
+            if let Some(name) = complete_word(&names) {
+                self.input = name;
+            }
+        } else if let Some((command, argument)) = self.typed_argument() {
+            if let Some(argument) = complete_word(&Self::matching_arguments(command, argument)) {
+                self.input = format!("{} {}", command.name, argument);
+            }
+        }
+    }
+
+    /// Returns the usage of the command being typed and the values of its argument which
+    /// match, or the names of the matching commands.
+    fn hint(&self) -> String {
+        if let Some((command, argument)) = self
+            .typed_argument()
+            .filter(|(command, _)| !command.arguments.is_empty())
+        {
+            return format!(
+                "{} {}  {}",
+                command.name,
+                command.usage,
+                Self::matching_arguments(command, argument).join(" ")
+            );
+        }
+        match self.matching_commands().as_slice() {
+            [command] => format!(
+                "{} {}  {}",
+                command.name, command.usage, command.description
+            ),
+            commands => commands
+                .iter()
+                .map(|command| command.name)
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+impl DrawableComponent for CommandLineComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let size = f.size();
+            let area = Rect::new(
+                0,
+                size.height.saturating_sub(1),
+                size.width,
+                1.min(size.height),
+            );
+            let line = Line::from(vec![
+                Span::raw(format!(":{}", self.input)),
+                Span::styled(
+                    format!("  {}", self.hint()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            f.render_widget(Clear, area);
+            f.render_widget(Paragraph::new(line), area);
+            let width = self.input.chars().map(compute_character_width).sum::<u16>();
+            f.set_cursor((area.x + 1 + width).min(area.right()), area.y);
+        }
+        Ok(())
+    }
+}
+
+impl Component for CommandLineComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.enter => {
+                self.submitted = Some(std::mem::take(&mut self.input));
+                self.hide();
+            }
+            key if key == self.key_config.exit_popup => self.hide(),
+            Key::Tab => self.complete(),
+            Key::Char(c) => self.input.push(c),
+            // like vim, deleting the empty line closes it
+            Key::Backspace if self.input.pop().is_none() => self.hide(),
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, CommandLineComponent, LineCommand};
+    use crate::config::KeyConfig;
+
+    #[test]
+    fn test_complete() {
+        assert_eq!(
+            parse(" set  limit 500"),
+            Some(("set", vec!["limit", "500"]))
+        );
+        assert_eq!(parse("  "), None);
+
+        let mut command_line = CommandLineComponent::new(KeyConfig::default());
+        command_line.open(vec![
+            LineCommand::new("table", "<table>", ""),
+            LineCommand::new("tab", "<tab>", ""),
+            LineCommand::new("export", "<format>", ""),
+        ]);
+        command_line.input = "t".to_string();
+        command_line.complete();
+        assert_eq!(command_line.input, "tab");
+        command_line.input = "e".to_string();
+        command_line.complete();
+        assert_eq!(command_line.input, "export ");
+
+        // the argument is completed from the values the command knows
+        command_line.open(vec![LineCommand::new("column", "<column>", "")
+            .with_arguments(
+                ["user_id", "user_name", "amount"]
+                    .map(String::from)
+                    .to_vec(),
+            )]);
+        command_line.input = "column u".to_string();
+        command_line.complete();
+        assert_eq!(command_line.input, "column user_");
+        assert_eq!(command_line.hint(), "column <column>  user_id user_name");
+        command_line.input.push('n');
+        command_line.complete();
+        assert_eq!(command_line.input, "column user_name ");
+    }
+}
@@ -0,0 +1,120 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// A popup which asks before a statement that changes the database is run.
+pub struct ConfirmComponent {
+    message: String,
+    sql: String,
+    confirmed: Option<String>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl ConfirmComponent {
+    const WIDTH: u16 = 70;
+    const HEIGHT: u16 = 12;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            message: String::new(),
+            sql: String::new(),
+            confirmed: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, message: String, sql: String) {
+        self.message = message;
+        self.sql = sql;
+        self.confirmed = None;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the statement once the user has confirmed it.
+    pub fn take_confirmed(&mut self) -> Option<String> {
+        self.confirmed.take()
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed.is_some()
+    }
+}
+
+impl DrawableComponent for ConfirmComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            let lines = vec![
+                Line::from(Span::styled(
+                    self.message.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::default(),
+                Line::from(self.sql.clone()),
+            ];
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                    Block::default()
+                        .title(format!(
+                            "Confirm [{}] run [{}] cancel",
+                            self.key_config.enter, self.key_config.exit_popup
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick)
+                        .style(Style::default().fg(Color::Yellow)),
+                ),
+                area,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for ConfirmComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.enter {
+            self.confirmed = Some(std::mem::take(&mut self.sql));
+            self.hide();
+        } else if key == self.key_config.exit_popup {
+            self.hide();
+        } else if key == self.key_config.exit {
+            return Ok(EventState::NotConsumed);
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
@@ -13,6 +13,8 @@ use ratatui::{
 
 pub struct ConnectionsComponent {
     connections: Vec<Connection>,
+    /// The `Connection::session_key`s of the favorite connections, which are marked with a star.
+    favorites: Vec<String>,
     state: ListState,
     key_config: KeyConfig,
 }
@@ -25,6 +27,7 @@ impl ConnectionsComponent {
         }
         Self {
             connections,
+            favorites: Vec::new(),
             key_config,
             state,
         }
@@ -72,6 +75,32 @@ impl ConnectionsComponent {
         self.state.select(Some(self.connections.len() - 1));
     }
 
+    /// Selects the connection with the given `Connection::session_key`.
+    pub fn select_connection(&mut self, session_key: &str) -> bool {
+        match self
+            .connections
+            .iter()
+            .position(|connection| connection.session_key() == session_key)
+        {
+            Some(index) => {
+                self.state.select(Some(index));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_favorites(&mut self, favorites: Vec<String>) {
+        self.favorites = favorites;
+    }
+
+    pub fn selected_connection_mut(&mut self) -> Option<&mut Connection> {
+        match self.state.selected() {
+            Some(i) => self.connections.get_mut(i),
+            None => None,
+        }
+    }
+
     pub fn selected_connection(&self) -> Option<&Connection> {
         match self.state.selected() {
             Some(i) => self.connections.get(i),
@@ -87,9 +116,16 @@ impl StatefulDrawableComponent for ConnectionsComponent {
         let conns = &self.connections;
         let mut connections: Vec<ListItem> = Vec::new();
         for c in conns {
+            let mark = if self.favorites.contains(&c.session_key()) {
+                "★ "
+            } else {
+                "  "
+            };
             match c.database_url_with_name() {
-                Ok(url) => connections
-                    .push(ListItem::new(vec![Line::from(Span::raw(url))]).style(Style::default())),
+                Ok(url) => connections.push(
+                    ListItem::new(vec![Line::from(Span::raw(format!("{}{}", mark, url)))])
+                        .style(Style::default()),
+                ),
                 Err(e) => {
                     return Err(anyhow::anyhow!(e).context("Failed to database_url_with_name"));
                 }
@@ -0,0 +1,219 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::{format_bytes, ServerOverview};
+use crate::event::Key;
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// A popup with an overview of the server of the connection: its version, uptime,
+/// connections, cache hit ratios and largest tables.
+pub struct DashboardComponent {
+    connection: String,
+    overview: ServerOverview,
+    fetched_at: DateTime<Local>,
+    refresh_requested: bool,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl DashboardComponent {
+    const WIDTH: u16 = 80;
+    /// The width of the bar of the largest table.
+    const BAR_WIDTH: usize = 20;
+    const NAME_WIDTH: usize = 26;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            connection: String::new(),
+            overview: ServerOverview::default(),
+            fetched_at: Local::now(),
+            refresh_requested: false,
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, connection: String, overview: ServerOverview) {
+        self.connection = connection;
+        self.overview = overview;
+        self.fetched_at = Local::now();
+        self.refresh_requested = false;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns true once if the user asked to fetch the figures again.
+    pub fn take_refresh(&mut self) -> bool {
+        std::mem::take(&mut self.refresh_requested)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let heading = Style::default().add_modifier(Modifier::BOLD);
+        let dim = Style::default().fg(Color::DarkGray);
+        let mut lines = vec![Line::from(Span::styled(self.connection.clone(), dim))];
+        lines.push(Line::default());
+        lines.extend(self.overview.figures.iter().map(|(name, value)| {
+            Line::from(vec![
+                Span::raw(format!("{:<24}", name)),
+                Span::styled(value.clone(), Style::default().fg(Color::Cyan)),
+            ])
+        }));
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled("Largest tables", heading)));
+        if self.overview.largest_tables.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "The server does not report the sizes of its tables.",
+                dim,
+            )));
+        }
+        let largest = self
+            .overview
+            .largest_tables
+            .first()
+            .map_or(0, |(_, size)| *size);
+        lines.extend(self.overview.largest_tables.iter().map(|(name, size)| {
+            Line::from(vec![
+                Span::raw(format!(
+                    "{:<width$} ",
+                    truncate(name, Self::NAME_WIDTH),
+                    width = Self::NAME_WIDTH
+                )),
+                Span::styled(
+                    format!("{:<width$}", bar(*size, largest), width = Self::BAR_WIDTH),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(format!(" {}", format_bytes(*size))),
+            ])
+        }));
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(
+            format!("fetched at {}", self.fetched_at.format("%H:%M:%S")),
+            dim,
+        )));
+        lines
+    }
+}
+
+/// Returns a bar of block characters as long as `size` is against `largest`.
+fn bar(size: u64, largest: u64) -> String {
+    if largest == 0 {
+        return String::new();
+    }
+    let length = (size as f64 / largest as f64 * DashboardComponent::BAR_WIDTH as f64).round();
+    "█".repeat((length as usize).max(1))
+}
+
+/// Cuts `name` to `width` characters, marking the cut with an ellipsis.
+fn truncate(name: &str, width: usize) -> String {
+    if name.chars().count() <= width {
+        name.to_string()
+    } else {
+        let mut cut: String = name.chars().take(width.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+impl DrawableComponent for DashboardComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let lines = self.lines();
+            let height = lines.len() as u16 + 2;
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(height)) / 2,
+                Self::WIDTH.min(f.size().width),
+                height.min(f.size().height),
+            );
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .title(format!(
+                            "Dashboard [{}] refresh [{}] close",
+                            self.key_config.refresh, self.key_config.exit_popup
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                area,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for DashboardComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit {
+            return Ok(EventState::NotConsumed);
+        } else if key == self.key_config.exit_popup || key == self.key_config.open_dashboard {
+            self.hide();
+        } else if key == self.key_config.refresh {
+            self.refresh_requested = true;
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bar, truncate, DashboardComponent};
+    use crate::config::KeyConfig;
+    use crate::database::ServerOverview;
+
+    #[test]
+    fn test_dashboard() {
+        assert_eq!(bar(100, 100).chars().count(), DashboardComponent::BAR_WIDTH);
+        assert_eq!(
+            bar(50, 100).chars().count(),
+            DashboardComponent::BAR_WIDTH / 2
+        );
+        // a small table still gets a bar
+        assert_eq!(bar(1, 1_000_000), "█");
+        assert_eq!(truncate("public.orders", 8), "public.…");
+
+        let mut dashboard = DashboardComponent::new(KeyConfig::default());
+        dashboard.open(
+            "local".to_string(),
+            ServerOverview::new(
+                vec![("uptime", Some("3d 4h".to_string()))],
+                vec![("shop.orders".to_string(), 2048)],
+            ),
+        );
+        let text = dashboard
+            .lines()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert!(text
+            .iter()
+            .any(|line| line.starts_with("uptime") && line.ends_with("3d 4h")));
+        assert!(text.iter().any(|line| line.ends_with(" 2.0 KiB")));
+    }
+}
@@ -3,10 +3,10 @@ use super::{
     EventState,
 };
 use crate::components::command::{self, CommandInfo};
-use crate::config::{Connection, KeyConfig};
-use crate::database::Pool;
+use crate::config::{Connection, DisplayConfig, KeyConfig, TreeIcons};
+use crate::database::{get_databases_with_tables, Dialect, Pool};
 use crate::event::Key;
-use crate::tree::{Database, DatabaseTree, DatabaseTreeItem};
+use crate::tree::{Database, DatabaseTree, DatabaseTreeItem, DatabaseTreeItemKind, TableKind};
 use crate::ui::common_nav;
 use crate::ui::scrolllist::draw_list_block;
 use anyhow::Result;
@@ -19,12 +19,22 @@ use ratatui::{
 };
 use std::collections::BTreeSet;
 use std::convert::From;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 // ▸
 const FOLDER_ICON_COLLAPSED: &str = "\u{25b8}";
 // ▾
 const FOLDER_ICON_EXPANDED: &str = "\u{25be}";
 const EMPTY_STR: &str = "";
+// ◇
+const VIEW_ICON: &str = "\u{25c7} ";
+// ◆
+const MATERIALIZED_VIEW_ICON: &str = "\u{25c6} ";
+// ƒ
+const FUNCTION_ICON: &str = "\u{0192} ";
+// ℙ
+const PROCEDURE_ICON: &str = "\u{2119} ";
+const SEQUENCE_ICON: &str = "# ";
 
 #[derive(PartialEq)]
 pub enum Focus {
@@ -36,52 +46,245 @@ pub struct DatabasesComponent {
     tree: DatabaseTree,
     filter: DatabaseFilterComponent,
     filtered_tree: Option<DatabaseTree>,
+    /// The databases whose tables are not read yet, which they are once expanded.
+    unloaded: BTreeSet<String>,
+    /// Everything read for the tree, including what `show_system_objects` hides.
+    databases: Vec<Database>,
+    show_system_objects: bool,
+    dialect: Dialect,
+    /// The database set in the connection, which is shown even if it is a system one.
+    connection_database: Option<String>,
     scroll: VerticalScroll,
     focus: Focus,
     key_config: KeyConfig,
+    display: DisplayConfig,
 }
 
 impl DatabasesComponent {
-    pub fn new(key_config: KeyConfig) -> Self {
+    pub fn new(key_config: KeyConfig, display: DisplayConfig) -> Self {
         Self {
             tree: DatabaseTree::default(),
             filter: DatabaseFilterComponent::new(),
             filtered_tree: None,
+            unloaded: BTreeSet::new(),
+            databases: Vec::new(),
+            show_system_objects: display.system_objects,
+            dialect: Dialect::MySql,
+            connection_database: None,
             scroll: VerticalScroll::new(false, false),
             focus: Focus::Tree,
             key_config,
+            display,
         }
     }
 
     pub async fn update(&mut self, connection: &Connection, pool: &Box<dyn Pool>) -> Result<()> {
-        let databases = match &connection.database {
+        self.unloaded.clear();
+        self.dialect = connection.dialect();
+        self.connection_database = connection.database().cloned();
+        let mut databases = match connection.database() {
             Some(database) => vec![Database::new(
                 database.clone(),
                 pool.get_tables(database.clone()).await?,
             )],
-            None => pool.get_databases().await?,
+            None => {
+                let databases = pool.get_databases().await?;
+                self.unloaded = databases
+                    .iter()
+                    .map(|database| database.name.clone())
+                    .collect();
+                databases
+            }
         };
-        self.tree = DatabaseTree::new(databases.as_slice(), &BTreeSet::new())?;
+        if let Some(schema) = &connection.schema {
+            for database in &mut databases {
+                database.retain_schema(schema);
+            }
+        }
+        self.databases = databases;
+        self.tree = DatabaseTree::new(&self.shown_databases(), &BTreeSet::new())?;
         self.filtered_tree = None;
         self.filter.reset();
         Ok(())
     }
 
+    /// Returns the databases without the system ones, unless they are shown or set in the
+    /// connection.
+    fn shown_databases(&self) -> Vec<Database> {
+        self.databases
+            .iter()
+            .filter(|database| {
+                self.show_system_objects
+                    || !database.is_system(self.dialect)
+                    || self.connection_database.as_ref() == Some(&database.name)
+            })
+            .map(|database| self.shown_database(database))
+            .collect()
+    }
+
+    fn shown_database(&self, database: &Database) -> Database {
+        let mut database = database.clone();
+        if !self.show_system_objects {
+            database.retain_user_objects(self.dialect);
+        }
+        database
+    }
+
+    /// Shows or hides the system databases, schemas and tables, keeping the expanded nodes
+    /// and the selection.
+    fn toggle_system_objects(&mut self) -> Result<()> {
+        self.show_system_objects = !self.show_system_objects;
+        let expanded = self.tree.expanded_nodes();
+        let selected = self.tree.selected_item().map(|item| item.kind().clone());
+        self.tree = DatabaseTree::new(&self.shown_databases(), &BTreeSet::new())?;
+        self.tree.expand_nodes(&expanded);
+        match selected {
+            Some(DatabaseTreeItemKind::Database { name, .. }) => {
+                self.tree.select_node(&name, None);
+            }
+            Some(DatabaseTreeItemKind::Schema {
+                database, schema, ..
+            }) => {
+                self.tree.select_node(&database.name, Some(&schema.name));
+            }
+            Some(DatabaseTreeItemKind::Table { database, table }) => {
+                self.tree
+                    .select_table(&database.name, table.schema.as_deref(), &table.name);
+            }
+            None => (),
+        }
+        if self.filtered_tree.is_some() {
+            self.filtered_tree = Some(self.tree.filter(self.filter.input_str()));
+        }
+        Ok(())
+    }
+
+    /// Returns the selected database if it was expanded but its tables are not read yet.
+    pub fn database_to_load(&self) -> Option<String> {
+        self.tree()
+            .selected_item()
+            .filter(|item| item.is_database() && !item.kind().is_database_collapsed())
+            .map(|item| item.kind().name())
+            .filter(|name| self.unloaded.contains(name))
+    }
+
+    /// Returns the databases whose tables are not read yet while the filter is typed, as it
+    /// only finds the tables which are in the tree.
+    pub fn databases_to_filter(&self) -> Vec<String> {
+        if matches!(self.focus, Focus::Filter) {
+            self.unloaded.iter().cloned().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn is_loaded(&self, database: &str) -> bool {
+        !self.unloaded.contains(database)
+    }
+
+    /// Reads the tables of `database`, for the first time once it is expanded or again to
+    /// refresh it.
+    pub async fn load_database(
+        &mut self,
+        connection: &Connection,
+        pool: &Box<dyn Pool>,
+        database: &str,
+    ) -> Result<()> {
+        self.load_databases(connection, pool, vec![database.to_string()])
+            .await?;
+        if let Some(filtered_tree) = self.filtered_tree.as_mut() {
+            filtered_tree.select_node(database, None);
+        }
+        Ok(())
+    }
+
+    /// Reads the tables of the databases named `names`, several at once.
+    pub async fn load_databases(
+        &mut self,
+        connection: &Connection,
+        pool: &Box<dyn Pool>,
+        names: Vec<String>,
+    ) -> Result<()> {
+        for mut database in get_databases_with_tables(pool.as_ref(), names).await? {
+            if let Some(schema) = &connection.schema {
+                database.retain_schema(schema);
+            }
+            let shown = self.shown_database(&database);
+            self.tree.set_children(&shown)?;
+            self.unloaded.remove(&database.name);
+            if let Some(old) = self.databases.iter_mut().find(|d| d.name == database.name) {
+                *old = database;
+            }
+        }
+        if let Some(filtered_tree) = self.filtered_tree.as_mut() {
+            *filtered_tree = self.tree.filter(self.filter.input_str());
+        }
+        Ok(())
+    }
+
     pub fn tree_focused(&self) -> bool {
         matches!(self.focus, Focus::Tree)
     }
 
+    pub fn tree_mut(&mut self) -> &mut DatabaseTree {
+        self.filtered_tree.as_mut().unwrap_or(&mut self.tree)
+    }
+
     pub fn tree(&self) -> &DatabaseTree {
         self.filtered_tree.as_ref().unwrap_or(&self.tree)
     }
 
+    /// Describes the selected item of the tree in words for screen readers.
+    pub fn describe(&self) -> String {
+        if !self.tree_focused() {
+            return format!("filter: {}", self.filter.input_str());
+        }
+        let item = self.tree().selected_item();
+        match item.map(DatabaseTreeItem::kind) {
+            Some(DatabaseTreeItemKind::Database { name, collapsed }) => format!(
+                "database {}, {}",
+                name,
+                if *collapsed { "collapsed" } else { "expanded" }
+            ),
+            Some(DatabaseTreeItemKind::Schema {
+                database,
+                schema,
+                collapsed,
+            }) => format!(
+                "schema {} in {}, {}",
+                schema.name,
+                database.name,
+                if *collapsed { "collapsed" } else { "expanded" }
+            ),
+            Some(DatabaseTreeItemKind::Table { database, table }) => {
+                let mut description = format!("{} {} in {}", table.kind, table.name, database.name);
+                if let Some(parent) = &table.partition_of {
+                    description.push_str(&format!(", partition of {}", parent));
+                }
+                if let Some(item) = item.filter(|item| item.is_partitioned()) {
+                    description.push_str(&format!(
+                        ", {} partitions, {}",
+                        item.children(),
+                        if item.is_partitions_collapsed() {
+                            "collapsed"
+                        } else {
+                            "expanded"
+                        }
+                    ));
+                }
+                description
+            }
+            None => "no databases".to_string(),
+        }
+    }
+
     fn tree_item_to_span(
         item: DatabaseTreeItem,
         selected: bool,
         width: u16,
         filter: Option<String>,
+        display: &DisplayConfig,
     ) -> Line<'static> {
-        let name = item.kind().name();
         let indent = item.info().indent();
 
         let indent_str = if indent == 0 {
@@ -90,16 +293,40 @@ impl DatabasesComponent {
             format!("{:w$}", " ", w = (indent as usize) * 2)
         };
 
-        let arrow = if item.kind().is_database() || item.kind().is_schema() {
-            if item.kind().is_database_collapsed() || item.kind().is_schema_collapsed() {
+        let arrow = if item.kind().is_database() || item.kind().is_schema() || item.is_partitioned()
+        {
+            if item.kind().is_database_collapsed()
+                || item.kind().is_schema_collapsed()
+                || item.is_partitions_collapsed()
+            {
                 FOLDER_ICON_COLLAPSED
             } else {
                 FOLDER_ICON_EXPANDED
             }
         } else {
-            EMPTY_STR
+            match item.kind() {
+                DatabaseTreeItemKind::Table { table, .. } => {
+                    table_icon(table.kind, display.tree_icons)
+                }
+                _ => EMPTY_STR,
+            }
         };
 
+        let count = if display.tree_counts
+            && (item.kind().is_database_collapsed()
+                || item.kind().is_schema_collapsed()
+                || item.is_partitions_collapsed())
+        {
+            format!(" ({})", item.children())
+        } else {
+            String::new()
+        };
+
+        let name = truncate(
+            &item.kind().name(),
+            (width as usize).saturating_sub(indent_str.width() + arrow.width() + count.width()),
+        );
+
         if let Some(filter) = filter {
             if item.kind().is_table() && name.contains(&filter) {
                 let (first, rest) = &name.split_at(name.find(filter.as_str()).unwrap_or(0));
@@ -122,7 +349,7 @@ impl DatabasesComponent {
                         },
                     ),
                     Span::styled(
-                        format!("{:w$}", last.to_string(), w = width as usize),
+                        pad(last, width as usize),
                         if selected {
                             Style::default().bg(Color::Blue)
                         } else {
@@ -134,7 +361,12 @@ impl DatabasesComponent {
         }
 
         Line::from(Span::styled(
-            format!("{}{}{:w$}", indent_str, arrow, name, w = width as usize),
+            format!(
+                "{}{}{}",
+                indent_str,
+                arrow,
+                pad(&format!("{}{}", name, count), width as usize)
+            ),
             if selected {
                 Style::default().bg(Color::Blue)
             } else {
@@ -182,18 +414,28 @@ impl DatabasesComponent {
             },
         );
 
+        // an unread database has no count of its tables yet
+        let uncounted = DisplayConfig {
+            tree_counts: false,
+            ..self.display.clone()
+        };
         let items = tree
             .iterate(self.scroll.get_top(), tree_height)
             .map(|(item, selected)| {
                 Self::tree_item_to_span(
                     item.clone(),
                     selected,
-                    area.width,
+                    chunks[1].width,
                     if self.filter.input_str().is_empty() {
                         None
                     } else {
                         Some(self.filter.input_str())
                     },
+                    if item.is_database() && self.unloaded.contains(&item.kind().name()) {
+                        &uncounted
+                    } else {
+                        &self.display
+                    },
                 )
             });
 
@@ -204,6 +446,55 @@ impl DatabasesComponent {
     }
 }
 
+fn table_icon(kind: TableKind, icons: TreeIcons) -> &'static str {
+    match icons {
+        TreeIcons::Glyphs => match kind {
+            TableKind::Table => EMPTY_STR,
+            TableKind::View => VIEW_ICON,
+            TableKind::MaterializedView => MATERIALIZED_VIEW_ICON,
+            TableKind::Function => FUNCTION_ICON,
+            TableKind::Procedure => PROCEDURE_ICON,
+            TableKind::Sequence => SEQUENCE_ICON,
+        },
+        TreeIcons::Letters => match kind {
+            TableKind::Table => "T ",
+            TableKind::View => "V ",
+            TableKind::MaterializedView => "MV ",
+            TableKind::Function => "F ",
+            TableKind::Procedure => "P ",
+            TableKind::Sequence => "S ",
+        },
+        TreeIcons::None => EMPTY_STR,
+    }
+}
+
+/// Cuts `name` to at most `width` columns, ending it with an ellipsis if it is cut. Wide
+/// characters such as CJK or emoji are never split.
+fn truncate(name: &str, width: usize) -> String {
+    if name.width() <= width {
+        return name.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in name.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width + 1 > width {
+            break;
+        }
+        used += char_width;
+        truncated.push(c);
+    }
+    if width > 0 {
+        truncated.push('\u{2026}');
+    }
+    truncated
+}
+
+/// Pads `text` with spaces to `width` columns, counting wide characters as two.
+fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
+
 impl DrawableComponent for DatabasesComponent {
     fn draw(&self, f: &mut Frame, area: Rect, focused: bool) -> Result<()> {
         let chunks = Layout::default()
@@ -218,7 +509,10 @@ impl DrawableComponent for DatabasesComponent {
 
 impl Component for DatabasesComponent {
     fn commands(&self, out: &mut Vec<CommandInfo>) {
-        out.push(CommandInfo::new(command::expand_collapse(&self.key_config)))
+        out.push(CommandInfo::new(command::expand_collapse(&self.key_config)));
+        out.push(CommandInfo::new(command::toggle_system_objects(
+            &self.key_config,
+        )));
     }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
@@ -227,6 +521,11 @@ impl Component for DatabasesComponent {
             return Ok(EventState::Consumed);
         }
 
+        if key == self.key_config.toggle_system_objects && self.focus == Focus::Tree {
+            self.toggle_system_objects()?;
+            return Ok(EventState::Consumed);
+        }
+
         if matches!(self.focus, Focus::Filter) {
             self.filtered_tree = if self.filter.input_str().is_empty() {
                 None
@@ -273,8 +572,69 @@ fn tree_nav(tree: &mut DatabaseTree, key: Key, key_config: &KeyConfig) -> bool {
 
 #[cfg(test)]
 mod test {
-    use super::{Color, Database, DatabaseTreeItem, DatabasesComponent, Line, Span, Style};
-    use crate::tree::Table;
+    use super::{
+        pad, truncate, Color, Database, DatabaseTreeItem, DatabasesComponent, DisplayConfig, Line,
+        Span, Style, TreeIcons,
+    };
+    use crate::tree::{Table, TableKind};
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("users", 10), "users");
+        assert_eq!(truncate("users", 5), "users");
+        assert_eq!(truncate("users", 4), "use\u{2026}");
+        assert_eq!(
+            truncate("\u{4e16}\u{754c}\u{5730}\u{56fe}", 6),
+            "\u{4e16}\u{754c}\u{2026}"
+        );
+        assert_eq!(
+            truncate("\u{4e16}\u{754c}\u{5730}\u{56fe}", 5),
+            "\u{4e16}\u{754c}\u{2026}"
+        );
+        assert_eq!(
+            truncate("\u{4e16}\u{754c}\u{5730}\u{56fe}", 4),
+            "\u{4e16}\u{2026}"
+        );
+        assert_eq!(truncate("\u{1f600}\u{1f600}log", 4), "\u{1f600}\u{2026}");
+        assert_eq!(truncate("caf\u{e9}_\u{e9}t\u{e9}", 6), "caf\u{e9}_\u{2026}");
+        assert_eq!(truncate("users", 0), "");
+    }
+
+    #[test]
+    fn test_pad() {
+        assert_eq!(pad("ab", 4), "ab  ");
+        assert_eq!(pad("\u{4e16}\u{754c}", 6), "\u{4e16}\u{754c}  ");
+        assert_eq!(pad("abcdef", 4), "abcdef");
+    }
+
+    #[test]
+    fn test_multibyte_tree_item_to_span() {
+        const WIDTH: u16 = 8;
+        assert_eq!(
+            DatabasesComponent::tree_item_to_span(
+                DatabaseTreeItem::new_table(
+                    &Database {
+                        name: "foo".to_string(),
+                        children: Vec::new(),
+                    },
+                    &Table {
+                        name: "\u{6ce8}\u{6587}\u{5c65}\u{6b74}".to_string(),
+                        create_time: None,
+                        update_time: None,
+                        engine: None,
+                        schema: None,
+                        kind: TableKind::Table,
+                        partition_of: None,
+                    },
+                ),
+                false,
+                WIDTH,
+                None,
+                &DisplayConfig::default(),
+            ),
+            Line::from(vec![Span::raw("  \u{6ce8}\u{6587}\u{2026}   ")])
+        );
+    }
 
     #[test]
     fn test_tree_tree_item_to_span() {
@@ -291,10 +651,11 @@ mod test {
                 false,
                 WIDTH,
                 None,
+                &DisplayConfig::default(),
             ),
             Line::from(vec![Span::raw(format!(
                 "\u{25b8}{:w$}",
-                "foo",
+                "foo (0)",
                 w = WIDTH as usize
             ))])
         );
@@ -311,14 +672,82 @@ mod test {
                 true,
                 WIDTH,
                 None,
+                &DisplayConfig::default(),
             ),
             Line::from(vec![Span::styled(
-                format!("\u{25b8}{:w$}", "foo", w = WIDTH as usize),
+                format!("\u{25b8}{:w$}", "foo (0)", w = WIDTH as usize),
                 Style::default().bg(Color::Blue)
             )])
         );
     }
 
+    #[test]
+    fn test_display_config_tree_item_to_span() {
+        const WIDTH: u16 = 10;
+        let display = DisplayConfig {
+            tree_icons: TreeIcons::Letters,
+            tree_counts: false,
+            ..DisplayConfig::default()
+        };
+        assert_eq!(
+            DatabasesComponent::tree_item_to_span(
+                DatabaseTreeItem::new_database(
+                    &Database {
+                        name: "foo".to_string(),
+                        children: vec![Table {
+                            name: "bar".to_string(),
+                            create_time: None,
+                            update_time: None,
+                            engine: None,
+                            schema: None,
+                            kind: TableKind::Table,
+                            partition_of: None,
+                        }
+                        .into()],
+                    },
+                    false,
+                ),
+                false,
+                WIDTH,
+                None,
+                &display,
+            ),
+            Line::from(vec![Span::raw(format!(
+                "\u{25b8}{:w$}",
+                "foo",
+                w = WIDTH as usize
+            ))])
+        );
+        assert_eq!(
+            DatabasesComponent::tree_item_to_span(
+                DatabaseTreeItem::new_table(
+                    &Database {
+                        name: "foo".to_string(),
+                        children: Vec::new(),
+                    },
+                    &Table {
+                        name: "bar".to_string(),
+                        create_time: None,
+                        update_time: None,
+                        engine: None,
+                        schema: None,
+                        kind: TableKind::MaterializedView,
+                        partition_of: None,
+                    },
+                ),
+                false,
+                WIDTH,
+                None,
+                &display,
+            ),
+            Line::from(vec![Span::raw(format!(
+                "  MV {:w$}",
+                "bar",
+                w = WIDTH as usize
+            ))])
+        );
+    }
+
     #[test]
     fn test_tree_table_tree_item_to_span() {
         const WIDTH: u16 = 10;
@@ -334,12 +763,15 @@ mod test {
                         create_time: None,
                         update_time: None,
                         engine: None,
-                        schema: None
+                        schema: None,
+                        kind: TableKind::Table,
+                        partition_of: None,
                     },
                 ),
                 false,
                 WIDTH,
                 None,
+                &DisplayConfig::default(),
             ),
             Line::from(vec![Span::raw(format!(
                 "  {:w$}",
@@ -360,18 +792,50 @@ mod test {
                         create_time: None,
                         update_time: None,
                         engine: None,
-                        schema: None
+                        schema: None,
+                        kind: TableKind::Table,
+                        partition_of: None,
                     },
                 ),
                 true,
                 WIDTH,
                 None,
+                &DisplayConfig::default(),
             ),
             Line::from(Span::styled(
                 format!("  {:w$}", "bar", w = WIDTH as usize),
                 Style::default().bg(Color::Blue),
             ))
         );
+
+        assert_eq!(
+            DatabasesComponent::tree_item_to_span(
+                DatabaseTreeItem::new_table(
+                    &Database {
+                        name: "foo".to_string(),
+                        children: Vec::new(),
+                    },
+                    &Table {
+                        name: "bar".to_string(),
+                        create_time: None,
+                        update_time: None,
+                        engine: None,
+                        schema: None,
+                        kind: TableKind::View,
+                        partition_of: None,
+                    },
+                ),
+                false,
+                WIDTH,
+                None,
+                &DisplayConfig::default(),
+            ),
+            Line::from(vec![Span::raw(format!(
+                "  \u{25c7} {:w$}",
+                "bar",
+                w = WIDTH as usize
+            ))])
+        );
     }
 
     #[test]
@@ -389,12 +853,15 @@ mod test {
                         create_time: None,
                         update_time: None,
                         engine: None,
-                        schema: None
+                        schema: None,
+                        kind: TableKind::Table,
+                        partition_of: None,
                     },
                 ),
                 false,
                 WIDTH,
                 Some("rb".to_string()),
+                &DisplayConfig::default(),
             ),
             Line::from(vec![
                 Span::raw(format!("  {}", "ba")),
@@ -415,12 +882,15 @@ mod test {
                         create_time: None,
                         update_time: None,
                         engine: None,
-                        schema: None
+                        schema: None,
+                        kind: TableKind::Table,
+                        partition_of: None,
                     },
                 ),
                 true,
                 WIDTH,
                 Some("rb".to_string()),
+                &DisplayConfig::default(),
             ),
             Line::from(vec![
                 Span::styled(format!("  {}", "ba"), Style::default().bg(Color::Blue)),
@@ -1,75 +1,187 @@
 use super::{Component, DrawableComponent, EventState};
 use crate::components::command::CommandInfo;
 use crate::config::KeyConfig;
+use crate::database::FailedStatement;
 use crate::event::Key;
 use anyhow::Result;
+use chrono::{DateTime, Local};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
+/// An error which was reported in this session.
+#[derive(Debug, Clone)]
+pub struct ErrorEntry {
+    pub time: DateTime<Local>,
+    /// The whole chain of the error, e.g. the message of the driver after what failed.
+    pub message: String,
+    /// The statement which failed, if the error came from running one.
+    pub statement: Option<String>,
+}
+
+impl ErrorEntry {
+    fn new(error: &anyhow::Error) -> Self {
+        Self {
+            time: Local::now(),
+            message: format!("{:#}", error),
+            statement: error
+                .downcast_ref::<FailedStatement>()
+                .map(|statement| statement.0.clone()),
+        }
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{}  {}",
+            self.time.format("%H:%M:%S"),
+            self.message.lines().next().unwrap_or_default()
+        )
+    }
+}
+
+/// The console of the errors of the session, which opens with the newest one when an error
+/// is reported and shows the full message and the failed statement of the selected one.
 pub struct ErrorComponent {
-    pub error: String,
+    entries: Vec<ErrorEntry>,
+    selected: usize,
+    scroll: u16,
     visible: bool,
     key_config: KeyConfig,
 }
 
 impl ErrorComponent {
-    const WIDTH: u16 = 65;
-    const HEIGHT: u16 = 10;
+    /// The most errors kept, the oldest being forgotten first.
+    const CAPACITY: usize = 100;
+    const LIST_HEIGHT: u16 = 8;
+
     pub fn new(key_config: KeyConfig) -> Self {
         Self {
-            error: String::new(),
+            entries: Vec::new(),
+            selected: 0,
+            scroll: 0,
             visible: false,
             key_config,
         }
     }
 
-    pub fn set(&mut self, error: String) -> anyhow::Result<()> {
-        self.error = error;
+    pub const fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Records the error and opens the console on it.
+    pub fn push(&mut self, error: &anyhow::Error) -> Result<()> {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.remove(0);
+        }
+        let entry = ErrorEntry::new(error);
+        crate::log!(Error, "{}", entry.message);
+        self.entries.push(entry);
+        self.selected = self.entries.len() - 1;
+        self.scroll = 0;
         self.show()
     }
+
+    pub fn open(&mut self) {
+        self.selected = self.entries.len().saturating_sub(1);
+        self.scroll = 0;
+        self.visible = true;
+    }
+
+    pub fn selected(&self) -> Option<&ErrorEntry> {
+        self.entries.get(self.selected)
+    }
+
+    fn details(&self) -> Vec<Line<'static>> {
+        let entry = match self.selected() {
+            Some(entry) => entry,
+            None => {
+                return vec![Line::from(Span::styled(
+                    "No errors in this session.",
+                    Style::default().fg(Color::DarkGray),
+                ))]
+            }
+        };
+        let mut lines: Vec<Line> = entry
+            .message
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect();
+        if let Some(statement) = &entry.statement {
+            lines.push(Line::default());
+            lines.push(Line::from(Span::styled(
+                "Statement:",
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            lines.extend(statement.lines().map(|line| Line::from(line.to_string())));
+        }
+        lines
+    }
 }
 
 impl DrawableComponent for ErrorComponent {
     fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
         if self.visible {
-            let error = Block::default()
-                .title("Error")
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Red));
-
+            let width = (f.size().width * 4 / 5).max(40).min(f.size().width);
+            let height = (f.size().height * 4 / 5).max(12).min(f.size().height);
             let area = Rect::new(
-                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
-                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
-                Self::WIDTH.min(f.size().width),
-                Self::HEIGHT.min(f.size().height),
+                (f.size().width - width) / 2,
+                (f.size().height - height) / 2,
+                width,
+                height,
             );
+            let block = Block::default()
+                .title(format!(
+                    "Errors ({}) [{}] close [{}/{}] select",
+                    self.entries.len(),
+                    self.key_config.exit_popup,
+                    self.key_config.scroll_up,
+                    self.key_config.scroll_down
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick)
+                .style(Style::default().fg(Color::Red));
+            let list_height = (self.entries.len() as u16).clamp(1, Self::LIST_HEIGHT);
             let chunks = Layout::default()
-                .vertical_margin(1)
-                .horizontal_margin(1)
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
-                .split(area);
+                .constraints([Constraint::Length(list_height), Constraint::Min(1)])
+                .split(block.inner(area));
+            let offset = self
+                .selected
+                .saturating_sub((list_height as usize).saturating_sub(1));
+            let list: Vec<Line> = self
+                .entries
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(list_height as usize)
+                .map(|(index, entry)| {
+                    let style = if index == self.selected {
+                        Style::default()
+                            .fg(Color::Blue)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Reset)
+                    };
+                    Line::from(Span::styled(entry.summary(), style))
+                })
+                .collect();
 
             f.render_widget(Clear, area);
-            f.render_widget(error, area);
+            f.render_widget(block, area);
+            f.render_widget(Paragraph::new(list), chunks[0]);
             f.render_widget(
-                Paragraph::new(self.error.to_string()).wrap(Wrap { trim: true }),
-                chunks[0],
-            );
-            f.render_widget(
-                Paragraph::new(Line::from(vec![Span::styled(
-                    format!(
-                        "Press [{}] to close this modal.",
-                        self.key_config.exit_popup
-                    ),
-                    Style::default(),
-                )]))
-                .alignment(Alignment::Right),
+                Paragraph::new(self.details())
+                    .block(
+                        Block::default()
+                            .borders(Borders::TOP)
+                            .style(Style::default().fg(Color::Reset)),
+                    )
+                    .wrap(Wrap { trim: false })
+                    .scroll((self.scroll, 0)),
                 chunks[1],
             );
         }
@@ -81,15 +193,34 @@ impl Component for ErrorComponent {
     fn commands(&self, _out: &mut Vec<CommandInfo>) {}
 
     fn event(&mut self, key: Key) -> Result<EventState> {
-        if self.visible {
-            if key == self.key_config.exit_popup {
-                self.error = String::new();
-                self.hide();
-                return Ok(EventState::Consumed);
-            }
+        if !self.visible {
             return Ok(EventState::NotConsumed);
         }
-        Ok(EventState::NotConsumed)
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.exit_popup || key == self.key_config.open_errors => {
+                self.hide()
+            }
+            key if key == self.key_config.scroll_up || key == self.key_config.move_up => {
+                self.selected = self.selected.saturating_sub(1);
+                self.scroll = 0;
+            }
+            key if (key == self.key_config.scroll_down || key == self.key_config.move_down)
+                && self.selected + 1 < self.entries.len() =>
+            {
+                self.selected += 1;
+                self.scroll = 0;
+            }
+            // the details of a long error scroll like a page
+            key if key == self.key_config.scroll_down_multiple_lines => {
+                self.scroll = self.scroll.saturating_add(5);
+            }
+            key if key == self.key_config.scroll_up_multiple_lines => {
+                self.scroll = self.scroll.saturating_sub(5);
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
     }
 
     fn hide(&mut self) {
@@ -102,3 +233,34 @@ impl Component for ErrorComponent {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ErrorComponent;
+    use crate::config::KeyConfig;
+    use crate::database::FailedStatement;
+
+    #[test]
+    fn test_push() {
+        let mut console = ErrorComponent::new(KeyConfig::default());
+        let error = anyhow::anyhow!("relation \"users\" does not exist")
+            .context(FailedStatement("SELECT * FROM users".to_string()));
+        console.push(&error).unwrap();
+        assert!(console.is_visible());
+        let entry = console.selected().unwrap();
+        assert_eq!(
+            entry.message,
+            "failed to run the statement: relation \"users\" does not exist"
+        );
+        assert_eq!(entry.statement.as_deref(), Some("SELECT * FROM users"));
+
+        // the newest error is selected, and the oldest ones are forgotten
+        for index in 0..ErrorComponent::CAPACITY {
+            console.push(&anyhow::anyhow!("error {}", index)).unwrap();
+        }
+        assert_eq!(console.entries.len(), ErrorComponent::CAPACITY);
+        assert_eq!(console.selected().unwrap().message, "error 99");
+        assert_eq!(console.selected().unwrap().statement, None);
+        assert_eq!(console.entries[0].message, "error 0");
+    }
+}
@@ -0,0 +1,128 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::Pool;
+use crate::event::Key;
+use crate::export::ExportJob;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, Clear, Gauge},
+    Frame,
+};
+use std::path::PathBuf;
+
+/// A popup which shows how far an export has got while it runs in the background.
+pub struct ExportProgressComponent {
+    job: Option<ExportJob>,
+    key_config: KeyConfig,
+}
+
+impl ExportProgressComponent {
+    const WIDTH: u16 = 70;
+    const HEIGHT: u16 = 3;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            job: None,
+            key_config,
+        }
+    }
+
+    pub fn start(&mut self, job: ExportJob) {
+        if let Some(job) = self.job.replace(job) {
+            job.cancel();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    /// Writes the next page and returns the path of the file and the number of rows once the
+    /// export is done. A failed export removes its file.
+    pub async fn step(&mut self, pool: &dyn Pool) -> Result<Option<(PathBuf, usize)>> {
+        let job = match self.job.as_mut() {
+            Some(job) => job,
+            None => return Ok(None),
+        };
+        if let Err(err) = job.step(pool).await {
+            if let Some(job) = self.job.take() {
+                job.cancel();
+            }
+            return Err(err.context("Export failed"));
+        }
+        match self.job.take() {
+            Some(job) if job.is_finished() => job.finish().map(Some),
+            job => {
+                self.job = job;
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl DrawableComponent for ExportProgressComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if let Some(job) = self.job.as_ref() {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            let (ratio, label) = match job.total() {
+                Some(total) if total > 0 => (
+                    (job.row_count() as f64 / total as f64).min(1.0),
+                    format!("{}/{} rows", job.row_count(), total),
+                ),
+                _ => (0.0, format!("{} rows", job.row_count())),
+            };
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Gauge::default()
+                    .block(
+                        Block::default()
+                            .title(format!(
+                                "Exporting to {} [{}] cancel",
+                                job.path().display(),
+                                self.key_config.exit_popup
+                            ))
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Thick),
+                    )
+                    .gauge_style(Style::default().fg(Color::Green))
+                    .ratio(ratio)
+                    .label(label),
+                area,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for ExportProgressComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.job.is_none() || key == self.key_config.exit {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit_popup {
+            self.hide();
+        }
+        Ok(EventState::Consumed)
+    }
+
+    /// Cancels the export.
+    fn hide(&mut self) {
+        if let Some(job) = self.job.take() {
+            job.cancel();
+        }
+    }
+
+    fn show(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
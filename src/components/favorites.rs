@@ -0,0 +1,183 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use crate::favorites::FavoriteTable;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// An entry of the favorites popup.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Favorite {
+    /// See `Connection::session_key`.
+    Connection(String),
+    /// A table of the connection in use.
+    Table(FavoriteTable),
+}
+
+impl Favorite {
+    fn label(&self) -> String {
+        match self {
+            Self::Connection(connection) => format!("[connection] {}", connection),
+            Self::Table(table) => format!("[table] {}", table.path()),
+        }
+    }
+}
+
+/// A popup which lists the favorite connections and tables to switch to.
+pub struct FavoritesComponent {
+    favorites: Vec<Favorite>,
+    selected: usize,
+    chosen: Option<Favorite>,
+    removed: Option<Favorite>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl FavoritesComponent {
+    const WIDTH: u16 = 80;
+    const HEIGHT: u16 = 16;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            favorites: Vec::new(),
+            selected: 0,
+            chosen: None,
+            removed: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, favorites: Vec<Favorite>) {
+        self.favorites = favorites;
+        self.chosen = None;
+        self.removed = None;
+        self.selected = self.selected.min(self.favorites.len().saturating_sub(1));
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the favorite once the user has picked it.
+    pub fn take_chosen(&mut self) -> Option<Favorite> {
+        self.chosen.take()
+    }
+
+    /// Returns the favorite once the user has unpinned it from the popup.
+    pub fn take_removed(&mut self) -> Option<Favorite> {
+        self.removed.take()
+    }
+}
+
+impl DrawableComponent for FavoritesComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            let height = area.height.saturating_sub(2) as usize;
+            let offset = self.selected.saturating_sub(height.saturating_sub(1));
+            let lines = if self.favorites.is_empty() {
+                vec![Line::from(Span::styled(
+                    format!(
+                        "No favorites. Press {} on a connection or a table to pin it.",
+                        self.key_config.toggle_favorite
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ))]
+            } else {
+                self.favorites
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(height)
+                    .map(|(index, favorite)| {
+                        Line::from(Span::styled(
+                            favorite.label(),
+                            if index == self.selected {
+                                Style::default()
+                                    .fg(Color::Blue)
+                                    .add_modifier(Modifier::BOLD)
+                            } else {
+                                Style::default()
+                            },
+                        ))
+                    })
+                    .collect()
+            };
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .title(format!(
+                            "Favorites ({}) [{}] open [{}] unpin",
+                            self.favorites.len(),
+                            self.key_config.enter,
+                            self.key_config.toggle_favorite
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                area,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for FavoritesComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.enter => {
+                self.chosen = self.favorites.get(self.selected).cloned();
+                self.hide();
+            }
+            key if key == self.key_config.exit_popup || key == self.key_config.open_favorites => {
+                self.hide()
+            }
+            key if key == self.key_config.toggle_favorite
+                && self.selected < self.favorites.len() =>
+            {
+                self.removed = Some(self.favorites.remove(self.selected));
+                self.selected = self.selected.min(self.favorites.len().saturating_sub(1));
+            }
+            key if (key == self.key_config.scroll_down || key == self.key_config.move_down)
+                && self.selected + 1 < self.favorites.len() =>
+            {
+                self.selected += 1;
+            }
+            key if key == self.key_config.scroll_up || key == self.key_config.move_up => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,461 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::ddl::{self, ForeignKey, REFERENTIAL_ACTIONS};
+use crate::database::Dialect;
+use crate::event::Key;
+use crate::tree::{Database, Table};
+use anyhow::Result;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Columns,
+    RefTable,
+    RefColumns,
+    OnDelete,
+    OnUpdate,
+}
+
+const FIELDS: [Field; 6] = [
+    Field::Name,
+    Field::Columns,
+    Field::RefTable,
+    Field::RefColumns,
+    Field::OnDelete,
+    Field::OnUpdate,
+];
+
+/// A popup which builds an `ALTER TABLE ... ADD CONSTRAINT ... FOREIGN KEY` statement.
+pub struct ForeignKeyFormComponent {
+    dialect: Dialect,
+    target: Option<(Database, Table)>,
+    field: Field,
+    name: String,
+    name_edited: bool,
+    /// The columns of the table and whether each of them is picked.
+    columns: Vec<(String, bool)>,
+    column_cursor: usize,
+    ref_table: String,
+    /// The tables of the same database which `ref_table` is completed from.
+    candidates: Vec<String>,
+    /// What was typed before completion started, so that repeated completion cycles.
+    completion_prefix: Option<String>,
+    ref_columns: String,
+    on_delete: usize,
+    on_update: usize,
+    ddl: Option<String>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl ForeignKeyFormComponent {
+    const WIDTH: u16 = 80;
+    const HEIGHT: u16 = 18;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            dialect: Dialect::MySql,
+            target: None,
+            field: Field::Name,
+            name: String::new(),
+            name_edited: false,
+            columns: Vec::new(),
+            column_cursor: 0,
+            ref_table: String::new(),
+            candidates: Vec::new(),
+            completion_prefix: None,
+            ref_columns: String::new(),
+            on_delete: 0,
+            on_update: 0,
+            ddl: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn open(
+        &mut self,
+        dialect: Dialect,
+        database: Database,
+        table: Table,
+        columns: Vec<String>,
+        candidates: Vec<String>,
+    ) {
+        self.dialect = dialect;
+        self.name = format!("fk_{}", table.name);
+        self.name_edited = false;
+        self.target = Some((database, table));
+        self.field = Field::Name;
+        self.columns = columns.into_iter().map(|column| (column, false)).collect();
+        self.column_cursor = 0;
+        self.ref_table = String::new();
+        self.candidates = candidates;
+        self.completion_prefix = None;
+        self.ref_columns = String::new();
+        self.on_delete = 0;
+        self.on_update = 0;
+        self.ddl = None;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the statement which was submitted, once.
+    pub fn take_ddl(&mut self) -> Option<String> {
+        self.ddl.take()
+    }
+
+    fn foreign_key(&self) -> ForeignKey {
+        ForeignKey {
+            name: self.name.trim().to_string(),
+            columns: self
+                .columns
+                .iter()
+                .filter(|(_, picked)| *picked)
+                .map(|(column, _)| column.clone())
+                .collect(),
+            ref_table: self.ref_table.trim().to_string(),
+            ref_columns: self
+                .ref_columns
+                .split(',')
+                .map(|column| column.trim().to_string())
+                .filter(|column| !column.is_empty())
+                .collect(),
+            on_delete: REFERENTIAL_ACTIONS[self.on_delete],
+            on_update: REFERENTIAL_ACTIONS[self.on_update],
+        }
+    }
+
+    fn preview(&self) -> Result<String> {
+        match &self.target {
+            Some((database, table)) => {
+                ddl::add_foreign_key(self.dialect, database, table, &self.foreign_key())
+            }
+            None => anyhow::bail!("no table is selected"),
+        }
+    }
+
+    /// Completes the referenced table with the next table which starts with what was typed.
+    fn complete(&mut self) {
+        let prefix = self
+            .completion_prefix
+            .get_or_insert_with(|| self.ref_table.clone())
+            .to_lowercase();
+        let matches = self
+            .candidates
+            .iter()
+            .filter(|candidate| candidate.to_lowercase().starts_with(&prefix))
+            .collect::<Vec<&String>>();
+        if matches.is_empty() {
+            return;
+        }
+        let next = matches
+            .iter()
+            .position(|candidate| **candidate == self.ref_table)
+            .map_or(0, |index| (index + 1) % matches.len());
+        self.ref_table = matches[next].clone();
+        if !self.name_edited {
+            if let Some((_, table)) = &self.target {
+                let referenced = self.ref_table.rsplit('.').next().unwrap_or_default();
+                self.name = format!("fk_{}_{}", table.name, referenced);
+            }
+        }
+    }
+
+    fn move_field(&mut self, down: bool) {
+        let index = FIELDS.iter().position(|f| *f == self.field).unwrap_or(0);
+        self.field = if down {
+            FIELDS[(index + 1).min(FIELDS.len() - 1)]
+        } else {
+            FIELDS[index.saturating_sub(1)]
+        };
+    }
+
+    fn input(&mut self) -> Option<&mut String> {
+        match self.field {
+            Field::Name => {
+                self.name_edited = true;
+                Some(&mut self.name)
+            }
+            Field::RefTable => {
+                self.completion_prefix = None;
+                Some(&mut self.ref_table)
+            }
+            Field::RefColumns => Some(&mut self.ref_columns),
+            _ => None,
+        }
+    }
+
+    fn cycle(&mut self, forward: bool) {
+        let step = |index: usize| {
+            if forward {
+                (index + 1) % REFERENTIAL_ACTIONS.len()
+            } else {
+                (index + REFERENTIAL_ACTIONS.len() - 1) % REFERENTIAL_ACTIONS.len()
+            }
+        };
+        match self.field {
+            Field::Columns if !self.columns.is_empty() => {
+                self.column_cursor = if forward {
+                    (self.column_cursor + 1).min(self.columns.len() - 1)
+                } else {
+                    self.column_cursor.saturating_sub(1)
+                };
+            }
+            Field::OnDelete => self.on_delete = step(self.on_delete),
+            Field::OnUpdate => self.on_update = step(self.on_update),
+            _ => (),
+        }
+    }
+
+    fn line(&self, field: Field) -> Line<'static> {
+        let (label, value) = match field {
+            Field::Name => ("name", vec![Span::raw(self.name.clone())]),
+            Field::Columns => (
+                "columns",
+                self.columns
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (column, picked))| {
+                        let style = if field == self.field && index == self.column_cursor {
+                            Style::default().bg(Color::Blue)
+                        } else {
+                            Style::default()
+                        };
+                        Span::styled(
+                            format!("[{}] {} ", if *picked { "x" } else { " " }, column),
+                            style,
+                        )
+                    })
+                    .collect(),
+            ),
+            Field::RefTable => ("references", vec![Span::raw(self.ref_table.clone())]),
+            Field::RefColumns => ("ref columns", vec![Span::raw(self.ref_columns.clone())]),
+            Field::OnDelete => (
+                "on delete",
+                vec![Span::raw(format!(
+                    "< {} >",
+                    REFERENTIAL_ACTIONS[self.on_delete]
+                ))],
+            ),
+            Field::OnUpdate => (
+                "on update",
+                vec![Span::raw(format!(
+                    "< {} >",
+                    REFERENTIAL_ACTIONS[self.on_update]
+                ))],
+            ),
+        };
+        let label_style = if field == self.field {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![Span::styled(format!("{:>12}: ", label), label_style)];
+        spans.extend(value);
+        Line::from(spans)
+    }
+
+    fn hint(&self) -> &'static str {
+        match self.field {
+            Field::Columns => "Left/Right to move, Space to pick",
+            Field::RefTable => "Tab to complete from the tables of this database",
+            Field::RefColumns => "comma separated, in the order of the picked columns",
+            Field::OnDelete | Field::OnUpdate => "Left/Right to change",
+            Field::Name => "",
+        }
+    }
+}
+
+impl DrawableComponent for ForeignKeyFormComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            let title = format!(
+                "New foreign key on {} [{}] create [{}] cancel",
+                self.target
+                    .as_ref()
+                    .map_or(String::new(), |(_, table)| table.name.clone()),
+                self.key_config.enter,
+                self.key_config.exit_popup
+            );
+            let block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick);
+            let inner = block.inner(area);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(FIELDS.len() as u16),
+                    Constraint::Length(2),
+                    Constraint::Min(1),
+                ])
+                .split(inner);
+
+            f.render_widget(Clear, area);
+            f.render_widget(block, area);
+            f.render_widget(
+                Paragraph::new(
+                    FIELDS
+                        .iter()
+                        .map(|field| self.line(*field))
+                        .collect::<Vec<Line>>(),
+                )
+                .wrap(Wrap { trim: false }),
+                chunks[0],
+            );
+            f.render_widget(
+                Paragraph::new(self.hint()).style(Style::default().fg(Color::DarkGray)),
+                chunks[1],
+            );
+            let preview = match self.preview() {
+                Ok(sql) => Paragraph::new(sql),
+                Err(e) => Paragraph::new(e.to_string()).style(Style::default().fg(Color::Red)),
+            };
+            f.render_widget(
+                preview
+                    .wrap(Wrap { trim: false })
+                    .block(Block::default().title("Preview").borders(Borders::TOP)),
+                chunks[2],
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for ForeignKeyFormComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit_popup => self.hide(),
+            key if key == self.key_config.enter => {
+                // an invalid form stays open, the preview tells what is wrong
+                if let Ok(sql) = self.preview() {
+                    self.ddl = Some(sql);
+                    self.hide();
+                }
+            }
+            Key::Up => self.move_field(false),
+            Key::Down => self.move_field(true),
+            Key::Tab if self.field == Field::RefTable => self.complete(),
+            Key::Tab => self.move_field(true),
+            Key::Left => self.cycle(false),
+            Key::Right => self.cycle(true),
+            Key::Char(' ') if self.field == Field::Columns => {
+                if let Some((_, picked)) = self.columns.get_mut(self.column_cursor) {
+                    *picked = !*picked;
+                }
+            }
+            Key::Char(c) => {
+                if let Some(input) = self.input() {
+                    input.push(c);
+                }
+            }
+            Key::Backspace => {
+                if let Some(input) = self.input() {
+                    input.pop();
+                }
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Dialect, ForeignKeyFormComponent, KeyConfig};
+    use crate::components::Component;
+    use crate::event::Key;
+    use crate::tree::{Database, Table, TableKind};
+
+    #[test]
+    fn test_complete_and_submit() {
+        let mut form = ForeignKeyFormComponent::new(KeyConfig::default());
+        form.open(
+            Dialect::MySql,
+            Database {
+                name: "shop".to_string(),
+                children: Vec::new(),
+            },
+            Table {
+                name: "orders".to_string(),
+                create_time: None,
+                update_time: None,
+                engine: None,
+                schema: None,
+                kind: TableKind::Table,
+                partition_of: None,
+            },
+            vec!["id".to_string(), "user_id".to_string()],
+            vec![
+                "items".to_string(),
+                "users".to_string(),
+                "user_roles".to_string(),
+            ],
+        );
+        for key in [
+            Key::Down,
+            Key::Right,
+            Key::Char(' '),
+            Key::Down,
+            Key::Char('u'),
+        ] {
+            form.event(key).unwrap();
+        }
+        form.event(Key::Tab).unwrap();
+        assert_eq!(form.ref_table, "users");
+        form.event(Key::Tab).unwrap();
+        assert_eq!(form.ref_table, "user_roles");
+        form.event(Key::Tab).unwrap();
+        assert_eq!(form.ref_table, "users");
+        assert_eq!(form.name, "fk_orders_users");
+
+        // the form stays open until the referenced columns are given
+        form.event(Key::Enter).unwrap();
+        assert!(form.is_visible());
+        form.event(Key::Down).unwrap();
+        form.event(Key::Char('i')).unwrap();
+        form.event(Key::Char('d')).unwrap();
+        form.event(Key::Enter).unwrap();
+        assert!(!form.is_visible());
+        assert_eq!(
+            form.take_ddl().unwrap(),
+            "ALTER TABLE `shop`.`orders` ADD CONSTRAINT `fk_orders_users` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE NO ACTION ON UPDATE NO ACTION"
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use super::{Component, DrawableComponent, EventState};
-use crate::components::command::CommandInfo;
+use super::{compute_character_width, Component, DrawableComponent, EventState};
+use crate::components::command::{CommandInfo, CMD_GROUPS};
 use crate::config::KeyConfig;
 use crate::event::Key;
 use crate::version::Version;
@@ -17,16 +17,16 @@ use std::convert::From;
 pub struct HelpComponent {
     cmds: Vec<CommandInfo>,
     visible: bool,
-    selection: u16,
+    /// The index of the selected command among the ones matching `input`.
+    selection: usize,
+    input: String,
+    searching: bool,
     key_config: KeyConfig,
 }
 
 impl DrawableComponent for HelpComponent {
     fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
         if self.visible {
-            let scroll_threshold = Self::HEIGHT / 3;
-            let scroll = self.selection.saturating_sub(scroll_threshold);
-
             let area = Rect::new(
                 (f.size().width.saturating_sub(Self::WIDTH)) / 2,
                 (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
@@ -47,12 +47,40 @@ impl DrawableComponent for HelpComponent {
                 .vertical_margin(1)
                 .horizontal_margin(1)
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+                .constraints(
+                    [
+                        Constraint::Length(1),
+                        Constraint::Min(1),
+                        Constraint::Length(1),
+                    ]
+                    .as_ref(),
+                )
                 .split(area);
 
+            if self.searching || !self.input.is_empty() {
+                f.render_widget(Paragraph::new(format!("Search: {}", self.input)), chunks[0]);
+                if self.searching {
+                    let width = self.input.chars().map(compute_character_width).sum::<u16>();
+                    f.set_cursor(
+                        (chunks[0].x + 8 + width).min(chunks[0].right().saturating_sub(1)),
+                        chunks[0].y,
+                    );
+                }
+            } else {
+                f.render_widget(
+                    Paragraph::new(format!(
+                        "Search [{}]  Close [{}]",
+                        self.key_config.filter, self.key_config.exit_popup
+                    ))
+                    .style(Style::default().fg(Color::DarkGray)),
+                    chunks[0],
+                );
+            }
+
+            let scroll = self.selected_line().saturating_sub(chunks[1].height / 3);
             f.render_widget(
-                Paragraph::new(self.get_text(chunks[0].width as usize)).scroll((scroll, 0)),
-                chunks[0],
+                Paragraph::new(self.get_text(chunks[1].width as usize)).scroll((scroll, 0)),
+                chunks[1],
             );
 
             f.render_widget(
@@ -61,7 +89,7 @@ impl DrawableComponent for HelpComponent {
                     Style::default(),
                 )]))
                 .alignment(Alignment::Right),
-                chunks[1],
+                chunks[2],
             );
         }
 
@@ -74,14 +102,52 @@ impl Component for HelpComponent {
 
     fn event(&mut self, key: Key) -> Result<EventState> {
         if self.visible {
+            if self.searching {
+                match key {
+                    key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+                    key if key == self.key_config.exit_popup => {
+                        self.input.clear();
+                        self.searching = false;
+                        self.selection = 0;
+                    }
+                    key if key == self.key_config.enter => self.searching = false,
+                    key if key == self.key_config.move_down => self.scroll_selection(1),
+                    key if key == self.key_config.move_up => self.scroll_selection(-1),
+                    Key::Char(c) => {
+                        self.input.push(c);
+                        self.selection = 0;
+                    }
+                    Key::Backspace => {
+                        self.input.pop();
+                        self.selection = 0;
+                    }
+                    _ => (),
+                }
+                return Ok(EventState::Consumed);
+            }
             if key == self.key_config.exit_popup {
                 self.hide();
                 return Ok(EventState::Consumed);
-            } else if key == self.key_config.scroll_down {
-                self.scroll_selection(true);
+            } else if key == self.key_config.filter {
+                self.searching = true;
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+                self.scroll_selection(1);
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+                self.scroll_selection(-1);
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.scroll_down_multiple_lines {
+                self.scroll_selection(Self::MULTIPLE_LINES);
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.scroll_up_multiple_lines {
+                self.scroll_selection(-Self::MULTIPLE_LINES);
                 return Ok(EventState::Consumed);
-            } else if key == self.key_config.scroll_up {
-                self.scroll_selection(false);
+            } else if key == self.key_config.scroll_to_top {
+                self.selection = 0;
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.scroll_to_bottom {
+                self.selection = self.matches().len().saturating_sub(1);
                 return Ok(EventState::Consumed);
             }
             return Ok(EventState::NotConsumed);
@@ -98,6 +164,9 @@ impl Component for HelpComponent {
 
     fn show(&mut self) -> Result<()> {
         self.visible = true;
+        self.input.clear();
+        self.searching = false;
+        self.selection = 0;
 
         Ok(())
     }
@@ -106,33 +175,71 @@ impl Component for HelpComponent {
 impl HelpComponent {
     const WIDTH: u16 = 65;
     const HEIGHT: u16 = 24;
+    const MULTIPLE_LINES: isize = 10;
+
     pub const fn new(key_config: KeyConfig) -> Self {
         Self {
             cmds: vec![],
             visible: false,
             selection: 0,
+            input: String::new(),
+            searching: false,
             key_config,
         }
     }
 
+    /// Returns true while the text to search for is typed, which takes all the keys.
+    pub fn is_searching(&self) -> bool {
+        self.visible && self.searching
+    }
+
+    /// Sets the commands of the components, which are shown grouped in the order of
+    /// `CMD_GROUPS`.
     pub fn set_cmds(&mut self, cmds: Vec<CommandInfo>) {
-        self.cmds = cmds
+        let mut cmds = cmds
             .into_iter()
             .filter(|e| !e.text.hide_help)
             .collect::<Vec<_>>();
+        // the sort is stable, so that a group keeps the order of the components
+        cmds.sort_by_key(|e| {
+            CMD_GROUPS
+                .iter()
+                .position(|group| *group == e.text.group)
+                .unwrap_or(CMD_GROUPS.len())
+        });
+        self.cmds = cmds;
+        self.selection = self.selection.min(self.matches().len().saturating_sub(1));
     }
 
-    fn scroll_selection(&mut self, inc: bool) {
-        let mut new_selection = self.selection;
+    /// Returns the commands whose name or group contains the search, ignoring case.
+    fn matches(&self) -> Vec<&CommandInfo> {
+        let input = self.input.to_lowercase();
+        self.cmds
+            .iter()
+            .filter(|e| {
+                e.text.name.to_lowercase().contains(&input)
+                    || e.text.group.to_lowercase().contains(&input)
+            })
+            .collect()
+    }
 
-        new_selection = if inc {
-            new_selection.saturating_add(1)
-        } else {
-            new_selection.saturating_sub(1)
-        };
-        new_selection = new_selection.max(0);
+    fn scroll_selection(&mut self, lines: isize) {
+        self.selection = self
+            .selection
+            .saturating_add_signed(lines)
+            .min(self.matches().len().saturating_sub(1));
+    }
 
-        self.selection = new_selection.min(self.cmds.len().saturating_sub(1) as u16);
+    /// Returns the line of the selected command, counting the group headers above it.
+    fn selected_line(&self) -> u16 {
+        let matches = self.matches();
+        let headers = matches
+            .iter()
+            .take(self.selection + 1)
+            .map(|e| e.text.group)
+            .dedup()
+            .count();
+        (self.selection + headers) as u16
     }
 
     fn get_text(&self, width: usize) -> Vec<Line> {
@@ -140,7 +247,16 @@ impl HelpComponent {
 
         let mut processed = 0;
 
-        for (key, group) in &self.cmds.iter().chunk_by(|e| e.text.group) {
+        let matches = self.matches();
+        if matches.is_empty() {
+            txt.push(Line::from(Span::styled(
+                format!(" No keys match `{}`", self.input),
+                Style::default().fg(Color::DarkGray),
+            )));
+            return txt;
+        }
+
+        for (key, group) in &matches.into_iter().chunk_by(|e| e.text.group) {
             txt.push(Line::from(Span::styled(
                 key.to_string(),
                 Style::default().add_modifier(Modifier::REVERSED),
@@ -168,6 +284,8 @@ impl HelpComponent {
 #[cfg(test)]
 mod test {
     use super::{Color, CommandInfo, HelpComponent, KeyConfig, Line, Modifier, Span, Style};
+    use crate::components::Component as _;
+    use crate::event::Key;
 
     #[test]
     fn test_get_text() {
@@ -186,11 +304,55 @@ mod test {
                     Style::default().add_modifier(Modifier::REVERSED)
                 )),
                 Line::from(Span::styled(
-                    " Scroll up/down/left/right [k,j,h,l]  3",
+                    " Filter [/]  3",
                     Style::default().bg(Color::Blue)
                 )),
-                Line::from(Span::styled(" Filter [/]  3", Style::default()))
+                Line::from(Span::styled(
+                    "-- Navigation --",
+                    Style::default().add_modifier(Modifier::REVERSED)
+                )),
+                Line::from(Span::styled(
+                    " Scroll up/down/left/right [k,j,h,l]  3",
+                    Style::default()
+                )),
             ]
         );
+        component.selection = 1;
+        assert_eq!(component.selected_line(), 3);
+    }
+
+    #[test]
+    fn test_search() {
+        let key_config = KeyConfig::default();
+        let mut component = HelpComponent::new(key_config.clone());
+        component.set_cmds(vec![
+            CommandInfo::new(crate::components::command::scroll(&key_config)),
+            CommandInfo::new(crate::components::command::filter(&key_config)),
+            CommandInfo::new(crate::components::command::edit_in_external_editor(
+                &key_config,
+            )),
+        ]);
+        component.show().unwrap();
+        component.event(key_config.filter).unwrap();
+        for c in "EDIT".chars() {
+            component.event(Key::Char(c)).unwrap();
+        }
+        let names = component
+            .matches()
+            .iter()
+            .map(|e| e.text.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Edit SQL in $EDITOR [<Ctrl+e>]"]);
+        assert!(component.is_searching());
+
+        // the group name matches too
+        component.event(key_config.exit_popup).unwrap();
+        assert!(component.visible);
+        assert!(!component.is_searching());
+        component.event(key_config.filter).unwrap();
+        for c in "navigation".chars() {
+            component.event(Key::Char(c)).unwrap();
+        }
+        assert_eq!(component.matches().len(), 1);
     }
 }
@@ -0,0 +1,250 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+};
+use serde_json::Value as Json;
+use std::collections::HashSet;
+
+/// A step from a JSON value into one of its children.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A visible line of the tree.
+struct Node {
+    depth: usize,
+    path: Vec<Segment>,
+    label: String,
+    /// `None` for scalars, otherwise whether the object or array is expanded.
+    expanded: Option<bool>,
+}
+
+/// A JSON document shown as a tree whose objects and arrays can be expanded and collapsed.
+pub struct JsonTree {
+    root: Json,
+    /// The JSON pointers of the expanded objects and arrays.
+    expanded: HashSet<String>,
+    selection: usize,
+}
+
+impl JsonTree {
+    /// Returns `None` unless `json` is an object or an array, which are the only values with
+    /// anything to expand.
+    pub fn new(json: Json) -> Option<Self> {
+        if !json.is_object() && !json.is_array() {
+            return None;
+        }
+        Some(Self {
+            root: json,
+            // the root is expanded, so that the first level is visible right away
+            expanded: HashSet::from([String::new()]),
+            selection: 0,
+        })
+    }
+
+    fn nodes(&self) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        self.push_nodes(&self.root, "$".to_string(), Vec::new(), &mut nodes);
+        nodes
+    }
+
+    fn push_nodes(&self, json: &Json, name: String, path: Vec<Segment>, nodes: &mut Vec<Node>) {
+        let children: Vec<(String, Segment, &Json)> = match json {
+            Json::Object(map) => map
+                .iter()
+                .map(|(key, value)| (key.clone(), Segment::Key(key.clone()), value))
+                .collect(),
+            Json::Array(values) => values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (index.to_string(), Segment::Index(index), value))
+                .collect(),
+            scalar => {
+                nodes.push(Node {
+                    depth: path.len(),
+                    label: format!("{}: {}", name, scalar),
+                    path,
+                    expanded: None,
+                });
+                return;
+            }
+        };
+        let expanded = self.expanded.contains(&pointer(&path));
+        let summary = match json {
+            Json::Array(_) => format!("[{}]", children.len()),
+            _ => format!("{{{}}}", children.len()),
+        };
+        nodes.push(Node {
+            depth: path.len(),
+            label: format!("{} {}", name, summary),
+            path: path.clone(),
+            expanded: Some(expanded),
+        });
+        if expanded {
+            for (name, segment, value) in children {
+                let mut path = path.clone();
+                path.push(segment);
+                self.push_nodes(value, name, path, nodes);
+            }
+        }
+    }
+
+    pub fn next(&mut self, lines: usize) {
+        let last = self.nodes().len().saturating_sub(1);
+        self.selection = self.selection.saturating_add(lines).min(last);
+    }
+
+    pub fn previous(&mut self, lines: usize) {
+        self.selection = self.selection.saturating_sub(lines);
+    }
+
+    /// Expands the selected object or array, or collapses it if it is expanded.
+    pub fn toggle(&mut self) {
+        if let Some(node) = self.nodes().get(self.selection) {
+            match node.expanded {
+                Some(true) => self.collapse(),
+                Some(false) => self.expand(),
+                None => (),
+            }
+        }
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(node) = self.nodes().get(self.selection) {
+            if node.expanded == Some(false) {
+                self.expanded.insert(pointer(&node.path));
+            }
+        }
+    }
+
+    /// Collapses the selected object or array, or moves to its parent if there is nothing to
+    /// collapse.
+    pub fn collapse(&mut self) {
+        let nodes = self.nodes();
+        let node = match nodes.get(self.selection) {
+            Some(node) => node,
+            None => return,
+        };
+        if node.expanded == Some(true) {
+            self.expanded.remove(&pointer(&node.path));
+        } else if let Some(parent) = nodes[..self.selection]
+            .iter()
+            .rposition(|parent| parent.depth < node.depth)
+        {
+            self.selection = parent;
+        }
+    }
+
+    /// Returns the JSONPath of the selected value, e.g. `$.items[0].name`.
+    pub fn selected_path(&self) -> Option<String> {
+        self.nodes()
+            .get(self.selection)
+            .map(|node| json_path(&node.path))
+    }
+
+    pub fn selection(&self) -> usize {
+        self.selection
+    }
+
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        self.nodes()
+            .into_iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let marker = match node.expanded {
+                    Some(true) => "▾ ",
+                    Some(false) => "▸ ",
+                    None => "  ",
+                };
+                let line = Line::from(format!(
+                    "{}{}{}",
+                    "  ".repeat(node.depth),
+                    marker,
+                    node.label
+                ));
+                if index == self.selection {
+                    line.style(Style::default().bg(Color::Blue))
+                } else {
+                    line
+                }
+            })
+            .collect()
+    }
+}
+
+/// The JSON pointer of `path`, which identifies an expanded node.
+fn pointer(path: &[Segment]) -> String {
+    path.iter()
+        .map(|segment| match segment {
+            Segment::Key(key) => format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+            Segment::Index(index) => format!("/{}", index),
+        })
+        .collect()
+}
+
+fn json_path(path: &[Segment]) -> String {
+    let mut out = "$".to_string();
+    for segment in path {
+        match segment {
+            Segment::Key(key)
+                if !key.is_empty()
+                    && !key.starts_with(|c: char| c.is_ascii_digit())
+                    && key.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                out.push('.');
+                out.push_str(key);
+            }
+            Segment::Key(key) => out.push_str(&format!("[{}]", Json::String(key.clone()))),
+            Segment::Index(index) => out.push_str(&format!("[{}]", index)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::JsonTree;
+    use serde_json::json;
+
+    fn labels(tree: &JsonTree) -> Vec<String> {
+        tree.lines().iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_expand_and_collapse() {
+        let mut tree = JsonTree::new(json!({"items": [{"name": "a"}], "my key": true})).unwrap();
+        assert_eq!(
+            labels(&tree),
+            ["▾ $ {2}", "  ▸ items [1]", "    my key: true"]
+        );
+
+        tree.next(1);
+        tree.toggle();
+        tree.next(1);
+        tree.expand();
+        tree.next(1);
+        assert_eq!(
+            labels(&tree),
+            [
+                "▾ $ {2}",
+                "  ▾ items [1]",
+                "    ▾ 0 {1}",
+                "        name: \"a\"",
+                "    my key: true"
+            ]
+        );
+        assert_eq!(tree.selected_path().unwrap(), "$.items[0].name");
+
+        // a scalar moves to its parent, which is then collapsed
+        tree.collapse();
+        assert_eq!(tree.selected_path().unwrap(), "$.items[0]");
+        tree.collapse();
+        assert_eq!(labels(&tree).len(), 4);
+
+        tree.next(10);
+        assert_eq!(tree.selected_path().unwrap(), "$[\"my key\"]");
+        assert!(JsonTree::new(json!("text")).is_none());
+    }
+}
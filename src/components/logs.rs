@@ -0,0 +1,156 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use crate::log::{self, LogEntry, LogLevel};
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// The messages of this session, e.g. why connecting failed, with the newest at the bottom.
+pub struct LogsComponent {
+    /// The most verbose level shown, which can be lowered from `log_level` but not raised.
+    level: LogLevel,
+    max_level: LogLevel,
+    /// How many lines above the newest message the view is scrolled, 0 following the new ones.
+    scroll: usize,
+    key_config: KeyConfig,
+}
+
+impl LogsComponent {
+    pub fn new(key_config: KeyConfig, log_level: LogLevel) -> Self {
+        Self {
+            level: log_level,
+            max_level: log_level,
+            scroll: 0,
+            key_config,
+        }
+    }
+
+    /// Switches between all the messages and the errors only.
+    fn toggle_level(&mut self) {
+        self.level = match self.level {
+            LogLevel::Info => LogLevel::Error,
+            _ => self.max_level,
+        };
+        self.scroll = 0;
+    }
+
+    fn line(entry: &LogEntry) -> Line<'static> {
+        let (label, color) = match entry.level {
+            LogLevel::Error => ("ERROR", Color::Red),
+            _ => ("INFO ", Color::Green),
+        };
+        Line::from(vec![
+            Span::styled(
+                entry.time.format("%H:%M:%S ").to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!("{} ", label),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                entry.message.clone(),
+                match entry.level {
+                    LogLevel::Error => Style::default().fg(Color::Red),
+                    _ => Style::default(),
+                },
+            ),
+        ])
+    }
+}
+
+impl DrawableComponent for LogsComponent {
+    fn draw(&self, f: &mut Frame, area: Rect, focused: bool) -> Result<()> {
+        let entries = log::entries(&self.level);
+        let height = area.height.saturating_sub(2) as usize;
+        let end = entries.len().saturating_sub(self.scroll.min(entries.len()));
+        let lines: Vec<Line> = if self.max_level == LogLevel::Quiet {
+            vec![Line::from(Span::styled(
+                "Nothing is logged with log_level = \"Quiet\".",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            entries[end.saturating_sub(height)..end]
+                .iter()
+                .map(Self::line)
+                .collect()
+        };
+        let shown = match self.level {
+            LogLevel::Info => "all",
+            _ => "errors",
+        };
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .title(format!(
+                        "Logs: {} ({}) [{}] errors/all",
+                        shown,
+                        entries.len(),
+                        self.key_config.filter
+                    ))
+                    .borders(Borders::ALL)
+                    .style(if focused {
+                        Style::default()
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+}
+
+impl Component for LogsComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if key == self.key_config.filter {
+            self.toggle_level();
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.scroll = self.scroll.saturating_add(1);
+        } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            self.scroll = self.scroll.saturating_sub(1);
+        } else if key == self.key_config.scroll_up_multiple_lines {
+            self.scroll = self.scroll.saturating_add(10);
+        } else if key == self.key_config.scroll_down_multiple_lines {
+            self.scroll = self.scroll.saturating_sub(10);
+        } else if key == self.key_config.scroll_to_top {
+            self.scroll = log::entries(&self.level).len();
+        } else if key == self.key_config.scroll_to_bottom {
+            self.scroll = 0;
+        } else {
+            return Ok(EventState::NotConsumed);
+        }
+        self.scroll = self.scroll.min(log::entries(&self.level).len());
+        Ok(EventState::Consumed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LogsComponent;
+    use crate::config::KeyConfig;
+    use crate::log::LogLevel;
+
+    #[test]
+    fn test_toggle_level() {
+        let mut logs = LogsComponent::new(KeyConfig::default(), LogLevel::Info);
+        logs.toggle_level();
+        assert_eq!(logs.level, LogLevel::Error);
+        logs.toggle_level();
+        assert_eq!(logs.level, LogLevel::Info);
+
+        // the level of the config is the most verbose one
+        let mut logs = LogsComponent::new(KeyConfig::default(), LogLevel::Error);
+        logs.toggle_level();
+        assert_eq!(logs.level, LogLevel::Error);
+    }
+}
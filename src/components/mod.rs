@@ -1,39 +1,86 @@
+pub mod activity;
+pub mod bookmarks;
+pub mod cell_viewer;
 pub mod clipboard;
+pub mod column_picker;
 pub mod command;
+pub mod command_line;
 pub mod completion;
+pub mod confirm;
 pub mod connections;
+pub mod dashboard;
 pub mod database_filter;
 pub mod databases;
 pub mod error;
+pub mod export_progress;
+pub mod favorites;
+pub mod foreign_key_form;
 pub mod help;
+pub mod json_tree;
+pub mod logs;
+pub mod object_search;
+pub mod parameter_form;
+pub mod prompt;
 pub mod properties;
+pub mod recent_tables;
 pub mod record_table;
+pub mod relations;
+pub mod result_chart;
+pub mod result_diff;
+pub mod roles;
+pub mod script_progress;
+pub mod session_settings;
 pub mod sql_editor;
+pub mod status_bar;
 pub mod tab;
 pub mod table;
 pub mod table_filter;
 pub mod table_status;
 pub mod table_value;
+pub mod toast;
+pub mod tutorial;
 pub mod utils;
 
 #[cfg(debug_assertions)]
 pub mod debug;
+pub use activity::ActivityComponent;
+pub use bookmarks::BookmarksComponent;
+pub use cell_viewer::CellViewerComponent;
 pub use clipboard::ClipboardComponent;
+pub use column_picker::ColumnPickerComponent;
 pub use command::CommandInfo;
+pub use command_line::{CommandLineComponent, LineCommand};
 pub use completion::CompletionComponent;
+pub use confirm::ConfirmComponent;
 pub use connections::ConnectionsComponent;
+pub use dashboard::DashboardComponent;
 pub use database_filter::DatabaseFilterComponent;
 pub use databases::DatabasesComponent;
 pub use error::ErrorComponent;
+pub use export_progress::ExportProgressComponent;
+pub use favorites::FavoritesComponent;
+pub use foreign_key_form::ForeignKeyFormComponent;
 pub use help::HelpComponent;
+pub use logs::LogsComponent;
+pub use object_search::ObjectSearchComponent;
+pub use parameter_form::ParameterFormComponent;
+pub use prompt::PromptComponent;
 pub use properties::PropertiesComponent;
+pub use recent_tables::RecentTablesComponent;
 pub use record_table::RecordTableComponent;
+pub use relations::RelationsComponent;
+pub use roles::RolesComponent;
+pub use script_progress::ScriptProgressComponent;
+pub use session_settings::SessionSettingsComponent;
 pub use sql_editor::SqlEditorComponent;
+pub use status_bar::StatusBarComponent;
 pub use tab::TabComponent;
-pub use table::TableComponent;
+pub use table::{TableComponent, TableCursor};
 pub use table_filter::TableFilterComponent;
 pub use table_status::TableStatusComponent;
 pub use table_value::TableValueComponent;
+pub use toast::ToastComponent;
+pub use tutorial::{TutorialComponent, TutorialProgress};
 
 use crate::{database::Pool, event::Key};
 use anyhow::Result;
@@ -80,6 +127,14 @@ pub trait PropertyTrait {
     fn draw(&mut self, f: &mut Frame, rect: Rect, focused: bool) -> Result<()>;
     fn event(&mut self, key: Key) -> Result<EventState>;
     fn content(&self) -> Option<String>;
+    /// Returns the number of cells in `content`, if it is made of cells.
+    fn cell_count(&self) -> Option<usize> {
+        None
+    }
+    /// Describes what is selected in words for screen readers.
+    fn describe(&self) -> String {
+        String::new()
+    }
 }
 
 /// base component trait
@@ -97,6 +152,15 @@ pub trait Component {
         Ok(EventState::NotConsumed)
     }
 
+    /// Adds the commands of the `:` command line which the component runs.
+    fn line_commands(&self, _out: &mut Vec<LineCommand>) {}
+
+    /// Runs a command of the `:` command line, or returns `NotConsumed` if it is not one of the
+    /// component's.
+    fn run_line_command(&mut self, _name: &str, _args: &[&str]) -> Result<EventState> {
+        Ok(EventState::NotConsumed)
+    }
+
     fn hide(&mut self) {}
 
     fn show(&mut self) -> Result<()> {
@@ -104,6 +168,7 @@ pub trait Component {
     }
 }
 
+/// Returns how many columns `c` takes, 0 for a control character like the newlines of a file.
 fn compute_character_width(c: char) -> u16 {
-    UnicodeWidthChar::width(c).unwrap().try_into().unwrap()
+    UnicodeWidthChar::width(c).unwrap_or(0).try_into().unwrap()
 }
@@ -0,0 +1,250 @@
+use super::{compute_character_width, Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::SchemaObject;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Scores how well `pattern` matches `text` as a subsequence, ignoring case, or returns `None`
+/// if it does not. Runs of matched characters and matches at the start of a name score higher.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let text = text.to_lowercase().chars().collect::<Vec<char>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+    for c in pattern
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+    {
+        let index = position + text[position..].iter().position(|t| *t == c)?;
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || matches!(text[index - 1], '.' | '_' | '-' | ' ') {
+            score += 3;
+        }
+        previous_match = Some(index);
+        position = index + 1;
+    }
+    // prefer the shorter of two equally good names
+    Some(score * 100 - text.len() as i64)
+}
+
+/// A popup which finds tables, views, columns and routines by their names.
+pub struct ObjectSearchComponent {
+    objects: Option<Vec<SchemaObject>>,
+    input: String,
+    /// The indexes of the matching objects, best first.
+    matches: Vec<usize>,
+    selected: usize,
+    chosen: Option<SchemaObject>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl ObjectSearchComponent {
+    const WIDTH: u16 = 80;
+    const HEIGHT: u16 = 20;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            objects: None,
+            input: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+            chosen: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Returns true if the objects of the connection have been loaded.
+    pub fn has_objects(&self) -> bool {
+        self.objects.is_some()
+    }
+
+    pub fn set_objects(&mut self, objects: Vec<SchemaObject>) {
+        self.objects = Some(objects);
+    }
+
+    /// Forgets the objects, so that they are loaded again on the next search.
+    pub fn reset(&mut self) {
+        self.objects = None;
+        self.matches.clear();
+    }
+
+    pub fn open(&mut self) {
+        self.input.clear();
+        self.chosen = None;
+        self.update_matches();
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the object once the user has picked it.
+    pub fn take_chosen(&mut self) -> Option<SchemaObject> {
+        self.chosen.take()
+    }
+
+    fn update_matches(&mut self) {
+        let objects = self.objects.as_deref().unwrap_or_default();
+        let mut matches = objects
+            .iter()
+            .enumerate()
+            .filter_map(|(index, object)| {
+                fuzzy_score(&self.input, &object.path()).map(|score| (score, index))
+            })
+            .collect::<Vec<_>>();
+        // the sort is stable, so that equal scores stay in the order of the server
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.matches = matches.into_iter().map(|(_, index)| index).collect();
+        self.selected = 0;
+    }
+
+    fn object(&self, index: usize) -> Option<&SchemaObject> {
+        self.objects.as_ref()?.get(*self.matches.get(index)?)
+    }
+}
+
+impl DrawableComponent for ObjectSearchComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(1)])
+                .split(area);
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.input.as_str()).block(
+                    Block::default()
+                        .title(format!(
+                            "Search objects ({} found) [{}] open",
+                            self.matches.len(),
+                            self.key_config.enter
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                chunks[0],
+            );
+            let height = chunks[1].height.saturating_sub(2) as usize;
+            let offset = self.selected.saturating_sub(height.saturating_sub(1));
+            let lines = (offset..(offset + height).min(self.matches.len()))
+                .filter_map(|index| {
+                    let object = self.object(index)?;
+                    let style = if index == self.selected {
+                        Style::default()
+                            .fg(Color::Blue)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default()
+                    };
+                    Some(Line::from(vec![
+                        Span::styled(
+                            format!("{:<10}", object.kind),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled(object.path(), style),
+                    ]))
+                })
+                .collect::<Vec<Line>>();
+            f.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                chunks[1],
+            );
+            let width = self.input.chars().map(compute_character_width).sum::<u16>();
+            f.set_cursor(
+                (chunks[0].x + 1 + width).min(chunks[0].right().saturating_sub(2)),
+                chunks[0].y + 1,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for ObjectSearchComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.enter => {
+                self.chosen = self.object(self.selected).cloned();
+                self.hide();
+            }
+            key if key == self.key_config.exit_popup => self.hide(),
+            key if key == self.key_config.move_down && self.selected + 1 < self.matches.len() => {
+                self.selected += 1;
+            }
+            key if key == self.key_config.move_up => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            Key::Char(c) => {
+                self.input.push(c);
+                self.update_matches();
+            }
+            Key::Backspace => {
+                self.input.pop();
+                self.update_matches();
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::fuzzy_score;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("usr", "shop.orders"), None);
+        assert!(fuzzy_score("", "shop.users").is_some());
+        // a run of characters beats scattered ones
+        assert!(
+            fuzzy_score("user", "shop.users").unwrap()
+                > fuzzy_score("user", "shop.ulster_remarks").unwrap()
+        );
+        // the start of a name beats the middle of one
+        assert!(
+            fuzzy_score("id", "shop.orders.id").unwrap()
+                > fuzzy_score("id", "shop.orders.paid").unwrap()
+        );
+        assert!(fuzzy_score("USERS", "shop.users").is_some());
+    }
+}
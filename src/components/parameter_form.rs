@@ -0,0 +1,198 @@
+use super::{compute_character_width, Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::collections::HashMap;
+
+/// A popup which asks for the values of the placeholders of a query before it runs.
+pub struct ParameterFormComponent {
+    names: Vec<String>,
+    inputs: Vec<String>,
+    field: usize,
+    /// The values which were submitted last by parameter name, to fill in the form again.
+    previous: HashMap<String, String>,
+    submitted: Option<Vec<String>>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl ParameterFormComponent {
+    const WIDTH: u16 = 60;
+    const LABEL_WIDTH: usize = 16;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            names: Vec::new(),
+            inputs: Vec::new(),
+            field: 0,
+            previous: HashMap::new(),
+            submitted: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, names: Vec<String>) {
+        self.inputs = names
+            .iter()
+            .map(|name| self.previous.get(name).cloned().unwrap_or_default())
+            .collect();
+        self.names = names;
+        self.field = 0;
+        self.submitted = None;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the values, one for each parameter, once the user has submitted them.
+    pub fn take_values(&mut self) -> Option<Vec<String>> {
+        self.submitted.take()
+    }
+
+    pub fn has_values(&self) -> bool {
+        self.submitted.is_some()
+    }
+
+    fn submit(&mut self) {
+        for (name, input) in self.names.iter().zip(&self.inputs) {
+            self.previous.insert(name.clone(), input.clone());
+        }
+        self.submitted = Some(self.inputs.clone());
+        self.hide();
+    }
+}
+
+impl DrawableComponent for ParameterFormComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let height = self.names.len() as u16 + 2;
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(height)) / 2,
+                Self::WIDTH.min(f.size().width),
+                height.min(f.size().height),
+            );
+            let lines = self
+                .names
+                .iter()
+                .zip(&self.inputs)
+                .enumerate()
+                .map(|(index, (name, input))| {
+                    let style = if index == self.field {
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default()
+                    };
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{:>width$}: ", name, width = Self::LABEL_WIDTH),
+                            style,
+                        ),
+                        Span::raw(input.clone()),
+                    ])
+                })
+                .collect::<Vec<Line>>();
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .title(format!(
+                            "Parameters [{}] next/run [{}] cancel, 'quote' to send text",
+                            self.key_config.enter, self.key_config.exit_popup
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                area,
+            );
+            let width = self.inputs[self.field]
+                .chars()
+                .map(compute_character_width)
+                .sum::<u16>();
+            f.set_cursor(
+                (area.x + 1 + Self::LABEL_WIDTH as u16 + 2 + width)
+                    .min(area.right().saturating_sub(2)),
+                area.y + 1 + self.field as u16,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for ParameterFormComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.exit_popup => self.hide(),
+            key if key == self.key_config.enter => {
+                if self.field + 1 < self.names.len() {
+                    self.field += 1;
+                } else {
+                    self.submit();
+                }
+            }
+            Key::Down | Key::Tab => self.field = (self.field + 1).min(self.names.len() - 1),
+            Key::Up => self.field = self.field.saturating_sub(1),
+            Key::Char(c) => self.inputs[self.field].push(c),
+            Key::Backspace => {
+                self.inputs[self.field].pop();
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{KeyConfig, ParameterFormComponent};
+    use crate::components::Component;
+    use crate::event::Key;
+
+    #[test]
+    fn test_fill_and_submit() {
+        let mut form = ParameterFormComponent::new(KeyConfig::default());
+        form.open(vec![":id".to_string(), "1".to_string()]);
+        for key in [Key::Char('4'), Key::Char('2'), Key::Enter, Key::Char('x')] {
+            form.event(key).unwrap();
+        }
+        assert!(form.is_visible());
+        form.event(Key::Enter).unwrap();
+        assert!(!form.is_visible());
+        assert_eq!(
+            form.take_values(),
+            Some(vec!["42".to_string(), "x".to_string()])
+        );
+
+        // the values are filled in again for the same parameters
+        form.open(vec!["1".to_string()]);
+        assert_eq!(form.inputs, vec!["x".to_string()]);
+    }
+}
@@ -0,0 +1,117 @@
+use super::{compute_character_width, Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// A popup which asks for a single line of text.
+pub struct PromptComponent {
+    label: String,
+    input: String,
+    submitted: Option<String>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl PromptComponent {
+    const WIDTH: u16 = 60;
+    const HEIGHT: u16 = 3;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            label: String::new(),
+            input: String::new(),
+            submitted: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, label: String) {
+        self.open_with(label, String::new());
+    }
+
+    /// Opens the prompt with `input` already typed, e.g. the path a file was saved to before.
+    pub fn open_with(&mut self, label: String, input: String) {
+        self.label = label;
+        self.input = input;
+        self.submitted = None;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the text once the user has submitted it.
+    pub fn take_submitted(&mut self) -> Option<String> {
+        self.submitted.take()
+    }
+}
+
+impl DrawableComponent for PromptComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.input.as_str()).block(
+                    Block::default()
+                        .title(self.label.as_str())
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                area,
+            );
+            let width = self.input.chars().map(compute_character_width).sum::<u16>();
+            f.set_cursor(
+                (area.x + 1 + width).min(area.right().saturating_sub(2)),
+                area.y + 1,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for PromptComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.enter => {
+                self.submitted = Some(std::mem::take(&mut self.input));
+                self.hide();
+            }
+            key if key == self.key_config.exit_popup => self.hide(),
+            Key::Char(c) => self.input.push(c),
+            Key::Backspace => {
+                self.input.pop();
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
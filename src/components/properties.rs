@@ -1,27 +1,37 @@
-use super::{ClipboardComponent, Component, EventState, PropertyTrait, StatefulDrawableComponent};
-use crate::clipboard::copy_to_clipboard;
+use super::{
+    ClipboardComponent, Component, ConfirmComponent, DrawableComponent, EventState,
+    ForeignKeyFormComponent, PropertyTrait, StatefulDrawableComponent,
+};
+use crate::clipboard::copy_in_background;
 use crate::components::command::{self, CommandInfo};
+use crate::components::toast::ToastQueue;
 use crate::components::TableComponent;
 use crate::config::KeyConfig;
-use crate::database::Pool;
+use crate::database::{ddl, missing_privilege, Dialect, Pool, TableRow, Value};
 use crate::event::Key;
-use crate::tree::{Database, Table};
+use crate::tree::{Database, Table, TableKind};
 use anyhow::Result;
 use async_trait::async_trait;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame,
 };
+use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Focus {
     Column,
     Constraint,
     ForeignKey,
     Index,
     Definition,
+    Dependency,
+    Trigger,
+    Statistic,
+    Privilege,
+    Partition,
 }
 
 impl std::fmt::Display for Focus {
@@ -35,24 +45,157 @@ pub struct PropertiesComponent {
     constraint_table: TableComponent,
     foreign_key_table: TableComponent,
     index_table: TableComponent,
+    dependency_table: TableComponent,
+    trigger_table: TableComponent,
+    statistic_table: TableComponent,
+    privilege_table: TableComponent,
+    partition_table: TableComponent,
     definition_viewer: ClipboardComponent,
+    errors: HashMap<Focus, String>,
     focus: Focus,
+    foreign_key_form: ForeignKeyFormComponent,
+    confirm: ConfirmComponent,
+    dialect: Dialect,
+    /// The tables which a new foreign key can reference.
+    tables: Vec<String>,
+    selected: Option<(Database, Table)>,
+    ddl: Option<String>,
+    toast: ToastQueue,
     key_config: KeyConfig,
 }
 
 impl PropertiesComponent {
-    pub fn new(key_config: KeyConfig) -> Self {
+    pub fn new(key_config: KeyConfig, toast: ToastQueue) -> Self {
         Self {
             column_table: TableComponent::new(key_config.clone()),
             constraint_table: TableComponent::new(key_config.clone()),
             foreign_key_table: TableComponent::new(key_config.clone()),
             index_table: TableComponent::new(key_config.clone()),
+            dependency_table: TableComponent::new(key_config.clone()),
+            trigger_table: TableComponent::new(key_config.clone()),
+            statistic_table: TableComponent::new(key_config.clone()),
+            privilege_table: TableComponent::new(key_config.clone()),
+            partition_table: TableComponent::new(key_config.clone()),
             definition_viewer: ClipboardComponent::new(key_config.clone()),
+            errors: HashMap::new(),
             focus: Focus::Column,
+            foreign_key_form: ForeignKeyFormComponent::new(key_config.clone()),
+            confirm: ConfirmComponent::new(key_config.clone()),
+            dialect: Dialect::MySql,
+            tables: Vec::new(),
+            selected: None,
+            ddl: None,
+            toast,
             key_config,
         }
     }
 
+    pub fn selected_tab(&self) -> Focus {
+        self.focus
+    }
+
+    pub fn select_tab(&mut self, focus: Focus) {
+        self.focus = focus;
+    }
+
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    pub fn set_tables(&mut self, tables: Vec<String>) {
+        self.tables = tables;
+    }
+
+    /// Returns true while the form for a foreign key is open, where the keys are typed.
+    pub fn is_editing(&self) -> bool {
+        self.foreign_key_form.is_visible()
+    }
+
+    /// Returns the statement to run once the user has finished a form or confirmed a drop.
+    pub fn take_ddl(&mut self) -> Option<String> {
+        self.ddl.take()
+    }
+
+    /// Returns the values of the column named `name`, or of the first column.
+    fn column_values(table: &TableComponent, name: &str) -> Vec<String> {
+        let index = table.headers.iter().position(|h| h == name).unwrap_or(0);
+        table
+            .rows
+            .iter()
+            .filter_map(|row| row.get(index).cloned())
+            .collect()
+    }
+
+    fn open_foreign_key_form(&mut self) {
+        if let Some((database, table)) = self.selected.clone() {
+            let columns = Self::column_values(&self.column_table, "name");
+            self.foreign_key_form
+                .open(self.dialect, database, table, columns, self.tables.clone());
+        }
+    }
+
+    fn confirm_drop_foreign_key(&mut self) -> Result<()> {
+        let (database, table) = match &self.selected {
+            Some(selected) => selected,
+            None => return Ok(()),
+        };
+        let name = self
+            .foreign_key_table
+            .selected_row
+            .selected()
+            .and_then(|index| {
+                Self::column_values(&self.foreign_key_table, "name")
+                    .get(index)
+                    .cloned()
+            })
+            .filter(|name| !name.is_empty());
+        let name = match name {
+            Some(name) => name,
+            None => anyhow::bail!("the selected foreign key has no name"),
+        };
+        let sql = ddl::drop_foreign_key(self.dialect, database, table, &name)?;
+        self.confirm
+            .open(format!("Drop the foreign key {}?", name), sql);
+        Ok(())
+    }
+
+    fn confirm_restart_sequence(&mut self) -> Result<()> {
+        if let Some((database, table)) = &self.selected {
+            let sql = ddl::restart_sequence(self.dialect, database, table)?;
+            self.confirm.open(
+                format!("Restart the sequence {} at its start value?", table.name),
+                sql,
+            );
+        }
+        Ok(())
+    }
+
+    fn is_sequence(&self) -> bool {
+        self.selected
+            .as_ref()
+            .is_some_and(|(_, table)| table.kind == TableKind::Sequence)
+    }
+
+    /// Describes the tab and its selected cell in words for screen readers.
+    pub fn describe(&self) -> String {
+        let component: &dyn PropertyTrait = match self.focus {
+            Focus::Column => &self.column_table,
+            Focus::Constraint => &self.constraint_table,
+            Focus::ForeignKey => &self.foreign_key_table,
+            Focus::Index => &self.index_table,
+            Focus::Definition => &self.definition_viewer,
+            Focus::Dependency => &self.dependency_table,
+            Focus::Trigger => &self.trigger_table,
+            Focus::Statistic => &self.statistic_table,
+            Focus::Privilege => &self.privilege_table,
+            Focus::Partition => &self.partition_table,
+        };
+        match self.errors.get(&self.focus) {
+            Some(error) => format!("{} tab, error: {}", self.focus, error),
+            None => format!("{} tab, {}", self.focus, component.describe()),
+        }
+    }
+
     fn focused_component(&mut self) -> &mut dyn PropertyTrait {
         match self.focus {
             Focus::Column => &mut self.column_table,
@@ -60,88 +203,154 @@ impl PropertiesComponent {
             Focus::ForeignKey => &mut self.foreign_key_table,
             Focus::Index => &mut self.index_table,
             Focus::Definition => &mut self.definition_viewer,
+            Focus::Dependency => &mut self.dependency_table,
+            Focus::Trigger => &mut self.trigger_table,
+            Focus::Statistic => &mut self.statistic_table,
+            Focus::Privilege => &mut self.privilege_table,
+            Focus::Partition => &mut self.partition_table,
         }
     }
 
+    /// Loads the metadata of each tab independently.
+    /// A failed query is recorded for its own tab and never blocks the other tabs.
     pub async fn update(
         &mut self,
         database: Database,
         table: Table,
         pool: &Box<dyn Pool>,
     ) -> Result<()> {
-        self.column_table.reset();
-        let columns = pool.get_columns(&database, &table).await?;
-        if !columns.is_empty() {
-            self.column_table.update(
-                columns
-                    .iter()
-                    .map(|c| c.columns())
-                    .collect::<Vec<Vec<String>>>(),
-                None,
-                columns.first().unwrap().fields(),
-                database.clone(),
-                table.clone(),
-                false,
-            );
-        }
-        self.constraint_table.reset();
-        let constraints = pool.get_constraints(&database, &table).await?;
-        if !constraints.is_empty() {
-            self.constraint_table.update(
-                constraints
-                    .iter()
-                    .map(|c| c.columns())
-                    .collect::<Vec<Vec<String>>>(),
-                None,
-                constraints.first().unwrap().fields(),
-                database.clone(),
-                table.clone(),
-                false,
-            );
-        }
-        self.foreign_key_table.reset();
-        let foreign_keys = pool.get_foreign_keys(&database, &table).await?;
-        if !foreign_keys.is_empty() {
-            self.foreign_key_table.update(
-                foreign_keys
-                    .iter()
-                    .map(|c| c.columns())
-                    .collect::<Vec<Vec<String>>>(),
-                None,
-                foreign_keys.first().unwrap().fields(),
-                database.clone(),
-                table.clone(),
-                false,
-            );
-        }
-        self.index_table.reset();
-        let indexes = pool.get_indexes(&database, &table).await?;
-        if !indexes.is_empty() {
-            self.index_table.update(
-                indexes
-                    .iter()
-                    .map(|c| c.columns())
-                    .collect::<Vec<Vec<String>>>(),
-                None,
-                indexes.first().unwrap().fields(),
-                database.clone(),
-                table.clone(),
-                false,
-            );
-        }
-        // create table sql is here
-        self.definition_viewer.reset();
-        let definition = pool.get_definition(&database, &table).await?;
-        if !definition.is_empty() {
-            self.definition_viewer
-                .update(definition, database.clone(), table.clone())
+        for focus in [
+            Focus::Column,
+            Focus::Constraint,
+            Focus::ForeignKey,
+            Focus::Index,
+            Focus::Definition,
+            Focus::Dependency,
+            Focus::Trigger,
+            Focus::Statistic,
+            Focus::Privilege,
+            Focus::Partition,
+        ] {
+            // a routine only has parameters, shown in the columns tab, and its source
+            if table.kind.is_routine() && !matches!(focus, Focus::Column | Focus::Definition) {
+                self.errors.remove(&focus);
+                self.update_table(focus, Ok(Vec::new()), &database, &table);
+                continue;
+            }
+            self.load(focus, &database, &table, pool).await;
         }
+        self.selected = Some((database, table));
+        Ok(())
+    }
+
+    /// Reloads only the focused tab.
+    pub async fn refresh(
+        &mut self,
+        database: Database,
+        table: Table,
+        pool: &Box<dyn Pool>,
+    ) -> Result<()> {
+        self.load(self.focus, &database, &table, pool).await;
         Ok(())
     }
 
+    async fn load(
+        &mut self,
+        focus: Focus,
+        database: &Database,
+        table: &Table,
+        pool: &Box<dyn Pool>,
+    ) {
+        self.errors.remove(&focus);
+        let rows = match focus {
+            Focus::Column => pool.get_columns(database, table).await,
+            Focus::Constraint => pool.get_constraints(database, table).await,
+            Focus::ForeignKey => pool.get_foreign_keys(database, table).await,
+            Focus::Index => pool.get_indexes(database, table).await,
+            Focus::Dependency => pool.get_dependencies(database, table).await,
+            Focus::Trigger => pool.get_triggers(database, table).await,
+            Focus::Statistic => pool.get_statistics(database, table).await,
+            Focus::Privilege => pool.get_privileges(database, table).await,
+            Focus::Partition => pool.get_partitions(database, table).await,
+            Focus::Definition => {
+                // create table sql is here
+                self.definition_viewer.reset();
+                match pool.get_definition(database, table).await {
+                    Ok(definition) if !definition.is_empty() => {
+                        self.definition_viewer
+                            .update(definition, database.clone(), table.clone())
+                    }
+                    Ok(_) => (),
+                    Err(e) => self.set_error(Focus::Definition, &e),
+                }
+                return;
+            }
+        };
+        self.update_table(focus, rows, database, table);
+    }
+
+    fn update_table(
+        &mut self,
+        focus: Focus,
+        rows: Result<Vec<Box<dyn TableRow>>>,
+        database: &Database,
+        table: &Table,
+    ) {
+        let component = match focus {
+            Focus::Column => &mut self.column_table,
+            Focus::Constraint => &mut self.constraint_table,
+            Focus::ForeignKey => &mut self.foreign_key_table,
+            Focus::Index => &mut self.index_table,
+            Focus::Dependency => &mut self.dependency_table,
+            Focus::Trigger => &mut self.trigger_table,
+            Focus::Statistic => &mut self.statistic_table,
+            Focus::Privilege => &mut self.privilege_table,
+            Focus::Partition => &mut self.partition_table,
+            Focus::Definition => return,
+        };
+        component.reset();
+        match rows {
+            Ok(rows) => {
+                if let Some(first) = rows.first() {
+                    component.update(
+                        rows.iter()
+                            .map(|c| c.columns().into_iter().map(Value::Text).collect())
+                            .collect::<Vec<Vec<Value>>>(),
+                        None,
+                        first.fields(),
+                        database.clone(),
+                        table.clone(),
+                        false,
+                    );
+                }
+            }
+            Err(e) => self.set_error(focus, &e),
+        }
+    }
+
+    fn set_error(&mut self, focus: Focus, error: &anyhow::Error) {
+        let message = match missing_privilege(error) {
+            Some(privilege) => format!("Missing privilege: {}\n\n{}", privilege, error),
+            None => format!("Failed to load: {}", error),
+        };
+        self.errors.insert(focus, message);
+    }
+
     fn tab_names(&self) -> Vec<(Focus, String)> {
+        let columns = command::tab_columns(&self.key_config).name;
         vec![
-            (Focus::Column, command::tab_columns(&self.key_config).name),
+            (
+                Focus::Column,
+                if self
+                    .selected
+                    .as_ref()
+                    .is_some_and(|(_, table)| table.kind.is_routine())
+                {
+                    columns.replace("Columns", "Parameters")
+                } else {
+                    columns
+                },
+            ),
             (
                 Focus::Constraint,
                 command::tab_constraints(&self.key_config).name,
@@ -155,6 +364,30 @@ impl PropertiesComponent {
                 Focus::Definition,
                 command::tab_definition(&self.key_config).name,
             ),
+            (
+                Focus::Dependency,
+                command::tab_dependencies(&self.key_config).name,
+            ),
+            (Focus::Trigger, command::tab_triggers(&self.key_config).name),
+            (
+                Focus::Statistic,
+                // the figures of a sequence are its state
+                if self.is_sequence() {
+                    command::tab_statistics(&self.key_config)
+                        .name
+                        .replace("Statistics", "Sequence")
+                } else {
+                    command::tab_statistics(&self.key_config).name
+                },
+            ),
+            (
+                Focus::Privilege,
+                command::tab_privileges(&self.key_config).name,
+            ),
+            (
+                Focus::Partition,
+                command::tab_partitions(&self.key_config).name,
+            ),
         ]
     }
 }
@@ -170,10 +403,20 @@ impl StatefulDrawableComponent for PropertiesComponent {
             .tab_names()
             .iter()
             .map(|(f, c)| {
-                ListItem::new(c.to_string()).style(if *f == self.focus {
-                    Style::default().bg(Color::Blue)
+                let style = if self.errors.contains_key(f) {
+                    Style::default().fg(Color::Red)
                 } else {
                     Style::default()
+                };
+                let name = if self.errors.contains_key(f) {
+                    format!("{} !", c)
+                } else {
+                    c.to_string()
+                };
+                ListItem::new(name).style(if *f == self.focus {
+                    style.bg(Color::Blue)
+                } else {
+                    style
                 })
             })
             .collect::<Vec<ListItem>>();
@@ -188,7 +431,22 @@ impl StatefulDrawableComponent for PropertiesComponent {
 
         f.render_widget(tab_list, layout[0]);
 
+        if let Some(error) = self.errors.get(&self.focus) {
+            let paragraph = Paragraph::new(error.to_string())
+                .wrap(Wrap { trim: true })
+                .style(Style::default().fg(Color::Red))
+                .block(
+                    Block::default()
+                        .title(self.focus.to_string())
+                        .borders(Borders::ALL),
+                );
+            f.render_widget(paragraph, layout[1]);
+            return Ok(());
+        }
+
         self.focused_component().draw(f, layout[1], focused)?;
+        self.foreign_key_form.draw(f, area, false)?;
+        self.confirm.draw(f, area, false)?;
         Ok(())
     }
 }
@@ -199,14 +457,48 @@ impl Component for PropertiesComponent {
         out.push(CommandInfo::new(command::toggle_property_tabs(
             &self.key_config,
         )));
+        out.push(CommandInfo::new(command::create_foreign_key(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::drop_foreign_key(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::restart_sequence(
+            &self.key_config,
+        )));
     }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.foreign_key_form.is_visible() {
+            let state = self.foreign_key_form.event(key)?;
+            self.ddl = self.foreign_key_form.take_ddl();
+            return Ok(state);
+        }
+        if self.confirm.is_visible() {
+            let state = self.confirm.event(key)?;
+            self.ddl = self.confirm.take_confirmed();
+            return Ok(state);
+        }
+        if key == self.key_config.restart_sequence && self.is_sequence() {
+            self.confirm_restart_sequence()?;
+            return Ok(EventState::Consumed);
+        }
+        if self.focus == Focus::ForeignKey {
+            if key == self.key_config.create_foreign_key {
+                self.open_foreign_key_form();
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.drop_foreign_key {
+                self.confirm_drop_foreign_key()?;
+                return Ok(EventState::Consumed);
+            }
+        }
+
         self.focused_component().event(key)?;
 
         if key == self.key_config.copy {
             if let Some(text) = self.focused_component().content() {
-                copy_to_clipboard(text.as_str())?
+                let cells = self.focused_component().cell_count();
+                copy_in_background(text, cells, self.toast.clone());
             }
         } else if key == self.key_config.tab_columns {
             self.focus = Focus::Column;
@@ -218,6 +510,16 @@ impl Component for PropertiesComponent {
             self.focus = Focus::Index;
         } else if key == self.key_config.tab_definition {
             self.focus = Focus::Definition;
+        } else if key == self.key_config.tab_dependencies {
+            self.focus = Focus::Dependency;
+        } else if key == self.key_config.tab_triggers {
+            self.focus = Focus::Trigger;
+        } else if key == self.key_config.tab_statistics {
+            self.focus = Focus::Statistic;
+        } else if key == self.key_config.tab_privileges {
+            self.focus = Focus::Privilege;
+        } else if key == self.key_config.tab_partitions {
+            self.focus = Focus::Partition;
         }
         Ok(EventState::NotConsumed)
     }
@@ -0,0 +1,196 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use crate::session::SessionTable;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// A table opened before, with a description of the filter and the sort it was left with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentTable {
+    pub table: SessionTable,
+    pub state: String,
+}
+
+/// A popup which lists the tables opened on the connection, the latest first, to go back to.
+pub struct RecentTablesComponent {
+    tables: Vec<RecentTable>,
+    selected: usize,
+    chosen: Option<SessionTable>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl RecentTablesComponent {
+    const WIDTH: u16 = 80;
+    const HEIGHT: u16 = 16;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            tables: Vec::new(),
+            selected: 0,
+            chosen: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Shows the tables with the one opened before the current one selected, to switch back
+    /// and forth between two tables with two keys.
+    pub fn open(&mut self, tables: Vec<RecentTable>) {
+        self.selected = usize::from(tables.len() > 1);
+        self.tables = tables;
+        self.chosen = None;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the table once the user has picked it.
+    pub fn take_chosen(&mut self) -> Option<SessionTable> {
+        self.chosen.take()
+    }
+}
+
+impl DrawableComponent for RecentTablesComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(Self::HEIGHT)) / 2,
+                Self::WIDTH.min(f.size().width),
+                Self::HEIGHT.min(f.size().height),
+            );
+            let height = area.height.saturating_sub(2) as usize;
+            let offset = self.selected.saturating_sub(height.saturating_sub(1));
+            let lines = if self.tables.is_empty() {
+                vec![Line::from(Span::styled(
+                    "No tables opened yet.",
+                    Style::default().fg(Color::DarkGray),
+                ))]
+            } else {
+                self.tables
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(height)
+                    .map(|(index, recent)| {
+                        Line::from(vec![
+                            Span::styled(
+                                recent.table.path(),
+                                if index == self.selected {
+                                    Style::default()
+                                        .fg(Color::Blue)
+                                        .add_modifier(Modifier::BOLD)
+                                } else {
+                                    Style::default()
+                                },
+                            ),
+                            Span::styled(
+                                format!("  {}", recent.state),
+                                Style::default().fg(Color::DarkGray),
+                            ),
+                        ])
+                    })
+                    .collect()
+            };
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .title(format!(
+                            "Recent tables ({}) [{}] open",
+                            self.tables.len(),
+                            self.key_config.enter
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                area,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for RecentTablesComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.enter || key == self.key_config.open_recent_tables => {
+                self.chosen = self
+                    .tables
+                    .get(self.selected)
+                    .map(|recent| recent.table.clone());
+                self.hide();
+            }
+            key if key == self.key_config.exit_popup => self.hide(),
+            key if (key == self.key_config.scroll_down || key == self.key_config.move_down)
+                && self.selected + 1 < self.tables.len() =>
+            {
+                self.selected += 1;
+            }
+            key if key == self.key_config.scroll_up || key == self.key_config.move_up => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RecentTable, RecentTablesComponent};
+    use crate::components::Component as _;
+    use crate::config::KeyConfig;
+    use crate::session::SessionTable;
+
+    fn recent(name: &str) -> RecentTable {
+        RecentTable {
+            table: SessionTable {
+                database: "shop".to_string(),
+                schema: None,
+                name: name.to_string(),
+            },
+            state: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_switch_back_to_previous_table() {
+        let key_config = KeyConfig::default();
+        let mut recent_tables = RecentTablesComponent::new(key_config.clone());
+        recent_tables.open(vec![recent("orders"), recent("users")]);
+        recent_tables.event(key_config.open_recent_tables).unwrap();
+        assert!(!recent_tables.is_visible());
+        assert_eq!(recent_tables.take_chosen(), Some(recent("users").table));
+
+        recent_tables.open(vec![recent("orders")]);
+        recent_tables.event(key_config.enter).unwrap();
+        assert_eq!(recent_tables.take_chosen(), Some(recent("orders").table));
+    }
+}
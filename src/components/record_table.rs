@@ -1,7 +1,14 @@
-use super::{Component, EventState, StatefulDrawableComponent};
-use crate::components::command::CommandInfo;
-use crate::components::{TableComponent, TableFilterComponent};
+use super::{Component, DrawableComponent, EventState, StatefulDrawableComponent};
+use crate::components::bookmarks::Bookmark;
+use crate::components::column_picker::ColumnLayout;
+use crate::components::command::{self, CommandInfo};
+use crate::components::table::column_name;
+use crate::components::{
+    ColumnPickerComponent, ConfirmComponent, TableComponent, TableFilterComponent,
+};
 use crate::config::KeyConfig;
+use crate::database::ddl::{insert, qualified_table};
+use crate::database::{row_size, Dialect, Value};
 use crate::event::Key;
 use crate::tree::{Database, Table as DTable};
 use anyhow::Result;
@@ -10,6 +17,7 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     Frame,
 };
+use std::collections::{HashMap, HashSet};
 
 pub enum Focus {
     Table,
@@ -20,6 +28,21 @@ pub struct RecordTableComponent {
     pub filter: TableFilterComponent,
     pub table: TableComponent,
     pub focus: Focus,
+    column_picker: ColumnPickerComponent,
+    confirm: ConfirmComponent,
+    headers: Vec<String>,
+    /// The rows with every column, of which the table shows the picked ones.
+    rows: Vec<Vec<Value>>,
+    /// The estimated number of bytes `rows` take.
+    rows_size: usize,
+    total_row_count: Option<usize>,
+    /// True if the rows are a random sample instead of the first rows.
+    sample: bool,
+    column_layouts: HashMap<(String, String), ColumnLayout>,
+    /// The widths set for the columns of each table, kept for the session.
+    column_widths: HashMap<(String, String), HashMap<String, usize>>,
+    /// The bookmarks of the table, whose rows are marked.
+    bookmarks: Vec<Bookmark>,
     key_config: KeyConfig,
 }
 
@@ -29,33 +52,228 @@ impl RecordTableComponent {
             filter: TableFilterComponent::new(key_config.clone()),
             table: TableComponent::new(key_config.clone()),
             focus: Focus::Table,
+            column_picker: ColumnPickerComponent::new(key_config.clone()),
+            confirm: ConfirmComponent::new(key_config.clone()),
+            headers: Vec::new(),
+            rows: Vec::new(),
+            rows_size: 0,
+            total_row_count: None,
+            sample: false,
+            column_layouts: HashMap::new(),
+            column_widths: HashMap::new(),
+            bookmarks: Vec::new(),
             key_config,
         }
     }
 
     pub fn update(
         &mut self,
-        rows: Vec<Vec<String>>,
+        rows: Vec<Vec<Value>>,
         total_row_count: Option<usize>,
         headers: Vec<String>,
         database: Database,
         table: DTable,
         hold_cursor_position: bool,
     ) {
-        self.table.update(
-            rows,
-            total_row_count,
-            headers,
-            database,
-            table.clone(),
-            hold_cursor_position,
-        );
+        let key = Self::layout_key(&database, &table);
+        let layout = self.column_layouts.get(&key).cloned();
+        self.column_picker.set_headers(headers.clone(), layout);
+        self.headers = headers;
+        self.rows_size = rows.iter().map(|row| row_size(row)).sum();
+        self.rows = rows;
+        self.total_row_count = total_row_count;
+        self.update_table(database, table.clone(), hold_cursor_position);
+        if let Some(widths) = self.column_widths.get(&key) {
+            self.table.set_column_width_overrides(widths.clone());
+        }
         self.filter.table = Some(table);
+        let columns = self.column_names();
+        self.filter.set_columns(columns);
+        self.mark_bookmarks();
+    }
+
+    /// Appends the next page of rows.
+    pub fn extend(&mut self, rows: Vec<Vec<Value>>) {
+        let projection = self.column_picker.projection();
+        self.table
+            .extend(rows.iter().map(|row| project(row, &projection)).collect());
+        self.rows_size += rows.iter().map(|row| row_size(row)).sum::<usize>();
+        self.rows.extend(rows);
+        self.mark_bookmarks();
+    }
+
+    /// Returns the estimated number of bytes the loaded rows take, counting both the rows with
+    /// every column and the shown ones.
+    pub fn loaded_size(&self) -> usize {
+        self.rows_size + self.table.loaded_size()
+    }
+
+    pub fn set_bookmarks(&mut self, bookmarks: Vec<Bookmark>) {
+        self.bookmarks = bookmarks;
+        self.mark_bookmarks();
+    }
+
+    /// Returns the names of the columns, without the sort icons the headers may have.
+    fn column_names(&self) -> Vec<String> {
+        self.headers
+            .iter()
+            .map(|header| column_name(header).to_string())
+            .collect()
+    }
+
+    fn mark_bookmarks(&mut self) {
+        let columns = self.column_names();
+        let marked = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| {
+                self.bookmarks
+                    .iter()
+                    .any(|bookmark| bookmark.matches(&columns, row))
+            })
+            .map(|(index, _)| index)
+            .collect::<HashSet<usize>>();
+        self.table.set_marked_rows(marked);
+    }
+
+    /// Returns the values which identify the selected row: its primary key, or all of its
+    /// columns if `primary_key` is empty or not among the loaded columns.
+    pub fn selected_key(&self, primary_key: &[String]) -> Option<Vec<(String, Value)>> {
+        let row = self.rows.get(self.table.selected_row.selected()?)?;
+        let columns = self.column_names();
+        let key = primary_key
+            .iter()
+            .map(|column| {
+                let index = columns.iter().position(|name| name == column)?;
+                Some((column.clone(), row.get(index)?.clone()))
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|key| !key.is_empty());
+        Some(key.unwrap_or_else(|| columns.into_iter().zip(row.clone()).collect()))
+    }
+
+    /// Returns the selected rows as `INSERT` statements. A selected area only copies its
+    /// columns, a single cell the whole row including the hidden columns.
+    pub fn insert_statements(&self, dialect: Dialect) -> Option<String> {
+        let (database, table) = self.table.table()?;
+        let (rows, columns) = self.table.selected_area()?;
+        let columns = match columns {
+            Some(columns) => self.column_picker.projection().get(columns)?.to_vec(),
+            None => (0..self.headers.len()).collect(),
+        };
+        let rows = self
+            .rows
+            .get(rows)?
+            .iter()
+            .map(|row| project(row, &columns))
+            .collect::<Vec<_>>();
+        Some(insert(
+            dialect,
+            &qualified_table(dialect, database, table),
+            &project(&self.column_names(), &columns),
+            &rows,
+        ))
+    }
+
+    /// Asks before `statement`, which deletes the selected row, is run.
+    pub fn confirm_delete(&mut self, statement: String) {
+        self.confirm.open("Delete this row?".to_string(), statement);
+    }
+
+    /// Returns the `DELETE` statement once the user has confirmed it.
+    pub fn take_confirmed_delete(&mut self) -> Option<String> {
+        self.confirm.take_confirmed()
+    }
+
+    /// Moves the cursor to the bookmarked row if it is loaded, and returns true if it is.
+    pub fn select_bookmark(&mut self, bookmark: &Bookmark) -> bool {
+        let columns = self.column_names();
+        match self
+            .rows
+            .iter()
+            .position(|row| bookmark.matches(&columns, row))
+        {
+            Some(index) => {
+                self.table.select_row(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sorts by the selected column, which may not be at the same position in the query,
+    /// alone or after the columns sorted by already if `secondary`.
+    pub fn add_order(&mut self, secondary: bool) {
+        if let Some(column) = self
+            .column_picker
+            .projection()
+            .get(self.table.selected_column())
+        {
+            if secondary {
+                self.table.add_order(*column)
+            } else {
+                self.table.set_order(*column)
+            }
+        }
+    }
+
+    pub fn is_sample(&self) -> bool {
+        self.sample
+    }
+
+    pub fn toggle_sample(&mut self) {
+        self.sample = !self.sample;
     }
 
     pub fn reset(&mut self) {
         self.table.reset();
         self.filter.reset();
+        self.headers = Vec::new();
+        self.rows = Vec::new();
+        self.rows_size = 0;
+        self.total_row_count = None;
+        self.sample = false;
+        self.bookmarks = Vec::new();
+    }
+
+    fn layout_key(database: &Database, table: &DTable) -> (String, String) {
+        (database.name.clone(), table.qualified_name())
+    }
+
+    /// Shows the rows with only the picked columns in the picked order.
+    fn update_table(&mut self, database: Database, table: DTable, hold_cursor_position: bool) {
+        let projection = self.column_picker.projection();
+        self.table.update(
+            self.rows
+                .iter()
+                .map(|row| project(row, &projection))
+                .collect(),
+            self.total_row_count,
+            project(&self.headers, &projection),
+            database,
+            table,
+            hold_cursor_position,
+        );
+        let sorted_column = self.table.first_order().and_then(|(column, is_asc)| {
+            projection
+                .iter()
+                .position(|projected| *projected == column)
+                .map(|index| (index, is_asc))
+        });
+        self.table.set_sorted_column(sorted_column);
+    }
+
+    fn apply_column_layout(&mut self) {
+        if let Some((database, table)) = self.table.table().cloned() {
+            self.column_layouts.insert(
+                Self::layout_key(&database, &table),
+                self.column_picker.layout(),
+            );
+            let cursor = self.table.cursor();
+            self.update_table(database, table, true);
+            self.table.restore_cursor(cursor);
+        }
     }
 
     pub fn filter_focused(&self) -> bool {
@@ -63,6 +281,13 @@ impl RecordTableComponent {
     }
 }
 
+fn project<T: Clone>(row: &[T], projection: &[usize]) -> Vec<T> {
+    projection
+        .iter()
+        .filter_map(|index| row.get(*index).cloned())
+        .collect()
+}
+
 impl StatefulDrawableComponent for RecordTableComponent {
     fn draw(&mut self, f: &mut Frame, area: Rect, focused: bool) -> Result<()> {
         let layout = Layout::default()
@@ -76,20 +301,64 @@ impl StatefulDrawableComponent for RecordTableComponent {
 
         self.filter
             .draw(f, layout[0], focused && matches!(self.focus, Focus::Filter))?;
+
+        self.column_picker.draw(f, area, false)?;
+        self.confirm.draw(f, area, false)?;
         Ok(())
     }
 }
 
 impl Component for RecordTableComponent {
     fn commands(&self, out: &mut Vec<CommandInfo>) {
-        self.table.commands(out)
+        self.table.commands(out);
+        self.column_picker.commands(out);
+        out.push(CommandInfo::new(command::bookmark_row(&self.key_config)));
+        out.push(CommandInfo::new(command::copy_as_insert(&self.key_config)));
+        out.push(CommandInfo::new(command::delete_row(&self.key_config)));
+        out.push(CommandInfo::new(command::export_to_file(&self.key_config)));
+        out.push(CommandInfo::new(command::export_to_json(&self.key_config)));
+        out.push(CommandInfo::new(command::toggle_sample(&self.key_config)));
     }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.confirm.is_visible() {
+            return self.confirm.event(key);
+        }
+        if self.column_picker.is_visible() {
+            let state = self.column_picker.event(key)?;
+            if state.is_consumed() {
+                self.apply_column_layout();
+            }
+            return Ok(state);
+        }
+        if self.table.is_searching() {
+            return self.table.event(key);
+        }
+        if key == self.key_config.open_column_picker
+            && matches!(self.focus, Focus::Table)
+            && !self.headers.is_empty()
+        {
+            self.column_picker.show()?;
+            return Ok(EventState::Consumed);
+        }
         if key == self.key_config.filter {
             self.focus = Focus::Filter;
             return Ok(EventState::Consumed);
         }
+        if (key == self.key_config.widen_column
+            || key == self.key_config.narrow_column
+            || key == self.key_config.fit_column)
+            && matches!(self.focus, Focus::Table)
+        {
+            let state = self.table.event(key)?;
+            if let Some((database, table)) = self.table.table() {
+                self.column_widths.insert(
+                    Self::layout_key(database, table),
+                    self.table.column_width_overrides().clone(),
+                );
+            }
+            return Ok(state);
+        }
         match key {
             key if matches!(self.focus, Focus::Filter) => return self.filter.event(key),
             key if matches!(self.focus, Focus::Table) => return self.table.event(key),
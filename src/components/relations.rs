@@ -0,0 +1,390 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::{Relation, SchemaObject};
+use crate::event::Key;
+use crate::tree::{Database, Table};
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// A popup which draws the foreign keys of a table as a graph: the tables which reference it
+/// on the left, and the tables it references on the right.
+///
+/// ```text
+/// order_items (order_id → id) ─┬──▶ orders ─┬──▶ customers (customer_id → id)
+/// payments (order_id → id) ────┘            └──▶ stores (store_id → id)
+/// ```
+pub struct RelationsComponent {
+    database: Option<Database>,
+    table: Option<Table>,
+    /// The foreign keys of other tables to this one.
+    incoming: Vec<Relation>,
+    /// The foreign keys of this table.
+    outgoing: Vec<Relation>,
+    /// The selected table, counting the incoming ones first.
+    selected: usize,
+    chosen: Option<SchemaObject>,
+    refresh_requested: bool,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl RelationsComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            database: None,
+            table: None,
+            incoming: Vec::new(),
+            outgoing: Vec::new(),
+            selected: 0,
+            chosen: None,
+            refresh_requested: false,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Shows the foreign keys from and to `table`. A key of the table to itself is on both
+    /// sides.
+    pub fn open(&mut self, database: Database, table: Table, relations: Vec<Relation>) {
+        let is_table =
+            |schema: &Option<String>, name: &str| *schema == table.schema && name == table.name;
+        self.outgoing = relations
+            .iter()
+            .filter(|relation| is_table(&relation.schema, &relation.table))
+            .cloned()
+            .collect();
+        self.incoming = relations
+            .into_iter()
+            .filter(|relation| is_table(&relation.ref_schema, &relation.ref_table))
+            .collect();
+        if self.table.as_ref() == Some(&table) {
+            self.selected = self.selected.min(self.len().saturating_sub(1));
+        } else {
+            self.selected = 0;
+        }
+        self.database = Some(database);
+        self.table = Some(table);
+        self.refresh_requested = false;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns true once if the user asked to read the foreign keys again.
+    pub fn take_refresh(&mut self) -> bool {
+        std::mem::take(&mut self.refresh_requested)
+    }
+
+    /// Returns the related table the user chose to jump to.
+    pub fn take_chosen(&mut self) -> Option<SchemaObject> {
+        self.chosen.take()
+    }
+
+    fn len(&self) -> usize {
+        self.incoming.len() + self.outgoing.len()
+    }
+
+    /// Returns the schema and the name of the related table at `index`.
+    fn related(&self, index: usize) -> Option<(&Option<String>, &String)> {
+        match self.incoming.get(index) {
+            Some(relation) => Some((&relation.schema, &relation.table)),
+            None => self
+                .outgoing
+                .get(index - self.incoming.len())
+                .map(|relation| (&relation.ref_schema, &relation.ref_table)),
+        }
+    }
+
+    fn choose_selected(&mut self) {
+        let database = match &self.database {
+            Some(database) => database.name.clone(),
+            None => return,
+        };
+        if let Some((schema, name)) = self.related(self.selected) {
+            self.chosen = Some(SchemaObject {
+                kind: "table".to_string(),
+                database,
+                schema: schema.clone(),
+                table: None,
+                name: name.clone(),
+            });
+        }
+    }
+
+    /// Names a related table by itself, or with its schema if it is in another one.
+    fn table_name(&self, schema: &Option<String>, name: &str) -> String {
+        match (schema, self.table.as_ref().map(|table| &table.schema)) {
+            (Some(schema), Some(current)) if Some(schema) != current.as_ref() => {
+                format!("{}.{}", schema, name)
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    fn incoming_label(&self, relation: &Relation) -> String {
+        format!(
+            "{} ({} → {})",
+            self.table_name(&relation.schema, &relation.table),
+            relation.columns.join(", "),
+            relation.ref_columns.join(", ")
+        )
+    }
+
+    fn outgoing_label(&self, relation: &Relation) -> String {
+        format!(
+            "{} ({} → {})",
+            self.table_name(&relation.ref_schema, &relation.ref_table),
+            relation.columns.join(", "),
+            relation.ref_columns.join(", ")
+        )
+    }
+
+    /// Lays out the graph, one table on each side per line, joined to the table in the middle
+    /// of the first line.
+    fn lines(&self) -> Vec<Line<'static>> {
+        let table = match &self.table {
+            Some(table) => table.name.clone(),
+            None => return Vec::new(),
+        };
+        let selected = Style::default()
+            .fg(Color::Blue)
+            .add_modifier(Modifier::BOLD);
+        let incoming: Vec<String> = self
+            .incoming
+            .iter()
+            .map(|relation| self.incoming_label(relation))
+            .collect();
+        let outgoing: Vec<String> = self
+            .outgoing
+            .iter()
+            .map(|relation| self.outgoing_label(relation))
+            .collect();
+        let label_width = incoming
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let table_width = table.chars().count();
+        let bus =
+            |index: usize, len: usize, first: &'static str, middle: &'static str, last| match index
+            {
+                _ if len == 1 => "─",
+                0 => first,
+                index if index + 1 == len => last,
+                _ => middle,
+            };
+        (0..incoming.len().max(outgoing.len()).max(1))
+            .map(|index| {
+                let mut spans = Vec::new();
+                if !incoming.is_empty() {
+                    match incoming.get(index) {
+                        Some(label) => {
+                            spans.push(Span::styled(
+                                label.clone(),
+                                if self.selected == index {
+                                    selected
+                                } else {
+                                    Style::default()
+                                },
+                            ));
+                            spans.push(Span::raw(format!(
+                                " {}{}{}",
+                                "─".repeat(label_width - label.chars().count() + 1),
+                                bus(index, incoming.len(), "┬", "┤", "┘"),
+                                if index == 0 { "──▶ " } else { "    " }
+                            )));
+                        }
+                        None => spans.push(Span::raw(" ".repeat(label_width + 7))),
+                    }
+                }
+                if index == 0 {
+                    spans.push(Span::styled(
+                        table.clone(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ));
+                } else if !outgoing.is_empty() {
+                    spans.push(Span::raw(" ".repeat(table_width)));
+                }
+                if let Some(label) = outgoing.get(index) {
+                    spans.push(Span::raw(format!(
+                        " {}{}──▶ ",
+                        if index == 0 { "─" } else { " " },
+                        bus(index, outgoing.len(), "┬", "├", "└")
+                    )));
+                    spans.push(Span::styled(
+                        label.clone(),
+                        if self.selected == self.incoming.len() + index {
+                            selected
+                        } else {
+                            Style::default()
+                        },
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+}
+
+impl DrawableComponent for RelationsComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let width = (f.size().width * 9 / 10).max(60).min(f.size().width);
+            let height = (f.size().height * 3 / 4).max(8).min(f.size().height);
+            let area = Rect::new(
+                (f.size().width - width) / 2,
+                (f.size().height - height) / 2,
+                width,
+                height,
+            );
+            let block = Block::default()
+                .title(format!(
+                    "Relations ({} referencing, {} referenced) [{}] jump [{}] refresh [{}] close",
+                    self.incoming.len(),
+                    self.outgoing.len(),
+                    self.key_config.enter,
+                    self.key_config.refresh,
+                    self.key_config.exit_popup
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick);
+            let inner = block.inner(area);
+            let mut lines = self.lines();
+            if self.len() == 0 {
+                lines.push(Line::default());
+                lines.push(Line::from(Span::styled(
+                    "No foreign keys reference this table or are defined on it.",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            // the selected table stays in view
+            let selected_line = match self.selected.checked_sub(self.incoming.len()) {
+                Some(index) => index,
+                None => self.selected,
+            };
+            let offset = selected_line.saturating_sub((inner.height as usize).saturating_sub(1));
+
+            f.render_widget(Clear, area);
+            f.render_widget(block, area);
+            f.render_widget(Paragraph::new(lines).scroll((offset as u16, 0)), inner);
+        }
+        Ok(())
+    }
+}
+
+impl Component for RelationsComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.exit_popup || key == self.key_config.open_relations => {
+                self.hide()
+            }
+            key if key == self.key_config.refresh => self.refresh_requested = true,
+            key if key == self.key_config.enter => self.choose_selected(),
+            key if (key == self.key_config.scroll_down || key == self.key_config.move_down)
+                && self.selected + 1 < self.len() =>
+            {
+                self.selected += 1;
+            }
+            key if key == self.key_config.scroll_up || key == self.key_config.move_up => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RelationsComponent;
+    use crate::components::Component;
+    use crate::config::KeyConfig;
+    use crate::database::Relation;
+    use crate::tree::{Database, Table, TableKind};
+
+    fn relation(table: &str, column: &str, ref_table: &str) -> Relation {
+        Relation {
+            name: format!("fk_{}_{}", table, ref_table),
+            schema: Some("public".to_string()),
+            table: table.to_string(),
+            columns: vec![column.to_string()],
+            ref_schema: Some("public".to_string()),
+            ref_table: ref_table.to_string(),
+            ref_columns: vec!["id".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_relations() {
+        let key_config = KeyConfig::default();
+        let mut relations = RelationsComponent::new(key_config.clone());
+        let table = Table {
+            name: "orders".to_string(),
+            create_time: None,
+            update_time: None,
+            engine: None,
+            schema: Some("public".to_string()),
+            kind: TableKind::Table,
+            partition_of: None,
+        };
+        relations.open(
+            Database::new("shop".to_string(), vec![]),
+            table,
+            vec![
+                relation("order_items", "order_id", "orders"),
+                relation("payments", "order_id", "orders"),
+                relation("orders", "customer_id", "customers"),
+            ],
+        );
+        let lines: Vec<String> = relations
+            .lines()
+            .iter()
+            .map(|line| line.to_string().trim_end().to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "order_items (order_id → id) ─┬──▶ orders ────▶ customers (customer_id → id)",
+                "payments (order_id → id) ────┘",
+            ]
+        );
+
+        // enter jumps to the selected table, the tables which reference this one first
+        relations.event(key_config.scroll_down).unwrap();
+        relations.event(key_config.scroll_down).unwrap();
+        relations.event(key_config.scroll_down).unwrap();
+        relations.event(key_config.enter).unwrap();
+        let chosen = relations.take_chosen().unwrap();
+        assert_eq!(chosen.name, "customers");
+        assert_eq!(chosen.database, "shop");
+        assert_eq!(chosen.schema.as_deref(), Some("public"));
+        assert!(relations.take_chosen().is_none());
+
+        relations.event(key_config.open_relations).unwrap();
+        assert!(!relations.is_visible());
+    }
+}
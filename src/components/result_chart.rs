@@ -0,0 +1,281 @@
+use crate::database::{Value, ValueFormat};
+use anyhow::{bail, Result};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, GraphType},
+    Frame,
+};
+
+/// The bars are scaled to this height, since a bar chart only shows whole numbers.
+const BAR_SCALE: f64 = 1000.0;
+const MAX_BAR_WIDTH: u16 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartKind {
+    Bar,
+    Line,
+}
+
+impl ChartKind {
+    /// Returns the next chart of the toggle, `None` going back to the table.
+    pub fn next(kind: Option<Self>) -> Option<Self> {
+        match kind {
+            None => Some(Self::Bar),
+            Some(Self::Bar) => Some(Self::Line),
+            Some(Self::Line) => None,
+        }
+    }
+}
+
+/// A chart of a result with two columns, the labels in the first and the numbers in the
+/// second, e.g. `SELECT day, count(*) FROM orders GROUP BY day`.
+#[derive(Debug)]
+pub struct ResultChart {
+    kind: ChartKind,
+    x_name: String,
+    y_name: String,
+    labels: Vec<String>,
+    values: Vec<f64>,
+    /// The points of the line chart, by the first column if it is numeric or temporal, or
+    /// else by the row number.
+    points: Vec<(f64, f64)>,
+    /// The labels of the first and the last point.
+    x_labels: Vec<String>,
+}
+
+impl ResultChart {
+    pub fn new(
+        kind: ChartKind,
+        headers: &[String],
+        rows: &[Vec<Value>],
+        value_format: &ValueFormat,
+    ) -> Result<Self> {
+        let (x_name, y_name) = match headers {
+            [x, y] => (x.clone(), y.clone()),
+            _ => bail!(
+                "A chart needs a result with two columns, the labels and the numbers, not {}",
+                headers.len()
+            ),
+        };
+        if rows.is_empty() {
+            bail!("The result has no rows to chart");
+        }
+        let mut labels = Vec::with_capacity(rows.len());
+        let mut values = Vec::with_capacity(rows.len());
+        for (index, row) in rows.iter().enumerate() {
+            let value = match row.get(1) {
+                Some(value) if value.is_numeric() => value.as_f64().unwrap_or_default(),
+                _ => bail!("{} is not a number in row {}", y_name, index + 1),
+            };
+            if kind == ChartKind::Bar && value < 0.0 {
+                bail!(
+                    "A bar chart cannot show the negative {} of row {}",
+                    y_name,
+                    index + 1
+                );
+            }
+            labels.push(
+                row.first()
+                    .map(|x| value_format.display(x))
+                    .unwrap_or_default(),
+            );
+            values.push(value);
+        }
+        let x_values: Option<Vec<f64>> = rows
+            .iter()
+            .map(|row| row.first().and_then(Value::as_f64))
+            .collect();
+        let mut points: Vec<(f64, f64, &String)> = match x_values {
+            Some(x_values) => x_values
+                .into_iter()
+                .zip(values.iter().copied())
+                .zip(&labels)
+                .map(|((x, y), label)| (x, y, label))
+                .collect(),
+            None => values
+                .iter()
+                .zip(&labels)
+                .enumerate()
+                .map(|(index, (y, label))| (index as f64, *y, label))
+                .collect(),
+        };
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let x_labels = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => vec![first.2.clone(), last.2.clone()],
+            _ => Vec::new(),
+        };
+        let points = points.into_iter().map(|(x, y, _)| (x, y)).collect();
+        Ok(Self {
+            kind,
+            x_name,
+            y_name,
+            labels,
+            values,
+            points,
+            x_labels,
+        })
+    }
+
+    fn bars(&self) -> Vec<Bar<'_>> {
+        let max = self.values.iter().copied().fold(0.0, f64::max);
+        self.values
+            .iter()
+            .zip(&self.labels)
+            .map(|(value, label)| {
+                let height = if max > 0.0 {
+                    (value / max * BAR_SCALE).round() as u64
+                } else {
+                    0
+                };
+                Bar::default()
+                    .value(height)
+                    .text_value(axis_label(*value))
+                    .label(Line::from(label.clone()))
+            })
+            .collect()
+    }
+
+    /// Returns the bounds of the points on both axes, widened if all the values are equal.
+    fn bounds(&self) -> ([f64; 2], [f64; 2]) {
+        let bounds = |values: &mut dyn Iterator<Item = f64>| {
+            let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+            if min < max {
+                [min, max]
+            } else {
+                [min - 1.0, max + 1.0]
+            }
+        };
+        (
+            bounds(&mut self.points.iter().map(|(x, _)| *x)),
+            bounds(&mut self.points.iter().map(|(_, y)| *y)),
+        )
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect, focused: bool) {
+        let block = Block::default()
+            .title(format!(
+                "{} chart of {} by {}",
+                match self.kind {
+                    ChartKind::Bar => "Bar",
+                    ChartKind::Line => "Line",
+                },
+                self.y_name,
+                self.x_name
+            ))
+            .borders(Borders::ALL)
+            .style(if focused {
+                Style::default()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            });
+        match self.kind {
+            ChartKind::Bar => {
+                let width = block.inner(area).width;
+                let bar_width = (width / self.values.len() as u16)
+                    .saturating_sub(1)
+                    .clamp(1, MAX_BAR_WIDTH);
+                f.render_widget(
+                    BarChart::default()
+                        .block(block)
+                        .data(BarGroup::default().bars(&self.bars()))
+                        .bar_width(bar_width)
+                        .bar_gap(1)
+                        .bar_style(Style::default().fg(Color::Cyan))
+                        .value_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+                        .max(BAR_SCALE as u64),
+                    area,
+                );
+            }
+            ChartKind::Line => {
+                let (x_bounds, y_bounds) = self.bounds();
+                let dataset = Dataset::default()
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(Color::Cyan))
+                    .data(&self.points);
+                f.render_widget(
+                    Chart::new(vec![dataset])
+                        .block(block)
+                        .x_axis(
+                            Axis::default()
+                                .title(self.x_name.clone())
+                                .bounds(x_bounds)
+                                .labels(self.x_labels.iter().cloned().map(Span::from).collect()),
+                        )
+                        .y_axis(
+                            Axis::default()
+                                .title(self.y_name.clone())
+                                .bounds(y_bounds)
+                                .labels(
+                                    [y_bounds[0], (y_bounds[0] + y_bounds[1]) / 2.0, y_bounds[1]]
+                                        .map(|y| Span::from(axis_label(y)))
+                                        .to_vec(),
+                                ),
+                        ),
+                    area,
+                );
+            }
+        }
+    }
+}
+
+/// Shows whole numbers without a fraction and the others with two digits.
+fn axis_label(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChartKind, ResultChart};
+    use crate::database::{Value, ValueFormat};
+
+    #[test]
+    fn test_result_chart() {
+        let headers = ["day", "orders"].map(String::from);
+        let rows = vec![
+            vec![Value::Int(3), Value::Int(20)],
+            vec![Value::Int(1), Value::Float(5.5)],
+            vec![Value::Int(2), Value::Int(0)],
+        ];
+        let chart =
+            ResultChart::new(ChartKind::Line, &headers, &rows, &ValueFormat::default()).unwrap();
+        // the line follows the first column, and the bars the order of the rows
+        assert_eq!(chart.points, vec![(1.0, 5.5), (2.0, 0.0), (3.0, 20.0)]);
+        assert_eq!(chart.x_labels, vec!["1", "3"]);
+        assert_eq!(chart.bounds(), ([1.0, 3.0], [0.0, 20.0]));
+        assert_eq!(chart.labels, vec!["3", "1", "2"]);
+        let chart =
+            ResultChart::new(ChartKind::Bar, &headers, &rows, &ValueFormat::default()).unwrap();
+        assert_eq!(chart.bars().len(), 3);
+
+        // the labels which are not numbers are charted by the row number
+        let rows = vec![
+            vec![Value::Text("a".to_string()), Value::Int(-1)],
+            vec![Value::Text("b".to_string()), Value::Int(1)],
+        ];
+        let chart =
+            ResultChart::new(ChartKind::Line, &headers, &rows, &ValueFormat::default()).unwrap();
+        assert_eq!(chart.points, vec![(0.0, -1.0), (1.0, 1.0)]);
+        assert_eq!(chart.x_labels, vec!["a", "b"]);
+        assert!(
+            ResultChart::new(ChartKind::Bar, &headers, &rows, &ValueFormat::default()).is_err()
+        );
+
+        let rows = vec![vec![Value::Int(1), Value::Text("x".to_string())]];
+        assert!(
+            ResultChart::new(ChartKind::Line, &headers, &rows, &ValueFormat::default()).is_err()
+        );
+        assert!(
+            ResultChart::new(ChartKind::Line, &headers[..1], &[], &ValueFormat::default()).is_err()
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use crate::database::Value;
+use std::collections::{HashMap, HashSet};
+
+/// How a row of a result differs from the snapshot it is compared with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowChange {
+    Added,
+    /// The row is in the snapshot with other values in these columns.
+    Changed(HashSet<usize>),
+}
+
+/// The rows of a result which were added or changed since a snapshot, by their index, and the
+/// rows of the snapshot which are gone.
+#[derive(Debug, Default)]
+pub struct ResultDiff {
+    pub rows: HashMap<usize, RowChange>,
+    pub removed: Vec<Vec<Value>>,
+}
+
+impl ResultDiff {
+    /// Compares the rows by the first column if its values are unique in both results, e.g.
+    /// an id, so that a changed row is told from a new one, or else by all their values.
+    /// Returns `None` if the columns differ.
+    pub fn new(
+        snapshot_headers: &[String],
+        snapshot: &[Vec<Value>],
+        headers: &[String],
+        rows: &[Vec<Value>],
+    ) -> Option<Self> {
+        if snapshot_headers != headers {
+            return None;
+        }
+        Some(match (keyed(snapshot), keyed(rows)) {
+            (Some(before), Some(_)) => {
+                let mut diff = Self::default();
+                let mut seen = HashSet::new();
+                for (index, row) in rows.iter().enumerate() {
+                    let row_key = key(row);
+                    match before.get(&row_key) {
+                        Some(&old) => {
+                            let columns: HashSet<usize> = row
+                                .iter()
+                                .zip(&snapshot[old])
+                                .enumerate()
+                                .filter(|(_, (new, old))| new != old)
+                                .map(|(column, _)| column)
+                                .collect();
+                            if !columns.is_empty() {
+                                diff.rows.insert(index, RowChange::Changed(columns));
+                            }
+                        }
+                        None => {
+                            diff.rows.insert(index, RowChange::Added);
+                        }
+                    }
+                    seen.insert(row_key);
+                }
+                diff.removed = snapshot
+                    .iter()
+                    .filter(|row| !seen.contains(&key(row)))
+                    .cloned()
+                    .collect();
+                diff
+            }
+            _ => {
+                // without a key, a changed row is a removed row and an added one
+                let mut before: HashMap<String, usize> = HashMap::new();
+                for row in snapshot {
+                    *before.entry(format!("{:?}", row)).or_default() += 1;
+                }
+                let mut diff = Self::default();
+                for (index, row) in rows.iter().enumerate() {
+                    match before.get_mut(&format!("{:?}", row)) {
+                        Some(count) if *count > 0 => *count -= 1,
+                        _ => {
+                            diff.rows.insert(index, RowChange::Added);
+                        }
+                    }
+                }
+                for row in snapshot {
+                    if let Some(count) = before.get_mut(&format!("{:?}", row)) {
+                        if *count > 0 {
+                            *count -= 1;
+                            diff.removed.push(row.clone());
+                        }
+                    }
+                }
+                diff
+            }
+        })
+    }
+
+    /// Counts the changes, and names the first removed rows by their first column.
+    pub fn summary(&self, headers: &[String]) -> String {
+        const SHOWN: usize = 5;
+        let added = self
+            .rows
+            .values()
+            .filter(|change| matches!(change, RowChange::Added))
+            .count();
+        let changed = self.rows.len() - added;
+        let mut summary = format!(
+            "compared with the snapshot: {} added, {} changed, {} removed",
+            added,
+            changed,
+            self.removed.len()
+        );
+        if let Some(column) = headers.first().filter(|_| !self.removed.is_empty()) {
+            let removed = self
+                .removed
+                .iter()
+                .take(SHOWN)
+                .filter_map(|row| row.first())
+                .map(|value| format!("{}={}", column, value))
+                .collect::<Vec<String>>()
+                .join(", ");
+            summary.push_str(&format!(
+                " ({}{})",
+                removed,
+                if self.removed.len() > SHOWN {
+                    ", …"
+                } else {
+                    ""
+                }
+            ));
+        }
+        summary
+    }
+}
+
+fn key(row: &[Value]) -> String {
+    format!("{:?}", row.first())
+}
+
+/// Returns the index of each row by its first column, or `None` if the values repeat.
+fn keyed(rows: &[Vec<Value>]) -> Option<HashMap<String, usize>> {
+    let mut keys = HashMap::new();
+    for (index, row) in rows.iter().enumerate() {
+        if keys.insert(key(row), index).is_some() {
+            return None;
+        }
+    }
+    Some(keys)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ResultDiff, RowChange};
+    use crate::database::Value;
+
+    fn row(id: i64, name: &str) -> Vec<Value> {
+        vec![Value::Int(id), Value::Text(name.to_string())]
+    }
+
+    #[test]
+    fn test_result_diff() {
+        let headers = ["id", "name"].map(String::from);
+        let snapshot = vec![row(1, "a"), row(2, "b"), row(3, "c")];
+        let rows = vec![row(1, "a"), row(3, "z"), row(4, "d")];
+        let diff = ResultDiff::new(&headers, &snapshot, &headers, &rows).unwrap();
+        assert_eq!(diff.rows.get(&0), None);
+        assert_eq!(
+            diff.rows.get(&1),
+            Some(&RowChange::Changed([1].into_iter().collect()))
+        );
+        assert_eq!(diff.rows.get(&2), Some(&RowChange::Added));
+        assert_eq!(diff.removed, vec![row(2, "b")]);
+        assert_eq!(
+            diff.summary(&headers),
+            "compared with the snapshot: 1 added, 1 changed, 1 removed (id=2)"
+        );
+
+        // the first column repeats, so the rows are compared as a whole
+        let snapshot = vec![row(1, "a"), row(1, "b")];
+        let rows = vec![row(1, "a"), row(1, "c")];
+        let diff = ResultDiff::new(&headers, &snapshot, &headers, &rows).unwrap();
+        assert_eq!(diff.rows.get(&1), Some(&RowChange::Added));
+        assert_eq!(diff.removed, vec![row(1, "b")]);
+
+        let other = ["id"].map(String::from);
+        assert!(ResultDiff::new(&other, &snapshot, &headers, &rows).is_none());
+    }
+}
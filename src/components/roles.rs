@@ -0,0 +1,224 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::DatabaseRole;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// A popup which lists the users and roles of the server, with the roles granted to them.
+/// The grants on a table are in its privileges tab.
+pub struct RolesComponent {
+    roles: Vec<DatabaseRole>,
+    selected: usize,
+    refresh_requested: bool,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl RolesComponent {
+    const NAME_WIDTH: usize = 32;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            roles: Vec::new(),
+            selected: 0,
+            refresh_requested: false,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Shows `roles`, keeping the selected role if it is still there.
+    pub fn open(&mut self, roles: Vec<DatabaseRole>) {
+        let selected = self.roles.get(self.selected).map(|role| &role.name);
+        self.selected = selected
+            .and_then(|name| roles.iter().position(|role| &role.name == name))
+            .unwrap_or(0);
+        self.roles = roles;
+        self.refresh_requested = false;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns true once if the user asked to list the roles again.
+    pub fn take_refresh(&mut self) -> bool {
+        std::mem::take(&mut self.refresh_requested)
+    }
+
+    fn line(role: &DatabaseRole) -> String {
+        format!(
+            "{:<width$} {:<5} {:<9} {}",
+            truncate(&role.name, Self::NAME_WIDTH),
+            if role.can_login { "yes" } else { "no" },
+            if role.superuser { "yes" } else { "" },
+            role.member_of.join(", "),
+            width = Self::NAME_WIDTH
+        )
+    }
+}
+
+/// Cuts `text` to `width` characters, marking the cut with an ellipsis.
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+impl DrawableComponent for RolesComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let width = (f.size().width * 4 / 5).max(60).min(f.size().width);
+            let height = (f.size().height * 3 / 4).max(8).min(f.size().height);
+            let area = Rect::new(
+                (f.size().width - width) / 2,
+                (f.size().height - height) / 2,
+                width,
+                height,
+            );
+            let block = Block::default()
+                .title(format!(
+                    "Users and roles ({}) [{}] refresh [{}] close",
+                    self.roles.len(),
+                    self.key_config.refresh,
+                    self.key_config.exit_popup
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(1)])
+                .split(block.inner(area));
+            let header = Line::from(Span::styled(
+                format!(
+                    "{:<width$} {:<5} {:<9} {}",
+                    "name",
+                    "login",
+                    "superuser",
+                    "member of",
+                    width = Self::NAME_WIDTH
+                ),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            let list_height = chunks[1].height as usize;
+            let offset = self.selected.saturating_sub(list_height.saturating_sub(1));
+            let list: Vec<Line> = if self.roles.is_empty() {
+                vec![Line::from(Span::styled(
+                    "No users or roles are visible to this user.",
+                    Style::default().fg(Color::DarkGray),
+                ))]
+            } else {
+                self.roles
+                    .iter()
+                    .enumerate()
+                    .skip(offset)
+                    .take(list_height)
+                    .map(|(index, role)| {
+                        let style = if index == self.selected {
+                            Style::default()
+                                .fg(Color::Blue)
+                                .add_modifier(Modifier::BOLD)
+                        } else if !role.can_login {
+                            Style::default().fg(Color::DarkGray)
+                        } else {
+                            Style::default()
+                        };
+                        Line::from(Span::styled(Self::line(role), style))
+                    })
+                    .collect()
+            };
+
+            f.render_widget(Clear, area);
+            f.render_widget(block, area);
+            f.render_widget(Paragraph::new(header), chunks[0]);
+            f.render_widget(Paragraph::new(list), chunks[1]);
+        }
+        Ok(())
+    }
+}
+
+impl Component for RolesComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        match key {
+            key if key == self.key_config.exit => return Ok(EventState::NotConsumed),
+            key if key == self.key_config.exit_popup || key == self.key_config.open_roles => {
+                self.hide()
+            }
+            key if key == self.key_config.refresh => self.refresh_requested = true,
+            key if (key == self.key_config.scroll_down || key == self.key_config.move_down)
+                && self.selected + 1 < self.roles.len() =>
+            {
+                self.selected += 1;
+            }
+            key if key == self.key_config.scroll_up || key == self.key_config.move_up => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            _ => (),
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RolesComponent;
+    use crate::components::Component;
+    use crate::config::KeyConfig;
+    use crate::database::DatabaseRole;
+
+    fn role(name: &str, can_login: bool) -> DatabaseRole {
+        DatabaseRole {
+            name: name.to_string(),
+            can_login,
+            superuser: false,
+            member_of: vec!["readers".to_string(), "writers".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_roles() {
+        let key_config = KeyConfig::default();
+        let mut roles = RolesComponent::new(key_config.clone());
+        roles.open(vec![role("app", true), role("readers", false)]);
+        assert!(RolesComponent::line(&roles.roles[0]).starts_with("app "));
+        assert!(RolesComponent::line(&roles.roles[1]).ends_with("no              readers, writers"));
+
+        // the selection follows the role when the list is fetched again
+        roles.event(key_config.scroll_down).unwrap();
+        roles.open(vec![role("admin", true), role("readers", false)]);
+        assert_eq!(roles.selected, 1);
+
+        assert!(!roles.take_refresh());
+        roles.event(key_config.refresh).unwrap();
+        assert!(roles.take_refresh());
+        roles.event(key_config.open_roles).unwrap();
+        assert!(!roles.is_visible());
+    }
+}
@@ -0,0 +1,311 @@
+use super::DrawableComponent;
+use crate::config::KeyConfig;
+use crate::database::script::{transaction_control, TransactionControl};
+use crate::database::{ExecuteResult, Pool, Value};
+use crate::tree::{Database, Table};
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+enum StatementResult {
+    Done {
+        rows: u64,
+        read: bool,
+        elapsed: Duration,
+    },
+    Failed {
+        error: String,
+        elapsed: Duration,
+    },
+    Skipped,
+}
+
+/// The rows returned by one of the statements of a script.
+pub struct ResultSet {
+    /// The index of the statement in the script.
+    pub statement: usize,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub database: Database,
+    pub table: Table,
+    pub truncated: bool,
+}
+
+/// Runs the statements of a script one at a time and shows how each of them went.
+pub struct ScriptProgressComponent {
+    statements: Vec<String>,
+    results: Vec<StatementResult>,
+    result_sets: Vec<ResultSet>,
+    stop_on_error: bool,
+    started_at: Instant,
+    elapsed: Option<Duration>,
+    key_config: KeyConfig,
+}
+
+impl ScriptProgressComponent {
+    pub fn new(statements: Vec<String>, stop_on_error: bool, key_config: KeyConfig) -> Self {
+        Self {
+            statements,
+            results: Vec::new(),
+            result_sets: Vec::new(),
+            stop_on_error,
+            started_at: Instant::now(),
+            elapsed: None,
+            key_config,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.elapsed.is_none()
+    }
+
+    pub fn result_sets(&self) -> &[ResultSet] {
+        &self.result_sets
+    }
+
+    /// Returns e.g. `#2 3 rows 1.20ms` for the result set at `index`.
+    pub fn result_set_title(&self, index: usize) -> String {
+        let result_set = &self.result_sets[index];
+        match self.results.get(result_set.statement) {
+            Some(StatementResult::Done { rows, elapsed, .. }) => format!(
+                "#{} {} rows {:.2?}",
+                result_set.statement + 1,
+                rows,
+                elapsed
+            ),
+            _ => format!("#{}", result_set.statement + 1),
+        }
+    }
+
+    pub fn toggle_stop_on_error(&mut self) {
+        self.stop_on_error = !self.stop_on_error;
+    }
+
+    /// Skips the statements which have not run yet.
+    pub fn cancel(&mut self) {
+        while self.results.len() < self.statements.len() {
+            self.results.push(StatementResult::Skipped);
+        }
+        self.finish();
+    }
+
+    fn finish(&mut self) {
+        if self.elapsed.is_none() {
+            self.elapsed = Some(self.started_at.elapsed());
+        }
+    }
+
+    /// Runs the next statement. The script stops at a failed statement unless it was
+    /// told to continue on errors. Returns the statement which opened or closed a
+    /// transaction, if the next one did.
+    pub async fn step(&mut self, pool: &dyn Pool) -> Option<TransactionControl> {
+        let statement = match self.statements.get(self.results.len()) {
+            Some(statement) if self.is_running() => statement,
+            _ => {
+                self.finish();
+                return None;
+            }
+        };
+        let started_at = Instant::now();
+        // the transaction has to keep its connection, which `execute` does not
+        let control = transaction_control(statement);
+        let no_rows =
+            |result: Result<()>| result.map(|()| ExecuteResult::Write { updated_rows: 0 });
+        let result = match control {
+            Some(TransactionControl::Begin) => no_rows(pool.begin().await),
+            Some(TransactionControl::Commit) => no_rows(pool.commit().await),
+            Some(TransactionControl::Rollback) => no_rows(pool.rollback().await),
+            None => pool.execute(statement).await,
+        };
+        // the pool gives up the transaction even if it fails to end it
+        let changed =
+            control.filter(|control| *control != TransactionControl::Begin || result.is_ok());
+        let result = match result {
+            Ok(ExecuteResult::Read {
+                headers,
+                rows,
+                database,
+                table,
+                truncated,
+            }) => {
+                let elapsed = started_at.elapsed();
+                let count = rows.len() as u64;
+                self.result_sets.push(ResultSet {
+                    statement: self.results.len(),
+                    headers,
+                    rows,
+                    database,
+                    table: *table,
+                    truncated,
+                });
+                StatementResult::Done {
+                    rows: count,
+                    read: true,
+                    elapsed,
+                }
+            }
+            Ok(ExecuteResult::Write { updated_rows }) => StatementResult::Done {
+                rows: updated_rows,
+                read: false,
+                elapsed: started_at.elapsed(),
+            },
+            Err(error) => StatementResult::Failed {
+                error: error.to_string(),
+                elapsed: started_at.elapsed(),
+            },
+        };
+        let failed = matches!(result, StatementResult::Failed { .. });
+        self.results.push(result);
+        if failed && self.stop_on_error {
+            self.cancel();
+        } else if self.results.len() == self.statements.len() {
+            self.finish();
+        }
+        changed
+    }
+
+    fn summary(&self) -> String {
+        let count = |f: fn(&StatementResult) -> bool| self.results.iter().filter(|r| f(r)).count();
+        let affected_rows = self
+            .results
+            .iter()
+            .map(|result| match result {
+                StatementResult::Done {
+                    rows, read: false, ..
+                } => *rows,
+                _ => 0,
+            })
+            .sum::<u64>();
+        format!(
+            "{} succeeded, {} failed, {} skipped, {} rows affected in {:.2?}",
+            count(|r| matches!(r, StatementResult::Done { .. })),
+            count(|r| matches!(r, StatementResult::Failed { .. })),
+            count(|r| matches!(r, StatementResult::Skipped)),
+            affected_rows,
+            self.elapsed.unwrap_or_else(|| self.started_at.elapsed())
+        )
+    }
+
+    fn line(&self, index: usize, statement: &str) -> Line<'static> {
+        // only the first line of a statement fits in the list
+        let statement = statement.lines().next().unwrap_or_default().to_string();
+        let (mark, style, detail) = match self.results.get(index) {
+            Some(StatementResult::Done {
+                rows,
+                read,
+                elapsed,
+            }) => (
+                "✓",
+                Style::default().fg(Color::Green),
+                format!(
+                    "{} rows {} ({:.2?})",
+                    rows,
+                    if *read { "returned" } else { "affected" },
+                    elapsed
+                ),
+            ),
+            Some(StatementResult::Failed { error, elapsed }) => (
+                "✗",
+                Style::default().fg(Color::Red),
+                format!("{} ({:.2?})", error, elapsed),
+            ),
+            Some(StatementResult::Skipped) => {
+                ("-", Style::default().fg(Color::DarkGray), String::new())
+            }
+            None if index == self.results.len() && self.is_running() => {
+                ("…", Style::default().fg(Color::Yellow), String::new())
+            }
+            None => (" ", Style::default().fg(Color::DarkGray), String::new()),
+        };
+        Line::from(vec![
+            Span::styled(format!("{} {:>3} ", mark, index + 1), style),
+            Span::raw(statement),
+            Span::styled(format!("  {}", detail), style),
+        ])
+    }
+}
+
+impl DrawableComponent for ScriptProgressComponent {
+    fn draw(&self, f: &mut Frame, area: Rect, focused: bool) -> Result<()> {
+        let mut lines = self
+            .statements
+            .iter()
+            .enumerate()
+            .map(|(index, statement)| self.line(index, statement))
+            .collect::<Vec<Line>>();
+        if !self.is_running() {
+            lines.push(Line::from(Span::styled(
+                self.summary(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+        }
+        // keep the statement which is running in sight
+        let height = area.height.saturating_sub(2) as usize;
+        let scroll = self.results.len().saturating_add(2).saturating_sub(height);
+        let title = format!(
+            "Script {}/{}, {} [{}]{}",
+            self.results.len(),
+            self.statements.len(),
+            if self.stop_on_error {
+                "stop on error"
+            } else {
+                "continue on error"
+            },
+            self.key_config.toggle_stop_on_error,
+            if self.is_running() {
+                format!(", cancel [{}]", self.key_config.exit_popup)
+            } else {
+                String::new()
+            }
+        );
+        f.render_widget(
+            Paragraph::new(lines).scroll((scroll as u16, 0)).block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .style(if focused {
+                        Style::default()
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{KeyConfig, ScriptProgressComponent, StatementResult};
+    use std::time::Duration;
+
+    #[test]
+    fn test_cancel_and_summary() {
+        let mut script = ScriptProgressComponent::new(
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            true,
+            KeyConfig::default(),
+        );
+        script.results.push(StatementResult::Done {
+            rows: 3,
+            read: false,
+            elapsed: Duration::ZERO,
+        });
+        assert!(script.is_running());
+
+        script.cancel();
+        assert!(!script.is_running());
+        script.elapsed = Some(Duration::ZERO);
+        assert_eq!(
+            script.summary(),
+            "1 succeeded, 0 failed, 2 skipped, 3 rows affected in 0.00ns"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::SessionSetting;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// A popup which shows the session settings of the connection and switches them.
+pub struct SessionSettingsComponent {
+    settings: Vec<SessionSetting>,
+    selected: usize,
+    change: Option<(String, String)>,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl SessionSettingsComponent {
+    const WIDTH: u16 = 70;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            settings: Vec::new(),
+            selected: 0,
+            change: None,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Shows `settings`, keeping the selection when the panel is opened again after a change.
+    pub fn open(&mut self, settings: Vec<SessionSetting>) {
+        self.selected = self.selected.min(settings.len().saturating_sub(1));
+        self.settings = settings;
+        self.change = None;
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the name of the setting and its new value once the user has switched it.
+    pub fn take_change(&mut self) -> Option<(String, String)> {
+        self.change.take()
+    }
+}
+
+impl DrawableComponent for SessionSettingsComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if self.visible {
+            let height = self.settings.len() as u16 + 4;
+            let area = Rect::new(
+                (f.size().width.saturating_sub(Self::WIDTH)) / 2,
+                (f.size().height.saturating_sub(height)) / 2,
+                Self::WIDTH.min(f.size().width),
+                height.min(f.size().height),
+            );
+            let mut lines = self
+                .settings
+                .iter()
+                .enumerate()
+                .map(|(index, setting)| {
+                    let style = if index == self.selected {
+                        Style::default()
+                            .fg(Color::Blue)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default()
+                    };
+                    let next = setting
+                        .next_choice()
+                        .map_or(String::new(), |next| format!("  -> {}", next));
+                    Line::from(vec![
+                        Span::styled(format!("{:<32}", setting.name), style),
+                        Span::styled(setting.value.clone(), style),
+                        Span::styled(next, Style::default().fg(Color::DarkGray)),
+                    ])
+                })
+                .collect::<Vec<Line>>();
+            lines.push(Line::default());
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "other values: {}set <name> <value>",
+                    self.key_config.command_line
+                ),
+                Style::default().fg(Color::DarkGray),
+            )));
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(lines).block(
+                    Block::default()
+                        .title(format!(
+                            "Session settings [{}] switch [{}] close",
+                            self.key_config.enter, self.key_config.exit_popup
+                        ))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                area,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for SessionSettingsComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit {
+            return Ok(EventState::NotConsumed);
+        } else if key == self.key_config.exit_popup {
+            self.hide();
+        } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            if self.selected + 1 < self.settings.len() {
+                self.selected += 1;
+            }
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.selected = self.selected.saturating_sub(1);
+        } else if key == self.key_config.enter {
+            if let Some(setting) = self.settings.get(self.selected) {
+                self.change = setting
+                    .next_choice()
+                    .map(|next| (setting.name.clone(), next.clone()));
+            }
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        Ok(())
+    }
+}
@@ -1,32 +1,111 @@
 use super::{
-    compute_character_width, CompletionComponent, Component, EventState, MovableComponent,
-    StatefulDrawableComponent, TableComponent,
+    CompletionComponent, Component, ConfirmComponent, DrawableComponent, EventState, LineCommand,
+    MovableComponent, ParameterFormComponent, ScriptProgressComponent, StatefulDrawableComponent,
+    TableComponent,
+};
+use crate::components::command::{self, CommandInfo};
+use crate::components::result_chart::{ChartKind, ResultChart};
+use crate::components::result_diff::ResultDiff;
+use crate::config::{EditorConfig, KeyConfig, SqlFormatConfig};
+use crate::database::format::format_sql;
+use crate::database::params::parse_value;
+use crate::database::script::{
+    destructive, split_statements, statement_at, transaction_control, with_limit,
+    TransactionControl,
+};
+use crate::database::{
+    Dialect, ExecuteResult, FailedStatement, Pool, QueryParams, RowStream, SpillFile, StreamedRows,
+    Value,
 };
-use crate::components::command::CommandInfo;
-use crate::config::KeyConfig;
-use crate::database::{ExecuteResult, Pool};
 use crate::event::Key;
-use crate::ui::stateful_paragraph::{ParagraphState, StatefulParagraph};
-use anyhow::Result;
+use crate::history::HistoryCursor;
+use crate::tree::{Database, Table, TableKind};
+use crate::ui::editor_view::{EditorView, EditorViewState};
+use crate::vim::Vim;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{block::Title, Block, Borders, Paragraph, Tabs, Wrap},
     Frame,
 };
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::error::TryRecvError;
 use unicode_width::UnicodeWidthStr;
 
-struct QueryResult {
-    updated_rows: u64,
+enum QueryResult {
+    Write { updated_rows: u64 },
+    Transaction(TransactionControl),
 }
 
 impl QueryResult {
     fn result_str(&self) -> String {
-        format!("Query OK, {} row affected", self.updated_rows)
+        match self {
+            Self::Write { updated_rows } => format!("Query OK, {} row affected", updated_rows),
+            Self::Transaction(TransactionControl::Begin) => "Transaction started".to_string(),
+            Self::Transaction(TransactionControl::Commit) => "Committed".to_string(),
+            Self::Transaction(TransactionControl::Rollback) => "Rolled back".to_string(),
+        }
     }
 }
 
+/// A result of the editor which is kept in memory to switch back to.
+struct RecentResult {
+    query: String,
+    headers: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    total: Option<usize>,
+    database: Database,
+    table: Table,
+}
+
+impl RecentResult {
+    const QUERY_WIDTH: usize = 24;
+
+    /// Names the result by its number, the start of its query and its row count.
+    fn title(&self, number: usize) -> String {
+        let query = self.query.split_whitespace().collect::<Vec<_>>().join(" ");
+        let query = if query.chars().count() > Self::QUERY_WIDTH {
+            let mut cut: String = query.chars().take(Self::QUERY_WIDTH - 1).collect();
+            cut.push('…');
+            cut
+        } else {
+            query
+        };
+        format!("{}: {} ({} rows)", number, query, self.rows.len())
+    }
+}
+
+/// What to do with the file whose path the user is asked for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqlFileAction {
+    Open,
+    Save,
+}
+
+/// Returns the placeholders which a query result is shown as a table of.
+fn result_table() -> (Database, Table) {
+    (
+        Database {
+            name: "-".to_string(),
+            children: Vec::new(),
+        },
+        Table {
+            name: "-".to_string(),
+            create_time: None,
+            update_time: None,
+            engine: None,
+            schema: None,
+            kind: TableKind::Table,
+            partition_of: None,
+        },
+    )
+}
+
 pub enum Focus {
     Editor,
     Table,
@@ -34,14 +113,66 @@ pub enum Focus {
 
 pub struct SqlEditorComponent {
     input: Vec<char>,
-    input_cursor_position_x: u16,
     input_idx: usize,
     table: TableComponent,
     query_result: Option<QueryResult>,
+    script: Option<ScriptProgressComponent>,
+    /// The result set of the script which is shown instead of its progress.
+    result_set: Option<usize>,
+    stop_on_error: bool,
+    /// Start a transaction before running a statement if none is open.
+    transaction_mode: bool,
+    in_transaction: bool,
     completion: CompletionComponent,
+    /// Asks for the values of the placeholders of the query before it runs.
+    parameter_form: ParameterFormComponent,
+    /// The dialect of the connection, whose placeholders the parameter form asks for.
+    dialect: Dialect,
+    /// Asks before a destructive statement runs.
+    confirm: ConfirmComponent,
+    confirm_destructive: bool,
+    /// The `LIMIT` which is added to the `SELECT`s without one.
+    auto_limit: Option<usize>,
+    /// The `LIMIT` which was added to the running query.
+    limit: Option<usize>,
+    /// The query which waits for the user to confirm it or to give the values of its
+    /// parameters.
+    pending_query: Option<String>,
     key_config: KeyConfig,
-    paragraph_state: ParagraphState,
+    editor_state: EditorViewState,
     focus: Focus,
+    external_edit_requested: bool,
+    file_requested: Option<SqlFileAction>,
+    /// The file the SQL was opened from or saved to last.
+    file: Option<PathBuf>,
+    history: HistoryCursor,
+    /// The query which was run last, until the app adds it to the history.
+    submitted: Option<String>,
+    /// The rows of the running query, which are shown as they arrive.
+    stream: Option<RowStream>,
+    /// The statement of the running query, for the error console if it fails.
+    streamed: Option<String>,
+    /// The rows of the result over the memory budget, which are loaded as they are scrolled to.
+    spilled: Option<SpillFile>,
+    /// The headers and the rows of a result which the next results are compared with.
+    snapshot: Option<(Vec<String>, Vec<Vec<Value>>)>,
+    /// The chart which the result is shown as instead of the table.
+    chart: Option<ChartKind>,
+    /// When the running query started.
+    started_at: Option<Instant>,
+    /// How long the last query took, until the app shows it.
+    query_time: Option<Duration>,
+    /// The last results, the oldest first, which can be shown again without running their
+    /// queries.
+    results: VecDeque<RecentResult>,
+    /// How many results are kept.
+    result_history: usize,
+    /// The recent result which the table shows, `None` while a new one is running.
+    shown_result: Option<usize>,
+    sql_format: SqlFormatConfig,
+    editor_config: EditorConfig,
+    /// The modes of the vim-like editing if it is turned on.
+    vim: Option<Vim>,
 }
 
 impl SqlEditorComponent {
@@ -49,16 +180,524 @@ impl SqlEditorComponent {
         Self {
             input: Vec::new(),
             input_idx: 0,
-            input_cursor_position_x: 0,
             table: TableComponent::new(key_config.clone()),
             completion: CompletionComponent::new(key_config.clone(), "", true),
+            parameter_form: ParameterFormComponent::new(key_config.clone()),
+            dialect: Dialect::MySql,
+            confirm: ConfirmComponent::new(key_config.clone()),
+            confirm_destructive: true,
+            auto_limit: None,
+            limit: None,
+            pending_query: None,
             focus: Focus::Editor,
-            paragraph_state: ParagraphState::default(),
+            editor_state: EditorViewState::default(),
             query_result: None,
+            script: None,
+            result_set: None,
+            stop_on_error: true,
+            transaction_mode: false,
+            in_transaction: false,
+            external_edit_requested: false,
+            file_requested: None,
+            file: None,
+            history: HistoryCursor::default(),
+            submitted: None,
+            stream: None,
+            streamed: None,
+            spilled: None,
+            snapshot: None,
+            chart: None,
+            started_at: None,
+            query_time: None,
+            results: VecDeque::new(),
+            result_history: 0,
+            shown_result: None,
+            sql_format: SqlFormatConfig::default(),
+            editor_config: EditorConfig::default(),
+            vim: None,
             key_config,
         }
     }
 
+    pub fn with_result_history(mut self, result_history: usize) -> Self {
+        self.result_history = result_history;
+        self
+    }
+
+    pub fn with_sql_format(mut self, sql_format: SqlFormatConfig) -> Self {
+        self.sql_format = sql_format;
+        self
+    }
+
+    pub fn with_editor_config(mut self, editor_config: EditorConfig) -> Self {
+        self.editor_config = editor_config;
+        self.vim = editor_config.vim.then(Vim::default);
+        self
+    }
+
+    pub fn query(&self) -> String {
+        self.input.iter().collect()
+    }
+
+    pub fn is_running_script(&self) -> bool {
+        self.script
+            .as_ref()
+            .is_some_and(|script| script.is_running())
+    }
+
+    /// Runs the next statement of the script, keeping track of the transactions it opens and
+    /// closes.
+    pub async fn step_script(&mut self, pool: &dyn Pool) {
+        if let Some(script) = self.script.as_mut() {
+            if let Some(control) = script.step(pool).await {
+                self.in_transaction = control == TransactionControl::Begin;
+            }
+        }
+    }
+
+    /// Returns how long the last query took once it has finished.
+    pub fn take_query_time(&mut self) -> Option<Duration> {
+        self.query_time.take()
+    }
+
+    fn finish_query(&mut self) {
+        if let Some(started_at) = self.started_at.take() {
+            self.query_time = Some(started_at.elapsed());
+        }
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Shows the rows of the running query which arrived since the last step, waiting a
+    /// little for them so that the event loop does not spin.
+    pub async fn step_stream(&mut self) -> Result<()> {
+        const WAIT: Duration = Duration::from_millis(50);
+        let stream = match self.stream.as_mut() {
+            Some(stream) => stream,
+            None => return Ok(()),
+        };
+        let mut message = match tokio::time::timeout(WAIT, stream.recv()).await {
+            Ok(Some(message)) => Ok(message),
+            Ok(None) => Err(TryRecvError::Disconnected),
+            Err(_) => return Ok(()),
+        };
+        loop {
+            match message {
+                Ok(Ok(StreamedRows::Rows { headers, rows })) => {
+                    if self.table.headers.is_empty() {
+                        let (database, table) = result_table();
+                        self.table
+                            .update(rows, None, headers, database, table, false);
+                    } else {
+                        self.table.extend(rows);
+                    }
+                    self.table.set_warning(Some(format!(
+                        "fetching, {} rows so far, press {} to stop",
+                        self.table.row_count(),
+                        self.key_config.exit_popup
+                    )));
+                }
+                Ok(Ok(StreamedRows::Done { truncated, spilled })) => {
+                    self.stream = None;
+                    self.finish_query();
+                    if let Some(query) = self.streamed.take() {
+                        self.remember_result(query);
+                    }
+                    if let Some(spilled) = spilled.filter(|spilled| spilled.remaining() > 0) {
+                        self.table.total_row_count =
+                            Some(self.table.row_count() + spilled.remaining());
+                        self.table.set_warning(Some(format!(
+                            "{} more rows on disk, scroll down to load them",
+                            spilled.remaining()
+                        )));
+                        self.spilled = Some(spilled);
+                    } else if truncated {
+                        self.table.set_warning(Some(format!(
+                            "stopped at the memory budget, press {} to export all rows",
+                            self.key_config.export_to_file
+                        )));
+                    } else {
+                        self.table.total_row_count = Some(self.table.row_count());
+                        self.table.set_warning(None);
+                        self.compare_with_snapshot();
+                        self.show_limit();
+                    }
+                    return Ok(());
+                }
+                Ok(Err(e)) => {
+                    self.stream = None;
+                    self.finish_query();
+                    self.table.set_warning(None);
+                    return Err(match self.streamed.take() {
+                        Some(statement) => e.context(FailedStatement(statement)),
+                        None => e,
+                    });
+                }
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => {
+                    self.stream = None;
+                    self.streamed = None;
+                    self.finish_query();
+                    return Ok(());
+                }
+            }
+            message = match self.stream.as_mut() {
+                Some(stream) => stream.try_recv(),
+                None => return Ok(()),
+            };
+        }
+    }
+
+    /// Sets whether destructive statements of the connection are confirmed before they run.
+    pub fn set_confirm_destructive(&mut self, enabled: bool) {
+        self.confirm_destructive = enabled;
+    }
+
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    pub fn set_auto_limit(&mut self, limit: Option<usize>) {
+        self.auto_limit = limit;
+    }
+
+    /// Loads the next page of the spilled rows once the last loaded row is selected.
+    fn load_spilled(&mut self) -> Result<()> {
+        const PAGE: usize = 500;
+        let spilled = match self.spilled.as_mut() {
+            Some(spilled) => spilled,
+            None => return Ok(()),
+        };
+        if self.table.selected_row.selected() != Some(self.table.row_count().saturating_sub(1)) {
+            return Ok(());
+        }
+        let rows = spilled.read(PAGE)?;
+        self.table.extend(rows);
+        let remaining = spilled.remaining();
+        if remaining == 0 {
+            self.spilled = None;
+            self.table.set_warning(None);
+        } else {
+            self.table.set_warning(Some(format!(
+                "{} more rows on disk, scroll down to load them",
+                remaining
+            )));
+        }
+        Ok(())
+    }
+
+    /// Tells that the result may have more rows than the `LIMIT` which was added to the query.
+    fn show_limit(&mut self) {
+        if let Some(limit) = self.limit.take() {
+            if self.snapshot.is_none() && self.table.row_count() >= limit {
+                self.table.set_warning(Some(format!(
+                    "limited to {} rows, add a LIMIT to the query for more",
+                    limit
+                )));
+            }
+        }
+    }
+
+    /// Returns the first destructive statement of `statements` and what it destroys, if they
+    /// are to be confirmed.
+    fn destructive_statement<'a>(
+        &self,
+        statements: &'a [String],
+    ) -> Option<(&'a str, &'static str)> {
+        if !self.confirm_destructive {
+            return None;
+        }
+        statements
+            .iter()
+            .find_map(|statement| destructive(statement).map(|reason| (statement.as_str(), reason)))
+    }
+
+    /// Sets the transaction mode of a new connection, whose pool has no open transaction.
+    pub fn set_transaction_mode(&mut self, enabled: bool) {
+        self.transaction_mode = enabled;
+        self.in_transaction = false;
+    }
+
+    /// Forgets the open transaction after its connection was lost. The transaction mode begins
+    /// a new one with the next statement.
+    pub fn forget_transaction(&mut self) {
+        self.in_transaction = false;
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
+    pub fn transaction_status(&self) -> Option<&'static str> {
+        if self.in_transaction {
+            Some("in transaction")
+        } else if self.transaction_mode {
+            Some("transaction mode")
+        } else {
+            None
+        }
+    }
+
+    async fn control_transaction(
+        &mut self,
+        control: TransactionControl,
+        pool: &dyn Pool,
+    ) -> Result<()> {
+        match control {
+            TransactionControl::Begin => {
+                pool.begin().await?;
+                self.in_transaction = true;
+            }
+            TransactionControl::Commit | TransactionControl::Rollback => {
+                // the pool gives up the transaction even if it fails to end it
+                self.in_transaction = false;
+                if control == TransactionControl::Commit {
+                    pool.commit().await?;
+                } else {
+                    pool.rollback().await?;
+                }
+            }
+        }
+        self.script = None;
+        self.query_result = Some(QueryResult::Transaction(control));
+        Ok(())
+    }
+
+    /// Takes a snapshot of the result, or forgets the snapshot if there is one.
+    fn toggle_snapshot(&mut self) {
+        if self.snapshot.take().is_some() {
+            self.table.set_row_changes(HashMap::new());
+            self.table.set_warning(None);
+            return;
+        }
+        if self.table.headers.is_empty() {
+            return;
+        }
+        self.snapshot = Some((self.table.headers.clone(), self.table.values().to_vec()));
+        self.table.set_warning(Some(format!(
+            "took a snapshot of {} rows, run the query again to compare",
+            self.table.row_count()
+        )));
+    }
+
+    /// Highlights how the result differs from the snapshot, if there is one.
+    fn compare_with_snapshot(&mut self) {
+        let (headers, rows) = match self.snapshot.as_ref() {
+            Some(snapshot) => snapshot,
+            None => return,
+        };
+        let diff = match ResultDiff::new(headers, rows, &self.table.headers, self.table.values()) {
+            Some(diff) => diff,
+            None => {
+                self.table.set_warning(Some(
+                    "the columns differ from the snapshot, so the rows are not compared"
+                        .to_string(),
+                ));
+                return;
+            }
+        };
+        self.table
+            .set_warning(Some(diff.summary(&self.table.headers)));
+        self.table.set_row_changes(diff.rows);
+    }
+
+    /// Keeps the result which the table shows, forgetting the oldest one beyond the history.
+    fn remember_result(&mut self, query: String) {
+        if self.result_history == 0 || self.table.headers.is_empty() {
+            return;
+        }
+        let (database, table) = self.table.table().cloned().unwrap_or_else(result_table);
+        self.results.push_back(RecentResult {
+            query,
+            headers: self.table.headers.clone(),
+            rows: self.table.values().to_vec(),
+            total: self.table.total_row_count,
+            database,
+            table,
+        });
+        while self.results.len() > self.result_history {
+            self.results.pop_front();
+        }
+        self.shown_result = Some(self.results.len() - 1);
+    }
+
+    /// Shows the recent result at `index` again.
+    fn select_recent_result(&mut self, index: usize) {
+        let result = match self.results.get(index) {
+            Some(result) => result,
+            None => return,
+        };
+        self.spilled = None;
+        self.table.update(
+            result.rows.clone(),
+            result.total,
+            result.headers.clone(),
+            result.database.clone(),
+            result.table.clone(),
+            false,
+        );
+        self.shown_result = Some(index);
+        self.query_result = None;
+        self.focus = Focus::Table;
+        self.compare_with_snapshot();
+    }
+
+    fn move_recent_result(&mut self, forward: bool) {
+        let last = self.results.len().saturating_sub(1);
+        let index = match (self.shown_result.unwrap_or(last), forward) {
+            (index, true) => (index + 1).min(last),
+            (index, false) => index.saturating_sub(1),
+        };
+        if Some(index) != self.shown_result {
+            self.select_recent_result(index);
+        }
+    }
+
+    /// Shows the result set at `index` of the script, or its progress if `index` is `None`.
+    fn select_result_set(&mut self, index: Option<usize>) {
+        let script = match self.script.as_ref() {
+            Some(script) => script,
+            None => return,
+        };
+        let result_set = match index.and_then(|index| script.result_sets().get(index)) {
+            Some(result_set) => result_set,
+            None => {
+                self.result_set = None;
+                return;
+            }
+        };
+        self.spilled = None;
+        let count = if result_set.truncated {
+            None
+        } else {
+            Some(result_set.rows.len())
+        };
+        self.table.update(
+            result_set.rows.clone(),
+            count,
+            result_set.headers.clone(),
+            result_set.database.clone(),
+            result_set.table.clone(),
+            false,
+        );
+        self.result_set = index;
+        self.focus = Focus::Table;
+    }
+
+    fn move_result_set(&mut self, forward: bool) {
+        let count = self
+            .script
+            .as_ref()
+            .map_or(0, |script| script.result_sets().len());
+        let index = match (self.result_set, forward) {
+            (None, true) if count > 0 => Some(0),
+            (None, _) => None,
+            (Some(index), true) => Some((index + 1).min(count.saturating_sub(1))),
+            (Some(0), false) => None,
+            (Some(index), false) => Some(index - 1),
+        };
+        self.select_result_set(index);
+    }
+
+    /// Describes the editor or the selected cell of the result in words for screen readers.
+    pub fn describe(&self) -> String {
+        match self.focus {
+            Focus::Editor => format!("SQL editor, {} characters", self.input.len()),
+            Focus::Table => format!("SQL result, {}", self.table.describe()),
+        }
+    }
+
+    /// Returns the number of loaded rows of the result and the total if it is known.
+    pub fn row_counts(&self) -> Option<(usize, Option<usize>)> {
+        if self.table.headers.is_empty() {
+            return None;
+        }
+        Some((self.table.row_count(), self.table.total_row_count))
+    }
+
+    /// Returns true once a query has shown a result table.
+    pub fn has_result(&self) -> bool {
+        !self.table.headers.is_empty()
+    }
+
+    /// Returns the headers and the values of the area selected in the result.
+    pub fn selected_area_values(&self) -> Option<(Vec<String>, Vec<Vec<Value>>)> {
+        self.table.selected_area_values()
+    }
+
+    pub fn is_table_focused(&self) -> bool {
+        matches!(self.focus, Focus::Table)
+    }
+
+    pub fn table(&self) -> &TableComponent {
+        &self.table
+    }
+
+    pub fn table_mut(&mut self) -> &mut TableComponent {
+        &mut self.table
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.table.is_searching()
+    }
+
+    /// Returns true while the parameter form takes the keys, even if the result has the focus.
+    pub fn is_filling_parameters(&self) -> bool {
+        self.parameter_form.is_visible()
+    }
+
+    /// Returns the current query if the user asked to edit it in an external editor.
+    pub fn take_external_edit(&mut self) -> Option<String> {
+        if !std::mem::take(&mut self.external_edit_requested) {
+            return None;
+        }
+        Some(self.input.iter().collect())
+    }
+
+    /// Returns whether the user asked to open or save a file.
+    pub fn take_file_request(&mut self) -> Option<SqlFileAction> {
+        self.file_requested.take()
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// Replaces the SQL with the content of the file.
+    pub fn open_file(&mut self, path: PathBuf) -> Result<()> {
+        let sql = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.set_input(sql);
+        self.focus = Focus::Editor;
+        self.file = Some(path);
+        Ok(())
+    }
+
+    pub fn save_file(&mut self, path: PathBuf) -> Result<()> {
+        std::fs::write(&path, self.query())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        self.file = Some(path);
+        Ok(())
+    }
+
+    /// Sets the SQL run on the connection before, oldest first.
+    pub fn set_history(&mut self, entries: Vec<String>) {
+        self.history.set_entries(entries);
+    }
+
+    /// Returns the query once it has been run.
+    pub fn take_submitted(&mut self) -> Option<String> {
+        self.submitted.take()
+    }
+
+    pub fn set_input(&mut self, input: String) {
+        self.input = input.chars().collect();
+        self.input_idx = self.input.len();
+        self.completion.update("");
+    }
+
     fn update_completion(&mut self) {
         let input = &self
             .input
@@ -118,20 +757,6 @@ impl SqlEditorComponent {
                 self.input_idx += 1;
             }
             self.input_idx -= self.completion.word().len();
-            self.input_cursor_position_x += middle
-                .join("")
-                .chars()
-                .map(compute_character_width)
-                .sum::<u16>();
-            if is_last_word {
-                self.input_cursor_position_x += " ".to_string().width() as u16
-            }
-            self.input_cursor_position_x -= self
-                .completion
-                .word()
-                .chars()
-                .map(compute_character_width)
-                .sum::<u16>();
             self.update_completion();
             return Ok(EventState::Consumed);
         }
@@ -139,6 +764,36 @@ impl SqlEditorComponent {
     }
 }
 
+impl SqlEditorComponent {
+    /// Draws the result as a table, or as a chart if one is toggled on.
+    fn draw_result(&mut self, f: &mut Frame, area: Rect, focused: bool) -> Result<()> {
+        let kind = match self.chart {
+            Some(kind) => kind,
+            None => return self.table.draw(f, area, focused),
+        };
+        match ResultChart::new(
+            kind,
+            &self.table.headers,
+            self.table.values(),
+            self.table.value_format(),
+        ) {
+            Ok(chart) => chart.draw(f, area, focused),
+            Err(e) => f.render_widget(
+                Paragraph::new(format!("{}", e))
+                    .block(
+                        Block::default()
+                            .title(format!("Chart [{}] next", self.key_config.toggle_chart))
+                            .borders(Borders::ALL),
+                    )
+                    .style(Style::default().fg(Color::DarkGray))
+                    .wrap(Wrap { trim: true }),
+                area,
+            ),
+        }
+        Ok(())
+    }
+}
+
 impl StatefulDrawableComponent for SqlEditorComponent {
     fn draw(&mut self, f: &mut Frame, area: Rect, focused: bool) -> Result<()> {
         let layout = Layout::default()
@@ -150,13 +805,55 @@ impl StatefulDrawableComponent for SqlEditorComponent {
             })
             .split(area);
 
-        let editor = StatefulParagraph::new(self.input.iter().collect::<String>())
-            .wrap(Wrap { trim: true })
-            .block(Block::default().borders(Borders::ALL));
+        let mut block = Block::default().borders(Borders::ALL);
+        if let Some(file) = &self.file {
+            block = block.title(file.display().to_string());
+        }
+        if let Some(vim) = &self.vim {
+            block = block
+                .title(Title::from(format!(" {} ", vim.mode().name())).alignment(Alignment::Right));
+        }
+        let editing = focused && matches!(self.focus, Focus::Editor);
+        let editor = EditorView::new(&self.input, self.input_idx)
+            .line_numbers(self.editor_config.line_numbers)
+            .highlight_line(self.editor_config.highlight_line && editing)
+            .wrap(self.editor_config.soft_wrap)
+            .selection(
+                self.vim
+                    .as_ref()
+                    .and_then(|vim| vim.selection(&self.input, self.input_idx)),
+            )
+            .block(block);
 
-        f.render_stateful_widget(editor, layout[0], &mut self.paragraph_state);
+        f.render_stateful_widget(editor, layout[0], &mut self.editor_state);
 
-        if let Some(result) = self.query_result.as_ref() {
+        if let Some(script) = self.script.as_ref() {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(1)])
+                .split(layout[1]);
+            let titles = std::iter::once("Progress".to_string())
+                .chain((0..script.result_sets().len()).map(|i| script.result_set_title(i)))
+                .map(Line::from)
+                .collect::<Vec<Line>>();
+            f.render_widget(
+                Tabs::new(titles)
+                    .select(self.result_set.map_or(0, |index| index + 1))
+                    .style(Style::default().fg(Color::DarkGray))
+                    .highlight_style(
+                        Style::default()
+                            .fg(Color::Reset)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                chunks[0],
+            );
+            match self.result_set {
+                Some(_) => {
+                    self.draw_result(f, chunks[1], focused && matches!(self.focus, Focus::Table))?
+                }
+                None => script.draw(f, chunks[1], focused)?,
+            }
+        } else if let Some(result) = self.query_result.as_ref() {
             let result = Paragraph::new(result.result_str())
                 .block(Block::default().borders(Borders::ALL).style(
                     if focused && matches!(self.focus, Focus::Editor) {
@@ -167,56 +864,230 @@ impl StatefulDrawableComponent for SqlEditorComponent {
                 ))
                 .wrap(Wrap { trim: true });
             f.render_widget(result, layout[1]);
+        } else if self.results.len() > 1 {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(1)])
+                .split(layout[1]);
+            let titles = self
+                .results
+                .iter()
+                .enumerate()
+                .map(|(index, result)| Line::from(result.title(index + 1)))
+                .collect::<Vec<Line>>();
+            f.render_widget(
+                Tabs::new(titles)
+                    .select(self.shown_result.unwrap_or(usize::MAX))
+                    .style(Style::default().fg(Color::DarkGray))
+                    .highlight_style(
+                        Style::default()
+                            .fg(Color::Reset)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                chunks[0],
+            );
+            self.draw_result(f, chunks[1], focused && matches!(self.focus, Focus::Table))?;
         } else {
-            self.table
-                .draw(f, layout[1], focused && matches!(self.focus, Focus::Table))?;
+            self.draw_result(f, layout[1], focused && matches!(self.focus, Focus::Table))?;
         }
 
-        if focused && matches!(self.focus, Focus::Editor) {
-            f.set_cursor(
-                (layout[0].x + 1)
-                    .saturating_add(
-                        self.input_cursor_position_x % layout[0].width.saturating_sub(2),
-                    )
-                    .min(area.right().saturating_sub(2)),
-                (layout[0].y
-                    + 1
-                    + self.input_cursor_position_x / layout[0].width.saturating_sub(2))
-                .min(layout[0].bottom()),
-            )
+        let (cursor_x, cursor_y) = self.editor_state.cursor();
+        if editing {
+            f.set_cursor(cursor_x, cursor_y)
         }
 
-        if focused && matches!(self.focus, Focus::Editor) {
+        if editing && !self.parameter_form.is_visible() && !self.confirm.is_visible() {
             self.completion.draw(
                 f,
                 area,
                 false,
-                self.input_cursor_position_x % layout[0].width.saturating_sub(2) + 1,
-                self.input_cursor_position_x / layout[0].width.saturating_sub(2),
+                cursor_x.saturating_sub(area.x),
+                cursor_y.saturating_sub(area.y + 1),
             )?;
         };
+        self.parameter_form.draw(f, area, false)?;
+        self.confirm.draw(f, area, false)?;
         Ok(())
     }
 }
 
 #[async_trait]
 impl Component for SqlEditorComponent {
-    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::edit_in_external_editor(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::open_or_save_sql_file(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::format_sql(&self.key_config)));
+        out.push(CommandInfo::new(command::run_statement(&self.key_config)));
+        out.push(CommandInfo::new(command::toggle_stop_on_error(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::snapshot_result(&self.key_config)));
+        out.push(CommandInfo::new(command::toggle_chart(&self.key_config)));
+        out.push(CommandInfo::new(command::switch_result_set(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::toggle_transaction_mode(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::commit_or_rollback(
+            &self.key_config,
+        )));
+    }
+
+    fn line_commands(&self, out: &mut Vec<LineCommand>) {
+        out.push(LineCommand::new(
+            "stop-on-error",
+            "on|off",
+            "Stop a script at a failed statement or continue",
+        ));
+    }
+
+    fn run_line_command(&mut self, name: &str, args: &[&str]) -> Result<EventState> {
+        if name != "stop-on-error" {
+            return Ok(EventState::NotConsumed);
+        }
+        let stop_on_error = match args {
+            ["on"] => true,
+            ["off"] => false,
+            _ => anyhow::bail!("usage: stop-on-error on|off"),
+        };
+        if stop_on_error != self.stop_on_error {
+            self.stop_on_error = stop_on_error;
+            if let Some(script) = self.script.as_mut() {
+                script.toggle_stop_on_error();
+            }
+        }
+        Ok(EventState::Consumed)
+    }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.table.is_searching() {
+            return self.table.event(key);
+        }
+        if self.parameter_form.is_visible() {
+            // the submitted values are run by `async_event`
+            if self.parameter_form.event(key)?.is_consumed() && !self.parameter_form.has_values() {
+                return Ok(EventState::Consumed);
+            }
+            return Ok(EventState::NotConsumed);
+        }
+        if self.confirm.is_visible() {
+            // the confirmed query is run by `async_event`
+            if self.confirm.event(key)?.is_consumed() && !self.confirm.is_confirmed() {
+                return Ok(EventState::Consumed);
+            }
+            return Ok(EventState::NotConsumed);
+        }
+        if let (Some(vim), Focus::Editor) = (self.vim.as_mut(), &self.focus) {
+            if vim.event(key, &mut self.input, &mut self.input_idx) {
+                self.completion.update("");
+                return Ok(EventState::Consumed);
+            }
+        }
         let input_str: String = self.input.iter().collect();
 
+        if key == self.key_config.toggle_stop_on_error {
+            self.stop_on_error = !self.stop_on_error;
+            if let Some(script) = self.script.as_mut() {
+                script.toggle_stop_on_error();
+            }
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.toggle_transaction_mode {
+            if self.transaction_mode && self.in_transaction {
+                anyhow::bail!("Commit or roll back the open transaction first");
+            }
+            self.transaction_mode = !self.transaction_mode;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.exit_popup && self.is_streaming() {
+            // dropping the stream stops the task which fetches the rows
+            self.stream = None;
+            if let Some(query) = self.streamed.take() {
+                self.remember_result(query);
+            }
+            self.table.set_warning(Some(format!(
+                "stopped after {} rows",
+                self.table.row_count()
+            )));
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.exit_popup && self.is_running_script() {
+            if let Some(script) = self.script.as_mut() {
+                script.cancel();
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config.snapshot_result
+            && matches!(self.focus, Focus::Table)
+            && !self.is_streaming()
+        {
+            self.toggle_snapshot();
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config.toggle_chart && matches!(self.focus, Focus::Table) {
+            self.chart = ChartKind::next(self.chart);
+            return Ok(EventState::Consumed);
+        }
+
+        if self.script.is_some() && matches!(self.focus, Focus::Table) {
+            if key == self.key_config.next_result_set {
+                self.move_result_set(true);
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.previous_result_set {
+                self.move_result_set(false);
+                return Ok(EventState::Consumed);
+            }
+        } else if matches!(self.focus, Focus::Table) && !self.is_streaming() {
+            if key == self.key_config.next_result_set {
+                self.move_recent_result(true);
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.previous_result_set {
+                self.move_recent_result(false);
+                return Ok(EventState::Consumed);
+            }
+        }
+
         if key == self.key_config.focus_above && matches!(self.focus, Focus::Table) {
             self.focus = Focus::Editor
         } else if key == self.key_config.enter {
             return self.complete();
+        } else if key == self.key_config.edit_in_external_editor
+            && matches!(self.focus, Focus::Editor)
+        {
+            self.external_edit_requested = true;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.format_sql && matches!(self.focus, Focus::Editor) {
+            self.set_input(format_sql(&self.query(), &self.sql_format));
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.open_sql_file {
+            self.file_requested = Some(SqlFileAction::Open);
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.save_sql_file {
+            self.file_requested = Some(SqlFileAction::Save);
+            return Ok(EventState::Consumed);
         }
 
         match key {
+            key if (key == self.key_config.move_up || key == self.key_config.move_down)
+                && matches!(self.focus, Focus::Editor) =>
+            {
+                let entry = if key == self.key_config.move_up {
+                    self.history.previous(&input_str)
+                } else {
+                    self.history.next()
+                };
+                if let Some(entry) = entry {
+                    self.set_input(entry);
+                }
+                return Ok(EventState::Consumed);
+            }
             Key::Char(c) if matches!(self.focus, Focus::Editor) => {
                 self.input.insert(self.input_idx, c);
                 self.input_idx += 1;
-                self.input_cursor_position_x += compute_character_width(c);
                 self.update_completion();
 
                 return Ok(EventState::Consumed);
@@ -224,9 +1095,8 @@ impl Component for SqlEditorComponent {
             Key::Esc if matches!(self.focus, Focus::Editor) => self.focus = Focus::Table,
             Key::Delete | Key::Backspace if matches!(self.focus, Focus::Editor) => {
                 if input_str.width() > 0 && !self.input.is_empty() && self.input_idx > 0 {
-                    let last_c = self.input.remove(self.input_idx - 1);
+                    self.input.remove(self.input_idx - 1);
                     self.input_idx -= 1;
-                    self.input_cursor_position_x -= compute_character_width(last_c);
                     self.completion.update("");
                 }
 
@@ -235,47 +1105,166 @@ impl Component for SqlEditorComponent {
             Key::Left if matches!(self.focus, Focus::Editor) => {
                 if !self.input.is_empty() && self.input_idx > 0 {
                     self.input_idx -= 1;
-                    self.input_cursor_position_x = self
-                        .input_cursor_position_x
-                        .saturating_sub(compute_character_width(self.input[self.input_idx]));
                     self.completion.update("");
                 }
                 return Ok(EventState::Consumed);
             }
             Key::Right if matches!(self.focus, Focus::Editor) => {
                 if self.input_idx < self.input.len() {
-                    let next_c = self.input[self.input_idx];
                     self.input_idx += 1;
-                    self.input_cursor_position_x += compute_character_width(next_c);
                     self.completion.update("");
                 }
                 return Ok(EventState::Consumed);
             }
-            key if matches!(self.focus, Focus::Table) => return self.table.event(key),
+            key if matches!(self.focus, Focus::Table) => {
+                let state = self.table.event(key)?;
+                self.load_spilled()?;
+                return Ok(state);
+            }
             _ => (),
         }
         Ok(EventState::NotConsumed)
     }
 
     async fn async_event(&mut self, key: Key, pool: &Box<dyn Pool>) -> Result<EventState> {
-        if key == self.key_config.enter && matches!(self.focus, Focus::Editor) {
-            let query = self.input.iter().collect();
-            let result = pool.execute(&query).await?;
+        if key == self.key_config.commit || key == self.key_config.rollback {
+            let control = if key == self.key_config.commit {
+                TransactionControl::Commit
+            } else {
+                TransactionControl::Rollback
+            };
+            self.control_transaction(control, pool.as_ref()).await?;
+            return Ok(EventState::Consumed);
+        }
+        let run_statement = key == self.key_config.run_statement;
+        if (key == self.key_config.enter || run_statement) && matches!(self.focus, Focus::Editor) {
+            let confirmed = self.confirm.take_confirmed().is_some();
+            let submitted_values = self.parameter_form.take_values();
+            let pending_query = self.pending_query.take();
+            let query = match pending_query.filter(|_| confirmed || submitted_values.is_some()) {
+                Some(query) => query,
+                None if run_statement => match statement_at(&self.query(), self.input_idx) {
+                    Some(statement) => statement,
+                    None => return Ok(EventState::Consumed),
+                },
+                None => self.query(),
+            };
+            if let Some(control) = transaction_control(&query) {
+                self.control_transaction(control, pool.as_ref()).await?;
+                return Ok(EventState::Consumed);
+            }
+            let statements = split_statements(&query);
+            if !confirmed && submitted_values.is_none() {
+                if let Some((statement, reason)) = self.destructive_statement(&statements) {
+                    let message = if statements.len() > 1 {
+                        format!("A statement of the script {}. Run the script?", reason)
+                    } else {
+                        format!("This statement {}. Run it?", reason)
+                    };
+                    self.confirm.open(message, statement.to_string());
+                    self.pending_query = Some(query);
+                    return Ok(EventState::Consumed);
+                }
+            }
+            let values: Option<Vec<Value>> = match submitted_values {
+                Some(values) => Some(values.iter().map(String::as_str).map(parse_value).collect()),
+                None if statements.len() == 1 => {
+                    let params = QueryParams::parse(&query, self.dialect);
+                    if !params.is_empty() {
+                        self.parameter_form.open(params.names().to_vec());
+                        self.pending_query = Some(query);
+                        return Ok(EventState::Consumed);
+                    }
+                    None
+                }
+                None => None,
+            };
+            self.submitted = Some(query.clone());
+            // a script which opens its own transaction is left to do so
+            if self.transaction_mode
+                && !self.in_transaction
+                && !statements
+                    .iter()
+                    .any(|statement| transaction_control(statement).is_some())
+            {
+                pool.begin().await?;
+                self.in_transaction = true;
+            }
+            self.spilled = None;
+            if statements.len() > 1 {
+                self.query_result = None;
+                self.result_set = None;
+                self.script = Some(ScriptProgressComponent::new(
+                    statements,
+                    self.stop_on_error,
+                    self.key_config.clone(),
+                ));
+                return Ok(EventState::Consumed);
+            }
+            self.script = None;
+            self.shown_result = None;
+            self.limit = None;
+            let query = match self
+                .auto_limit
+                .and_then(|limit| Some((with_limit(statements.first()?, limit)?, limit)))
+            {
+                Some((limited, limit)) => {
+                    self.limit = Some(limit);
+                    limited
+                }
+                None => query,
+            };
+            self.started_at = Some(Instant::now());
+            // the values of placeholders are only bound by `execute_params`
+            let stream = match values {
+                Some(_) => None,
+                None => pool.stream(&query),
+            };
+            if let Some(stream) = stream {
+                let (database, table) = result_table();
+                self.table
+                    .update(Vec::new(), None, Vec::new(), database, table, false);
+                self.table
+                    .set_warning(Some("fetching the first rows".to_string()));
+                self.stream = Some(stream);
+                self.streamed = Some(query);
+                self.focus = Focus::Table;
+                self.query_result = None;
+                return Ok(EventState::Consumed);
+            }
+            self.stream = None;
+            let result = match values {
+                Some(values) => pool.execute_params(&query, &values).await,
+                None => pool.execute(&query).await,
+            };
+            self.finish_query();
+            let result = result.map_err(|e| e.context(FailedStatement(query.clone())))?;
             match result {
                 ExecuteResult::Read {
                     headers,
                     rows,
                     database,
                     table,
+                    truncated,
                 } => {
-                    let count = Some(rows.len());
+                    let count = if truncated { None } else { Some(rows.len()) };
                     self.table
-                        .update(rows, count, headers, database, table, false);
+                        .update(rows, count, headers, database, *table, false);
+                    self.remember_result(query);
+                    if truncated {
+                        self.table.set_warning(Some(format!(
+                            "stopped at the memory budget, press {} to export all rows",
+                            self.key_config.export_to_file
+                        )));
+                    } else {
+                        self.compare_with_snapshot();
+                        self.show_limit();
+                    }
                     self.focus = Focus::Table;
                     self.query_result = None;
                 }
                 ExecuteResult::Write { updated_rows } => {
-                    self.query_result = Some(QueryResult { updated_rows })
+                    self.query_result = Some(QueryResult::Write { updated_rows })
                 }
             }
             return Ok(EventState::Consumed);
@@ -284,3 +1273,69 @@ impl Component for SqlEditorComponent {
         Ok(EventState::NotConsumed)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{result_table, Focus, SqlEditorComponent};
+    use crate::components::Component;
+    use crate::config::KeyConfig;
+    use crate::database::Value;
+
+    #[test]
+    fn test_sql_file() {
+        let path = std::env::temp_dir().join(format!("zhobo-editor-{}.sql", std::process::id()));
+        let mut editor = SqlEditorComponent::new(KeyConfig::default());
+        editor.set_input("SELECT 1;\n".to_string());
+        editor.save_file(path.clone()).unwrap();
+
+        let mut other = SqlEditorComponent::new(KeyConfig::default());
+        other.open_file(path.clone()).unwrap();
+        assert_eq!(other.query(), "SELECT 1;\n");
+        assert_eq!(other.file(), Some(path.as_path()));
+        assert!(other.open_file(path.with_extension("missing")).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_destructive_statement() {
+        let mut editor = SqlEditorComponent::new(KeyConfig::default());
+        let statements = vec!["SELECT 1".to_string(), "DROP TABLE a".to_string()];
+        assert_eq!(
+            editor.destructive_statement(&statements),
+            Some(("DROP TABLE a", "drops an object"))
+        );
+        editor.set_confirm_destructive(false);
+        assert_eq!(editor.destructive_statement(&statements), None);
+    }
+
+    #[test]
+    fn test_recent_results() {
+        let key_config = KeyConfig::default();
+        let mut editor = SqlEditorComponent::new(key_config.clone()).with_result_history(3);
+        for number in 0..5 {
+            let (database, table) = result_table();
+            editor.table.update(
+                vec![vec![Value::Int(number)]],
+                Some(1),
+                vec!["n".to_string()],
+                database,
+                table,
+                false,
+            );
+            editor.remember_result(format!("SELECT {}   AS n", number));
+        }
+        // the oldest results are forgotten
+        assert_eq!(editor.results.len(), 3);
+        assert_eq!(editor.results[0].query, "SELECT 2   AS n");
+        assert_eq!(editor.results[0].title(1), "1: SELECT 2 AS n (1 rows)");
+
+        editor.focus = Focus::Table;
+        editor.event(key_config.previous_result_set).unwrap();
+        editor.event(key_config.previous_result_set).unwrap();
+        editor.event(key_config.previous_result_set).unwrap();
+        assert_eq!(editor.shown_result, Some(0));
+        assert_eq!(editor.table.values(), &[vec![Value::Int(2)]]);
+        editor.event(key_config.next_result_set).unwrap();
+        assert_eq!(editor.table.values(), &[vec![Value::Int(3)]]);
+    }
+}
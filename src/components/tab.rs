@@ -1,22 +1,25 @@
-use super::{Component, DrawableComponent, EventState};
+use super::{Component, DrawableComponent, EventState, LineCommand};
 use crate::components::command::{self, CommandInfo};
 use crate::config::KeyConfig;
 use crate::event::Key;
 use anyhow::Result;
 use ratatui::{
-    layout::Rect,
+    layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
-    text::Line,
-    widgets::{Block, Borders, Tabs},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, Tabs},
     Frame,
 };
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-#[derive(Debug, Clone, Copy, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, Serialize, Deserialize)]
 pub enum Tab {
     Records,
     Properties,
     Sql,
+    Logs,
 }
 
 impl std::fmt::Display for Tab {
@@ -27,6 +30,8 @@ impl std::fmt::Display for Tab {
 
 pub struct TabComponent {
     pub selected_tab: Tab,
+    /// Shown at the right of the tabs, e.g. whether a transaction is open.
+    status: Option<&'static str>,
     key_config: KeyConfig,
 }
 
@@ -34,6 +39,7 @@ impl TabComponent {
     pub fn new(key_config: KeyConfig) -> Self {
         Self {
             selected_tab: Tab::Records,
+            status: None,
             key_config,
         }
     }
@@ -42,11 +48,16 @@ impl TabComponent {
         self.selected_tab = Tab::Records;
     }
 
+    pub fn set_status(&mut self, status: Option<&'static str>) {
+        self.status = status;
+    }
+
     fn names(&self) -> Vec<String> {
         vec![
             command::tab_records(&self.key_config).name,
             command::tab_properties(&self.key_config).name,
             command::tab_sql_editor(&self.key_config).name,
+            command::tab_logs(&self.key_config).name,
         ]
     }
 }
@@ -54,8 +65,18 @@ impl TabComponent {
 impl DrawableComponent for TabComponent {
     fn draw(&self, f: &mut Frame, area: Rect, _focused: bool) -> Result<()> {
         let titles: Vec<_> = self.names().iter().cloned().map(Line::from).collect();
+        let mut block = Block::default().borders(Borders::ALL);
+        if let Some(status) = self.status {
+            block = block.title(
+                Title::from(Span::styled(
+                    format!(" {} ", status),
+                    Style::default().fg(Color::Black).bg(Color::Yellow),
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
         let tabs = Tabs::new(titles)
-            .block(Block::default().borders(Borders::ALL))
+            .block(block)
             .select(self.selected_tab as usize)
             .style(Style::default().fg(Color::DarkGray))
             .highlight_style(
@@ -81,7 +102,32 @@ impl Component for TabComponent {
         } else if key == self.key_config.tab_properties {
             self.selected_tab = Tab::Properties;
             return Ok(EventState::Consumed);
+        } else if key == self.key_config.tab_logs {
+            self.selected_tab = Tab::Logs;
+            return Ok(EventState::Consumed);
         }
         Ok(EventState::NotConsumed)
     }
+
+    fn line_commands(&self, out: &mut Vec<LineCommand>) {
+        out.push(LineCommand::new(
+            "tab",
+            "records|properties|sql|logs",
+            "Switch to a tab",
+        ));
+    }
+
+    fn run_line_command(&mut self, name: &str, args: &[&str]) -> Result<EventState> {
+        if name != "tab" {
+            return Ok(EventState::NotConsumed);
+        }
+        match args
+            .first()
+            .and_then(|arg| Tab::iter().find(|tab| tab.to_string().eq_ignore_ascii_case(arg)))
+        {
+            Some(tab) => self.selected_tab = tab,
+            None => anyhow::bail!("usage: tab records|properties|sql|logs"),
+        }
+        Ok(EventState::Consumed)
+    }
 }
@@ -1,21 +1,29 @@
+use super::result_diff::RowChange;
 use super::PropertyTrait;
 use super::{
-    utils::scroll_vertical::VerticalScroll, Component, DrawableComponent, EventState,
-    StatefulDrawableComponent, TableStatusComponent, TableValueComponent,
+    utils::scroll_vertical::VerticalScroll, CellViewerComponent, Component, DrawableComponent,
+    EventState, LineCommand, StatefulDrawableComponent, TableStatusComponent, TableValueComponent,
 };
 use crate::components::command::{self, CommandInfo};
 use crate::config::KeyConfig;
+use crate::database::{row_size, ColumnFormat, Value, ValueFormat};
 use crate::event::Key;
 use crate::tree::{Database, Table as DTable};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use ratatui::layout::Flex;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Row, Table, TableState},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
     Frame,
 };
+use rust_decimal::Decimal;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::convert::From;
+use std::ops::{Range, RangeInclusive};
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, PartialEq)]
@@ -70,19 +78,43 @@ impl OrderManager {
         None
     }
 
+    /// Returns the direction of the sort by each column, followed by its priority if the rows
+    /// are sorted by more than one column.
     fn generate_header_icons(&mut self, header_length: usize) -> Vec<String> {
         let mut header_icons = vec![String::new(); header_length];
+        let numbered = self.orders.len() > 1;
         for (index, order) in self.orders.iter().enumerate() {
             let arrow = if order.is_asc { "↑" } else { "↓" };
             if header_icons.len() > order.column_number - 1 {
-                header_icons[order.column_number - 1] =
-                    format!("{arrow}{number}", arrow = arrow, number = index + 1);
+                header_icons[order.column_number - 1] = if numbered {
+                    format!("{arrow}{number}", arrow = arrow, number = index + 1)
+                } else {
+                    arrow.to_string()
+                };
             }
         }
 
         header_icons
     }
 
+    /// Returns the index of the column the rows are sorted by first and whether it is ascending.
+    fn first_order(&self) -> Option<(usize, bool)> {
+        self.orders
+            .first()
+            .map(|order| (order.column_number - 1, order.is_asc))
+    }
+
+    /// Sorts by the selected column alone, or turns it around and then removes it if it is the
+    /// only column the rows are sorted by.
+    fn set_order(&mut self, selected_column: usize) {
+        let selected_column_number = selected_column + 1;
+        if !matches!(self.orders.as_slice(), [order] if order.column_number == selected_column_number)
+        {
+            self.orders.clear();
+        }
+        self.add_order(selected_column);
+    }
+
     fn add_order(&mut self, selected_column: usize) {
         let selected_column_number = selected_column + 1;
         if let Some(position) = self
@@ -102,9 +134,29 @@ impl OrderManager {
     }
 }
 
+/// A search for a text in the loaded rows, unlike the filter which the server runs.
+#[derive(Debug, Default)]
+struct TableSearch {
+    input: String,
+    /// Whether the text is still being typed.
+    typing: bool,
+    /// The matching cells as `(row, column)`, in the order of the rows.
+    matches: Vec<(usize, usize)>,
+}
+
+/// The position of the cursor, which is restored when the table is opened again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TableCursor {
+    row: Option<usize>,
+    column: usize,
+    offset: usize,
+    column_page_start: usize,
+}
+
 pub struct TableComponent {
     pub headers: Vec<String>,
-    pub rows: Vec<Vec<String>>,
+    /// The loaded rows, which are only turned into text for the cells on the screen.
+    values: Vec<Vec<Value>>,
     pub total_row_count: Option<usize>,
     pub eod: bool,
     pub selected_row: TableState,
@@ -113,7 +165,40 @@ pub struct TableComponent {
     selected_column: usize,
     selection_area_corner: Option<(usize, usize)>,
     column_page_start: std::cell::Cell<usize>,
+    /// How many columns from the left stay on the screen while the others scroll. A draw
+    /// pins fewer if they would take more than half of the width.
+    pinned_columns: std::cell::Cell<usize>,
+    /// The columns which the last draw showed whole, as `(first, last + 1)`.
+    visible_columns: std::cell::Cell<(usize, usize)>,
+    /// The width of each column, measured when it is first shown, so that a table with
+    /// thousands of columns only measures the ones on the screen.
+    column_widths: std::cell::RefCell<Vec<Option<usize>>>,
+    /// The first and the last row with a value in the sorted column, as `(column, range)`,
+    /// which are found once per update rather than on every draw.
+    sort_range: std::cell::Cell<Option<(usize, Option<(usize, usize)>)>>,
+    /// The widths set with the keys, by the name of the column, which replace the measured
+    /// ones.
+    column_width_overrides: HashMap<String, usize>,
     scroll: VerticalScroll,
+    column_formats: Vec<Option<ColumnFormat>>,
+    /// The shown column the rows are sorted by first and whether it is ascending.
+    sorted_column: Option<(usize, bool)>,
+    loaded_size: usize,
+    warning: Option<String>,
+    /// How many rows are fetched at once, shown in the title so that it is clear why not all
+    /// rows are there.
+    page_size: Option<usize>,
+    cell_viewer: CellViewerComponent,
+    /// The rows whose numbers are highlighted, e.g. bookmarked rows.
+    marked_rows: HashSet<usize>,
+    /// How the rows differ from a snapshot of an earlier result, when comparing with one.
+    row_changes: HashMap<usize, RowChange>,
+    search: Option<TableSearch>,
+    /// Whether the selected row is shown as a list of its columns instead of the rows.
+    record_view: bool,
+    /// How many lines the view of the row is scrolled down.
+    record_scroll: std::cell::Cell<usize>,
+    value_format: ValueFormat,
     key_config: KeyConfig,
 }
 
@@ -122,28 +207,82 @@ impl TableComponent {
         Self {
             selected_row: TableState::default(),
             headers: vec![],
-            rows: vec![],
+            values: vec![],
             total_row_count: None,
             orders: OrderManager::new(),
             table: None,
             selected_column: 0,
             selection_area_corner: None,
             column_page_start: std::cell::Cell::new(0),
+            pinned_columns: std::cell::Cell::new(0),
+            visible_columns: std::cell::Cell::new((0, 0)),
+            column_widths: std::cell::RefCell::new(Vec::new()),
+            sort_range: std::cell::Cell::new(None),
+            column_width_overrides: HashMap::new(),
             scroll: VerticalScroll::new(false, false),
             eod: false,
+            column_formats: Vec::new(),
+            sorted_column: None,
+            loaded_size: 0,
+            warning: None,
+            page_size: None,
+            cell_viewer: CellViewerComponent::new(key_config.clone()),
+            marked_rows: HashSet::new(),
+            row_changes: HashMap::new(),
+            search: None,
+            record_view: false,
+            record_scroll: std::cell::Cell::new(0),
+            value_format: ValueFormat::default(),
             key_config,
         }
     }
 
+    /// Shows where the visible rows are in the range of the sorted column on the bottom border.
+    fn draw_sort_marker(&self, f: &mut Frame, area: Rect, rows_height: u16) {
+        let marker = self.sorted_column.and_then(|(column, is_asc)| {
+            let range = match self.sort_range.get() {
+                Some((cached, range)) if cached == column => range,
+                _ => {
+                    let range = value_range(&self.values, column);
+                    self.sort_range.set(Some((column, range)));
+                    range
+                }
+            }?;
+            // a row takes a line and a margin below the header
+            let top = self.scroll.get_top();
+            let visible = top..(top + (rows_height.saturating_sub(2) / 2).max(1) as usize);
+            let marker = sort_marker(&self.values, column, range, visible, is_asc, self.eod);
+            Some(format!(" {}: {} ", self.headers.get(column)?, marker))
+        });
+        if let Some(marker) = marker {
+            let width = marker.width() as u16;
+            if width + 4 <= area.width && area.height > 0 {
+                f.render_widget(
+                    Paragraph::new(marker),
+                    Rect::new(area.right() - width - 2, area.bottom() - 1, width, 1),
+                );
+            }
+        }
+    }
+
     fn title(&self) -> String {
-        self.table.as_ref().map_or(" - ".to_string(), |table| {
-            format!("{}.{}", table.0.name, table.1.name)
-        })
+        self.table
+            .as_ref()
+            .map_or(" - ".to_string(), |table| match self.page_size {
+                Some(page_size) => {
+                    format!("{}.{} [limit {}]", table.0.name, table.1.name, page_size)
+                }
+                None => format!("{}.{}", table.0.name, table.1.name),
+            })
+    }
+
+    pub fn set_page_size(&mut self, page_size: Option<usize>) {
+        self.page_size = page_size;
     }
 
     pub fn update(
         &mut self,
-        rows: Vec<Vec<String>>,
+        rows: Vec<Vec<Value>>,
         total_row_count: Option<usize>,
         headers: Vec<String>,
         database: Database,
@@ -155,30 +294,202 @@ impl TableComponent {
             self.selected_row.select(Some(0))
         }
         self.headers = headers;
-        self.rows = rows;
+        self.loaded_size = rows.iter().map(|row| row_size(row)).sum();
+        self.values = rows;
+        self.column_formats = column_formats(&self.values, &self.value_format);
+        self.warning = None;
         self.total_row_count = total_row_count;
         self.selected_column = if hold_cursor_position {
             self.selected_column
         } else {
+            self.pinned_columns.set(0);
+            self.column_width_overrides.clear();
             0
         };
         self.selection_area_corner = None;
         self.column_page_start = std::cell::Cell::new(0);
+        self.visible_columns = std::cell::Cell::new((0, 0));
+        self.column_widths.get_mut().clear();
+        self.sort_range.set(None);
         self.scroll = VerticalScroll::new(false, false);
         self.eod = false;
+        self.marked_rows.clear();
+        self.row_changes.clear();
         self.table = Some((database, table));
+        self.update_matches();
+    }
+
+    pub fn cursor(&self) -> TableCursor {
+        TableCursor {
+            row: self.selected_row.selected(),
+            column: self.selected_column,
+            offset: self.selected_row.offset(),
+            column_page_start: self.column_page_start.get(),
+        }
+    }
+
+    /// Moves the cursor back to `cursor` as far as the loaded rows and columns allow.
+    pub fn restore_cursor(&mut self, cursor: TableCursor) {
+        if self.values.is_empty() {
+            return;
+        }
+        let last_row = self.values.len().saturating_sub(1);
+        let last_column = self.headers.len().saturating_sub(1);
+        self.selected_row
+            .select(cursor.row.map(|row| row.min(last_row)));
+        *self.selected_row.offset_mut() = cursor.offset.min(last_row);
+        self.selected_column = cursor.column.min(last_column);
+        self.column_page_start
+            .set(cursor.column_page_start.min(self.selected_column));
+    }
+
+    /// Appends the next page of rows.
+    pub fn extend(&mut self, rows: Vec<Vec<Value>>) {
+        self.loaded_size += rows.iter().map(|row| row_size(row)).sum::<usize>();
+        self.values.extend(rows);
+        self.column_formats = column_formats(&self.values, &self.value_format);
+        self.column_widths.get_mut().clear();
+        self.sort_range.set(None);
+        self.update_matches();
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the estimated number of bytes the loaded rows take.
+    pub fn loaded_size(&self) -> usize {
+        self.loaded_size
+    }
+
+    /// Shows `warning` in the status bar until the next update.
+    pub fn set_warning(&mut self, warning: Option<String>) {
+        self.warning = warning;
+    }
+
+    /// Returns the typed value of the selected cell.
+    fn selected_value(&self) -> Option<&Value> {
+        self.values
+            .get(self.selected_row.selected()?)?
+            .get(self.selected_column)
+    }
+
+    /// Returns the text of a cell, lined up with the other cells of its column.
+    fn cell(&self, row_index: usize, column_index: usize) -> String {
+        match (
+            self.values
+                .get(row_index)
+                .and_then(|row| row.get(column_index)),
+            self.column_formats.get(column_index),
+        ) {
+            (Some(value), Some(Some(format))) => format.format(value, &self.value_format),
+            _ => self.display(row_index, column_index),
+        }
+    }
+
+    /// Returns the text of a cell as the `[display]` config shows its value.
+    fn display(&self, row_index: usize, column_index: usize) -> String {
+        self.values
+            .get(row_index)
+            .and_then(|row| row.get(column_index))
+            .map_or(String::new(), |value| self.value_format.display(value))
+    }
+
+    /// Returns the text of a cell as the grid draws it, with its control characters made
+    /// visible.
+    fn shown_cell(&self, row_index: usize, column_index: usize) -> String {
+        printable(&self.cell(row_index, column_index)).into_owned()
+    }
+
+    pub fn value_format(&self) -> &ValueFormat {
+        &self.value_format
+    }
+
+    pub fn set_value_format(&mut self, value_format: ValueFormat) {
+        self.value_format = value_format;
+        self.column_formats = column_formats(&self.values, &self.value_format);
+        self.column_widths.get_mut().clear();
+    }
+
+    /// Returns true if the cell in the current column page is a number.
+    fn is_numeric_cell(&self, column_index: usize) -> bool {
+        matches!(
+            self.column_at(column_index)
+                .and_then(|column| self.column_formats.get(column)),
+            Some(Some(ColumnFormat::Numeric { .. }))
+        )
+    }
+
+    /// Returns true if the cell in the current column page is NULL.
+    fn is_null_cell(&self, row_index: usize, column_index: usize) -> bool {
+        matches!(
+            self.values
+                .get(row_index)
+                .zip(self.column_at(column_index))
+                .and_then(|(row, column)| row.get(column)),
+            Some(value) if value.is_null()
+        )
+    }
+
+    /// Returns the column of the rows which is shown at `column_index` on the screen, after
+    /// the row numbers and the pinned columns.
+    fn column_at(&self, column_index: usize) -> Option<usize> {
+        let pinned = self.pinned_columns.get();
+        match column_index {
+            0 => None,
+            index if index <= pinned => Some(index - 1),
+            index => Some(self.column_page_start.get() + index - 1 - pinned),
+        }
+    }
+
+    /// Returns where a column of the rows is shown on the screen, or `None` if it is scrolled
+    /// out on the left.
+    fn display_index(&self, column: usize) -> Option<usize> {
+        let pinned = self.pinned_columns.get();
+        if column < pinned {
+            Some(column + 1)
+        } else if column >= self.column_page_start.get() {
+            Some(column - self.column_page_start.get() + pinned + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Pins the columns up to the selected one so that they stay on the screen, or unpins
+    /// them. One column at least is left to scroll.
+    fn toggle_pinned_columns(&mut self) {
+        if self.pinned_columns.get() > 0 {
+            self.pinned_columns.set(0);
+        } else {
+            self.pinned_columns
+                .set((self.selected_column + 1).min(self.headers.len().saturating_sub(1)));
+        }
+    }
+
+    pub fn table(&self) -> Option<&(Database, DTable)> {
+        self.table.as_ref()
     }
 
     pub fn reset(&mut self) {
         self.selected_row.select(None);
         self.headers = Vec::new();
-        self.rows = Vec::new();
+        self.values = Vec::new();
+        self.column_formats = Vec::new();
+        self.sorted_column = None;
+        self.loaded_size = 0;
+        self.warning = None;
         self.orders = OrderManager::new();
         self.selected_column = 0;
         self.selection_area_corner = None;
         self.column_page_start = std::cell::Cell::new(0);
+        self.pinned_columns = std::cell::Cell::new(0);
+        self.column_width_overrides.clear();
+        self.visible_columns = std::cell::Cell::new((0, 0));
+        self.column_widths.get_mut().clear();
+        self.sort_range.set(None);
         self.scroll = VerticalScroll::new(false, false);
         self.eod = false;
+        self.search = None;
         self.table = None;
     }
 
@@ -186,8 +497,45 @@ impl TableComponent {
         self.selection_area_corner = None;
     }
 
-    pub fn add_order(&mut self) {
-        self.orders.add_order(self.selected_column)
+    /// Toggles the order of `column`, the index of the column in the query.
+    pub fn add_order(&mut self, column: usize) {
+        self.orders.add_order(column)
+    }
+
+    pub fn set_order(&mut self, column: usize) {
+        self.orders.set_order(column)
+    }
+
+    /// Returns the columns the rows are sorted by, as indexes of the columns in the query and
+    /// whether they are ascending, to sort by them again with `set_orders`.
+    pub fn orders(&self) -> Vec<(usize, bool)> {
+        self.orders
+            .orders
+            .iter()
+            .map(|order| (order.column_number - 1, order.is_asc))
+            .collect()
+    }
+
+    pub fn set_orders(&mut self, orders: &[(usize, bool)]) {
+        self.orders.orders = orders
+            .iter()
+            .map(|&(column, is_asc)| Order::new(column + 1, is_asc))
+            .collect();
+    }
+
+    pub fn selected_column(&self) -> usize {
+        self.selected_column
+    }
+
+    /// Returns the index of the column in the query the rows are sorted by first and whether
+    /// it is ascending.
+    pub fn first_order(&self) -> Option<(usize, bool)> {
+        self.orders.first_order()
+    }
+
+    /// Sets the shown column whose range is marked next to the status bar.
+    pub fn set_sorted_column(&mut self, sorted_column: Option<(usize, bool)>) {
+        self.sorted_column = sorted_column;
     }
 
     pub fn generate_order_query(&mut self) -> Option<String> {
@@ -214,8 +562,8 @@ impl TableComponent {
     fn next_row(&mut self, lines: usize) {
         let i = match self.selected_row.selected() {
             Some(i) => {
-                if i + lines >= self.rows.len() {
-                    Some(self.rows.len().saturating_sub(1))
+                if i + lines >= self.values.len() {
+                    Some(self.values.len().saturating_sub(1))
                 } else {
                     Some(i + lines)
                 }
@@ -242,7 +590,7 @@ impl TableComponent {
     }
 
     fn scroll_to_top(&mut self) {
-        if self.rows.is_empty() {
+        if self.values.is_empty() {
             return;
         }
         self.reset_selection();
@@ -250,16 +598,16 @@ impl TableComponent {
     }
 
     fn scroll_to_bottom(&mut self) {
-        if self.rows.is_empty() {
+        if self.values.is_empty() {
             return;
         }
         self.reset_selection();
         self.selected_row
-            .select(Some(self.rows.len().saturating_sub(1)));
+            .select(Some(self.values.len().saturating_sub(1)));
     }
 
     fn next_column(&mut self) {
-        if self.rows.is_empty() {
+        if self.values.is_empty() {
             return;
         }
         self.reset_selection();
@@ -270,7 +618,7 @@ impl TableComponent {
     }
 
     fn previous_column(&mut self) {
-        if self.rows.is_empty() {
+        if self.values.is_empty() {
             return;
         }
         self.reset_selection();
@@ -280,6 +628,34 @@ impl TableComponent {
         self.selected_column -= 1;
     }
 
+    /// Moves to the columns right of the ones on the screen.
+    fn next_column_page(&mut self) {
+        if self.values.is_empty() {
+            return;
+        }
+        self.reset_selection();
+        let (left, right) = self.visible_columns.get();
+        let start = right
+            .max(left + 1)
+            .min(self.headers.len().saturating_sub(1));
+        self.selected_column = start;
+        self.column_page_start.set(start);
+    }
+
+    /// Moves to about as many columns left of the ones on the screen.
+    fn previous_column_page(&mut self) {
+        if self.values.is_empty() {
+            return;
+        }
+        self.reset_selection();
+        let (left, right) = self.visible_columns.get();
+        let start = left
+            .saturating_sub(right.saturating_sub(left).max(1))
+            .max(self.pinned_columns.get());
+        self.selected_column = start;
+        self.column_page_start.set(start);
+    }
+
     fn expand_selected_area_x(&mut self, positive: bool) {
         if self.selection_area_corner.is_none() {
             self.selection_area_corner = Some((
@@ -310,7 +686,7 @@ impl TableComponent {
             self.selection_area_corner = Some((
                 x,
                 if positive {
-                    (y + 1).min(self.rows.len().saturating_sub(1))
+                    (y + 1).min(self.values.len().saturating_sub(1))
                 } else {
                     y.saturating_sub(1)
                 },
@@ -337,19 +713,100 @@ impl TableComponent {
         if let Some((x, y)) = self.selection_area_corner {
             let selected_row_index = self.selected_row.selected()?;
             return Some(
-                self.rows[y.min(selected_row_index)..y.max(selected_row_index) + 1]
+                self.values[y.min(selected_row_index)..y.max(selected_row_index) + 1]
                     .iter()
                     .map(|row| {
-                        row[x.min(self.selected_column)..x.max(self.selected_column) + 1].join(",")
+                        row[x.min(self.selected_column)..x.max(self.selected_column) + 1]
+                            .iter()
+                            .map(Value::to_string)
+                            .collect::<Vec<String>>()
+                            .join(",")
                     })
                     .collect::<Vec<String>>()
                     .join("\n"),
             );
         }
-        self.rows
-            .get(self.selected_row.selected()?)?
-            .get(self.selected_column)
-            .map(|cell| cell.to_string())
+        self.selected_value().map(Value::to_string)
+    }
+
+    /// Returns the indexes of the selected rows, and of the selected columns if an area is
+    /// selected rather than a single cell.
+    pub fn selected_area(&self) -> Option<(RangeInclusive<usize>, Option<RangeInclusive<usize>>)> {
+        let row = self.selected_row.selected()?;
+        Some(match self.selection_area_corner {
+            Some((x, y)) => (
+                y.min(row)..=y.max(row),
+                Some(x.min(self.selected_column)..=x.max(self.selected_column)),
+            ),
+            None => (row..=row, None),
+        })
+    }
+
+    /// Returns the headers and the values of the selected area as they are shown, i.e. without
+    /// the hidden columns and in the order of the sort, or `None` if no area is selected.
+    pub fn selected_area_values(&self) -> Option<(Vec<String>, Vec<Vec<Value>>)> {
+        let (rows, columns) = self.selected_area()?;
+        let columns = columns?;
+        let headers = self
+            .headers
+            .get(columns.clone())?
+            .iter()
+            .map(|header| column_name(header).to_string())
+            .collect();
+        let values = self
+            .values
+            .get(rows)?
+            .iter()
+            .map(|row| {
+                row.get(columns.clone())
+                    .map_or_else(Vec::new, <[Value]>::to_vec)
+            })
+            .collect();
+        Some((headers, values))
+    }
+
+    /// Summarizes the numbers of the selected area, or returns `None` if no area is selected.
+    pub fn selection_summary(&self) -> Option<String> {
+        let (rows, columns) = self.selected_area()?;
+        let columns = columns?;
+        let values = self.values.get(rows)?;
+        Some(summarize(values.iter().flat_map(|row| {
+            row.get(columns.clone()).unwrap_or_default()
+        })))
+    }
+
+    /// Returns the number of cells `content` holds.
+    pub fn selected_cell_count(&self) -> usize {
+        match (self.selection_area_corner, self.selected_row.selected()) {
+            (Some((x, y)), Some(row)) => {
+                (x.abs_diff(self.selected_column) + 1) * (y.abs_diff(row) + 1)
+            }
+            (None, Some(_)) => 1,
+            _ => 0,
+        }
+    }
+
+    /// Describes the selected cell in words for screen readers, e.g.
+    /// `row 3 of 120, name: alice`.
+    pub fn describe(&self) -> String {
+        let row = match self.selected_row.selected() {
+            Some(row) => row,
+            None => return "no rows".to_string(),
+        };
+        let mut description = format!(
+            "row {} of {}, {}: {}",
+            row + 1,
+            self.total_row_count.unwrap_or(self.values.len()),
+            self.headers
+                .get(self.selected_column)
+                .map_or("", String::as_str),
+            self.selected_value()
+                .map_or(String::new(), Value::to_string),
+        );
+        if self.selection_area_corner.is_some() {
+            description.push_str(&format!(", {} cells selected", self.selected_cell_count()));
+        }
+        description
     }
 
     fn selected_column_index(&self) -> usize {
@@ -366,14 +823,11 @@ impl TableComponent {
         selected_column_index: usize,
     ) -> bool {
         if let Some((x, y)) = self.selection_area_corner {
-            let x_in_page = x
-                .saturating_add(1)
-                .saturating_sub(self.column_page_start.get());
             return matches!(
-                self.selected_row.selected(),
-                Some(selected_row_index)
-                if (x_in_page.min(selected_column_index).max(1)..x_in_page.max(selected_column_index) + 1)
-                    .contains(&column_index)
+                (self.selected_row.selected(), self.column_at(column_index)),
+                (Some(selected_row_index), Some(column))
+                if (x.min(self.selected_column)..x.max(self.selected_column) + 1)
+                    .contains(&column)
                     && (y.min(selected_row_index)..y.max(selected_row_index) + 1)
                         .contains(&row_index)
             );
@@ -384,6 +838,211 @@ impl TableComponent {
         )
     }
 
+    /// Returns the values of the loaded rows.
+    pub fn values(&self) -> &[Vec<Value>] {
+        &self.values
+    }
+
+    pub fn set_row_changes(&mut self, changes: HashMap<usize, RowChange>) {
+        self.row_changes = changes;
+    }
+
+    /// Returns the color of a cell of an added row or a changed cell.
+    fn change_color(&self, row_index: usize, column_index: usize) -> Option<Color> {
+        match self.row_changes.get(&row_index)? {
+            RowChange::Added => Some(Color::Green),
+            RowChange::Changed(_) if column_index == 0 => Some(Color::Yellow),
+            RowChange::Changed(columns) => self
+                .column_at(column_index)
+                .filter(|column| columns.contains(column))
+                .map(|_| Color::Yellow),
+        }
+    }
+
+    /// Returns the column named `name`, ignoring the case, or else the only column whose name
+    /// starts with it.
+    fn find_column(&self, name: &str) -> Option<usize> {
+        let name = name.to_lowercase();
+        if let Some(index) = self
+            .headers
+            .iter()
+            .position(|header| column_name(header).to_lowercase() == name)
+        {
+            return Some(index);
+        }
+        let mut columns = self
+            .headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| column_name(header).to_lowercase().starts_with(&name));
+        match (columns.next(), columns.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    }
+
+    pub fn column_width_overrides(&self) -> &HashMap<String, usize> {
+        &self.column_width_overrides
+    }
+
+    pub fn set_column_width_overrides(&mut self, overrides: HashMap<String, usize>) {
+        self.column_width_overrides = overrides;
+    }
+
+    /// Makes the selected column wider or narrower by `delta` characters.
+    fn resize_column(&mut self, delta: isize) {
+        if let Some(width) = self.column_width(self.selected_column) {
+            let width = width
+                .saturating_add_signed(delta)
+                .clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+            self.column_width_overrides.insert(
+                column_name(&self.headers[self.selected_column]).to_string(),
+                width,
+            );
+        }
+    }
+
+    /// Fits the selected column to its longest loaded value, or measures it again as usual
+    /// if it already fits.
+    fn fit_column(&mut self) {
+        let header = match self.headers.get(self.selected_column) {
+            Some(header) => column_name(header).to_string(),
+            None => return,
+        };
+        let width = (0..self.values.len())
+            .map(|row_index| display_width(&self.shown_cell(row_index, self.selected_column)))
+            .chain([header.width()])
+            .max()
+            .unwrap_or_default()
+            .clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+        if self.column_width_overrides.get(&header) == Some(&width) {
+            self.column_width_overrides.remove(&header);
+        } else {
+            self.column_width_overrides.insert(header, width);
+        }
+    }
+
+    /// Returns true while the text to search for is typed, which takes all the keys.
+    pub fn is_searching(&self) -> bool {
+        self.search.as_ref().is_some_and(|search| search.typing)
+    }
+
+    pub fn is_viewing_cell(&self) -> bool {
+        self.cell_viewer.is_visible()
+    }
+
+    fn update_matches(&mut self) {
+        if let Some(search) = self.search.as_mut() {
+            search.matches = find_matches(&self.values, &search.input);
+        }
+    }
+
+    fn is_match(&self, row_index: usize, column_index: usize) -> bool {
+        match (self.search.as_ref(), self.column_at(column_index)) {
+            (Some(search), Some(column)) => {
+                search.matches.binary_search(&(row_index, column)).is_ok()
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves the cursor to the first match after it, or the last one before it, wrapping
+    /// around the loaded rows.
+    fn jump_to_match(&mut self, forward: bool) {
+        let cursor = (
+            self.selected_row.selected().unwrap_or_default(),
+            self.selected_column,
+        );
+        let found = match self.search.as_ref() {
+            Some(search) if forward => search
+                .matches
+                .iter()
+                .find(|cell| **cell > cursor)
+                .or_else(|| search.matches.first()),
+            Some(search) => search
+                .matches
+                .iter()
+                .rev()
+                .find(|cell| **cell < cursor)
+                .or_else(|| search.matches.last()),
+            None => None,
+        };
+        if let Some(&(row, column)) = found {
+            self.reset_selection();
+            self.selected_row.select(Some(row));
+            self.selected_column = column;
+        }
+    }
+
+    /// Shows the text searched for and which of the matches the cursor is on.
+    fn search_line(&self) -> Option<String> {
+        let search = self.search.as_ref().filter(|search| search.typing)?;
+        let cursor = (
+            self.selected_row.selected().unwrap_or_default(),
+            self.selected_column,
+        );
+        Some(match search.matches.binary_search(&cursor) {
+            Ok(index) => format!(
+                "Search: {} ({} of {})",
+                search.input,
+                index + 1,
+                search.matches.len()
+            ),
+            Err(_) => format!(
+                "Search: {} ({} matches)",
+                search.input,
+                search.matches.len()
+            ),
+        })
+    }
+
+    fn search_event(&mut self, key: Key) -> EventState {
+        let search = match self.search.as_mut() {
+            Some(search) if search.typing => search,
+            _ => return EventState::NotConsumed,
+        };
+        match key {
+            key if key == self.key_config.enter => {
+                search.typing = false;
+                if search.input.is_empty() {
+                    self.search = None;
+                } else {
+                    let cursor = (
+                        self.selected_row.selected().unwrap_or_default(),
+                        self.selected_column,
+                    );
+                    if search.matches.binary_search(&cursor).is_err() {
+                        self.jump_to_match(true);
+                    }
+                }
+                return EventState::Consumed;
+            }
+            key if key == self.key_config.exit_popup => {
+                self.search = None;
+                return EventState::Consumed;
+            }
+            Key::Char(c) => search.input.push(c),
+            Key::Backspace => {
+                search.input.pop();
+            }
+            _ => return EventState::Consumed,
+        }
+        self.update_matches();
+        EventState::Consumed
+    }
+
+    pub fn set_marked_rows(&mut self, rows: HashSet<usize>) {
+        self.marked_rows = rows;
+    }
+
+    /// Moves the cursor to a loaded row.
+    pub fn select_row(&mut self, index: usize) {
+        if index < self.values.len() {
+            self.selection_area_corner = None;
+            self.selected_row.select(Some(index));
+        }
+    }
+
     fn is_number_column(&self, row_index: usize, column_index: usize) -> bool {
         matches!(
             self.selected_row.selected(),
@@ -392,61 +1051,85 @@ impl TableComponent {
     }
 
     fn headers(&self, left: usize, right: usize) -> Vec<String> {
-        let mut headers = self.headers.clone()[left..right].to_vec();
+        let mut headers = self.headers[left..right].to_vec();
         headers.insert(0, "".to_string());
         headers
     }
 
-    fn rows(&self, left: usize, right: usize) -> Vec<Vec<String>> {
-        let rows = self
-            .rows
-            .iter()
-            .map(|row| row.to_vec())
-            .collect::<Vec<Vec<String>>>();
-        let mut new_rows: Vec<Vec<String>> =
-            rows.iter().map(|row| row[left..right].to_vec()).collect();
-        for (index, row) in new_rows.iter_mut().enumerate() {
-            row.insert(0, (index + 1).to_string())
+    /// Returns the cells of the columns `left..right` of the rows in `visible`, each row
+    /// starting with its number.
+    fn rows(&self, left: usize, right: usize, visible: Range<usize>) -> Vec<Vec<String>> {
+        visible
+            .map(|row_index| {
+                std::iter::once((row_index + 1).to_string())
+                    .chain(
+                        (left..right).map(|column_index| self.shown_cell(row_index, column_index)),
+                    )
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the width of a column, or `None` if there is no such column.
+    fn column_width(&self, column_index: usize) -> Option<usize> {
+        let header = self.headers.get(column_index)?;
+        if let Some(width) = self.column_width_overrides.get(column_name(header)) {
+            return Some(*width);
+        }
+        let mut widths = self.column_widths.borrow_mut();
+        if widths.len() < self.headers.len() {
+            widths.resize(self.headers.len(), None);
         }
-        new_rows
+        Some(*widths[column_index].get_or_insert_with(|| {
+            (0..self.values.len())
+                .map(|row_index| display_width(&self.shown_cell(row_index, column_index)))
+                .max()
+                .map_or(MIN_COLUMN_WIDTH, |v| {
+                    v.max(header.width()).clamp(MIN_COLUMN_WIDTH, 20)
+                })
+        }))
     }
 
+    /// Lays out the columns which fit into `area_width` and returns their cells for the rows in
+    /// `visible` only, so that a draw does not format the rows which are not on the screen.
     fn calculate_cell_widths(
         &self,
         area_width: u16,
+        visible: Range<usize>,
     ) -> (usize, Vec<String>, Vec<Vec<String>>, Vec<Constraint>) {
-        if self.rows.is_empty() {
+        if self.values.is_empty() {
             return (0, Vec::new(), Vec::new(), Vec::new());
         }
-        if self.selected_column_index() < self.column_page_start.get() {
-            self.column_page_start.set(self.selected_column_index());
+        let number_column_width = (self.values.len() + 1).to_string().width() as u16;
+
+        // the pinned columns are laid out first, and the others scroll in the rest of the width
+        let mut pinned = self
+            .pinned_columns
+            .get()
+            .min(self.headers.len().saturating_sub(1));
+        let pinned_width = |pinned: usize| {
+            (0..pinned)
+                .map(|column_index| self.column_width(column_index).unwrap_or(3) + 1)
+                .sum::<usize>()
+        };
+        while pinned > 0 && pinned_width(pinned) > area_width as usize / 2 {
+            pinned -= 1;
+        }
+        self.pinned_columns.set(pinned);
+        let area_width = area_width.saturating_sub(pinned_width(pinned) as u16);
+        let anchor_column_index = self.selected_column_index().max(pinned);
+        if self.column_page_start.get() < pinned {
+            self.column_page_start.set(pinned);
+        }
+        if anchor_column_index < self.column_page_start.get() {
+            self.column_page_start.set(anchor_column_index);
         }
 
-        let far_right_column_index = self.selected_column_index();
-        let mut column_index = self.selected_column_index();
-        let number_column_width = (self.rows.len() + 1).to_string().width() as u16;
+        let far_right_column_index = anchor_column_index;
+        let mut column_index = anchor_column_index;
         let mut widths = Vec::new();
         loop {
-            let length = self
-                .rows
-                .iter()
-                .map(|row| {
-                    row.get(column_index)
-                        .map_or(String::new(), |cell| cell.to_string())
-                        .width()
-                })
-                .collect::<Vec<usize>>()
-                .iter()
-                .max()
-                .map_or(3, |v| {
-                    *v.max(
-                        &self
-                            .headers
-                            .get(column_index)
-                            .map_or(3, |header| header.to_string().width()),
-                    )
-                    .clamp(&3, &20)
-                });
+            let length = self.column_width(column_index).unwrap_or(3);
             if widths.iter().map(|(_, width)| width).sum::<usize>() + length + widths.len() + 1
                 >= area_width.saturating_sub(number_column_width) as usize
             {
@@ -462,33 +1145,11 @@ impl TableComponent {
         widths.reverse();
 
         let far_left_column_index = column_index;
-        let selected_column_index = widths.len().saturating_sub(1);
         let mut column_index = far_right_column_index + 1;
         while widths.iter().map(|(_, width)| width).sum::<usize>() + widths.len()
             < area_width.saturating_sub(number_column_width) as usize
         {
-            let length = self
-                .rows
-                .iter()
-                .map(|row| {
-                    row.get(column_index)
-                        .map_or(String::new(), |cell| cell.to_string())
-                        .width()
-                })
-                .collect::<Vec<usize>>()
-                .iter()
-                .max()
-                .map_or(3, |v| {
-                    *v.max(
-                        self.headers
-                            .iter()
-                            .map(|header| header.to_string().width())
-                            .collect::<Vec<usize>>()
-                            .get(column_index)
-                            .unwrap_or(&3),
-                    )
-                    .clamp(&3, &20)
-                });
+            let length = self.column_width(column_index).unwrap_or(3);
             match self.headers.get(column_index) {
                 Some(header) => {
                     widths.push((header.to_string(), length));
@@ -497,7 +1158,7 @@ impl TableComponent {
             }
             column_index += 1
         }
-        if self.selected_column_index() != self.headers.len().saturating_sub(1)
+        if anchor_column_index != self.headers.len().saturating_sub(1)
             && column_index.saturating_sub(1) != self.headers.len().saturating_sub(1)
         {
             widths.pop();
@@ -507,30 +1168,463 @@ impl TableComponent {
             .iter()
             .map(|(_, width)| Constraint::Length(*width as u16))
             .collect::<Vec<Constraint>>();
-        if self.selected_column_index() != self.headers.len().saturating_sub(1)
+        if anchor_column_index != self.headers.len().saturating_sub(1)
             && column_index.saturating_sub(1) != self.headers.len().saturating_sub(1)
         {
             constraints.push(Constraint::Min(10));
         }
+        constraints.splice(
+            0..0,
+            (0..pinned).map(|column_index| {
+                Constraint::Length(self.column_width(column_index).unwrap_or(3) as u16)
+            }),
+        );
         constraints.insert(0, Constraint::Length(number_column_width));
         self.column_page_start.set(far_left_column_index);
-
+        self.visible_columns
+            .set((far_left_column_index, far_left_column_index + widths.len()));
+
+        let mut headers = self.headers(far_left_column_index, far_right_column_index);
+        headers.splice(1..1, self.headers[..pinned].iter().cloned());
+        let mut rows = self.rows(
+            far_left_column_index,
+            far_right_column_index,
+            visible.clone(),
+        );
+        for (row, row_index) in rows.iter_mut().zip(visible) {
+            row.splice(
+                1..1,
+                (0..pinned).map(|column_index| self.shown_cell(row_index, column_index)),
+            );
+            for (cell, constraint) in row.iter_mut().zip(&constraints).skip(1) {
+                if let Constraint::Length(width) = constraint {
+                    *cell = truncate_cell(cell, *width as usize);
+                }
+            }
+        }
         (
-            self.selection_area_corner
-                .map_or(selected_column_index + 1, |(x, _)| {
-                    if x > self.selected_column {
-                        (selected_column_index + 1)
-                            .saturating_sub(x.saturating_sub(self.selected_column))
+            self.display_index(self.selected_column).unwrap_or_default(),
+            headers,
+            rows,
+            constraints,
+        )
+    }
+}
+
+/// Returns how many columns the widest line of `text` takes, counting its graphemes as the
+/// terminal draws them: CJK characters and emoji as two and combining marks as none.
+fn display_width(text: &str) -> usize {
+    text.split('\n')
+        .map(|line| line.graphemes(true).map(|g| g.width()).sum::<usize>())
+        .max()
+        .unwrap_or_default()
+}
+
+/// Replaces the control characters of a cell other than line breaks, which the terminal would
+/// act on instead of drawing them and so shift the rest of the row, e.g. a tab with spaces and
+/// a carriage return with `␍`.
+fn printable(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(|c| c.is_control() && c != '\n') {
+        return Cow::Borrowed(text);
+    }
+    let mut printable = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => printable.push(c),
+            // the line ends of Windows are line breaks
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\t' => printable.push_str(&" ".repeat(TAB_WIDTH)),
+            '\u{7f}' => printable.push('\u{2421}'),
+            // the control pictures of the C0 block are in the order of the characters
+            c if (c as u32) < 0x20 => {
+                printable.push(char::from_u32(0x2400 + c as u32).unwrap_or('\u{fffd}'))
+            }
+            c if c.is_control() => printable.push('\u{fffd}'),
+            c => printable.push(c),
+        }
+    }
+    Cow::Owned(printable)
+}
+
+/// Counts the values which are not null and adds up the numbers among them, like the status
+/// bar of a spreadsheet, e.g. `count: 3, sum: 6, avg: 2, min: 1, max: 3`. Numbers held as text
+/// count as numbers too.
+fn summarize<'a>(values: impl Iterator<Item = &'a Value>) -> String {
+    let mut count = 0;
+    let mut numbers = 0;
+    // `None` once the sum overflows
+    let mut sum = Some(Decimal::ZERO);
+    let mut min: Option<Decimal> = None;
+    let mut max: Option<Decimal> = None;
+    for value in values.filter(|value| !value.is_null()) {
+        count += 1;
+        if let Some(number) = value.as_decimal() {
+            numbers += 1;
+            sum = sum.and_then(|sum| sum.checked_add(number));
+            min = Some(min.map_or(number, |min| min.min(number)));
+            max = Some(max.map_or(number, |max| max.max(number)));
+        }
+    }
+    let mut summary = format!("count: {}", count);
+    if let (Some(min), Some(max)) = (min, max) {
+        let show = |number: Option<Decimal>| {
+            number.map_or("-".to_string(), |number| number.normalize().to_string())
+        };
+        let avg = sum
+            .and_then(|sum| sum.checked_div(Decimal::from(numbers)))
+            .map(|avg| avg.round_dp(SUMMARY_DECIMAL_PLACES));
+        summary.push_str(&format!(
+            ", sum: {}, avg: {}, min: {}, max: {}",
+            show(sum),
+            show(avg),
+            show(Some(min)),
+            show(Some(max))
+        ));
+    }
+    summary
+}
+
+/// Cuts the lines of a cell which are wider than the column, ending them with `…`. A wide
+/// character is never split, and a combining mark stays with its character.
+fn truncate_cell(cell: &str, width: usize) -> String {
+    if display_width(cell) <= width {
+        return cell.to_string();
+    }
+    cell.split('\n')
+        .map(|line| {
+            if display_width(line) <= width {
+                return line.to_string();
+            }
+            let mut truncated = String::new();
+            let mut truncated_width = 0;
+            for grapheme in line.graphemes(true) {
+                let grapheme_width = grapheme.width();
+                if truncated_width + grapheme_width + 1 > width {
+                    break;
+                }
+                truncated.push_str(grapheme);
+                truncated_width += grapheme_width;
+            }
+            truncated.push('…');
+            truncated
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Returns the name of a column without the sort icon its header may have, e.g. `id ↑1`.
+pub fn column_name(header: &str) -> &str {
+    match header.rsplit_once(' ') {
+        Some((name, icon)) if icon.starts_with(['↑', '↓']) => name,
+        _ => header,
+    }
+}
+
+fn column_formats(rows: &[Vec<Value>], value_format: &ValueFormat) -> Vec<Option<ColumnFormat>> {
+    (0..rows.first().map_or(0, |row| row.len()))
+        .map(|column_index| {
+            ColumnFormat::of(
+                rows.iter().filter_map(|row| row.get(column_index)),
+                value_format,
+            )
+        })
+        .collect()
+}
+
+/// Returns the rows which a draw of `height` lines may show: a screenful from the row which
+/// was at the top last time, moved to keep `selected` on it, and one more for the table to
+/// scroll into while it brings `selected` into view.
+fn visible_rows(offset: usize, selected: Option<usize>, height: usize, len: usize) -> Range<usize> {
+    let selected = selected.unwrap_or(offset);
+    let mut start = offset.min(selected);
+    if selected >= start + height {
+        start = selected + 1 - height;
+    }
+    start.min(len)..(start + height * 2).min(len)
+}
+
+const MIN_COLUMN_WIDTH: usize = 3;
+/// How many decimal places the average of the selected numbers is rounded to.
+const SUMMARY_DECIMAL_PLACES: u32 = 6;
+/// How many spaces a tab in a cell is shown as.
+const TAB_WIDTH: usize = 4;
+/// The widest the names of the columns are shown in the view of a row.
+const MAX_RECORD_NAME_WIDTH: usize = 30;
+const MAX_COLUMN_WIDTH: usize = 120;
+/// How many characters a column grows or shrinks by at a time.
+const COLUMN_WIDTH_STEP: isize = 2;
+const SORT_MARKER_WIDTH: usize = 12;
+const SORT_MARKER_LABEL_LENGTH: usize = 16;
+
+/// Returns the first and the last row which have a value in `column`.
+fn value_range(values: &[Vec<Value>], column: usize) -> Option<(usize, usize)> {
+    let has_value = |row: &Vec<Value>| row.get(column).is_some_and(|value| !value.is_null());
+    Some((
+        values.iter().position(has_value)?,
+        values.iter().rposition(has_value)?,
+    ))
+}
+
+/// Draws where the visible rows fall in the range of a sorted column from its smallest to its
+/// largest value, e.g. `1 ──━━──────── 98765…`. `values` are the loaded rows, and the rows
+/// from `first` to `last` have a value in `column`. Numbers and points in time are placed by
+/// their value, anything else by the position of its row.
+fn sort_marker(
+    values: &[Vec<Value>],
+    column: usize,
+    (first, last): (usize, usize),
+    visible: Range<usize>,
+    is_asc: bool,
+    eod: bool,
+) -> String {
+    let value = |row: usize| &values[row][column];
+    let position = |row: usize| {
+        let row = row.clamp(first, last);
+        let position = match (
+            value(row).as_f64(),
+            value(first).as_f64(),
+            value(last).as_f64(),
+        ) {
+            (Some(value), Some(start), Some(end)) if start != end => {
+                (value - start) / (end - start)
+            }
+            _ if first != last => (row - first) as f64 / (last - first) as f64,
+            _ => 0.0,
+        };
+        if is_asc {
+            position
+        } else {
+            1.0 - position
+        }
+    };
+    let start = position(visible.start);
+    let end = position(visible.end.saturating_sub(1).max(visible.start));
+    let (low, high) = (start.min(end), start.max(end));
+    let bar = (0..SORT_MARKER_WIDTH)
+        .map(|cell| {
+            let from = cell as f64 / SORT_MARKER_WIDTH as f64;
+            let to = (cell + 1) as f64 / SORT_MARKER_WIDTH as f64;
+            if from <= high && (low < to || cell + 1 == SORT_MARKER_WIDTH) {
+                '━'
+            } else {
+                '─'
+            }
+        })
+        .collect::<String>();
+    let label = |value: &Value| {
+        value
+            .to_string()
+            .chars()
+            .take(SORT_MARKER_LABEL_LENGTH)
+            .collect::<String>()
+    };
+    let first_label = label(value(first));
+    // more rows may follow the last loaded one
+    let last_label = format!("{}{}", label(value(last)), if eod { "" } else { "…" });
+    let (min, max) = if is_asc {
+        (first_label, last_label)
+    } else {
+        (last_label, first_label)
+    };
+    format!("{} {} {}", min, bar, max)
+}
+
+impl TableComponent {
+    fn draw_grid(&mut self, f: &mut Frame, area: Rect, focused: bool) {
+        self.selected_row.selected().map_or_else(
+            || {
+                self.scroll.reset();
+            },
+            |selection| {
+                self.scroll.update(
+                    selection,
+                    self.values.len(),
+                    area.height.saturating_sub(2) as usize,
+                );
+            },
+        );
+
+        let block = Block::default().borders(Borders::NONE);
+        // only the rows around the top of the last draw are turned into cells
+        let selected = match self.selection_area_corner {
+            Some((_, y)) => Some(y),
+            None => self.selected_row.selected(),
+        };
+        let visible = visible_rows(
+            self.selected_row.offset(),
+            selected,
+            area.height as usize,
+            self.values.len(),
+        );
+        let (selected_column_index, headers, rows, constraints) =
+            self.calculate_cell_widths(block.inner(area).width, visible.clone());
+        let pinned = self.pinned_columns.get();
+        let header_cells = headers.iter().enumerate().map(|(column_index, h)| {
+            let style = if selected_column_index == column_index {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            // the headers of the pinned columns stand out from the ones which scroll
+            Cell::from(h.to_string()).style(if (1..=pinned).contains(&column_index) {
+                style.fg(Color::Cyan)
+            } else {
+                style
+            })
+        });
+        let header = Row::new(header_cells).height(1).bottom_margin(1);
+        let rows = rows.iter().zip(visible.clone()).map(|(item, row_index)| {
+            let height = item
+                .iter()
+                .map(|content| content.chars().filter(|c| *c == '\n').count())
+                .max()
+                .unwrap_or(0)
+                + 1;
+            let cells = item.iter().enumerate().map(|(column_index, c)| {
+                Cell::from(if self.is_numeric_cell(column_index) {
+                    Text::from(c.to_string()).alignment(Alignment::Right)
+                } else {
+                    Text::from(c.to_string())
+                })
+                .style(
+                    if self.is_selected_cell(row_index, column_index, selected_column_index) {
+                        Style::default().bg(Color::Blue)
+                    } else if self.is_match(row_index, column_index) {
+                        Style::default().fg(Color::Black).bg(Color::LightYellow)
+                    } else if column_index == 0 && self.marked_rows.contains(&row_index) {
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD)
+                    } else if let Some(color) = self.change_color(row_index, column_index) {
+                        Style::default().fg(color)
+                    } else if self.is_number_column(row_index, column_index) {
+                        Style::default().add_modifier(Modifier::BOLD)
+                    } else if self.is_null_cell(row_index, column_index) {
+                        Style::default().fg(Color::DarkGray)
                     } else {
-                        (selected_column_index + 1)
-                            .saturating_add(self.selected_column.saturating_sub(x))
-                    }
+                        Style::default()
+                    },
+                )
+            });
+            Row::new(cells).height(height as u16).bottom_margin(1)
+        });
+        let table = Table::default()
+            .rows(rows)
+            .header(header)
+            .style(if focused {
+                Style::default()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            })
+            .widths(&constraints);
+        let mut state = TableState::default()
+            .with_offset(self.selected_row.offset().saturating_sub(visible.start))
+            .with_selected(selected.map(|selected| selected - visible.start));
+        f.render_stateful_widget(table, area, &mut state);
+        *self.selected_row.offset_mut() = visible.start + state.offset();
+    }
+
+    /// Draws the selected row as its columns and their wrapped values, one below the other,
+    /// like `\x` in psql.
+    fn draw_record(&self, f: &mut Frame, area: Rect, focused: bool) {
+        let row_index = match self
+            .selected_row
+            .selected()
+            .filter(|index| *index < self.values.len())
+        {
+            Some(row_index) => row_index,
+            None => return,
+        };
+        let name_width = self
+            .headers
+            .iter()
+            .map(|header| header.width())
+            .max()
+            .unwrap_or_default()
+            .min(MAX_RECORD_NAME_WIDTH);
+        let value_width = (area.width as usize).saturating_sub(name_width + 3).max(1);
+        let mut lines = Vec::new();
+        let mut selected_lines = 0..0;
+        for (column, header) in self.headers.iter().enumerate() {
+            let start = lines.len();
+            let value = printable(&self.display(row_index, column)).into_owned();
+            let style = if column == self.selected_column {
+                Style::default().bg(Color::Blue)
+            } else if self
+                .values
+                .get(row_index)
+                .and_then(|values| values.get(column))
+                .is_some_and(Value::is_null)
+            {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            for (index, part) in wrap_text(&value, value_width).into_iter().enumerate() {
+                let name = if index == 0 {
+                    truncate_cell(header, name_width)
+                } else {
+                    String::new()
+                };
+                let padding = " ".repeat(name_width.saturating_sub(name.width()));
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{}{}", name, padding),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(" │ "),
+                    Span::styled(part, style),
+                ]));
+            }
+            if column == self.selected_column {
+                selected_lines = start..lines.len();
+            }
+        }
+
+        // the view scrolls only as far as it takes to show the selected column
+        let height = area.height as usize;
+        let mut offset = self.record_scroll.get();
+        if selected_lines.start < offset {
+            offset = selected_lines.start;
+        } else if selected_lines.end > offset + height {
+            offset = selected_lines
+                .end
+                .saturating_sub(height)
+                .min(selected_lines.start);
+        }
+        self.record_scroll.set(offset);
+        f.render_widget(
+            Paragraph::new(lines)
+                .scroll((offset as u16, 0))
+                .style(if focused {
+                    Style::default()
+                } else {
+                    Style::default().fg(Color::DarkGray)
                 }),
-            self.headers(far_left_column_index, far_right_column_index),
-            self.rows(far_left_column_index, far_right_column_index),
-            constraints,
-        )
+            area,
+        );
+    }
+}
+
+/// Splits the lines of a text which are wider than `width` into more lines.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for line in text.split('\n') {
+        let mut wrapped = String::new();
+        let mut wrapped_width = 0;
+        for grapheme in line.graphemes(true) {
+            let grapheme_width = grapheme.width();
+            if wrapped_width + grapheme_width > width && !wrapped.is_empty() {
+                lines.push(std::mem::take(&mut wrapped));
+                wrapped_width = 0;
+            }
+            wrapped.push_str(grapheme);
+            wrapped_width += grapheme_width;
+        }
+        lines.push(wrapped);
     }
+    lines
 }
 
 impl PropertyTrait for TableComponent {
@@ -545,6 +1639,14 @@ impl PropertyTrait for TableComponent {
     fn content(&self) -> Option<String> {
         TableComponent::content(self)
     }
+
+    fn cell_count(&self) -> Option<usize> {
+        Some(self.selected_cell_count())
+    }
+
+    fn describe(&self) -> String {
+        TableComponent::describe(self)
+    }
 }
 
 impl StatefulDrawableComponent for TableComponent {
@@ -576,78 +1678,24 @@ impl StatefulDrawableComponent for TableComponent {
             area,
         );
 
-        self.selected_row.selected().map_or_else(
-            || {
-                self.scroll.reset();
-            },
-            |selection| {
-                self.scroll.update(
-                    selection,
-                    self.rows.len(),
-                    chunks[1].height.saturating_sub(2) as usize,
-                );
-            },
-        );
-
-        let block = Block::default().borders(Borders::NONE);
-        let (selected_column_index, headers, rows, constraints) =
-            self.calculate_cell_widths(block.inner(chunks[0]).width);
-        let header_cells = headers.iter().enumerate().map(|(column_index, h)| {
-            Cell::from(h.to_string()).style(if selected_column_index == column_index {
-                Style::default().add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
-            })
-        });
-        let header = Row::new(header_cells).height(1).bottom_margin(1);
-        let rows = rows.iter().enumerate().map(|(row_index, item)| {
-            let height = item
-                .iter()
-                .map(|content| content.chars().filter(|c| *c == '\n').count())
-                .max()
-                .unwrap_or(0)
-                + 1;
-            let cells = item.iter().enumerate().map(|(column_index, c)| {
-                Cell::from(c.to_string()).style(
-                    if self.is_selected_cell(row_index, column_index, selected_column_index) {
-                        Style::default().bg(Color::Blue)
-                    } else if self.is_number_column(row_index, column_index) {
-                        Style::default().add_modifier(Modifier::BOLD)
-                    } else {
-                        Style::default()
-                    },
-                )
-            });
-            Row::new(cells).height(height as u16).bottom_margin(1)
-        });
-        let table = Table::default()
-            .rows(rows)
-            .header(header)
-            .style(if focused {
-                Style::default()
-            } else {
-                Style::default().fg(Color::DarkGray)
-            })
-            .widths(&constraints);
-        let mut state = self.selected_row.clone();
-        f.render_stateful_widget(
-            table,
-            chunks[1],
-            if let Some((_, y)) = self.selection_area_corner {
-                state.select(Some(y));
-                &mut state
-            } else {
-                &mut self.selected_row
-            },
-        );
+        if self.record_view {
+            self.draw_record(f, chunks[1], focused);
+        } else {
+            self.draw_grid(f, chunks[1], focused);
+        }
 
-        TableValueComponent::new(self.content().unwrap_or_default()).draw(f, chunks[0], focused)?;
+        TableValueComponent::new(
+            self.search_line()
+                .or_else(|| self.content())
+                .unwrap_or_default(),
+        )
+        .draw(f, chunks[0], focused)?;
 
         TableStatusComponent::new(
-            if self.rows.is_empty() {
+            if self.values.is_empty() {
                 None
             } else {
-                Some(self.rows.len())
+                Some(self.values.len())
             },
             self.total_row_count,
             if self.headers.is_empty() {
@@ -655,11 +1703,21 @@ impl StatefulDrawableComponent for TableComponent {
             } else {
                 Some(self.headers.len())
             },
+            Some(self.visible_columns.get()).filter(|(left, right)| {
+                !self.values.is_empty() && right - left < self.headers.len()
+            }),
+            self.pinned_columns.get(),
             self.table.as_ref().map(|t| t.1.clone()),
+            self.warning.clone(),
         )
+        .summary(self.selection_summary())
         .draw(f, chunks[2], focused)?;
 
-        self.scroll.draw(f, chunks[1]);
+        if !self.record_view {
+            self.scroll.draw(f, chunks[1]);
+            self.draw_sort_marker(f, area, chunks[1].height);
+        }
+        self.cell_viewer.draw(f, area, false)?;
         Ok(())
     }
 }
@@ -675,11 +1733,132 @@ impl Component for TableComponent {
         out.push(CommandInfo::new(command::move_to_head_tail_of_line(
             &self.key_config,
         )));
+        out.push(CommandInfo::new(command::column_pages(&self.key_config)));
+        out.push(CommandInfo::new(command::pin_columns(&self.key_config)));
+        out.push(CommandInfo::new(command::toggle_record_view(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::resize_column(&self.key_config)));
         out.push(CommandInfo::new(command::sort_by_column(&self.key_config)));
+        out.push(CommandInfo::new(command::view_cell(&self.key_config)));
+        out.push(CommandInfo::new(command::search_in_table(&self.key_config)));
+        out.push(CommandInfo::new(command::json_tree(&self.key_config)));
+    }
+
+    fn line_commands(&self, out: &mut Vec<LineCommand>) {
+        out.push(LineCommand::new(
+            "row",
+            "<number>",
+            "Jump to a loaded row by its number",
+        ));
+        out.push(
+            LineCommand::new("column", "<column>", "Move to a column by its name").with_arguments(
+                self.headers
+                    .iter()
+                    .map(|header| column_name(header).to_string())
+                    .collect(),
+            ),
+        );
+    }
+
+    fn run_line_command(&mut self, name: &str, args: &[&str]) -> Result<EventState> {
+        match name {
+            "row" => {
+                let number = match args {
+                    [number] => number.parse::<usize>().ok().filter(|number| *number > 0),
+                    _ => None,
+                }
+                .context("usage: row <number>")?;
+                if number > self.values.len() {
+                    anyhow::bail!(
+                        "row {} is not loaded, there are {} rows",
+                        number,
+                        self.values.len()
+                    );
+                }
+                self.reset_selection();
+                self.selected_row.select(Some(number - 1));
+            }
+            "column" => {
+                let name = args.join(" ");
+                let column = self
+                    .find_column(&name)
+                    .with_context(|| format!("column `{}` is not found", name))?;
+                self.reset_selection();
+                self.selected_column = column;
+            }
+            _ => return Ok(EventState::NotConsumed),
+        }
+        Ok(EventState::Consumed)
     }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
-        if key == self.key_config.scroll_left {
+        if self.cell_viewer.is_visible() {
+            return self.cell_viewer.event(key);
+        }
+        if self.search_event(key).is_consumed() {
+            return Ok(EventState::Consumed);
+        }
+        if key == self.key_config.search_in_table {
+            self.search = Some(TableSearch {
+                typing: true,
+                ..TableSearch::default()
+            });
+            return Ok(EventState::Consumed);
+        } else if self.search.is_some() {
+            if key == self.key_config.next_match || key == self.key_config.previous_match {
+                self.jump_to_match(key == self.key_config.next_match);
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.exit_popup {
+                self.search = None;
+                return Ok(EventState::Consumed);
+            }
+        }
+        if key == self.key_config.toggle_record_view {
+            self.record_view = !self.record_view;
+            self.record_scroll.set(0);
+            return Ok(EventState::Consumed);
+        } else if self.record_view {
+            // the columns are listed downwards and the rows follow each other sideways
+            if key == self.key_config.scroll_down {
+                self.next_column();
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.scroll_up {
+                self.previous_column();
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.scroll_right {
+                self.next_row(1);
+                return Ok(EventState::NotConsumed);
+            } else if key == self.key_config.scroll_left {
+                self.previous_row(1);
+                return Ok(EventState::Consumed);
+            }
+        }
+        if key == self.key_config.pin_columns {
+            self.toggle_pinned_columns();
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.widen_column {
+            self.resize_column(COLUMN_WIDTH_STEP);
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.narrow_column {
+            self.resize_column(-COLUMN_WIDTH_STEP);
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.fit_column {
+            self.fit_column();
+            return Ok(EventState::Consumed);
+        }
+        if key == self.key_config.view_cell {
+            if let Some(value) = self.selected_value() {
+                let title = self
+                    .headers
+                    .get(self.selected_column)
+                    .cloned()
+                    .unwrap_or_default();
+                let value = value.clone();
+                self.cell_viewer.open(title, &value);
+            }
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.scroll_left {
             self.previous_column();
             return Ok(EventState::Consumed);
         } else if key == self.key_config.scroll_down {
@@ -704,6 +1883,12 @@ impl Component for TableComponent {
             self.move_to_head_of_line();
         } else if key == self.key_config.move_to_tail_of_line {
             self.move_to_tail_of_line();
+        } else if key == self.key_config.next_column_page {
+            self.next_column_page();
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.previous_column_page {
+            self.previous_column_page();
+            return Ok(EventState::Consumed);
         } else if key == self.key_config.scroll_right {
             self.next_column();
             return Ok(EventState::Consumed);
@@ -727,11 +1912,42 @@ impl Component for TableComponent {
     }
 }
 
+/// Returns the cells which contain the text, ignoring the case.
+fn find_matches(rows: &[Vec<Value>], input: &str) -> Vec<(usize, usize)> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+    let input = input.to_lowercase();
+    rows.iter()
+        .enumerate()
+        .flat_map(|(row_index, row)| {
+            let input = &input;
+            row.iter()
+                .enumerate()
+                .filter(move |(_, value)| value.to_string().to_lowercase().contains(input.as_str()))
+                .map(move |(column_index, _)| (row_index, column_index))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
-    use super::{KeyConfig, Order, OrderManager, TableComponent};
+    use super::{
+        display_width, find_matches, printable, sort_marker, summarize, truncate_cell,
+        visible_rows, wrap_text, DTable, Database, KeyConfig, Order, OrderManager, TableComponent,
+        Value,
+    };
+    use crate::components::Component;
+    use crate::event::Key;
+    use crate::tree::TableKind;
     use ratatui::layout::Constraint;
 
+    fn texts(rows: Vec<Vec<String>>) -> Vec<Vec<Value>> {
+        rows.into_iter()
+            .map(|row| row.into_iter().map(Value::Text).collect())
+            .collect()
+    }
+
     #[test]
     fn test_headers() {
         let mut component = TableComponent::new(KeyConfig::default());
@@ -742,11 +1958,157 @@ mod test {
     #[test]
     fn test_rows() {
         let mut component = TableComponent::new(KeyConfig::default());
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
-        assert_eq!(component.rows(1, 2), vec![vec!["1", "b"], vec!["2", "e"]],)
+        ]);
+        assert_eq!(
+            component.rows(1, 2, 0..2),
+            vec![vec!["1", "b"], vec!["2", "e"]],
+        );
+        assert_eq!(component.rows(0, 2, 1..2), vec![vec!["2", "d", "e"]]);
+    }
+
+    #[test]
+    fn test_visible_rows() {
+        // the rows from the last top
+        assert_eq!(visible_rows(10, Some(12), 5, 100), 10..20);
+        // moved up to the selection
+        assert_eq!(visible_rows(10, Some(3), 5, 100), 3..13);
+        // moved down to the selection, e.g. after jumping to the last row
+        assert_eq!(visible_rows(0, Some(99), 5, 100), 95..100);
+        assert_eq!(visible_rows(0, None, 5, 3), 0..3);
+        assert_eq!(visible_rows(0, None, 5, 0), 0..0);
+    }
+
+    #[test]
+    fn test_search() {
+        let rows = texts(
+            [["1", "Alice"], ["2", "bob"], ["3", "alicia"]]
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+        );
+        assert_eq!(find_matches(&rows, "ALI"), vec![(0, 1), (2, 1)]);
+        assert!(find_matches(&rows, "").is_empty());
+
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = ["id", "name"].iter().map(|h| h.to_string()).collect();
+        component.values = rows;
+        component.selected_row.select(Some(1));
+        for key in [
+            Key::Char('f'),
+            Key::Char('a'),
+            Key::Char('l'),
+            Key::Char('x'),
+        ] {
+            component.event(key).unwrap();
+        }
+        assert!(component.is_searching());
+        assert!(component.search.as_ref().unwrap().matches.is_empty());
+        component.event(Key::Backspace).unwrap();
+        // enter jumps to the first match after the cursor, and the search wraps around
+        component.event(Key::Enter).unwrap();
+        assert!(!component.is_searching());
+        assert_eq!(component.selected_row.selected(), Some(2));
+        assert_eq!(component.selected_column, 1);
+        component.event(Key::Char('.')).unwrap();
+        assert_eq!(component.selected_row.selected(), Some(0));
+        component.event(Key::Char(',')).unwrap();
+        assert_eq!(component.selected_row.selected(), Some(2));
+        component.event(Key::Esc).unwrap();
+        assert!(component.search.is_none());
+    }
+
+    #[test]
+    fn test_line_commands() {
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = ["id", "user_name ↑1", "user_email"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect();
+        component.values = texts(vec![vec![String::new(); 3]; 5]);
+        component.run_line_command("row", &["4"]).unwrap();
+        assert_eq!(component.selected_row.selected(), Some(3));
+        assert!(component.run_line_command("row", &["6"]).is_err());
+        assert!(component.run_line_command("row", &["0"]).is_err());
+
+        component
+            .run_line_command("column", &["USER_EMAIL"])
+            .unwrap();
+        assert_eq!(component.selected_column, 2);
+        component.run_line_command("column", &["user_n"]).unwrap();
+        assert_eq!(component.selected_column, 1);
+        // the prefix matches two columns
+        assert!(component.run_line_command("column", &["user"]).is_err());
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut component = TableComponent::new(KeyConfig::default());
+        assert_eq!(component.describe(), "no rows");
+
+        component.headers = ["id", "name"].iter().map(|h| h.to_string()).collect();
+        component.values = texts(vec![
+            ["1", "alice"].iter().map(|h| h.to_string()).collect(),
+            ["2", "bob"].iter().map(|h| h.to_string()).collect(),
+        ]);
+        component.selected_row.select(Some(1));
+        component.selected_column = 1;
+        assert_eq!(component.describe(), "row 2 of 2, name: bob");
+
+        component.total_row_count = Some(120);
+        component.selection_area_corner = Some((0, 0));
+        assert_eq!(
+            component.describe(),
+            "row 2 of 120, name: bob, 4 cells selected"
+        );
+    }
+
+    #[test]
+    fn test_title() {
+        let mut component = TableComponent::new(KeyConfig::default());
+        assert_eq!(component.title(), " - ");
+        component.table = Some((
+            Database::new("shop".to_string(), vec![]),
+            DTable {
+                name: "orders".to_string(),
+                create_time: None,
+                update_time: None,
+                engine: None,
+                schema: None,
+                kind: TableKind::default(),
+                partition_of: None,
+            },
+        ));
+        component.set_page_size(Some(200));
+        assert_eq!(component.title(), "shop.orders [limit 200]");
+    }
+
+    #[test]
+    fn test_restore_cursor() {
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect();
+        component.values = texts(vec![
+            vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
+            vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
+        ]);
+        component.selected_row.select(Some(1));
+        component.selected_column = 2;
+        let cursor = component.cursor();
+
+        component.selected_row.select(Some(0));
+        component.selected_column = 0;
+        component.restore_cursor(cursor);
+        assert_eq!(component.selected_row.selected(), Some(1));
+        assert_eq!(component.selected_column, 2);
+
+        // the cursor is clamped to the loaded rows and columns
+        component.values.pop();
+        component.headers.pop();
+        component.restore_cursor(cursor);
+        assert_eq!(component.selected_row.selected(), Some(0));
+        assert_eq!(component.selected_column, 1);
     }
 
     #[test]
@@ -763,10 +2125,10 @@ mod test {
 
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(1));
         component.selected_column = 1;
         component.expand_selected_area_x(false);
@@ -788,10 +2150,10 @@ mod test {
 
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(1));
         component.selected_column = 1;
         component.expand_selected_area_x(true);
@@ -812,10 +2174,10 @@ mod test {
         // 2  d |e| f
 
         let mut component = TableComponent::new(KeyConfig::default());
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(1));
         component.selected_column = 1;
         component.expand_selected_area_y(false);
@@ -836,10 +2198,10 @@ mod test {
         // 2  d |e| f
 
         let mut component = TableComponent::new(KeyConfig::default());
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(0));
         component.selected_column = 1;
         component.expand_selected_area_y(true);
@@ -851,10 +2213,10 @@ mod test {
     fn test_expand_selected_by_horizontal_line() {
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
             vec!["g", "h", "i"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
 
         // select one line
         component.selected_row.select(Some(0));
@@ -877,10 +2239,10 @@ mod test {
     fn test_is_number_column() {
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(0));
         assert!(component.is_number_column(0, 0));
         assert!(!component.is_number_column(0, 1));
@@ -894,10 +2256,10 @@ mod test {
 
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(0));
         assert_eq!(component.content(), Some("a".to_string()));
     }
@@ -910,10 +2272,10 @@ mod test {
 
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(0));
         component.selection_area_corner = Some((1, 1));
         assert_eq!(component.content(), Some("a,b\nd,e".to_string()));
@@ -927,10 +2289,10 @@ mod test {
 
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(0));
         // a
         assert!(component.is_selected_cell(0, 1, 1));
@@ -948,10 +2310,10 @@ mod test {
 
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         component.selected_row.select(Some(0));
         component.selection_area_corner = Some((1, 1));
         // a
@@ -971,10 +2333,10 @@ mod test {
         let mut component = TableComponent::new(KeyConfig::default());
 
         component.headers = vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
             vec!["g", "h", "i"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
 
         // cursor returns to the top.
         component.expand_selected_area_y(true);
@@ -993,10 +2355,10 @@ mod test {
 
         // if component has a header, cursor is moved to tail of line.
         component.headers = vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
             vec!["g", "h", "i"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
 
         component.move_to_tail_of_line();
         assert_eq!(component.selected_column, 2);
@@ -1006,15 +2368,15 @@ mod test {
     fn test_calculate_cell_widths_when_sum_of_cell_widths_is_greater_than_table_width() {
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["aaaaa", "bbbbb", "ccccc"]
                 .iter()
                 .map(|h| h.to_string())
                 .collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
         let (selected_column_index, headers, rows, constraints) =
-            component.calculate_cell_widths(10);
+            component.calculate_cell_widths(10, 0..component.values.len());
         assert_eq!(selected_column_index, 1);
         assert_eq!(headers, vec!["", "1", "2"]);
         assert_eq!(rows, vec![vec!["1", "aaaaa", "bbbbb"], vec!["2", "d", "e"]]);
@@ -1028,20 +2390,149 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_column_pages() {
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = (0..1000).map(|i| format!("c{}", i)).collect();
+        component.values = texts(vec![(0..1000).map(|i| i.to_string()).collect()]);
+        component.calculate_cell_widths(40, 0..component.values.len());
+        let (left, right) = component.visible_columns.get();
+        assert_eq!(left, 0);
+        assert!(right > 1 && right < 20);
+        // only the columns on the screen and the one cut off at the edge are measured
+        assert_eq!(
+            component
+                .column_widths
+                .borrow()
+                .iter()
+                .filter(|width| width.is_some())
+                .count(),
+            right + 1
+        );
+
+        component.next_column_page();
+        assert_eq!(component.selected_column, right);
+        let (_, headers, _, _) = component.calculate_cell_widths(40, 0..component.values.len());
+        assert_eq!(headers[1], format!("c{}", right));
+
+        component.previous_column_page();
+        assert_eq!(component.selected_column, 0);
+        component.previous_column_page();
+        assert_eq!(component.selected_column, 0);
+    }
+
+    #[test]
+    fn test_pinned_columns() {
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = (0..100).map(|i| format!("c{}", i)).collect();
+        component.values = texts(vec![(0..100).map(|i| format!("v{}", i)).collect()]);
+        component.selected_column = 1;
+        component.toggle_pinned_columns();
+        assert_eq!(component.pinned_columns.get(), 2);
+
+        // the pinned columns stay on the left of the columns which scroll
+        component.selected_column = 50;
+        let (selected_column_index, headers, rows, constraints) =
+            component.calculate_cell_widths(40, 0..component.values.len());
+        assert_eq!(&headers[..3], ["", "c0", "c1"]);
+        assert_eq!(&rows[0][..3], ["1", "v0", "v1"]);
+        assert_eq!(headers.len(), constraints.len());
+        assert_eq!(headers[selected_column_index], "c50");
+        assert_eq!(component.column_at(2), Some(1));
+        assert_eq!(component.column_at(selected_column_index), Some(50));
+        assert_eq!(component.display_index(10), None);
+
+        // they take at most half of the width
+        component.calculate_cell_widths(8, 0..component.values.len());
+        assert_eq!(component.pinned_columns.get(), 1);
+
+        component.toggle_pinned_columns();
+        assert_eq!(component.pinned_columns.get(), 0);
+    }
+
+    #[test]
+    fn test_column_width() {
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = ["id", "note ↑1"].iter().map(|h| h.to_string()).collect();
+        component.values = texts(vec![vec!["1".to_string(), "n".repeat(30)]]);
+        assert_eq!(component.column_width(1), Some(20));
+
+        component.selected_column = 1;
+        component.resize_column(-2);
+        assert_eq!(component.column_width(1), Some(18));
+        assert_eq!(component.column_width_overrides().get("note"), Some(&18));
+        component.fit_column();
+        assert_eq!(component.column_width(1), Some(30));
+        // fitting again measures the column as usual
+        component.fit_column();
+        assert_eq!(component.column_width(1), Some(20));
+
+        component.selected_column = 0;
+        for _ in 0..5 {
+            component.resize_column(-2);
+        }
+        assert_eq!(component.column_width(0), Some(3));
+
+        assert_eq!(truncate_cell("abcdef", 4), "abc…");
+        assert_eq!(truncate_cell("abc\nabcdef", 4), "abc\nabc…");
+        assert_eq!(truncate_cell("abcd", 4), "abcd");
+    }
+
+    #[test]
+    fn test_wide_characters() {
+        // CJK characters and emoji take two columns, combining marks none
+        assert_eq!(display_width("世界"), 4);
+        assert_eq!(display_width("e\u{301}te\u{301}\n😀"), 3);
+        assert_eq!(truncate_cell("世界地図", 6), "世界…");
+        assert_eq!(truncate_cell("世界地図", 5), "世界…");
+        assert_eq!(
+            truncate_cell("e\u{301}e\u{301}e\u{301}e\u{301}", 3),
+            "e\u{301}e\u{301}…"
+        );
+        assert_eq!(wrap_text("世界地", 4), vec!["世界", "地"]);
+
+        assert_eq!(printable("a\tb\r\nc\u{1b}"), "a    b\nc\u{241b}");
+        assert!(matches!(printable("a\nb"), std::borrow::Cow::Borrowed(_)));
+
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = vec!["a".to_string()];
+        component.values = texts(vec![vec!["世界世界".to_string()], vec!["a\tb".to_string()]]);
+        assert_eq!(component.column_width(0), Some(8));
+    }
+
+    #[test]
+    fn test_record_view() {
+        assert_eq!(wrap_text("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(wrap_text("ab\n\nc", 3), vec!["ab", "", "c"]);
+
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = ["id", "name"].iter().map(|h| h.to_string()).collect();
+        component.values = texts(vec![vec!["1".to_string(), "a".to_string()]; 2]);
+        component.selected_row.select(Some(0));
+        component.event(Key::Char('X')).unwrap();
+        component.event(Key::Char('j')).unwrap();
+        assert_eq!(component.selected_column, 1);
+        component.event(Key::Char('l')).unwrap();
+        assert_eq!(component.selected_row.selected(), Some(1));
+        component.event(Key::Char('X')).unwrap();
+        component.event(Key::Char('j')).unwrap();
+        assert_eq!(component.selected_row.selected(), Some(1));
+    }
+
     #[test]
     fn test_calculate_cell_widths_when_sum_of_cell_widths_is_less_than_table_width() {
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["aaaaa", "bbbbb", "ccccc"]
                 .iter()
                 .map(|h| h.to_string())
                 .collect(),
             vec!["d", "e", "f"].iter().map(|h| h.to_string()).collect(),
-        ];
+        ]);
 
         let (selected_column_index, headers, rows, constraints) =
-            component.calculate_cell_widths(20);
+            component.calculate_cell_widths(20, 0..component.values.len());
         assert_eq!(selected_column_index, 1);
         assert_eq!(headers, vec!["", "1", "2", "3"]);
         assert_eq!(
@@ -1066,7 +2557,7 @@ mod test {
     fn test_calculate_cell_widths_when_component_has_multiple_rows() {
         let mut component = TableComponent::new(KeyConfig::default());
         component.headers = vec!["1", "2", "3"].iter().map(|h| h.to_string()).collect();
-        component.rows = vec![
+        component.values = texts(vec![
             vec!["aaaaa", "bbbbb", "ccccc"]
                 .iter()
                 .map(|h| h.to_string())
@@ -1075,10 +2566,10 @@ mod test {
                 .iter()
                 .map(|h| h.to_string())
                 .collect(),
-        ];
+        ]);
 
         let (selected_column_index, headers, rows, constraints) =
-            component.calculate_cell_widths(20);
+            component.calculate_cell_widths(20, 0..component.values.len());
         assert_eq!(selected_column_index, 1);
         assert_eq!(headers, vec!["", "1", "2", "3"]);
         assert_eq!(
@@ -1129,6 +2620,15 @@ mod test {
         let mut order_manager = OrderManager::new();
         assert_eq!(order_manager.generate_header_icons(1), vec![String::new()]);
 
+        // the priority is only shown with more than one column
+        order_manager.add_order(0);
+        assert_eq!(
+            order_manager.generate_header_icons(1),
+            vec!["↑".to_string()]
+        );
+        order_manager.add_order(0);
+        order_manager.add_order(0);
+
         order_manager.add_order(1);
         order_manager.add_order(1);
         order_manager.add_order(2);
@@ -1170,4 +2670,86 @@ mod test {
         order_manager.add_order(1);
         assert_eq!(order_manager.orders, vec![Order::new(3, true)]);
     }
+
+    #[test]
+    fn test_summarize() {
+        let values = [
+            Value::Int(1),
+            Value::Null,
+            Value::Decimal("2.5".parse().unwrap()),
+            Value::Text("3".to_string()),
+            Value::Text("n/a".to_string()),
+        ];
+        assert_eq!(
+            summarize(values.iter()),
+            "count: 4, sum: 6.5, avg: 2.166667, min: 1, max: 3"
+        );
+        assert_eq!(
+            summarize([Value::Text("a".to_string()), Value::Null].iter()),
+            "count: 1"
+        );
+
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = vec!["a".to_string(), "b".to_string()];
+        component.values = vec![
+            vec![Value::Int(1), Value::Int(10)],
+            vec![Value::Int(2), Value::Int(20)],
+        ];
+        assert_eq!(component.selection_summary(), None);
+        component.selected_row.select(Some(0));
+        component.expand_selected_area_y(true);
+        assert_eq!(
+            component.selection_summary(),
+            Some("count: 2, sum: 3, avg: 1.5, min: 1, max: 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_order() {
+        let mut order_manager = OrderManager::new();
+        order_manager.add_order(1);
+        order_manager.add_order(2);
+
+        // replaces the other columns
+        order_manager.set_order(2);
+        assert_eq!(order_manager.orders, vec![Order::new(3, true)]);
+
+        // cycles through descending and no sort
+        order_manager.set_order(2);
+        assert_eq!(order_manager.orders, vec![Order::new(3, false)]);
+        order_manager.set_order(2);
+        assert!(order_manager.orders.is_empty());
+    }
+
+    #[test]
+    fn test_sort_marker() {
+        let ascending = (1..=100)
+            .map(|i| vec![Value::Null, Value::Int(i)])
+            .collect::<Vec<Vec<Value>>>();
+        assert_eq!(value_range(&ascending, 1), Some((0, 99)));
+        assert_eq!(
+            sort_marker(&ascending, 1, (0, 99), 0..10, true, true),
+            "1 ━━────────── 100"
+        );
+
+        // the smallest value is on the left, and more rows may follow it
+        let descending = ascending.into_iter().rev().collect::<Vec<Vec<Value>>>();
+        assert_eq!(
+            sort_marker(&descending, 1, (0, 99), 0..10, false, false),
+            "1… ──────────━━ 100"
+        );
+
+        // text is placed by the position of its row
+        let texts = [None, Some("a"), Some("b"), Some("c"), None]
+            .map(|text| vec![Value::from(text)])
+            .to_vec();
+        assert_eq!(value_range(&texts, 0), Some((1, 3)));
+        assert_eq!(
+            sort_marker(&texts, 0, (1, 3), 2..3, true, true),
+            "a ──────━───── c"
+        );
+
+        assert_eq!(value_range(&[vec![Value::Null]], 0), None);
+        assert_eq!(value_range(&descending, 2), None);
+    }
 }
@@ -5,24 +5,34 @@ use super::{
 use crate::components::command::CommandInfo;
 use crate::config::KeyConfig;
 use crate::event::Key;
+use crate::history::HistoryCursor;
 use crate::tree::Table;
 use anyhow::Result;
 use ratatui::{
     layout::Rect,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 use unicode_width::UnicodeWidthStr;
 
+const KEYWORDS_IN_WHERE_CLAUSE: &[&str] = &[
+    "AND", "OR", "NOT", "IN", "IS", "NULL", "LIKE", "ILIKE", "BETWEEN", "TRUE", "FALSE",
+];
+const SIMPLE_OPERATORS: &[&str] = &["<=", ">=", "!=", "<>", "=", "<", ">"];
+
 pub struct TableFilterComponent {
     key_config: KeyConfig,
     pub table: Option<Table>,
+    /// The columns of the table, which are never quoted on the right side of a comparison.
+    columns: Vec<String>,
     input: Vec<char>,
     input_idx: usize,
     input_cursor_position: u16,
     completion: CompletionComponent,
+    error: Option<String>,
+    history: HistoryCursor,
 }
 
 impl TableFilterComponent {
@@ -30,10 +40,13 @@ impl TableFilterComponent {
         Self {
             key_config: key_config.clone(),
             table: None,
+            columns: Vec::new(),
             input: Vec::new(),
             input_idx: 0,
             input_cursor_position: 0,
             completion: CompletionComponent::new(key_config, "", false),
+            error: None,
+            history: HistoryCursor::default(),
         }
     }
 
@@ -41,11 +54,60 @@ impl TableFilterComponent {
         self.input.iter().collect()
     }
 
+    pub fn set_input(&mut self, input: String) {
+        self.input = input.chars().collect();
+        self.input_idx = self.input.len();
+        self.input_cursor_position = self
+            .input
+            .iter()
+            .copied()
+            .map(compute_character_width)
+            .sum();
+        self.error = None;
+    }
+
+    /// Returns the condition which is put after `WHERE`, or `None` if the input is empty.
+    pub fn query(&self) -> Option<String> {
+        let input = self.input_str();
+        if input.trim().is_empty() {
+            None
+        } else {
+            Some(to_where_clause(input.trim(), &self.columns))
+        }
+    }
+
+    /// Checks the input before sending it to the database.
+    pub fn validate(&self) -> Result<(), String> {
+        self.query()
+            .map_or(Ok(()), |query| validate_where_clause(&query))
+    }
+
+    pub fn set_columns(&mut self, columns: Vec<String>) {
+        self.columns = columns;
+    }
+
+    pub fn set_error(&mut self, error: impl Into<String>) {
+        let error: String = error.into();
+        self.error = Some(error.lines().next().unwrap_or_default().to_string());
+    }
+
+    pub fn clear_error(&mut self) {
+        self.error = None;
+    }
+
     pub fn reset(&mut self) {
         self.table = None;
+        self.columns = Vec::new();
         self.input = Vec::new();
         self.input_idx = 0;
         self.input_cursor_position = 0;
+        self.error = None;
+        self.history = HistoryCursor::default();
+    }
+
+    /// Sets the filters applied to the table before, oldest first.
+    pub fn set_history(&mut self, entries: Vec<String>) {
+        self.history.set_entries(entries);
     }
 
     fn update_completion(&mut self) {
@@ -128,30 +190,149 @@ impl TableFilterComponent {
     }
 }
 
+/// Converts a simple `column op value` expression into a valid condition.
+/// A bare word on the right side is quoted unless it names one of `columns`, possibly
+/// qualified, so that `created_at > updated_at` still compares two columns.
+/// Any other input is regarded as a raw condition and returned as it is.
+fn to_where_clause(input: &str, columns: &[String]) -> String {
+    let (column, rest) = match input.find(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        Some(index) if index > 0 => input.split_at(index),
+        _ => return input.to_string(),
+    };
+    let rest = rest.trim_start();
+    let operator = match SIMPLE_OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+        Some(operator) => *operator,
+        None => return input.to_string(),
+    };
+    let value = rest[operator.len()..].trim();
+    let is_bare_word = !value.is_empty()
+        && !value.starts_with(['\'', '"', '`'])
+        && value
+            .chars()
+            .all(|c| !c.is_whitespace() && !matches!(c, '(' | ')'))
+        && value.parse::<f64>().is_err()
+        && !KEYWORDS_IN_WHERE_CLAUSE.contains(&value.to_uppercase().as_str());
+    let is_column = || {
+        let name = value.rsplit('.').next().unwrap_or(value);
+        columns
+            .iter()
+            .any(|column| column.eq_ignore_ascii_case(name))
+    };
+
+    if !is_bare_word || is_column() {
+        return input.to_string();
+    }
+    format!("{} {} '{}'", column, operator, value.replace('\'', "''"))
+}
+
+fn validate_where_clause(input: &str) -> Result<(), String> {
+    let mut depth = 0_i32;
+    let mut quote: Option<char> = None;
+    for c in input.chars() {
+        match (quote, c) {
+            (Some(q), c) if q == c => quote = None,
+            (Some(_), _) => (),
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth < 0 {
+                    return Err("unexpected `)`".to_string());
+                }
+            }
+            (None, ';') => return Err("`;` is not allowed in a filter".to_string()),
+            _ => (),
+        }
+    }
+    if let Some(q) = quote {
+        return Err(format!("unterminated quote `{}`", q));
+    }
+    if depth > 0 {
+        return Err("missing `)`".to_string());
+    }
+    Ok(())
+}
+
+/// Splits the input into styled spans: keywords, string literals and numbers are highlighted.
+fn highlight(input: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let chars = input.chars().collect::<Vec<char>>();
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let start = index;
+        let style = if matches!(c, '\'' | '"' | '`') {
+            index += 1;
+            while index < chars.len() && chars[index] != c {
+                index += 1;
+            }
+            index = (index + 1).min(chars.len());
+            if c == '\'' {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::Cyan)
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            while index < chars.len() && (chars[index].is_alphanumeric() || chars[index] == '_') {
+                index += 1;
+            }
+            let word = chars[start..index].iter().collect::<String>();
+            if KEYWORDS_IN_WHERE_CLAUSE.contains(&word.to_uppercase().as_str()) {
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD)
+            } else if word.parse::<f64>().is_ok() {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            }
+        } else {
+            index += 1;
+            Style::default()
+        };
+        spans.push(Span::styled(
+            chars[start..index].iter().collect::<String>(),
+            style,
+        ));
+    }
+    spans
+}
+
 impl StatefulDrawableComponent for TableFilterComponent {
     fn draw(&mut self, f: &mut Frame, area: Rect, focused: bool) -> Result<()> {
-        let query = Paragraph::new(Line::from(vec![
+        let mut spans = vec![
             Span::styled(
                 self.table
                     .as_ref()
                     .map_or("-".to_string(), |table| table.name.to_string()),
                 Style::default().fg(Color::Blue),
             ),
-            Span::from(format!(
-                " {}",
-                if focused || !self.input.is_empty() {
-                    self.input.iter().collect::<String>()
-                } else {
-                    "Enter a SQL expression in WHERE clause to filter records".to_string()
-                }
-            )),
-        ]))
-        .style(if focused {
-            Style::default()
+            Span::from(" "),
+        ];
+        if focused || !self.input.is_empty() {
+            spans.extend(highlight(&self.input_str()));
         } else {
-            Style::default().fg(Color::DarkGray)
-        })
-        .block(Block::default().borders(Borders::ALL));
+            spans.push(Span::from(
+                "Enter a SQL expression in WHERE clause to filter records",
+            ));
+        }
+        let block = match &self.error {
+            Some(error) => Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title(Span::styled(
+                    format!(" {} ", error),
+                    Style::default().fg(Color::Red),
+                )),
+            None => Block::default().borders(Borders::ALL),
+        };
+        let query = Paragraph::new(Line::from(spans))
+            .style(if focused {
+                Style::default()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            })
+            .block(block);
         f.render_widget(query, area);
 
         if focused {
@@ -198,7 +379,25 @@ impl Component for TableFilterComponent {
             return self.complete();
         }
 
-        self.completion.selected_candidate();
+        // the arrows choose a candidate while the completion is shown
+        if self.completion.selected_candidate().is_none() {
+            let entry = if key == self.key_config.move_up {
+                self.history.previous(&input_str)
+            } else if key == self.key_config.move_down {
+                self.history.next()
+            } else {
+                None
+            };
+            if let Some(entry) = entry {
+                self.set_input(entry);
+                self.completion.update("");
+                return Ok(EventState::Consumed);
+            }
+        }
+
+        if matches!(key, Key::Char(_) | Key::Delete | Key::Backspace) {
+            self.clear_error();
+        }
 
         match key {
             Key::Char(c) => {
@@ -258,7 +457,7 @@ impl Component for TableFilterComponent {
 
 #[cfg(test)]
 mod test {
-    use super::{KeyConfig, TableFilterComponent};
+    use super::{to_where_clause, validate_where_clause, KeyConfig, TableFilterComponent};
 
     #[test]
     fn test_complete() {
@@ -295,4 +494,51 @@ mod test {
         assert!(filter.complete().is_ok());
         assert_eq!(filter.input, vec!['a', 'n', ' ', 'c', 'd', 'e', 'f', 'g']);
     }
+
+    #[test]
+    fn test_to_where_clause() {
+        let columns = ["name", "created_at", "updated_at"].map(String::from);
+        let to_where_clause = |input| to_where_clause(input, &columns);
+        assert_eq!(to_where_clause("name = foo"), "name = 'foo'");
+        assert_eq!(to_where_clause("name=foo"), "name = 'foo'");
+        assert_eq!(to_where_clause("name != o'neil"), "name != 'o''neil'");
+        assert_eq!(to_where_clause("name ~ '^a'"), "name ~ '^a'");
+        assert_eq!(to_where_clause("id >= 10"), "id >= 10");
+        assert_eq!(to_where_clause("deleted_at IS NULL"), "deleted_at IS NULL");
+        assert_eq!(to_where_clause("name = 'foo'"), "name = 'foo'");
+        assert_eq!(to_where_clause("age = null"), "age = null");
+        assert_eq!(
+            to_where_clause("id = 1 AND name = 'foo'"),
+            "id = 1 AND name = 'foo'"
+        );
+        assert_eq!(
+            to_where_clause("created_at > updated_at"),
+            "created_at > updated_at"
+        );
+        assert_eq!(
+            to_where_clause("created_at > t.Updated_At"),
+            "created_at > t.Updated_At"
+        );
+        assert_eq!(to_where_clause("t.x = y"), "t.x = y");
+    }
+
+    #[test]
+    fn test_validate_where_clause() {
+        assert!(validate_where_clause("id IN (1, 2)").is_ok());
+        assert!(validate_where_clause("name = ';)'").is_ok());
+        assert!(validate_where_clause("name = 'foo").is_err());
+        assert!(validate_where_clause("id IN (1, 2").is_err());
+        assert!(validate_where_clause("id = 1)").is_err());
+        assert!(validate_where_clause("id = 1; DROP TABLE users").is_err());
+    }
+
+    #[test]
+    fn test_query() {
+        let mut filter = TableFilterComponent::new(KeyConfig::default());
+        assert_eq!(filter.query(), None);
+        filter.input = "  ".chars().collect();
+        assert_eq!(filter.query(), None);
+        filter.input = "name = foo ".chars().collect();
+        assert_eq!(filter.query(), Some("name = 'foo'".to_string()));
+    }
 }
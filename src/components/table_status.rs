@@ -14,9 +14,16 @@ use ratatui::{
 #[derive(Default)]
 pub struct TableStatusComponent {
     column_count: Option<usize>,
+    /// The columns on the screen as `(first, last + 1)`, if not all of them fit.
+    visible_columns: Option<(usize, usize)>,
+    /// How many columns from the left stay on the screen while the others scroll.
+    pinned_columns: usize,
     row_count: Option<usize>,
     total_row_count: Option<usize>,
     table: Option<Table>,
+    warning: Option<String>,
+    /// The count, sum, average, minimum and maximum of the selected cells.
+    summary: Option<String>,
 }
 
 impl TableStatusComponent {
@@ -24,47 +31,102 @@ impl TableStatusComponent {
         row_count: Option<usize>,
         total_row_count: Option<usize>,
         column_count: Option<usize>,
+        visible_columns: Option<(usize, usize)>,
+        pinned_columns: usize,
         table: Option<Table>,
+        warning: Option<String>,
     ) -> Self {
         Self {
             row_count,
             total_row_count,
             column_count,
+            visible_columns,
+            pinned_columns,
             table,
+            warning,
+            summary: None,
         }
     }
+
+    pub fn summary(mut self, summary: Option<String>) -> Self {
+        self.summary = summary;
+        self
+    }
 }
 
 impl DrawableComponent for TableStatusComponent {
     fn draw(&self, f: &mut Frame, area: Rect, focused: bool) -> Result<()> {
-        let status = Paragraph::new(Line::from(vec![
+        let mut spans = vec![
             Span::from(format!(
                 "rows: {} / {}, ",
                 self.row_count.map_or("-".to_string(), |c| c.to_string()),
                 self.total_row_count
                     .map_or("-".to_string(), |c| c.to_string()),
             )),
-            Span::from(format!(
-                "columns: {}, ",
-                self.column_count.map_or("-".to_string(), |c| c.to_string())
-            )),
+            Span::from(match (self.visible_columns, self.column_count) {
+                (Some((left, right)), Some(count)) => {
+                    format!(
+                        "columns: {}, ",
+                        columns_text(left, right, count, self.pinned_columns)
+                    )
+                }
+                _ => format!(
+                    "columns: {}, ",
+                    self.column_count.map_or("-".to_string(), |c| c.to_string())
+                ),
+            }),
             Span::from(format!(
                 "engine: {}",
                 self.table.as_ref().map_or("-".to_string(), |c| {
                     c.engine.as_ref().map_or("-".to_string(), |e| e.to_string())
                 })
             )),
-        ]))
-        .block(Block::default().borders(Borders::TOP).style(if focused {
-            Style::default()
-        } else {
-            Style::default().fg(Color::DarkGray)
-        }));
+        ];
+        if let Some(summary) = &self.summary {
+            spans.push(Span::styled(
+                format!(", {}", summary),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if let Some(warning) = &self.warning {
+            spans.push(Span::styled(
+                format!(", {}", warning),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        let status = Paragraph::new(Line::from(spans)).block(
+            Block::default().borders(Borders::TOP).style(if focused {
+                Style::default()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            }),
+        );
         f.render_widget(status, area);
         Ok(())
     }
 }
 
+/// Describes where the columns on the screen are, e.g. `1–2 | ‹ 12–18 of 64 ›` with two pinned
+/// columns, where the arrows tell that more columns are hidden on that side.
+fn columns_text(left: usize, right: usize, count: usize, pinned: usize) -> String {
+    let hidden_left = if left > pinned { "‹ " } else { "" };
+    let hidden_right = if right < count { " ›" } else { "" };
+    let pinned = match pinned {
+        0 => String::new(),
+        1 => "1 | ".to_string(),
+        pinned => format!("1–{} | ", pinned),
+    };
+    format!(
+        "{}{}{}–{} of {}{}",
+        pinned,
+        hidden_left,
+        left + 1,
+        right,
+        count,
+        hidden_right
+    )
+}
+
 impl Component for TableStatusComponent {
     fn commands(&self, _out: &mut Vec<CommandInfo>) {}
 
@@ -72,3 +134,17 @@ impl Component for TableStatusComponent {
         Ok(EventState::NotConsumed)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::columns_text;
+
+    #[test]
+    fn test_columns_text() {
+        assert_eq!(columns_text(0, 7, 64, 0), "1–7 of 64 ›");
+        assert_eq!(columns_text(11, 18, 64, 0), "‹ 12–18 of 64 ›");
+        assert_eq!(columns_text(57, 64, 64, 0), "‹ 58–64 of 64");
+        assert_eq!(columns_text(2, 9, 64, 2), "1–2 | 3–9 of 64 ›");
+        assert_eq!(columns_text(11, 18, 64, 1), "1 | ‹ 12–18 of 64 ›");
+    }
+}
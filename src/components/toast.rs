@@ -0,0 +1,84 @@
+use super::DrawableComponent;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Messages which can be queued from anywhere, e.g. from inside a pool.
+pub type ToastQueue = Arc<Mutex<Vec<String>>>;
+
+/// A short-lived notification in the bottom right corner.
+pub struct ToastComponent {
+    queue: ToastQueue,
+    current: Option<(String, Instant)>,
+}
+
+impl ToastComponent {
+    const WIDTH: u16 = 50;
+    const HEIGHT: u16 = 4;
+    const DURATION: Duration = Duration::from_secs(3);
+
+    pub fn new() -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(Vec::new())),
+            current: None,
+        }
+    }
+
+    pub fn queue(&self) -> ToastQueue {
+        self.queue.clone()
+    }
+
+    pub fn push(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        crate::log!(Info, "{}", message);
+        self.current = Some((message, Instant::now()));
+    }
+
+    /// Shows the latest queued message and hides the current one when it expires.
+    pub fn update(&mut self) {
+        if let Some(message) = self
+            .queue
+            .lock()
+            .ok()
+            .and_then(|mut q| q.drain(..).next_back())
+        {
+            self.push(message);
+        }
+        if matches!(&self.current, Some((_, shown_at)) if shown_at.elapsed() > Self::DURATION) {
+            self.current = None;
+        }
+    }
+}
+
+impl DrawableComponent for ToastComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        if let Some((message, _)) = &self.current {
+            let width = Self::WIDTH.min(f.size().width);
+            let height = Self::HEIGHT.min(f.size().height);
+            let area = Rect::new(
+                f.size().width.saturating_sub(width),
+                f.size().height.saturating_sub(height),
+                width,
+                height,
+            );
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(message.as_str())
+                    .wrap(Wrap { trim: true })
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .style(Style::default().fg(Color::Yellow)),
+                    ),
+                area,
+            );
+        }
+        Ok(())
+    }
+}
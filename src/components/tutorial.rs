@@ -0,0 +1,209 @@
+use super::DrawableComponent;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use anyhow::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// What the app looks like after a key, to tell whether a step of the tutorial is done.
+pub struct TutorialProgress {
+    pub key: Key,
+    pub connected: bool,
+    pub table: Option<String>,
+    pub filtered: bool,
+    pub sorted: bool,
+    pub sql_result: bool,
+    pub table_focused: bool,
+}
+
+/// What the user has to do to finish a step.
+enum Goal {
+    Connect,
+    OpenTable(&'static str),
+    Filter,
+    Sort,
+    RunSql,
+    CopyOrExport,
+    /// The last step, which is only read.
+    None,
+}
+
+struct Step {
+    text: String,
+    goal: Goal,
+}
+
+/// The steps of the guided tour, shown one at a time over the bottom of the screen.
+pub struct TutorialComponent {
+    steps: Vec<Step>,
+    current: usize,
+    key_config: KeyConfig,
+}
+
+impl TutorialComponent {
+    const WIDTH: u16 = 64;
+    const HEIGHT: u16 = 7;
+
+    pub fn new(key_config: KeyConfig) -> Self {
+        let k = &key_config;
+        let steps = vec![
+            Step {
+                text: format!(
+                    "Welcome to zhobo! This tour uses a small company database.\nSelect the tutorial connection and press {} to connect.",
+                    k.enter
+                ),
+                goal: Goal::Connect,
+            },
+            Step {
+                text: format!(
+                    "The tree lists the databases and tables. Move with {}/{}, expand `main` with {} and open the `employees` table with {}.",
+                    k.scroll_down, k.scroll_up, k.enter, k.enter
+                ),
+                goal: Goal::OpenTable("employees"),
+            },
+            Step {
+                text: format!(
+                    "Press {} to move to the rows, then {} to filter them: type `salary > 6000` and press {}.",
+                    k.focus_right, k.filter, k.enter
+                ),
+                goal: Goal::Filter,
+            },
+            Step {
+                text: format!(
+                    "Press {} to go back to the rows, move to a column with {}/{} and press {} to sort by it.",
+                    k.scroll_down, k.scroll_left, k.scroll_right, k.sort_by_column
+                ),
+                goal: Goal::Sort,
+            },
+            Step {
+                text: format!(
+                    "Press {} to open the SQL editor, type `SELECT * FROM departments` and press {} to run it.",
+                    k.tab_sql_editor, k.enter
+                ),
+                goal: Goal::RunSql,
+            },
+            Step {
+                text: format!(
+                    "Move to the result with {}, then copy the selected cell with {} or export all rows to a CSV file with {}.",
+                    k.scroll_down, k.copy, k.export_to_file
+                ),
+                goal: Goal::CopyOrExport,
+            },
+            Step {
+                text: format!(
+                    "That's the tour! Press {} any time to see every key, or {} to quit.",
+                    k.open_help, k.quit
+                ),
+                goal: Goal::None,
+            },
+        ];
+        Self {
+            steps,
+            current: 0,
+            key_config,
+        }
+    }
+
+    /// Moves to the next step if the current one is done.
+    pub fn update(&mut self, progress: &TutorialProgress) {
+        let done = match self.steps.get(self.current).map(|step| &step.goal) {
+            Some(Goal::Connect) => progress.connected,
+            Some(Goal::OpenTable(name)) => progress.table.as_deref() == Some(*name),
+            Some(Goal::Filter) => progress.filtered,
+            Some(Goal::Sort) => progress.sorted,
+            Some(Goal::RunSql) => progress.sql_result,
+            Some(Goal::CopyOrExport) => {
+                progress.table_focused
+                    && (progress.key == self.key_config.copy
+                        || progress.key == self.key_config.export_to_file)
+            }
+            Some(Goal::None) | None => false,
+        };
+        if done {
+            self.current += 1;
+        }
+    }
+}
+
+impl DrawableComponent for TutorialComponent {
+    fn draw(&self, f: &mut Frame, _area: Rect, _focused: bool) -> Result<()> {
+        let step = match self.steps.get(self.current) {
+            Some(step) => step,
+            None => return Ok(()),
+        };
+        let width = Self::WIDTH.min(f.size().width);
+        let height = Self::HEIGHT.min(f.size().height);
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            f.size().height.saturating_sub(height),
+            width,
+            height,
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(step.text.as_str())
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format!(
+                            "Tutorial {}/{}",
+                            self.current + 1,
+                            self.steps.len()
+                        ))
+                        .style(Style::default().fg(Color::Yellow)),
+                )
+                .wrap(Wrap { trim: true }),
+            area,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TutorialComponent, TutorialProgress};
+    use crate::config::KeyConfig;
+    use crate::event::Key;
+
+    fn progress(key: Key) -> TutorialProgress {
+        TutorialProgress {
+            key,
+            connected: true,
+            table: None,
+            filtered: false,
+            sorted: false,
+            sql_result: false,
+            table_focused: true,
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let mut tutorial = TutorialComponent::new(KeyConfig::default());
+        tutorial.update(&progress(Key::Enter));
+        assert_eq!(tutorial.current, 1);
+
+        // the wrong table does not finish the step
+        let mut opened = progress(Key::Enter);
+        opened.table = Some("departments".to_string());
+        tutorial.update(&opened);
+        assert_eq!(tutorial.current, 1);
+        opened.table = Some("employees".to_string());
+        tutorial.update(&opened);
+        assert_eq!(tutorial.current, 2);
+
+        tutorial.current = 5;
+        tutorial.update(&progress(Key::Char('x')));
+        assert_eq!(tutorial.current, 5);
+        tutorial.update(&progress(KeyConfig::default().copy));
+        assert_eq!(tutorial.current, 6);
+
+        // the last step stays until quitting
+        tutorial.update(&progress(KeyConfig::default().copy));
+        assert_eq!(tutorial.current, 6);
+    }
+}
@@ -0,0 +1,637 @@
+use super::script::is_select;
+use super::{
+    Capabilities, DatabaseRole, ExecuteResult, Ping, Pool, Relation, RowStream, SchemaObject,
+    ServerOverview, ServerSession, SessionSetting, TableRow, Value,
+};
+use crate::config::get_app_config_path;
+use crate::tree::{Child, Database, Schema, Table, TableKind};
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// A row of a properties tab which was read before.
+#[derive(Clone)]
+struct CachedRow {
+    fields: Vec<String>,
+    columns: Vec<String>,
+}
+
+impl TableRow for CachedRow {
+    fn fields(&self) -> Vec<String> {
+        self.fields.clone()
+    }
+
+    fn columns(&self) -> Vec<String> {
+        self.columns.clone()
+    }
+}
+
+/// The databases, tables, columns and other metadata of a connection which were read before,
+/// shared between the pool which fills it and the app which clears it on a refresh.
+#[derive(Default)]
+pub struct MetadataCache {
+    databases: Mutex<Option<Vec<Database>>>,
+    tables: Mutex<HashMap<String, Vec<Child>>>,
+    objects: Mutex<Option<Vec<SchemaObject>>>,
+    /// The rows of the properties tabs, keyed by the tab and the table.
+    rows: Mutex<HashMap<(&'static str, String), Vec<CachedRow>>>,
+    definitions: Mutex<HashMap<String, String>>,
+    /// Where the databases and the objects are saved, so that they are not read again when
+    /// connecting next time.
+    path: Option<PathBuf>,
+}
+
+impl MetadataCache {
+    /// Returns a cache which also keeps the databases and the objects of the connection
+    /// identified by `key` on disk.
+    pub fn on_disk(key: &str) -> anyhow::Result<Self> {
+        let directory = get_app_config_path()?.join("metadata");
+        std::fs::create_dir_all(&directory)?;
+        let file_name = key
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let path = directory.join(format!("{}.json", file_name));
+        let cache = Self {
+            path: Some(path.clone()),
+            ..Self::default()
+        };
+        if path.exists() {
+            let stored: StoredMetadata = serde_json::from_str(&std::fs::read_to_string(&path)?)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            *cache.databases.lock().unwrap() = stored
+                .databases
+                .map(|databases| databases.into_iter().map(Database::from).collect());
+            *cache.tables.lock().unwrap() = stored
+                .tables
+                .into_iter()
+                .map(Database::from)
+                .map(|database| (database.name, database.children))
+                .collect();
+            *cache.objects.lock().unwrap() = stored.objects;
+        }
+        Ok(cache)
+    }
+
+    /// Forgets everything, so that it is read again from the database.
+    pub fn clear(&self) {
+        *self.databases.lock().unwrap() = None;
+        self.tables.lock().unwrap().clear();
+        *self.objects.lock().unwrap() = None;
+        self.rows.lock().unwrap().clear();
+        self.definitions.lock().unwrap().clear();
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Forgets the tables of `database` and what was read about them, keeping the other
+    /// databases.
+    pub fn clear_database(&self, database: &str) {
+        self.tables.lock().unwrap().remove(database);
+        *self.objects.lock().unwrap() = None;
+        let prefix = format!("{}.", database);
+        self.rows
+            .lock()
+            .unwrap()
+            .retain(|(_, key), _| !key.starts_with(&prefix));
+        self.definitions
+            .lock()
+            .unwrap()
+            .retain(|key, _| !key.starts_with(&prefix));
+        self.save();
+    }
+
+    /// Forgets the columns, constraints and the other properties of `table`.
+    pub fn clear_table(&self, database: &Database, table: &Table) {
+        let key = table_key(database, table);
+        self.rows.lock().unwrap().retain(|(_, k), _| *k != key);
+        self.definitions.lock().unwrap().remove(&key);
+    }
+
+    fn save(&self) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+        let stored = StoredMetadata {
+            databases: self
+                .databases
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|databases| databases.iter().map(StoredDatabase::from).collect()),
+            tables: self
+                .tables
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, children)| {
+                    StoredDatabase::from(&Database::new(name.clone(), children.clone()))
+                })
+                .collect(),
+            objects: self.objects.lock().unwrap().clone(),
+        };
+        if let Ok(json) = serde_json::to_string(&stored) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+fn table_key(database: &Database, table: &Table) -> String {
+    format!(
+        "{}.{}.{}",
+        database.name,
+        table.schema.as_deref().unwrap_or_default(),
+        table.name
+    )
+}
+
+/// Wraps a pool and answers the metadata queries from `MetadataCache` after the first time.
+/// Any statement which is not a read query may change the schema, so it clears the cache.
+pub struct CachedPool {
+    pool: Box<dyn Pool>,
+    cache: Arc<MetadataCache>,
+}
+
+impl CachedPool {
+    pub fn new(pool: Box<dyn Pool>, cache: Arc<MetadataCache>) -> Self {
+        Self { pool, cache }
+    }
+
+    async fn rows<'a, F, Fut>(
+        &'a self,
+        name: &'static str,
+        database: &Database,
+        table: &Table,
+        f: F,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = anyhow::Result<Vec<Box<dyn TableRow>>>> + Send + 'a,
+    {
+        let key = (name, table_key(database, table));
+        let cached = self.cache.rows.lock().unwrap().get(&key).cloned();
+        let rows = match cached {
+            Some(rows) => rows,
+            None => {
+                let rows = f()
+                    .await?
+                    .into_iter()
+                    .map(|row| CachedRow {
+                        fields: row.fields(),
+                        columns: row.columns(),
+                    })
+                    .collect::<Vec<_>>();
+                self.cache.rows.lock().unwrap().insert(key, rows.clone());
+                rows
+            }
+        };
+        Ok(rows
+            .into_iter()
+            .map(|row| Box::new(row) as Box<dyn TableRow>)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Pool for CachedPool {
+    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+        let result = self.pool.execute(query).await;
+        if !is_select(query) {
+            self.cache.clear();
+        }
+        result
+    }
+
+    async fn execute_params(&self, query: &str, values: &[Value]) -> anyhow::Result<ExecuteResult> {
+        let result = self.pool.execute_params(query, values).await;
+        if !is_select(query) {
+            self.cache.clear();
+        }
+        result
+    }
+
+    fn stream(&self, query: &str) -> Option<RowStream> {
+        self.pool.stream(query)
+    }
+
+    async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
+        let cached = self.cache.databases.lock().unwrap().clone();
+        if let Some(databases) = cached {
+            return Ok(databases);
+        }
+        let databases = self.pool.get_databases().await?;
+        *self.cache.databases.lock().unwrap() = Some(databases.clone());
+        self.cache.save();
+        Ok(databases)
+    }
+
+    async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>> {
+        let cached = self.cache.tables.lock().unwrap().get(&database).cloned();
+        if let Some(tables) = cached {
+            return Ok(tables);
+        }
+        let tables = self.pool.get_tables(database.clone()).await?;
+        self.cache
+            .tables
+            .lock()
+            .unwrap()
+            .insert(database, tables.clone());
+        self.cache.save();
+        Ok(tables)
+    }
+
+    async fn get_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        page: usize,
+        filter: Option<String>,
+        orders: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        self.pool
+            .get_records(database, table, page, filter, orders)
+            .await
+    }
+
+    async fn get_columns(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.rows("columns", database, table, || {
+            self.pool.get_columns(database, table)
+        })
+        .await
+    }
+
+    async fn get_sample(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        self.pool.get_sample(database, table, filter).await
+    }
+
+    async fn get_total_row_count(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<usize> {
+        self.pool.get_total_row_count(database, table, filter).await
+    }
+
+    async fn get_constraints(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.rows("constraints", database, table, || {
+            self.pool.get_constraints(database, table)
+        })
+        .await
+    }
+
+    async fn get_foreign_keys(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.rows("foreign keys", database, table, || {
+            self.pool.get_foreign_keys(database, table)
+        })
+        .await
+    }
+
+    async fn get_primary_key(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        self.pool.get_primary_key(database, table).await
+    }
+
+    async fn get_indexes(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.rows("indexes", database, table, || {
+            self.pool.get_indexes(database, table)
+        })
+        .await
+    }
+
+    async fn get_definition(&self, database: &Database, table: &Table) -> anyhow::Result<String> {
+        let key = table_key(database, table);
+        let cached = self.cache.definitions.lock().unwrap().get(&key).cloned();
+        if let Some(definition) = cached {
+            return Ok(definition);
+        }
+        let definition = self.pool.get_definition(database, table).await?;
+        self.cache
+            .definitions
+            .lock()
+            .unwrap()
+            .insert(key, definition.clone());
+        Ok(definition)
+    }
+
+    async fn get_dependencies(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.rows("dependencies", database, table, || {
+            self.pool.get_dependencies(database, table)
+        })
+        .await
+    }
+
+    async fn get_triggers(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.rows("triggers", database, table, || {
+            self.pool.get_triggers(database, table)
+        })
+        .await
+    }
+
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_statistics(database, table).await
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        self.pool.get_server_overview().await
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        self.pool.get_sessions().await
+    }
+
+    async fn get_privileges(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_privileges(database, table).await
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        self.pool.get_roles().await
+    }
+
+    async fn get_relations(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        self.pool.get_relations(database, table).await
+    }
+
+    async fn get_partitions(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_partitions(database, table).await
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        let cached = self.cache.objects.lock().unwrap().clone();
+        if let Some(objects) = cached {
+            return Ok(objects);
+        }
+        let objects = self.pool.get_objects().await?;
+        *self.cache.objects.lock().unwrap() = Some(objects.clone());
+        self.cache.save();
+        Ok(objects)
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        self.pool.get_session_settings().await
+    }
+
+    async fn begin(&self) -> anyhow::Result<()> {
+        self.pool.begin().await
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        self.pool.commit().await
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        self.pool.rollback().await
+    }
+
+    async fn close(&self) {
+        self.pool.close().await
+    }
+
+    fn ping(&self) -> Ping {
+        self.pool.ping()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.pool.capabilities()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredMetadata {
+    databases: Option<Vec<StoredDatabase>>,
+    /// The tables of the databases which were expanded.
+    #[serde(default)]
+    tables: Vec<StoredDatabase>,
+    objects: Option<Vec<SchemaObject>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredDatabase {
+    name: String,
+    children: Vec<StoredChild>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum StoredChild {
+    Table(StoredTable),
+    Schema {
+        name: String,
+        tables: Vec<StoredTable>,
+    },
+}
+
+/// A table without its creation and update times, which are not worth keeping.
+#[derive(Serialize, Deserialize)]
+struct StoredTable {
+    name: String,
+    engine: Option<String>,
+    schema: Option<String>,
+    kind: TableKind,
+    /// Missing from the caches written before partitions were nested.
+    #[serde(default)]
+    partition_of: Option<String>,
+}
+
+impl From<&Database> for StoredDatabase {
+    fn from(database: &Database) -> Self {
+        Self {
+            name: database.name.clone(),
+            children: database
+                .children
+                .iter()
+                .map(|child| match child {
+                    Child::Table(table) => StoredChild::Table(table.into()),
+                    Child::Schema(schema) => StoredChild::Schema {
+                        name: schema.name.clone(),
+                        tables: schema.tables.iter().map(StoredTable::from).collect(),
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<StoredDatabase> for Database {
+    fn from(database: StoredDatabase) -> Self {
+        Database::new(
+            database.name,
+            database
+                .children
+                .into_iter()
+                .map(|child| match child {
+                    StoredChild::Table(table) => Table::from(table).into(),
+                    StoredChild::Schema { name, tables } => Schema {
+                        name,
+                        tables: tables.into_iter().map(Table::from).collect(),
+                    }
+                    .into(),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<&Table> for StoredTable {
+    fn from(table: &Table) -> Self {
+        Self {
+            name: table.name.clone(),
+            engine: table.engine.clone(),
+            schema: table.schema.clone(),
+            kind: table.kind,
+            partition_of: table.partition_of.clone(),
+        }
+    }
+}
+
+impl From<StoredTable> for Table {
+    fn from(table: StoredTable) -> Self {
+        Self {
+            name: table.name,
+            create_time: None,
+            update_time: None,
+            engine: table.engine,
+            schema: table.schema,
+            kind: table.kind,
+            partition_of: table.partition_of,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{table_key, CachedRow, MetadataCache, StoredDatabase};
+    use crate::tree::{Database, Schema, Table, TableKind};
+
+    fn table(name: &str, kind: TableKind) -> Table {
+        Table {
+            name: name.to_string(),
+            create_time: None,
+            update_time: None,
+            engine: None,
+            schema: Some("public".to_string()),
+            kind,
+            partition_of: None,
+        }
+    }
+
+    #[test]
+    fn test_stored_database() {
+        let database = Database::new(
+            "foo".to_string(),
+            vec![Schema {
+                name: "public".to_string(),
+                tables: vec![
+                    table("bar", TableKind::Table),
+                    table("baz", TableKind::View),
+                    Table {
+                        partition_of: Some("bar".to_string()),
+                        ..table("bar_2024", TableKind::Table)
+                    },
+                ],
+            }
+            .into()],
+        );
+        let json = serde_json::to_string(&StoredDatabase::from(&database)).unwrap();
+        let stored: StoredDatabase = serde_json::from_str(&json).unwrap();
+        assert_eq!(Database::from(stored), database);
+    }
+
+    #[test]
+    fn test_clear_table() {
+        let cache = MetadataCache::default();
+        let database = Database::new("foo".to_string(), vec![]);
+        let bar = table("bar", TableKind::Table);
+        let baz = table("baz", TableKind::Table);
+        let row = CachedRow {
+            fields: vec!["id".to_string()],
+            columns: vec!["name".to_string()],
+        };
+        for table in [&bar, &baz] {
+            cache
+                .rows
+                .lock()
+                .unwrap()
+                .insert(("columns", table_key(&database, table)), vec![row.clone()]);
+        }
+        *cache.databases.lock().unwrap() = Some(vec![database.clone()]);
+
+        cache.clear_table(&database, &bar);
+        let rows = cache
+            .rows
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![("columns", "foo.public.baz".to_string())]);
+        assert!(cache.databases.lock().unwrap().is_some());
+
+        cache
+            .tables
+            .lock()
+            .unwrap()
+            .insert("foo".to_string(), vec![bar.clone().into()]);
+        cache.clear_database("foo");
+        assert!(cache.tables.lock().unwrap().is_empty());
+        assert!(cache.rows.lock().unwrap().is_empty());
+        assert!(cache.databases.lock().unwrap().is_some());
+
+        cache.clear();
+        assert!(cache.rows.lock().unwrap().is_empty());
+        assert!(cache.databases.lock().unwrap().is_none());
+    }
+}
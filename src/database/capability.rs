@@ -0,0 +1,288 @@
+use super::script::skip_comments;
+use std::fmt;
+
+/// The product a connection talks to, which decides what its version supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Server {
+    MySql,
+    MariaDb,
+    Postgres,
+    /// Amazon Redshift, which reports the PostgreSQL version it was forked from.
+    Redshift,
+    /// CockroachDB, whose own version is read instead of the PostgreSQL version it reports.
+    CockroachDb,
+    Sqlite,
+}
+
+impl fmt::Display for Server {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MySql => write!(f, "MySQL"),
+            Self::MariaDb => write!(f, "MariaDB"),
+            Self::Postgres => write!(f, "PostgreSQL"),
+            Self::Redshift => write!(f, "Redshift"),
+            Self::CockroachDb => write!(f, "CockroachDB"),
+            Self::Sqlite => write!(f, "SQLite"),
+        }
+    }
+}
+
+/// A feature which older versions of a server lack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    CommonTableExpressions,
+    WindowFunctions,
+    GeneratedColumns,
+    CheckConstraints,
+    Procedures,
+}
+
+impl Capability {
+    const ALL: [Self; 5] = [
+        Self::CommonTableExpressions,
+        Self::WindowFunctions,
+        Self::GeneratedColumns,
+        Self::CheckConstraints,
+        Self::Procedures,
+    ];
+
+    /// Returns the first version of `server` which has the capability, or `None` if no version
+    /// has it.
+    fn since(self, server: Server) -> Option<Version> {
+        let version = match (self, server) {
+            (Self::CommonTableExpressions, Server::MySql) => Version(8, 0, 1),
+            (Self::CommonTableExpressions, Server::MariaDb) => Version(10, 2, 1),
+            (Self::CommonTableExpressions, Server::Postgres) => Version(8, 4, 0),
+            (Self::CommonTableExpressions, Server::Sqlite) => Version(3, 8, 3),
+            // Redshift reports 8.0.2 but has these since its first release
+            (
+                Self::CommonTableExpressions | Self::WindowFunctions | Self::Procedures,
+                Server::Redshift,
+            ) => Version(0, 0, 0),
+            (Self::WindowFunctions, Server::MySql) => Version(8, 0, 2),
+            (Self::WindowFunctions, Server::MariaDb) => Version(10, 2, 0),
+            (Self::WindowFunctions, Server::Postgres) => Version(8, 4, 0),
+            (Self::WindowFunctions, Server::Sqlite) => Version(3, 25, 0),
+            (Self::GeneratedColumns, Server::MySql) => Version(5, 7, 6),
+            (Self::GeneratedColumns, Server::MariaDb) => Version(10, 2, 5),
+            (Self::GeneratedColumns, Server::Postgres) => Version(12, 0, 0),
+            (Self::GeneratedColumns, Server::Sqlite) => Version(3, 31, 0),
+            (Self::GeneratedColumns | Self::CheckConstraints, Server::Redshift) => return None,
+            (Self::Procedures, Server::CockroachDb) => Version(23, 2, 0),
+            (_, Server::CockroachDb) => Version(0, 0, 0),
+            // information_schema.CHECK_CONSTRAINTS
+            (Self::CheckConstraints, Server::MySql) => Version(8, 0, 16),
+            (Self::CheckConstraints, Server::MariaDb) => Version(10, 2, 22),
+            (Self::CheckConstraints, Server::Postgres | Server::Sqlite) => Version(0, 0, 0),
+            // CREATE PROCEDURE and pg_proc.prokind
+            (Self::Procedures, Server::Postgres) => Version(11, 0, 0),
+            (Self::Procedures, Server::MySql | Server::MariaDb) => Version(0, 0, 0),
+            (Self::Procedures, Server::Sqlite) => return None,
+        };
+        Some(version)
+    }
+
+    /// Returns true if `statement` uses the capability, so that its failure can be explained.
+    fn is_used_by(self, statement: &str) -> bool {
+        let statement = skip_comments(statement).to_uppercase();
+        match self {
+            Self::CommonTableExpressions => statement.starts_with("WITH"),
+            Self::WindowFunctions => statement.contains("OVER (") || statement.contains("OVER("),
+            Self::GeneratedColumns => statement.contains("GENERATED ALWAYS"),
+            Self::CheckConstraints => false,
+            Self::Procedures => statement.contains("PROCEDURE"),
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CommonTableExpressions => write!(f, "common table expressions (WITH)"),
+            Self::WindowFunctions => write!(f, "window functions"),
+            Self::GeneratedColumns => write!(f, "generated columns"),
+            Self::CheckConstraints => write!(f, "check constraints"),
+            Self::Procedures => write!(f, "procedures"),
+        }
+    }
+}
+
+/// A server version, e.g. `Version(8, 0, 32)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub u32, pub u32, pub u32);
+
+impl Version {
+    /// Reads the numbers a version string such as `12.5 (Debian 12.5-1)` or
+    /// `10.6.12-MariaDB-log` starts with.
+    fn parse(version: &str) -> Option<Self> {
+        let numbers = version
+            .trim()
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()?;
+        let mut parts = numbers.split('.').map(|part| part.parse::<u32>().ok());
+        let major = parts.next().flatten()?;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+/// What the server of a connection supports, detected from its version when connecting.
+/// A server whose version is not known is assumed to support everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    server: Option<(Server, Version)>,
+}
+
+impl Capabilities {
+    /// Reads the version string the server reports, e.g. the result of `SELECT VERSION()`.
+    pub fn new(server: Server, version: &str) -> Self {
+        let server = match server {
+            Server::MySql if version.contains("MariaDB") => Server::MariaDb,
+            server => server,
+        };
+        Self {
+            server: Version::parse(version).map(|version| (server, version)),
+        }
+    }
+
+    /// Returns the server, which tells MariaDB from MySQL, or `None` if its version is not known.
+    pub fn server(&self) -> Option<Server> {
+        self.server.map(|(server, _)| server)
+    }
+
+    pub fn supports(&self, capability: Capability) -> bool {
+        match self.server {
+            Some((server, version)) => capability
+                .since(server)
+                .is_some_and(|since| version >= since),
+            None => true,
+        }
+    }
+
+    /// Returns the capabilities which the server lacks but a newer version of it has.
+    pub fn missing(&self) -> Vec<Capability> {
+        let Some((server, _)) = self.server else {
+            return Vec::new();
+        };
+        Capability::ALL
+            .into_iter()
+            .filter(|capability| !self.supports(*capability) && capability.since(server).is_some())
+            .collect()
+    }
+
+    /// Returns why the server does not have `capability`.
+    pub fn unsupported(&self, capability: Capability) -> String {
+        match self.server {
+            Some((server, version)) => match capability.since(server) {
+                Some(since) => format!(
+                    "{} need {} {} or later, but the server is {}",
+                    capability, server, since, version
+                ),
+                None => format!("{} does not support {}", server, capability),
+            },
+            None => format!("the server does not support {}", capability),
+        }
+    }
+
+    /// Explains the failure of `statement` if it uses a feature which the server lacks,
+    /// instead of leaving only the syntax error of the server.
+    pub fn explain(&self, statement: &str, error: anyhow::Error) -> anyhow::Error {
+        match Capability::ALL
+            .into_iter()
+            .find(|capability| !self.supports(*capability) && capability.is_used_by(statement))
+        {
+            Some(capability) => anyhow::anyhow!("{} ({})", self.unsupported(capability), error),
+            None => error,
+        }
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.server {
+            Some((server, version)) => write!(f, "{} {}", server, version),
+            None => write!(f, "an unknown server version"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Capabilities, Capability, Server, Version};
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(Version::parse("8.0.32"), Some(Version(8, 0, 32)));
+        assert_eq!(
+            Version::parse("12.5 (Debian 12.5-1.pgdg100+1)"),
+            Some(Version(12, 5, 0))
+        );
+        assert_eq!(
+            Version::parse("10.6.12-MariaDB-1:10.6.12+maria~ubu2004"),
+            Some(Version(10, 6, 12))
+        );
+        assert_eq!(Version::parse("unknown"), None);
+    }
+
+    #[test]
+    fn test_supports() {
+        let mysql = Capabilities::new(Server::MySql, "5.7.44-log");
+        assert!(!mysql.supports(Capability::CommonTableExpressions));
+        assert!(!mysql.supports(Capability::CheckConstraints));
+        assert!(mysql.supports(Capability::GeneratedColumns));
+        assert_eq!(
+            mysql.missing(),
+            [
+                Capability::CommonTableExpressions,
+                Capability::WindowFunctions,
+                Capability::CheckConstraints
+            ]
+        );
+
+        let mariadb = Capabilities::new(Server::MySql, "10.6.12-MariaDB");
+        assert!(mariadb.missing().is_empty());
+        assert_eq!(mariadb.server(), Some(Server::MariaDb));
+        assert_eq!(mysql.server(), Some(Server::MySql));
+        assert_eq!(mariadb.to_string(), "MariaDB 10.6.12");
+
+        let postgres = Capabilities::new(Server::Postgres, "11.20");
+        assert_eq!(postgres.missing(), [Capability::GeneratedColumns]);
+        assert!(Capabilities::default().supports(Capability::Procedures));
+
+        // no version of SQLite has procedures
+        let sqlite = Capabilities::new(Server::Sqlite, "3.45.1");
+        assert!(!sqlite.supports(Capability::Procedures));
+        assert!(sqlite.missing().is_empty());
+
+        // what Redshift lacks is missing from every version, so nothing is reported
+        let redshift = Capabilities::new(Server::Redshift, "8.0.2");
+        assert!(redshift.supports(Capability::WindowFunctions));
+        assert!(!redshift.supports(Capability::CheckConstraints));
+        assert!(redshift.missing().is_empty());
+
+        let cockroach = Capabilities::new(Server::CockroachDb, "23.1.11");
+        assert_eq!(cockroach.missing(), [Capability::Procedures]);
+    }
+
+    #[test]
+    fn test_explain() {
+        let mysql = Capabilities::new(Server::MySql, "5.7.44");
+        let error = mysql.explain(
+            "-- recent orders\nwith recent AS (SELECT 1) SELECT * FROM recent",
+            anyhow::anyhow!("syntax error"),
+        );
+        assert_eq!(
+            error.to_string(),
+            "common table expressions (WITH) need MySQL 8.0.1 or later, but the server is 5.7.44 (syntax error)"
+        );
+        let error = mysql.explain("SELECT * FROM users", anyhow::anyhow!("syntax error"));
+        assert_eq!(error.to_string(), "syntax error");
+    }
+}
@@ -0,0 +1,1091 @@
+use super::script::skip_comments;
+use super::{
+    format_bytes, format_uptime, hit_ratio, Capabilities, DatabaseRole, Dependency, ExecuteResult,
+    MemoryBudget, Partition, Ping, Pool, Privilege, Relation, RowBuffer, RowSender, RowStream,
+    SchemaObject, ServerOverview, ServerSession, SessionSetting, Statistic, TableRow,
+    Value as CellValue,
+};
+use crate::tree::{Child, Database, Table, TableKind};
+use async_trait::async_trait;
+use reqwest::Url;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// The settings shown in the session panel and the values they can be switched to.
+const SESSION_SETTINGS: [(&str, &[&str]); 2] = [
+    ("session_timezone", &["", "UTC"]),
+    ("join_use_nulls", &["0", "1"]),
+];
+
+/// The format of the metadata, which is read at once.
+const METADATA_FORMAT: &str = "JSONCompact";
+
+/// The format of the rows of a query, a line each after a line with the column names, which
+/// are read as they arrive.
+const ROWS_FORMAT: &str = "JSONCompactEachRowWithNames";
+
+/// A pool for ClickHouse, which talks to its HTTP interface.
+pub struct ClickHousePool {
+    client: reqwest::Client,
+    endpoint: Url,
+    user: String,
+    password: String,
+    limit_size: usize,
+    memory_budget: MemoryBudget,
+}
+
+impl ClickHousePool {
+    pub async fn new(
+        database_url: &str,
+        limit_size: usize,
+        timeout_second: u64,
+        memory_budget: MemoryBudget,
+        session: &BTreeMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        let url = Url::parse(database_url)?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow::anyhow!("clickhouse needs the host in {}", database_url))?;
+        // 8443 is the default port of the HTTPS interface
+        let secure = url
+            .query_pairs()
+            .any(|(name, value)| name == "secure" && value == "true");
+        let scheme = if secure || url.port() == Some(8443) {
+            "https"
+        } else {
+            "http"
+        };
+        let mut endpoint = Url::parse(&format!(
+            "{}://{}:{}/",
+            scheme,
+            host,
+            url.port().unwrap_or(8123)
+        ))?;
+        let database = url.path().trim_start_matches('/');
+        if !database.is_empty() {
+            endpoint.query_pairs_mut().append_pair("database", database);
+        }
+        // the HTTP interface takes settings as parameters of every request
+        for (name, value) in session {
+            endpoint.query_pairs_mut().append_pair(name, value);
+        }
+
+        let pool = Self {
+            client: reqwest::Client::builder()
+                .connect_timeout(Duration::from_secs(timeout_second))
+                .build()?,
+            endpoint,
+            user: percent_decode(url.username()),
+            password: percent_decode(url.password().unwrap_or_default()),
+            limit_size,
+            memory_budget,
+        };
+        pool.send("SELECT 1".to_string()).await?;
+        Ok(pool)
+    }
+
+    /// Returns the request which runs `query`, answered in `format` unless the query has a
+    /// FORMAT clause. It owns what it needs, so that it can be sent from another task.
+    fn request(&self, query: String, format: &str) -> reqwest::RequestBuilder {
+        let mut endpoint = self.endpoint.clone();
+        endpoint
+            .query_pairs_mut()
+            .append_pair("default_format", format);
+        self.client
+            .post(endpoint)
+            .header("X-ClickHouse-User", &self.user)
+            .header("X-ClickHouse-Key", &self.password)
+            .body(query)
+    }
+
+    async fn send(&self, query: String) -> anyhow::Result<reqwest::Response> {
+        send(self.request(query, METADATA_FORMAT)).await
+    }
+
+    /// Runs a read query and returns the column names and the rows.
+    async fn fetch(&self, query: &str) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        let response = self
+            .send(query.trim().trim_end_matches(';').to_string())
+            .await?;
+        let mut result: Value = serde_json::from_str(&response.text().await?)?;
+        let headers = result["meta"]
+            .as_array()
+            .map(|meta| {
+                meta.iter()
+                    .map(|column| column["name"].as_str().unwrap_or_default().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let rows = match result["data"].take() {
+            Value::Array(rows) => rows
+                .into_iter()
+                .map(|row| match row {
+                    Value::Array(values) => values,
+                    _ => Vec::new(),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok((headers, rows))
+    }
+
+    async fn fetch_strings(&self, query: &str) -> anyhow::Result<Vec<Vec<String>>> {
+        let (_, rows) = self.fetch(query).await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|v| convert_value(v).to_string())
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+pub struct Column {
+    name: String,
+    r#type: String,
+    default: String,
+    comment: String,
+}
+
+impl TableRow for Column {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            "name".to_string(),
+            "type".to_string(),
+            "default".to_string(),
+            "comment".to_string(),
+        ]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![
+            self.name.to_string(),
+            self.r#type.to_string(),
+            self.default.to_string(),
+            self.comment.to_string(),
+        ]
+    }
+}
+
+pub struct Constraint {
+    name: String,
+    column_name: String,
+}
+
+impl TableRow for Constraint {
+    fn fields(&self) -> Vec<String> {
+        vec!["name".to_string(), "column_name".to_string()]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![self.name.to_string(), self.column_name.to_string()]
+    }
+}
+
+pub struct Index {
+    name: String,
+    expression: String,
+    r#type: String,
+}
+
+impl TableRow for Index {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            "name".to_string(),
+            "expression".to_string(),
+            "type".to_string(),
+        ]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![
+            self.name.to_string(),
+            self.expression.to_string(),
+            self.r#type.to_string(),
+        ]
+    }
+}
+
+#[async_trait]
+impl Pool for ClickHousePool {
+    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+        let query = query.trim();
+        if is_read(query) {
+            let request = self.request(query.trim_end_matches(';').to_string(), ROWS_FORMAT);
+            let mut reader = RowReader::new(send(request).await?).await?;
+            // the rest of the response is not downloaded once the rows are over the budget
+            let mut records = RowBuffer::new(self.memory_budget.bytes);
+            while let Some(row) = reader.next().await? {
+                if !records.push(row.into_iter().map(convert_value).collect()) {
+                    break;
+                }
+            }
+            return Ok(ExecuteResult::Read {
+                headers: reader.headers,
+                truncated: records.truncated,
+                rows: records.rows,
+                database: Database {
+                    name: "-".to_string(),
+                    children: Vec::new(),
+                },
+                table: Box::new(Table {
+                    name: "-".to_string(),
+                    create_time: None,
+                    update_time: None,
+                    engine: None,
+                    schema: None,
+                    kind: TableKind::Table,
+                    partition_of: None,
+                }),
+            });
+        }
+
+        let response = self.send(query.to_string()).await?;
+        // e.g. {"read_rows":"0","written_rows":"1",...}
+        let updated_rows = response
+            .headers()
+            .get("X-ClickHouse-Summary")
+            .and_then(|summary| summary.to_str().ok())
+            .and_then(|summary| serde_json::from_str::<Value>(summary).ok())
+            .and_then(|summary| summary["written_rows"].as_str()?.parse().ok())
+            .unwrap_or_default();
+        Ok(ExecuteResult::Write { updated_rows })
+    }
+
+    async fn execute_params(
+        &self,
+        _query: &str,
+        _values: &[CellValue],
+    ) -> anyhow::Result<ExecuteResult> {
+        // the HTTP interface only binds parameters written as {name:Type}
+        anyhow::bail!("Query parameters are not supported for ClickHouse")
+    }
+
+    fn stream(&self, query: &str) -> Option<RowStream> {
+        let query = query.trim().trim_end_matches(';').to_string();
+        if !is_read(&query) {
+            return None;
+        }
+        let request = self.request(query, ROWS_FORMAT);
+        let (mut sender, stream) = RowSender::channel(self.memory_budget);
+        tokio::spawn(async move {
+            let result = async {
+                let mut reader = RowReader::new(send(request).await?).await?;
+                while let Some(row) = reader.next().await? {
+                    let row = row.into_iter().map(convert_value).collect();
+                    if !sender.push(row, || reader.headers.clone()).await {
+                        break;
+                    }
+                }
+                Ok::<_, anyhow::Error>(())
+            }
+            .await;
+            sender.finish(result).await;
+        });
+        Some(stream)
+    }
+
+    async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
+        let databases = self
+            .fetch_strings("SELECT name FROM system.databases ORDER BY name")
+            .await?;
+        let databases = databases
+            .into_iter()
+            .filter_map(|row| row.into_iter().next())
+            .collect();
+        Ok(databases
+            .into_iter()
+            .map(|name| Database::new(name, Vec::new()))
+            .collect())
+    }
+
+    async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>> {
+        let query = format!(
+            "SELECT name, engine FROM system.tables WHERE database = {} ORDER BY name",
+            quote(&database)
+        );
+        let tables = self
+            .fetch_strings(&query)
+            .await?
+            .into_iter()
+            .map(|row| Table {
+                name: row.first().cloned().unwrap_or_default(),
+                create_time: None,
+                update_time: None,
+                kind: match row.get(1).map(String::as_str) {
+                    Some("View") => TableKind::View,
+                    Some("MaterializedView") => TableKind::MaterializedView,
+                    _ => TableKind::Table,
+                },
+                engine: row.get(1).cloned(),
+                schema: None,
+                partition_of: None,
+            })
+            .collect::<Vec<Table>>();
+        Ok(tables.into_iter().map(|table| table.into()).collect())
+    }
+
+    async fn get_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        page: usize,
+        filter: Option<String>,
+        orders: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<CellValue>>)> {
+        let query = format!(
+            "SELECT * FROM `{database}`.`{table}`{filter}{orders} LIMIT {page}, {limit}",
+            database = database.name,
+            table = table.name,
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+            orders = orders.map_or(String::new(), |orders| format!(" {}", orders)),
+            page = page,
+            limit = self.limit_size,
+        );
+        let (headers, rows) = self.fetch(&query).await?;
+        Ok((
+            headers,
+            rows.into_iter()
+                .map(|row| row.into_iter().map(convert_value).collect())
+                .collect(),
+        ))
+    }
+
+    async fn get_sample(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<CellValue>>)> {
+        let query = format!(
+            "SELECT * FROM `{database}`.`{table}`{filter} ORDER BY rand() LIMIT {limit}",
+            database = database.name,
+            table = table.name,
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+            limit = self.limit_size,
+        );
+        self.execute(&query).await?.into_records()
+    }
+
+    async fn get_total_row_count(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<usize> {
+        let query = format!(
+            "SELECT count() FROM `{database}`.`{table}`{filter}",
+            database = database.name,
+            table = table.name,
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+        );
+        let rows = self.fetch_strings(&query).await?;
+        Ok(rows
+            .first()
+            .and_then(|row| row.first())
+            .and_then(|count| count.parse().ok())
+            .unwrap_or_default())
+    }
+
+    async fn get_columns(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let query = format!(
+            "SELECT name, type, default_expression, comment FROM system.columns WHERE database = {} AND table = {} ORDER BY position",
+            quote(&database.name),
+            quote(&table.name)
+        );
+        let mut columns: Vec<Box<dyn TableRow>> = vec![];
+        for row in self.fetch_strings(&query).await? {
+            let mut row = row.into_iter();
+            columns.push(Box::new(Column {
+                name: row.next().unwrap_or_default(),
+                r#type: row.next().unwrap_or_default(),
+                default: row.next().unwrap_or_default(),
+                comment: row.next().unwrap_or_default(),
+            }))
+        }
+        Ok(columns)
+    }
+
+    async fn get_constraints(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // ClickHouse has no unique constraints, so show the primary and sorting keys instead
+        let query = format!(
+            "SELECT if(is_in_primary_key, 'PRIMARY KEY', 'SORTING KEY'), name FROM system.columns WHERE database = {} AND table = {} AND (is_in_primary_key OR is_in_sorting_key) ORDER BY position",
+            quote(&database.name),
+            quote(&table.name)
+        );
+        let mut constraints: Vec<Box<dyn TableRow>> = vec![];
+        for row in self.fetch_strings(&query).await? {
+            let mut row = row.into_iter();
+            constraints.push(Box::new(Constraint {
+                name: row.next().unwrap_or_default(),
+                column_name: row.next().unwrap_or_default(),
+            }))
+        }
+        Ok(constraints)
+    }
+
+    async fn get_foreign_keys(
+        &self,
+        _database: &Database,
+        _table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // ClickHouse does not support foreign keys
+        Ok(vec![])
+    }
+
+    async fn get_primary_key(
+        &self,
+        _database: &Database,
+        _table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        // the primary key of ClickHouse sorts the rows but does not make them unique
+        Ok(vec![])
+    }
+
+    async fn get_indexes(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let query = format!(
+            "SELECT name, expr, type FROM system.data_skipping_indices WHERE database = {} AND table = {}",
+            quote(&database.name),
+            quote(&table.name)
+        );
+        let mut indexes: Vec<Box<dyn TableRow>> = vec![];
+        for row in self.fetch_strings(&query).await? {
+            let mut row = row.into_iter();
+            indexes.push(Box::new(Index {
+                name: row.next().unwrap_or_default(),
+                expression: row.next().unwrap_or_default(),
+                r#type: row.next().unwrap_or_default(),
+            }))
+        }
+        Ok(indexes)
+    }
+
+    async fn get_dependencies(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // ClickHouse has no foreign keys or routines, only views which select from the table
+        let query = format!(
+            "SELECT lower(engine), concat(database, '.', name), '' FROM system.tables WHERE engine IN ('View', 'MaterializedView', 'LiveView') AND position(create_table_query, {}) > 0 ORDER BY name",
+            quote(&format!("{}.{}", database.name, table.name))
+        );
+        let mut dependencies: Vec<Box<dyn TableRow>> = vec![];
+        for row in self.fetch_strings(&query).await? {
+            let mut row = row.into_iter();
+            dependencies.push(Box::new(Dependency {
+                kind: row.next().unwrap_or_default(),
+                name: row.next().unwrap_or_default(),
+                detail: row.next().unwrap_or_default(),
+            }))
+        }
+        Ok(dependencies)
+    }
+
+    async fn get_triggers(
+        &self,
+        _database: &Database,
+        _table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // ClickHouse has no triggers
+        Ok(vec![])
+    }
+
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let query = format!(
+            "SELECT engine, toString(total_rows), toString(total_bytes) FROM system.tables WHERE database = {} AND name = {}",
+            quote(&database.name),
+            quote(&table.name)
+        );
+        let row = self
+            .fetch_strings(&query)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let field = |index: usize| row.get(index).filter(|value| *value != "NULL").cloned();
+        Ok(Statistic::rows(vec![
+            (
+                "rows (exact)",
+                self.get_total_row_count(database, table, None)
+                    .await
+                    .ok()
+                    .map(|count| count.to_string()),
+            ),
+            ("engine", field(0)),
+            (
+                "data size",
+                field(2)
+                    .and_then(|bytes| bytes.parse().ok())
+                    .map(format_bytes),
+            ),
+        ]))
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        let query = "
+        SELECT
+            version(),
+            toString(uptime()),
+            toString((SELECT sum(value) FROM system.metrics
+                WHERE metric IN ('TCPConnection', 'HTTPConnection', 'MySQLConnection'))),
+            toString((SELECT count() FROM system.processes)),
+            toString((SELECT sum(value) FROM system.events WHERE event = 'MarkCacheHits')),
+            toString((SELECT sum(value) FROM system.events WHERE event = 'MarkCacheMisses'))
+        ";
+        let row = self
+            .fetch_strings(query)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let field = |index: usize| row.get(index).filter(|value| *value != "NULL").cloned();
+        let number = |index: usize| field(index).and_then(|value| value.parse::<u64>().ok());
+        let largest_tables = self
+            .fetch_strings(&format!(
+                "
+        SELECT concat(database, '.', name), toString(total_bytes)
+        FROM system.tables
+        WHERE database NOT IN ('system', 'INFORMATION_SCHEMA', 'information_schema')
+            AND total_bytes IS NOT NULL
+        ORDER BY total_bytes DESC
+        LIMIT {}
+        ",
+                ServerOverview::LARGEST_TABLES
+            ))
+            .await?
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [name, size] => Some((name.clone(), size.parse().ok()?)),
+                _ => None,
+            })
+            .collect();
+        Ok(ServerOverview::new(
+            vec![
+                (
+                    "server version",
+                    field(0).map(|version| format!("ClickHouse {}", version)),
+                ),
+                ("uptime", number(1).map(format_uptime)),
+                ("connections", field(2)),
+                ("running queries", field(3)),
+                (
+                    "mark cache hit ratio",
+                    number(4)
+                        .zip(number(5))
+                        .and_then(|(hits, misses)| hit_ratio(hits, misses)),
+                ),
+            ],
+            largest_tables,
+        ))
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        // a query is the closest to a session, and the one listing them is left out
+        let query = "
+        SELECT query_id, user, current_database, toString(toUInt64(elapsed)), query
+        FROM system.processes
+        WHERE query_id != queryID()
+        ORDER BY elapsed DESC
+        ";
+        Ok(self
+            .fetch_strings(query)
+            .await?
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [id, user, database, elapsed, query] => Some(ServerSession {
+                    id: id.clone(),
+                    user: user.clone(),
+                    database: Some(database.clone()).filter(|database| !database.is_empty()),
+                    state: "running".to_string(),
+                    elapsed: elapsed.parse().ok(),
+                    query: Some(query.clone()),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn get_privileges(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // a grant without a database or a table covers all of them
+        let query = format!(
+            "
+        SELECT
+            ifNull(user_name, role_name),
+            toString(access_type),
+            multiIf(table IS NOT NULL, 'table', database IS NOT NULL, 'database', 'global'),
+            toString(grant_option)
+        FROM system.grants
+        WHERE is_partial_revoke = 0
+            AND (database IS NULL OR database = {})
+            AND (table IS NULL OR table = {})
+            AND column IS NULL
+        ORDER BY 1, 2
+        ",
+            quote(&database.name),
+            quote(&table.name)
+        );
+        Ok(self
+            .fetch_strings(&query)
+            .await?
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [grantee, privilege, level, grantable] => Some(Box::new(Privilege {
+                    grantee: grantee.clone(),
+                    privilege: privilege.clone(),
+                    level: level.clone(),
+                    grantable: grantable == "1",
+                })
+                    as Box<dyn TableRow>),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        // ClickHouse has no superuser, only the grants listed in the privileges tab
+        let query = "
+        SELECT name, 1 FROM system.users
+        UNION ALL
+        SELECT name, 0 FROM system.roles
+        ORDER BY 1
+        ";
+        let mut member_of: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for row in self
+            .fetch_strings(
+                "SELECT ifNull(user_name, role_name), granted_role_name FROM system.role_grants ORDER BY 2",
+            )
+            .await?
+        {
+            if let [member, role] = row.as_slice() {
+                member_of
+                    .entry(member.clone())
+                    .or_default()
+                    .push(role.clone());
+            }
+        }
+        Ok(self
+            .fetch_strings(query)
+            .await?
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [name, is_user] => Some(DatabaseRole {
+                    member_of: member_of.remove(name).unwrap_or_default(),
+                    name: name.clone(),
+                    can_login: is_user == "1",
+                    superuser: false,
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn get_relations(
+        &self,
+        _database: &Database,
+        _table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        // ClickHouse does not support foreign keys
+        Ok(vec![])
+    }
+
+    async fn get_partitions(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // the parts of a table without a partition key are all in the partition `all`
+        let query = format!(
+            "
+        SELECT
+            partition,
+            (SELECT partition_key FROM system.tables WHERE database = {0} AND name = {1}),
+            toString(sum(rows)),
+            toString(sum(bytes_on_disk))
+        FROM system.parts
+        WHERE active AND database = {0} AND table = {1} AND partition_id <> 'all'
+        GROUP BY partition
+        ORDER BY partition
+        ",
+            quote(&database.name),
+            quote(&table.name)
+        );
+        Ok(self
+            .fetch_strings(&query)
+            .await?
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [partition, key, rows, size] => Some(Box::new(Partition {
+                    name: partition.clone(),
+                    bounds: format!("{} = {}", key, partition),
+                    rows: rows.parse().ok(),
+                    size: size.parse().ok(),
+                }) as Box<dyn TableRow>),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        let query = "
+        SELECT if(engine = 'View', 'view', 'table'), database, '', name FROM system.tables
+        UNION ALL
+        SELECT 'column', database, table, name FROM system.columns
+        ";
+        Ok(self
+            .fetch_strings(query)
+            .await?
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [kind, database, table, name] => Some(SchemaObject {
+                    kind: kind.clone(),
+                    database: database.clone(),
+                    schema: None,
+                    table: Some(table.clone()).filter(|table| !table.is_empty()),
+                    name: name.clone(),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        let query = format!(
+            "SELECT name, value FROM system.settings WHERE name IN ({})",
+            SESSION_SETTINGS
+                .iter()
+                .map(|(name, _)| quote(name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let rows = self
+            .fetch_strings(&query)
+            .await?
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [name, value] => Some((name.clone(), value.clone())),
+                _ => None,
+            })
+            .collect();
+        Ok(SessionSetting::from_rows(rows, &SESSION_SETTINGS))
+    }
+
+    async fn begin(&self) -> anyhow::Result<()> {
+        anyhow::bail!("ClickHouse does not support transactions")
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        anyhow::bail!("ClickHouse does not support transactions")
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        anyhow::bail!("ClickHouse does not support transactions")
+    }
+
+    async fn close(&self) {}
+
+    fn ping(&self) -> Ping {
+        let request = self.request("SELECT 1".to_string(), METADATA_FORMAT);
+        Box::pin(async move {
+            send(request).await?;
+            Ok(())
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // none of the capabilities depend on the version of ClickHouse
+        Capabilities::default()
+    }
+
+    async fn get_definition(&self, database: &Database, table: &Table) -> anyhow::Result<String> {
+        let query = format!("SHOW CREATE TABLE `{}`.`{}`", database.name, table.name);
+        let rows = self.fetch_strings(&query).await?;
+        Ok(rows
+            .into_iter()
+            .next()
+            .and_then(|row| row.into_iter().next())
+            .unwrap_or_default())
+    }
+}
+
+/// Quotes `value` as a ClickHouse string literal.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Converts a value of `JSONCompact`, in which 64 bit integers and decimals are quoted.
+fn convert_value(value: Value) -> CellValue {
+    match value {
+        Value::Null => CellValue::Null,
+        Value::Bool(value) => CellValue::Bool(value),
+        Value::Number(number) => {
+            if let Some(value) = number.as_i64() {
+                CellValue::Int(value)
+            } else if let Some(value) = number.as_u64() {
+                CellValue::UInt(value)
+            } else {
+                CellValue::Float(number.as_f64().unwrap_or_default())
+            }
+        }
+        Value::String(value) => CellValue::Text(value),
+        Value::Array(values) => CellValue::Array(values.into_iter().map(convert_value).collect()),
+        value => CellValue::Json(value),
+    }
+}
+
+/// Decodes the `%XX` escapes of the user info of a URL.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Sends `request`, failing with the message of the server if it was not run.
+async fn send(request: reqwest::RequestBuilder) -> anyhow::Result<reqwest::Response> {
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("{}", response.text().await?.trim()));
+    }
+    Ok(response)
+}
+
+/// Reads the rows of a response in `ROWS_FORMAT` a line at a time as they arrive, so that a
+/// large result is not held in memory as a whole before the memory budget applies.
+struct RowReader {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+    headers: Vec<String>,
+}
+
+impl RowReader {
+    async fn new(response: reqwest::Response) -> anyhow::Result<Self> {
+        let mut reader = Self {
+            response,
+            buffer: Vec::new(),
+            headers: Vec::new(),
+        };
+        if let Some(line) = reader.next_line().await? {
+            reader.headers = parse_line(&line)?;
+        }
+        Ok(reader)
+    }
+
+    /// Returns the next row, or `None` after the last one.
+    async fn next(&mut self) -> anyhow::Result<Option<Vec<Value>>> {
+        match self.next_line().await? {
+            Some(line) => Ok(Some(parse_line(&line)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn next_line(&mut self) -> anyhow::Result<Option<Vec<u8>>> {
+        loop {
+            if let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+                let line = self.buffer.drain(..=end).collect::<Vec<u8>>();
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                return Ok(Some(line));
+            }
+            match self.response.chunk().await? {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None if self.buffer.trim_ascii().is_empty() => return Ok(None),
+                None => return Ok(Some(std::mem::take(&mut self.buffer))),
+            }
+        }
+    }
+}
+
+/// Parses a line of `ROWS_FORMAT`. An error after the first rows is written into the body
+/// instead of a line, so it is shown as it is.
+fn parse_line<T: serde::de::DeserializeOwned>(line: &[u8]) -> anyhow::Result<T> {
+    serde_json::from_slice(line)
+        .map_err(|_| anyhow::anyhow!("{}", String::from_utf8_lossy(line).trim()))
+}
+
+/// Returns true for a query which answers with rows, looking past the comments it starts with,
+/// e.g. the tag of `query_tag`.
+fn is_read(query: &str) -> bool {
+    let upper = skip_comments(query).to_uppercase();
+    ["SELECT", "WITH", "SHOW", "DESCRIBE", "EXPLAIN"]
+        .iter()
+        .any(|keyword| upper.starts_with(keyword))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{convert_value, percent_decode, quote, CellValue, ClickHousePool};
+    use crate::database::{row_size, ExecuteResult, MemoryBudget, Pool, StreamedRows};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answers every request with `body`, like the HTTP interface of ClickHouse, and returns
+    /// the URL to connect to it.
+    async fn serve(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("clickhouse://default@{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 1024];
+                    // reads the headers and as much of the body as they announce
+                    loop {
+                        match socket.read(&mut buffer).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(read) => request.extend_from_slice(&buffer[..read]),
+                        }
+                        let text = String::from_utf8_lossy(&request).to_lowercase();
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let length = text[..end]
+                                .lines()
+                                .find_map(|line| line.strip_prefix("content-length:"))
+                                .and_then(|length| length.trim().parse::<usize>().ok())
+                                .unwrap_or_default();
+                            if request.len() >= end + 4 + length {
+                                break;
+                            }
+                        }
+                    }
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_comments() {
+        let url = serve("[\"id\"]\n[1]\n[2]\n").await;
+        let pool = ClickHousePool::new(
+            &url,
+            100,
+            5,
+            MemoryBudget {
+                bytes: usize::MAX,
+                spill: false,
+            },
+            &BTreeMap::new(),
+        )
+        .await
+        .unwrap();
+        for query in ["/* zhobo */ SELECT id FROM t", "-- ids\nselect id FROM t"] {
+            match pool.execute(&query.to_string()).await.unwrap() {
+                ExecuteResult::Read { headers, rows, .. } => {
+                    assert_eq!(headers, vec!["id".to_string()]);
+                    assert_eq!(rows, vec![vec![CellValue::Int(1)], vec![CellValue::Int(2)]]);
+                }
+                ExecuteResult::Write { .. } => panic!("{} was run as a write", query),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rows_stop_at_memory_budget() {
+        let url = serve("[\"id\"]\n[1]\n[2]\n[3]").await;
+        let memory_budget = MemoryBudget {
+            bytes: row_size(&[CellValue::Int(1)]) * 2,
+            spill: false,
+        };
+        let pool = ClickHousePool::new(&url, 100, 5, memory_budget, &BTreeMap::new())
+            .await
+            .unwrap();
+        match pool.execute(&"SELECT id FROM t".to_string()).await.unwrap() {
+            ExecuteResult::Read {
+                rows, truncated, ..
+            } => {
+                assert_eq!(rows.len(), 2);
+                assert!(truncated);
+            }
+            ExecuteResult::Write { .. } => panic!("the SELECT was run as a write"),
+        }
+
+        let mut stream = pool.stream("SELECT id FROM t").unwrap();
+        let mut rows = Vec::new();
+        while let Some(message) = stream.recv().await {
+            match message.unwrap() {
+                StreamedRows::Rows {
+                    headers,
+                    rows: part,
+                } => {
+                    assert_eq!(headers, vec!["id".to_string()]);
+                    rows.extend(part);
+                }
+                StreamedRows::Done { truncated, .. } => assert!(truncated),
+            }
+        }
+        assert_eq!(rows, vec![vec![CellValue::Int(1)], vec![CellValue::Int(2)]]);
+        assert!(pool.stream("INSERT INTO t VALUES (1)").is_none());
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("foo"), "'foo'");
+        assert_eq!(quote("it's"), "'it\\'s'");
+        assert_eq!(quote("a\\b"), "'a\\\\b'");
+    }
+
+    #[test]
+    fn test_convert_value() {
+        assert_eq!(convert_value(json!(null)).to_string(), "NULL");
+        assert_eq!(convert_value(json!("foo")).to_string(), "foo");
+        assert_eq!(convert_value(json!(1.5)).to_string(), "1.5");
+        assert_eq!(convert_value(json!(1)), CellValue::Int(1));
+        assert_eq!(convert_value(json!([1, 2])).to_string(), "1,2");
+        assert_eq!(convert_value(json!({"a": 1})).to_string(), "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("p%40ss%3Aw%2Frd"), "p@ss:w/rd");
+        assert_eq!(percent_decode("%E3%81%82%"), "あ%");
+        assert_eq!(percent_decode("100%zz"), "100%zz");
+    }
+}
@@ -0,0 +1,439 @@
+use super::{Dialect, Value};
+use crate::tree::{Database, Table};
+
+/// The actions which can be taken on the referencing rows when a referenced row changes.
+pub const REFERENTIAL_ACTIONS: [&str; 5] = [
+    "NO ACTION",
+    "RESTRICT",
+    "CASCADE",
+    "SET NULL",
+    "SET DEFAULT",
+];
+
+pub struct ForeignKey {
+    pub name: String,
+    pub columns: Vec<String>,
+    /// `table` or `schema.table`
+    pub ref_table: String,
+    pub ref_columns: Vec<String>,
+    pub on_delete: &'static str,
+    pub on_update: &'static str,
+}
+
+pub fn quote_identifier(dialect: Dialect, name: &str) -> String {
+    match dialect {
+        Dialect::MySql | Dialect::ClickHouse => format!("`{}`", name.replace('`', "``")),
+        Dialect::Postgres | Dialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// Returns the name of `table` as it is written in a statement on the current connection.
+pub fn qualified_table(dialect: Dialect, database: &Database, table: &Table) -> String {
+    match (dialect, &table.schema) {
+        (Dialect::Postgres, Some(schema)) => format!(
+            "{}.{}",
+            quote_identifier(dialect, schema),
+            quote_identifier(dialect, &table.name)
+        ),
+        (Dialect::MySql | Dialect::ClickHouse, _) => format!(
+            "{}.{}",
+            quote_identifier(dialect, &database.name),
+            quote_identifier(dialect, &table.name)
+        ),
+        _ => quote_identifier(dialect, &table.name),
+    }
+}
+
+/// Writes `value` as a literal of the dialect, e.g. `'it''s'` or `NULL`.
+pub fn literal(dialect: Dialect, value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(value) => if *value { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Float(float) if !float.is_finite() => non_finite(dialect, *float),
+        Value::Int(_) | Value::UInt(_) | Value::Float(_) | Value::Decimal(_) => value.to_string(),
+        Value::Bytes(bytes) => {
+            let hex = bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>();
+            match dialect {
+                Dialect::Postgres => format!("'\\x{}'::bytea", hex),
+                Dialect::ClickHouse => format!("unhex('{}')", hex),
+                Dialect::MySql | Dialect::Sqlite => format!("X'{}'", hex),
+            }
+        }
+        Value::Array(values) if matches!(dialect, Dialect::Postgres | Dialect::ClickHouse) => {
+            let values = values
+                .iter()
+                .map(|value| literal(dialect, value))
+                .collect::<Vec<String>>()
+                .join(", ");
+            match dialect {
+                // an empty `ARRAY[]` has no type to infer, while `'{}'` takes the column's
+                Dialect::Postgres if values.is_empty() => "'{}'".to_string(),
+                Dialect::Postgres => format!("ARRAY[{}]", values),
+                _ => format!("[{}]", values),
+            }
+        }
+        value => {
+            let text = value.to_string().replace('\'', "''");
+            match dialect {
+                // a backslash starts an escape sequence in their strings
+                Dialect::MySql | Dialect::ClickHouse => format!("'{}'", text.replace('\\', "\\\\")),
+                Dialect::Postgres | Dialect::Sqlite => format!("'{}'", text),
+            }
+        }
+    }
+}
+
+/// Writes NaN or an infinity, which have no numeric literal in SQL.
+fn non_finite(dialect: Dialect, float: f64) -> String {
+    let (postgres, clickhouse) = if float.is_nan() {
+        ("NaN", "nan")
+    } else if float > 0.0 {
+        ("Infinity", "inf")
+    } else {
+        ("-Infinity", "-inf")
+    };
+    match dialect {
+        Dialect::Postgres => format!("'{}'::float8", postgres),
+        Dialect::ClickHouse => clickhouse.to_string(),
+        // SQLite reads a literal beyond the range of a double as an infinity
+        Dialect::Sqlite if !float.is_nan() => {
+            format!("{}9e999", if float > 0.0 { "" } else { "-" })
+        }
+        // MySQL cannot store either, and SQLite stores a NaN as NULL
+        Dialect::MySql | Dialect::Sqlite => "NULL".to_string(),
+    }
+}
+
+/// Returns the condition which selects a row by the values of its key, e.g.
+/// `"id" = 1 AND "region" = 'eu'`.
+pub fn key_condition(dialect: Dialect, key: &[(String, Value)]) -> String {
+    key.iter()
+        .map(|(column, value)| match value {
+            Value::Null => format!("{} IS NULL", quote_identifier(dialect, column)),
+            value => format!(
+                "{} = {}",
+                quote_identifier(dialect, column),
+                literal(dialect, value)
+            ),
+        })
+        .collect::<Vec<String>>()
+        .join(" AND ")
+}
+
+pub fn delete_row(
+    dialect: Dialect,
+    database: &Database,
+    table: &Table,
+    key: &[(String, Value)],
+) -> String {
+    let table = qualified_table(dialect, database, table);
+    let condition = key_condition(dialect, key);
+    match dialect {
+        Dialect::ClickHouse => format!("ALTER TABLE {} DELETE WHERE {};", table, condition),
+        _ => format!("DELETE FROM {} WHERE {};", table, condition),
+    }
+}
+
+/// Writes one `INSERT` statement for each of `rows`, whose values are in the order of
+/// `columns`. `table` is the name as it is written in a statement.
+pub fn insert(dialect: Dialect, table: &str, columns: &[String], rows: &[Vec<Value>]) -> String {
+    let columns = quote_list(dialect, columns);
+    rows.iter()
+        .map(|row| {
+            format!(
+                "INSERT INTO {} ({}) VALUES ({});",
+                table,
+                columns,
+                row.iter()
+                    .map(|value| literal(dialect, value))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn quote_list(dialect: Dialect, names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| quote_identifier(dialect, name))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn add_foreign_key(
+    dialect: Dialect,
+    database: &Database,
+    table: &Table,
+    foreign_key: &ForeignKey,
+) -> anyhow::Result<String> {
+    match dialect {
+        Dialect::Sqlite => anyhow::bail!("SQLite cannot add a foreign key to an existing table"),
+        Dialect::ClickHouse => anyhow::bail!("ClickHouse does not support foreign keys"),
+        Dialect::MySql | Dialect::Postgres => (),
+    }
+    if foreign_key.columns.is_empty() {
+        anyhow::bail!("pick at least one column");
+    }
+    if foreign_key.ref_table.trim().is_empty() {
+        anyhow::bail!("the referenced table is empty");
+    }
+    if foreign_key.columns.len() != foreign_key.ref_columns.len() {
+        anyhow::bail!(
+            "{} columns reference {} columns",
+            foreign_key.columns.len(),
+            foreign_key.ref_columns.len()
+        );
+    }
+    let ref_table = foreign_key
+        .ref_table
+        .trim()
+        .split('.')
+        .map(|part| quote_identifier(dialect, part))
+        .collect::<Vec<String>>()
+        .join(".");
+    Ok(format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE {} ON UPDATE {}",
+        qualified_table(dialect, database, table),
+        quote_identifier(dialect, &foreign_key.name),
+        quote_list(dialect, &foreign_key.columns),
+        ref_table,
+        quote_list(dialect, &foreign_key.ref_columns),
+        foreign_key.on_delete,
+        foreign_key.on_update,
+    ))
+}
+
+pub fn drop_foreign_key(
+    dialect: Dialect,
+    database: &Database,
+    table: &Table,
+    name: &str,
+) -> anyhow::Result<String> {
+    let drop = match dialect {
+        Dialect::MySql => "DROP FOREIGN KEY",
+        Dialect::Postgres => "DROP CONSTRAINT",
+        Dialect::Sqlite => anyhow::bail!("SQLite cannot drop a foreign key of an existing table"),
+        Dialect::ClickHouse => anyhow::bail!("ClickHouse does not support foreign keys"),
+    };
+    Ok(format!(
+        "ALTER TABLE {} {} {}",
+        qualified_table(dialect, database, table),
+        drop,
+        quote_identifier(dialect, name)
+    ))
+}
+
+/// Returns the statement which restarts `sequence` at its start value.
+pub fn restart_sequence(
+    dialect: Dialect,
+    database: &Database,
+    sequence: &Table,
+) -> anyhow::Result<String> {
+    match dialect {
+        Dialect::MySql | Dialect::Postgres => Ok(format!(
+            "ALTER SEQUENCE {} RESTART",
+            qualified_table(dialect, database, sequence)
+        )),
+        Dialect::Sqlite => anyhow::bail!("SQLite has no sequences"),
+        Dialect::ClickHouse => anyhow::bail!("ClickHouse has no sequences"),
+    }
+}
+
+/// Returns the statement which ends the session `id` of `ServerSession`, stopping the query it
+/// runs.
+pub fn kill_session(dialect: Dialect, id: &str) -> anyhow::Result<String> {
+    let numeric = || -> anyhow::Result<u64> {
+        id.parse()
+            .map_err(|_| anyhow::anyhow!("{} is not the id of a session", id))
+    };
+    Ok(match dialect {
+        Dialect::MySql => format!("KILL {}", numeric()?),
+        Dialect::Postgres => format!("SELECT pg_terminate_backend({})", numeric()?),
+        Dialect::ClickHouse => format!(
+            "KILL QUERY WHERE query_id = {}",
+            literal(dialect, &Value::Text(id.to_string()))
+        ),
+        Dialect::Sqlite => anyhow::bail!("SQLite has no sessions to end"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        add_foreign_key, delete_row, drop_foreign_key, insert, kill_session, literal,
+        restart_sequence, ForeignKey,
+    };
+    use crate::database::{Dialect, Value};
+    use crate::tree::{Database, Table, TableKind};
+
+    fn orders(schema: Option<&str>) -> (Database, Table) {
+        (
+            Database {
+                name: "shop".to_string(),
+                children: Vec::new(),
+            },
+            Table {
+                name: "orders".to_string(),
+                create_time: None,
+                update_time: None,
+                engine: None,
+                schema: schema.map(|s| s.to_string()),
+                kind: TableKind::Table,
+                partition_of: None,
+            },
+        )
+    }
+
+    fn foreign_key() -> ForeignKey {
+        ForeignKey {
+            name: "fk_orders_users".to_string(),
+            columns: vec!["user_id".to_string()],
+            ref_table: "public.users".to_string(),
+            ref_columns: vec!["id".to_string()],
+            on_delete: "CASCADE",
+            on_update: "NO ACTION",
+        }
+    }
+
+    #[test]
+    fn test_add_foreign_key() {
+        let (database, table) = orders(Some("public"));
+        assert_eq!(
+            add_foreign_key(Dialect::Postgres, &database, &table, &foreign_key()).unwrap(),
+            r#"ALTER TABLE "public"."orders" ADD CONSTRAINT "fk_orders_users" FOREIGN KEY ("user_id") REFERENCES "public"."users" ("id") ON DELETE CASCADE ON UPDATE NO ACTION"#
+        );
+        let (database, table) = orders(None);
+        let mut foreign_key = foreign_key();
+        foreign_key.ref_table = "users".to_string();
+        assert_eq!(
+            add_foreign_key(Dialect::MySql, &database, &table, &foreign_key).unwrap(),
+            "ALTER TABLE `shop`.`orders` ADD CONSTRAINT `fk_orders_users` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE ON UPDATE NO ACTION"
+        );
+        assert!(add_foreign_key(Dialect::Sqlite, &database, &table, &foreign_key).is_err());
+        foreign_key.ref_columns.clear();
+        assert!(add_foreign_key(Dialect::MySql, &database, &table, &foreign_key).is_err());
+    }
+
+    #[test]
+    fn test_drop_foreign_key() {
+        let (database, table) = orders(None);
+        assert_eq!(
+            drop_foreign_key(Dialect::MySql, &database, &table, "fk").unwrap(),
+            "ALTER TABLE `shop`.`orders` DROP FOREIGN KEY `fk`"
+        );
+    }
+
+    #[test]
+    fn test_restart_sequence() {
+        let (database, table) = orders(Some("public"));
+        assert_eq!(
+            restart_sequence(Dialect::Postgres, &database, &table).unwrap(),
+            r#"ALTER SEQUENCE "public"."orders" RESTART"#
+        );
+        assert!(restart_sequence(Dialect::Sqlite, &database, &table).is_err());
+    }
+
+    #[test]
+    fn test_literal() {
+        let text = Value::Text(r"it's C:\".to_string());
+        assert_eq!(literal(Dialect::Postgres, &text), r"'it''s C:\'");
+        assert_eq!(literal(Dialect::MySql, &text), r"'it''s C:\\'");
+        assert_eq!(literal(Dialect::Sqlite, &Value::Null), "NULL");
+        assert_eq!(literal(Dialect::Sqlite, &Value::Int(-3)), "-3");
+        assert_eq!(
+            literal(Dialect::Sqlite, &Value::Bytes(vec![0, 255])),
+            "X'00ff'"
+        );
+        assert_eq!(
+            literal(Dialect::Postgres, &Value::Bytes(vec![1])),
+            r"'\x01'::bytea"
+        );
+    }
+
+    #[test]
+    fn test_literal_non_finite_float() {
+        let nan = Value::Float(f64::NAN);
+        let infinity = Value::Float(f64::INFINITY);
+        let negative = Value::Float(f64::NEG_INFINITY);
+        assert_eq!(literal(Dialect::Postgres, &nan), "'NaN'::float8");
+        assert_eq!(literal(Dialect::Postgres, &negative), "'-Infinity'::float8");
+        assert_eq!(literal(Dialect::ClickHouse, &infinity), "inf");
+        assert_eq!(literal(Dialect::Sqlite, &negative), "-9e999");
+        assert_eq!(literal(Dialect::Sqlite, &nan), "NULL");
+        assert_eq!(literal(Dialect::MySql, &infinity), "NULL");
+        assert_eq!(literal(Dialect::MySql, &Value::Float(1.5)), "1.5");
+    }
+
+    #[test]
+    fn test_literal_array() {
+        let array = Value::Array(vec![Value::Int(1), Value::Null]);
+        assert_eq!(literal(Dialect::Postgres, &array), "ARRAY[1, NULL]");
+        assert_eq!(literal(Dialect::ClickHouse, &array), "[1, NULL]");
+        assert_eq!(literal(Dialect::Postgres, &Value::Array(vec![])), "'{}'");
+        assert_eq!(
+            literal(
+                Dialect::Postgres,
+                &Value::Array(vec![Value::Text("it's".to_string())])
+            ),
+            "ARRAY['it''s']"
+        );
+    }
+
+    #[test]
+    fn test_delete_row() {
+        let (database, table) = orders(Some("shop"));
+        let key = vec![
+            ("id".to_string(), Value::Int(7)),
+            ("region".to_string(), Value::Null),
+        ];
+        assert_eq!(
+            delete_row(Dialect::Postgres, &database, &table, &key),
+            r#"DELETE FROM "shop"."orders" WHERE "id" = 7 AND "region" IS NULL;"#
+        );
+        assert_eq!(
+            delete_row(Dialect::MySql, &database, &table, &key[..1]),
+            "DELETE FROM `shop`.`orders` WHERE `id` = 7;"
+        );
+    }
+
+    #[test]
+    fn test_insert() {
+        let rows = vec![
+            vec![Value::Int(1), Value::Text("O'Brien".to_string())],
+            vec![Value::Int(2), Value::Null],
+        ];
+        assert_eq!(
+            insert(
+                Dialect::Postgres,
+                r#""public"."users""#,
+                &["id".to_string(), "name".to_string()],
+                &rows
+            ),
+            [
+                r#"INSERT INTO "public"."users" ("id", "name") VALUES (1, 'O''Brien');"#,
+                r#"INSERT INTO "public"."users" ("id", "name") VALUES (2, NULL);"#,
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_kill_session() {
+        assert_eq!(kill_session(Dialect::MySql, "42").unwrap(), "KILL 42");
+        assert_eq!(
+            kill_session(Dialect::Postgres, "42").unwrap(),
+            "SELECT pg_terminate_backend(42)"
+        );
+        assert_eq!(
+            kill_session(Dialect::ClickHouse, "a'b").unwrap(),
+            "KILL QUERY WHERE query_id = 'a''b'"
+        );
+        assert!(kill_session(Dialect::MySql, "42; DROP TABLE users").is_err());
+        assert!(kill_session(Dialect::Sqlite, "1").is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use crate::config::{KeywordCase, SqlFormatConfig};
+use sqlformat::{FormatOptions, Indent, QueryParams};
+
+/// Breaks a query into one clause per line and indents it as configured, e.g. a query pasted
+/// as a single line. Placeholders like `?`, `$1` and `:name` are kept.
+pub fn format_sql(sql: &str, config: &SqlFormatConfig) -> String {
+    sqlformat::format(
+        sql,
+        &QueryParams::None,
+        FormatOptions {
+            indent: Indent::Spaces(config.indent),
+            uppercase: config.keyword_case == KeywordCase::Upper,
+            lines_between_queries: 1,
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::format_sql;
+    use crate::config::{KeywordCase, SqlFormatConfig};
+
+    #[test]
+    fn test_format_sql() {
+        let config = SqlFormatConfig {
+            indent: 4,
+            keyword_case: KeywordCase::Upper,
+        };
+        assert_eq!(
+            format_sql(
+                "select id, name from users where id = ? order by name",
+                &config
+            ),
+            "SELECT\n    id,\n    name\nFROM\n    users\nWHERE\n    id = ?\nORDER BY\n    name"
+        );
+        let config = SqlFormatConfig {
+            indent: 2,
+            keyword_case: KeywordCase::Preserve,
+        };
+        assert_eq!(
+            format_sql("select 1; select :name", &config),
+            "select\n  1;\nselect\n  :name"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use super::Pool;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// The longest wait between two attempts to reach a server which went away.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// What changed about the connection since the last check.
+#[derive(Debug)]
+pub enum HealthEvent {
+    /// The server stopped answering, with why the ping failed.
+    Lost(anyhow::Error),
+    /// The server answers again after it was lost.
+    Reconnected,
+}
+
+/// Pings the server of a connection every `keepalive_second` so that a dropped connection is
+/// noticed before the next action fails, and while it is lost pings it with a growing delay
+/// until it answers again. The pool itself replaces its broken connections once it does.
+pub struct HealthCheck {
+    /// `None` if the connection is only pinged after it was lost.
+    interval: Option<Duration>,
+    ping: Option<JoinHandle<anyhow::Result<()>>>,
+    next: Instant,
+    /// How many pings failed in a row, 0 while the server answers.
+    failures: usize,
+}
+
+impl Default for HealthCheck {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl HealthCheck {
+    /// Pings every `keepalive_second`, or only to reconnect if it is 0.
+    pub fn new(keepalive_second: u64) -> Self {
+        let interval = (keepalive_second > 0).then(|| Duration::from_secs(keepalive_second));
+        Self {
+            interval,
+            ping: None,
+            next: Instant::now() + interval.unwrap_or_default(),
+            failures: 0,
+        }
+    }
+
+    pub fn is_reconnecting(&self) -> bool {
+        self.failures > 0
+    }
+
+    /// Marks the connection as lost after an action failed on it, so that the server is pinged
+    /// right away.
+    pub fn lost(&mut self) {
+        self.failures = self.failures.max(1);
+        if self.ping.is_none() {
+            self.next = Instant::now();
+        }
+    }
+
+    /// Starts a ping when one is due, and returns what changed once a ping has finished.
+    pub async fn check(&mut self, pool: &dyn Pool) -> Option<HealthEvent> {
+        match self.ping.as_ref() {
+            Some(ping) if ping.is_finished() => (),
+            Some(_) => return None,
+            None => {
+                if (self.interval.is_some() || self.is_reconnecting())
+                    && Instant::now() >= self.next
+                {
+                    self.ping = Some(tokio::spawn(pool.ping()));
+                }
+                return None;
+            }
+        }
+        let result = match self.ping.take()?.await {
+            Ok(result) => result,
+            Err(e) => Err(e.into()),
+        };
+        self.finish(result)
+    }
+
+    fn finish(&mut self, result: anyhow::Result<()>) -> Option<HealthEvent> {
+        match result {
+            Ok(()) => {
+                let reconnected = self.is_reconnecting();
+                self.failures = 0;
+                self.next = Instant::now() + self.interval.unwrap_or_default();
+                reconnected.then_some(HealthEvent::Reconnected)
+            }
+            Err(e) => {
+                self.failures += 1;
+                self.next = Instant::now() + reconnect_delay(self.failures);
+                (self.failures == 1).then_some(HealthEvent::Lost(e))
+            }
+        }
+    }
+}
+
+/// Doubles the delay after each failed attempt, from a second up to `MAX_RECONNECT_DELAY`.
+fn reconnect_delay(failures: usize) -> Duration {
+    let exponent = failures.saturating_sub(1).min(5) as u32;
+    Duration::from_secs(2_u64.pow(exponent)).min(MAX_RECONNECT_DELAY)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{reconnect_delay, HealthCheck, HealthEvent};
+    use std::time::Duration;
+
+    #[test]
+    fn test_reconnect_delay() {
+        assert_eq!(reconnect_delay(1), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(3), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(100), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_finish() {
+        let mut health = HealthCheck::new(30);
+        assert!(health.finish(Ok(())).is_none());
+
+        // only the first failure is reported, and the next success ends reconnecting
+        assert!(matches!(
+            health.finish(Err(anyhow::anyhow!("connection refused"))),
+            Some(HealthEvent::Lost(_))
+        ));
+        assert!(health
+            .finish(Err(anyhow::anyhow!("connection refused")))
+            .is_none());
+        assert!(health.is_reconnecting());
+        assert!(matches!(
+            health.finish(Ok(())),
+            Some(HealthEvent::Reconnected)
+        ));
+        assert!(!health.is_reconnecting());
+
+        // an action which failed on the connection starts reconnecting
+        health.lost();
+        assert!(health.is_reconnecting());
+    }
+}
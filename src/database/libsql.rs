@@ -0,0 +1,992 @@
+use super::script::is_select;
+use super::sqlite::{
+    parse_trigger, Column, Constraint, ForeignKey, Index, DATABASE_SIZE_QUERY, DATA_SIZE_QUERY,
+    DEFINITION_QUERY, INDEX_SIZE_QUERY, LARGEST_TABLES_QUERY, PRIMARY_KEY_QUERY, RELATIONS_QUERY,
+    SESSION_SETTINGS,
+};
+use super::{
+    check_setting_name, format_bytes, Capabilities, DatabaseRole, Dependency, Dialect,
+    ExecuteResult, MemoryBudget, Ping, Pool, QueryParams, Relation, RowBuffer, RowStream,
+    SchemaObject, ServerOverview, ServerSession, SessionSetting, Statistic, TableRow, Trigger,
+    Value as CellValue,
+};
+use crate::tree::{Child, Database, Table, TableKind};
+use async_trait::async_trait;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// A pool for libSQL servers such as Turso, which talks to their HTTP API (Hrana over HTTP).
+/// Every request runs on a new stream on the server, except in a transaction, whose stream
+/// is kept open by passing its baton along.
+pub struct LibSqlPool {
+    client: reqwest::Client,
+    endpoint: Url,
+    auth_token: String,
+    /// The pragmas run at the start of every stream.
+    session: Vec<String>,
+    limit_size: usize,
+    memory_budget: MemoryBudget,
+    /// The baton of the stream of the open transaction.
+    transaction: Mutex<Option<String>>,
+}
+
+/// The columns, rows and affected row count of a statement.
+#[derive(Debug, Default, PartialEq)]
+struct StatementResult {
+    headers: Vec<String>,
+    rows: Vec<Vec<CellValue>>,
+    affected_row_count: u64,
+}
+
+impl StatementResult {
+    fn into_strings(self) -> Vec<Vec<String>> {
+        self.rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|value| match value {
+                        CellValue::Null => String::new(),
+                        value => value.to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn into_optional_strings(self) -> Vec<Vec<Option<String>>> {
+        self.rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|value| Some(value).filter(|v| !v.is_null()).map(|v| v.to_string()))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl LibSqlPool {
+    pub async fn new(
+        database_url: &str,
+        limit_size: usize,
+        timeout_second: u64,
+        memory_budget: MemoryBudget,
+        session: &BTreeMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        let url = Url::parse(database_url)?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow::anyhow!("libsql needs the host in {}", database_url))?;
+        // a local sqld serves plain HTTP, Turso only HTTPS
+        let scheme = if ["localhost", "127.0.0.1"].contains(&host) {
+            "http"
+        } else {
+            "https"
+        };
+        let endpoint = Url::parse(&match url.port() {
+            Some(port) => format!("{}://{}:{}/v2/pipeline", scheme, host, port),
+            None => format!("{}://{}/v2/pipeline", scheme, host),
+        })?;
+        let mut pragmas = vec![];
+        for (name, value) in session {
+            check_setting_name(name)?;
+            pragmas.push(format!("PRAGMA {} = '{}'", name, value.replace('\'', "''")));
+        }
+
+        let pool = Self {
+            client: reqwest::Client::builder()
+                .connect_timeout(Duration::from_secs(timeout_second))
+                .build()?,
+            endpoint,
+            auth_token: url.password().unwrap_or_default().to_string(),
+            session: pragmas,
+            limit_size,
+            memory_budget,
+            transaction: Mutex::new(None),
+        };
+        pool.query("SELECT 1", &[]).await?;
+        Ok(pool)
+    }
+
+    /// Runs `statements` in one request and returns the baton of the stream if `keep_open`.
+    async fn pipeline(
+        &self,
+        baton: Option<String>,
+        statements: &[Value],
+        keep_open: bool,
+    ) -> anyhow::Result<(Option<String>, Vec<StatementResult>)> {
+        let session: &[String] = if baton.is_some() { &[] } else { &self.session };
+        let mut request = self
+            .client
+            .post(self.endpoint.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(pipeline_body(baton, session, statements, keep_open).to_string());
+        if !self.auth_token.is_empty() {
+            request = request.bearer_auth(&self.auth_token);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("{}", response.text().await?.trim()));
+        }
+        parse_pipeline(
+            serde_json::from_str(&response.text().await?)?,
+            session.len(),
+        )
+    }
+
+    /// Runs a statement on a new stream.
+    async fn query(&self, sql: &str, args: &[&str]) -> anyhow::Result<StatementResult> {
+        let (_, results) = self.pipeline(None, &[statement(sql, args)], false).await?;
+        results
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("libsql returned no result for {}", sql))
+    }
+
+    /// Runs `stmt`, the request of `query`, in the open transaction or else on a new stream.
+    async fn execute_statement(&self, query: &str, stmt: Value) -> anyhow::Result<ExecuteResult> {
+        let mut transaction = self.transaction.lock().await;
+        let baton = transaction.take();
+        let keep_open = baton.is_some();
+        let (baton, mut results) = self.pipeline(baton, &[stmt], keep_open).await?;
+        if keep_open {
+            *transaction = baton;
+        }
+        let result = results.pop().unwrap_or_default();
+        if is_select(query) {
+            return Ok(self.read_result(result));
+        }
+        Ok(ExecuteResult::Write {
+            updated_rows: result.affected_row_count,
+        })
+    }
+
+    /// Puts the rows of a read into `RowBuffer`, so that they can be cut off at the memory
+    /// budget.
+    fn read_result(&self, result: StatementResult) -> ExecuteResult {
+        let mut records = RowBuffer::new(self.memory_budget.bytes);
+        for row in result.rows {
+            if !records.push(row) {
+                break;
+            }
+        }
+        ExecuteResult::Read {
+            headers: result.headers,
+            truncated: records.truncated,
+            rows: records.rows,
+            database: Database {
+                name: "-".to_string(),
+                children: Vec::new(),
+            },
+            table: Box::new(Table {
+                name: "-".to_string(),
+                create_time: None,
+                update_time: None,
+                engine: None,
+                schema: None,
+                kind: TableKind::Table,
+                partition_of: None,
+            }),
+        }
+    }
+
+    /// Ends the transaction with `sql`, which is `COMMIT` or `ROLLBACK`.
+    async fn finish_transaction(&self, sql: &str) -> anyhow::Result<()> {
+        let baton = match self.transaction.lock().await.take() {
+            Some(baton) => baton,
+            None => anyhow::bail!("No transaction is open"),
+        };
+        self.pipeline(Some(baton), &[statement(sql, &[])], false)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Returns the body of a pipeline request, which runs the session pragmas on a new stream
+/// before `statements`.
+fn pipeline_body(
+    baton: Option<String>,
+    session: &[String],
+    statements: &[Value],
+    keep_open: bool,
+) -> Value {
+    let mut requests = session
+        .iter()
+        .map(|pragma| json!({"type": "execute", "stmt": statement(pragma, &[])}))
+        .chain(
+            statements
+                .iter()
+                .map(|stmt| json!({"type": "execute", "stmt": stmt})),
+        )
+        .collect::<Vec<_>>();
+    if !keep_open {
+        requests.push(json!({"type": "close"}));
+    }
+    json!({"baton": baton, "requests": requests})
+}
+
+fn statement(sql: &str, args: &[&str]) -> Value {
+    json!({
+        "sql": sql,
+        "args": args
+            .iter()
+            .map(|arg| json!({"type": "text", "value": arg}))
+            .collect::<Vec<_>>(),
+        "want_rows": true,
+    })
+}
+
+/// Builds the request of a statement with the values of its parameters, as numbers where they
+/// were typed as one.
+fn bound_statement(sql: &str, values: &[CellValue]) -> Value {
+    json!({
+        "sql": sql,
+        "args": values
+            .iter()
+            .map(|value| match value {
+                CellValue::Null => json!({"type": "null"}),
+                // integers are sent as strings so that they keep their precision
+                CellValue::Int(value) => json!({"type": "integer", "value": value.to_string()}),
+                CellValue::Float(value) => json!({"type": "float", "value": value}),
+                value => json!({"type": "text", "value": value.to_string()}),
+            })
+            .collect::<Vec<_>>(),
+        "want_rows": true,
+    })
+}
+
+/// Reads the baton and the results of the statements after the first `skip` ones, failing
+/// with the message of the first statement which failed.
+fn parse_pipeline(
+    mut response: Value,
+    skip: usize,
+) -> anyhow::Result<(Option<String>, Vec<StatementResult>)> {
+    let baton = response["baton"].as_str().map(str::to_string);
+    let mut results = vec![];
+    let entries = match response["results"].take() {
+        Value::Array(entries) => entries,
+        _ => anyhow::bail!("libsql returned no results"),
+    };
+    for mut entry in entries {
+        if entry["type"] == "error" {
+            anyhow::bail!(
+                "{}",
+                entry["error"]["message"]
+                    .as_str()
+                    .unwrap_or("unknown error")
+            );
+        }
+        if entry["response"]["type"] != "execute" {
+            continue;
+        }
+        let mut result = entry["response"]["result"].take();
+        results.push(StatementResult {
+            headers: result["cols"]
+                .as_array()
+                .map(|cols| {
+                    cols.iter()
+                        .map(|col| col["name"].as_str().unwrap_or_default().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            rows: match result["rows"].take() {
+                Value::Array(rows) => rows
+                    .into_iter()
+                    .map(|row| match row {
+                        Value::Array(values) => values.into_iter().map(convert_value).collect(),
+                        _ => Vec::new(),
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            },
+            affected_row_count: result["affected_row_count"].as_u64().unwrap_or_default(),
+        });
+    }
+    Ok((baton, results.into_iter().skip(skip).collect()))
+}
+
+/// Converts a Hrana value, in which integers are strings and blobs are base64.
+fn convert_value(mut value: Value) -> CellValue {
+    match value["type"].as_str() {
+        Some("integer") => value["value"]
+            .as_str()
+            .and_then(|v| v.parse().ok())
+            .map_or(CellValue::Null, CellValue::Int),
+        Some("float") => CellValue::Float(value["value"].as_f64().unwrap_or_default()),
+        Some("text") => match value["value"].take() {
+            Value::String(text) => CellValue::Text(text),
+            _ => CellValue::Null,
+        },
+        Some("blob") => value["base64"]
+            .as_str()
+            .and_then(decode_base64)
+            .map_or(CellValue::Null, CellValue::Bytes),
+        _ => CellValue::Null,
+    }
+}
+
+/// Decodes base64 with or without padding.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0_u32, 0);
+    for c in text.bytes().filter(|c| *c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = buffer << 6 | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[async_trait]
+impl Pool for LibSqlPool {
+    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+        let query = query.trim();
+        self.execute_statement(query, statement(query, &[])).await
+    }
+
+    async fn execute_params(
+        &self,
+        query: &str,
+        values: &[CellValue],
+    ) -> anyhow::Result<ExecuteResult> {
+        let (query, values) =
+            QueryParams::parse(query.trim(), Dialect::Sqlite).bind(Dialect::Sqlite, values)?;
+        self.execute_statement(&query, bound_statement(&query, &values))
+            .await
+    }
+
+    fn stream(&self, _query: &str) -> Option<RowStream> {
+        // the HTTP API answers with the whole result at once
+        None
+    }
+
+    async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
+        // a libSQL server serves one database over a URL
+        Ok(vec![Database::new("main".to_string(), Vec::new())])
+    }
+
+    async fn get_tables(&self, _database: String) -> anyhow::Result<Vec<Child>> {
+        let result = self
+            .query(
+                "SELECT name, type FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'libsql_%' ORDER BY name",
+                &[],
+            )
+            .await?;
+        Ok(result
+            .into_strings()
+            .into_iter()
+            .map(|row| {
+                Table {
+                    name: row.first().cloned().unwrap_or_default(),
+                    create_time: None,
+                    update_time: None,
+                    engine: None,
+                    schema: None,
+                    kind: if row.get(1).map(String::as_str) == Some("view") {
+                        TableKind::View
+                    } else {
+                        TableKind::Table
+                    },
+                    partition_of: None,
+                }
+                .into()
+            })
+            .collect())
+    }
+
+    async fn get_records(
+        &self,
+        _database: &Database,
+        table: &Table,
+        page: usize,
+        filter: Option<String>,
+        orders: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<CellValue>>)> {
+        let query = format!(
+            "SELECT * FROM `{table}`{filter}{orders} LIMIT {page}, {limit}",
+            table = table.name,
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+            orders = orders.map_or(String::new(), |orders| format!(" {}", orders)),
+            page = page,
+            limit = self.limit_size,
+        );
+        let result = self.query(&query, &[]).await?;
+        Ok((result.headers, result.rows))
+    }
+
+    async fn get_sample(
+        &self,
+        _database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<CellValue>>)> {
+        let query = format!(
+            "SELECT * FROM `{table}`{filter} ORDER BY RANDOM() LIMIT {limit}",
+            table = table.name,
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+            limit = self.limit_size
+        );
+        self.read_result(self.query(&query, &[]).await?)
+            .into_records()
+    }
+
+    async fn get_total_row_count(
+        &self,
+        _database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<usize> {
+        let query = format!(
+            "SELECT COUNT(*) FROM `{table}`{filter}",
+            table = table.name,
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+        );
+        Ok(self
+            .query(&query, &[])
+            .await?
+            .into_strings()
+            .first()
+            .and_then(|row| row.first())
+            .and_then(|count| count.parse().ok())
+            .unwrap_or_default())
+    }
+
+    async fn get_columns(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let result = self
+            .query(
+                "SELECT name, type, \"notnull\", dflt_value FROM pragma_table_info(?)",
+                &[&table.name],
+            )
+            .await?;
+        let mut columns: Vec<Box<dyn TableRow>> = vec![];
+        for row in result.into_optional_strings() {
+            let mut row = row.into_iter();
+            columns.push(Box::new(Column {
+                name: row.next().flatten(),
+                r#type: row.next().flatten(),
+                null: Some(if row.next().flatten().as_deref() == Some("1") {
+                    "✔︎".to_string()
+                } else {
+                    String::new()
+                }),
+                default: row.next().flatten(),
+                comment: None,
+            }))
+        }
+        Ok(columns)
+    }
+
+    async fn get_constraints(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let result = self
+            .query(
+                "
+            SELECT
+                s.name AS index_name,
+                i.name AS column_name,
+                p.origin
+            FROM
+                sqlite_master s
+                JOIN pragma_index_list(s.tbl_name) p ON s.name = p.name,
+                pragma_index_info(s.name) i
+            WHERE
+                s.type = 'index'
+                AND tbl_name = ?
+                AND NOT p.origin = 'c'
+            ",
+                &[&table.name],
+            )
+            .await?;
+        let mut constraints: Vec<Box<dyn TableRow>> = vec![];
+        for row in result.into_strings() {
+            let mut row = row.into_iter();
+            constraints.push(Box::new(Constraint {
+                name: row.next().unwrap_or_default(),
+                column_name: row.next().unwrap_or_default(),
+                origin: row.next().unwrap_or_default(),
+            }))
+        }
+        Ok(constraints)
+    }
+
+    async fn get_foreign_keys(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let result = self
+            .query(
+                "SELECT p.\"from\", p.\"table\", p.\"to\" FROM pragma_foreign_key_list(?) p",
+                &[&table.name],
+            )
+            .await?;
+        let mut foreign_keys: Vec<Box<dyn TableRow>> = vec![];
+        for row in result.into_optional_strings() {
+            let mut row = row.into_iter();
+            foreign_keys.push(Box::new(ForeignKey {
+                column_name: row.next().flatten(),
+                ref_table: row.next().flatten(),
+                ref_column: row.next().flatten(),
+            }))
+        }
+        Ok(foreign_keys)
+    }
+
+    async fn get_primary_key(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .query(PRIMARY_KEY_QUERY, &[&table.name])
+            .await?
+            .into_strings()
+            .into_iter()
+            .filter_map(|row| row.into_iter().next())
+            .collect())
+    }
+
+    async fn get_indexes(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let result = self
+            .query(
+                "
+            SELECT
+                m.name AS index_name,
+                p.name
+            FROM
+                sqlite_master m,
+                pragma_index_info(m.name) p
+            WHERE
+                m.type = 'index'
+                AND m.tbl_name = ?
+            ",
+                &[&table.name],
+            )
+            .await?;
+        let mut indexes: Vec<Box<dyn TableRow>> = vec![];
+        for row in result.into_optional_strings() {
+            let mut row = row.into_iter();
+            indexes.push(Box::new(Index {
+                name: row.next().flatten(),
+                column_name: row.next().flatten(),
+                r#type: Some(String::new()),
+            }))
+        }
+        Ok(indexes)
+    }
+
+    async fn get_definition(&self, _database: &Database, table: &Table) -> anyhow::Result<String> {
+        Ok(self
+            .query(DEFINITION_QUERY, &[&table.name])
+            .await?
+            .into_strings()
+            .into_iter()
+            .filter_map(|row| row.into_iter().next())
+            .collect::<Vec<String>>()
+            .join("\n\n"))
+    }
+
+    async fn get_dependencies(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let result = self
+            .query(
+                "
+        SELECT * FROM (
+            SELECT type AS kind, name, '' AS detail
+            FROM sqlite_master
+            WHERE type IN ('view', 'trigger')
+                AND name <> ?1
+                AND (tbl_name = ?1 OR sql LIKE '%' || ?1 || '%')
+            UNION ALL
+            SELECT 'foreign key', m.name || '.' || p.\"from\", 'references ' || p.\"to\"
+            FROM sqlite_master m
+            JOIN pragma_foreign_key_list(m.name) p
+            WHERE m.type = 'table' AND p.\"table\" = ?1
+        )
+        ORDER BY kind, name
+        ",
+                &[&table.name],
+            )
+            .await?;
+        let mut dependencies: Vec<Box<dyn TableRow>> = vec![];
+        for row in result.into_strings() {
+            let mut row = row.into_iter();
+            dependencies.push(Box::new(Dependency {
+                kind: row.next().unwrap_or_default(),
+                name: row.next().unwrap_or_default(),
+                detail: row.next().unwrap_or_default(),
+            }))
+        }
+        Ok(dependencies)
+    }
+
+    async fn get_triggers(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let result = self
+            .query(
+                "SELECT name, sql FROM sqlite_master WHERE type = 'trigger' AND tbl_name = ? ORDER BY name",
+                &[&table.name],
+            )
+            .await?;
+        let mut triggers: Vec<Box<dyn TableRow>> = vec![];
+        for row in result.into_strings() {
+            let mut row = row.into_iter();
+            let name = row.next().unwrap_or_default();
+            let (timing, event, statement) = parse_trigger(&row.next().unwrap_or_default());
+            triggers.push(Box::new(Trigger {
+                name,
+                timing,
+                event,
+                statement,
+            }))
+        }
+        Ok(triggers)
+    }
+
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let rows = self.get_total_row_count(database, table, None).await?;
+        let mut sizes = vec![];
+        for query in [DATA_SIZE_QUERY, INDEX_SIZE_QUERY] {
+            // Turso does not expose dbstat, so the sizes are left out there
+            sizes.push(
+                self.query(query, &[&table.name])
+                    .await
+                    .ok()
+                    .and_then(|result| result.into_strings().into_iter().next())
+                    .and_then(|row| row.into_iter().next())
+                    .and_then(|bytes| bytes.parse().ok())
+                    .map(format_bytes),
+            );
+        }
+        let mut sizes = sizes.into_iter();
+        Ok(Statistic::rows(vec![
+            ("rows (exact)", Some(rows.to_string())),
+            ("data size", sizes.next().flatten()),
+            ("index size", sizes.next().flatten()),
+        ]))
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        let first = |result: Option<StatementResult>| {
+            result
+                .and_then(|result| result.into_strings().into_iter().next())
+                .and_then(|row| row.into_iter().next())
+        };
+        let version = first(Some(self.query("SELECT sqlite_version()", &[]).await?));
+        let database_size = first(self.query(DATABASE_SIZE_QUERY, &[]).await.ok())
+            .and_then(|bytes| bytes.parse().ok())
+            .map(format_bytes);
+        // Turso does not expose dbstat, so the largest tables are left out there
+        let largest_tables = self
+            .query(
+                &format!(
+                    "{} LIMIT {}",
+                    LARGEST_TABLES_QUERY,
+                    ServerOverview::LARGEST_TABLES
+                ),
+                &[],
+            )
+            .await
+            .map(|result| result.into_strings())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [name, size] => Some((name.clone(), size.parse().ok()?)),
+                _ => None,
+            })
+            .collect();
+        Ok(ServerOverview::new(
+            vec![
+                (
+                    "server version",
+                    version.map(|version| format!("libSQL {}", version)),
+                ),
+                ("database size", database_size),
+            ],
+            largest_tables,
+        ))
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        anyhow::bail!("SQLite has no sessions of other clients to list")
+    }
+
+    async fn get_privileges(
+        &self,
+        _database: &Database,
+        _table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // SQLite has no privileges, whoever can open the file can change it
+        Ok(vec![])
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        anyhow::bail!("SQLite has no users or roles")
+    }
+
+    async fn get_relations(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        let result = self.query(RELATIONS_QUERY, &[&table.name]).await?;
+        Ok(Relation::merge_columns(
+            result
+                .into_strings()
+                .into_iter()
+                .filter_map(|row| match <[String; 5]>::try_from(row) {
+                    Ok([name, table, column, ref_table, ref_column]) => Some(Relation {
+                        name,
+                        schema: None,
+                        table,
+                        columns: vec![column],
+                        ref_schema: None,
+                        ref_table,
+                        ref_columns: vec![ref_column],
+                    }),
+                    Err(_) => None,
+                })
+                .collect(),
+        ))
+    }
+
+    async fn get_partitions(
+        &self,
+        _database: &Database,
+        _table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // SQLite does not partition tables
+        Ok(vec![])
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        let result = self
+            .query(
+                "
+        SELECT type AS kind, NULL AS table_name, name
+        FROM sqlite_master
+        WHERE type IN ('table', 'view')
+        UNION ALL
+        SELECT 'column', m.name, p.name
+        FROM sqlite_master AS m JOIN pragma_table_info(m.name) AS p
+        WHERE m.type IN ('table', 'view')
+        ",
+                &[],
+            )
+            .await?;
+        Ok(result
+            .into_optional_strings()
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [Some(kind), table, Some(name)] => Some(SchemaObject {
+                    kind: kind.clone(),
+                    database: "main".to_string(),
+                    schema: None,
+                    table: table.clone(),
+                    name: name.clone(),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        let statements = SESSION_SETTINGS
+            .iter()
+            .map(|(name, _)| statement(&format!("SELECT * FROM pragma_{}", name), &[]))
+            .collect::<Vec<_>>();
+        let (_, results) = self.pipeline(None, &statements, false).await?;
+        let rows = SESSION_SETTINGS
+            .iter()
+            .zip(results)
+            .map(|((name, _), result)| {
+                let value = result
+                    .into_strings()
+                    .into_iter()
+                    .next()
+                    .and_then(|row| row.into_iter().next())
+                    .unwrap_or_default();
+                (name.to_string(), value)
+            })
+            .collect();
+        Ok(SessionSetting::from_rows(rows, &SESSION_SETTINGS))
+    }
+
+    async fn begin(&self) -> anyhow::Result<()> {
+        let mut transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            anyhow::bail!("A transaction is already open");
+        }
+        let (baton, _) = self
+            .pipeline(None, &[statement("BEGIN", &[])], true)
+            .await?;
+        *transaction = Some(baton.ok_or_else(|| anyhow::anyhow!("libsql returned no baton"))?);
+        Ok(())
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        self.finish_transaction("COMMIT").await
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        self.finish_transaction("ROLLBACK").await
+    }
+
+    async fn close(&self) {
+        // closing the stream of an open transaction rolls it back
+        if let Some(baton) = self.transaction.lock().await.take() {
+            let _ = self.pipeline(Some(baton), &[], false).await;
+        }
+    }
+
+    fn ping(&self) -> Ping {
+        let mut request = self
+            .client
+            .post(self.endpoint.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(pipeline_body(None, &[], &[statement("SELECT 1", &[])], false).to_string());
+        if !self.auth_token.is_empty() {
+            request = request.bearer_auth(&self.auth_token);
+        }
+        Box::pin(async move {
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!("{}", response.text().await?.trim()));
+            }
+            Ok(())
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // the server is not asked for its version, as that would cost a request when connecting
+        Capabilities::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{convert_value, decode_base64, parse_pipeline, pipeline_body, CellValue};
+    use serde_json::json;
+
+    #[test]
+    fn test_pipeline_body() {
+        let body = pipeline_body(
+            None,
+            &["PRAGMA foreign_keys = '1'".to_string()],
+            &[json!({"sql": "SELECT 1"})],
+            false,
+        );
+        assert_eq!(
+            body,
+            json!({
+                "baton": null,
+                "requests": [
+                    {"type": "execute", "stmt": {"sql": "PRAGMA foreign_keys = '1'", "args": [], "want_rows": true}},
+                    {"type": "execute", "stmt": {"sql": "SELECT 1"}},
+                    {"type": "close"},
+                ],
+            })
+        );
+        let body = pipeline_body(Some("abc".to_string()), &[], &[], true);
+        assert_eq!(body, json!({"baton": "abc", "requests": []}));
+    }
+
+    #[test]
+    fn test_parse_pipeline() {
+        let response = json!({
+            "baton": null,
+            "results": [
+                {"type": "ok", "response": {"type": "execute", "result": {"cols": [], "rows": [], "affected_row_count": 0}}},
+                {"type": "ok", "response": {"type": "execute", "result": {
+                    "cols": [{"name": "id", "decltype": "INTEGER"}, {"name": "name", "decltype": "TEXT"}],
+                    "rows": [[{"type": "integer", "value": "1"}, {"type": "null"}]],
+                    "affected_row_count": 3,
+                }}},
+                {"type": "ok", "response": {"type": "close"}},
+            ],
+        });
+        let (baton, results) = parse_pipeline(response, 1).unwrap();
+        assert_eq!(baton, None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].headers, vec!["id", "name"]);
+        assert_eq!(
+            results[0].rows,
+            vec![vec![CellValue::Int(1), CellValue::Null]]
+        );
+        assert_eq!(results[0].affected_row_count, 3);
+
+        let response = json!({
+            "baton": "b",
+            "results": [{"type": "error", "error": {"message": "no such table: foo"}}],
+        });
+        assert_eq!(
+            parse_pipeline(response, 0).unwrap_err().to_string(),
+            "no such table: foo"
+        );
+    }
+
+    #[test]
+    fn test_convert_value() {
+        assert_eq!(convert_value(json!({"type": "null"})), CellValue::Null);
+        assert_eq!(
+            convert_value(json!({"type": "integer", "value": "-42"})),
+            CellValue::Int(-42)
+        );
+        assert_eq!(
+            convert_value(json!({"type": "float", "value": 1.5})),
+            CellValue::Float(1.5)
+        );
+        assert_eq!(
+            convert_value(json!({"type": "text", "value": "foo"})),
+            CellValue::Text("foo".to_string())
+        );
+        assert_eq!(
+            convert_value(json!({"type": "blob", "base64": "AP8="})),
+            CellValue::Bytes(vec![0, 255])
+        );
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("aGVsbG8="), Some(b"hello".to_vec()));
+        assert_eq!(decode_base64("aGVsbG8"), Some(b"hello".to_vec()));
+        assert_eq!(decode_base64(""), Some(vec![]));
+        assert_eq!(decode_base64("a*b"), None);
+    }
+}
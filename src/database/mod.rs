@@ -1,32 +1,87 @@
+pub mod cache;
+pub mod capability;
+pub mod clickhouse;
+pub mod ddl;
+pub mod format;
+pub mod health;
+pub mod libsql;
 pub mod mysql;
+pub mod params;
 pub mod postgres;
+pub mod query_log;
+pub mod retry;
+pub mod script;
+pub mod spill;
 pub mod sqlite;
+pub mod tag;
+pub mod value;
 
+pub use cache::{CachedPool, MetadataCache};
+pub use capability::{Capabilities, Capability, Server};
+pub use clickhouse::ClickHousePool;
+pub use health::{HealthCheck, HealthEvent};
+pub use libsql::LibSqlPool;
 pub use mysql::MySqlPool;
+pub use params::QueryParams;
 pub use postgres::PostgresPool;
+pub use query_log::{LoggedPool, QueryLog};
+pub use retry::RetryPool;
+pub use spill::SpillFile;
 pub use sqlite::SqlitePool;
+pub use tag::{QueryTag, TaggedPool};
+pub use value::{BinaryFormat, ColumnFormat, DisplayTimeZone, Value, ValueFormat};
 
 use crate::tree::{Child, Database, Table};
 use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// The flavor of SQL spoken by a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    MySql,
+    Postgres,
+    Sqlite,
+    ClickHouse,
+}
 
 #[async_trait]
 pub trait Pool: Send + Sync {
     async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult>;
+    /// Runs `query` with the values of its placeholders, one for each of
+    /// `QueryParams::names`, bound by the driver.
+    async fn execute_params(&self, query: &str, values: &[Value]) -> anyhow::Result<ExecuteResult>;
+    /// Runs a read query on another task and sends its rows as they arrive, or returns `None`
+    /// if the query has to be run with `execute`, e.g. because a transaction is open.
+    fn stream(&self, query: &str) -> Option<RowStream>;
+    /// Returns the databases without their tables, which `get_tables` reads once a database is
+    /// expanded in the tree.
     async fn get_databases(&self) -> anyhow::Result<Vec<Database>>;
     async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>>;
     async fn get_records(
         &self,
         database: &Database,
         table: &Table,
-        page: u16,
+        page: usize,
         filter: Option<String>,
         orders: Option<String>,
-    ) -> anyhow::Result<(Vec<String>, Vec<Vec<String>>)>;
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)>;
     async fn get_columns(
         &self,
         database: &Database,
         table: &Table,
     ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// Returns a page of rows picked at random, so that a huge table can be explored without
+    /// reading it from the start.
+    async fn get_sample(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)>;
     async fn get_total_row_count(
         &self,
         database: &Database,
@@ -48,30 +103,990 @@ pub trait Pool: Send + Sync {
         database: &Database,
         table: &Table,
     ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// Returns the columns of the primary key of `table` in order, or nothing if it has none.
+    async fn get_primary_key(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>>;
     async fn get_definition(&self, database: &Database, table: &Table) -> anyhow::Result<String>;
+    /// Returns the views, routines, triggers and foreign keys which depend on `table`.
+    async fn get_dependencies(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// Returns the tables, views, columns and routines of every database, for the object search.
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>>;
+    /// Returns the current values of the session settings which often explain surprising data,
+    /// e.g. the time zone.
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>>;
+    /// Returns the triggers which fire on changes to `table`.
+    async fn get_triggers(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// Returns the row counts, sizes and upkeep of `table`, which change too often to be cached.
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// Returns the version, uptime, connections, cache hit ratios and largest tables of the
+    /// server, for the dashboard.
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview>;
+    /// Returns the sessions of the other clients of the server with what they run, the longest
+    /// running first.
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>>;
+    /// Returns who holds which privilege on `table`, with the grants on its database and on
+    /// the whole server where the server has them.
+    async fn get_privileges(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// Returns the users and roles of the server.
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>>;
+    /// Returns the foreign keys of `table` and those of the tables which reference it, for the
+    /// relation graph.
+    async fn get_relations(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>>;
+    /// Returns the partitions of `table` with their bounds, or nothing if it is not
+    /// partitioned.
+    async fn get_partitions(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// Starts a transaction which `execute` runs in until `commit` or `rollback`.
+    async fn begin(&self) -> anyhow::Result<()>;
+    async fn commit(&self) -> anyhow::Result<()>;
+    async fn rollback(&self) -> anyhow::Result<()>;
     async fn close(&self);
+    /// Returns a check that the server still answers, which runs apart from the pool so that
+    /// the health check does not wait for it.
+    fn ping(&self) -> Ping;
+    /// Returns what the server supports, as detected from its version when connecting.
+    fn capabilities(&self) -> Capabilities;
+}
+
+/// The transaction which was started with `Pool::begin`, holding its connection.
+pub struct TransactionSlot<DB: sqlx::Database> {
+    transaction: tokio::sync::Mutex<Option<sqlx::Transaction<'static, DB>>>,
+}
+
+impl<DB: sqlx::Database> TransactionSlot<DB> {
+    pub fn new() -> Self {
+        Self {
+            transaction: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    pub async fn lock(
+        &self,
+    ) -> tokio::sync::MutexGuard<'_, Option<sqlx::Transaction<'static, DB>>> {
+        self.transaction.lock().await
+    }
+
+    /// Returns true if a transaction is open, or if the slot is in use and might be opening one.
+    pub fn is_open(&self) -> bool {
+        self.transaction
+            .try_lock()
+            .map_or(true, |transaction| transaction.is_some())
+    }
+
+    pub async fn begin(&self, pool: &sqlx::Pool<DB>) -> anyhow::Result<()> {
+        let mut transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            anyhow::bail!("A transaction is already open");
+        }
+        *transaction = Some(pool.begin().await?);
+        Ok(())
+    }
+
+    pub async fn commit(&self) -> anyhow::Result<()> {
+        match self.transaction.lock().await.take() {
+            Some(transaction) => Ok(transaction.commit().await?),
+            None => anyhow::bail!("No transaction is open"),
+        }
+    }
+
+    pub async fn rollback(&self) -> anyhow::Result<()> {
+        match self.transaction.lock().await.take() {
+            Some(transaction) => Ok(transaction.rollback().await?),
+            None => anyhow::bail!("No transaction is open"),
+        }
+    }
+}
+
+/// A session setting and the values it can be switched to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSetting {
+    pub name: String,
+    pub value: String,
+    pub choices: Vec<String>,
+}
+
+impl SessionSetting {
+    /// Builds the settings from `(name, value)` rows, with the choices of `known` settings.
+    pub fn from_rows(rows: Vec<(String, String)>, known: &[(&str, &[&str])]) -> Vec<Self> {
+        rows.into_iter()
+            .map(|(name, value)| Self {
+                choices: known
+                    .iter()
+                    .find(|(known, _)| known.eq_ignore_ascii_case(&name))
+                    .map_or(Vec::new(), |(_, choices)| {
+                        choices.iter().map(|choice| choice.to_string()).collect()
+                    }),
+                name,
+                value,
+            })
+            .collect()
+    }
+
+    /// Returns the choice after the current value.
+    pub fn next_choice(&self) -> Option<&String> {
+        let next = self
+            .choices
+            .iter()
+            .position(|choice| choice.eq_ignore_ascii_case(&self.value))
+            .map_or(0, |index| index + 1);
+        self.choices.get(next % self.choices.len().max(1))
+    }
+}
+
+/// Fails unless `name` can be put into a statement as a setting name.
+pub fn check_setting_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        anyhow::bail!("`{}` is not a valid setting name", name);
+    }
+    Ok(())
+}
+
+/// An object which would break if its table was altered or dropped.
+pub struct Dependency {
+    pub kind: String,
+    pub name: String,
+    pub detail: String,
+}
+
+impl TableRow for Dependency {
+    fn fields(&self) -> Vec<String> {
+        vec!["type".to_string(), "name".to_string(), "detail".to_string()]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![self.kind.clone(), self.name.clone(), self.detail.clone()]
+    }
+}
+
+/// A parameter of a routine, which is shown instead of the columns of a table.
+pub struct Parameter {
+    pub name: String,
+    pub mode: String,
+    pub r#type: String,
+}
+
+impl TableRow for Parameter {
+    fn fields(&self) -> Vec<String> {
+        vec!["name".to_string(), "mode".to_string(), "type".to_string()]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![self.name.clone(), self.mode.clone(), self.r#type.clone()]
+    }
+}
+
+/// A table, view, column or routine, as listed by the object search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaObject {
+    /// `table`, `view`, `column`, `function` or `procedure`.
+    pub kind: String,
+    pub database: String,
+    pub schema: Option<String>,
+    /// The table of a column.
+    pub table: Option<String>,
+    pub name: String,
+}
+
+impl SchemaObject {
+    /// Returns e.g. `shop.public.users.email`.
+    pub fn path(&self) -> String {
+        [
+            Some(&self.database),
+            self.schema.as_ref(),
+            self.table.as_ref(),
+            Some(&self.name),
+        ]
+        .into_iter()
+        .flatten()
+        .join(".")
+    }
+}
+
+pub struct Trigger {
+    pub name: String,
+    pub timing: String,
+    pub event: String,
+    pub statement: String,
+}
+
+impl TableRow for Trigger {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            "name".to_string(),
+            "timing".to_string(),
+            "event".to_string(),
+            "statement".to_string(),
+        ]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.timing.clone(),
+            self.event.clone(),
+            self.statement.clone(),
+        ]
+    }
+}
+
+/// A figure about a table, e.g. its size on disk.
+pub struct Statistic {
+    pub name: String,
+    pub value: String,
+}
+
+impl Statistic {
+    /// Builds the rows from `(name, value)` pairs, leaving out the figures the server does not
+    /// report.
+    pub fn rows(pairs: Vec<(&str, Option<String>)>) -> Vec<Box<dyn TableRow>> {
+        pairs
+            .into_iter()
+            .filter_map(|(name, value)| {
+                value.filter(|value| !value.is_empty()).map(|value| {
+                    Box::new(Self {
+                        name: name.to_string(),
+                        value,
+                    }) as Box<dyn TableRow>
+                })
+            })
+            .collect()
+    }
+}
+
+impl TableRow for Statistic {
+    fn fields(&self) -> Vec<String> {
+        vec!["name".to_string(), "value".to_string()]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![self.name.clone(), self.value.clone()]
+    }
+}
+
+/// A privilege held on a table, or on its database or the whole server.
+pub struct Privilege {
+    pub grantee: String,
+    /// E.g. `SELECT` or `INSERT`.
+    pub privilege: String,
+    /// `table`, `database` or `global`.
+    pub level: String,
+    /// True if the grantee may grant the privilege to others.
+    pub grantable: bool,
+}
+
+impl TableRow for Privilege {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            "grantee".to_string(),
+            "privilege".to_string(),
+            "level".to_string(),
+            "grantable".to_string(),
+        ]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![
+            self.grantee.clone(),
+            self.privilege.clone(),
+            self.level.clone(),
+            if self.grantable { "YES" } else { "NO" }.to_string(),
+        ]
+    }
+}
+
+/// A foreign key from the columns of a table to those of the table it references.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relation {
+    pub name: String,
+    pub schema: Option<String>,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub ref_schema: Option<String>,
+    pub ref_table: String,
+    pub ref_columns: Vec<String>,
+}
+
+impl Relation {
+    /// Merges the foreign keys read one column per row, the columns of a key in a row after
+    /// the other, into one per key.
+    pub fn merge_columns(relations: Vec<Relation>) -> Vec<Relation> {
+        let mut merged: Vec<Relation> = Vec::new();
+        for relation in relations {
+            match merged.last_mut() {
+                Some(last)
+                    if last.name == relation.name
+                        && last.schema == relation.schema
+                        && last.table == relation.table =>
+                {
+                    last.columns.extend(relation.columns);
+                    last.ref_columns.extend(relation.ref_columns);
+                }
+                _ => merged.push(relation),
+            }
+        }
+        merged
+    }
+}
+
+/// A partition of a table.
+pub struct Partition {
+    pub name: String,
+    /// The values the partition holds, e.g. `FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')`.
+    pub bounds: String,
+    /// The estimated number of rows, unknown until the partition is analyzed.
+    pub rows: Option<i64>,
+    /// The size in bytes with the indexes.
+    pub size: Option<u64>,
+}
+
+impl TableRow for Partition {
+    fn fields(&self) -> Vec<String> {
+        vec![
+            "name".to_string(),
+            "bounds".to_string(),
+            "rows".to_string(),
+            "size".to_string(),
+        ]
+    }
+
+    fn columns(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.bounds.clone(),
+            self.rows.map(|rows| rows.to_string()).unwrap_or_default(),
+            self.size.map(format_bytes).unwrap_or_default(),
+        ]
+    }
+}
+
+/// A user or a role of the server. MySQL names a user with its host, e.g. `app@%`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseRole {
+    pub name: String,
+    /// False for a role, or a locked account.
+    pub can_login: bool,
+    pub superuser: bool,
+    /// The roles granted to this one.
+    pub member_of: Vec<String>,
+}
+
+/// Formats a size in bytes with a binary unit, e.g. `1.5 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// The figures of the dashboard of a connection.
+#[derive(Debug, Default)]
+pub struct ServerOverview {
+    /// `(name, value)` pairs, e.g. the uptime, leaving out the figures the server does not
+    /// report.
+    pub figures: Vec<(String, String)>,
+    /// The names of the largest tables with their size in bytes, the largest first.
+    pub largest_tables: Vec<(String, u64)>,
+}
+
+impl ServerOverview {
+    /// How many of the largest tables are shown.
+    pub const LARGEST_TABLES: usize = 10;
+
+    pub fn new(figures: Vec<(&str, Option<String>)>, largest_tables: Vec<(String, u64)>) -> Self {
+        Self {
+            figures: figures
+                .into_iter()
+                .filter_map(|(name, value)| {
+                    value
+                        .filter(|value| !value.is_empty())
+                        .map(|value| (name.to_string(), value))
+                })
+                .collect(),
+            largest_tables,
+        }
+    }
+}
+
+/// A session of a client of the server, or a query for ClickHouse, which has no sessions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerSession {
+    /// What `ddl::kill_session` ends the session by: the connection id for MySQL, the process
+    /// id for PostgreSQL, the query id for ClickHouse.
+    pub id: String,
+    pub user: String,
+    pub database: Option<String>,
+    /// E.g. `Query` or `Sleep` for MySQL, `active` or `idle` for PostgreSQL.
+    pub state: String,
+    /// How many seconds the query has run, or the session has been idle.
+    pub elapsed: Option<u64>,
+    pub query: Option<String>,
+}
+
+/// Formats a duration in seconds with its two largest units, e.g. `3d 4h` or `12m 5s`.
+pub fn format_uptime(seconds: u64) -> String {
+    let units = [
+        (seconds / 86_400, "d"),
+        (seconds / 3_600 % 24, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let first = units
+        .iter()
+        .position(|(value, _)| *value > 0)
+        .unwrap_or(units.len() - 1);
+    units[first..]
+        .iter()
+        .take(2)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the share of the reads which were served from a cache as a percentage, or `None`
+/// if nothing was read yet.
+pub fn hit_ratio(hits: u64, misses: u64) -> Option<String> {
+    let total = hits.saturating_add(misses);
+    (total > 0).then(|| format!("{:.2}%", hits as f64 * 100.0 / total as f64))
 }
 
 pub enum ExecuteResult {
     Read {
         headers: Vec<String>,
-        rows: Vec<Vec<String>>,
+        rows: Vec<Vec<Value>>,
         database: Database,
-        table: Table,
+        table: Box<Table>,
+        /// True if the rows were cut off at the memory budget.
+        truncated: bool,
     },
     Write {
         updated_rows: u64,
     },
 }
 
+impl ExecuteResult {
+    /// Returns the headers and the rows of a read.
+    pub fn into_records(self) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        match self {
+            Self::Read { headers, rows, .. } => Ok((headers, rows)),
+            Self::Write { .. } => anyhow::bail!("the statement did not return any rows"),
+        }
+    }
+}
+
+/// How much memory the rows of a result may take on a connection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryBudget {
+    /// The estimated size of the rows in bytes.
+    pub bytes: usize,
+    /// Whether the rows over the budget are written to a temporary file rather than dropped.
+    pub spill: bool,
+}
+
+/// Collects the rows of a query until their estimated size goes over the memory budget.
+pub struct RowBuffer {
+    budget: usize,
+    size: usize,
+    pub rows: Vec<Vec<Value>>,
+    pub truncated: bool,
+}
+
+impl RowBuffer {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            size: 0,
+            rows: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    /// Adds `row` and returns false if no more rows should be fetched.
+    pub fn push(&mut self, row: Vec<Value>) -> bool {
+        if self.try_push(row).is_err() {
+            self.truncated = true;
+            return false;
+        }
+        true
+    }
+
+    /// Adds `row`, or gives it back if it does not fit into the budget any more.
+    pub fn try_push(&mut self, row: Vec<Value>) -> Result<(), Vec<Value>> {
+        let size = self.size + row_size(&row);
+        if size > self.budget {
+            return Err(row);
+        }
+        self.size = size;
+        self.rows.push(row);
+        Ok(())
+    }
+}
+
+/// A part of the result of a query which is streamed by `Pool::stream`.
+#[derive(Debug)]
+pub enum StreamedRows {
+    /// The rows which arrived since the last part.
+    Rows {
+        headers: Vec<String>,
+        rows: Vec<Vec<Value>>,
+    },
+    /// All rows were sent, or the rest was dropped at the memory budget if `truncated`, or
+    /// written to `spilled` if the connection spills to disk.
+    Done {
+        truncated: bool,
+        spilled: Option<SpillFile>,
+    },
+}
+
+pub type RowStream = mpsc::Receiver<anyhow::Result<StreamedRows>>;
+
+/// How many databases have their tables read at once.
+const METADATA_CONCURRENCY: usize = 8;
+
+/// Reads the tables of the databases named `names` a few at a time rather than one after the
+/// other, so that e.g. filtering the tree of a server with hundreds of databases does not wait
+/// for each in turn, keeping their order.
+pub async fn get_databases_with_tables<P: Pool + ?Sized>(
+    pool: &P,
+    names: Vec<String>,
+) -> anyhow::Result<Vec<Database>> {
+    futures::stream::iter(names)
+        .map(|name| async move {
+            let tables = pool.get_tables(name.clone()).await?;
+            Ok::<_, anyhow::Error>(Database::new(name, tables))
+        })
+        .buffered(METADATA_CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+/// A query which only checks that the server answers, owning what it needs to run.
+pub type Ping = futures::future::BoxFuture<'static, anyhow::Result<()>>;
+
+/// Sends the rows of a streamed query in parts, so that the receiver is not woken up for
+/// every row.
+pub struct RowSender {
+    sender: mpsc::Sender<anyhow::Result<StreamedRows>>,
+    buffer: RowBuffer,
+    spill_to_disk: bool,
+    spill: Option<SpillFile>,
+    headers: Vec<String>,
+    sent_at: Instant,
+}
+
+impl RowSender {
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn channel(memory_budget: MemoryBudget) -> (Self, RowStream) {
+        let (sender, receiver) = mpsc::channel(16);
+        (
+            Self {
+                sender,
+                buffer: RowBuffer::new(memory_budget.bytes),
+                spill_to_disk: memory_budget.spill,
+                spill: None,
+                headers: Vec::new(),
+                sent_at: Instant::now(),
+            },
+            receiver,
+        )
+    }
+
+    /// Adds `row` and returns false if no more rows should be fetched, because of the memory
+    /// budget or because nobody waits for them any more. `headers` is called for the first row.
+    /// The rows over the budget are written to a spill file if the connection spills to disk.
+    pub async fn push(&mut self, row: Vec<Value>, headers: impl FnOnce() -> Vec<String>) -> bool {
+        if self.headers.is_empty() {
+            self.headers = headers();
+        }
+        if self.spill.is_none() {
+            let row = match self.buffer.try_push(row) {
+                Ok(()) if self.sent_at.elapsed() < Self::INTERVAL => return true,
+                Ok(()) => return self.flush().await,
+                Err(row) => row,
+            };
+            if !self.spill_to_disk {
+                self.buffer.truncated = true;
+                return false;
+            }
+            match SpillFile::create() {
+                Ok(spill) => self.spill = Some(spill),
+                Err(error) => return self.stop_spilling(error),
+            }
+            if !self.flush().await {
+                return false;
+            }
+            return self.spill_row(&row);
+        }
+        self.spill_row(&row) && !self.sender.is_closed()
+    }
+
+    fn spill_row(&mut self, row: &[Value]) -> bool {
+        let result = match self.spill.as_mut() {
+            Some(spill) => spill.push(row),
+            None => return false,
+        };
+        match result {
+            Ok(()) => true,
+            Err(error) => self.stop_spilling(error),
+        }
+    }
+
+    /// Keeps the rows spilled so far but drops the rest, as if the result was truncated.
+    fn stop_spilling(&mut self, error: anyhow::Error) -> bool {
+        crate::log!(Error, "Failed to spill rows to disk: {:#}", error);
+        self.buffer.truncated = true;
+        false
+    }
+
+    async fn flush(&mut self) -> bool {
+        self.sent_at = Instant::now();
+        // the buffer keeps counting the size of the sent rows against the budget
+        let rows = std::mem::take(&mut self.buffer.rows);
+        if rows.is_empty() {
+            return !self.sender.is_closed();
+        }
+        self.sender
+            .send(Ok(StreamedRows::Rows {
+                headers: self.headers.clone(),
+                rows,
+            }))
+            .await
+            .is_ok()
+    }
+
+    /// Sends the remaining rows and then `result`.
+    pub async fn finish(mut self, result: anyhow::Result<()>) {
+        if !self.flush().await {
+            return;
+        }
+        let truncated = self.buffer.truncated;
+        let spilled = self.spill.take();
+        let _ = self
+            .sender
+            .send(result.map(|()| StreamedRows::Done { truncated, spilled }))
+            .await;
+    }
+}
+
+/// Returns the estimated number of bytes `row` takes in memory.
+pub fn row_size(row: &[Value]) -> usize {
+    row.iter().map(Value::estimated_size).sum()
+}
+
+/// The statement which failed, attached to its error as context so that the error console can
+/// show it.
+#[derive(Debug)]
+pub struct FailedStatement(pub String);
+
+impl std::fmt::Display for FailedStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to run the statement")
+    }
+}
+
+/// Returns a short description of the missing privilege if `error` was raised because
+/// the current user is not allowed to read a catalog or a table.
+pub fn missing_privilege(error: &anyhow::Error) -> Option<String> {
+    let database_error = match error.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(database_error)) => database_error,
+        _ => return None,
+    };
+    let message = database_error.message();
+    match database_error.code().as_deref() {
+        // postgres: insufficient_privilege
+        Some("42501") => Some(
+            message
+                .strip_prefix("permission denied for ")
+                .map_or(message.to_string(), |object| {
+                    format!("no privilege on {}", object)
+                }),
+        ),
+        // mysql: ER_TABLEACCESS_DENIED_ERROR, ER_COLUMNACCESS_DENIED_ERROR
+        Some("42000") if message.contains(" command denied to user ") => Some(
+            message
+                .split_once(" command denied")
+                .map_or(message.to_string(), |(command, _)| {
+                    format!("{} privilege", command)
+                }),
+        ),
+        // mysql: ER_SPECIFIC_ACCESS_DENIED_ERROR, ER_DBACCESS_DENIED_ERROR
+        Some("42000") if message.starts_with("Access denied") => Some(
+            message
+                .split_once("the ")
+                .and_then(|(_, rest)| rest.split_once(" privilege"))
+                .map_or(message.to_string(), |(privilege, _)| {
+                    format!("{} privilege", privilege)
+                }),
+        ),
+        _ => None,
+    }
+}
+
+/// Returns true if `error` is likely to succeed when the same query is run again,
+/// e.g. a deadlock, a serialization failure or a reset connection.
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    if is_disconnect(error) {
+        return true;
+    }
+    match error.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(database_error)) => {
+            is_transient_code(database_error.code().as_deref())
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if the error means the server could not be reached, e.g. it restarted.
+pub fn is_disconnect(error: &anyhow::Error) -> bool {
+    if let Some(e) = error.downcast_ref::<reqwest::Error>() {
+        return e.is_connect() || e.is_timeout();
+    }
+    match error.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Io(e)) => matches!(
+            e.kind(),
+            std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::BrokenPipe
+                | std::io::ErrorKind::UnexpectedEof
+                | std::io::ErrorKind::TimedOut
+        ),
+        Some(sqlx::Error::PoolTimedOut) => true,
+        _ => false,
+    }
+}
+
+fn is_transient_code(code: Option<&str>) -> bool {
+    match code {
+        // postgres: serialization_failure, deadlock_detected
+        // mysql: ER_LOCK_DEADLOCK
+        Some("40001") | Some("40P01") => true,
+        // sqlite: SQLITE_BUSY, SQLITE_LOCKED and their extended codes,
+        // which are shorter than the five characters of SQLSTATE
+        Some(code) if code.len() < 5 => code
+            .parse::<u32>()
+            .is_ok_and(|code| matches!(code & 0xff, 5 | 6)),
+        _ => false,
+    }
+}
+
 pub trait TableRow: std::marker::Send {
     fn fields(&self) -> Vec<String>;
     fn columns(&self) -> Vec<String>;
 }
 
-#[macro_export]
-macro_rules! get_or_null {
-    ($value:expr) => {
-        $value.map_or("NULL".to_string(), |v| v.to_string())
+#[cfg(test)]
+mod test {
+    use super::{
+        check_setting_name, format_bytes, format_uptime, hit_ratio, is_transient_code,
+        MemoryBudget, Relation, RowBuffer, RowSender, ServerOverview, SessionSetting, Statistic,
+        StreamedRows, Value,
     };
+
+    #[test]
+    fn test_row_buffer() {
+        let row = vec![Value::Int(1), Value::Text("a".to_string())];
+        let mut buffer = RowBuffer::new(super::row_size(&row) * 2);
+        assert!(buffer.push(row.clone()));
+        assert!(buffer.push(row.clone()));
+        assert!(!buffer.push(row));
+        assert_eq!(buffer.rows.len(), 2);
+        assert!(buffer.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_row_sender() {
+        let row = vec![Value::Int(1)];
+        let (mut sender, mut stream) = RowSender::channel(MemoryBudget {
+            bytes: super::row_size(&row) * 3,
+            spill: false,
+        });
+        for _ in 0..3 {
+            assert!(sender.push(row.clone(), || vec!["id".to_string()]).await);
+        }
+        assert!(!sender.push(row.clone(), || unreachable!()).await);
+        sender.finish(Ok(())).await;
+
+        let mut rows = 0;
+        while let Some(message) = stream.recv().await {
+            match message.unwrap() {
+                StreamedRows::Rows {
+                    headers,
+                    rows: part,
+                } => {
+                    assert_eq!(headers, vec!["id".to_string()]);
+                    rows += part.len();
+                }
+                StreamedRows::Done { truncated, spilled } => {
+                    assert!(truncated);
+                    assert!(spilled.is_none());
+                }
+            }
+        }
+        assert_eq!(rows, 3);
+    }
+
+    #[tokio::test]
+    async fn test_row_sender_spills_to_disk() {
+        let row = vec![Value::Int(1)];
+        let (mut sender, mut stream) = RowSender::channel(MemoryBudget {
+            bytes: super::row_size(&row) * 2,
+            spill: true,
+        });
+        for _ in 0..5 {
+            assert!(sender.push(row.clone(), || vec!["id".to_string()]).await);
+        }
+        sender.finish(Ok(())).await;
+
+        let mut rows = 0;
+        while let Some(message) = stream.recv().await {
+            match message.unwrap() {
+                StreamedRows::Rows { rows: part, .. } => rows += part.len(),
+                StreamedRows::Done { truncated, spilled } => {
+                    assert!(!truncated);
+                    let mut spilled = spilled.unwrap();
+                    assert_eq!(spilled.remaining(), 3);
+                    assert_eq!(spilled.read(10).unwrap(), vec![row.clone(); 3]);
+                }
+            }
+        }
+        assert_eq!(rows, 2);
+    }
+
+    #[tokio::test]
+    async fn test_row_sender_stops_without_receiver() {
+        let (mut sender, stream) = RowSender::channel(MemoryBudget {
+            bytes: usize::MAX,
+            spill: false,
+        });
+        drop(stream);
+        // let the next row be sent at once
+        sender.sent_at -= RowSender::INTERVAL;
+        assert!(!sender.push(vec![Value::Int(1)], Vec::new).await);
+    }
+
+    #[test]
+    fn test_is_transient_code() {
+        assert!(is_transient_code(Some("40001")));
+        assert!(is_transient_code(Some("40P01")));
+        assert!(is_transient_code(Some("5")));
+        assert!(is_transient_code(Some("517")));
+        assert!(!is_transient_code(Some("42501")));
+        assert!(!is_transient_code(Some("19")));
+        assert!(!is_transient_code(None));
+    }
+
+    #[test]
+    fn test_statistics() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+
+        let rows = Statistic::rows(vec![
+            ("rows", Some("3".to_string())),
+            ("engine", None),
+            ("collation", Some(String::new())),
+        ]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].columns(), vec!["rows", "3"]);
+    }
+
+    #[test]
+    fn test_server_overview() {
+        assert_eq!(format_uptime(0), "0s");
+        assert_eq!(format_uptime(725), "12m 5s");
+        assert_eq!(format_uptime(3 * 86_400 + 4 * 3_600 + 59), "3d 4h");
+        assert_eq!(hit_ratio(999, 1), Some("99.90%".to_string()));
+        assert_eq!(hit_ratio(0, 0), None);
+
+        let overview = ServerOverview::new(
+            vec![
+                ("server version", Some("8.0.36".to_string())),
+                ("uptime", None),
+            ],
+            vec![],
+        );
+        assert_eq!(
+            overview.figures,
+            vec![("server version".to_string(), "8.0.36".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_session_setting() {
+        let settings = SessionSetting::from_rows(
+            vec![
+                ("time_zone".to_string(), "SYSTEM".to_string()),
+                ("sql_mode".to_string(), "ANSI".to_string()),
+            ],
+            &[("time_zone", &["SYSTEM", "+00:00"])],
+        );
+        assert_eq!(
+            settings[0].next_choice().map(String::as_str),
+            Some("+00:00")
+        );
+        assert_eq!(settings[1].next_choice(), None);
+
+        assert!(check_setting_name("search_path").is_ok());
+        assert!(check_setting_name("x = 1; DROP TABLE users").is_err());
+    }
+
+    #[test]
+    fn test_merge_relation_columns() {
+        let relation = |name: &str, column: &str, ref_column: &str| Relation {
+            name: name.to_string(),
+            schema: None,
+            table: "order_items".to_string(),
+            columns: vec![column.to_string()],
+            ref_schema: None,
+            ref_table: "orders".to_string(),
+            ref_columns: vec![ref_column.to_string()],
+        };
+        let merged = Relation::merge_columns(vec![
+            relation("fk_order", "order_id", "id"),
+            relation("fk_order", "shop_id", "shop_id"),
+            relation("fk_product", "product_id", "id"),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].columns, vec!["order_id", "shop_id"]);
+        assert_eq!(merged[0].ref_columns, vec!["id", "shop_id"]);
+        assert_eq!(merged[1].columns, vec!["product_id"]);
+    }
 }
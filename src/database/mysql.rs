@@ -1,17 +1,51 @@
-use crate::get_or_null;
-
-use super::{ExecuteResult, Pool, TableRow};
-use crate::tree::{Child, Database, Table};
+use super::script::is_select;
+use super::{
+    check_setting_name, format_bytes, format_uptime, hit_ratio, Capabilities, Capability,
+    DatabaseRole, Dependency, Dialect, ExecuteResult, MemoryBudget, Parameter, Partition, Ping,
+    Pool, Privilege, QueryParams, Relation, RowBuffer, RowSender, RowStream, SchemaObject, Server,
+    ServerOverview, ServerSession, SessionSetting, Statistic, TableRow, TransactionSlot, Trigger,
+    Value,
+};
+use crate::tree::{Child, Database, Table, TableKind};
 use async_trait::async_trait;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use futures::TryStreamExt;
 use sqlx::mysql::{MySqlColumn, MySqlPoolOptions, MySqlRow};
 use sqlx::{Column as _, Row as _, TypeInfo as _};
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
+/// The settings shown in the session panel and the values they can be switched to.
+/// `tx_isolation` is the name of `transaction_isolation` before MySQL 8.0.
+const SESSION_SETTINGS: [(&str, &[&str]); 4] = [
+    ("time_zone", &["SYSTEM", "+00:00"]),
+    ("sql_mode", &["TRADITIONAL", "ANSI", ""]),
+    (
+        "transaction_isolation",
+        &[
+            "READ-UNCOMMITTED",
+            "READ-COMMITTED",
+            "REPEATABLE-READ",
+            "SERIALIZABLE",
+        ],
+    ),
+    (
+        "tx_isolation",
+        &[
+            "READ-UNCOMMITTED",
+            "READ-COMMITTED",
+            "REPEATABLE-READ",
+            "SERIALIZABLE",
+        ],
+    ),
+];
+
 pub struct MySqlPool {
     pool: sqlx::mysql::MySqlPool,
     limit_size: usize,
+    memory_budget: MemoryBudget,
+    transaction: TransactionSlot<sqlx::MySql>,
+    capabilities: Capabilities,
 }
 
 impl MySqlPool {
@@ -19,29 +53,278 @@ impl MySqlPool {
         database_url: &str,
         limit_size: usize,
         timeout_second: u64,
+        memory_budget: MemoryBudget,
+        session: &BTreeMap<String, String>,
     ) -> anyhow::Result<Self> {
+        for name in session.keys() {
+            check_setting_name(name)?;
+        }
+        let session = session.clone();
+        let pool = MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(timeout_second))
+            .after_connect(move |connection, _| {
+                let session = session.clone();
+                Box::pin(async move {
+                    for (name, value) in session {
+                        sqlx::query(&format!("SET SESSION {} = ?", name))
+                            .bind(value)
+                            .execute(&mut *connection)
+                            .await?;
+                    }
+                    Ok(())
+                })
+            })
+            .connect(database_url)
+            .await?;
+        let capabilities = sqlx::query_scalar::<_, String>("SELECT VERSION()")
+            .fetch_one(&pool)
+            .await
+            .map(|version| Capabilities::new(Server::MySql, &version))
+            .unwrap_or_default();
         Ok(Self {
-            pool: MySqlPoolOptions::new()
-                .acquire_timeout(Duration::from_secs(timeout_second))
-                .connect(database_url)
-                .await?,
+            pool,
             limit_size,
+            memory_budget,
+            transaction: TransactionSlot::new(),
+            capabilities,
         })
     }
+
+    /// Returns the parameters of a routine, the return value of a function first.
+    async fn get_parameters(
+        &self,
+        database: &Database,
+        routine: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            CAST(COALESCE(PARAMETER_NAME, '(return)') AS CHAR),
+            CAST(COALESCE(PARAMETER_MODE, '') AS CHAR),
+            CAST(DTD_IDENTIFIER AS CHAR)
+        FROM information_schema.PARAMETERS
+        WHERE SPECIFIC_SCHEMA = ? AND SPECIFIC_NAME = ?
+        ORDER BY ORDINAL_POSITION
+        ",
+        )
+        .bind(&database.name)
+        .bind(&routine.name)
+        .fetch(&self.pool);
+        let mut parameters: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            parameters.push(Box::new(Parameter {
+                name: row.try_get(0)?,
+                mode: row.try_get(1)?,
+                r#type: row.try_get(2)?,
+            }))
+        }
+        Ok(parameters)
+    }
+
+    async fn execute_with<'e, E>(
+        &self,
+        executor: E,
+        query: &str,
+        values: &[Value],
+    ) -> anyhow::Result<ExecuteResult>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::MySql>,
+    {
+        let query = query.trim();
+
+        if is_select(query) {
+            let mut rows = bind_values(query, values).fetch(executor);
+            let mut headers = vec![];
+            let mut records = RowBuffer::new(self.memory_budget.bytes);
+            while let Some(row) = rows.try_next().await? {
+                headers = row
+                    .columns()
+                    .iter()
+                    .map(|column| column.name().to_string())
+                    .collect();
+                let mut new_row = vec![];
+                for column in row.columns() {
+                    new_row.push(convert_column_value(&row, column)?)
+                }
+                if !records.push(new_row) {
+                    break;
+                }
+            }
+
+            return Ok(ExecuteResult::Read {
+                headers,
+                truncated: records.truncated,
+                rows: records.rows,
+                database: Database {
+                    name: "-".to_string(),
+                    children: Vec::new(),
+                },
+                table: Box::new(Table {
+                    name: "-".to_string(),
+                    create_time: None,
+                    update_time: None,
+                    engine: None,
+                    schema: None,
+                    kind: TableKind::Table,
+                    partition_of: None,
+                }),
+            });
+        }
+
+        let result = bind_values(query, values).execute(executor).await?;
+        Ok(ExecuteResult::Write {
+            updated_rows: result.rows_affected(),
+        })
+    }
+
+    async fn get_check_constraints(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            tc.CONSTRAINT_NAME,
+            cc.CHECK_CLAUSE
+        FROM
+            information_schema.TABLE_CONSTRAINTS AS tc
+            JOIN information_schema.CHECK_CONSTRAINTS AS cc ON cc.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA
+            AND cc.CONSTRAINT_NAME = tc.CONSTRAINT_NAME
+        WHERE
+            tc.CONSTRAINT_TYPE = 'CHECK'
+            AND tc.TABLE_SCHEMA = ?
+            AND tc.TABLE_NAME = ?
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut constraints: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            constraints.push(Box::new(Constraint {
+                name: row.try_get("CONSTRAINT_NAME")?,
+                column_name: String::new(),
+                definition: row.try_get("CHECK_CLAUSE")?,
+            }))
+        }
+        Ok(constraints)
+    }
+
+    /// Returns the state of a MariaDB sequence, which is stored as its only row.
+    async fn get_sequence_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let query = format!(
+            "
+        SELECT
+            CAST(next_not_cached_value AS CHAR) AS next_value,
+            CAST(increment AS CHAR) AS increment,
+            CAST(minimum_value AS CHAR) AS minimum_value,
+            CAST(maximum_value AS CHAR) AS maximum_value,
+            CAST(start_value AS CHAR) AS start_value,
+            CAST(cache_size AS CHAR) AS cache_size,
+            CAST(cycle_option AS CHAR) AS cycle_option
+        FROM `{}`.`{}`
+        ",
+            database.name, table.name
+        );
+        let row = sqlx::query(&query).fetch_one(&self.pool).await?;
+        // a sequence belongs to no column, but is used by the defaults which take from it
+        let used_by: Vec<String> = sqlx::query_scalar(
+            "
+        SELECT CAST(CONCAT(TABLE_NAME, '.', COLUMN_NAME) AS CHAR)
+        FROM information_schema.COLUMNS
+        WHERE TABLE_SCHEMA = ?
+            AND COLUMN_DEFAULT LIKE 'nextval(%'
+            AND LOCATE(CONCAT('`', ?, '`)'), COLUMN_DEFAULT) > 0
+        ORDER BY TABLE_NAME, COLUMN_NAME
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(Statistic::rows(vec![
+            ("next value (not cached)", row.try_get("next_value")?),
+            ("increment", row.try_get("increment")?),
+            ("minimum", row.try_get("minimum_value")?),
+            ("maximum", row.try_get("maximum_value")?),
+            ("start", row.try_get("start_value")?),
+            ("cache", row.try_get("cache_size")?),
+            (
+                "cycles",
+                Some(
+                    if row.try_get::<Option<String>, _>("cycle_option")?.as_deref() == Some("1") {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                    .to_string(),
+                ),
+            ),
+            ("used by", Some(used_by.join(", "))),
+        ]))
+    }
+}
+
+/// Returns the members of an `enum('a','b')` or `set('a','b')` column type.
+fn enum_members(r#type: &str) -> Option<Vec<String>> {
+    let lower = r#type.to_lowercase();
+    let list = if lower.starts_with("enum(") {
+        &r#type[5..]
+    } else if lower.starts_with("set(") {
+        &r#type[4..]
+    } else {
+        return None;
+    };
+    let mut members = Vec::new();
+    let mut chars = list.strip_suffix(')').unwrap_or(list).chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\'' {
+            continue;
+        }
+        let mut member = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                // a quote is escaped by doubling it
+                '\'' if chars.peek() == Some(&'\'') => {
+                    chars.next();
+                    member.push('\'');
+                }
+                '\'' => break,
+                c => member.push(c),
+            }
+        }
+        members.push(member);
+    }
+    Some(members)
 }
 
 pub struct Constraint {
     name: String,
     column_name: String,
+    /// The expression of a CHECK constraint.
+    definition: Option<String>,
 }
 
 impl TableRow for Constraint {
     fn fields(&self) -> Vec<String> {
-        vec!["name".to_string(), "column_name".to_string()]
+        vec![
+            "name".to_string(),
+            "column_name".to_string(),
+            "definition".to_string(),
+        ]
     }
 
     fn columns(&self) -> Vec<String> {
-        vec![self.name.to_string(), self.column_name.to_string()]
+        vec![
+            self.name.to_string(),
+            self.column_name.to_string(),
+            self.definition.clone().unwrap_or_default(),
+        ]
     }
 }
 
@@ -51,6 +334,8 @@ pub struct Column {
     null: Option<String>,
     default: Option<String>,
     comment: Option<String>,
+    /// The members of an enum or set type.
+    values: Option<Vec<String>>,
 }
 
 impl TableRow for Column {
@@ -61,6 +346,7 @@ impl TableRow for Column {
             "null".to_string(),
             "default".to_string(),
             "comment".to_string(),
+            "values".to_string(),
         ]
     }
 
@@ -81,6 +367,9 @@ impl TableRow for Column {
             self.comment
                 .as_ref()
                 .map_or(String::new(), |comment| comment.to_string()),
+            self.values
+                .as_ref()
+                .map_or(String::new(), |values| values.join(", ")),
         ]
     }
 }
@@ -150,49 +439,79 @@ impl TableRow for Index {
     }
 }
 
+/// Builds `query` with `values` bound to its placeholders, as numbers where they were typed
+/// as one.
+fn bind_values<'q>(
+    query: &'q str,
+    values: &[Value],
+) -> sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments> {
+    values
+        .iter()
+        .fold(sqlx::query(query), |query, value| match value {
+            Value::Null => query.bind(None::<String>),
+            Value::Int(value) => query.bind(*value),
+            Value::Float(value) => query.bind(*value),
+            value => query.bind(value.to_string()),
+        })
+}
+
 #[async_trait]
 impl Pool for MySqlPool {
     async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
-        let query = query.trim();
+        let mut transaction = self.transaction.lock().await;
+        let result = match transaction.as_mut() {
+            Some(transaction) => self.execute_with(&mut **transaction, query, &[]).await,
+            None => self.execute_with(&self.pool, query, &[]).await,
+        };
+        result.map_err(|e| self.capabilities.explain(query, e))
+    }
 
-        if query.to_uppercase().starts_with("SELECT") {
-            let mut rows = sqlx::query(query).fetch(&self.pool);
-            let mut headers = vec![];
-            let mut records = vec![];
-            while let Some(row) = rows.try_next().await? {
-                headers = row
-                    .columns()
-                    .iter()
-                    .map(|column| column.name().to_string())
-                    .collect();
-                let mut new_row = vec![];
-                for column in row.columns() {
-                    new_row.push(convert_column_value_to_string(&row, column)?)
-                }
-                records.push(new_row)
-            }
+    async fn execute_params(&self, query: &str, values: &[Value]) -> anyhow::Result<ExecuteResult> {
+        let (query, values) =
+            QueryParams::parse(query, Dialect::MySql).bind(Dialect::MySql, values)?;
+        let mut transaction = self.transaction.lock().await;
+        let result = match transaction.as_mut() {
+            Some(transaction) => self.execute_with(&mut **transaction, &query, &values).await,
+            None => self.execute_with(&self.pool, &query, &values).await,
+        };
+        result.map_err(|e| self.capabilities.explain(&query, e))
+    }
 
-            return Ok(ExecuteResult::Read {
-                headers,
-                rows: records,
-                database: Database {
-                    name: "-".to_string(),
-                    children: Vec::new(),
-                },
-                table: Table {
-                    name: "-".to_string(),
-                    create_time: None,
-                    update_time: None,
-                    engine: None,
-                    schema: None,
-                },
-            });
+    fn stream(&self, query: &str) -> Option<RowStream> {
+        let query = query.trim().to_string();
+        // a transaction holds its connection, which the task cannot borrow
+        if !is_select(&query) || self.transaction.is_open() {
+            return None;
         }
-
-        let result = sqlx::query(query).execute(&self.pool).await?;
-        Ok(ExecuteResult::Write {
-            updated_rows: result.rows_affected(),
-        })
+        let pool = self.pool.clone();
+        let (mut sender, stream) = RowSender::channel(self.memory_budget);
+        let capabilities = self.capabilities;
+        tokio::spawn(async move {
+            let result = async {
+                let mut rows = sqlx::query(&query).fetch(&pool);
+                while let Some(row) = rows.try_next().await? {
+                    let mut new_row = vec![];
+                    for column in row.columns() {
+                        new_row.push(convert_column_value(&row, column)?)
+                    }
+                    let headers = || {
+                        row.columns()
+                            .iter()
+                            .map(|column| column.name().to_string())
+                            .collect()
+                    };
+                    if !sender.push(new_row, headers).await {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            .await;
+            sender
+                .finish(result.map_err(|e| capabilities.explain(&query, e)))
+                .await;
+        });
+        Some(stream)
     }
 
     async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
@@ -202,14 +521,10 @@ impl Pool for MySqlPool {
             .iter()
             .map(|table| table.get(0))
             .collect::<Vec<String>>();
-        let mut list = vec![];
-        for db in databases {
-            list.push(Database::new(
-                db.clone(),
-                self.get_tables(db.clone()).await?,
-            ))
-        }
-        Ok(list)
+        Ok(databases
+            .into_iter()
+            .map(|name| Database::new(name, Vec::new()))
+            .collect())
     }
 
     async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>> {
@@ -223,6 +538,58 @@ impl Pool for MySqlPool {
                 update_time: row.try_get("Update_time")?,
                 engine: row.try_get("Engine")?,
                 schema: None,
+                // the status of a view has only its name and this comment
+                kind: if row.try_get::<Option<String>, _>("Comment")?.as_deref() == Some("VIEW") {
+                    TableKind::View
+                } else {
+                    TableKind::Table
+                },
+                partition_of: None,
+            })
+        }
+        if self.capabilities.server() == Some(Server::MariaDb) {
+            // the status of a sequence looks like that of a table, only its type tells them apart
+            let sequences: Vec<String> = sqlx::query_scalar(
+                "
+            SELECT CAST(TABLE_NAME AS CHAR)
+            FROM information_schema.TABLES
+            WHERE TABLE_SCHEMA = ? AND TABLE_TYPE = 'SEQUENCE'
+            ",
+            )
+            .bind(&database)
+            .fetch_all(&self.pool)
+            .await?;
+            for table in tables.iter_mut() {
+                if sequences.contains(&table.name) {
+                    table.kind = TableKind::Sequence;
+                }
+            }
+        }
+        // MariaDB lists the packages of its Oracle mode as routines too
+        let mut rows = sqlx::query(
+            "
+        SELECT CAST(ROUTINE_NAME AS CHAR), CAST(ROUTINE_TYPE AS CHAR)
+        FROM information_schema.ROUTINES
+        WHERE ROUTINE_SCHEMA = ? AND ROUTINE_TYPE IN ('FUNCTION', 'PROCEDURE')
+        ORDER BY ROUTINE_NAME
+        ",
+        )
+        .bind(&database)
+        .fetch(&self.pool);
+        while let Some(row) = rows.try_next().await? {
+            let routine_type: String = row.try_get(1)?;
+            tables.push(Table {
+                name: row.try_get(0)?,
+                create_time: None,
+                update_time: None,
+                engine: None,
+                schema: None,
+                kind: if routine_type == "PROCEDURE" {
+                    TableKind::Procedure
+                } else {
+                    TableKind::Function
+                },
+                partition_of: None,
             })
         }
         Ok(tables.into_iter().map(|table| table.into()).collect())
@@ -232,10 +599,10 @@ impl Pool for MySqlPool {
         &self,
         database: &Database,
         table: &Table,
-        page: u16,
+        page: usize,
         filter: Option<String>,
         orders: Option<String>,
-    ) -> anyhow::Result<(Vec<String>, Vec<Vec<String>>)> {
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
         let query = if let (Some(filter), Some(orders)) = (&filter, &orders) {
             format!(
                 "SELECT * FROM `{database}`.`{table}` WHERE {filter} {orders} LIMIT {page}, {limit}",
@@ -284,13 +651,29 @@ impl Pool for MySqlPool {
                 .collect();
             let mut new_row = vec![];
             for column in row.columns() {
-                new_row.push(convert_column_value_to_string(&row, column)?)
+                new_row.push(convert_column_value(&row, column)?)
             }
             records.push(new_row)
         }
         Ok((headers, records))
     }
 
+    async fn get_sample(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        let query = format!(
+            "SELECT * FROM `{database}`.`{table}`{filter} ORDER BY RAND() LIMIT {limit}",
+            database = database.name,
+            table = table.name,
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+            limit = self.limit_size
+        );
+        self.execute(&query).await?.into_records()
+    }
+
     async fn get_total_row_count(
         &self,
         database: &Database,
@@ -320,6 +703,9 @@ impl Pool for MySqlPool {
         database: &Database,
         table: &Table,
     ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        if table.kind.is_routine() {
+            return self.get_parameters(database, table).await;
+        }
         let query = format!(
             "SHOW FULL COLUMNS FROM `{}`.`{}`",
             database.name, table.name
@@ -327,9 +713,11 @@ impl Pool for MySqlPool {
         let mut rows = sqlx::query(query.as_str()).fetch(&self.pool);
         let mut columns: Vec<Box<dyn TableRow>> = vec![];
         while let Some(row) = rows.try_next().await? {
+            let r#type: Option<String> = row.try_get("Type")?;
             columns.push(Box::new(Column {
                 name: row.try_get("Field")?,
-                r#type: row.try_get("Type")?,
+                values: r#type.as_deref().and_then(enum_members),
+                r#type,
                 null: row.try_get("Null")?,
                 default: row.try_get("Default")?,
                 comment: row.try_get("Comment")?,
@@ -365,8 +753,14 @@ impl Pool for MySqlPool {
             constraints.push(Box::new(Constraint {
                 name: row.try_get("CONSTRAINT_NAME")?,
                 column_name: row.try_get("COLUMN_NAME")?,
+                definition: None,
             }))
         }
+        drop(rows);
+        // information_schema.CHECK_CONSTRAINTS only exists since MySQL 8.0.16 and MariaDB 10.2
+        if self.capabilities.supports(Capability::CheckConstraints) {
+            constraints.extend(self.get_check_constraints(database, table).await?);
+        }
         Ok(constraints)
     }
 
@@ -408,6 +802,25 @@ impl Pool for MySqlPool {
         Ok(foreign_keys)
     }
 
+    async fn get_primary_key(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "
+        SELECT CAST(COLUMN_NAME AS CHAR)
+        FROM information_schema.KEY_COLUMN_USAGE
+        WHERE CONSTRAINT_NAME = 'PRIMARY' AND TABLE_SCHEMA = ? AND TABLE_NAME = ?
+        ORDER BY ORDINAL_POSITION
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
     async fn get_indexes(
         &self,
         database: &Database,
@@ -441,77 +854,598 @@ impl Pool for MySqlPool {
         Ok(foreign_keys)
     }
 
+    async fn get_dependencies(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // views and routines are matched by their definitions, which mention `db`.`table`
+        let mut rows = sqlx::query(
+            "
+        SELECT * FROM (
+            SELECT
+                'view' AS kind,
+                CONCAT(TABLE_SCHEMA, '.', TABLE_NAME) AS name,
+                '' AS detail
+            FROM information_schema.VIEWS
+            WHERE VIEW_DEFINITION LIKE CONCAT('%`', ?, '`.`', ?, '`%')
+            UNION ALL
+            SELECT DISTINCT
+                'foreign key',
+                CONSTRAINT_NAME,
+                CONCAT('on ', TABLE_SCHEMA, '.', TABLE_NAME)
+            FROM information_schema.KEY_COLUMN_USAGE
+            WHERE REFERENCED_TABLE_SCHEMA = ? AND REFERENCED_TABLE_NAME = ?
+            UNION ALL
+            SELECT
+                LOWER(ROUTINE_TYPE),
+                CONCAT(ROUTINE_SCHEMA, '.', ROUTINE_NAME),
+                'body mentions the table'
+            FROM information_schema.ROUTINES
+            WHERE ROUTINE_DEFINITION LIKE CONCAT('%', ?, '%')
+            UNION ALL
+            SELECT
+                'trigger',
+                CONCAT(TRIGGER_SCHEMA, '.', TRIGGER_NAME),
+                CONCAT(ACTION_TIMING, ' ', EVENT_MANIPULATION)
+            FROM information_schema.TRIGGERS
+            WHERE EVENT_OBJECT_SCHEMA = ? AND EVENT_OBJECT_TABLE = ?
+        ) AS dependencies
+        ORDER BY kind, name
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .bind(&database.name)
+        .bind(&table.name)
+        .bind(&table.name)
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut dependencies: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            dependencies.push(Box::new(Dependency {
+                kind: row.try_get("kind")?,
+                name: row.try_get("name")?,
+                detail: row.try_get("detail")?,
+            }))
+        }
+        Ok(dependencies)
+    }
+
+    async fn get_triggers(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT TRIGGER_NAME, ACTION_TIMING, EVENT_MANIPULATION, ACTION_STATEMENT
+        FROM information_schema.TRIGGERS
+        WHERE EVENT_OBJECT_SCHEMA = ? AND EVENT_OBJECT_TABLE = ?
+        ORDER BY ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut triggers: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            triggers.push(Box::new(Trigger {
+                name: row.try_get("TRIGGER_NAME")?,
+                timing: row.try_get("ACTION_TIMING")?,
+                event: row.try_get("EVENT_MANIPULATION")?,
+                statement: row.try_get("ACTION_STATEMENT")?,
+            }))
+        }
+        Ok(triggers)
+    }
+
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        if table.kind == TableKind::Sequence {
+            return self.get_sequence_statistics(database, table).await;
+        }
+        let row = sqlx::query(
+            "
+        SELECT
+            CAST(TABLE_ROWS AS SIGNED) AS estimated_rows,
+            CAST(DATA_LENGTH AS SIGNED) AS data_size,
+            CAST(INDEX_LENGTH AS SIGNED) AS index_size,
+            CAST(DATA_FREE AS SIGNED) AS free_size,
+            CAST(ENGINE AS CHAR) AS engine,
+            CAST(TABLE_COLLATION AS CHAR) AS collation,
+            CAST(AUTO_INCREMENT AS CHAR) AS auto_increment,
+            CAST(CREATE_TIME AS CHAR) AS created,
+            CAST(UPDATE_TIME AS CHAR) AS updated,
+            CAST(TABLE_TYPE AS CHAR) AS table_type
+        FROM information_schema.TABLES
+        WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch_one(&self.pool)
+        .await?;
+        let size = |column: &str| -> anyhow::Result<Option<String>> {
+            Ok(row
+                .try_get::<Option<i64>, _>(column)?
+                .map(|bytes| format_bytes(bytes.max(0) as u64)))
+        };
+        let mut statistics = vec![
+            (
+                "rows (exact)",
+                self.get_total_row_count(database, table, None)
+                    .await
+                    .ok()
+                    .map(|count| count.to_string()),
+            ),
+            (
+                "rows (estimated)",
+                row.try_get::<Option<i64>, _>("estimated_rows")?
+                    .map(|count| count.to_string()),
+            ),
+            ("data size", size("data_size")?),
+            ("index size", size("index_size")?),
+            ("free space", size("free_size")?),
+            ("engine", row.try_get("engine")?),
+            ("collation", row.try_get("collation")?),
+            ("next auto increment", row.try_get("auto_increment")?),
+            ("created", row.try_get("created")?),
+            ("updated", row.try_get("updated")?),
+        ];
+        if self.capabilities.server() == Some(Server::MariaDb) {
+            // MariaDB keeps the history of a table WITH SYSTEM VERSIONING in hidden columns
+            let table_type: Option<String> = row.try_get("table_type")?;
+            statistics.push((
+                "system versioned",
+                Some(if table_type.as_deref() == Some("SYSTEM VERSIONED") {
+                    "yes".to_string()
+                } else {
+                    "no".to_string()
+                }),
+            ));
+        }
+        Ok(Statistic::rows(statistics))
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        let status: HashMap<String, String> = sqlx::query_as::<_, (String, String)>(
+            "
+        SHOW GLOBAL STATUS WHERE Variable_name IN (
+            'Uptime', 'Threads_connected', 'Threads_running', 'Max_used_connections',
+            'Innodb_buffer_pool_read_requests', 'Innodb_buffer_pool_reads'
+        )
+        ",
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .collect();
+        let number = |name: &str| status.get(name).and_then(|value| value.parse::<u64>().ok());
+        let max_connections: Option<String> =
+            sqlx::query_scalar("SELECT CAST(@@max_connections AS CHAR)")
+                .fetch_one(&self.pool)
+                .await
+                .ok();
+        let largest_tables: Vec<(String, i64)> = sqlx::query_as(&format!(
+            "
+        SELECT
+            CAST(CONCAT(TABLE_SCHEMA, '.', TABLE_NAME) AS CHAR) AS name,
+            CAST(COALESCE(DATA_LENGTH, 0) + COALESCE(INDEX_LENGTH, 0) AS SIGNED) AS size
+        FROM information_schema.TABLES
+        WHERE TABLE_TYPE = 'BASE TABLE'
+            AND TABLE_SCHEMA NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')
+        ORDER BY size DESC
+        LIMIT {}
+        ",
+            ServerOverview::LARGEST_TABLES
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        // the read requests are served from the buffer pool unless they had to read the disk
+        let reads = number("Innodb_buffer_pool_reads");
+        let buffer_pool_hits = number("Innodb_buffer_pool_read_requests")
+            .zip(reads)
+            .and_then(|(requests, reads)| hit_ratio(requests.saturating_sub(reads), reads));
+        Ok(ServerOverview::new(
+            vec![
+                ("server version", Some(self.capabilities.to_string())),
+                ("uptime", number("Uptime").map(format_uptime)),
+                (
+                    "connections",
+                    status
+                        .get("Threads_connected")
+                        .map(|connected| match &max_connections {
+                            Some(max) => format!("{} of {}", connected, max),
+                            None => connected.clone(),
+                        }),
+                ),
+                ("running threads", status.get("Threads_running").cloned()),
+                (
+                    "most connections",
+                    status.get("Max_used_connections").cloned(),
+                ),
+                ("buffer pool hit ratio", buffer_pool_hits),
+            ],
+            largest_tables
+                .into_iter()
+                .map(|(name, size)| (name, size.max(0) as u64))
+                .collect(),
+        ))
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            CAST(ID AS CHAR) AS id,
+            CAST(USER AS CHAR) AS user_name,
+            CAST(DB AS CHAR) AS database_name,
+            CAST(COMMAND AS CHAR) AS state,
+            CAST(TIME AS SIGNED) AS elapsed,
+            CAST(INFO AS CHAR) AS query
+        FROM information_schema.PROCESSLIST
+        WHERE ID <> CONNECTION_ID()
+        ORDER BY TIME DESC
+        ",
+        )
+        .fetch(&self.pool);
+        let mut sessions = vec![];
+        while let Some(row) = rows.try_next().await? {
+            sessions.push(ServerSession {
+                id: row.try_get("id")?,
+                user: row.try_get("user_name")?,
+                database: row.try_get("database_name")?,
+                state: row.try_get("state")?,
+                elapsed: row
+                    .try_get::<Option<i64>, _>("elapsed")?
+                    .map(|seconds| seconds.max(0) as u64),
+                query: row.try_get("query")?,
+            })
+        }
+        Ok(sessions)
+    }
+
+    async fn get_privileges(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // USAGE is granted to every user and means no privilege at all
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            CAST(GRANTEE AS CHAR) AS grantee,
+            CAST(PRIVILEGE_TYPE AS CHAR) AS privilege,
+            'table' AS level,
+            CAST(IS_GRANTABLE AS CHAR) AS grantable
+        FROM information_schema.TABLE_PRIVILEGES
+        WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+        UNION ALL
+        SELECT CAST(GRANTEE AS CHAR), CAST(PRIVILEGE_TYPE AS CHAR), 'database', CAST(IS_GRANTABLE AS CHAR)
+        FROM information_schema.SCHEMA_PRIVILEGES
+        WHERE TABLE_SCHEMA = ?
+        UNION ALL
+        SELECT CAST(GRANTEE AS CHAR), CAST(PRIVILEGE_TYPE AS CHAR), 'global', CAST(IS_GRANTABLE AS CHAR)
+        FROM information_schema.USER_PRIVILEGES
+        WHERE PRIVILEGE_TYPE <> 'USAGE'
+        ORDER BY grantee, privilege
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .bind(&database.name)
+        .fetch(&self.pool);
+        let mut privileges: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            privileges.push(Box::new(Privilege {
+                grantee: row.try_get("grantee")?,
+                privilege: row.try_get("privilege")?,
+                level: row.try_get("level")?,
+                grantable: row.try_get::<String, _>("grantable")? == "YES",
+            }))
+        }
+        Ok(privileges)
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        let users: Vec<(String, String, String)> = sqlx::query_as(
+            "
+        SELECT
+            CAST(CONCAT('''', User, '''@''', Host, '''') AS CHAR),
+            CAST(account_locked AS CHAR),
+            CAST(Super_priv AS CHAR)
+        FROM mysql.user
+        ORDER BY User, Host
+        ",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        // roles came with MySQL 8.0, before which nobody is a member of one
+        let edges: Vec<(String, String)> = sqlx::query_as(
+            "
+        SELECT
+            CAST(CONCAT('''', TO_USER, '''@''', TO_HOST, '''') AS CHAR),
+            CAST(CONCAT('''', FROM_USER, '''@''', FROM_HOST, '''') AS CHAR)
+        FROM mysql.role_edges
+        ORDER BY FROM_USER, FROM_HOST
+        ",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap_or_default();
+        let mut member_of: HashMap<String, Vec<String>> = HashMap::new();
+        for (member, role) in edges {
+            member_of.entry(member).or_default().push(role);
+        }
+        Ok(users
+            .into_iter()
+            .map(|(name, locked, super_priv)| DatabaseRole {
+                member_of: member_of.remove(&name).unwrap_or_default(),
+                name,
+                can_login: locked != "Y",
+                superuser: super_priv == "Y",
+            })
+            .collect())
+    }
+
+    async fn get_relations(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        // the tree shows the tables of a database apart, so the keys to other databases are
+        // left out
+        let rows: Vec<(String, String, String, String, String)> = sqlx::query_as(
+            "
+        SELECT
+            CAST(CONSTRAINT_NAME AS CHAR),
+            CAST(TABLE_NAME AS CHAR),
+            CAST(COLUMN_NAME AS CHAR),
+            CAST(REFERENCED_TABLE_NAME AS CHAR),
+            CAST(REFERENCED_COLUMN_NAME AS CHAR)
+        FROM information_schema.KEY_COLUMN_USAGE
+        WHERE REFERENCED_TABLE_NAME IS NOT NULL
+            AND TABLE_SCHEMA = ?
+            AND REFERENCED_TABLE_SCHEMA = ?
+            AND (TABLE_NAME = ? OR REFERENCED_TABLE_NAME = ?)
+        ORDER BY TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION
+        ",
+        )
+        .bind(&database.name)
+        .bind(&database.name)
+        .bind(&table.name)
+        .bind(&table.name)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(Relation::merge_columns(
+            rows.into_iter()
+                .map(|(name, table, column, ref_table, ref_column)| Relation {
+                    name,
+                    schema: None,
+                    table,
+                    columns: vec![column],
+                    ref_schema: None,
+                    ref_table,
+                    ref_columns: vec![ref_column],
+                })
+                .collect(),
+        ))
+    }
+
+    async fn get_partitions(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // an unpartitioned table has a single row without a partition name
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            CAST(CONCAT_WS('.', PARTITION_NAME, SUBPARTITION_NAME) AS CHAR) AS name,
+            CAST(PARTITION_METHOD AS CHAR) AS method,
+            CAST(PARTITION_DESCRIPTION AS CHAR) AS description,
+            CAST(TABLE_ROWS AS SIGNED) AS table_rows,
+            CAST(DATA_LENGTH + INDEX_LENGTH AS SIGNED) AS size
+        FROM information_schema.PARTITIONS
+        WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? AND PARTITION_NAME IS NOT NULL
+        ORDER BY PARTITION_ORDINAL_POSITION, SUBPARTITION_ORDINAL_POSITION
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut partitions: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            let method: Option<String> = row.try_get("method")?;
+            let description: Option<String> = row.try_get("description")?;
+            partitions.push(Box::new(Partition {
+                name: row.try_get("name")?,
+                bounds: partition_bounds(method.as_deref(), description.as_deref()),
+                rows: row.try_get("table_rows")?,
+                size: row
+                    .try_get::<Option<i64>, _>("size")?
+                    .map(|size| size.max(0) as u64),
+            }))
+        }
+        Ok(partitions)
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            CASE TABLE_TYPE
+                WHEN 'VIEW' THEN 'view'
+                WHEN 'SEQUENCE' THEN 'sequence'
+                ELSE 'table'
+            END AS kind,
+            TABLE_SCHEMA AS database_name,
+            CAST(NULL AS CHAR) AS table_name,
+            TABLE_NAME AS name
+        FROM information_schema.TABLES
+        UNION ALL
+        SELECT 'column', TABLE_SCHEMA, TABLE_NAME, COLUMN_NAME
+        FROM information_schema.COLUMNS
+        UNION ALL
+        SELECT LOWER(ROUTINE_TYPE), ROUTINE_SCHEMA, NULL, ROUTINE_NAME
+        FROM information_schema.ROUTINES
+        WHERE ROUTINE_TYPE IN ('FUNCTION', 'PROCEDURE')
+        ",
+        )
+        .fetch(&self.pool);
+        let mut objects = vec![];
+        while let Some(row) = rows.try_next().await? {
+            objects.push(SchemaObject {
+                kind: row.try_get("kind")?,
+                database: row.try_get("database_name")?,
+                schema: None,
+                table: row.try_get("table_name")?,
+                name: row.try_get("name")?,
+            })
+        }
+        Ok(objects)
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        let query = format!(
+            "SHOW SESSION VARIABLES WHERE Variable_name IN ({})",
+            SESSION_SETTINGS
+                .iter()
+                .map(|(name, _)| format!("'{}'", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let rows: Vec<(String, String)> = sqlx::query_as(&query).fetch_all(&self.pool).await?;
+        Ok(SessionSetting::from_rows(rows, &SESSION_SETTINGS))
+    }
+
+    async fn begin(&self) -> anyhow::Result<()> {
+        self.transaction.begin(&self.pool).await
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        self.transaction.commit().await
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        self.transaction.rollback().await
+    }
+
     async fn close(&self) {
         self.pool.close().await;
     }
 
+    fn ping(&self) -> Ping {
+        let pool = self.pool.clone();
+        Box::pin(async move {
+            sqlx::query("SELECT 1").execute(&pool).await?;
+            Ok(())
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
     async fn get_definition(&self, database: &Database, table: &Table) -> anyhow::Result<String> {
-        let query = format!("SHOW CREATE TABLE `{}`.`{}`;", database.name, table.name);
+        let (object, column) = match table.kind {
+            TableKind::Function => ("FUNCTION", 2),
+            TableKind::Procedure => ("PROCEDURE", 2),
+            TableKind::Sequence => ("SEQUENCE", 1),
+            _ => ("TABLE", 1),
+        };
+        let query = format!(
+            "SHOW CREATE {} `{}`.`{}`;",
+            object, database.name, table.name
+        );
         let row = sqlx::query(query.as_str()).fetch_one(&self.pool).await?;
-        Ok(row.get::<String, usize>(1))
+        // the source of a routine is NULL without the privilege to see it
+        Ok(row
+            .try_get::<Option<String>, usize>(column)?
+            .unwrap_or_default())
+    }
+}
+
+/// Describes the values of a partition from its method and the description of its bounds,
+/// e.g. `VALUES LESS THAN (2025)` for a range partition.
+fn partition_bounds(method: Option<&str>, description: Option<&str>) -> String {
+    match (method, description) {
+        (Some(method), Some(description)) if method.starts_with("RANGE") => {
+            format!("VALUES LESS THAN ({})", description)
+        }
+        (Some(method), Some(description)) if method.starts_with("LIST") => {
+            format!("VALUES IN ({})", description)
+        }
+        (Some(method), _) => method.to_string(),
+        (None, _) => String::new(),
     }
 }
 
-fn convert_column_value_to_string(row: &MySqlRow, column: &MySqlColumn) -> anyhow::Result<String> {
+fn convert_column_value(row: &MySqlRow, column: &MySqlColumn) -> anyhow::Result<Value> {
     let column_name = column.name();
 
     if let Ok(value) = row.try_get(column_name) {
         let value: Option<String> = value;
-        Ok(value.unwrap_or_else(|| "NULL".to_string()))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<&str> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i8> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i16> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i32> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i64> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<f32> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<f64> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<u8> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<u16> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<u32> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<u64> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<rust_decimal::Decimal> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<NaiveDate> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<NaiveTime> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<NaiveDateTime> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<chrono::DateTime<chrono::Utc>> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<serde_json::Value> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<bool> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else {
         anyhow::bail!(
             "column type not implemented: `{}` {}",
@@ -520,3 +1454,36 @@ fn convert_column_value_to_string(row: &MySqlRow, column: &MySqlColumn) -> anyho
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{enum_members, partition_bounds};
+
+    #[test]
+    fn test_enum_members() {
+        assert_eq!(
+            enum_members("enum('small','it''s big','a,b')"),
+            Some(vec![
+                "small".to_string(),
+                "it's big".to_string(),
+                "a,b".to_string()
+            ])
+        );
+        assert_eq!(enum_members("set('x')"), Some(vec!["x".to_string()]));
+        assert_eq!(enum_members("varchar(255)"), None);
+    }
+
+    #[test]
+    fn test_partition_bounds() {
+        assert_eq!(
+            partition_bounds(Some("RANGE COLUMNS"), Some("'2025-01-01'")),
+            "VALUES LESS THAN ('2025-01-01')"
+        );
+        assert_eq!(
+            partition_bounds(Some("LIST"), Some("1,2,3")),
+            "VALUES IN (1,2,3)"
+        );
+        assert_eq!(partition_bounds(Some("HASH"), None), "HASH");
+        assert_eq!(partition_bounds(None, None), "");
+    }
+}
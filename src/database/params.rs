@@ -0,0 +1,271 @@
+use super::{Dialect, Value};
+
+/// Where a placeholder is in a query and which parameter it stands for.
+#[derive(Debug, Clone, PartialEq)]
+struct Placeholder {
+    start: usize,
+    end: usize,
+    param: usize,
+}
+
+/// The placeholders of a query, whose values are asked for before it runs and bound by the
+/// driver instead of being written into the SQL: `$1` in PostgreSQL, `?` and `:name` in MySQL
+/// and SQLite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryParams {
+    chars: Vec<char>,
+    /// The parameters in the order they first appear, e.g. `1` for the first `?`, `$1` or
+    /// `:id`. A numbered or named parameter which appears again is asked for once.
+    names: Vec<String>,
+    placeholders: Vec<Placeholder>,
+}
+
+impl QueryParams {
+    /// Finds the placeholders of `dialect` which are outside of quotes, comments and
+    /// dollar-quoted bodies. ClickHouse has none that can be bound, so that e.g. its
+    /// `{id:UInt32}` is left as it is, as is the `?` operator of PostgreSQL.
+    pub fn parse(query: &str, dialect: Dialect) -> Self {
+        let question_and_named = matches!(dialect, Dialect::MySql | Dialect::Sqlite);
+        let chars = query.chars().collect::<Vec<char>>();
+        let mut names: Vec<String> = Vec::new();
+        let mut placeholders = Vec::new();
+        let mut positional = 0;
+        let mut index = 0;
+        while index < chars.len() {
+            let start = index;
+            let name = match chars[index] {
+                quote @ ('\'' | '"' | '`') => {
+                    index += 1;
+                    while index < chars.len() {
+                        if chars[index] == '\\' && quote != '`' {
+                            index += 1;
+                        } else if chars[index] == quote {
+                            break;
+                        }
+                        index += 1;
+                    }
+                    None
+                }
+                '-' if chars.get(index + 1) == Some(&'-') => {
+                    while index < chars.len() && chars[index] != '\n' {
+                        index += 1;
+                    }
+                    None
+                }
+                '/' if chars.get(index + 1) == Some(&'*') => {
+                    index += 2;
+                    while index < chars.len()
+                        && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/'))
+                    {
+                        index += 1;
+                    }
+                    index += 1;
+                    None
+                }
+                '?' if question_and_named => {
+                    positional += 1;
+                    Some(positional.to_string())
+                }
+                '$' if dialect == Dialect::Postgres
+                    && chars.get(index + 1).is_some_and(char::is_ascii_digit) =>
+                {
+                    index += 1;
+                    while chars.get(index + 1).is_some_and(char::is_ascii_digit) {
+                        index += 1;
+                    }
+                    Some(chars[start..=index].iter().collect())
+                }
+                '$' => {
+                    // e.g. $$ ... $$ or $body$ ... $body$ in PostgreSQL
+                    if let Some(length) = chars[index + 1..]
+                        .iter()
+                        .position(|c| !(c.is_alphanumeric() || *c == '_'))
+                        .filter(|length| chars[index + 1 + length] == '$')
+                    {
+                        let tag = &chars[index..index + length + 2];
+                        index += tag.len();
+                        while index < chars.len() && !chars[index..].starts_with(tag) {
+                            index += 1;
+                        }
+                        index += tag.len() - 1;
+                    }
+                    None
+                }
+                ':' if question_and_named
+                    && index
+                        .checked_sub(1)
+                        .map_or(true, |previous| chars[previous] != ':')
+                    && chars
+                        .get(index + 1)
+                        .is_some_and(|c| c.is_alphabetic() || *c == '_') =>
+                {
+                    while chars
+                        .get(index + 1)
+                        .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+                    {
+                        index += 1;
+                    }
+                    Some(chars[start..=index].iter().collect())
+                }
+                _ => None,
+            };
+            if let Some(name) = name {
+                let param = match names.iter().position(|known| *known == name) {
+                    Some(param) => param,
+                    None => {
+                        names.push(name);
+                        names.len() - 1
+                    }
+                };
+                placeholders.push(Placeholder {
+                    start,
+                    end: index + 1,
+                    param,
+                });
+            }
+            index += 1;
+        }
+        Self {
+            chars,
+            names,
+            placeholders,
+        }
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Rewrites the placeholders into those of `dialect`, returning the query and the values
+    /// in the order they are bound. `values` are those of `names`.
+    pub fn bind(&self, dialect: Dialect, values: &[Value]) -> anyhow::Result<(String, Vec<Value>)> {
+        if values.len() != self.names.len() {
+            anyhow::bail!(
+                "The query has {} parameters but {} values were given",
+                self.names.len(),
+                values.len()
+            );
+        }
+        let mut query = String::new();
+        let mut bound = Vec::new();
+        let mut last = 0;
+        for placeholder in &self.placeholders {
+            query.extend(&self.chars[last..placeholder.start]);
+            match dialect {
+                // a parameter which appears again is bound once
+                Dialect::Postgres => query.push_str(&format!("${}", placeholder.param + 1)),
+                Dialect::MySql | Dialect::Sqlite | Dialect::ClickHouse => {
+                    query.push('?');
+                    bound.push(values[placeholder.param].clone());
+                }
+            }
+            last = placeholder.end;
+        }
+        query.extend(&self.chars[last..]);
+        if dialect == Dialect::Postgres {
+            bound = values.to_vec();
+        }
+        Ok((query, bound))
+    }
+}
+
+/// Reads the value typed for a parameter: `NULL`, a number, or else text. Quoting it in
+/// single quotes keeps e.g. `'42'` as text.
+pub fn parse_value(input: &str) -> Value {
+    let trimmed = input.trim();
+    if trimmed.eq_ignore_ascii_case("null") {
+        return Value::Null;
+    }
+    if let Some(text) = trimmed
+        .strip_prefix('\'')
+        .and_then(|text| text.strip_suffix('\''))
+    {
+        return Value::Text(text.to_string());
+    }
+    if let Ok(number) = trimmed.parse::<i64>() {
+        return Value::Int(number);
+    }
+    // so that e.g. `inf` or `NaN` stay text
+    if trimmed
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+    {
+        if let Ok(number) = trimmed.parse::<f64>() {
+            return Value::Float(number);
+        }
+    }
+    Value::Text(input.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_value, Dialect, QueryParams, Value};
+
+    #[test]
+    fn test_parse() {
+        let params = QueryParams::parse(
+            "SELECT ':skip', '?', a -- ?\nFROM t WHERE a = :id AND b = ? AND c = :id",
+            Dialect::MySql,
+        );
+        assert_eq!(params.names(), &[":id".to_string(), "1".to_string()]);
+        assert!(QueryParams::parse("SELECT 1", Dialect::Sqlite).is_empty());
+        assert_eq!(
+            QueryParams::parse("SELECT $2, $1, $2, $$ $3 $$", Dialect::Postgres).names(),
+            &["$2".to_string(), "$1".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_dialect() {
+        // the jsonb operators and casts of PostgreSQL
+        assert!(QueryParams::parse(
+            "SELECT a::int FROM t WHERE data ? 'k' AND data ?| b AND c = :id",
+            Dialect::Postgres
+        )
+        .is_empty());
+        assert!(QueryParams::parse(
+            "SELECT * FROM t WHERE id = {id:UInt32} AND a = ?",
+            Dialect::ClickHouse
+        )
+        .is_empty());
+        assert!(QueryParams::parse("SELECT $1", Dialect::MySql).is_empty());
+    }
+
+    #[test]
+    fn test_bind() {
+        let params = QueryParams::parse(
+            "SELECT * FROM t WHERE a = :id AND b = ? OR c = :id",
+            Dialect::MySql,
+        );
+        let values = vec![Value::Int(1), Value::Text("x".to_string())];
+        assert_eq!(
+            params.bind(Dialect::Postgres, &values).unwrap(),
+            (
+                "SELECT * FROM t WHERE a = $1 AND b = $2 OR c = $1".to_string(),
+                values.clone()
+            )
+        );
+        assert_eq!(
+            params.bind(Dialect::MySql, &values).unwrap(),
+            (
+                "SELECT * FROM t WHERE a = ? AND b = ? OR c = ?".to_string(),
+                vec![Value::Int(1), Value::Text("x".to_string()), Value::Int(1)]
+            )
+        );
+        assert!(params.bind(Dialect::Sqlite, &values[..1]).is_err());
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("null"), Value::Null);
+        assert_eq!(parse_value("42"), Value::Int(42));
+        assert_eq!(parse_value("-1.5"), Value::Float(-1.5));
+        assert_eq!(parse_value("'42'"), Value::Text("42".to_string()));
+        assert_eq!(parse_value("NaN"), Value::Text("NaN".to_string()));
+        assert_eq!(parse_value(" a b"), Value::Text(" a b".to_string()));
+    }
+}
@@ -1,18 +1,66 @@
-use crate::get_or_null;
-
-use super::{ExecuteResult, Pool, TableRow};
-use crate::tree::{Child, Database, Schema, Table};
+use super::ddl::{literal, quote_identifier};
+use super::script::is_select;
+use super::{
+    format_bytes, format_uptime, hit_ratio, Capabilities, Capability, DatabaseRole, Dependency,
+    ExecuteResult, MemoryBudget, Parameter, Partition, Ping, Pool, Privilege, QueryParams,
+    Relation, RowBuffer, RowSender, RowStream, SchemaObject, Server, ServerOverview, ServerSession,
+    SessionSetting, Statistic, TableRow, TransactionSlot, Trigger, Value,
+};
+use crate::database::Dialect;
+use crate::tree::{Child, Database, Schema, Table, TableKind};
 use async_trait::async_trait;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use futures::TryStreamExt;
 use itertools::Itertools;
 use sqlx::postgres::{PgColumn, PgPool, PgPoolOptions, PgRow};
 use sqlx::{Column as _, Row as _, TypeInfo as _};
+use std::collections::BTreeMap;
 use std::time::Duration;
 
+/// The settings shown in the session panel and the values they can be switched to, besides the
+/// value the server starts a session with.
+const SESSION_SETTINGS: [(&str, &[&str]); 3] = [
+    ("TimeZone", &["UTC"]),
+    ("search_path", &[]),
+    (
+        "default_transaction_isolation",
+        &["read committed", "repeatable read", "serializable"],
+    ),
+];
+
+/// The tables, columns and routines of the database for the object search in Redshift, which
+/// has no information_schema.routines.
+const REDSHIFT_OBJECTS: &str = "
+SELECT
+    CASE WHEN table_type = 'VIEW' THEN 'view' ELSE 'table' END AS kind,
+    table_schema::text AS schema_name,
+    NULL::text AS table_name,
+    table_name::text AS name
+FROM svv_tables
+WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+UNION ALL
+SELECT 'column', table_schema::text, table_name::text, column_name::text
+FROM svv_columns
+WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+UNION ALL
+SELECT
+    CASE WHEN p.prokind = 'p' THEN 'procedure' ELSE 'function' END,
+    n.nspname::text,
+    NULL,
+    p.proname::text
+FROM pg_proc_info p
+JOIN pg_namespace n ON n.oid = p.pronamespace
+WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+";
+
 pub struct PostgresPool {
     pool: PgPool,
     limit_size: usize,
+    memory_budget: MemoryBudget,
+    transaction: TransactionSlot<sqlx::Postgres>,
+    capabilities: Capabilities,
+    /// PostgreSQL, or Redshift or CockroachDB, which speak its protocol but whose catalogs differ.
+    server: Server,
 }
 
 impl PostgresPool {
@@ -20,29 +68,244 @@ impl PostgresPool {
         database_url: &str,
         limit_size: usize,
         timeout_second: u64,
+        memory_budget: MemoryBudget,
+        session: &BTreeMap<String, String>,
+        server: Server,
     ) -> anyhow::Result<Self> {
+        let session = session.clone();
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(timeout_second))
+            .after_connect(move |connection, _| {
+                let session = session.clone();
+                Box::pin(async move {
+                    for (name, value) in session {
+                        sqlx::query("SELECT set_config($1, $2, false)")
+                            .bind(name)
+                            .bind(value)
+                            .execute(&mut *connection)
+                            .await?;
+                    }
+                    Ok(())
+                })
+            })
+            .connect(database_url)
+            .await?;
+        // CockroachDB reports the PostgreSQL version it is compatible with as server_version
+        let version = if server == Server::CockroachDb {
+            "SELECT substring(version() FROM 'v([0-9.]+)')"
+        } else {
+            "SHOW server_version"
+        };
+        let capabilities = sqlx::query_scalar::<_, String>(version)
+            .fetch_one(&pool)
+            .await
+            .map(|version| Capabilities::new(server, &version))
+            .unwrap_or_default();
         Ok(Self {
-            pool: PgPoolOptions::new()
-                .acquire_timeout(Duration::from_secs(timeout_second))
-                .connect(database_url)
-                .await?,
+            pool,
             limit_size,
+            memory_budget,
+            transaction: TransactionSlot::new(),
+            capabilities,
+            server,
+        })
+    }
+
+    async fn execute_with<'e, E>(
+        &self,
+        executor: E,
+        query: &str,
+        values: &[Value],
+    ) -> anyhow::Result<ExecuteResult>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+    {
+        let query = query.trim();
+        if is_select(query) {
+            let mut rows = bind_values(query, values).fetch(executor);
+            let mut headers = vec![];
+            let mut records = RowBuffer::new(self.memory_budget.bytes);
+            while let Some(row) = rows.try_next().await? {
+                headers = row
+                    .columns()
+                    .iter()
+                    .map(|column| column.name().to_string())
+                    .collect();
+                let mut new_row = vec![];
+                for column in row.columns() {
+                    new_row.push(convert_column_value(&row, column)?)
+                }
+                if !records.push(new_row) {
+                    break;
+                }
+            }
+            return Ok(ExecuteResult::Read {
+                headers,
+                truncated: records.truncated,
+                rows: records.rows,
+                database: Database {
+                    name: "-".to_string(),
+                    children: Vec::new(),
+                },
+                table: Box::new(Table {
+                    name: "-".to_string(),
+                    create_time: None,
+                    update_time: None,
+                    engine: None,
+                    schema: None,
+                    kind: TableKind::Table,
+                    partition_of: None,
+                }),
+            });
+        }
+
+        let result = bind_values(query, values).execute(executor).await?;
+        Ok(ExecuteResult::Write {
+            updated_rows: result.rows_affected(),
         })
     }
+
+    /// Assembles the `CREATE TABLE` statement of a table with its indexes and comments from
+    /// pg_catalog, as PostgreSQL has no function which returns it.
+    async fn create_table(&self, table: &Table) -> anyhow::Result<String> {
+        let schema = table.schema.as_deref().unwrap_or("public");
+        let qualified = format!(
+            "{}.{}",
+            quote_identifier(Dialect::Postgres, schema),
+            quote_identifier(Dialect::Postgres, &table.name)
+        );
+        let relation = sqlx::query(
+            "
+        SELECT c.relpersistence::text AS persistence, obj_description(c.oid, 'pg_class') AS comment
+        FROM pg_class c
+        WHERE c.oid = format('%I.%I', $1::text, $2::text)::regclass
+        ",
+        )
+        .bind(schema)
+        .bind(&table.name)
+        .fetch_one(&self.pool)
+        .await?;
+        // information_schema knows identity and generated columns without failing on the
+        // versions whose pg_attribute lacks them
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            a.attname::text AS name,
+            format_type(a.atttypid, a.atttypmod) AS type,
+            a.attnotnull AS not_null,
+            c.column_default::text AS default_value,
+            c.identity_generation::text AS identity,
+            c.generation_expression::text AS generation,
+            col_description(a.attrelid, a.attnum) AS comment
+        FROM pg_attribute a
+        LEFT JOIN information_schema.columns c
+            ON c.table_schema = $1 AND c.table_name = $2 AND c.column_name = a.attname
+        WHERE a.attrelid = format('%I.%I', $1::text, $2::text)::regclass
+            AND a.attnum > 0 AND NOT a.attisdropped
+        ORDER BY a.attnum
+        ",
+        )
+        .bind(schema)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut columns = vec![];
+        let mut comments = vec![];
+        while let Some(row) = rows.try_next().await? {
+            let name: String = row.try_get("name")?;
+            if let Some(comment) = row.try_get::<Option<String>, _>("comment")? {
+                comments.push(format!(
+                    "COMMENT ON COLUMN {}.{} IS {};",
+                    qualified,
+                    quote_identifier(Dialect::Postgres, &name),
+                    literal(Dialect::Postgres, &Value::Text(comment))
+                ));
+            }
+            columns.push(ColumnDefinition {
+                name,
+                r#type: row.try_get("type")?,
+                not_null: row.try_get("not_null")?,
+                default: row.try_get("default_value")?,
+                identity: row.try_get("identity")?,
+                generation: row.try_get("generation")?,
+            });
+        }
+        drop(rows);
+        // NOT NULL is a constraint of its own since PostgreSQL 18, but is written on the column
+        let constraints = sqlx::query_as(
+            "
+        SELECT conname::text, pg_get_constraintdef(oid, true)
+        FROM pg_constraint
+        WHERE conrelid = format('%I.%I', $1::text, $2::text)::regclass AND contype <> 'n'
+        ORDER BY position(contype::text in 'pucfx'), conname
+        ",
+        )
+        .bind(schema)
+        .bind(&table.name)
+        .fetch_all(&self.pool)
+        .await?;
+        // the indexes of primary keys and unique constraints are created with them
+        let indexes: Vec<String> = sqlx::query_scalar(
+            "
+        SELECT pg_get_indexdef(i.indexrelid) || ';'
+        FROM pg_index i
+        WHERE i.indrelid = format('%I.%I', $1::text, $2::text)::regclass
+            AND NOT EXISTS (SELECT 1 FROM pg_constraint c WHERE c.conindid = i.indexrelid)
+        ORDER BY i.indexrelid
+        ",
+        )
+        .bind(schema)
+        .bind(&table.name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut statements = vec![create_table(
+            &qualified,
+            relation.try_get::<String, _>("persistence")? == "u",
+            &columns,
+            &constraints,
+        )];
+        if !indexes.is_empty() {
+            statements.push(indexes.join("\n"));
+        }
+        if let Some(comment) = relation.try_get::<Option<String>, _>("comment")? {
+            comments.insert(
+                0,
+                format!(
+                    "COMMENT ON TABLE {} IS {};",
+                    qualified,
+                    literal(Dialect::Postgres, &Value::Text(comment))
+                ),
+            );
+        }
+        if !comments.is_empty() {
+            statements.push(comments.join("\n"));
+        }
+        Ok(statements.join("\n\n"))
+    }
 }
 
 pub struct Constraint {
     name: String,
     column_name: String,
+    /// The expression of a CHECK constraint.
+    definition: Option<String>,
 }
 
 impl TableRow for Constraint {
     fn fields(&self) -> Vec<String> {
-        vec!["name".to_string(), "column_name".to_string()]
+        vec![
+            "name".to_string(),
+            "column_name".to_string(),
+            "definition".to_string(),
+        ]
     }
 
     fn columns(&self) -> Vec<String> {
-        vec![self.name.to_string(), self.column_name.to_string()]
+        vec![
+            self.name.to_string(),
+            self.column_name.to_string(),
+            self.definition.clone().unwrap_or_default(),
+        ]
     }
 }
 
@@ -52,6 +315,8 @@ pub struct Column {
     null: Option<String>,
     default: Option<String>,
     comment: Option<String>,
+    /// The members of an enum or set type.
+    values: Option<Vec<String>>,
 }
 
 impl TableRow for Column {
@@ -62,6 +327,7 @@ impl TableRow for Column {
             "null".to_string(),
             "default".to_string(),
             "comment".to_string(),
+            "values".to_string(),
         ]
     }
 
@@ -82,6 +348,9 @@ impl TableRow for Column {
             self.comment
                 .as_ref()
                 .map_or(String::new(), |comment| comment.to_string()),
+            self.values
+                .as_ref()
+                .map_or(String::new(), |values| values.join(", ")),
         ]
     }
 }
@@ -151,47 +420,79 @@ impl TableRow for Index {
     }
 }
 
+/// Builds `query` with `values` bound to its placeholders, as numbers where they were typed
+/// as one.
+fn bind_values<'q>(
+    query: &'q str,
+    values: &[Value],
+) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+    values
+        .iter()
+        .fold(sqlx::query(query), |query, value| match value {
+            Value::Null => query.bind(None::<String>),
+            Value::Int(value) => query.bind(*value),
+            Value::Float(value) => query.bind(*value),
+            value => query.bind(value.to_string()),
+        })
+}
+
 #[async_trait]
 impl Pool for PostgresPool {
     async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
-        let query = query.trim();
-        if query.to_uppercase().starts_with("SELECT") {
-            let mut rows = sqlx::query(query).fetch(&self.pool);
-            let mut headers = vec![];
-            let mut records = vec![];
-            while let Some(row) = rows.try_next().await? {
-                headers = row
-                    .columns()
-                    .iter()
-                    .map(|column| column.name().to_string())
-                    .collect();
-                let mut new_row = vec![];
-                for column in row.columns() {
-                    new_row.push(convert_column_value_to_string(&row, column)?)
+        let mut transaction = self.transaction.lock().await;
+        let result = match transaction.as_mut() {
+            Some(transaction) => self.execute_with(&mut **transaction, query, &[]).await,
+            None => self.execute_with(&self.pool, query, &[]).await,
+        };
+        result.map_err(|e| self.capabilities.explain(query, e))
+    }
+
+    async fn execute_params(&self, query: &str, values: &[Value]) -> anyhow::Result<ExecuteResult> {
+        let (query, values) =
+            QueryParams::parse(query, Dialect::Postgres).bind(Dialect::Postgres, values)?;
+        let mut transaction = self.transaction.lock().await;
+        let result = match transaction.as_mut() {
+            Some(transaction) => self.execute_with(&mut **transaction, &query, &values).await,
+            None => self.execute_with(&self.pool, &query, &values).await,
+        };
+        result.map_err(|e| self.capabilities.explain(&query, e))
+    }
+
+    fn stream(&self, query: &str) -> Option<RowStream> {
+        let query = query.trim().to_string();
+        // a transaction holds its connection, which the task cannot borrow
+        if !is_select(&query) || self.transaction.is_open() {
+            return None;
+        }
+        let pool = self.pool.clone();
+        let (mut sender, stream) = RowSender::channel(self.memory_budget);
+        let capabilities = self.capabilities;
+        tokio::spawn(async move {
+            let result = async {
+                let mut rows = sqlx::query(&query).fetch(&pool);
+                while let Some(row) = rows.try_next().await? {
+                    let mut new_row = vec![];
+                    for column in row.columns() {
+                        new_row.push(convert_column_value(&row, column)?)
+                    }
+                    let headers = || {
+                        row.columns()
+                            .iter()
+                            .map(|column| column.name().to_string())
+                            .collect()
+                    };
+                    if !sender.push(new_row, headers).await {
+                        break;
+                    }
                 }
-                records.push(new_row)
+                Ok(())
             }
-            return Ok(ExecuteResult::Read {
-                headers,
-                rows: records,
-                database: Database {
-                    name: "-".to_string(),
-                    children: Vec::new(),
-                },
-                table: Table {
-                    name: "-".to_string(),
-                    create_time: None,
-                    update_time: None,
-                    engine: None,
-                    schema: None,
-                },
-            });
-        }
-
-        let result = sqlx::query(query).execute(&self.pool).await?;
-        Ok(ExecuteResult::Write {
-            updated_rows: result.rows_affected(),
-        })
+            .await;
+            sender
+                .finish(result.map_err(|e| capabilities.explain(&query, e)))
+                .await;
+        });
+        Some(stream)
     }
 
     async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
@@ -201,31 +502,18 @@ impl Pool for PostgresPool {
             .iter()
             .map(|table| table.get(0))
             .collect::<Vec<String>>();
-        let mut list = vec![];
-        for db in databases {
-            list.push(Database::new(
-                db.clone(),
-                self.get_tables(db.clone()).await?,
-            ))
-        }
-        Ok(list)
+        Ok(databases
+            .into_iter()
+            .map(|name| Database::new(name, Vec::new()))
+            .collect())
     }
 
     async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>> {
-        let mut rows =
-            sqlx::query("SELECT * FROM information_schema.tables WHERE table_catalog = $1")
-                .bind(database)
-                .fetch(&self.pool);
-        let mut tables = Vec::new();
-        while let Some(row) = rows.try_next().await? {
-            tables.push(Table {
-                name: row.try_get("table_name")?,
-                create_time: None,
-                update_time: None,
-                engine: None,
-                schema: row.try_get("table_schema")?,
-            })
-        }
+        let tables = match self.server {
+            Server::Redshift => self.get_redshift_tables(&database).await?,
+            Server::CockroachDb => self.get_cockroach_tables(&database).await?,
+            _ => self.get_postgres_tables(&database).await?,
+        };
         let mut schemas = vec![];
         for (key, group) in &tables
             .iter()
@@ -249,10 +537,10 @@ impl Pool for PostgresPool {
         &self,
         database: &Database,
         table: &Table,
-        page: u16,
+        page: usize,
         filter: Option<String>,
         orders: Option<String>,
-    ) -> anyhow::Result<(Vec<String>, Vec<Vec<String>>)> {
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
         let query = if let (Some(filter), Some(orders)) = (&filter, &orders) {
             format!(
                 r#"SELECT * FROM "{database}"."{table_schema}"."{table}" WHERE {filter} {orders} LIMIT {limit} OFFSET {page}"#,
@@ -306,7 +594,7 @@ impl Pool for PostgresPool {
                 .collect();
             let mut new_row = vec![];
             for column in row.columns() {
-                match convert_column_value_to_string(&row, column) {
+                match convert_column_value(&row, column) {
                     Ok(v) => new_row.push(v),
                     Err(_) => {
                         if json_records.is_none() {
@@ -328,13 +616,18 @@ impl Pool for PostgresPool {
                                 .get(column.name())
                                 .unwrap()
                             {
-                                serde_json::Value::String(v) => new_row.push(v.to_string()),
-                                serde_json::Value::Null => new_row.push("NULL".to_string()),
-                                serde_json::Value::Array(v) => {
-                                    new_row.push(v.iter().map(|v| v.to_string()).join(","))
+                                serde_json::Value::String(v) => {
+                                    new_row.push(Value::Text(v.to_string()))
                                 }
-                                serde_json::Value::Number(v) => new_row.push(v.to_string()),
-                                serde_json::Value::Bool(v) => new_row.push(v.to_string()),
+                                serde_json::Value::Null => new_row.push(Value::Null),
+                                serde_json::Value::Array(v) => new_row.push(Value::Array(
+                                    v.iter().map(|v| Value::Text(v.to_string())).collect(),
+                                )),
+                                serde_json::Value::Number(v) => new_row.push(v.as_i64().map_or(
+                                    Value::Float(v.as_f64().unwrap_or_default()),
+                                    Value::Int,
+                                )),
+                                serde_json::Value::Bool(v) => new_row.push(Value::Bool(*v)),
                                 others => {
                                     panic!(
                                         "column type not implemented: `{}` {}",
@@ -352,6 +645,43 @@ impl Pool for PostgresPool {
         Ok((headers, records))
     }
 
+    async fn get_sample(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        let table_schema = table.schema.clone().unwrap_or_else(|| "public".to_string());
+        // a filter may match few of the sampled rows, so then every matching row is shuffled
+        let sample = match filter {
+            Some(_) => None,
+            // Redshift and CockroachDB have no TABLESAMPLE
+            None if self.server != Server::Postgres => None,
+            None => {
+                let estimated_rows: Option<f32> = sqlx::query_scalar(
+                    "SELECT c.reltuples FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace WHERE n.nspname = $1 AND c.relname = $2",
+                )
+                .bind(&table_schema)
+                .bind(&table.name)
+                .fetch_optional(&self.pool)
+                .await?;
+                sample_percent(estimated_rows.unwrap_or_default() as f64, self.limit_size)
+            }
+        };
+        let query = format!(
+            r#"SELECT * FROM "{database}"."{table_schema}"."{table}"{sample}{filter} ORDER BY random() LIMIT {limit}"#,
+            database = database.name,
+            table = table.name,
+            sample = sample.map_or(String::new(), |percent| format!(
+                " TABLESAMPLE BERNOULLI ({:.6})",
+                percent
+            )),
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+            limit = self.limit_size
+        );
+        self.execute(&query).await?.into_records()
+    }
+
     async fn get_total_row_count(
         &self,
         database: &Database,
@@ -383,23 +713,67 @@ impl Pool for PostgresPool {
         database: &Database,
         table: &Table,
     ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        if table.kind.is_routine() {
+            return self.get_parameters(table).await;
+        }
+        if self.server == Server::Redshift {
+            return self.get_redshift_columns(database, table).await;
+        }
         let table_schema = table
             .schema
             .as_ref()
             .map_or("public", |schema| schema.as_str());
-        let mut rows = sqlx::query(
-            "SELECT * FROM information_schema.columns WHERE table_catalog = $1 AND table_schema = $2 AND table_name = $3"
-        )
-        .bind(&database.name).bind(table_schema).bind(&table.name)
-        .fetch(&self.pool);
+        // information_schema.columns.generation_expression only exists since PostgreSQL 12
+        let generation_expression = if self.capabilities.supports(Capability::GeneratedColumns) {
+            "c.generation_expression::text"
+        } else {
+            "NULL::text"
+        };
+        let query = format!(
+            "
+        SELECT
+            c.column_name,
+            CASE WHEN c.data_type = 'USER-DEFINED' THEN c.udt_name::text ELSE c.data_type::text END AS data_type,
+            c.is_nullable::text,
+            c.column_default::text,
+            {} AS generation_expression,
+            (
+                SELECT array_agg(e.enumlabel::text ORDER BY e.enumsortorder)
+                FROM pg_type t
+                JOIN pg_namespace tn ON tn.oid = t.typnamespace
+                JOIN pg_enum e ON e.enumtypid = t.oid
+                WHERE t.typname = c.udt_name AND tn.nspname = c.udt_schema
+            ) AS enum_values
+        FROM
+            information_schema.columns AS c
+        WHERE
+            c.table_catalog = $1
+            AND c.table_schema = $2
+            AND c.table_name = $3
+        ORDER BY
+            c.ordinal_position
+        ",
+            generation_expression
+        );
+        let mut rows = sqlx::query(&query)
+            .bind(&database.name)
+            .bind(table_schema)
+            .bind(&table.name)
+            .fetch(&self.pool);
         let mut columns: Vec<Box<dyn TableRow>> = vec![];
         while let Some(row) = rows.try_next().await? {
             columns.push(Box::new(Column {
                 name: row.try_get("column_name")?,
                 r#type: row.try_get("data_type")?,
                 null: row.try_get("is_nullable")?,
-                default: row.try_get("column_default")?,
+                default: match row.try_get::<Option<String>, _>("generation_expression")? {
+                    Some(expression) => {
+                        Some(format!("GENERATED ALWAYS AS ({}) STORED", expression))
+                    }
+                    None => row.try_get("column_default")?,
+                },
                 comment: None,
+                values: row.try_get("enum_values")?,
             }))
         }
         Ok(columns)
@@ -438,6 +812,49 @@ impl Pool for PostgresPool {
             constraints.push(Box::new(Constraint {
                 name: row.try_get("constraint_name")?,
                 column_name: row.try_get("column_name")?,
+                definition: None,
+            }))
+        }
+        drop(rows);
+        // Redshift has no CHECK constraints
+        if self.server == Server::Redshift {
+            return Ok(constraints);
+        }
+
+        // CHECK constraints are not in key_column_usage
+        let table_schema = table
+            .schema
+            .as_ref()
+            .map_or("public", |schema| schema.as_str());
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            con.conname::text AS constraint_name,
+            pg_get_constraintdef(con.oid) AS definition,
+            (
+                SELECT string_agg(a.attname::text, ', ' ORDER BY a.attnum)
+                FROM pg_attribute a
+                WHERE a.attrelid = con.conrelid AND a.attnum = ANY(con.conkey)
+            ) AS column_name
+        FROM
+            pg_constraint con
+            JOIN pg_class c ON c.oid = con.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE
+            con.contype = 'c'
+            AND n.nspname = $1
+            AND c.relname = $2
+        ",
+        )
+        .bind(table_schema)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        while let Some(row) = rows.try_next().await? {
+            let column_name: Option<String> = row.try_get("column_name")?;
+            constraints.push(Box::new(Constraint {
+                name: row.try_get("constraint_name")?,
+                column_name: column_name.unwrap_or_default(),
+                definition: row.try_get("definition")?,
             }))
         }
         Ok(constraints)
@@ -483,11 +900,40 @@ impl Pool for PostgresPool {
         Ok(constraints)
     }
 
+    async fn get_primary_key(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        let table_schema = table
+            .schema
+            .as_ref()
+            .map_or("public", |schema| schema.as_str());
+        Ok(sqlx::query_scalar(
+            "
+        SELECT kcu.column_name::text
+        FROM information_schema.table_constraints tc
+        JOIN information_schema.key_column_usage kcu
+            ON kcu.constraint_name = tc.constraint_name AND kcu.table_schema = tc.table_schema
+            AND kcu.table_name = tc.table_name
+        WHERE tc.constraint_type = 'PRIMARY KEY' AND tc.table_schema = $1 AND tc.table_name = $2
+        ORDER BY kcu.ordinal_position
+        ",
+        )
+        .bind(table_schema)
+        .bind(&table.name)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
     async fn get_indexes(
         &self,
         _database: &Database,
         table: &Table,
     ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        if self.server == Server::Redshift {
+            return self.get_redshift_keys(table).await;
+        }
         let mut rows = sqlx::query(
             "
         SELECT
@@ -527,43 +973,762 @@ impl Pool for PostgresPool {
         Ok(foreign_keys)
     }
 
-    async fn get_definition(&self, _database: &Database, _table: &Table) -> anyhow::Result<String> {
-        Ok("Sorry, Postgres SQL is not supported Table Definitions.\n\
-            Please see this issue if you want to implement this feature, see here!\n\
-            https://github.com/kyoto7250/zhobo/issues/94"
-            .to_owned())
+    async fn get_definition(&self, _database: &Database, table: &Table) -> anyhow::Result<String> {
+        if self.server == Server::Redshift {
+            return self.get_redshift_definition(table).await;
+        }
+        if self.server == Server::CockroachDb {
+            return self.get_cockroach_definition(table).await;
+        }
+        if table.kind.is_routine() {
+            // overloads share the name, so all of them are shown
+            // pg_proc.prokind only exists since PostgreSQL 11, and pg_get_functiondef fails on
+            // aggregates
+            let kind = if self.capabilities.supports(Capability::Procedures) {
+                "p.prokind IN ('f', 'p')"
+            } else {
+                "NOT p.proisagg"
+            };
+            let row = sqlx::query(&format!(
+                "
+            SELECT string_agg(pg_get_functiondef(p.oid), chr(10) ORDER BY p.oid)
+            FROM pg_proc p
+            JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE n.nspname = $1 AND p.proname = $2 AND {}
+            ",
+                kind
+            ))
+            .bind(table.schema.as_deref().unwrap_or("public"))
+            .bind(&table.name)
+            .fetch_one(&self.pool)
+            .await?;
+            return Ok(row.try_get::<Option<String>, _>(0)?.unwrap_or_default());
+        }
+        if table.kind == TableKind::Sequence {
+            let row = sqlx::query(
+                "
+            SELECT format(
+                'CREATE SEQUENCE %I.%I AS %s INCREMENT BY %s MINVALUE %s MAXVALUE %s START WITH %s CACHE %s%s;',
+                schemaname, sequencename, data_type, increment_by, min_value, max_value,
+                start_value, cache_size, CASE WHEN cycle THEN ' CYCLE' ELSE '' END
+            )
+            FROM pg_sequences
+            WHERE schemaname = $1 AND sequencename = $2
+            ",
+            )
+            .bind(table.schema.as_deref().unwrap_or("public"))
+            .bind(&table.name)
+            .fetch_one(&self.pool)
+            .await?;
+            return Ok(row.try_get(0)?);
+        }
+        if table.kind.is_view() {
+            let create = if table.kind == TableKind::MaterializedView {
+                "MATERIALIZED VIEW"
+            } else {
+                "OR REPLACE VIEW"
+            };
+            let row = sqlx::query(
+                "SELECT format('CREATE %s %I.%I AS%s%s', $1::text, $2::text, $3::text, chr(10),
+                    pg_get_viewdef(format('%I.%I', $2::text, $3::text)::regclass, true))",
+            )
+            .bind(create)
+            .bind(table.schema.as_deref().unwrap_or("public"))
+            .bind(&table.name)
+            .fetch_one(&self.pool)
+            .await?;
+            return Ok(row.try_get(0)?);
+        }
+        self.create_table(table).await
+    }
+
+    async fn get_dependencies(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let table_schema = table
+            .schema
+            .as_ref()
+            .map_or("public", |schema| schema.as_str());
+        let mut rows = sqlx::query(
+            "
+        WITH t AS (
+            SELECT c.oid, c.reltype
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2
+        )
+        SELECT * FROM (
+            SELECT DISTINCT
+                CASE v.relkind WHEN 'm' THEN 'materialized view' ELSE 'view' END AS kind,
+                v.oid::regclass::text AS name,
+                '' AS detail
+            FROM t
+            JOIN pg_depend d ON d.refobjid = t.oid AND d.classid = 'pg_rewrite'::regclass
+            JOIN pg_rewrite r ON r.oid = d.objid
+            JOIN pg_class v ON v.oid = r.ev_class
+            WHERE v.oid <> t.oid
+            UNION ALL
+            SELECT 'foreign key', con.conname::text, 'on ' || con.conrelid::regclass::text
+            FROM t
+            JOIN pg_constraint con ON con.confrelid = t.oid AND con.contype = 'f'
+            UNION ALL
+            SELECT DISTINCT 'function', p.oid::regprocedure::text, ''
+            FROM t
+            JOIN pg_depend d ON d.refobjid IN (t.oid, t.reltype) AND d.classid = 'pg_proc'::regclass
+            JOIN pg_proc p ON p.oid = d.objid
+            UNION ALL
+            -- the bodies of most functions are not tracked by pg_depend
+            SELECT 'function', p.oid::regprocedure::text, 'body mentions the table'
+            FROM t, pg_proc p
+            JOIN pg_namespace pn ON pn.oid = p.pronamespace
+            WHERE pn.nspname NOT IN ('pg_catalog', 'information_schema')
+                AND p.prosrc ILIKE '%' || $2 || '%'
+                AND NOT EXISTS (
+                    SELECT 1 FROM pg_depend d
+                    WHERE d.objid = p.oid AND d.refobjid IN (t.oid, t.reltype)
+                )
+            UNION ALL
+            SELECT 'trigger', tg.tgname::text, 'runs ' || tg.tgfoid::regprocedure::text
+            FROM t
+            JOIN pg_trigger tg ON tg.tgrelid = t.oid AND NOT tg.tgisinternal
+        ) AS dependencies
+        ORDER BY kind, name
+        ",
+        )
+        .bind(table_schema)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut dependencies: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            dependencies.push(Box::new(Dependency {
+                kind: row.try_get("kind")?,
+                name: row.try_get("name")?,
+                detail: row.try_get("detail")?,
+            }))
+        }
+        Ok(dependencies)
     }
 
-    async fn close(&self) {
-        self.pool.close().await;
+    async fn get_triggers(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // Redshift has no triggers
+        if self.server == Server::Redshift {
+            return Ok(vec![]);
+        }
+        let table_schema = table
+            .schema
+            .as_ref()
+            .map_or("public", |schema| schema.as_str());
+        // information_schema has a row per event, which are joined back into one trigger
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            trigger_name::text AS name,
+            action_timing::text AS timing,
+            string_agg(event_manipulation::text, ' OR ' ORDER BY event_manipulation) AS event,
+            action_statement::text AS statement
+        FROM information_schema.triggers
+        WHERE event_object_schema = $1 AND event_object_table = $2
+        GROUP BY trigger_name, action_timing, action_statement, action_order
+        ORDER BY action_timing, action_order, trigger_name
+        ",
+        )
+        .bind(table_schema)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut triggers: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            triggers.push(Box::new(Trigger {
+                name: row.try_get("name")?,
+                timing: row.try_get("timing")?,
+                event: row.try_get("event")?,
+                statement: row.try_get("statement")?,
+            }))
+        }
+        Ok(triggers)
     }
-}
 
-impl PostgresPool {
-    async fn get_json_records(
+    async fn get_statistics(
         &self,
         database: &Database,
         table: &Table,
-        page: u16,
-        filter: Option<String>,
-        orders: Option<String>,
-    ) -> anyhow::Result<Vec<serde_json::Value>> {
-        let query = if let (Some(filter), Some(orders)) = (&filter, &orders) {
-            format!(
-                r#"SELECT to_json("{table}".*) FROM "{database}"."{table_schema}"."{table}" WHERE {filter} {orders} LIMIT {limit} OFFSET {page}"#,
-                database = database.name,
-                table = table.name,
-                filter = filter,
-                orders = orders,
-                table_schema = table.schema.clone().unwrap_or_else(|| "public".to_string()),
-                page = page,
-                limit = self.limit_size
-            )
-        } else if let Some(filter) = filter {
-            format!(
-                r#"SELECT to_json("{table}".*) FROM "{database}"."{table_schema}"."{table}" WHERE {filter} LIMIT {limit} OFFSET {page}"#,
-                database = database.name,
-                table = table.name,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        if table.kind == TableKind::Sequence {
+            return self.get_sequence_statistics(table).await;
+        }
+        if self.server == Server::Redshift {
+            return self.get_redshift_statistics(database, table).await;
+        }
+        if self.server == Server::CockroachDb {
+            return self.get_cockroach_statistics(database, table).await;
+        }
+        let table_schema = table
+            .schema
+            .as_ref()
+            .map_or("public", |schema| schema.as_str());
+        let row = sqlx::query(
+            "
+        SELECT
+            c.reltuples::bigint AS estimated_rows,
+            pg_table_size(c.oid) AS data_size,
+            pg_indexes_size(c.oid) AS index_size,
+            pg_total_relation_size(c.oid) AS total_size,
+            s.n_dead_tup AS dead_rows,
+            s.last_vacuum::text AS last_vacuum,
+            s.last_autovacuum::text AS last_autovacuum,
+            s.last_analyze::text AS last_analyze,
+            s.last_autoanalyze::text AS last_autoanalyze
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_stat_all_tables s ON s.relid = c.oid
+        WHERE n.nspname = $1 AND c.relname = $2
+        ",
+        )
+        .bind(table_schema)
+        .bind(&table.name)
+        .fetch_one(&self.pool)
+        .await?;
+        let size = |column: &str| -> anyhow::Result<Option<String>> {
+            Ok(row
+                .try_get::<Option<i64>, _>(column)?
+                .map(|bytes| format_bytes(bytes.max(0) as u64)))
+        };
+        Ok(Statistic::rows(vec![
+            (
+                "rows (exact)",
+                self.get_total_row_count(database, table, None)
+                    .await
+                    .ok()
+                    .map(|count| count.to_string()),
+            ),
+            (
+                "rows (estimated)",
+                // -1 until the table is first vacuumed or analyzed
+                row.try_get::<Option<i64>, _>("estimated_rows")?
+                    .filter(|count| *count >= 0)
+                    .map(|count| count.to_string()),
+            ),
+            (
+                "dead rows",
+                row.try_get::<Option<i64>, _>("dead_rows")?
+                    .map(|count| count.to_string()),
+            ),
+            ("data size", size("data_size")?),
+            ("index size", size("index_size")?),
+            ("total size", size("total_size")?),
+            ("last vacuum", row.try_get("last_vacuum")?),
+            ("last autovacuum", row.try_get("last_autovacuum")?),
+            ("last analyze", row.try_get("last_analyze")?),
+            ("last autoanalyze", row.try_get("last_autoanalyze")?),
+        ]))
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        // Redshift and CockroachDB lack some of these functions and views, whose figures are
+        // left out there
+        let uptime: Option<i64> = sqlx::query_scalar(
+            "SELECT extract(epoch FROM now() - pg_postmaster_start_time())::bigint",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .ok();
+        let connections: Option<(i64, i64)> = sqlx::query_as(
+            "
+        SELECT count(*)::bigint, count(*) FILTER (WHERE state = 'active')::bigint
+        FROM pg_stat_activity
+        ",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .ok();
+        let max_connections: Option<String> = sqlx::query_scalar("SHOW max_connections")
+            .fetch_one(&self.pool)
+            .await
+            .ok();
+        let blocks: Option<(Option<i64>, Option<i64>)> = sqlx::query_as(
+            "SELECT sum(blks_hit)::bigint, sum(blks_read)::bigint FROM pg_stat_database",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .ok();
+        let database_size: Option<i64> =
+            sqlx::query_scalar("SELECT pg_database_size(current_database())")
+                .fetch_one(&self.pool)
+                .await
+                .ok();
+        let largest_tables: Vec<(String, i64)> = sqlx::query_as(
+            "
+        SELECT n.nspname || '.' || c.relname AS name, pg_total_relation_size(c.oid) AS size
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind IN ('r', 'm', 'p')
+            AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+            AND n.nspname NOT LIKE 'pg_toast%'
+        ORDER BY size DESC
+        LIMIT $1
+        ",
+        )
+        .bind(ServerOverview::LARGEST_TABLES as i64)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap_or_default();
+        Ok(ServerOverview::new(
+            vec![
+                ("server version", Some(self.capabilities.to_string())),
+                (
+                    "uptime",
+                    uptime.map(|seconds| format_uptime(seconds.max(0) as u64)),
+                ),
+                (
+                    "connections",
+                    connections.map(|(count, _)| match &max_connections {
+                        Some(max) => format!("{} of {}", count, max),
+                        None => count.to_string(),
+                    }),
+                ),
+                (
+                    "active connections",
+                    connections.map(|(_, active)| active.to_string()),
+                ),
+                (
+                    "cache hit ratio",
+                    blocks.and_then(|(hits, reads)| {
+                        hit_ratio(
+                            hits.unwrap_or_default().max(0) as u64,
+                            reads.unwrap_or_default().max(0) as u64,
+                        )
+                    }),
+                ),
+                (
+                    "database size",
+                    database_size.map(|bytes| format_bytes(bytes.max(0) as u64)),
+                ),
+            ],
+            largest_tables
+                .into_iter()
+                .map(|(name, size)| (name, size.max(0) as u64))
+                .collect(),
+        ))
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        // the background workers of the server are left out, as they cannot be ended
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            pid::text AS id,
+            COALESCE(usename::text, '') AS user_name,
+            datname::text AS database_name,
+            COALESCE(state, '')::text AS state,
+            extract(epoch FROM now() - COALESCE(query_start, backend_start))::bigint AS elapsed,
+            query
+        FROM pg_stat_activity
+        WHERE pid <> pg_backend_pid() AND backend_type = 'client backend'
+        ORDER BY elapsed DESC NULLS LAST
+        ",
+        )
+        .fetch(&self.pool);
+        let mut sessions = vec![];
+        while let Some(row) = rows.try_next().await? {
+            sessions.push(ServerSession {
+                id: row.try_get("id")?,
+                user: row.try_get("user_name")?,
+                database: row.try_get("database_name")?,
+                state: row.try_get("state")?,
+                elapsed: row
+                    .try_get::<Option<i64>, _>("elapsed")?
+                    .map(|seconds| seconds.max(0) as u64),
+                query: row
+                    .try_get::<Option<String>, _>("query")?
+                    .filter(|query| !query.is_empty()),
+            })
+        }
+        Ok(sessions)
+    }
+
+    async fn get_privileges(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let table_schema = table
+            .schema
+            .as_ref()
+            .map_or("public", |schema| schema.as_str());
+        // information_schema only lists the grants the current user is part of, so the access
+        // list of the table is read where the server has one; a table without one still gives
+        // its owner every privilege
+        let query = if matches!(self.server, Server::Redshift | Server::CockroachDb) {
+            "
+        SELECT
+            grantee::text AS grantee,
+            privilege_type::text AS privilege,
+            is_grantable::text = 'YES' AS grantable
+        FROM information_schema.table_privileges
+        WHERE table_schema = $1 AND table_name = $2
+        ORDER BY 1, 2
+        "
+        } else {
+            "
+        SELECT
+            CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee)::text END AS grantee,
+            a.privilege_type::text AS privilege,
+            a.is_grantable AS grantable
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        CROSS JOIN LATERAL aclexplode(COALESCE(c.relacl, acldefault('r', c.relowner))) a
+        WHERE n.nspname = $1 AND c.relname = $2
+        ORDER BY 1, 2
+        "
+        };
+        let mut rows = sqlx::query(query)
+            .bind(table_schema)
+            .bind(&table.name)
+            .fetch(&self.pool);
+        let mut privileges: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            privileges.push(Box::new(Privilege {
+                grantee: row.try_get("grantee")?,
+                privilege: row.try_get("privilege")?,
+                level: "table".to_string(),
+                grantable: row.try_get("grantable")?,
+            }))
+        }
+        Ok(privileges)
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        // Redshift has users and groups instead of roles
+        if self.server == Server::Redshift {
+            let users: Vec<(String, bool)> =
+                sqlx::query_as("SELECT usename::text, usesuper FROM pg_user ORDER BY usename")
+                    .fetch_all(&self.pool)
+                    .await?;
+            return Ok(users
+                .into_iter()
+                .map(|(name, superuser)| DatabaseRole {
+                    name,
+                    can_login: true,
+                    superuser,
+                    member_of: Vec::new(),
+                })
+                .collect());
+        }
+        // the predefined pg_ roles are left out
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            r.rolname::text AS name,
+            r.rolcanlogin AS can_login,
+            r.rolsuper AS superuser,
+            ARRAY(
+                SELECT b.rolname::text
+                FROM pg_auth_members m
+                JOIN pg_roles b ON b.oid = m.roleid
+                WHERE m.member = r.oid
+                ORDER BY 1
+            ) AS member_of
+        FROM pg_roles r
+        WHERE r.rolname !~ '^pg_'
+        ORDER BY 1
+        ",
+        )
+        .fetch(&self.pool);
+        let mut roles = vec![];
+        while let Some(row) = rows.try_next().await? {
+            roles.push(DatabaseRole {
+                name: row.try_get("name")?,
+                can_login: row.try_get("can_login")?,
+                superuser: row.try_get("superuser")?,
+                member_of: row.try_get("member_of")?,
+            })
+        }
+        Ok(roles)
+    }
+
+    async fn get_relations(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        // Redshift does not enforce foreign keys and cannot unnest their columns
+        if self.server == Server::Redshift {
+            return Ok(vec![]);
+        }
+        let table_schema = table
+            .schema
+            .as_ref()
+            .map_or("public", |schema| schema.as_str());
+        let rows: Vec<(String, String, String, String, String, String, String)> = sqlx::query_as(
+            "
+        SELECT
+            con.conname::text,
+            n.nspname::text,
+            c.relname::text,
+            a.attname::text,
+            rn.nspname::text,
+            r.relname::text,
+            ra.attname::text
+        FROM pg_constraint con
+        JOIN pg_class c ON c.oid = con.conrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_class r ON r.oid = con.confrelid
+        JOIN pg_namespace rn ON rn.oid = r.relnamespace
+        CROSS JOIN LATERAL unnest(con.conkey, con.confkey) WITH ORDINALITY AS k(attnum, ref_attnum, position)
+        JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+        JOIN pg_attribute ra ON ra.attrelid = con.confrelid AND ra.attnum = k.ref_attnum
+        WHERE con.contype = 'f'
+            AND ((n.nspname = $1 AND c.relname = $2) OR (rn.nspname = $1 AND r.relname = $2))
+        ORDER BY n.nspname, c.relname, con.conname, k.position
+        ",
+        )
+        .bind(table_schema)
+        .bind(&table.name)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(Relation::merge_columns(
+            rows.into_iter()
+                .map(
+                    |(name, schema, table, column, ref_schema, ref_table, ref_column)| Relation {
+                        name,
+                        schema: Some(schema),
+                        table,
+                        columns: vec![column],
+                        ref_schema: Some(ref_schema),
+                        ref_table,
+                        ref_columns: vec![ref_column],
+                    },
+                )
+                .collect(),
+        ))
+    }
+
+    async fn get_partitions(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // Redshift and CockroachDB have no declarative partitions
+        if matches!(self.server, Server::Redshift | Server::CockroachDb) {
+            return Ok(vec![]);
+        }
+        let table_schema = table
+            .schema
+            .as_ref()
+            .map_or("public", |schema| schema.as_str());
+        // reltuples is -1 until the partition is analyzed
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            c.relname::text AS name,
+            COALESCE(pg_get_expr(c.relpartbound, c.oid), '')::text AS bounds,
+            CASE WHEN c.reltuples < 0 THEN NULL ELSE c.reltuples::bigint END AS table_rows,
+            pg_total_relation_size(c.oid) AS size
+        FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        JOIN pg_class p ON p.oid = i.inhparent
+        JOIN pg_namespace n ON n.oid = p.relnamespace
+        WHERE n.nspname = $1 AND p.relname = $2 AND c.relispartition
+        ORDER BY 1
+        ",
+        )
+        .bind(table_schema)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut partitions: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            partitions.push(Box::new(Partition {
+                name: row.try_get("name")?,
+                bounds: row.try_get("bounds")?,
+                rows: row.try_get("table_rows")?,
+                size: row
+                    .try_get::<Option<i64>, _>("size")?
+                    .map(|size| size.max(0) as u64),
+            }))
+        }
+        Ok(partitions)
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        // information_schema only lists the objects of the database which is connected to
+        let database: String = sqlx::query_scalar("SELECT current_database()")
+            .fetch_one(&self.pool)
+            .await?;
+        let query = if self.server == Server::Redshift {
+            REDSHIFT_OBJECTS
+        } else {
+            "
+        SELECT
+            CASE WHEN table_type = 'VIEW' THEN 'view' ELSE 'table' END AS kind,
+            table_schema::text AS schema_name,
+            NULL::text AS table_name,
+            table_name::text AS name
+        FROM information_schema.tables
+        WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+        UNION ALL
+        SELECT 'column', table_schema::text, table_name::text, column_name::text
+        FROM information_schema.columns
+        WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+        UNION ALL
+        SELECT
+            COALESCE(LOWER(routine_type::text), 'function'),
+            routine_schema::text,
+            NULL,
+            routine_name::text
+        FROM information_schema.routines
+        WHERE routine_schema NOT IN ('pg_catalog', 'information_schema')
+        "
+        };
+        let mut rows = sqlx::query(query).fetch(&self.pool);
+        let mut objects = vec![];
+        while let Some(row) = rows.try_next().await? {
+            objects.push(SchemaObject {
+                kind: row.try_get("kind")?,
+                database: database.clone(),
+                schema: row.try_get("schema_name")?,
+                table: row.try_get("table_name")?,
+                name: row.try_get("name")?,
+            })
+        }
+        Ok(objects)
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        let rows: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT name, setting, reset_val FROM pg_settings WHERE name = ANY($1) ORDER BY name",
+        )
+        .bind(
+            SESSION_SETTINGS
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let defaults = rows
+            .iter()
+            .map(|(_, _, default)| default.clone())
+            .collect::<Vec<_>>();
+        let mut settings = SessionSetting::from_rows(
+            rows.into_iter()
+                .map(|(name, value, _)| (name, value))
+                .collect(),
+            &SESSION_SETTINGS,
+        );
+        for (setting, default) in settings.iter_mut().zip(defaults) {
+            if !setting.choices.is_empty() && !setting.choices.contains(&default) {
+                setting.choices.insert(0, default);
+            }
+        }
+        Ok(settings)
+    }
+
+    async fn begin(&self) -> anyhow::Result<()> {
+        self.transaction.begin(&self.pool).await
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        self.transaction.commit().await
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        self.transaction.rollback().await
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    fn ping(&self) -> Ping {
+        let pool = self.pool.clone();
+        Box::pin(async move {
+            sqlx::query("SELECT 1").execute(&pool).await?;
+            Ok(())
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+}
+
+impl PostgresPool {
+    /// Returns the parameters of a routine and the return type of a function, for every
+    /// overload of its name.
+    async fn get_parameters(&self, routine: &Table) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT name, mode, type FROM (
+            SELECT
+                r.specific_name::text AS specific_name,
+                p.ordinal_position::int AS position,
+                COALESCE(p.parameter_name::text, '$' || p.ordinal_position) AS name,
+                p.parameter_mode::text AS mode,
+                CASE WHEN p.data_type = 'USER-DEFINED' THEN p.udt_name::text ELSE p.data_type::text END AS type
+            FROM information_schema.routines r
+            JOIN information_schema.parameters p
+                ON p.specific_schema = r.specific_schema AND p.specific_name = r.specific_name
+            WHERE r.routine_schema = $1 AND r.routine_name = $2
+            UNION ALL
+            SELECT
+                r.specific_name::text,
+                0,
+                '(return)',
+                '',
+                CASE WHEN r.data_type = 'USER-DEFINED' THEN r.type_udt_name::text ELSE r.data_type::text END
+            FROM information_schema.routines r
+            WHERE r.routine_schema = $1 AND r.routine_name = $2 AND r.data_type IS NOT NULL
+        ) parameters
+        ORDER BY specific_name, position
+        ",
+        )
+        .bind(routine.schema.as_deref().unwrap_or("public"))
+        .bind(&routine.name)
+        .fetch(&self.pool);
+        let mut parameters: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            parameters.push(Box::new(Parameter {
+                name: row.try_get("name")?,
+                mode: row
+                    .try_get::<Option<String>, _>("mode")?
+                    .unwrap_or_default(),
+                r#type: row
+                    .try_get::<Option<String>, _>("type")?
+                    .unwrap_or_default(),
+            }))
+        }
+        Ok(parameters)
+    }
+
+    async fn get_json_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        page: usize,
+        filter: Option<String>,
+        orders: Option<String>,
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        let query = if let (Some(filter), Some(orders)) = (&filter, &orders) {
+            format!(
+                r#"SELECT to_json("{table}".*) FROM "{database}"."{table_schema}"."{table}" WHERE {filter} {orders} LIMIT {limit} OFFSET {page}"#,
+                database = database.name,
+                table = table.name,
+                filter = filter,
+                orders = orders,
+                table_schema = table.schema.clone().unwrap_or_else(|| "public".to_string()),
+                page = page,
+                limit = self.limit_size
+            )
+        } else if let Some(filter) = filter {
+            format!(
+                r#"SELECT to_json("{table}".*) FROM "{database}"."{table_schema}"."{table}" WHERE {filter} LIMIT {limit} OFFSET {page}"#,
+                database = database.name,
+                table = table.name,
                 filter = filter,
                 table_schema = table.schema.clone().unwrap_or_else(|| "public".to_string()),
                 page = page,
@@ -595,61 +1760,514 @@ impl PostgresPool {
     }
 }
 
-fn convert_column_value_to_string(row: &PgRow, column: &PgColumn) -> anyhow::Result<String> {
+impl PostgresPool {
+    /// Returns the state of a sequence with the column it belongs to, which is the one of a
+    /// `serial` or identity column.
+    async fn get_sequence_statistics(
+        &self,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // last_value is NULL until the sequence is first used, or without the privilege to read
+        // it
+        let row = sqlx::query(
+            "
+        SELECT
+            s.last_value::text AS last_value,
+            s.increment_by::text AS increment_by,
+            s.min_value::text AS min_value,
+            s.max_value::text AS max_value,
+            s.start_value::text AS start_value,
+            s.cache_size::text AS cache_size,
+            s.cycle AS cycle,
+            s.data_type::text AS data_type,
+            (
+                SELECT format('%I.%I.%I', tn.nspname, t.relname, a.attname)
+                FROM pg_depend d
+                JOIN pg_class t ON t.oid = d.refobjid
+                JOIN pg_namespace tn ON tn.oid = t.relnamespace
+                JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = d.refobjsubid
+                WHERE d.classid = 'pg_class'::regclass
+                    AND d.objid = format('%I.%I', s.schemaname, s.sequencename)::regclass
+                    AND d.refclassid = 'pg_class'::regclass
+                    AND d.deptype IN ('a', 'i')
+                LIMIT 1
+            ) AS owned_by
+        FROM pg_sequences s
+        WHERE s.schemaname = $1 AND s.sequencename = $2
+        ",
+        )
+        .bind(table.schema.as_deref().unwrap_or("public"))
+        .bind(&table.name)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(Statistic::rows(vec![
+            (
+                "current value",
+                Some(
+                    row.try_get::<Option<String>, _>("last_value")?
+                        .unwrap_or_else(|| "not used yet".to_string()),
+                ),
+            ),
+            ("increment", row.try_get("increment_by")?),
+            ("minimum", row.try_get("min_value")?),
+            ("maximum", row.try_get("max_value")?),
+            ("start", row.try_get("start_value")?),
+            ("cache", row.try_get("cache_size")?),
+            (
+                "cycles",
+                Some(if row.try_get("cycle")? { "yes" } else { "no" }.to_string()),
+            ),
+            ("type", row.try_get("data_type")?),
+            ("owned by", row.try_get("owned_by")?),
+        ]))
+    }
+
+    async fn get_postgres_tables(&self, database: &str) -> anyhow::Result<Vec<Table>> {
+        // materialized views are not in information_schema, and sequences are apart from the
+        // tables there
+        let mut rows = sqlx::query(
+            "
+        SELECT table_schema::text, table_name::text, table_type::text
+        FROM information_schema.tables
+        WHERE table_catalog = $1
+        UNION ALL
+        SELECT schemaname::text, matviewname::text, 'MATERIALIZED VIEW'
+        FROM pg_matviews
+        WHERE current_database() = $1
+        UNION ALL
+        SELECT DISTINCT routine_schema::text, routine_name::text, COALESCE(routine_type::text, 'FUNCTION')
+        FROM information_schema.routines
+        WHERE routine_catalog = $1
+            AND routine_schema NOT IN ('pg_catalog', 'information_schema')
+        UNION ALL
+        SELECT sequence_schema::text, sequence_name::text, 'SEQUENCE'
+        FROM information_schema.sequences
+        WHERE sequence_catalog = $1
+        ",
+        )
+        .bind(database)
+        .fetch(&self.pool);
+        let mut tables = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            tables.push(table_of_row(&row)?)
+        }
+        drop(rows);
+        // relispartition is new in PostgreSQL 10, before which there are no partitions to nest
+        let partitions: Vec<(String, String, String)> = sqlx::query_as(
+            "
+        SELECT n.nspname::text, c.relname::text, p.relname::text
+        FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        JOIN pg_class p ON p.oid = i.inhparent
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relispartition
+        ",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap_or_default();
+        for (schema, name, parent) in partitions {
+            if let Some(table) = tables
+                .iter_mut()
+                .find(|table| table.name == name && table.schema.as_deref() == Some(&schema))
+            {
+                table.partition_of = Some(parent);
+            }
+        }
+        Ok(tables)
+    }
+}
+
+/// The catalog of Redshift, which has neither pg_matviews, information_schema.routines nor
+/// most functions of pg_catalog, but describes its tables in its own `svv_` views.
+impl PostgresPool {
+    async fn get_redshift_tables(&self, database: &str) -> anyhow::Result<Vec<Table>> {
+        // svv_tables also lists the external tables of Redshift Spectrum
+        let mut rows = sqlx::query(
+            "
+        SELECT table_schema::text, table_name::text, table_type::text
+        FROM svv_tables
+        WHERE table_catalog = $1
+            AND table_schema NOT IN ('pg_catalog', 'information_schema')
+        ",
+        )
+        .bind(database)
+        .fetch(&self.pool);
+        let mut tables = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            tables.push(table_of_row(&row)?)
+        }
+        drop(rows);
+        let mut rows = sqlx::query(
+            "
+        SELECT DISTINCT
+            n.nspname::text AS table_schema,
+            p.proname::text AS table_name,
+            CASE WHEN p.prokind = 'p' THEN 'PROCEDURE' ELSE 'FUNCTION' END AS table_type
+        FROM pg_proc_info p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+        ",
+        )
+        .fetch(&self.pool);
+        while let Some(row) = rows.try_next().await? {
+            tables.push(table_of_row(&row)?)
+        }
+        Ok(tables)
+    }
+
+    async fn get_redshift_columns(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            column_name::text,
+            data_type::text,
+            is_nullable::text,
+            column_default::text,
+            remarks::text
+        FROM svv_columns
+        WHERE table_catalog = $1 AND table_schema = $2 AND table_name = $3
+        ORDER BY ordinal_position
+        ",
+        )
+        .bind(&database.name)
+        .bind(table.schema.as_deref().unwrap_or("public"))
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut columns: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            columns.push(Box::new(Column {
+                name: row.try_get("column_name")?,
+                r#type: row.try_get("data_type")?,
+                null: row.try_get("is_nullable")?,
+                default: row.try_get("column_default")?,
+                comment: row.try_get("remarks")?,
+                values: None,
+            }))
+        }
+        Ok(columns)
+    }
+
+    /// Returns the distribution and sort keys of a table, which take the place of indexes in
+    /// Redshift.
+    async fn get_redshift_keys(&self, table: &Table) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            a.attname::text AS column_name,
+            a.attisdistkey AS distkey,
+            a.attsortkeyord::int AS sortkey
+        FROM pg_attribute a
+        JOIN pg_class c ON c.oid = a.attrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relname = $2 AND a.attnum > 0
+            AND (a.attisdistkey OR a.attsortkeyord <> 0)
+        ORDER BY abs(a.attsortkeyord), a.attnum
+        ",
+        )
+        .bind(table.schema.as_deref().unwrap_or("public"))
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut keys: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            let column_name: Option<String> = row.try_get("column_name")?;
+            if row.try_get::<bool, _>("distkey")? {
+                keys.push(Box::new(Index {
+                    name: Some("distkey".to_string()),
+                    column_name: column_name.clone(),
+                    r#type: Some("key".to_string()),
+                }))
+            }
+            // the columns of an interleaved sort key have a negative position
+            let sortkey: i32 = row.try_get("sortkey")?;
+            if sortkey != 0 {
+                keys.push(Box::new(Index {
+                    name: Some("sortkey".to_string()),
+                    column_name,
+                    r#type: Some(
+                        if sortkey > 0 {
+                            "compound"
+                        } else {
+                            "interleaved"
+                        }
+                        .to_string(),
+                    ),
+                }))
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Returns the DDL which Redshift shows for a table, view or procedure. A function has only
+    /// its body in pg_proc.
+    async fn get_redshift_definition(&self, table: &Table) -> anyhow::Result<String> {
+        let schema = table.schema.as_deref().unwrap_or("public");
+        if table.kind == TableKind::Function {
+            let sources: Vec<String> = sqlx::query_scalar(
+                "
+            SELECT p.prosrc::text
+            FROM pg_proc p
+            JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE n.nspname = $1 AND p.proname = $2
+            ",
+            )
+            .bind(schema)
+            .bind(&table.name)
+            .fetch_all(&self.pool)
+            .await?;
+            return Ok(sources.join("\n\n"));
+        }
+        let object = match table.kind {
+            TableKind::Procedure => "PROCEDURE",
+            kind if kind.is_view() => "VIEW",
+            _ => "TABLE",
+        };
+        let query = format!(
+            "SHOW {} {}.{}",
+            object,
+            quote_identifier(Dialect::Postgres, schema),
+            quote_identifier(Dialect::Postgres, &table.name)
+        );
+        let rows: Vec<String> = sqlx::query_scalar(&query).fetch_all(&self.pool).await?;
+        Ok(rows.join("\n"))
+    }
+
+    async fn get_redshift_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut statistics = vec![(
+            "rows (exact)",
+            self.get_total_row_count(database, table, None)
+                .await
+                .ok()
+                .map(|count| count.to_string()),
+        )];
+        // views are not in svv_table_info, nor are tables without rows
+        let row = sqlx::query(
+            "
+        SELECT
+            tbl_rows::bigint AS estimated_rows,
+            size::bigint * 1048576 AS data_size,
+            diststyle::text,
+            sortkey1::text,
+            unsorted::float8,
+            stats_off::float8
+        FROM svv_table_info
+        WHERE \"schema\" = $1 AND \"table\" = $2
+        ",
+        )
+        .bind(table.schema.as_deref().unwrap_or("public"))
+        .bind(&table.name)
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(row) = row {
+            let percent = |column: &str| -> anyhow::Result<Option<String>> {
+                Ok(row
+                    .try_get::<Option<f64>, _>(column)?
+                    .map(|percent| format!("{:.2}%", percent)))
+            };
+            statistics.extend([
+                (
+                    "rows (estimated)",
+                    row.try_get::<Option<i64>, _>("estimated_rows")?
+                        .map(|count| count.to_string()),
+                ),
+                (
+                    "data size",
+                    row.try_get::<Option<i64>, _>("data_size")?
+                        .map(|bytes| format_bytes(bytes.max(0) as u64)),
+                ),
+                ("dist style", row.try_get("diststyle")?),
+                ("first sort key", row.try_get("sortkey1")?),
+                ("unsorted", percent("unsorted")?),
+                ("stats off", percent("stats_off")?),
+            ]);
+        }
+        Ok(Statistic::rows(statistics))
+    }
+}
+
+/// The catalog of CockroachDB, which has no TABLESAMPLE, sizes or pg_stat_* figures, but shows
+/// the DDL of any object and the zone configuration which places the ranges of a table.
+impl PostgresPool {
+    async fn get_cockroach_tables(&self, database: &str) -> anyhow::Result<Vec<Table>> {
+        // information_schema.tables also lists sequences and materialized views
+        let mut rows = sqlx::query(
+            "
+        SELECT table_schema::text, table_name::text, table_type::text
+        FROM information_schema.tables
+        WHERE table_catalog = $1
+        UNION ALL
+        SELECT DISTINCT
+            n.nspname::text,
+            p.proname::text,
+            CASE WHEN p.prokind = 'p' THEN 'PROCEDURE' ELSE 'FUNCTION' END
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE current_database() = $1
+            AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'crdb_internal', 'pg_extension')
+        ",
+        )
+        .bind(database)
+        .fetch(&self.pool);
+        let mut tables = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            tables.push(table_of_row(&row)?)
+        }
+        Ok(tables)
+    }
+
+    async fn get_cockroach_definition(&self, table: &Table) -> anyhow::Result<String> {
+        // SHOW CREATE finds a table, view or sequence by its name alone
+        let object = match table.kind {
+            TableKind::Function => "FUNCTION ",
+            TableKind::Procedure => "PROCEDURE ",
+            _ => "",
+        };
+        let query = format!(
+            "SHOW CREATE {}{}.{}",
+            object,
+            quote_identifier(
+                Dialect::Postgres,
+                table.schema.as_deref().unwrap_or("public")
+            ),
+            quote_identifier(Dialect::Postgres, &table.name)
+        );
+        let mut rows = sqlx::query(&query).fetch(&self.pool);
+        let mut statements = vec![];
+        while let Some(row) = rows.try_next().await? {
+            statements.push(row.try_get::<String, _>("create_statement")?);
+        }
+        Ok(statements.join("\n\n"))
+    }
+
+    async fn get_cockroach_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let table_schema = table.schema.as_deref().unwrap_or("public");
+        let estimated_rows: Option<i64> = sqlx::query_scalar(
+            "
+        SELECT s.estimated_row_count::int8
+        FROM crdb_internal.table_row_statistics s
+        JOIN crdb_internal.tables t ON t.table_id = s.table_id
+        WHERE t.database_name = $1 AND t.schema_name = $2 AND t.name = $3
+        ",
+        )
+        .bind(&database.name)
+        .bind(table_schema)
+        .bind(&table.name)
+        .fetch_optional(&self.pool)
+        .await?;
+        let (target, zone) = if table.kind.is_routine() {
+            (None, Vec::new())
+        } else {
+            let row = sqlx::query(&format!(
+                "SHOW ZONE CONFIGURATION FROM TABLE {}.{}.{}",
+                quote_identifier(Dialect::Postgres, &database.name),
+                quote_identifier(Dialect::Postgres, table_schema),
+                quote_identifier(Dialect::Postgres, &table.name)
+            ))
+            .fetch_one(&self.pool)
+            .await?;
+            let raw: String = row.try_get("raw_config_sql")?;
+            (
+                row.try_get::<Option<String>, _>("target")?,
+                zone_config(&raw)
+                    .into_iter()
+                    .map(|(name, value)| (format!("zone {}", name), value))
+                    .collect(),
+            )
+        };
+        let mut statistics = vec![
+            (
+                "rows (exact)",
+                self.get_total_row_count(database, table, None)
+                    .await
+                    .ok()
+                    .map(|count| count.to_string()),
+            ),
+            (
+                "rows (estimated)",
+                estimated_rows.map(|count| count.to_string()),
+            ),
+            // the zone of the table itself, or the one it inherits, e.g. RANGE default
+            ("zone", target),
+        ];
+        statistics.extend(
+            zone.iter()
+                .map(|(name, value): &(String, String)| (name.as_str(), Some(value.clone()))),
+        );
+        Ok(Statistic::rows(statistics))
+    }
+}
+
+/// Reads the settings of a zone from the statement CockroachDB shows for it, e.g.
+/// `ALTER RANGE default CONFIGURE ZONE USING` followed by `num_replicas = 3,` on each line.
+fn zone_config(raw: &str) -> Vec<(String, String)> {
+    raw.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let (name, value) = line.trim().trim_end_matches(',').split_once(" = ")?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn convert_column_value(row: &PgRow, column: &PgColumn) -> anyhow::Result<Value> {
     let column_name = column.name();
     if let Ok(value) = row.try_get(column_name) {
         let value: Option<i16> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i32> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i64> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<rust_decimal::Decimal> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<&[u8]> = value;
-        Ok(value.map_or("NULL".to_string(), |values| {
-            format!(
-                "\\x{}",
-                values
-                    .iter()
-                    .map(|v| format!("{:02x}", v))
-                    .collect::<String>()
-            )
-        }))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<NaiveDate> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: String = value;
-        Ok(value)
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<chrono::DateTime<chrono::Utc>> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<chrono::DateTime<chrono::Local>> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value.map(|v| v.with_timezone(&chrono::Utc))))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<NaiveDateTime> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<NaiveDate> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<NaiveTime> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<serde_json::Value> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get::<Option<bool>, _>(column_name) {
         let value: Option<bool> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<Vec<String>> = value;
-        Ok(value.map_or("NULL".to_string(), |v| v.join(",")))
+        Ok(Value::from(value))
     } else {
         anyhow::bail!(
             "column type not implemented: `{}` {}",
@@ -658,3 +2276,166 @@ fn convert_column_value_to_string(row: &PgRow, column: &PgColumn) -> anyhow::Res
         )
     }
 }
+
+/// Returns the percentage of rows TABLESAMPLE has to pick to get about twice `limit` rows, or
+/// `None` if the table is small or has not been analyzed yet.
+fn sample_percent(estimated_rows: f64, limit: usize) -> Option<f64> {
+    let percent = limit as f64 * 2.0 * 100.0 / estimated_rows;
+    (estimated_rows > 0.0 && percent < 100.0).then_some(percent)
+}
+
+/// Reads a relation or routine from a row with `table_schema`, `table_name` and `table_type`.
+fn table_of_row(row: &PgRow) -> anyhow::Result<Table> {
+    let table_type: String = row.try_get("table_type")?;
+    Ok(Table {
+        name: row.try_get("table_name")?,
+        create_time: None,
+        update_time: None,
+        engine: None,
+        schema: row.try_get("table_schema")?,
+        kind: match table_type.as_str() {
+            "VIEW" => TableKind::View,
+            "MATERIALIZED VIEW" => TableKind::MaterializedView,
+            "FUNCTION" => TableKind::Function,
+            "PROCEDURE" => TableKind::Procedure,
+            "SEQUENCE" => TableKind::Sequence,
+            _ => TableKind::Table,
+        },
+        partition_of: None,
+    })
+}
+
+/// A column as it is written in `CREATE TABLE`.
+struct ColumnDefinition {
+    name: String,
+    r#type: String,
+    not_null: bool,
+    default: Option<String>,
+    /// `ALWAYS` or `BY DEFAULT` for an identity column.
+    identity: Option<String>,
+    /// The expression of a generated column.
+    generation: Option<String>,
+}
+
+fn create_table(
+    qualified: &str,
+    unlogged: bool,
+    columns: &[ColumnDefinition],
+    constraints: &[(String, String)],
+) -> String {
+    let mut lines = columns
+        .iter()
+        .map(|column| {
+            let mut line = format!(
+                "    {} {}",
+                quote_identifier(Dialect::Postgres, &column.name),
+                column.r#type
+            );
+            match (&column.identity, &column.generation, &column.default) {
+                (Some(identity), _, _) if !identity.is_empty() => {
+                    line.push_str(&format!(" GENERATED {} AS IDENTITY", identity))
+                }
+                (_, Some(expression), _) if !expression.is_empty() => {
+                    line.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", expression))
+                }
+                (_, _, Some(default)) => line.push_str(&format!(" DEFAULT {}", default)),
+                _ => (),
+            }
+            if column.not_null {
+                line.push_str(" NOT NULL");
+            }
+            line
+        })
+        .collect::<Vec<String>>();
+    lines.extend(constraints.iter().map(|(name, definition)| {
+        format!(
+            "    CONSTRAINT {} {}",
+            quote_identifier(Dialect::Postgres, name),
+            definition
+        )
+    }));
+    format!(
+        "CREATE {}TABLE {} (\n{}\n);",
+        if unlogged { "UNLOGGED " } else { "" },
+        qualified,
+        lines.join(",\n")
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{create_table, sample_percent, zone_config, ColumnDefinition};
+
+    #[test]
+    fn test_sample_percent() {
+        assert_eq!(sample_percent(1_000_000.0, 200), Some(0.04));
+        assert_eq!(sample_percent(300.0, 200), None);
+        assert_eq!(sample_percent(0.0, 200), None);
+        // a table which has never been analyzed
+        assert_eq!(sample_percent(-1.0, 200), None);
+    }
+
+    #[test]
+    fn test_create_table() {
+        let column = |name: &str, r#type: &str| ColumnDefinition {
+            name: name.to_string(),
+            r#type: r#type.to_string(),
+            not_null: false,
+            default: None,
+            identity: None,
+            generation: None,
+        };
+        let columns = [
+            ColumnDefinition {
+                not_null: true,
+                identity: Some("BY DEFAULT".to_string()),
+                ..column("id", "bigint")
+            },
+            ColumnDefinition {
+                default: Some("'new'::text".to_string()),
+                ..column("status", "text")
+            },
+            ColumnDefinition {
+                generation: Some("(price * 2)".to_string()),
+                ..column("double", "numeric(10,2)")
+            },
+        ];
+        assert_eq!(
+            create_table(
+                r#""shop"."orders""#,
+                false,
+                &columns,
+                &[("orders_pkey".to_string(), "PRIMARY KEY (id)".to_string())]
+            ),
+            [
+                r#"CREATE TABLE "shop"."orders" ("#,
+                r#"    "id" bigint GENERATED BY DEFAULT AS IDENTITY NOT NULL,"#,
+                r#"    "status" text DEFAULT 'new'::text,"#,
+                r#"    "double" numeric(10,2) GENERATED ALWAYS AS ((price * 2)) STORED,"#,
+                r#"    CONSTRAINT "orders_pkey" PRIMARY KEY (id)"#,
+                ");",
+            ]
+            .join("\n")
+        );
+        assert!(
+            create_table("t", true, &columns[..1], &[]).starts_with("CREATE UNLOGGED TABLE t (")
+        );
+    }
+
+    #[test]
+    fn test_zone_config() {
+        let raw = "ALTER RANGE default CONFIGURE ZONE USING
+\trange_min_bytes = 134217728,
+\tgc.ttlseconds = 14400,
+\tconstraints = '[]'";
+        assert_eq!(
+            zone_config(raw),
+            [
+                ("range_min_bytes", "134217728"),
+                ("gc.ttlseconds", "14400"),
+                ("constraints", "'[]'")
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+        );
+    }
+}
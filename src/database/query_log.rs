@@ -0,0 +1,356 @@
+use super::{
+    Capabilities, DatabaseRole, ExecuteResult, Ping, Pool, Relation, RowStream, SchemaObject,
+    ServerOverview, ServerSession, SessionSetting, StreamedRows, TableRow, Value,
+};
+use crate::tree::{Child, Database, Table};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// A line of the query log.
+#[derive(Debug, Serialize)]
+struct QueryLogEntry<'a> {
+    time: String,
+    connection: &'a str,
+    statement: &'a str,
+    duration_ms: u128,
+    /// The rows read or changed, or `None` if the statement failed.
+    rows: Option<u64>,
+    error: Option<String>,
+}
+
+/// Appends the statements run on a connection to a JSON Lines file, one object per statement
+/// with its duration, row count and error.
+#[derive(Debug, Clone)]
+pub struct QueryLog {
+    path: PathBuf,
+    connection: String,
+}
+
+impl QueryLog {
+    pub fn new(path: PathBuf, connection: String) -> Self {
+        Self { path, connection }
+    }
+
+    fn line(
+        &self,
+        statement: &str,
+        started: Instant,
+        result: Result<u64, &anyhow::Error>,
+    ) -> String {
+        let entry = QueryLogEntry {
+            time: chrono::Local::now().to_rfc3339(),
+            connection: &self.connection,
+            statement: statement.trim(),
+            duration_ms: started.elapsed().as_millis(),
+            rows: result.as_ref().ok().copied(),
+            error: result.err().map(|e| format!("{:#}", e)),
+        };
+        serde_json::to_string(&entry).unwrap_or_default()
+    }
+
+    fn write(&self, statement: &str, started: Instant, result: Result<u64, &anyhow::Error>) {
+        let line = self.line(statement, started, result);
+        // a log which cannot be written must not fail the statement
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+        {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Wraps a pool and writes the statements it executes, the streamed ones and the transactions
+/// to the query log.
+pub struct LoggedPool {
+    pool: Box<dyn Pool>,
+    log: QueryLog,
+}
+
+impl LoggedPool {
+    pub fn new(pool: Box<dyn Pool>, log: QueryLog) -> Self {
+        Self { pool, log }
+    }
+}
+
+#[async_trait]
+impl Pool for LoggedPool {
+    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+        let started = Instant::now();
+        let result = self.pool.execute(query).await;
+        let rows = match &result {
+            Ok(ExecuteResult::Read { rows, .. }) => Ok(rows.len() as u64),
+            Ok(ExecuteResult::Write { updated_rows }) => Ok(*updated_rows),
+            Err(e) => Err(e),
+        };
+        self.log.write(query, started, rows);
+        result
+    }
+
+    async fn execute_params(&self, query: &str, values: &[Value]) -> anyhow::Result<ExecuteResult> {
+        let started = Instant::now();
+        let result = self.pool.execute_params(query, values).await;
+        let rows = match &result {
+            Ok(ExecuteResult::Read { rows, .. }) => Ok(rows.len() as u64),
+            Ok(ExecuteResult::Write { updated_rows }) => Ok(*updated_rows),
+            Err(e) => Err(e),
+        };
+        self.log.write(query, started, rows);
+        result
+    }
+
+    /// Passes the rows on as they arrive and logs the statement once the last of them did.
+    fn stream(&self, query: &str) -> Option<RowStream> {
+        let mut stream = self.pool.stream(query)?;
+        let (sender, receiver) = mpsc::channel(16);
+        let log = self.log.clone();
+        let query = query.to_string();
+        let started = Instant::now();
+        tokio::spawn(async move {
+            let mut count = 0;
+            while let Some(message) = stream.recv().await {
+                let done = match &message {
+                    Ok(StreamedRows::Rows { rows, .. }) => {
+                        count += rows.len() as u64;
+                        false
+                    }
+                    Ok(StreamedRows::Done { .. }) => {
+                        log.write(&query, started, Ok(count));
+                        true
+                    }
+                    Err(e) => {
+                        log.write(&query, started, Err(e));
+                        true
+                    }
+                };
+                let sent = sender.send(message).await.is_ok();
+                if done {
+                    return;
+                }
+                // the query was stopped after these rows
+                if !sent {
+                    log.write(&query, started, Ok(count));
+                    return;
+                }
+            }
+        });
+        Some(receiver)
+    }
+
+    async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
+        self.pool.get_databases().await
+    }
+
+    async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>> {
+        self.pool.get_tables(database).await
+    }
+
+    async fn get_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        page: usize,
+        filter: Option<String>,
+        orders: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        self.pool
+            .get_records(database, table, page, filter, orders)
+            .await
+    }
+
+    async fn get_columns(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_columns(database, table).await
+    }
+
+    async fn get_sample(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        self.pool.get_sample(database, table, filter).await
+    }
+
+    async fn get_total_row_count(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<usize> {
+        self.pool.get_total_row_count(database, table, filter).await
+    }
+
+    async fn get_constraints(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_constraints(database, table).await
+    }
+
+    async fn get_foreign_keys(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_foreign_keys(database, table).await
+    }
+
+    async fn get_primary_key(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        self.pool.get_primary_key(database, table).await
+    }
+
+    async fn get_indexes(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_indexes(database, table).await
+    }
+
+    async fn get_definition(&self, database: &Database, table: &Table) -> anyhow::Result<String> {
+        self.pool.get_definition(database, table).await
+    }
+
+    async fn get_dependencies(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_dependencies(database, table).await
+    }
+
+    async fn get_triggers(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_triggers(database, table).await
+    }
+
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_statistics(database, table).await
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        self.pool.get_server_overview().await
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        self.pool.get_sessions().await
+    }
+
+    async fn get_privileges(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_privileges(database, table).await
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        self.pool.get_roles().await
+    }
+
+    async fn get_relations(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        self.pool.get_relations(database, table).await
+    }
+
+    async fn get_partitions(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_partitions(database, table).await
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        self.pool.get_objects().await
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        self.pool.get_session_settings().await
+    }
+
+    async fn begin(&self) -> anyhow::Result<()> {
+        let started = Instant::now();
+        let result = self.pool.begin().await;
+        self.log
+            .write("BEGIN", started, result.as_ref().map(|()| 0));
+        result
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        let started = Instant::now();
+        let result = self.pool.commit().await;
+        self.log
+            .write("COMMIT", started, result.as_ref().map(|()| 0));
+        result
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        let started = Instant::now();
+        let result = self.pool.rollback().await;
+        self.log
+            .write("ROLLBACK", started, result.as_ref().map(|()| 0));
+        result
+    }
+
+    async fn close(&self) {
+        self.pool.close().await
+    }
+
+    fn ping(&self) -> Ping {
+        self.pool.ping()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.pool.capabilities()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::QueryLog;
+    use std::time::Instant;
+
+    #[test]
+    fn test_line() {
+        let log = QueryLog::new("queries.jsonl".into(), "production".to_string());
+        let line: serde_json::Value =
+            serde_json::from_str(&log.line(" SELECT 1\n", Instant::now(), Ok(1))).unwrap();
+        assert_eq!(line["connection"], "production");
+        assert_eq!(line["statement"], "SELECT 1");
+        assert_eq!(line["rows"], 1);
+        assert!(line["error"].is_null());
+        assert!(line["duration_ms"].is_u64());
+
+        let error = anyhow::anyhow!("syntax error").context("failed to run the statement");
+        let line: serde_json::Value =
+            serde_json::from_str(&log.line("SELEC 1", Instant::now(), Err(&error))).unwrap();
+        assert!(line["rows"].is_null());
+        assert_eq!(line["error"], "failed to run the statement: syntax error");
+    }
+}
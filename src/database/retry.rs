@@ -0,0 +1,320 @@
+use super::script::is_side_effect_free;
+use super::{
+    is_transient, Capabilities, DatabaseRole, ExecuteResult, Ping, Pool, Relation, RowStream,
+    SchemaObject, ServerOverview, ServerSession, SessionSetting, TableRow, Value,
+};
+use crate::components::toast::ToastQueue;
+use crate::tree::{Child, Database, Table};
+use async_trait::async_trait;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const BASE_DELAY_MILLIS: u64 = 100;
+const MAX_JITTER_MILLIS: u64 = 100;
+
+/// Wraps a pool and retries read queries which failed with a transient error.
+pub struct RetryPool {
+    pool: Box<dyn Pool>,
+    retry_count: usize,
+    toast: ToastQueue,
+    /// Nothing is retried in a transaction, which is aborted by the error anyway.
+    in_transaction: AtomicBool,
+}
+
+impl RetryPool {
+    pub fn new(pool: Box<dyn Pool>, retry_count: usize, toast: ToastQueue) -> Self {
+        Self {
+            pool,
+            retry_count,
+            toast,
+            in_transaction: AtomicBool::new(false),
+        }
+    }
+
+    async fn retry<'a, T, F, Fut>(&'a self, name: &str, f: F) -> anyhow::Result<T>
+    where
+        F: Fn() -> Fut + Send,
+        Fut: Future<Output = anyhow::Result<T>> + Send + 'a,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Err(e)
+                    if attempt < self.retry_count
+                        && is_transient(&e)
+                        && !self.in_transaction.load(Ordering::SeqCst) =>
+                {
+                    attempt += 1;
+                    if let Ok(mut toast) = self.toast.lock() {
+                        toast.push(format!(
+                            "Retrying {} ({}/{}): {}",
+                            name, attempt, self.retry_count, e
+                        ));
+                    }
+                    tokio::time::sleep(backoff(attempt)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Exponential backoff with jitter so that concurrent clients do not retry in lockstep.
+fn backoff(attempt: usize) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(6) as u32;
+    Duration::from_millis(
+        BASE_DELAY_MILLIS * 2_u64.pow(exponent) + fastrand::u64(0..MAX_JITTER_MILLIS),
+    )
+}
+
+#[async_trait]
+impl Pool for RetryPool {
+    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+        // a timeout may come after the server ran the statement, so only those which change
+        // nothing are run again
+        if is_side_effect_free(query) {
+            self.retry("query", || self.pool.execute(query)).await
+        } else {
+            self.pool.execute(query).await
+        }
+    }
+
+    async fn execute_params(&self, query: &str, values: &[Value]) -> anyhow::Result<ExecuteResult> {
+        if is_side_effect_free(query) {
+            self.retry("query", || self.pool.execute_params(query, values))
+                .await
+        } else {
+            self.pool.execute_params(query, values).await
+        }
+    }
+
+    // rows which were already shown cannot be taken back, so a stream is never retried
+    fn stream(&self, query: &str) -> Option<RowStream> {
+        self.pool.stream(query)
+    }
+
+    async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
+        self.retry("databases", || self.pool.get_databases()).await
+    }
+
+    async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>> {
+        self.retry("tables", || self.pool.get_tables(database.clone()))
+            .await
+    }
+
+    async fn get_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        page: usize,
+        filter: Option<String>,
+        orders: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        self.retry("records", || {
+            self.pool
+                .get_records(database, table, page, filter.clone(), orders.clone())
+        })
+        .await
+    }
+
+    async fn get_columns(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("columns", || self.pool.get_columns(database, table))
+            .await
+    }
+
+    async fn get_sample(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        self.retry("sample", || {
+            self.pool.get_sample(database, table, filter.clone())
+        })
+        .await
+    }
+
+    async fn get_total_row_count(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<usize> {
+        self.retry("row count", || {
+            self.pool
+                .get_total_row_count(database, table, filter.clone())
+        })
+        .await
+    }
+
+    async fn get_constraints(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("constraints", || self.pool.get_constraints(database, table))
+            .await
+    }
+
+    async fn get_foreign_keys(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("foreign keys", || {
+            self.pool.get_foreign_keys(database, table)
+        })
+        .await
+    }
+
+    async fn get_primary_key(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        self.retry("primary key", || self.pool.get_primary_key(database, table))
+            .await
+    }
+
+    async fn get_indexes(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("indexes", || self.pool.get_indexes(database, table))
+            .await
+    }
+
+    async fn get_definition(&self, database: &Database, table: &Table) -> anyhow::Result<String> {
+        self.retry("definition", || self.pool.get_definition(database, table))
+            .await
+    }
+
+    async fn get_dependencies(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("dependencies", || {
+            self.pool.get_dependencies(database, table)
+        })
+        .await
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        self.retry("objects", || self.pool.get_objects()).await
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        self.retry("session settings", || self.pool.get_session_settings())
+            .await
+    }
+
+    async fn get_triggers(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("triggers", || self.pool.get_triggers(database, table))
+            .await
+    }
+
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("statistics", || self.pool.get_statistics(database, table))
+            .await
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        self.retry("server overview", || self.pool.get_server_overview())
+            .await
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        self.retry("sessions", || self.pool.get_sessions()).await
+    }
+
+    async fn get_privileges(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("privileges", || self.pool.get_privileges(database, table))
+            .await
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        self.retry("roles", || self.pool.get_roles()).await
+    }
+
+    async fn get_relations(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        self.retry("relations", || self.pool.get_relations(database, table))
+            .await
+    }
+
+    async fn get_partitions(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.retry("partitions", || self.pool.get_partitions(database, table))
+            .await
+    }
+
+    // a transaction is bound to its connection, so it is never retried
+    async fn begin(&self) -> anyhow::Result<()> {
+        self.pool.begin().await?;
+        self.in_transaction.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    // the transaction is gone even if ending it failed
+    async fn commit(&self) -> anyhow::Result<()> {
+        self.in_transaction.store(false, Ordering::SeqCst);
+        self.pool.commit().await
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        self.in_transaction.store(false, Ordering::SeqCst);
+        self.pool.rollback().await
+    }
+
+    async fn close(&self) {
+        self.pool.close().await
+    }
+
+    fn ping(&self) -> Ping {
+        self.pool.ping()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.pool.capabilities()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::backoff;
+    use std::time::Duration;
+
+    #[test]
+    fn test_backoff() {
+        assert!(backoff(1) >= Duration::from_millis(100));
+        assert!(backoff(1) < Duration::from_millis(200));
+        assert!(backoff(3) >= Duration::from_millis(400));
+        assert!(backoff(3) < Duration::from_millis(500));
+        assert!(backoff(100) < Duration::from_millis(6500));
+    }
+}
@@ -0,0 +1,412 @@
+use std::ops::Range;
+
+/// Splits a script into statements at the semicolons which are outside of quotes, comments,
+/// dollar-quoted bodies and brackets. Empty statements are dropped.
+pub fn split_statements(script: &str) -> Vec<String> {
+    let chars = script.chars().collect::<Vec<char>>();
+    statement_ranges(&chars)
+        .into_iter()
+        .map(|range| chars[range].iter().collect::<String>().trim().to_string())
+        .filter(|statement| !is_blank(statement))
+        .collect()
+}
+
+/// Returns the statement of a script which the cursor, a character index, is in. The cursor
+/// right after a semicolon, or on blank lines after the last statement, is taken to be in the
+/// statement before.
+pub fn statement_at(script: &str, cursor: usize) -> Option<String> {
+    let chars = script.chars().collect::<Vec<char>>();
+    let ranges = statement_ranges(&chars);
+    let mut index = ranges
+        .iter()
+        .position(|range| cursor <= range.end)
+        .unwrap_or(ranges.len() - 1);
+    let before = &chars[ranges[index].start..cursor.clamp(ranges[index].start, chars.len())];
+    if index > 0 && before.iter().all(|c| c.is_whitespace() && *c != '\n') {
+        index -= 1;
+    }
+    ranges[..=index]
+        .iter()
+        .rev()
+        .map(|range| chars[range.clone()].iter().collect::<String>())
+        .find(|statement| !is_blank(statement))
+        .map(|statement| statement.trim().to_string())
+}
+
+/// Returns where the statements of a script are, ending before their semicolons.
+fn statement_ranges(chars: &[char]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut index = 0;
+    while index < chars.len() {
+        if let Some(end) = quoted_end(chars, index) {
+            index = end + 1;
+            continue;
+        }
+        match chars[index] {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                ranges.push(start..index);
+                start = index + 1;
+            }
+            _ => (),
+        }
+        index += 1;
+    }
+    let end = chars.len();
+    ranges.push(start.min(end)..end);
+    ranges
+}
+
+/// Returns where the quoted text, comment or dollar-quoted body which starts at `index` ends,
+/// if one does.
+fn quoted_end(chars: &[char], mut index: usize) -> Option<usize> {
+    match chars[index] {
+        quote @ ('\'' | '"' | '`') => {
+            index += 1;
+            while index < chars.len() {
+                if chars[index] == '\\' && quote != '`' {
+                    index += 1;
+                } else if chars[index] == quote {
+                    break;
+                }
+                index += 1;
+            }
+        }
+        '-' if chars.get(index + 1) == Some(&'-') => {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+        }
+        '/' if chars.get(index + 1) == Some(&'*') => {
+            index += 2;
+            while index < chars.len()
+                && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/'))
+            {
+                index += 1;
+            }
+            index += 1;
+        }
+        '$' => {
+            // e.g. $$ ... $$ or $body$ ... $body$ in PostgreSQL
+            let length = chars[index + 1..]
+                .iter()
+                .position(|c| !(c.is_alphanumeric() || *c == '_'))
+                .filter(|length| chars[index + 1 + length] == '$')?;
+            let tag = &chars[index..index + length + 2];
+            index += tag.len();
+            while index < chars.len() && !chars[index..].starts_with(tag) {
+                index += 1;
+            }
+            index += tag.len() - 1;
+        }
+        _ => return None,
+    }
+    Some(index)
+}
+
+/// Returns the words of `statement` in upper case, leaving out quoted text, comments and
+/// what is in brackets, e.g. subqueries.
+fn keywords(statement: &str) -> Vec<String> {
+    words(statement, false)
+}
+
+/// Returns the words of `statement` in upper case, leaving out quoted text and comments, and
+/// what is in brackets unless `nested` is true.
+fn words(statement: &str, nested: bool) -> Vec<String> {
+    let chars = statement.chars().collect::<Vec<char>>();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    let mut index = 0;
+    while index < chars.len() {
+        let quoted = quoted_end(&chars, index);
+        let c = chars[index];
+        match c {
+            '(' | '[' if quoted.is_none() => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+        if quoted.is_none() && (nested || depth == 0) && (c.is_alphanumeric() || c == '_') {
+            word.extend(c.to_uppercase());
+        } else if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        index = quoted.unwrap_or(index) + 1;
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Returns what `statement` destroys if it is `DROP`, `TRUNCATE`, `ALTER`, or `DELETE` or
+/// `UPDATE` without `WHERE`, which the SQL editor asks about before running it.
+pub fn destructive(statement: &str) -> Option<&'static str> {
+    let keywords = keywords(statement);
+    let has_where = keywords.iter().any(|keyword| keyword == "WHERE");
+    match keywords.first()?.as_str() {
+        "DROP" => Some("drops an object"),
+        "TRUNCATE" => Some("empties a table"),
+        "ALTER" => Some("alters an object"),
+        "DELETE" if !has_where => Some("deletes every row of a table"),
+        "UPDATE" if !has_where => Some("updates every row of a table"),
+        _ => None,
+    }
+}
+
+/// Returns `statement` with `LIMIT limit` appended if it is a `SELECT` which has no `LIMIT`
+/// or `FETCH` of its own. Those which lock or write rows with `FOR UPDATE` or `INTO` are left
+/// alone.
+pub fn with_limit(statement: &str, limit: usize) -> Option<String> {
+    if !is_select(statement) {
+        return None;
+    }
+    let keywords = keywords(statement);
+    if keywords
+        .iter()
+        .any(|keyword| matches!(keyword.as_str(), "LIMIT" | "FETCH" | "FOR" | "INTO"))
+    {
+        return None;
+    }
+    let statement = statement.trim_end().trim_end_matches(';').trim_end();
+    // on a line of its own so that a comment at the end does not hide it
+    Some(format!("{}\nLIMIT {}", statement, limit))
+}
+
+/// A statement which starts or ends a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionControl {
+    Begin,
+    Commit,
+    Rollback,
+}
+
+/// Returns what `statement` does to the transaction, if it is `BEGIN`, `COMMIT` or `ROLLBACK`.
+/// These have to go through `Pool::begin` and friends so that the transaction keeps its
+/// connection.
+pub fn transaction_control(statement: &str) -> Option<TransactionControl> {
+    let statement = statement
+        .trim()
+        .trim_end_matches(';')
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_uppercase();
+    match statement.as_str() {
+        "BEGIN" | "BEGIN WORK" | "BEGIN TRANSACTION" | "START TRANSACTION" => {
+            Some(TransactionControl::Begin)
+        }
+        "COMMIT" | "COMMIT WORK" | "COMMIT TRANSACTION" | "END" => Some(TransactionControl::Commit),
+        "ROLLBACK" | "ROLLBACK WORK" | "ROLLBACK TRANSACTION" | "ABORT" => {
+            Some(TransactionControl::Rollback)
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if `statement` has nothing but whitespace and comments.
+fn is_blank(statement: &str) -> bool {
+    skip_comments(statement).is_empty()
+}
+
+/// Returns true if `statement` returns rows, even if it starts with comments like a query tag.
+pub fn is_select(statement: &str) -> bool {
+    skip_comments(statement)
+        .to_uppercase()
+        .starts_with("SELECT")
+}
+
+/// Functions which change something even when they are called from a `SELECT`.
+const FUNCTIONS_WITH_SIDE_EFFECTS: &[&str] = &[
+    "NEXTVAL",
+    "SETVAL",
+    "SET_CONFIG",
+    "PG_TERMINATE_BACKEND",
+    "PG_CANCEL_BACKEND",
+    "PG_RELOAD_CONF",
+    "PG_NOTIFY",
+    "PG_ADVISORY_LOCK",
+    "PG_ADVISORY_XACT_LOCK",
+    "PG_TRY_ADVISORY_LOCK",
+    "PG_TRY_ADVISORY_XACT_LOCK",
+    "PG_ADVISORY_UNLOCK",
+    "LO_IMPORT",
+    "LO_EXPORT",
+    "LO_UNLINK",
+    "DBLINK_EXEC",
+    "GET_LOCK",
+    "RELEASE_LOCK",
+];
+
+/// Returns true if running `statement` twice has the same effect as running it once: a
+/// `SELECT` which neither locks rows with `FOR UPDATE`/`FOR SHARE`, writes with `INTO`, nor
+/// calls a function known to change something, e.g. `nextval`.
+pub fn is_side_effect_free(statement: &str) -> bool {
+    is_select(statement)
+        && !words(statement, true).iter().any(|word| {
+            matches!(word.as_str(), "FOR" | "INTO")
+                || FUNCTIONS_WITH_SIDE_EFFECTS.contains(&word.as_str())
+        })
+}
+
+/// Returns `statement` without the whitespace and comments it starts with.
+pub(super) fn skip_comments(statement: &str) -> &str {
+    let mut rest = statement.trim_start();
+    loop {
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            return rest;
+        }
+        rest = rest.trim_start();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        destructive, is_select, is_side_effect_free, split_statements, statement_at,
+        transaction_control, with_limit, TransactionControl,
+    };
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(
+            split_statements("CREATE TABLE a (id int);\nINSERT INTO a VALUES (1);\n"),
+            vec!["CREATE TABLE a (id int)", "INSERT INTO a VALUES (1)"]
+        );
+        assert_eq!(split_statements("SELECT 1"), vec!["SELECT 1"]);
+        assert_eq!(split_statements(" ; -- nothing\n;"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_split_statements_ignores_quoted_semicolons() {
+        assert_eq!(
+            split_statements(
+                "INSERT INTO a VALUES ('x;y', \"z;\", 'it\\'s;');\n-- a;b\nSELECT `c;d` /* ; */ FROM a"
+            ),
+            vec![
+                "INSERT INTO a VALUES ('x;y', \"z;\", 'it\\'s;')",
+                "-- a;b\nSELECT `c;d` /* ; */ FROM a"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_statements_ignores_bracketed_semicolons() {
+        assert_eq!(
+            split_statements("SELECT f(';', (1; 2)); SELECT a[1;2]"),
+            vec!["SELECT f(';', (1; 2))", "SELECT a[1;2]"]
+        );
+    }
+
+    #[test]
+    fn test_statement_at() {
+        let script = "SELECT 1; -- one\nSELECT ';'\n  FROM a;\n\n";
+        assert_eq!(statement_at(script, 3), Some("SELECT 1".to_string()));
+        // right after the semicolon
+        assert_eq!(statement_at(script, 9), Some("SELECT 1".to_string()));
+        assert_eq!(
+            statement_at(script, 20),
+            Some("-- one\nSELECT ';'\n  FROM a".to_string())
+        );
+        // on the blank lines at the end
+        assert_eq!(
+            statement_at(script, script.len()),
+            Some("-- one\nSELECT ';'\n  FROM a".to_string())
+        );
+        assert_eq!(statement_at(" ; ", 1), None);
+    }
+
+    #[test]
+    fn test_destructive() {
+        assert_eq!(destructive("drop table a"), Some("drops an object"));
+        assert_eq!(
+            destructive("/* tag */ DELETE FROM a"),
+            Some("deletes every row of a table")
+        );
+        assert_eq!(destructive("DELETE FROM a WHERE id = 1"), None);
+        assert_eq!(
+            destructive("UPDATE a SET b = 'where'"),
+            Some("updates every row of a table")
+        );
+        assert_eq!(destructive("SELECT 'DROP'"), None);
+        assert_eq!(destructive("-- DROP\nINSERT INTO a VALUES (1)"), None);
+    }
+
+    #[test]
+    fn test_with_limit() {
+        assert_eq!(
+            with_limit("SELECT * FROM a -- all;\n;", 200),
+            Some("SELECT * FROM a -- all;\nLIMIT 200".to_string())
+        );
+        assert_eq!(
+            with_limit("SELECT * FROM (SELECT * FROM a LIMIT 5) b", 10),
+            Some("SELECT * FROM (SELECT * FROM a LIMIT 5) b\nLIMIT 10".to_string())
+        );
+        assert_eq!(with_limit("select * from a limit 5", 10), None);
+        assert_eq!(with_limit("SELECT * FROM a FOR UPDATE", 10), None);
+        assert!(with_limit("SELECT 'limit' FROM a", 10).is_some());
+        assert_eq!(with_limit("DELETE FROM a", 10), None);
+    }
+
+    #[test]
+    fn test_is_select() {
+        assert!(is_select("select 1"));
+        assert!(is_select("/* zhobo user=jane */ SELECT 1"));
+        assert!(is_select("-- a\n  SELECT 1"));
+        assert!(!is_select("/* SELECT */ DELETE FROM a"));
+    }
+
+    #[test]
+    fn test_is_side_effect_free() {
+        assert!(is_side_effect_free(
+            "SELECT * FROM a WHERE b = 'for update'"
+        ));
+        assert!(is_side_effect_free("select count(*) from (select 1) t"));
+        assert!(!is_side_effect_free("SELECT nextval('a_id_seq')"));
+        assert!(!is_side_effect_free("select setval('a_id_seq', 1)"));
+        assert!(!is_side_effect_free("SELECT * FROM a FOR UPDATE"));
+        assert!(!is_side_effect_free(
+            "SELECT * FROM (SELECT * FROM a FOR SHARE) t"
+        ));
+        assert!(!is_side_effect_free("SELECT pg_terminate_backend(42)"));
+        assert!(!is_side_effect_free("SELECT * INTO b FROM a"));
+        assert!(!is_side_effect_free("DELETE FROM a"));
+    }
+
+    #[test]
+    fn test_transaction_control() {
+        assert_eq!(
+            transaction_control("start  transaction;"),
+            Some(TransactionControl::Begin)
+        );
+        assert_eq!(
+            transaction_control(" COMMIT "),
+            Some(TransactionControl::Commit)
+        );
+        assert_eq!(
+            transaction_control("rollback"),
+            Some(TransactionControl::Rollback)
+        );
+        assert_eq!(transaction_control("ROLLBACK TO SAVEPOINT a"), None);
+    }
+
+    #[test]
+    fn test_split_statements_with_dollar_quotes() {
+        assert_eq!(
+            split_statements(
+                "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ LANGUAGE plpgsql; SELECT $1"
+            ),
+            vec![
+                "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ LANGUAGE plpgsql",
+                "SELECT $1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use super::Value;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json::json;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+static SPILL_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// The rows of a result over the memory budget, written to a temporary file as JSON lines
+/// and read back a page at a time as they are scrolled to. The file is removed when this is
+/// dropped.
+#[derive(Debug)]
+pub struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    reader: Option<BufReader<File>>,
+    written: usize,
+    read: usize,
+}
+
+impl SpillFile {
+    /// Creates a new file which only the user can read. The name is predictable, so a file
+    /// or a link which is already there is never opened; the next name is tried instead.
+    pub fn create() -> Result<Self> {
+        const ATTEMPTS: usize = 100;
+        for _ in 0..ATTEMPTS {
+            let path = std::env::temp_dir().join(format!(
+                "zhobo-rows-{}-{}.jsonl",
+                std::process::id(),
+                SPILL_FILES.fetch_add(1, Ordering::Relaxed)
+            ));
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            match options.open(&path) {
+                Ok(file) => {
+                    return Ok(Self {
+                        path,
+                        writer: BufWriter::new(file),
+                        reader: None,
+                        written: 0,
+                        read: 0,
+                    })
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to create {}", path.display()))
+                }
+            }
+        }
+        bail!(
+            "Failed to create a spill file in {}",
+            std::env::temp_dir().display()
+        )
+    }
+
+    pub fn push(&mut self, row: &[Value]) -> Result<()> {
+        let line = serde_json::Value::Array(row.iter().map(encode).collect());
+        serde_json::to_writer(&mut self.writer, &line)?;
+        self.writer.write_all(b"\n")?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Returns the number of rows which were not read back yet.
+    pub fn remaining(&self) -> usize {
+        self.written - self.read
+    }
+
+    /// Reads back the next `count` rows.
+    pub fn read(&mut self, count: usize) -> Result<Vec<Vec<Value>>> {
+        self.writer.flush()?;
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => self
+                .reader
+                .insert(BufReader::new(File::open(&self.path).with_context(
+                    || format!("Failed to open {}", self.path.display()),
+                )?)),
+        };
+        let mut rows = Vec::new();
+        let mut line = String::new();
+        while rows.len() < count && self.read < self.written {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let values: Vec<serde_json::Value> = serde_json::from_str(&line)?;
+            rows.push(values.iter().map(decode).collect::<Result<_>>()?);
+            self.read += 1;
+        }
+        Ok(rows)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Writes a value with a tag of its type, so that it is read back as the same type.
+fn encode(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(value) => json!(["b", value]),
+        Value::Int(value) => json!(["i", value]),
+        Value::UInt(value) => json!(["u", value]),
+        // as text, since JSON has no NaN or infinity
+        Value::Float(value) => json!(["f", value.to_string()]),
+        Value::Decimal(value) => json!(["d", value.to_string()]),
+        Value::Text(value) => json!(["s", value]),
+        Value::Bytes(value) => json!(["x", value]),
+        Value::Json(value) => json!(["j", value]),
+        Value::Date(value) => json!(["D", value.to_string()]),
+        Value::Time(value) => json!(["T", value.to_string()]),
+        Value::Timestamp(value) => json!(["S", value.format(TIMESTAMP_FORMAT).to_string()]),
+        Value::TimestampTz(value) => json!(["Z", value.to_rfc3339()]),
+        Value::Array(values) => json!(["a", values.iter().map(encode).collect::<Vec<_>>()]),
+    }
+}
+
+fn decode(value: &serde_json::Value) -> Result<Value> {
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+    let (tag, payload) = match value.as_array().map(Vec::as_slice) {
+        Some([tag, payload]) => (tag.as_str().unwrap_or_default(), payload),
+        _ => bail!("Unexpected value in the spill file: {}", value),
+    };
+    let text = || payload.as_str().context("Expected text in the spill file");
+    Ok(match tag {
+        "b" => Value::Bool(
+            payload
+                .as_bool()
+                .context("Expected a bool in the spill file")?,
+        ),
+        "i" => Value::Int(
+            payload
+                .as_i64()
+                .context("Expected an integer in the spill file")?,
+        ),
+        "u" => Value::UInt(
+            payload
+                .as_u64()
+                .context("Expected an integer in the spill file")?,
+        ),
+        "f" => Value::Float(text()?.parse()?),
+        "d" => Value::Decimal(text()?.parse()?),
+        "s" => Value::Text(text()?.to_string()),
+        "x" => Value::Bytes(serde_json::from_value(payload.clone())?),
+        "j" => Value::Json(payload.clone()),
+        "D" => Value::Date(text()?.parse()?),
+        "T" => Value::Time(text()?.parse()?),
+        "S" => Value::Timestamp(NaiveDateTime::parse_from_str(text()?, TIMESTAMP_FORMAT)?),
+        "Z" => Value::TimestampTz(DateTime::parse_from_rfc3339(text()?)?.with_timezone(&Utc)),
+        "a" => Value::Array(
+            payload
+                .as_array()
+                .context("Expected an array in the spill file")?
+                .iter()
+                .map(decode)
+                .collect::<Result<_>>()?,
+        ),
+        _ => bail!("Unknown value type in the spill file: {}", tag),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SpillFile, Value};
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn test_spill_and_read_back() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let timestamp = date.and_hms_micro_opt(12, 30, 5, 250).unwrap();
+        let row = vec![
+            Value::Null,
+            Value::Bool(true),
+            Value::Int(-1),
+            Value::UInt(u64::MAX),
+            Value::Float(f64::INFINITY),
+            Value::Decimal("1.50".parse().unwrap()),
+            Value::Text("a\nb".to_string()),
+            Value::Bytes(vec![0, 255]),
+            Value::Json(serde_json::json!({"a": [1]})),
+            Value::Date(date),
+            Value::Time(timestamp.time()),
+            Value::Timestamp(timestamp),
+            Value::TimestampTz(Utc.from_utc_datetime(&timestamp)),
+            Value::Array(vec![Value::Int(1), Value::Null]),
+        ];
+        let mut spill = SpillFile::create().unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&spill.path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        for _ in 0..3 {
+            spill.push(&row).unwrap();
+        }
+        assert_eq!(spill.read(2).unwrap(), vec![row.clone(), row.clone()]);
+        assert_eq!(spill.remaining(), 1);
+        assert_eq!(spill.read(2).unwrap(), vec![row]);
+        assert!(spill.read(2).unwrap().is_empty());
+
+        let path = spill.path.clone();
+        drop(spill);
+        assert!(!path.exists());
+    }
+}
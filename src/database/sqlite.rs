@@ -1,17 +1,111 @@
-use crate::get_or_null;
-
-use super::{ExecuteResult, Pool, TableRow};
-use crate::tree::{Child, Database, Table};
+use super::script::is_select;
+use super::{
+    check_setting_name, format_bytes, Capabilities, DatabaseRole, Dependency, Dialect,
+    ExecuteResult, MemoryBudget, Ping, Pool, QueryParams, Relation, RowBuffer, RowSender,
+    RowStream, SchemaObject, Server, ServerOverview, ServerSession, SessionSetting, Statistic,
+    TableRow, TransactionSlot, Trigger, Value,
+};
+use crate::tree::{Child, Database, Table, TableKind};
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
 use futures::TryStreamExt;
-use sqlx::sqlite::{SqliteColumn, SqlitePoolOptions, SqliteRow};
+use sqlx::sqlite::{SqliteColumn, SqliteConnectOptions, SqlitePoolOptions, SqliteRow};
 use sqlx::{Column as _, Row as _, TypeInfo as _};
+use std::collections::BTreeMap;
+use std::str::FromStr;
 use std::time::Duration;
 
+/// Reads the schema once, because SQLite does not read the file until the first query and an
+/// encrypted one would only fail there.
+async fn check_readable(pool: &sqlx::sqlite::SqlitePool, encrypted: bool) -> anyhow::Result<()> {
+    if encrypted
+        && sqlx::query("PRAGMA cipher_version")
+            .fetch_optional(pool)
+            .await?
+            .is_none()
+    {
+        anyhow::bail!(
+            "the connection has a key, but zhobo is built without SQLCipher. Install it with `--features sqlcipher`"
+        );
+    }
+    sqlx::query("SELECT count(*) FROM sqlite_master")
+        .execute(pool)
+        .await
+        .map_err(|e| explain_open_error(e, encrypted))?;
+    Ok(())
+}
+
+/// SQLite only says "file is not a database" for an encrypted file or a wrong key.
+fn explain_open_error(error: sqlx::Error, encrypted: bool) -> anyhow::Error {
+    let not_a_database = matches!(
+        &error,
+        sqlx::Error::Database(e) if e.message().contains("file is not a database")
+    );
+    match (not_a_database, encrypted) {
+        (true, true) => anyhow::anyhow!("the key cannot decrypt the database"),
+        (true, false) => anyhow::anyhow!(
+            "the file is not a SQLite database. Set `key` in the connection if it is encrypted with SQLCipher"
+        ),
+        (false, _) => error.into(),
+    }
+}
+
+/// The pragmas shown in the session panel and the values they can be switched to.
+pub(super) const SESSION_SETTINGS: [(&str, &[&str]); 2] = [
+    ("foreign_keys", &["0", "1"]),
+    ("recursive_triggers", &["0", "1"]),
+];
+
+/// The bytes the pages of a table take, from the dbstat virtual table.
+pub(super) const DATA_SIZE_QUERY: &str = "SELECT SUM(pgsize) FROM dbstat WHERE name = ?";
+
+/// The bytes the pages of the indexes of a table take.
+pub(super) const INDEX_SIZE_QUERY: &str = "
+SELECT SUM(d.pgsize) FROM dbstat d
+JOIN sqlite_master m ON m.name = d.name
+WHERE m.type = 'index' AND m.tbl_name = ?
+";
+
+/// The bytes the pages of the tables and their indexes take, the largest first.
+pub(super) const LARGEST_TABLES_QUERY: &str = "
+SELECT m.tbl_name, SUM(d.pgsize) AS size FROM dbstat d
+JOIN sqlite_master m ON m.name = d.name
+WHERE m.tbl_name NOT LIKE 'sqlite_%'
+GROUP BY m.tbl_name
+ORDER BY size DESC
+";
+
+/// The bytes the database file takes.
+pub(super) const DATABASE_SIZE_QUERY: &str =
+    "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()";
+
+/// The columns of the primary key of a table, in the order of the key.
+pub(super) const PRIMARY_KEY_QUERY: &str =
+    "SELECT name FROM pragma_table_info(?) WHERE pk > 0 ORDER BY pk";
+
+/// The foreign keys of a table and those which reference it, one row per column. SQLite does
+/// not name them, so a key is told by its number in its table; `to` is empty for a key which
+/// references the primary key without naming its columns.
+pub(super) const RELATIONS_QUERY: &str = "
+SELECT CAST(p.id AS TEXT), m.name, p.\"from\", p.\"table\", COALESCE(p.\"to\", '')
+FROM sqlite_master AS m JOIN pragma_foreign_key_list(m.name) AS p
+WHERE m.type = 'table' AND (m.name = ?1 OR p.\"table\" = ?1 COLLATE NOCASE)
+ORDER BY m.name, p.id, p.seq
+";
+
+/// The statements which create a table or a view, and then its indexes and triggers.
+pub(super) const DEFINITION_QUERY: &str = "
+SELECT sql || ';' FROM sqlite_master
+WHERE tbl_name = ? AND sql IS NOT NULL
+ORDER BY CASE type WHEN 'index' THEN 1 WHEN 'trigger' THEN 2 ELSE 0 END, name
+";
+
 pub struct SqlitePool {
     pool: sqlx::sqlite::SqlitePool,
     limit_size: usize,
+    memory_budget: MemoryBudget,
+    transaction: TransactionSlot<sqlx::Sqlite>,
+    capabilities: Capabilities,
 }
 
 impl SqlitePool {
@@ -19,21 +113,114 @@ impl SqlitePool {
         database_url: &str,
         limit_size: usize,
         timeout_second: u64,
+        memory_budget: MemoryBudget,
+        session: &BTreeMap<String, String>,
+        key: Option<&str>,
     ) -> anyhow::Result<Self> {
+        for name in session.keys() {
+            check_setting_name(name)?;
+        }
+        let session = session.clone();
+        let mut options = SqliteConnectOptions::from_str(database_url)?;
+        if let Some(key) = key {
+            // sqlx runs the key pragma before the others, as SQLCipher needs
+            options = options.pragma("key", format!("'{}'", key.replace('\'', "''")));
+        }
+        let pool = SqlitePoolOptions::new()
+            .acquire_timeout(Duration::from_secs(timeout_second))
+            .after_connect(move |connection, _| {
+                let session = session.clone();
+                Box::pin(async move {
+                    for (name, value) in session {
+                        // a pragma does not take parameters
+                        sqlx::query(&format!(
+                            "PRAGMA {} = '{}'",
+                            name,
+                            value.replace('\'', "''")
+                        ))
+                        .execute(&mut *connection)
+                        .await?;
+                    }
+                    Ok(())
+                })
+            })
+            .connect_with(options)
+            .await
+            .map_err(|e| explain_open_error(e, key.is_some()))?;
+        check_readable(&pool, key.is_some()).await?;
+        let capabilities = sqlx::query_scalar::<_, String>("SELECT sqlite_version()")
+            .fetch_one(&pool)
+            .await
+            .map(|version| Capabilities::new(Server::Sqlite, &version))
+            .unwrap_or_default();
         Ok(Self {
-            pool: SqlitePoolOptions::new()
-                .acquire_timeout(Duration::from_secs(timeout_second))
-                .connect(database_url)
-                .await?,
+            pool,
             limit_size,
+            memory_budget,
+            transaction: TransactionSlot::new(),
+            capabilities,
+        })
+    }
+
+    async fn execute_with<'e, E>(
+        &self,
+        executor: E,
+        query: &str,
+        values: &[Value],
+    ) -> anyhow::Result<ExecuteResult>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    {
+        let query = query.trim();
+        if is_select(query) {
+            let mut rows = bind_values(query, values).fetch(executor);
+            let mut headers = vec![];
+            let mut records = RowBuffer::new(self.memory_budget.bytes);
+            while let Some(row) = rows.try_next().await? {
+                headers = row
+                    .columns()
+                    .iter()
+                    .map(|column| column.name().to_string())
+                    .collect();
+                let mut new_row = vec![];
+                for column in row.columns() {
+                    new_row.push(convert_column_value(&row, column)?)
+                }
+                if !records.push(new_row) {
+                    break;
+                }
+            }
+            return Ok(ExecuteResult::Read {
+                headers,
+                truncated: records.truncated,
+                rows: records.rows,
+                database: Database {
+                    name: "-".to_string(),
+                    children: Vec::new(),
+                },
+                table: Box::new(Table {
+                    name: "-".to_string(),
+                    create_time: None,
+                    update_time: None,
+                    engine: None,
+                    schema: None,
+                    kind: TableKind::Table,
+                    partition_of: None,
+                }),
+            });
+        }
+
+        let result = bind_values(query, values).execute(executor).await?;
+        Ok(ExecuteResult::Write {
+            updated_rows: result.rows_affected(),
         })
     }
 }
 
 pub struct Constraint {
-    name: String,
-    column_name: String,
-    origin: String,
+    pub(super) name: String,
+    pub(super) column_name: String,
+    pub(super) origin: String,
 }
 
 impl TableRow for Constraint {
@@ -55,11 +242,11 @@ impl TableRow for Constraint {
 }
 
 pub struct Column {
-    name: Option<String>,
-    r#type: Option<String>,
-    null: Option<String>,
-    default: Option<String>,
-    comment: Option<String>,
+    pub(super) name: Option<String>,
+    pub(super) r#type: Option<String>,
+    pub(super) null: Option<String>,
+    pub(super) default: Option<String>,
+    pub(super) comment: Option<String>,
 }
 
 impl TableRow for Column {
@@ -95,9 +282,9 @@ impl TableRow for Column {
 }
 
 pub struct ForeignKey {
-    column_name: Option<String>,
-    ref_table: Option<String>,
-    ref_column: Option<String>,
+    pub(super) column_name: Option<String>,
+    pub(super) ref_table: Option<String>,
+    pub(super) ref_column: Option<String>,
 }
 
 impl TableRow for ForeignKey {
@@ -125,9 +312,9 @@ impl TableRow for ForeignKey {
 }
 
 pub struct Index {
-    name: Option<String>,
-    column_name: Option<String>,
-    r#type: Option<String>,
+    pub(super) name: Option<String>,
+    pub(super) column_name: Option<String>,
+    pub(super) r#type: Option<String>,
 }
 
 impl TableRow for Index {
@@ -154,47 +341,79 @@ impl TableRow for Index {
     }
 }
 
+/// Builds `query` with `values` bound to its placeholders, as numbers where they were typed
+/// as one.
+fn bind_values<'q>(
+    query: &'q str,
+    values: &[Value],
+) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+    values
+        .iter()
+        .fold(sqlx::query(query), |query, value| match value {
+            Value::Null => query.bind(None::<String>),
+            Value::Int(value) => query.bind(*value),
+            Value::Float(value) => query.bind(*value),
+            value => query.bind(value.to_string()),
+        })
+}
+
 #[async_trait]
 impl Pool for SqlitePool {
     async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
-        let query = query.trim();
-        if query.to_uppercase().starts_with("SELECT") {
-            let mut rows = sqlx::query(query).fetch(&self.pool);
-            let mut headers = vec![];
-            let mut records = vec![];
-            while let Some(row) = rows.try_next().await? {
-                headers = row
-                    .columns()
-                    .iter()
-                    .map(|column| column.name().to_string())
-                    .collect();
-                let mut new_row = vec![];
-                for column in row.columns() {
-                    new_row.push(convert_column_value_to_string(&row, column)?)
+        let mut transaction = self.transaction.lock().await;
+        let result = match transaction.as_mut() {
+            Some(transaction) => self.execute_with(&mut **transaction, query, &[]).await,
+            None => self.execute_with(&self.pool, query, &[]).await,
+        };
+        result.map_err(|e| self.capabilities.explain(query, e))
+    }
+
+    async fn execute_params(&self, query: &str, values: &[Value]) -> anyhow::Result<ExecuteResult> {
+        let (query, values) =
+            QueryParams::parse(query, Dialect::Sqlite).bind(Dialect::Sqlite, values)?;
+        let mut transaction = self.transaction.lock().await;
+        let result = match transaction.as_mut() {
+            Some(transaction) => self.execute_with(&mut **transaction, &query, &values).await,
+            None => self.execute_with(&self.pool, &query, &values).await,
+        };
+        result.map_err(|e| self.capabilities.explain(&query, e))
+    }
+
+    fn stream(&self, query: &str) -> Option<RowStream> {
+        let query = query.trim().to_string();
+        // a transaction holds its connection, which the task cannot borrow
+        if !is_select(&query) || self.transaction.is_open() {
+            return None;
+        }
+        let pool = self.pool.clone();
+        let (mut sender, stream) = RowSender::channel(self.memory_budget);
+        let capabilities = self.capabilities;
+        tokio::spawn(async move {
+            let result = async {
+                let mut rows = sqlx::query(&query).fetch(&pool);
+                while let Some(row) = rows.try_next().await? {
+                    let mut new_row = vec![];
+                    for column in row.columns() {
+                        new_row.push(convert_column_value(&row, column)?)
+                    }
+                    let headers = || {
+                        row.columns()
+                            .iter()
+                            .map(|column| column.name().to_string())
+                            .collect()
+                    };
+                    if !sender.push(new_row, headers).await {
+                        break;
+                    }
                 }
-                records.push(new_row)
+                Ok(())
             }
-            return Ok(ExecuteResult::Read {
-                headers,
-                rows: records,
-                database: Database {
-                    name: "-".to_string(),
-                    children: Vec::new(),
-                },
-                table: Table {
-                    name: "-".to_string(),
-                    create_time: None,
-                    update_time: None,
-                    engine: None,
-                    schema: None,
-                },
-            });
-        }
-
-        let result = sqlx::query(query).execute(&self.pool).await?;
-        Ok(ExecuteResult::Write {
-            updated_rows: result.rows_affected(),
-        })
+            .await;
+            sender
+                .finish(result.map_err(|e| capabilities.explain(&query, e)))
+                .await;
+        });
+        Some(stream)
     }
 
     async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
@@ -204,27 +423,31 @@ impl Pool for SqlitePool {
             .iter()
             .map(|table| table.get(0))
             .collect::<Vec<String>>();
-        let mut list = vec![];
-        for db in databases {
-            list.push(Database::new(
-                db.clone(),
-                self.get_tables(db.clone()).await?,
-            ))
-        }
-        Ok(list)
+        Ok(databases
+            .into_iter()
+            .map(|name| Database::new(name, Vec::new()))
+            .collect())
     }
 
     async fn get_tables(&self, _database: String) -> anyhow::Result<Vec<Child>> {
         let mut rows =
-            sqlx::query("SELECT name FROM sqlite_master WHERE type = 'table'").fetch(&self.pool);
+            sqlx::query("SELECT name, type FROM sqlite_master WHERE type IN ('table', 'view')")
+                .fetch(&self.pool);
         let mut tables = Vec::new();
         while let Some(row) = rows.try_next().await? {
+            let table_type: String = row.try_get("type")?;
             tables.push(Table {
                 name: row.try_get("name")?,
                 create_time: None,
                 update_time: None,
                 engine: None,
                 schema: None,
+                kind: if table_type == "view" {
+                    TableKind::View
+                } else {
+                    TableKind::Table
+                },
+                partition_of: None,
             })
         }
         Ok(tables.into_iter().map(|table| table.into()).collect())
@@ -234,10 +457,10 @@ impl Pool for SqlitePool {
         &self,
         _database: &Database,
         table: &Table,
-        page: u16,
+        page: usize,
         filter: Option<String>,
         orders: Option<String>,
-    ) -> anyhow::Result<(Vec<String>, Vec<Vec<String>>)> {
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
         let query = if let (Some(filter), Some(orders)) = (&filter, &orders) {
             format!(
                 "SELECT * FROM `{table}` WHERE {filter} {orders} LIMIT {page}, {limit}",
@@ -282,13 +505,28 @@ impl Pool for SqlitePool {
                 .collect();
             let mut new_row = vec![];
             for column in row.columns() {
-                new_row.push(convert_column_value_to_string(&row, column)?)
+                new_row.push(convert_column_value(&row, column)?)
             }
             records.push(new_row)
         }
         Ok((headers, records))
     }
 
+    async fn get_sample(
+        &self,
+        _database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        let query = format!(
+            "SELECT * FROM `{table}`{filter} ORDER BY RANDOM() LIMIT {limit}",
+            table = table.name,
+            filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter)),
+            limit = self.limit_size
+        );
+        self.execute(&query).await?.into_records()
+    }
+
     async fn get_total_row_count(
         &self,
         _database: &Database,
@@ -390,6 +628,17 @@ impl Pool for SqlitePool {
         Ok(foreign_keys)
     }
 
+    async fn get_primary_key(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        Ok(sqlx::query_scalar(PRIMARY_KEY_QUERY)
+            .bind(&table.name)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
     async fn get_indexes(
         &self,
         _database: &Database,
@@ -422,54 +671,277 @@ impl Pool for SqlitePool {
     }
 
     async fn get_definition(&self, _database: &Database, table: &Table) -> anyhow::Result<String> {
-        let query = sqlx::query("SELECT sql FROM sqlite_master WHERE type='table' AND name=?;")
-            .bind(&table.name);
-        let row = query.fetch_one(&self.pool).await?;
-        Ok(row.get::<String, usize>(0))
+        let statements: Vec<String> = sqlx::query_scalar(DEFINITION_QUERY)
+            .bind(&table.name)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(statements.join("\n\n"))
+    }
+    async fn get_dependencies(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT * FROM (
+            SELECT type AS kind, name, '' AS detail
+            FROM sqlite_master
+            WHERE type IN ('view', 'trigger')
+                AND name <> ?1
+                AND (tbl_name = ?1 OR sql LIKE '%' || ?1 || '%')
+            UNION ALL
+            SELECT 'foreign key', m.name || '.' || p.\"from\", 'references ' || p.\"to\"
+            FROM sqlite_master m
+            JOIN pragma_foreign_key_list(m.name) p
+            WHERE m.type = 'table' AND p.\"table\" = ?1
+        )
+        ORDER BY kind, name
+        ",
+        )
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut dependencies: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            dependencies.push(Box::new(Dependency {
+                kind: row.try_get("kind")?,
+                name: row.try_get("name")?,
+                detail: row.try_get("detail")?,
+            }))
+        }
+        Ok(dependencies)
     }
+
+    async fn get_triggers(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let mut rows = sqlx::query(
+            "SELECT name, sql FROM sqlite_master WHERE type = 'trigger' AND tbl_name = ? ORDER BY name",
+        )
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut triggers: Vec<Box<dyn TableRow>> = vec![];
+        while let Some(row) = rows.try_next().await? {
+            let sql: String = row.try_get("sql")?;
+            let (timing, event, statement) = parse_trigger(&sql);
+            triggers.push(Box::new(Trigger {
+                name: row.try_get("name")?,
+                timing,
+                event,
+                statement,
+            }))
+        }
+        Ok(triggers)
+    }
+
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        let rows = self.get_total_row_count(database, table, None).await?;
+        // dbstat is only there if SQLite was built with SQLITE_ENABLE_DBSTAT_VTAB
+        let size = |query: &'static str| async move {
+            sqlx::query_scalar::<_, Option<i64>>(query)
+                .bind(&table.name)
+                .fetch_one(&self.pool)
+                .await
+                .ok()
+                .flatten()
+                .map(|bytes| format_bytes(bytes.max(0) as u64))
+        };
+        Ok(Statistic::rows(vec![
+            ("rows (exact)", Some(rows.to_string())),
+            ("data size", size(DATA_SIZE_QUERY).await),
+            ("index size", size(INDEX_SIZE_QUERY).await),
+        ]))
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        let database_size: i64 = sqlx::query_scalar(DATABASE_SIZE_QUERY)
+            .fetch_one(&self.pool)
+            .await?;
+        let journal_mode: String = sqlx::query_scalar("SELECT * FROM pragma_journal_mode")
+            .fetch_one(&self.pool)
+            .await?;
+        // a file has neither an uptime nor connections, and dbstat may be missing
+        let largest_tables: Vec<(String, i64)> = sqlx::query_as(&format!(
+            "{} LIMIT {}",
+            LARGEST_TABLES_QUERY,
+            ServerOverview::LARGEST_TABLES
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .unwrap_or_default();
+        Ok(ServerOverview::new(
+            vec![
+                ("server version", Some(self.capabilities.to_string())),
+                (
+                    "database size",
+                    Some(format_bytes(database_size.max(0) as u64)),
+                ),
+                ("journal mode", Some(journal_mode)),
+            ],
+            largest_tables
+                .into_iter()
+                .map(|(name, size)| (name, size.max(0) as u64))
+                .collect(),
+        ))
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        anyhow::bail!("SQLite has no sessions of other clients to list")
+    }
+
+    async fn get_privileges(
+        &self,
+        _database: &Database,
+        _table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // SQLite has no privileges, whoever can open the file can change it
+        Ok(vec![])
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        anyhow::bail!("SQLite has no users or roles")
+    }
+
+    async fn get_relations(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        let rows: Vec<(String, String, String, String, String)> = sqlx::query_as(RELATIONS_QUERY)
+            .bind(&table.name)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(Relation::merge_columns(
+            rows.into_iter()
+                .map(|(name, table, column, ref_table, ref_column)| Relation {
+                    name,
+                    schema: None,
+                    table,
+                    columns: vec![column],
+                    ref_schema: None,
+                    ref_table,
+                    ref_columns: vec![ref_column],
+                })
+                .collect(),
+        ))
+    }
+
+    async fn get_partitions(
+        &self,
+        _database: &Database,
+        _table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        // SQLite does not partition tables
+        Ok(vec![])
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT type AS kind, NULL AS table_name, name
+        FROM sqlite_master
+        WHERE type IN ('table', 'view')
+        UNION ALL
+        SELECT 'column', m.name, p.name
+        FROM sqlite_master AS m JOIN pragma_table_info(m.name) AS p
+        WHERE m.type IN ('table', 'view')
+        ",
+        )
+        .fetch(&self.pool);
+        let mut objects = vec![];
+        while let Some(row) = rows.try_next().await? {
+            objects.push(SchemaObject {
+                kind: row.try_get("kind")?,
+                database: "main".to_string(),
+                schema: None,
+                table: row.try_get("table_name")?,
+                name: row.try_get("name")?,
+            })
+        }
+        Ok(objects)
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        let mut rows = vec![];
+        for (name, _) in SESSION_SETTINGS {
+            let value: i64 = sqlx::query_scalar(&format!("SELECT * FROM pragma_{}", name))
+                .fetch_one(&self.pool)
+                .await?;
+            rows.push((name.to_string(), value.to_string()));
+        }
+        Ok(SessionSetting::from_rows(rows, &SESSION_SETTINGS))
+    }
+
+    async fn begin(&self) -> anyhow::Result<()> {
+        self.transaction.begin(&self.pool).await
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        self.transaction.commit().await
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        self.transaction.rollback().await
+    }
+
     async fn close(&self) {
         self.pool.close().await;
     }
+
+    fn ping(&self) -> Ping {
+        let pool = self.pool.clone();
+        Box::pin(async move {
+            sqlx::query("SELECT 1").execute(&pool).await?;
+            Ok(())
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
 }
 
-fn convert_column_value_to_string(
-    row: &SqliteRow,
-    column: &SqliteColumn,
-) -> anyhow::Result<String> {
+fn convert_column_value(row: &SqliteRow, column: &SqliteColumn) -> anyhow::Result<Value> {
     let column_name = column.name();
     if let Ok(value) = row.try_get(column_name) {
         let value: Option<String> = value;
-        Ok(value.unwrap_or_else(|| "NULL".to_string()))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<&str> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i16> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i32> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<i64> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<f32> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<f64> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<chrono::DateTime<chrono::Utc>> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<chrono::DateTime<chrono::Local>> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value.map(|v| v.with_timezone(&chrono::Utc))))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<NaiveDateTime> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<bool> = value;
-        Ok(get_or_null!(value))
+        Ok(Value::from(value))
     } else {
         anyhow::bail!(
             "column type not implemented: `{}` {}",
@@ -478,3 +950,175 @@ fn convert_column_value_to_string(
         )
     }
 }
+
+/// Splits the `CREATE TRIGGER` statement of a trigger into its timing, event and body, as
+/// sqlite_master only keeps the statement.
+pub(super) fn parse_trigger(sql: &str) -> (String, String, String) {
+    let upper = sql.to_ascii_uppercase();
+    let begin = upper.find("BEGIN").unwrap_or(upper.len());
+    let mut tokens = upper[..begin]
+        .split_whitespace()
+        .skip_while(|token| *token != "TRIGGER")
+        .skip(1)
+        .peekable();
+    if tokens.peek() == Some(&"IF") {
+        tokens.nth(2);
+    }
+    // the name, which might be a keyword itself
+    tokens.next();
+    let mut timing = "BEFORE".to_string();
+    let mut event = String::new();
+    for token in tokens {
+        match token {
+            "BEFORE" | "AFTER" => timing = token.to_string(),
+            "INSTEAD" => timing = "INSTEAD OF".to_string(),
+            "DELETE" | "INSERT" | "UPDATE" => {
+                event = token.to_string();
+                break;
+            }
+            _ => (),
+        }
+    }
+    let statement = match upper.rfind("END") {
+        Some(end) if end > begin => sql[begin + "BEGIN".len()..end].trim().to_string(),
+        _ => sql.to_string(),
+    };
+    (timing, event, statement)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_trigger, Pool, SqlitePool};
+    use crate::tree::{Database, Table, TableKind};
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn test_statistics() {
+        let path = std::env::temp_dir().join(format!("zhobo-statistics-{}.db", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let url = format!("sqlite://{}", path.display());
+        let pool = SqlitePool::new(&url, 200, 5, 1024, &BTreeMap::new(), None)
+            .await
+            .unwrap();
+        for statement in [
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)",
+            "CREATE INDEX t_name ON t (name)",
+            "INSERT INTO t (name) VALUES ('a'), ('b')",
+        ] {
+            pool.execute(&statement.to_string()).await.unwrap();
+        }
+        let table = Table {
+            name: "t".to_string(),
+            create_time: None,
+            update_time: None,
+            engine: None,
+            schema: None,
+            kind: TableKind::default(),
+            partition_of: None,
+        };
+        let statistics = pool
+            .get_statistics(&Database::new("main".to_string(), vec![]), &table)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.columns())
+            .collect::<Vec<_>>();
+        assert_eq!(statistics[0], vec!["rows (exact)", "2"]);
+        assert!(statistics
+            .iter()
+            .all(|row| row[0] != "data size" || row[1].ends_with("KiB")));
+        pool.close().await;
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_file() {
+        let path = std::env::temp_dir().join(format!("zhobo-encrypted-{}.db", std::process::id()));
+        std::fs::write(&path, [0x5a_u8; 4096]).unwrap();
+        let url = format!("sqlite://{}", path.display());
+
+        let error = SqlitePool::new(&url, 200, 5, 1024, &BTreeMap::new(), None)
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("Set `key`"), "{}", error);
+        if !cfg!(feature = "sqlcipher") {
+            let error = SqlitePool::new(&url, 200, 5, 1024, &BTreeMap::new(), Some("secret"))
+                .await
+                .err()
+                .unwrap();
+            assert!(error.to_string().contains("without SQLCipher"), "{}", error);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_trigger() {
+        assert_eq!(
+            parse_trigger(
+                "CREATE TRIGGER after AFTER UPDATE OF name ON users BEGIN\n  UPDATE users SET updated = 1 WHERE id = new.id;\nEND"
+            ),
+            (
+                "AFTER".to_string(),
+                "UPDATE".to_string(),
+                "UPDATE users SET updated = 1 WHERE id = new.id;".to_string()
+            )
+        );
+        assert_eq!(
+            parse_trigger(
+                "create trigger if not exists log_delete delete on users begin insert into log values (old.id); end"
+            ),
+            (
+                "BEFORE".to_string(),
+                "DELETE".to_string(),
+                "insert into log values (old.id);".to_string()
+            )
+        );
+        assert_eq!(
+            parse_trigger("CREATE TRIGGER v_insert INSTEAD OF INSERT ON v BEGIN SELECT 1; END").0,
+            "INSTEAD OF"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_definition() {
+        let path = std::env::temp_dir().join(format!("zhobo-definition-{}.db", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let url = format!("sqlite://{}", path.display());
+        let pool = SqlitePool::new(&url, 200, 5, 1024, &BTreeMap::new(), None)
+            .await
+            .unwrap();
+        for statement in [
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT UNIQUE)",
+            "CREATE INDEX t_name ON t (name)",
+            "CREATE TRIGGER t_delete AFTER DELETE ON t BEGIN SELECT 1; END",
+        ] {
+            pool.execute(&statement.to_string()).await.unwrap();
+        }
+        let table = Table {
+            name: "t".to_string(),
+            create_time: None,
+            update_time: None,
+            engine: None,
+            schema: None,
+            kind: TableKind::default(),
+            partition_of: None,
+        };
+        let definition = pool
+            .get_definition(&Database::new("main".to_string(), vec![]), &table)
+            .await
+            .unwrap();
+        // the index of the UNIQUE constraint has no statement of its own
+        assert_eq!(
+            definition,
+            [
+                "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT UNIQUE);",
+                "CREATE INDEX t_name ON t (name);",
+                "CREATE TRIGGER t_delete AFTER DELETE ON t BEGIN SELECT 1; END;",
+            ]
+            .join("\n\n")
+        );
+        pool.close().await;
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+use super::{
+    Capabilities, DatabaseRole, ExecuteResult, Ping, Pool, Relation, RowStream, SchemaObject,
+    ServerOverview, ServerSession, SessionSetting, TableRow, Value,
+};
+use crate::tree::{Child, Database, Table};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+
+/// The fields of the comment which is prepended to the statements run as SQL, e.g. from the
+/// editor, so that they can be told apart in the server logs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTag {
+    /// `None` until the user has been asked for the value.
+    fields: Vec<(String, Option<String>)>,
+}
+
+impl QueryTag {
+    /// `$NAME` values are read from the environment and `?` values are asked for.
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        Self {
+            fields: config
+                .iter()
+                .map(|(name, value)| {
+                    let value = match value.as_str() {
+                        "?" => None,
+                        value => Some(match value.strip_prefix('$') {
+                            Some(variable) => std::env::var(variable).unwrap_or_default(),
+                            None => value.to_string(),
+                        }),
+                    };
+                    (name.clone(), value)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the first field which has to be asked for.
+    pub fn pending_field(&self) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(_, value)| value.is_none())
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn set(&mut self, name: &str, value: String) {
+        if let Some((_, field)) = self.fields.iter_mut().find(|(n, _)| n == name) {
+            *field = Some(value);
+        }
+    }
+
+    /// Returns e.g. `/* zhobo user=jane ticket=OPS-123 */`, or `None` if no fields are set.
+    pub fn comment(&self) -> Option<String> {
+        if self.fields.is_empty() {
+            return None;
+        }
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, value)| {
+                // the comment must not end early
+                let value = value.as_deref().unwrap_or_default().replace("*/", "* /");
+                format!(" {}={}", name, value.replace(char::is_whitespace, "_"))
+            })
+            .collect::<String>();
+        Some(format!("/* zhobo{} */", fields))
+    }
+}
+
+/// Wraps a pool and prepends the query tag to the statements it executes. The other methods
+/// build their queries inside the driver, so they are passed on without the tag.
+pub struct TaggedPool {
+    pool: Box<dyn Pool>,
+    comment: String,
+}
+
+impl TaggedPool {
+    pub fn new(pool: Box<dyn Pool>, comment: String) -> Self {
+        Self { pool, comment }
+    }
+}
+
+#[async_trait]
+impl Pool for TaggedPool {
+    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+        self.pool
+            .execute(&format!("{} {}", self.comment, query.trim_start()))
+            .await
+    }
+
+    async fn execute_params(&self, query: &str, values: &[Value]) -> anyhow::Result<ExecuteResult> {
+        self.pool
+            .execute_params(&format!("{} {}", self.comment, query.trim_start()), values)
+            .await
+    }
+
+    fn stream(&self, query: &str) -> Option<RowStream> {
+        self.pool
+            .stream(&format!("{} {}", self.comment, query.trim_start()))
+    }
+
+    async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
+        self.pool.get_databases().await
+    }
+
+    async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>> {
+        self.pool.get_tables(database).await
+    }
+
+    async fn get_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        page: usize,
+        filter: Option<String>,
+        orders: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        self.pool
+            .get_records(database, table, page, filter, orders)
+            .await
+    }
+
+    async fn get_columns(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_columns(database, table).await
+    }
+
+    async fn get_sample(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        self.pool.get_sample(database, table, filter).await
+    }
+
+    async fn get_total_row_count(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<usize> {
+        self.pool.get_total_row_count(database, table, filter).await
+    }
+
+    async fn get_constraints(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_constraints(database, table).await
+    }
+
+    async fn get_foreign_keys(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_foreign_keys(database, table).await
+    }
+
+    async fn get_primary_key(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<String>> {
+        self.pool.get_primary_key(database, table).await
+    }
+
+    async fn get_indexes(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_indexes(database, table).await
+    }
+
+    async fn get_definition(&self, database: &Database, table: &Table) -> anyhow::Result<String> {
+        self.pool.get_definition(database, table).await
+    }
+
+    async fn get_dependencies(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_dependencies(database, table).await
+    }
+
+    async fn get_triggers(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_triggers(database, table).await
+    }
+
+    async fn get_statistics(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_statistics(database, table).await
+    }
+
+    async fn get_server_overview(&self) -> anyhow::Result<ServerOverview> {
+        self.pool.get_server_overview().await
+    }
+
+    async fn get_sessions(&self) -> anyhow::Result<Vec<ServerSession>> {
+        self.pool.get_sessions().await
+    }
+
+    async fn get_privileges(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_privileges(database, table).await
+    }
+
+    async fn get_roles(&self) -> anyhow::Result<Vec<DatabaseRole>> {
+        self.pool.get_roles().await
+    }
+
+    async fn get_relations(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Relation>> {
+        self.pool.get_relations(database, table).await
+    }
+
+    async fn get_partitions(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
+        self.pool.get_partitions(database, table).await
+    }
+
+    async fn get_objects(&self) -> anyhow::Result<Vec<SchemaObject>> {
+        self.pool.get_objects().await
+    }
+
+    async fn get_session_settings(&self) -> anyhow::Result<Vec<SessionSetting>> {
+        self.pool.get_session_settings().await
+    }
+
+    async fn begin(&self) -> anyhow::Result<()> {
+        self.pool.begin().await
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        self.pool.commit().await
+    }
+
+    async fn rollback(&self) -> anyhow::Result<()> {
+        self.pool.rollback().await
+    }
+
+    async fn close(&self) {
+        self.pool.close().await
+    }
+
+    fn ping(&self) -> Ping {
+        self.pool.ping()
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.pool.capabilities()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::QueryTag;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_query_tag() {
+        let config = BTreeMap::from([
+            ("user".to_string(), "jane".to_string()),
+            ("ticket".to_string(), "?".to_string()),
+        ]);
+        let mut tag = QueryTag::new(&config);
+        assert_eq!(tag.pending_field(), Some("ticket"));
+
+        tag.set("ticket", "OPS 123 */".to_string());
+        assert_eq!(tag.pending_field(), None);
+        assert_eq!(
+            tag.comment(),
+            Some("/* zhobo ticket=OPS_123_*_/ user=jane */".to_string())
+        );
+        assert_eq!(QueryTag::new(&BTreeMap::new()).comment(), None);
+    }
+}
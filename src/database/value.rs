@@ -0,0 +1,612 @@
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc,
+};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A value of a cell, converted from the type of the database column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Decimal(Decimal),
+    Text(String),
+    Bytes(Vec<u8>),
+    Json(serde_json::Value),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Timestamp(NaiveDateTime),
+    TimestampTz(DateTime<Utc>),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Converts the value for a JSON export. Decimals are written as strings so that they
+    /// keep their precision.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Null => serde_json::Value::Null,
+            Self::Bool(value) => (*value).into(),
+            Self::Int(value) => (*value).into(),
+            Self::UInt(value) => (*value).into(),
+            Self::Float(value) => serde_json::Number::from_f64(*value)
+                .map_or(serde_json::Value::Null, serde_json::Value::Number),
+            Self::Json(value) => value.clone(),
+            Self::Array(values) => values.iter().map(Self::to_json).collect(),
+            _ => self.to_string().into(),
+        }
+    }
+
+    /// Returns the approximate number of bytes the value takes in memory.
+    pub fn estimated_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + match self {
+                Self::Text(value) => value.capacity(),
+                Self::Bytes(value) => value.capacity(),
+                Self::Json(value) => value.to_string().len(),
+                Self::Array(values) => values.iter().map(Self::estimated_size).sum(),
+                _ => 0,
+            }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::Int(_) | Self::UInt(_) | Self::Float(_) | Self::Decimal(_)
+        )
+    }
+
+    pub fn is_temporal(&self) -> bool {
+        matches!(
+            self,
+            Self::Date(_) | Self::Time(_) | Self::Timestamp(_) | Self::TimestampTz(_)
+        )
+    }
+
+    /// Returns the value as an exact number if it is a number, or text or JSON holding one,
+    /// e.g. a number which SQLite stored as text.
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match self {
+            Self::Int(value) => Some(Decimal::from(*value)),
+            Self::UInt(value) => Some(Decimal::from(*value)),
+            Self::Float(value) => Decimal::from_f64(*value),
+            Self::Decimal(value) => Some(*value),
+            Self::Text(value) => parse_decimal(value.trim()),
+            Self::Json(serde_json::Value::Number(value)) => parse_decimal(&value.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Returns numbers and points in time on a linear scale, so that the distance between two
+    /// values can be measured.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(value) => Some(*value as f64),
+            Self::UInt(value) => Some(*value as f64),
+            Self::Float(value) => Some(*value),
+            Self::Decimal(value) => value.to_f64(),
+            Self::Date(value) => Some(f64::from(value.num_days_from_ce())),
+            Self::Time(value) => Some(f64::from(value.num_seconds_from_midnight())),
+            Self::Timestamp(value) => Some(value.and_utc().timestamp() as f64),
+            Self::TimestampTz(value) => Some(value.timestamp() as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of digits needed to show the fractional seconds: 0, 3, 6 or 9.
+    fn fraction_digits(&self) -> usize {
+        let nanosecond = match self {
+            Self::Time(value) => value.nanosecond(),
+            Self::Timestamp(value) => value.nanosecond(),
+            Self::TimestampTz(value) => value.nanosecond(),
+            _ => return 0,
+        } % 1_000_000_000;
+        if nanosecond == 0 {
+            0
+        } else if nanosecond % 1_000_000 == 0 {
+            3
+        } else if nanosecond % 1_000 == 0 {
+            6
+        } else {
+            9
+        }
+    }
+}
+
+/// The time zone which timestamps with a time zone are shown in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum DisplayTimeZone {
+    #[default]
+    Utc,
+    /// The time zone of the computer zhobo runs on.
+    Local,
+    /// An offset from UTC such as `+09:00`.
+    Offset(FixedOffset),
+}
+
+impl DisplayTimeZone {
+    /// Returns the point in time in this time zone, or `None` for UTC, which it already is in.
+    fn convert(&self, value: &DateTime<Utc>) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Self::Utc => None,
+            Self::Local => Some(value.with_timezone(&Local).fixed_offset()),
+            Self::Offset(offset) => Some(value.with_timezone(offset)),
+        }
+    }
+}
+
+impl TryFrom<String> for DisplayTimeZone {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "utc" => Ok(Self::Utc),
+            "local" => Ok(Self::Local),
+            _ => value.parse().map(Self::Offset).map_err(|_| {
+                format!(
+                    "invalid time zone `{}`, use \"utc\", \"local\" or an offset such as \"+09:00\"",
+                    value
+                )
+            }),
+        }
+    }
+}
+
+impl From<DisplayTimeZone> for String {
+    fn from(time_zone: DisplayTimeZone) -> Self {
+        match time_zone {
+            DisplayTimeZone::Utc => "utc".to_string(),
+            DisplayTimeZone::Local => "local".to_string(),
+            DisplayTimeZone::Offset(offset) => offset.to_string(),
+        }
+    }
+}
+
+/// How binary values are shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryFormat {
+    /// `\x00ff`, as PostgreSQL writes bytea.
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// How the values are shown in the cells of the tables. Copying and exporting the rows, and
+/// the SQL built from them, use the values as they are.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ValueFormat {
+    pub time_zone: DisplayTimeZone,
+    /// Groups the digits of the numbers by thousands with this character.
+    pub thousands_separator: Option<char>,
+    /// Rounds floating-point numbers to this many digits after the decimal point.
+    pub float_precision: Option<usize>,
+    pub binary: BinaryFormat,
+}
+
+impl ValueFormat {
+    pub fn display(&self, value: &Value) -> String {
+        match value {
+            Value::Int(value) => self.group_thousands(value.to_string()),
+            Value::UInt(value) => self.group_thousands(value.to_string()),
+            Value::Float(value) if value.is_finite() => {
+                self.group_thousands(match self.float_precision {
+                    Some(precision) => format!("{:.*}", precision, value),
+                    None => value.to_string(),
+                })
+            }
+            Value::Decimal(value) => self.group_thousands(value.to_string()),
+            Value::Bytes(value) if self.binary == BinaryFormat::Base64 => encode_base64(value),
+            Value::TimestampTz(value) => self
+                .time_zone
+                .convert(value)
+                .map_or_else(|| value.to_string(), |value| value.to_string()),
+            Value::Array(values) => values
+                .iter()
+                .map(|value| self.display(value))
+                .collect::<Vec<String>>()
+                .join(","),
+            value => value.to_string(),
+        }
+    }
+
+    /// Inserts the separator between the groups of three digits before the decimal point.
+    fn group_thousands(&self, number: String) -> String {
+        let separator = match self.thousands_separator {
+            Some(separator) => separator,
+            None => return number,
+        };
+        let (integer, fraction) = split_decimal(&number);
+        let (sign, digits) = match integer.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", integer),
+        };
+        let mut grouped = String::from(sign);
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index) % 3 == 0 {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            grouped.push('.');
+            grouped.push_str(fraction);
+        }
+        grouped
+    }
+}
+
+/// Encodes bytes as base64 with padding.
+fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |buffer, (index, byte)| {
+                buffer | u32::from(*byte) << (16 - 8 * index)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(char::from(
+                    ALPHABET[(buffer >> (18 - 6 * index) & 0x3f) as usize],
+                ));
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// How the cells of a column are lined up when every value in it is of the same kind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnFormat {
+    /// Right-aligned with the decimal points below each other.
+    Numeric {
+        integer_width: usize,
+        fraction_width: usize,
+    },
+    /// Fractional seconds padded to the same number of digits.
+    Temporal { fraction_digits: usize },
+}
+
+impl ColumnFormat {
+    /// Returns the format of a column, or `None` if it mixes kinds of values or is all NULL.
+    pub fn of<'a>(
+        values: impl Iterator<Item = &'a Value> + Clone,
+        value_format: &ValueFormat,
+    ) -> Option<Self> {
+        let mut non_null = values.clone().filter(|value| !value.is_null()).peekable();
+        non_null.peek()?;
+        if non_null.clone().all(Value::is_numeric) {
+            let (integer_width, fraction_width) =
+                values.fold((0, 0), |(integer_width, fraction_width), value| {
+                    let value = value_format.display(value);
+                    let (integer, fraction) = split_decimal(&value);
+                    (
+                        integer_width.max(integer.chars().count()),
+                        fraction_width.max(fraction.map_or(0, |f| f.chars().count())),
+                    )
+                });
+            Some(Self::Numeric {
+                integer_width,
+                fraction_width,
+            })
+        } else if non_null.clone().all(Value::is_temporal) {
+            Some(Self::Temporal {
+                fraction_digits: non_null.map(Value::fraction_digits).max().unwrap_or(0),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn format(&self, value: &Value, value_format: &ValueFormat) -> String {
+        match *self {
+            Self::Numeric {
+                integer_width,
+                fraction_width,
+            } => {
+                let value = value_format.display(value);
+                let (integer, fraction) = split_decimal(&value);
+                let fraction = match fraction {
+                    Some(fraction) => format!(".{:<width$}", fraction, width = fraction_width),
+                    None if fraction_width > 0 => " ".repeat(fraction_width + 1),
+                    None => String::new(),
+                };
+                format!("{:>width$}{}", integer, fraction, width = integer_width)
+            }
+            Self::Temporal { fraction_digits } => {
+                let fraction = if fraction_digits == 0 {
+                    String::new()
+                } else {
+                    format!("%.{}f", fraction_digits)
+                };
+                match value {
+                    Value::Time(value) => {
+                        value.format(&format!("%H:%M:%S{}", fraction)).to_string()
+                    }
+                    Value::Timestamp(value) => value
+                        .format(&format!("%Y-%m-%d %H:%M:%S{}", fraction))
+                        .to_string(),
+                    Value::TimestampTz(value) => {
+                        let format = format!("%Y-%m-%d %H:%M:%S{} %Z", fraction);
+                        match value_format.time_zone.convert(value) {
+                            Some(value) => value.format(&format).to_string(),
+                            None => value.format(&format).to_string(),
+                        }
+                    }
+                    value => value_format.display(value),
+                }
+            }
+        }
+    }
+}
+
+/// Splits a formatted number into the part before the decimal point and the part after it.
+fn split_decimal(value: &str) -> (&str, Option<&str>) {
+    match value.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (value, None),
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "NULL"),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Int(value) => write!(f, "{}", value),
+            Self::UInt(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::Decimal(value) => write!(f, "{}", value),
+            Self::Text(value) => write!(f, "{}", value),
+            Self::Bytes(value) => write!(
+                f,
+                "\\x{}",
+                value
+                    .iter()
+                    .map(|v| format!("{:02x}", v))
+                    .collect::<String>()
+            ),
+            Self::Json(value) => write!(f, "{}", value),
+            Self::Date(value) => write!(f, "{}", value),
+            Self::Time(value) => write!(f, "{}", value),
+            Self::Timestamp(value) => write!(f, "{}", value),
+            Self::TimestampTz(value) => write!(f, "{}", value),
+            Self::Array(values) => write!(
+                f,
+                "{}",
+                values
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($($type:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$type> for Value {
+                fn from(value: $type) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(
+    bool => Bool,
+    i8 => Int,
+    i16 => Int,
+    i32 => Int,
+    i64 => Int,
+    u8 => UInt,
+    u16 => UInt,
+    u32 => UInt,
+    u64 => UInt,
+    f32 => Float,
+    f64 => Float,
+    Decimal => Decimal,
+    String => Text,
+    &str => Text,
+    Vec<u8> => Bytes,
+    &[u8] => Bytes,
+    serde_json::Value => Json,
+    NaiveDate => Date,
+    NaiveTime => Time,
+    NaiveDateTime => Timestamp,
+    DateTime<Utc> => TimestampTz,
+);
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, |v| v.into())
+    }
+}
+
+/// Parses a number such as `-1.5` or `2e3`.
+fn parse_decimal(text: &str) -> Option<Decimal> {
+    text.parse()
+        .ok()
+        .or_else(|| Decimal::from_scientific(text).ok())
+}
+
+impl From<Vec<String>> for Value {
+    fn from(values: Vec<String>) -> Self {
+        Self::Array(values.into_iter().map(Self::Text).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BinaryFormat, ColumnFormat, DisplayTimeZone, Value, ValueFormat};
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Value::Null.to_string(), "NULL");
+        assert_eq!(Value::from(Some(1_i32)).to_string(), "1");
+        assert_eq!(Value::from(None::<i32>).to_string(), "NULL");
+        assert_eq!(Value::from(&[0_u8, 255][..]).to_string(), "\\x00ff");
+        assert_eq!(
+            Value::from(vec!["a".to_string(), "b".to_string()]).to_string(),
+            "a,b"
+        );
+    }
+
+    #[test]
+    fn test_numeric_column_format() {
+        let values = [
+            Value::Float(1.5),
+            Value::Int(-100),
+            Value::Null,
+            Value::Float(0.25),
+        ];
+        let format = ColumnFormat::of(values.iter(), &ValueFormat::default()).unwrap();
+        assert_eq!(
+            format,
+            ColumnFormat::Numeric {
+                integer_width: 4,
+                fraction_width: 2
+            }
+        );
+        assert_eq!(
+            values
+                .iter()
+                .map(|value| format.format(value, &ValueFormat::default()))
+                .collect::<Vec<String>>(),
+            ["   1.5 ", "-100   ", "NULL   ", "   0.25"]
+        );
+    }
+
+    #[test]
+    fn test_temporal_column_format() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let values = [
+            Value::Timestamp(date.and_hms_opt(3, 4, 5).unwrap()),
+            Value::Timestamp(date.and_hms_milli_opt(3, 4, 5, 500).unwrap()),
+        ];
+        let format = ColumnFormat::of(values.iter(), &ValueFormat::default()).unwrap();
+        assert_eq!(format, ColumnFormat::Temporal { fraction_digits: 3 });
+        assert_eq!(
+            format.format(&values[0], &ValueFormat::default()),
+            "2024-01-02 03:04:05.000"
+        );
+        assert_eq!(
+            format.format(&values[1], &ValueFormat::default()),
+            "2024-01-02 03:04:05.500"
+        );
+    }
+
+    #[test]
+    fn test_value_format() {
+        let format = ValueFormat {
+            time_zone: DisplayTimeZone::try_from("+09:00".to_string()).unwrap(),
+            thousands_separator: Some(','),
+            float_precision: Some(2),
+            binary: BinaryFormat::Base64,
+        };
+        assert_eq!(format.display(&Value::Int(-1234567)), "-1,234,567");
+        assert_eq!(format.display(&Value::UInt(123)), "123");
+        assert_eq!(format.display(&Value::Float(1234.5)), "1,234.50");
+        assert_eq!(format.display(&Value::Float(f64::NAN)), "NaN");
+        assert_eq!(format.display(&Value::from(&b"hello"[..])), "aGVsbG8=");
+        assert_eq!(format.display(&Value::from(&[0_u8, 255][..])), "AP8=");
+        let time = Utc.with_ymd_and_hms(2024, 1, 2, 20, 0, 0).unwrap();
+        assert_eq!(
+            format.display(&Value::TimestampTz(time)),
+            "2024-01-03 05:00:00 +09:00"
+        );
+        assert_eq!(
+            ValueFormat::default().display(&Value::TimestampTz(time)),
+            "2024-01-02 20:00:00 UTC"
+        );
+
+        // the cells of a column line up with the separators
+        let values = [Value::Int(1000), Value::Float(2.5)];
+        let column = ColumnFormat::of(values.iter(), &format).unwrap();
+        assert_eq!(column.format(&values[0], &format), "1,000   ");
+        assert_eq!(column.format(&values[1], &format), "    2.50");
+
+        assert!(DisplayTimeZone::try_from("Mars".to_string()).is_err());
+        assert_eq!(
+            DisplayTimeZone::try_from("Local".to_string()),
+            Ok(DisplayTimeZone::Local)
+        );
+    }
+
+    #[test]
+    fn test_as_f64() {
+        assert_eq!(Value::Int(-3).as_f64(), Some(-3.0));
+        assert_eq!(Value::Text("3".to_string()).as_f64(), None);
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        assert_eq!(
+            Value::Date(date.succ_opt().unwrap()).as_f64().unwrap()
+                - Value::Date(date).as_f64().unwrap(),
+            1.0
+        );
+        assert_eq!(
+            Value::Timestamp(date.and_hms_opt(0, 1, 0).unwrap())
+                .as_f64()
+                .unwrap()
+                - Value::Timestamp(date.and_hms_opt(0, 0, 0).unwrap())
+                    .as_f64()
+                    .unwrap(),
+            60.0
+        );
+    }
+
+    #[test]
+    fn test_as_decimal() {
+        assert_eq!(Value::Int(-3).as_decimal(), Some((-3).into()));
+        assert_eq!(Value::Float(0.5).as_decimal(), Some("0.5".parse().unwrap()));
+        assert_eq!(
+            Value::Text(" 1.25 ".to_string()).as_decimal(),
+            Some("1.25".parse().unwrap())
+        );
+        assert_eq!(
+            Value::Text("2e3".to_string()).as_decimal(),
+            Some(2000.into())
+        );
+        assert_eq!(
+            Value::Json(serde_json::json!(7)).as_decimal(),
+            Some(7.into())
+        );
+        assert_eq!(Value::Text("abc".to_string()).as_decimal(), None);
+        assert_eq!(Value::Text(String::new()).as_decimal(), None);
+        assert_eq!(Value::Float(f64::NAN).as_decimal(), None);
+        assert_eq!(Value::Bool(true).as_decimal(), None);
+    }
+
+    #[test]
+    fn test_mixed_column_format() {
+        assert_eq!(
+            ColumnFormat::of(
+                [Value::Int(1), Value::Text("a".to_string())].iter(),
+                &ValueFormat::default()
+            ),
+            None
+        );
+        assert_eq!(
+            ColumnFormat::of([Value::Null].iter(), &ValueFormat::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use crate::database::Relation;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// The format of an entity relationship diagram, which other tools render.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagramFormat {
+    /// Graphviz, e.g. `dot -Tsvg shop.dot > shop.svg`.
+    Dot,
+    /// A Mermaid `erDiagram`, which GitHub renders in Markdown.
+    Mermaid,
+}
+
+impl DiagramFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dot" => Some(Self::Dot),
+            "mermaid" => Some(Self::Mermaid),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::Mermaid => "mmd",
+        }
+    }
+}
+
+/// A table of a diagram, with the name and the type of each column.
+pub struct DiagramTable {
+    pub schema: Option<String>,
+    pub name: String,
+    pub columns: Vec<(String, String)>,
+    pub primary_key: Vec<String>,
+}
+
+impl DiagramTable {
+    fn id(&self) -> String {
+        table_id(&self.schema, &self.name)
+    }
+}
+
+fn table_id(schema: &Option<String>, name: &str) -> String {
+    match schema {
+        Some(schema) => format!("{}.{}", schema, name),
+        None => name.to_string(),
+    }
+}
+
+/// Writes the tables and the foreign keys between them as a diagram named `name`.
+pub fn format_diagram(
+    format: DiagramFormat,
+    name: &str,
+    tables: &[DiagramTable],
+    relations: &[Relation],
+) -> String {
+    match format {
+        DiagramFormat::Dot => dot(name, tables, relations),
+        DiagramFormat::Mermaid => mermaid(tables, relations),
+    }
+}
+
+/// Writes the diagram into `path`, or into a file named after `name` and the time, and returns
+/// the path.
+pub fn write_diagram_to_file(
+    format: DiagramFormat,
+    name: &str,
+    tables: &[DiagramTable],
+    relations: &[Relation],
+    path: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let path = path.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "{}-{}.{}",
+            name,
+            chrono::Local::now().format("%Y%m%d%H%M%S"),
+            format.extension()
+        ))
+    });
+    std::fs::write(&path, format_diagram(format, name, tables, relations))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Draws each table as an HTML-like table with a port per column, which the edges of the
+/// foreign keys start and end at.
+fn dot(name: &str, tables: &[DiagramTable], relations: &[Relation]) -> String {
+    let mut out = format!(
+        "digraph \"{}\" {{\n    rankdir=LR;\n    node [shape=plaintext];\n",
+        dot_string(name)
+    );
+    for table in tables {
+        let id = table.id();
+        out.push_str(&format!(
+            "    \"{}\" [label=<<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">\
+             <tr><td bgcolor=\"lightgrey\"><b>{}</b></td></tr>",
+            dot_string(&id),
+            html(&id)
+        ));
+        for (column, r#type) in &table.columns {
+            let key = if table.primary_key.contains(column) {
+                " (PK)"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<tr><td port=\"{}\" align=\"left\">{}: {}{}</td></tr>",
+                html(column),
+                html(column),
+                html(r#type),
+                key
+            ));
+        }
+        out.push_str("</table>>];\n");
+    }
+    for relation in relations {
+        out.push_str(&format!(
+            "    \"{}\":\"{}\" -> \"{}\":\"{}\" [label=\"{}\"];\n",
+            dot_string(&table_id(&relation.schema, &relation.table)),
+            dot_string(relation.columns.first().map_or("", String::as_str)),
+            dot_string(&table_id(&relation.ref_schema, &relation.ref_table)),
+            dot_string(relation.ref_columns.first().map_or("", String::as_str)),
+            dot_string(&relation.name)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Draws each table as an entity, a foreign key being many rows of its table to one of the
+/// referenced table.
+fn mermaid(tables: &[DiagramTable], relations: &[Relation]) -> String {
+    let mut out = "erDiagram\n".to_string();
+    for table in tables {
+        out.push_str(&format!("    {} {{\n", mermaid_word(&table.id())));
+        for (column, r#type) in &table.columns {
+            let mut keys = Vec::new();
+            if table.primary_key.contains(column) {
+                keys.push("PK");
+            }
+            if relations.iter().any(|relation| {
+                relation.schema == table.schema
+                    && relation.table == table.name
+                    && relation.columns.contains(column)
+            }) {
+                keys.push("FK");
+            }
+            out.push_str(&format!(
+                "        {} {}{}\n",
+                mermaid_word(r#type),
+                mermaid_word(column),
+                if keys.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}", keys.join(", "))
+                }
+            ));
+        }
+        out.push_str("    }\n");
+    }
+    for relation in relations {
+        out.push_str(&format!(
+            "    {} }}o--|| {} : \"{}\"\n",
+            mermaid_word(&table_id(&relation.schema, &relation.table)),
+            mermaid_word(&table_id(&relation.ref_schema, &relation.ref_table)),
+            relation.name.replace('"', "'")
+        ));
+    }
+    out
+}
+
+fn dot_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Mermaid only takes names and types of letters, digits, `_`, `-` and brackets, so the
+/// other characters become `_`, e.g. `public.orders` or `character varying(255)`.
+fn mermaid_word(text: &str) -> String {
+    let word: String = text
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "_-()[]".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if word.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        word
+    } else {
+        format!("_{}", word)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_diagram, DiagramFormat, DiagramTable};
+    use crate::database::Relation;
+
+    #[test]
+    fn test_format_diagram() {
+        let tables = vec![
+            DiagramTable {
+                schema: Some("public".to_string()),
+                name: "orders".to_string(),
+                columns: vec![("id".to_string(), "integer".to_string())],
+                primary_key: vec!["id".to_string()],
+            },
+            DiagramTable {
+                schema: Some("public".to_string()),
+                name: "order_items".to_string(),
+                columns: vec![
+                    ("id".to_string(), "integer".to_string()),
+                    ("order_id".to_string(), "integer".to_string()),
+                    ("note".to_string(), "character varying(255)".to_string()),
+                ],
+                primary_key: vec!["id".to_string()],
+            },
+        ];
+        let relations = vec![Relation {
+            name: "fk_order".to_string(),
+            schema: Some("public".to_string()),
+            table: "order_items".to_string(),
+            columns: vec!["order_id".to_string()],
+            ref_schema: Some("public".to_string()),
+            ref_table: "orders".to_string(),
+            ref_columns: vec!["id".to_string()],
+        }];
+
+        let dot = format_diagram(DiagramFormat::Dot, "shop", &tables, &relations);
+        assert!(dot.starts_with("digraph \"shop\" {\n"));
+        assert!(dot.contains("<td port=\"order_id\" align=\"left\">order_id: integer</td>"));
+        assert!(dot.contains("<td port=\"id\" align=\"left\">id: integer (PK)</td>"));
+        assert!(dot.contains(
+            "    \"public.order_items\":\"order_id\" -> \"public.orders\":\"id\" [label=\"fk_order\"];\n"
+        ));
+
+        let mermaid = format_diagram(DiagramFormat::Mermaid, "shop", &tables, &relations);
+        assert_eq!(
+            mermaid,
+            "erDiagram
+    public_orders {
+        integer id PK
+    }
+    public_order_items {
+        integer id PK
+        integer order_id FK
+        character_varying(255) note
+    }
+    public_order_items }o--|| public_orders : \"fk_order\"
+"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Returns the editor command from `$VISUAL` or `$EDITOR`, split into the program and its arguments.
+fn editor_command() -> Vec<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    editor.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+/// Creates a new file for the SQL which only the user can read. The name is predictable, so a
+/// file or a link which is already there is never opened; the next name is tried instead.
+fn create_file() -> Result<(PathBuf, File)> {
+    const ATTEMPTS: usize = 100;
+    for _ in 0..ATTEMPTS {
+        let path = std::env::temp_dir().join(format!(
+            "zhobo-{}-{}.sql",
+            std::process::id(),
+            TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create {}", path.display()))
+            }
+        }
+    }
+    bail!(
+        "Failed to create a temporary SQL file in {}",
+        std::env::temp_dir().display()
+    )
+}
+
+/// Opens `text` in the user's editor and returns the edited text.
+/// The terminal must be restored before calling this.
+pub fn edit(text: &str) -> Result<String> {
+    let (path, mut file) = create_file()?;
+    // the file is created here, so it is removed here whether the write fails or not
+    let written = file
+        .write_all(text.as_bytes())
+        .context("Failed to write the temporary SQL file");
+    drop(file);
+    if let Err(e) = written {
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+
+    let command = editor_command();
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .arg(&path)
+        .status()
+        .map_err(|e| anyhow!("`{}`: {}", command.join(" "), e));
+    let edited = status.and_then(|status| {
+        if !status.success() {
+            return Err(anyhow!("`{}` exited with {}", command.join(" "), status));
+        }
+        fs::read_to_string(&path).context("Failed to read the temporary SQL file")
+    });
+    let _ = fs::remove_file(&path);
+
+    // most editors append a newline at the end of the file
+    Ok(edited?.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -0,0 +1,256 @@
+use super::Key;
+use crate::config::KeyConfig;
+use anyhow::{anyhow, Context};
+use std::time::{Duration, Instant};
+
+/// How long a started chord waits for its next key before the keys act on their own.
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A sequence of keys which runs the action of a key, e.g. `<Space> b` for `open_bookmarks`.
+#[derive(Debug, Clone, PartialEq)]
+struct Chord {
+    keys: Vec<Key>,
+    action: String,
+    key: Key,
+}
+
+/// Holds the keys of a chord which has been started, and turns a finished chord into the key of
+/// its action.
+#[derive(Default)]
+pub struct Chords {
+    chords: Vec<Chord>,
+    pending: Vec<Key>,
+    since: Option<Instant>,
+}
+
+impl Chords {
+    /// Reads the chords of the key config, whose sequences are written like `g g` or
+    /// `<leader> e` and whose actions are the names of the other keys, e.g. `scroll_to_top`.
+    pub fn new(key_config: &KeyConfig) -> anyhow::Result<Self> {
+        let keys = serde_json::to_value(key_config)?;
+        let chords = key_config
+            .chords
+            .iter()
+            .map(|(sequence, action)| {
+                let key = keys
+                    .get(action)
+                    .and_then(|key| serde_json::from_value::<Key>(key.clone()).ok())
+                    .ok_or_else(|| anyhow!("unknown action `{}`", action))?;
+                Ok(Chord {
+                    keys: parse_sequence(sequence, key_config.leader)?,
+                    action: action.clone(),
+                    key,
+                })
+            })
+            .collect::<anyhow::Result<Vec<Chord>>>()
+            .context("Failed to read the chords in key_bind.ron")?;
+        Ok(Self {
+            chords,
+            ..Self::default()
+        })
+    }
+
+    /// Returns the keys to handle after `key` was pressed: none while a chord is unfinished,
+    /// the key of the action once it is finished, or the pressed keys if they are no chord.
+    pub fn feed(&mut self, key: Key) -> Vec<Key> {
+        self.pending.push(key);
+        if self.is_prefix() {
+            self.since = Some(Instant::now());
+            return Vec::new();
+        }
+        if let Some(chord) = self.exact() {
+            let key = chord.key;
+            self.reset();
+            return vec![key];
+        }
+        let last = self.pending.pop();
+        let mut keys = std::mem::take(&mut self.pending);
+        self.since = None;
+        match last {
+            // the last key may start a chord of its own
+            Some(last) if !keys.is_empty() => keys.extend(self.feed(last)),
+            Some(last) => keys.push(last),
+            None => (),
+        }
+        keys
+    }
+
+    /// Returns the keys of a chord which waited too long for its next key, to be handled as if
+    /// they were pressed on their own.
+    pub fn expire(&mut self) -> Vec<Key> {
+        match self.since {
+            Some(since) if since.elapsed() >= TIMEOUT => {
+                let keys = match self.exact() {
+                    Some(chord) => vec![chord.key],
+                    None => self.pending.clone(),
+                };
+                self.reset();
+                keys
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.since = None;
+    }
+
+    /// Returns the keys of the unfinished chord.
+    pub fn pending(&self) -> &[Key] {
+        &self.pending
+    }
+
+    /// Returns the keys which can finish or continue the unfinished chord, with the actions of
+    /// the chords they lead to.
+    pub fn continuations(&self) -> Vec<(Key, &str)> {
+        self.chords
+            .iter()
+            .filter(|chord| {
+                chord.keys.len() > self.pending.len() && chord.keys.starts_with(&self.pending)
+            })
+            .map(|chord| (chord.keys[self.pending.len()], chord.action.as_str()))
+            .collect()
+    }
+
+    /// Returns each chord as it is written in the help, e.g. `<Space> b`, with its action.
+    pub fn list(&self) -> Vec<(String, &str)> {
+        self.chords
+            .iter()
+            .map(|chord| (format_sequence(&chord.keys), chord.action.as_str()))
+            .collect()
+    }
+
+    fn is_prefix(&self) -> bool {
+        self.chords.iter().any(|chord| {
+            chord.keys.len() > self.pending.len() && chord.keys.starts_with(&self.pending)
+        })
+    }
+
+    fn exact(&self) -> Option<&Chord> {
+        self.chords.iter().find(|chord| chord.keys == self.pending)
+    }
+}
+
+pub fn format_sequence(keys: &[Key]) -> String {
+    keys.iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn parse_sequence(sequence: &str, leader: Key) -> anyhow::Result<Vec<Key>> {
+    let keys = sequence
+        .split_whitespace()
+        .map(|name| {
+            if name.eq_ignore_ascii_case("<leader>") {
+                Ok(leader)
+            } else {
+                parse_key(name).ok_or_else(|| anyhow!("unknown key `{}` in `{}`", name, sequence))
+            }
+        })
+        .collect::<anyhow::Result<Vec<Key>>>()?;
+    if keys.is_empty() {
+        return Err(anyhow!("empty chord"));
+    }
+    Ok(keys)
+}
+
+/// Parses a key written as the help shows it, e.g. `g`, `<Space>`, `<Enter>` or `<Ctrl+e>`.
+fn parse_key(name: &str) -> Option<Key> {
+    fn char(name: &str) -> Option<char> {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ if name.eq_ignore_ascii_case("space") => Some(' '),
+            _ => None,
+        }
+    }
+
+    if let Some(c) = char(name).filter(|_| name.len() == 1 || !name.starts_with('<')) {
+        return Some(Key::Char(c));
+    }
+    let name = name.strip_prefix('<')?.strip_suffix('>')?;
+    if let Some(c) = name.strip_prefix("Ctrl+") {
+        return char(c).map(Key::Ctrl);
+    }
+    if let Some(c) = name.strip_prefix("Alt+") {
+        return char(c).map(Key::Alt);
+    }
+    if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+        return (n <= 12).then(|| Key::from_f(n));
+    }
+    Some(match name {
+        "Space" => Key::Char(' '),
+        "Enter" => Key::Enter,
+        "Tab" => Key::Tab,
+        "Backspace" => Key::Backspace,
+        "Esc" => Key::Esc,
+        "Left" => Key::Left,
+        "Right" => Key::Right,
+        "Up" => Key::Up,
+        "Down" => Key::Down,
+        "Ins" => Key::Ins,
+        "Delete" => Key::Delete,
+        "Home" => Key::Home,
+        "End" => Key::End,
+        "PageUp" => Key::PageUp,
+        "PageDown" => Key::PageDown,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_key, Chords};
+    use crate::config::KeyConfig;
+    use crate::event::Key;
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("g"), Some(Key::Char('g')));
+        assert_eq!(parse_key("<"), Some(Key::Char('<')));
+        assert_eq!(parse_key("<Space>"), Some(Key::Char(' ')));
+        assert_eq!(parse_key("<Ctrl+e>"), Some(Key::Ctrl('e')));
+        assert_eq!(parse_key("<F5>"), Some(Key::F5));
+        assert_eq!(parse_key("<Nope>"), None);
+        assert_eq!(parse_key("gg"), None);
+    }
+
+    #[test]
+    fn test_feed() {
+        let mut key_config = KeyConfig {
+            chords: [("g g", "scroll_to_top"), ("<leader> b", "open_bookmarks")]
+                .into_iter()
+                .map(|(sequence, action)| (sequence.to_string(), action.to_string()))
+                .collect(),
+            ..KeyConfig::default()
+        };
+        let mut chords = Chords::new(&key_config).unwrap();
+
+        assert!(chords.feed(Key::Char('g')).is_empty());
+        assert_eq!(chords.pending(), [Key::Char('g')]);
+        assert_eq!(chords.continuations(), [(Key::Char('g'), "scroll_to_top")]);
+        assert_eq!(chords.feed(Key::Char('g')), [key_config.scroll_to_top]);
+
+        // the leader is space by default
+        assert!(chords.feed(Key::Char(' ')).is_empty());
+        assert_eq!(chords.feed(Key::Char('b')), [key_config.open_bookmarks]);
+
+        // keys which are no chord are handled as they were pressed, and the last one may
+        // start the next chord
+        assert!(chords.feed(Key::Char('g')).is_empty());
+        assert_eq!(chords.feed(Key::Char(' ')), [Key::Char('g')]);
+        assert_eq!(chords.pending(), [Key::Char(' ')]);
+        assert_eq!(
+            chords.feed(Key::Char('x')),
+            [Key::Char(' '), Key::Char('x')]
+        );
+        assert_eq!(chords.feed(Key::Char('j')), [Key::Char('j')]);
+
+        key_config
+            .chords
+            .insert("z".to_string(), "no_such_action".to_string());
+        assert!(Chords::new(&key_config).is_err());
+    }
+}
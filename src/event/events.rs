@@ -1,6 +1,7 @@
 use crate::event::Key;
 use crossterm::event;
-use std::{sync::mpsc, thread, time::Duration};
+use std::sync::mpsc;
+use std::{thread, time::Duration};
 
 #[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
@@ -27,6 +28,9 @@ pub enum Event<I> {
 pub struct Events {
     rx: mpsc::Receiver<Event<Key>>,
     _tx: mpsc::Sender<Event<Key>>,
+    /// Asks the input thread to stop reading, with a sender to acknowledge it once it has.
+    pause_tx: mpsc::Sender<mpsc::Sender<()>>,
+    resume_tx: mpsc::Sender<()>,
 }
 
 impl Events {
@@ -40,8 +44,17 @@ impl Events {
     pub fn with_config(config: EventConfig) -> Events {
         let (tx, rx) = mpsc::channel();
 
+        let (pause_tx, pause_rx) = mpsc::channel::<mpsc::Sender<()>>();
+        let (resume_tx, resume_rx) = mpsc::channel();
+
         let event_tx = tx.clone();
         thread::spawn(move || loop {
+            // a pause is only taken between two polls, so that no input is read while an
+            // external process owns the terminal
+            if let Ok(acknowledge) = pause_rx.try_recv() {
+                let _ = acknowledge.send(());
+                let _ = resume_rx.recv();
+            }
             if event::poll(config.tick_rate).unwrap() {
                 if let event::Event::Key(key) = event::read().unwrap() {
                     let key = Key::from(key);
@@ -53,10 +66,32 @@ impl Events {
             event_tx.send(Event::Tick).unwrap();
         });
 
-        Events { rx, _tx: tx }
+        Events {
+            rx,
+            _tx: tx,
+            pause_tx,
+            resume_tx,
+        }
+    }
+
+    /// Stops reading the input, and returns once the input thread is no longer polling.
+    pub fn pause(&self) {
+        let (acknowledge, acknowledged) = mpsc::channel();
+        if self.pause_tx.send(acknowledge).is_ok() {
+            let _ = acknowledged.recv();
+        }
+    }
+
+    pub fn resume(&self) {
+        let _ = self.resume_tx.send(());
     }
 
     pub fn next(&self) -> Result<Event<Key>, mpsc::RecvError> {
         self.rx.recv()
     }
+
+    /// Returns the next event if there is one, without waiting.
+    pub fn try_next(&self) -> Option<Event<Key>> {
+        self.rx.try_recv().ok()
+    }
 }
@@ -1,13 +1,9 @@
 use crossterm::event;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[cfg(test)]
-use serde::Serialize;
-
 /// Represents a key.
-#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Deserialize)]
-#[cfg_attr(test, derive(Serialize))]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
 pub enum Key {
     /// Both Enter (or Return) and numpad Enter
     Enter,
@@ -1,3 +1,4 @@
+pub mod chord;
 mod events;
 mod key;
 
@@ -0,0 +1,437 @@
+use crate::database::script::split_statements;
+use crate::database::{ExecuteResult, Pool, Value};
+use crate::tree::{Database, Table};
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// The number of rows fetched at once when a query is exported.
+const QUERY_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    /// A single array of objects.
+    Json,
+    /// One object per line.
+    JsonLines,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "jsonl" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// Writes rows into a file one page at a time, so that they never have to be kept in memory.
+struct ExportWriter {
+    format: ExportFormat,
+    path: PathBuf,
+    writer: BufWriter<File>,
+    headers: Vec<String>,
+    row_count: usize,
+}
+
+impl ExportWriter {
+    /// Creates `path`, or a file named after `name` and the time in the current directory.
+    fn create(name: &str, format: ExportFormat, path: Option<PathBuf>) -> Result<Self> {
+        let path = path.unwrap_or_else(|| {
+            PathBuf::from(format!(
+                "{}-{}.{}",
+                name,
+                chrono::Local::now().format("%Y%m%d%H%M%S"),
+                format.extension()
+            ))
+        });
+        let file =
+            File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(Self {
+            format,
+            path,
+            writer: BufWriter::new(file),
+            headers: Vec::new(),
+            row_count: 0,
+        })
+    }
+
+    fn write_headers(&mut self, headers: &[String]) -> Result<()> {
+        write_headers(&mut self.writer, self.format, headers)?;
+        self.headers = headers.to_vec();
+        Ok(())
+    }
+
+    fn write_rows(&mut self, rows: &[Vec<Value>]) -> Result<()> {
+        write_rows(
+            &mut self.writer,
+            self.format,
+            &self.headers,
+            rows,
+            self.row_count,
+        )?;
+        self.row_count += rows.len();
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(PathBuf, usize)> {
+        write_end(&mut self.writer, self.format)?;
+        self.writer.flush()?;
+        Ok((self.path, self.row_count))
+    }
+}
+
+fn write_headers(writer: &mut impl Write, format: ExportFormat, headers: &[String]) -> Result<()> {
+    match format {
+        ExportFormat::Csv => writeln!(writer, "{}", csv_line(headers.iter().map(String::as_str)))?,
+        ExportFormat::Json => write!(writer, "[")?,
+        ExportFormat::JsonLines => (),
+    }
+    Ok(())
+}
+
+/// Writes `rows`, the first of which is the row number `written` of the output.
+fn write_rows(
+    writer: &mut impl Write,
+    format: ExportFormat,
+    headers: &[String],
+    rows: &[Vec<Value>],
+    written: usize,
+) -> Result<()> {
+    for (index, row) in rows.iter().enumerate() {
+        match format {
+            ExportFormat::Csv => {
+                let cells = row
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>();
+                writeln!(writer, "{}", csv_line(cells.iter().map(String::as_str)))?
+            }
+            ExportFormat::Json => write!(
+                writer,
+                "{}\n  {}",
+                if written + index == 0 { "" } else { "," },
+                json_object(headers, row)
+            )?,
+            ExportFormat::JsonLines => writeln!(writer, "{}", json_object(headers, row))?,
+        }
+    }
+    Ok(())
+}
+
+fn write_end(writer: &mut impl Write, format: ExportFormat) -> Result<()> {
+    if format == ExportFormat::Json {
+        writeln!(writer, "\n]")?;
+    }
+    Ok(())
+}
+
+/// Returns rows which are already loaded, e.g. the cells selected in a table, in the format
+/// of an export.
+pub fn format_rows(format: ExportFormat, headers: &[String], rows: &[Vec<Value>]) -> String {
+    let mut out = Vec::new();
+    // writing into memory cannot fail
+    let _ = write_headers(&mut out, format, headers)
+        .and_then(|_| write_rows(&mut out, format, headers, rows, 0))
+        .and_then(|_| write_end(&mut out, format));
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Writes rows which are already loaded into `path`, or into a file named after `name` and
+/// the time, and returns the path.
+pub fn write_rows_to_file(
+    name: &str,
+    format: ExportFormat,
+    headers: &[String],
+    rows: &[Vec<Value>],
+    path: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let mut writer = ExportWriter::create(name, format, path)?;
+    writer.write_headers(headers)?;
+    writer.write_rows(rows)?;
+    Ok(writer.finish()?.0)
+}
+
+/// Joins `cells` with commas, quoting the ones which contain a comma, a quote or a newline.
+fn csv_line<'a>(cells: impl Iterator<Item = &'a str>) -> String {
+    cells
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Returns a row as a JSON object whose keys are in the order of the columns.
+fn json_object(headers: &[String], row: &[Value]) -> String {
+    let fields = headers
+        .iter()
+        .zip(row)
+        .map(|(header, value)| {
+            format!(
+                "{}:{}",
+                serde_json::Value::from(header.as_str()),
+                value.to_json()
+            )
+        })
+        .collect::<Vec<String>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+enum Source {
+    Records {
+        database: Database,
+        table: Table,
+        filter: Option<String>,
+        orders: Option<String>,
+    },
+    Query {
+        query: String,
+        /// The number of columns the query returns, known once its first row is read.
+        columns: Option<usize>,
+    },
+}
+
+/// Streams every row of a table or a SELECT query into a file, one page per step.
+pub struct ExportJob {
+    source: Source,
+    writer: ExportWriter,
+    total: Option<usize>,
+    finished: bool,
+}
+
+impl ExportJob {
+    /// Exports every record of `table`. `total` is the number of rows if it is known.
+    pub fn records(
+        format: ExportFormat,
+        database: Database,
+        table: Table,
+        filter: Option<String>,
+        orders: Option<String>,
+        total: Option<usize>,
+        path: Option<PathBuf>,
+    ) -> Result<Self> {
+        Ok(Self {
+            writer: ExportWriter::create(&table.name, format, path)?,
+            source: Source::Records {
+                database,
+                table,
+                filter,
+                orders,
+            },
+            total,
+            finished: false,
+        })
+    }
+
+    /// Exports every row of a SELECT query by running it again one page at a time.
+    pub fn query(format: ExportFormat, query: &str, path: Option<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            writer: ExportWriter::create("query", format, path)?,
+            source: Source::Query {
+                query: single_statement(query)?,
+                columns: None,
+            },
+            total: None,
+            finished: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.writer.path
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.writer.row_count
+    }
+
+    pub fn total(&self) -> Option<usize> {
+        self.total
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Writes the next page of rows.
+    pub async fn step(&mut self, pool: &dyn Pool) -> Result<()> {
+        let offset = self.writer.row_count;
+        let (headers, rows, last) = match &mut self.source {
+            Source::Records {
+                database,
+                table,
+                filter,
+                orders,
+            } => {
+                let (headers, rows) = pool
+                    .get_records(database, table, offset, filter.clone(), orders.clone())
+                    .await?;
+                let last = rows.is_empty();
+                (headers, rows, last)
+            }
+            Source::Query { query, columns } => {
+                let column_count = match *columns {
+                    Some(column_count) => column_count,
+                    None => {
+                        let (headers, _) = read(pool, &probe_query(query)).await?;
+                        *columns = Some(headers.len());
+                        headers.len()
+                    }
+                };
+                if column_count == 0 {
+                    // the query returns no rows
+                    (Vec::new(), Vec::new(), true)
+                } else {
+                    let (headers, rows) =
+                        read(pool, &page_query(query, column_count, offset)).await?;
+                    let last = rows.len() < QUERY_PAGE_SIZE;
+                    (headers, rows, last)
+                }
+            }
+        };
+        if offset == 0 {
+            self.writer.write_headers(&headers)?;
+        }
+        self.writer.write_rows(&rows)?;
+        self.finished = last;
+        Ok(())
+    }
+
+    /// Returns the path of the file and the number of rows once every row is written.
+    pub fn finish(self) -> Result<(PathBuf, usize)> {
+        self.writer.finish()
+    }
+
+    /// Stops the export and removes the incomplete file.
+    pub fn cancel(self) {
+        let path = self.writer.path.clone();
+        drop(self.writer);
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Returns the query without the semicolon and the comments after it, so that it can be put
+/// in a subquery.
+fn single_statement(query: &str) -> Result<String> {
+    match split_statements(query).as_slice() {
+        [statement] => Ok(statement.clone()),
+        [] => anyhow::bail!("there is no query to export"),
+        _ => anyhow::bail!("only a single query can be exported"),
+    }
+}
+
+/// Reads one row of `query` to learn its columns.
+fn probe_query(query: &str) -> String {
+    // the query is on lines of its own so that a comment at its end does not hide the rest
+    format!("SELECT * FROM (\n{}\n) AS export LIMIT 1", query)
+}
+
+/// Returns a page of `query`, sorted by every column so that the pages neither overlap nor
+/// skip rows, which rows without an order may do from one run to the next.
+fn page_query(query: &str, columns: usize, offset: usize) -> String {
+    let order = (1..=columns)
+        .map(|column| column.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!(
+        "SELECT * FROM (\n{}\n) AS export ORDER BY {} LIMIT {} OFFSET {}",
+        query, order, QUERY_PAGE_SIZE, offset
+    )
+}
+
+/// Runs a page of a query and returns its headers and rows.
+async fn read(pool: &dyn Pool, query: &String) -> Result<(Vec<String>, Vec<Vec<Value>>)> {
+    match pool.execute(query).await? {
+        ExecuteResult::Read {
+            headers,
+            rows,
+            truncated,
+            ..
+        } => {
+            if truncated {
+                anyhow::bail!(
+                    "a page of {} rows exceeds the memory budget",
+                    QUERY_PAGE_SIZE
+                );
+            }
+            Ok((headers, rows))
+        }
+        ExecuteResult::Write { .. } => anyhow::bail!("only a SELECT query can be exported"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{csv_line, format_rows, json_object, page_query, single_statement, ExportFormat};
+    use crate::database::Value;
+
+    #[test]
+    fn test_csv_line() {
+        assert_eq!(
+            csv_line(["a", "b,c", "say \"hi\"", "x\ny"].into_iter()),
+            "a,\"b,c\",\"say \"\"hi\"\"\",\"x\ny\""
+        );
+    }
+
+    #[test]
+    fn test_json_object() {
+        let headers = vec!["id".to_string(), "name".to_string(), "tags".to_string()];
+        let row = vec![
+            Value::Int(1),
+            Value::Text("say \"hi\"".to_string()),
+            Value::Array(vec![Value::Null, Value::Bool(true)]),
+        ];
+        assert_eq!(
+            json_object(&headers, &row),
+            r#"{"id":1,"name":"say \"hi\"","tags":[null,true]}"#
+        );
+    }
+
+    #[test]
+    fn test_format_rows() {
+        let headers = vec!["id".to_string(), "name".to_string()];
+        let rows = vec![
+            vec![Value::Int(1), Value::Text("a,b".to_string())],
+            vec![Value::Int(2), Value::Null],
+        ];
+        assert_eq!(
+            format_rows(ExportFormat::Csv, &headers, &rows),
+            "id,name\n1,\"a,b\"\n2,NULL\n"
+        );
+        assert_eq!(
+            format_rows(ExportFormat::Json, &headers, &rows),
+            "[\n  {\"id\":1,\"name\":\"a,b\"},\n  {\"id\":2,\"name\":null}\n]\n"
+        );
+        assert_eq!(
+            format_rows(ExportFormat::JsonLines, &headers, &rows[1..]),
+            "{\"id\":2,\"name\":null}\n"
+        );
+    }
+
+    #[test]
+    fn test_page_query() {
+        let query = single_statement("SELECT a, b FROM t -- all of them\n; -- done\n").unwrap();
+        assert_eq!(
+            page_query(&query, 2, 1000),
+            "SELECT * FROM (\nSELECT a, b FROM t -- all of them\n) AS export ORDER BY 1, 2 LIMIT 1000 OFFSET 1000"
+        );
+        assert!(single_statement("SELECT 1; SELECT 2").is_err());
+        assert!(single_statement("-- nothing").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::config::get_app_config_path;
+use crate::tree::{Database, Table};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A table pinned as a favorite, found again in the tree by its path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FavoriteTable {
+    pub database: String,
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+impl FavoriteTable {
+    pub fn new(database: &Database, table: &Table) -> Self {
+        Self {
+            database: database.name.clone(),
+            schema: table.schema.clone(),
+            name: table.name.clone(),
+        }
+    }
+
+    pub fn path(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}.{}", self.database, schema, self.name),
+            None => format!("{}.{}", self.database, self.name),
+        }
+    }
+}
+
+/// The connections and tables pinned as favorites, saved whenever they change.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Favorites {
+    /// See `Connection::session_key`.
+    #[serde(default)]
+    pub connections: Vec<String>,
+    /// Tables keyed by `Connection::session_key`.
+    #[serde(default)]
+    pub tables: BTreeMap<String, Vec<FavoriteTable>>,
+}
+
+impl Favorites {
+    fn path() -> anyhow::Result<PathBuf> {
+        Ok(get_app_config_path()?.join("favorites.json"))
+    }
+
+    /// Returns the saved favorites, which are empty if none was ever pinned.
+    pub fn load() -> anyhow::Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        std::fs::write(Self::path()?, serde_json::to_string_pretty(self)?)
+            .context("Failed to save the favorites")
+    }
+
+    /// Pins the connection, or unpins it if it is already a favorite. Returns true if it was
+    /// pinned.
+    pub fn toggle_connection(&mut self, connection: &str) -> bool {
+        match self.connections.iter().position(|c| c == connection) {
+            Some(index) => {
+                self.connections.remove(index);
+                false
+            }
+            None => {
+                self.connections.push(connection.to_string());
+                true
+            }
+        }
+    }
+
+    /// Pins the table of the connection, or unpins it if it is already a favorite. Returns
+    /// true if it was pinned.
+    pub fn toggle_table(&mut self, connection: &str, table: FavoriteTable) -> bool {
+        let tables = self.tables.entry(connection.to_string()).or_default();
+        let pinned = match tables.iter().position(|t| *t == table) {
+            Some(index) => {
+                tables.remove(index);
+                false
+            }
+            None => {
+                tables.push(table);
+                true
+            }
+        };
+        if tables.is_empty() {
+            self.tables.remove(connection);
+        }
+        pinned
+    }
+
+    pub fn tables_of(&self, connection: &str) -> &[FavoriteTable] {
+        self.tables
+            .get(connection)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FavoriteTable, Favorites};
+
+    fn table(name: &str) -> FavoriteTable {
+        FavoriteTable {
+            database: "shop".to_string(),
+            schema: Some("public".to_string()),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_toggle_favorites() {
+        let mut favorites = Favorites::default();
+        assert!(favorites.toggle_connection("mysql Foo DB"));
+        assert!(favorites.toggle_table("mysql Foo DB", table("orders")));
+        assert!(favorites.toggle_table("mysql Foo DB", table("users")));
+        assert!(!favorites.toggle_table("mysql Foo DB", table("orders")));
+        assert_eq!(favorites.tables_of("mysql Foo DB"), &[table("users")]);
+        assert_eq!(table("users").path(), "shop.public.users");
+
+        let json = serde_json::to_string(&favorites).unwrap();
+        assert_eq!(serde_json::from_str::<Favorites>(&json).unwrap(), favorites);
+
+        assert!(!favorites.toggle_connection("mysql Foo DB"));
+        assert!(!favorites.toggle_table("mysql Foo DB", table("users")));
+        assert_eq!(favorites, Favorites::default());
+    }
+}
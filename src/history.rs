@@ -0,0 +1,138 @@
+use crate::config::get_app_config_path;
+use crate::tree::{Database, Table};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// The filters and SQL entered before, saved so that they can be recalled after zhobo restarts.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    /// Filters keyed by the connection and the table, see `History::table_key`.
+    #[serde(default)]
+    pub filters: BTreeMap<String, Vec<String>>,
+    /// SQL keyed by `Connection::session_key`.
+    #[serde(default)]
+    pub sql: BTreeMap<String, Vec<String>>,
+}
+
+impl History {
+    const MAX_ENTRIES: usize = 100;
+
+    fn path() -> anyhow::Result<PathBuf> {
+        Ok(get_app_config_path()?.join("history.json"))
+    }
+
+    /// Returns the saved history, which is empty if zhobo has never quit normally.
+    pub fn load() -> anyhow::Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        std::fs::write(Self::path()?, serde_json::to_string(self)?)
+            .context("Failed to save the history")
+    }
+
+    pub fn table_key(connection: &str, database: &Database, table: &Table) -> String {
+        match &table.schema {
+            Some(schema) => format!("{}/{}/{}.{}", connection, database.name, schema, table.name),
+            None => format!("{}/{}/{}", connection, database.name, table.name),
+        }
+    }
+
+    /// Appends `entry` as the newest one, dropping an older copy of it and the oldest entries
+    /// beyond the limit.
+    pub fn push(entries: &mut Vec<String>, entry: String) {
+        if entry.trim().is_empty() {
+            return;
+        }
+        entries.retain(|old| *old != entry);
+        entries.push(entry);
+        if entries.len() > Self::MAX_ENTRIES {
+            entries.drain(..entries.len() - Self::MAX_ENTRIES);
+        }
+    }
+}
+
+/// Walks through the history of an input, newest first, and back to what was typed before.
+#[derive(Debug, Default, Clone)]
+pub struct HistoryCursor {
+    entries: Vec<String>,
+    position: Option<usize>,
+    draft: String,
+}
+
+impl HistoryCursor {
+    pub fn set_entries(&mut self, entries: Vec<String>) {
+        self.entries = entries;
+        self.position = None;
+    }
+
+    /// Returns the entry before the current one, keeping `input` if it is left for the newest.
+    pub fn previous(&mut self, input: &str) -> Option<String> {
+        let position = match self.position {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = input.to_string();
+                self.entries.len() - 1
+            }
+            Some(position) => position.saturating_sub(1),
+        };
+        self.position = Some(position);
+        self.entries.get(position).cloned()
+    }
+
+    /// Returns the entry after the current one, or the kept input after the newest.
+    pub fn next(&mut self) -> Option<String> {
+        match self.position {
+            None => None,
+            Some(position) if position + 1 < self.entries.len() => {
+                self.position = Some(position + 1);
+                self.entries.get(position + 1).cloned()
+            }
+            Some(_) => {
+                self.position = None;
+                Some(std::mem::take(&mut self.draft))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{History, HistoryCursor};
+
+    #[test]
+    fn test_push() {
+        let mut entries = vec!["a".to_string(), "b".to_string()];
+        History::push(&mut entries, "a".to_string());
+        assert_eq!(entries, vec!["b".to_string(), "a".to_string()]);
+        History::push(&mut entries, " ".to_string());
+        assert_eq!(entries.len(), 2);
+        for index in 0..200 {
+            History::push(&mut entries, index.to_string());
+        }
+        assert_eq!(entries.len(), History::MAX_ENTRIES);
+        assert_eq!(entries.last(), Some(&"199".to_string()));
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut cursor = HistoryCursor::default();
+        assert_eq!(cursor.previous("typed"), None);
+        cursor.set_entries(vec!["old".to_string(), "new".to_string()]);
+        assert_eq!(cursor.next(), None);
+        assert_eq!(cursor.previous("typed"), Some("new".to_string()));
+        assert_eq!(cursor.previous("new"), Some("old".to_string()));
+        assert_eq!(cursor.previous("old"), Some("old".to_string()));
+        assert_eq!(cursor.next(), Some("new".to_string()));
+        assert_eq!(cursor.next(), Some("typed".to_string()));
+        assert_eq!(cursor.next(), None);
+    }
+}
@@ -1,18 +1,21 @@
-use crate::config::KeyConfig;
+use crate::config::{KeyConfig, KeyScope};
 use crate::event::Key;
 use ron::de::SpannedError;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::PathBuf;
 
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(test, derive(PartialEq))]
 pub struct KeyBind {
     pub scroll_up: Option<Key>,
     pub scroll_down: Option<Key>,
     pub scroll_right: Option<Key>,
     pub scroll_left: Option<Key>,
     pub sort_by_column: Option<Key>,
+    pub add_sort_column: Option<Key>,
     pub move_up: Option<Key>,
     pub move_down: Option<Key>,
     pub copy: Option<Key>,
@@ -43,10 +46,69 @@ pub struct KeyBind {
     pub tab_foreign_keys: Option<Key>,
     pub tab_indexes: Option<Key>,
     pub tab_definition: Option<Key>,
+    pub tab_dependencies: Option<Key>,
+    pub tab_triggers: Option<Key>,
+    pub tab_statistics: Option<Key>,
+    pub tab_privileges: Option<Key>,
+    pub tab_partitions: Option<Key>,
     pub tab_sql_editor: Option<Key>,
+    pub tab_logs: Option<Key>,
     pub tab_properties: Option<Key>,
     pub extend_or_shorten_widget_width_to_right: Option<Key>,
     pub extend_or_shorten_widget_width_to_left: Option<Key>,
+    pub edit_in_external_editor: Option<Key>,
+    pub open_sql_file: Option<Key>,
+    pub save_sql_file: Option<Key>,
+    pub refresh: Option<Key>,
+    pub refresh_all: Option<Key>,
+    pub open_column_picker: Option<Key>,
+    pub view_cell: Option<Key>,
+    pub bookmark_row: Option<Key>,
+    pub open_bookmarks: Option<Key>,
+    pub toggle_favorite: Option<Key>,
+    pub open_favorites: Option<Key>,
+    pub open_recent_tables: Option<Key>,
+    pub toggle_system_objects: Option<Key>,
+    pub open_errors: Option<Key>,
+    pub copy_as_insert: Option<Key>,
+    pub delete_row: Option<Key>,
+    pub export_to_file: Option<Key>,
+    pub export_to_json: Option<Key>,
+    pub export_to_json_lines: Option<Key>,
+    pub toggle_sample: Option<Key>,
+    pub command_line: Option<Key>,
+    pub search_objects: Option<Key>,
+    pub session_settings: Option<Key>,
+    pub toggle_stop_on_error: Option<Key>,
+    pub create_foreign_key: Option<Key>,
+    pub drop_foreign_key: Option<Key>,
+    pub restart_sequence: Option<Key>,
+    pub toggle_transaction_mode: Option<Key>,
+    pub commit: Option<Key>,
+    pub rollback: Option<Key>,
+    pub next_result_set: Option<Key>,
+    pub previous_result_set: Option<Key>,
+    pub snapshot_result: Option<Key>,
+    pub search_in_table: Option<Key>,
+    pub next_match: Option<Key>,
+    pub previous_match: Option<Key>,
+    pub next_column_page: Option<Key>,
+    pub previous_column_page: Option<Key>,
+    pub pin_columns: Option<Key>,
+    pub widen_column: Option<Key>,
+    pub narrow_column: Option<Key>,
+    pub fit_column: Option<Key>,
+    pub toggle_record_view: Option<Key>,
+    pub toggle_chart: Option<Key>,
+    pub open_dashboard: Option<Key>,
+    pub open_activity: Option<Key>,
+    pub open_roles: Option<Key>,
+    pub open_relations: Option<Key>,
+    pub format_sql: Option<Key>,
+    pub run_statement: Option<Key>,
+    pub leader: Option<Key>,
+    pub chords: Option<BTreeMap<String, String>>,
+    pub scoped: Option<BTreeMap<KeyScope, KeyBind>>,
 }
 
 impl KeyBind {
@@ -76,9 +138,15 @@ macro_rules! merge {
 }
 
 impl From<KeyBind> for KeyConfig {
-    #[allow(clippy::field_reassign_with_default)]
     fn from(kb: KeyBind) -> Self {
-        let mut kc = KeyConfig::default();
+        kb.apply(KeyConfig::default())
+    }
+}
+
+impl KeyBind {
+    /// Returns `kc` with the keys which are set in this key bind replaced.
+    pub fn apply(self, mut kc: KeyConfig) -> KeyConfig {
+        let kb = self;
         merge!(kc.scroll_up, kb.scroll_up);
         merge!(kc.scroll_down, kb.scroll_down);
         merge!(kc.scroll_right, kb.scroll_right);
@@ -104,6 +172,7 @@ impl From<KeyBind> for KeyConfig {
         merge!(kc.move_to_head_of_line, kb.move_to_head_of_line);
         merge!(kc.move_to_tail_of_line, kb.move_to_tail_of_line);
         merge!(kc.sort_by_column, kb.sort_by_column);
+        merge!(kc.add_sort_column, kb.add_sort_column);
         merge!(
             kc.extend_selection_by_one_cell_left,
             kb.extend_selection_by_one_cell_left
@@ -127,11 +196,17 @@ impl From<KeyBind> for KeyConfig {
         merge!(kc.tab_records, kb.tab_records);
         merge!(kc.tab_properties, kb.tab_properties);
         merge!(kc.tab_sql_editor, kb.tab_sql_editor);
+        merge!(kc.tab_logs, kb.tab_logs);
         merge!(kc.tab_columns, kb.tab_columns);
         merge!(kc.tab_constraints, kb.tab_constraints);
         merge!(kc.tab_foreign_keys, kb.tab_foreign_keys);
         merge!(kc.tab_definition, kb.tab_definition);
         merge!(kc.tab_indexes, kb.tab_indexes);
+        merge!(kc.tab_dependencies, kb.tab_dependencies);
+        merge!(kc.tab_triggers, kb.tab_triggers);
+        merge!(kc.tab_statistics, kb.tab_statistics);
+        merge!(kc.tab_privileges, kb.tab_privileges);
+        merge!(kc.tab_partitions, kb.tab_partitions);
         merge!(
             kc.extend_or_shorten_widget_width_to_right,
             kb.extend_or_shorten_widget_width_to_right
@@ -140,6 +215,59 @@ impl From<KeyBind> for KeyConfig {
             kc.extend_or_shorten_widget_width_to_left,
             kb.extend_or_shorten_widget_width_to_left
         );
+        merge!(kc.edit_in_external_editor, kb.edit_in_external_editor);
+        merge!(kc.open_sql_file, kb.open_sql_file);
+        merge!(kc.save_sql_file, kb.save_sql_file);
+        merge!(kc.refresh, kb.refresh);
+        merge!(kc.refresh_all, kb.refresh_all);
+        merge!(kc.open_column_picker, kb.open_column_picker);
+        merge!(kc.view_cell, kb.view_cell);
+        merge!(kc.bookmark_row, kb.bookmark_row);
+        merge!(kc.open_bookmarks, kb.open_bookmarks);
+        merge!(kc.toggle_favorite, kb.toggle_favorite);
+        merge!(kc.open_favorites, kb.open_favorites);
+        merge!(kc.open_recent_tables, kb.open_recent_tables);
+        merge!(kc.toggle_system_objects, kb.toggle_system_objects);
+        merge!(kc.open_errors, kb.open_errors);
+        merge!(kc.copy_as_insert, kb.copy_as_insert);
+        merge!(kc.delete_row, kb.delete_row);
+        merge!(kc.export_to_file, kb.export_to_file);
+        merge!(kc.export_to_json, kb.export_to_json);
+        merge!(kc.export_to_json_lines, kb.export_to_json_lines);
+        merge!(kc.toggle_sample, kb.toggle_sample);
+        merge!(kc.command_line, kb.command_line);
+        merge!(kc.search_objects, kb.search_objects);
+        merge!(kc.session_settings, kb.session_settings);
+        merge!(kc.toggle_stop_on_error, kb.toggle_stop_on_error);
+        merge!(kc.create_foreign_key, kb.create_foreign_key);
+        merge!(kc.drop_foreign_key, kb.drop_foreign_key);
+        merge!(kc.restart_sequence, kb.restart_sequence);
+        merge!(kc.toggle_transaction_mode, kb.toggle_transaction_mode);
+        merge!(kc.commit, kb.commit);
+        merge!(kc.rollback, kb.rollback);
+        merge!(kc.next_result_set, kb.next_result_set);
+        merge!(kc.previous_result_set, kb.previous_result_set);
+        merge!(kc.snapshot_result, kb.snapshot_result);
+        merge!(kc.search_in_table, kb.search_in_table);
+        merge!(kc.next_match, kb.next_match);
+        merge!(kc.previous_match, kb.previous_match);
+        merge!(kc.next_column_page, kb.next_column_page);
+        merge!(kc.previous_column_page, kb.previous_column_page);
+        merge!(kc.pin_columns, kb.pin_columns);
+        merge!(kc.widen_column, kb.widen_column);
+        merge!(kc.narrow_column, kb.narrow_column);
+        merge!(kc.fit_column, kb.fit_column);
+        merge!(kc.toggle_record_view, kb.toggle_record_view);
+        merge!(kc.toggle_chart, kb.toggle_chart);
+        merge!(kc.open_dashboard, kb.open_dashboard);
+        merge!(kc.open_activity, kb.open_activity);
+        merge!(kc.open_roles, kb.open_roles);
+        merge!(kc.open_relations, kb.open_relations);
+        merge!(kc.format_sql, kb.format_sql);
+        merge!(kc.run_statement, kb.run_statement);
+        merge!(kc.leader, kb.leader);
+        merge!(kc.chords, kb.chords);
+        merge!(kc.scoped, kb.scoped);
         kc
     }
 }
@@ -147,7 +275,7 @@ impl From<KeyBind> for KeyConfig {
 #[cfg(test)]
 mod test {
     use super::KeyBind;
-    use crate::config::KeyConfig;
+    use crate::config::{KeyConfig, KeyScope};
     use crate::event::Key;
     use std::path::Path;
 
@@ -178,4 +306,18 @@ mod test {
         let build_kc = KeyConfig::from(kb);
         assert_eq!(build_kc.scroll_up, Key::Char('M'));
     }
+
+    #[test]
+    fn test_scoped_key_config() {
+        let kb: KeyBind = ron::from_str(
+            "(copy: Some(Char('c')), scoped: Some({Editor: (copy: Some(Char('Y')))}))",
+        )
+        .unwrap();
+        let kc = KeyConfig::from(kb);
+        assert_eq!(kc.copy, Key::Char('c'));
+        assert_eq!(kc.scoped(KeyScope::Editor).copy, Key::Char('Y'));
+        // the other keys of a scope are the global ones
+        assert_eq!(kc.scoped(KeyScope::Editor).scroll_up, kc.scroll_up);
+        assert_eq!(kc.scoped(KeyScope::Tree).copy, Key::Char('c'));
+    }
 }
@@ -1,6 +1,9 @@
+use chrono::{DateTime, Local};
 use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
 
-#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Clone, Deserialize)]
+#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Clone, Copy, Deserialize)]
 pub enum LogLevel {
     Quiet,
     Error,
@@ -54,6 +57,54 @@ impl std::str::FromStr for LogLevel {
     }
 }
 
+/// A message of this session, shown in the logs tab.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: DateTime<Local>,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// The most messages kept, the oldest being forgotten first.
+const CAPACITY: usize = 1000;
+
+static ENTRIES: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+/// Keeps a message for the logs tab; which messages it shows depends on `log_level`.
+pub fn record(level: LogLevel, message: impl Into<String>) {
+    if let Ok(mut entries) = ENTRIES.lock() {
+        if entries.len() == CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(LogEntry {
+            time: Local::now(),
+            level,
+            message: message.into(),
+        });
+    }
+}
+
+/// Returns the messages which `level` lets through, the oldest first.
+pub fn entries(level: &LogLevel) -> Vec<LogEntry> {
+    ENTRIES
+        .lock()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|entry| entry.level != LogLevel::Quiet && level.is_writable(&entry.level))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:ident, $($arg:tt)+) => {
+        $crate::log::record($crate::log::LogLevel::$level, format!($($arg)+))
+    };
+}
+
 #[macro_export]
 macro_rules! outln {
     ($config:ident #$level:path, $($expr:expr),+) => {{
@@ -79,3 +130,27 @@ macro_rules! debug {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{entries, record, LogLevel};
+
+    #[test]
+    fn test_entries() {
+        record(LogLevel::Error, "test_entries error");
+        record(LogLevel::Info, "test_entries info");
+        let messages = |level| {
+            entries(&level)
+                .into_iter()
+                .map(|entry| entry.message)
+                .filter(|message| message.starts_with("test_entries"))
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            messages(LogLevel::Info),
+            ["test_entries error", "test_entries info"]
+        );
+        assert_eq!(messages(LogLevel::Error), ["test_entries error"]);
+        assert!(messages(LogLevel::Quiet).is_empty());
+    }
+}
@@ -1,21 +1,36 @@
+mod announce;
 mod app;
 mod cli;
 mod clipboard;
 mod components;
 mod config;
 mod database;
+mod diagram;
+mod editor;
 mod event;
+mod export;
+mod favorites;
+mod history;
 mod key_bind;
+mod preset;
+mod session;
 mod tree;
+mod tutorial;
 mod ui;
 mod version;
+mod vim;
 
 #[macro_use]
 mod log;
 
 use crate::app::App;
+use crate::cli::Command;
 use crate::config::Config;
+use crate::event::chord::Chords;
 use crate::event::{Event, Key};
+use crate::favorites::Favorites;
+use crate::history::History;
+use crate::session::Session;
 use anyhow::Result;
 use crossterm::execute;
 use crossterm::{
@@ -30,14 +45,59 @@ use std::panic::{set_hook, take_hook};
 async fn main() -> anyhow::Result<()> {
     let value = crate::cli::parse();
     let config = Config::new(&value.config)?;
+    if let Some(command) = value.command {
+        return run_command(command, &config).await;
+    }
+    let config = if value.config.tutorial {
+        tutorial::config(config).await?
+    } else {
+        config
+    };
+    clipboard::set_backend(config.clipboard.clone());
     setup_terminal()?;
 
     let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
     let events = event::Events::new(250);
     let mut app = App::new(config.clone());
+    if value.config.tutorial {
+        app.start_tutorial();
+    }
     terminal.clear()?;
 
+    match Chords::new(&app.config.key_config) {
+        Ok(chords) => app.set_chords(chords),
+        Err(err) => app.error.push(&err)?,
+    }
+
+    if let Some(theme) = &config.display.syntax_theme {
+        if let Err(err) = ui::syntax_text::set_theme(theme) {
+            app.error.push(&err)?;
+        }
+    }
+
+    match History::load() {
+        Ok(history) => app.set_history(history),
+        Err(err) => app.error.push(&err)?,
+    }
+
+    match Favorites::load() {
+        Ok(favorites) => app.set_favorites(favorites),
+        Err(err) => app.error.push(&err)?,
+    }
+
+    if (value.config.restore || config.restore_session) && !value.config.tutorial {
+        match Session::load() {
+            Ok(Some(session)) => {
+                if let Err(err) = app.restore_session(session).await {
+                    app.error.push(&err)?
+                }
+            }
+            Ok(None) => (),
+            Err(err) => app.error.push(&err)?,
+        }
+    }
+
     loop {
         terminal.draw(|f| {
             if let Err(err) = app.draw(f) {
@@ -52,8 +112,13 @@ async fn main() -> anyhow::Result<()> {
                 std::process::exit(1);
             }
         })?;
-        match events.next()? {
-            Event::Input(key) => match app.event(key).await {
+        let event = if app.is_busy() {
+            events.try_next()
+        } else {
+            Some(events.next()?)
+        };
+        match event {
+            Some(Event::Input(key)) => match app.event(key).await {
                 Ok(state) => {
                     if !state.is_consumed()
                         && (key == app.config.key_config.quit || key == app.config.key_config.exit)
@@ -61,14 +126,78 @@ async fn main() -> anyhow::Result<()> {
                         break;
                     }
                 }
-                Err(err) => app.error.set(err.to_string())?,
+                Err(err) => app.report(&err)?,
             },
-            Event::Tick => (),
+            Some(Event::Tick) | None => {
+                if let Err(err) = app.expire_chord().await {
+                    app.report(&err)?
+                }
+                app.check_health().await;
+            }
+        }
+
+        if app.is_busy() {
+            if let Err(err) = app.step().await {
+                app.report(&err)?
+            }
+        }
+        app.announce();
+
+        if let Some(query) = app.take_external_edit() {
+            events.pause();
+            shutdown_terminal();
+            let edited = editor::edit(&query);
+            enable_raw_mode()?;
+            io::stdout().execute(EnterAlternateScreen)?;
+            events.resume();
+            terminal.clear()?;
+            match edited {
+                Ok(query) => app.finish_external_edit(query),
+                Err(err) => app.error.push(&err)?,
+            }
         }
     }
 
+    // the tour must not replace the session of the real connections
+    let saved = if value.config.tutorial {
+        Ok(())
+    } else {
+        app.session().save().and(app.history().save())
+    };
     shutdown_terminal();
     terminal.show_cursor()?;
+    saved
+}
+
+async fn run_command(command: Command, config: &Config) -> Result<()> {
+    match command {
+        Command::SetPassword { connection } => {
+            if !config
+                .conn
+                .iter()
+                .any(|conn| conn.name() == Some(&connection))
+            {
+                anyhow::bail!("connection `{}` is not found in config.toml", connection);
+            }
+            let password = rpassword::prompt_password(format!("Password for {}: ", connection))?;
+            config::set_keyring_password(&connection, &password)?;
+            println!("Stored the password for `{}` in the keyring", connection);
+        }
+        Command::ExportPreset { path } => {
+            preset::export(&config.key_config, &config.display, &path)?;
+            println!(
+                "Wrote the keymap and display settings to {}",
+                path.display()
+            );
+        }
+        Command::ImportPreset { source } => {
+            let path = preset::import(&source).await?;
+            println!(
+                "Saved the preset to {}. key_bind.ron and [display] in config.toml still take precedence",
+                path.display()
+            );
+        }
+    }
     Ok(())
 }
 
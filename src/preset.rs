@@ -0,0 +1,115 @@
+use crate::config::{get_app_config_path, DisplayConfig, KeyConfig};
+use crate::key_bind::KeyBind;
+use anyhow::Context;
+use ron::extensions::Extensions;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The file in the config directory which an imported preset is saved to.
+pub const PRESET_FILE: &str = "preset.ron";
+
+/// A keymap and display settings which can be shared with others as one file.
+#[derive(Debug, Default, Deserialize)]
+pub struct Preset {
+    #[serde(default)]
+    pub key_config: KeyBind,
+    pub display: Option<DisplayConfig>,
+}
+
+/// The effective settings, written out in the format `Preset` reads.
+#[derive(Serialize)]
+struct ExportedPreset<'a> {
+    key_config: &'a KeyConfig,
+    display: &'a DisplayConfig,
+}
+
+impl Preset {
+    /// Returns an empty preset if none has been imported or it cannot be read.
+    pub fn load(path: PathBuf) -> Self {
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents).unwrap_or_else(|e| {
+                eprintln!("fail to parse preset file: {}", e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Keys may be written without `Some(...)`, as the exported file does.
+    fn parse(contents: &str) -> anyhow::Result<Self> {
+        Ok(ron::Options::default()
+            .with_default_extension(Extensions::IMPLICIT_SOME)
+            .from_str(contents)?)
+    }
+}
+
+/// Writes the keymap and the display settings in use to `path`.
+pub fn export(key_config: &KeyConfig, display: &DisplayConfig, path: &Path) -> anyhow::Result<()> {
+    let contents = ron::ser::to_string_pretty(
+        &ExportedPreset {
+            key_config,
+            display,
+        },
+        PrettyConfig::default().extensions(Extensions::IMPLICIT_SOME),
+    )?;
+    std::fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Reads a preset from a file or an http(s) URL and saves it to the config directory,
+/// returning where it was saved.
+pub async fn import(source: &str) -> anyhow::Result<PathBuf> {
+    let contents = if source.starts_with("http://") || source.starts_with("https://") {
+        reqwest::get(source)
+            .await?
+            .error_for_status()?
+            .text()
+            .await?
+    } else {
+        std::fs::read_to_string(source).with_context(|| format!("Failed to read {}", source))?
+    };
+    Preset::parse(&contents).with_context(|| format!("{} is not a zhobo preset", source))?;
+    let path = get_app_config_path()?.join(PRESET_FILE);
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ExportedPreset, Preset};
+    use crate::config::{DisplayConfig, KeyConfig, TreeIcons};
+    use crate::event::Key;
+
+    #[test]
+    fn test_exported_preset_is_read_back() {
+        let key_config = KeyConfig {
+            scroll_up: Key::Char('w'),
+            ..KeyConfig::default()
+        };
+        let display = DisplayConfig {
+            tree_icons: TreeIcons::Letters,
+            tree_counts: false,
+            ..DisplayConfig::default()
+        };
+        let contents = ron::to_string(&ExportedPreset {
+            key_config: &key_config,
+            display: &display,
+        })
+        .unwrap();
+
+        let preset = Preset::parse(&contents).unwrap();
+        assert_eq!(KeyConfig::from(preset.key_config), key_config);
+        assert_eq!(preset.display, Some(display));
+    }
+
+    #[test]
+    fn test_partial_preset() {
+        let preset =
+            Preset::parse("(key_config: (quit: Char('x'), scroll_down: Some(Char('n'))))").unwrap();
+        assert_eq!(preset.key_config.quit, Some(Key::Char('x')));
+        assert_eq!(preset.key_config.scroll_down, Some(Key::Char('n')));
+        assert_eq!(preset.key_config.scroll_up, None);
+        assert_eq!(preset.display, None);
+        assert!(Preset::parse("(key_config: (quit: 1))").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::components::tab::Tab;
+use crate::config::get_app_config_path;
+use crate::tree::{Database, Table};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The table which was open when zhobo quit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTable {
+    pub database: String,
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+impl SessionTable {
+    pub fn new(database: &Database, table: &Table) -> Self {
+        Self {
+            database: database.name.clone(),
+            schema: table.schema.clone(),
+            name: table.name.clone(),
+        }
+    }
+
+    pub fn path(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}.{}", self.database, schema, self.name),
+            None => format!("{}.{}", self.database, self.name),
+        }
+    }
+}
+
+/// Where the user was when zhobo quit, saved so that `--restore` can resume there.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// See `Connection::session_key`.
+    pub connection: Option<String>,
+    #[serde(default)]
+    pub expanded: Vec<String>,
+    pub table: Option<SessionTable>,
+    pub tab: Tab,
+    #[serde(default)]
+    pub sql: String,
+    #[serde(default)]
+    pub filter: String,
+    /// The tables opened on the connection, the latest first.
+    #[serde(default)]
+    pub recent_tables: Vec<SessionTable>,
+}
+
+impl Session {
+    fn path() -> anyhow::Result<PathBuf> {
+        Ok(get_app_config_path()?.join("session.json"))
+    }
+
+    /// Returns the saved session, or `None` if zhobo has never quit normally.
+    pub fn load() -> anyhow::Result<Option<Self>> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        let session = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(session))
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        std::fs::write(Self::path()?, serde_json::to_string_pretty(self)?)
+            .context("Failed to save the session")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Session, SessionTable, Tab};
+
+    #[test]
+    fn test_session_round_trip() {
+        let session = Session {
+            connection: Some("mysql Foo DB".to_string()),
+            expanded: vec!["foo".to_string(), "foo/public".to_string()],
+            table: Some(SessionTable {
+                database: "foo".to_string(),
+                schema: Some("public".to_string()),
+                name: "users".to_string(),
+            }),
+            tab: Tab::Sql,
+            sql: "SELECT 1".to_string(),
+            filter: "id > 1".to_string(),
+            recent_tables: vec![SessionTable {
+                database: "foo".to_string(),
+                schema: None,
+                name: "orders".to_string(),
+            }],
+        };
+        let json = serde_json::to_string(&session).unwrap();
+        assert_eq!(serde_json::from_str::<Session>(&json).unwrap(), session);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::tree::{
-    databasetreeitems::DatabaseTreeItems, error::Result, item::DatabaseTreeItemKind,
-    tree_iter::TreeIterator,
+    databasetreeitems::DatabaseTreeItems, error::Result, item::DatabaseTreeItem,
+    item::DatabaseTreeItemKind, tree_iter::TreeIterator,
 };
-use crate::tree::{Database, Table};
+use crate::tree::{Database, Table, TableKind};
 use std::collections::BTreeSet;
 
 #[derive(Copy, Clone, Debug)]
@@ -66,6 +66,10 @@ impl DatabaseTree {
         self.visual_selection.as_ref()
     }
 
+    pub fn selected_item(&self) -> Option<&DatabaseTreeItem> {
+        self.selection.map(|index| &self.items.tree_items[index])
+    }
+
     pub fn selected_table(&self) -> Option<(Database, Table)> {
         self.selection.and_then(|index| {
             let item = &self.items.tree_items[index];
@@ -79,6 +83,226 @@ impl DatabaseTree {
         })
     }
 
+    /// Returns the name of the database of the selected item.
+    pub fn selected_database(&self) -> Option<&str> {
+        self.selected_item().map(|item| match item.kind() {
+            DatabaseTreeItemKind::Database { name, .. } => name.as_str(),
+            DatabaseTreeItemKind::Schema { database, .. }
+            | DatabaseTreeItemKind::Table { database, .. } => database.name.as_str(),
+        })
+    }
+
+    /// Returns the tables of `database`, without its views, routines and partitions.
+    pub fn tables(&self, database: &str) -> Vec<Table> {
+        self.items
+            .tree_items
+            .iter()
+            .filter_map(|item| match item.kind() {
+                DatabaseTreeItemKind::Table { database: d, table }
+                    if d.name == database
+                        && table.kind == TableKind::Table
+                        && table.partition_of.is_none() =>
+                {
+                    Some(table.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the tables and views of `database` as `schema.table`, or `table` if it has no
+    /// schemas.
+    pub fn table_names(&self, database: &str) -> Vec<String> {
+        self.items
+            .tree_items
+            .iter()
+            .filter_map(|item| match item.kind() {
+                DatabaseTreeItemKind::Table { database: d, table }
+                    if d.name == database && !table.kind.is_routine() =>
+                {
+                    Some(match &table.schema {
+                        Some(schema) => format!("{}.{}", schema, table.name),
+                        None => table.name.clone(),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the ids of the expanded databases and schemas, `database` or `database/schema`.
+    pub fn expanded_nodes(&self) -> Vec<String> {
+        self.items
+            .tree_items
+            .iter()
+            .filter_map(|item| match item.kind() {
+                DatabaseTreeItemKind::Database { name, collapsed } if !collapsed => {
+                    Some(name.clone())
+                }
+                DatabaseTreeItemKind::Schema {
+                    database,
+                    schema,
+                    collapsed,
+                } if !collapsed => Some(format!("{}/{}", database.name, schema.name)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Expands the databases and schemas whose ids are in `nodes`.
+    pub fn expand_nodes(&mut self, nodes: &[String]) {
+        for index in 0..self.items.len() {
+            let id = match self.items.tree_items[index].kind() {
+                DatabaseTreeItemKind::Database { name, .. } => name.clone(),
+                DatabaseTreeItemKind::Schema {
+                    database, schema, ..
+                } => format!("{}/{}", database.name, schema.name),
+                DatabaseTreeItemKind::Table { .. } => continue,
+            };
+            if nodes.contains(&id) {
+                self.items.expand(index, false);
+            }
+        }
+        self.visual_selection = self.calc_visual_selection();
+    }
+
+    /// Replaces the schemas and tables of `database`, e.g. once they are read when it is
+    /// expanded. The selection stays on the database if it was under it.
+    pub fn set_children(&mut self, database: &Database) -> Result<()> {
+        let old = match self.items.set_children(database)? {
+            Some(old) => old,
+            None => return Ok(()),
+        };
+        let new_end = old.start
+            + 1
+            + self.items.tree_items[old.start + 1..]
+                .iter()
+                .take_while(|item| !item.is_database())
+                .count();
+        self.selection = self.selection.map(|selection| {
+            if old.contains(&selection) {
+                old.start
+            } else if selection >= old.end {
+                selection - old.end + new_end
+            } else {
+                selection
+            }
+        });
+        self.visual_selection = self.calc_visual_selection();
+        Ok(())
+    }
+
+    /// Selects a table and expands its database and schema. Returns false if it is not in the tree.
+    pub fn select_table(&mut self, database: &str, schema: Option<&str>, table: &str) -> bool {
+        let index = self.items.tree_items.iter().position(|item| {
+            matches!(
+                item.kind(),
+                DatabaseTreeItemKind::Table { database: d, table: t }
+                    if d.name == database && t.schema.as_deref() == schema && t.name == table
+            )
+        });
+        match index {
+            Some(index) => {
+                let mut parents = vec![database.to_string()];
+                if let Some(schema) = schema {
+                    parents.push(format!("{}/{}", database, schema));
+                }
+                self.expand_nodes(&parents);
+                self.expand_partitioned_parents(index);
+                self.selection = Some(index);
+                self.visual_selection = self.calc_visual_selection();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Expands the partitioned tables which the partition at `index` is under.
+    fn expand_partitioned_parents(&mut self, index: usize) {
+        let mut indent = self.items.tree_items[index].info().indent();
+        let mut parents = vec![];
+        for i in (0..index).rev() {
+            let item = &self.items.tree_items[i];
+            if !item.kind().is_table() {
+                break;
+            }
+            if item.info().indent() < indent {
+                indent = item.info().indent();
+                if item.is_partitions_collapsed() {
+                    parents.push(i);
+                }
+            }
+        }
+        // the outermost first, since expanding it shows the inner ones collapsed
+        for i in parents.into_iter().rev() {
+            self.items.expand(i, false);
+        }
+        self.visual_selection = self.calc_visual_selection();
+    }
+
+    /// Selects a database, or one of its schemas if `schema` is given.
+    pub fn select_node(&mut self, database: &str, schema: Option<&str>) -> bool {
+        let index = self
+            .items
+            .tree_items
+            .iter()
+            .position(|item| match (item.kind(), schema) {
+                (DatabaseTreeItemKind::Database { name, .. }, None) => name == database,
+                (
+                    DatabaseTreeItemKind::Schema {
+                        database: d,
+                        schema: s,
+                        ..
+                    },
+                    Some(schema),
+                ) => d.name == database && s.name == schema,
+                _ => false,
+            });
+        match index {
+            Some(index) => {
+                if schema.is_some() {
+                    self.expand_nodes(&[database.to_string()]);
+                }
+                self.selection = Some(index);
+                self.visual_selection = self.calc_visual_selection();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Selects the first table whose `database.schema.table` ends with `path`, e.g. `users` or
+    /// `public.users`.
+    pub fn select_table_path(&mut self, path: &str) -> bool {
+        let path = path.split('.').collect::<Vec<_>>();
+        let found = self
+            .items
+            .tree_items
+            .iter()
+            .find_map(|item| match item.kind() {
+                DatabaseTreeItemKind::Table { database, table } => {
+                    let full_path = [
+                        Some(database.name.as_str()),
+                        table.schema.as_deref(),
+                        Some(table.name.as_str()),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                    full_path
+                        .ends_with(&path)
+                        .then(|| (database.name.clone(), table.clone()))
+                }
+                _ => None,
+            });
+        match found {
+            Some((database, table)) => {
+                self.select_table(&database, table.schema.as_deref(), &table.name)
+            }
+            None => false,
+        }
+    }
+
     pub fn move_selection(&mut self, dir: MoveSelection) -> bool {
         self.selection.map_or(false, |selection| {
             let new_index = match dir {
@@ -291,6 +515,11 @@ impl DatabaseTree {
             return Some(current_index);
         }
 
+        if item.is_partitioned() && !item.is_partitions_collapsed() {
+            self.items.collapse(current_index, false);
+            return Some(current_index);
+        }
+
         self.select_parent(current_index)
     }
 
@@ -329,6 +558,14 @@ impl DatabaseTree {
             return self.selection_updown(current_selection, false);
         }
 
+        if item.is_partitioned() {
+            if item.is_partitions_collapsed() {
+                self.items.expand(current_selection, false);
+                return Some(current_selection);
+            }
+            return self.selection_updown(current_selection, false);
+        }
+
         None
     }
 
@@ -343,7 +580,7 @@ impl DatabaseTree {
 
 #[cfg(test)]
 mod test {
-    use crate::tree::{Database, DatabaseTree, MoveSelection, Schema, Table};
+    use crate::tree::{Database, DatabaseTree, MoveSelection, Schema, Table, TableKind};
     use std::collections::BTreeSet;
 
     impl Table {
@@ -354,6 +591,8 @@ mod test {
                 update_time: None,
                 engine: None,
                 schema: None,
+                kind: TableKind::Table,
+                partition_of: None,
             }
         }
 
@@ -364,6 +603,8 @@ mod test {
                 update_time: None,
                 engine: None,
                 schema: Some(schema),
+                kind: TableKind::Table,
+                partition_of: None,
             }
         }
     }
@@ -410,6 +651,53 @@ mod test {
         assert_eq!(tree.selection, Some(2));
     }
 
+    #[test]
+    fn test_set_children() {
+        let items = vec![
+            Database::new("a".to_string(), vec![]),
+            Database::new("b".to_string(), vec![]),
+        ];
+        let mut tree = DatabaseTree::new(&items, &BTreeSet::new()).unwrap();
+
+        // the tables of `a` are read once it is expanded
+        assert!(tree.move_selection(MoveSelection::Right));
+        tree.set_children(&Database::new(
+            "a".to_string(),
+            vec![
+                Table::new("c".to_string()).into(),
+                Table::new("d".to_string()).into(),
+            ],
+        ))
+        .unwrap();
+        assert_eq!(tree.selection, Some(0));
+        assert_eq!(tree.expanded_nodes(), vec!["a".to_string()]);
+        assert!(tree.move_selection(MoveSelection::Down));
+        assert_eq!(
+            tree.selected_table().map(|(_, table)| table.name),
+            Some("c".to_string())
+        );
+
+        // reading them again keeps the selection on the database, or after it
+        tree.set_children(&Database::new(
+            "a".to_string(),
+            vec![Table::new("c".to_string()).into()],
+        ))
+        .unwrap();
+        assert_eq!(tree.selection, Some(0));
+        assert!(tree.select_node("b", None));
+        tree.set_children(&Database::new(
+            "a".to_string(),
+            vec![
+                Table::new("c".to_string()).into(),
+                Table::new("d".to_string()).into(),
+                Table::new("e".to_string()).into(),
+            ],
+        ))
+        .unwrap();
+        assert_eq!(tree.selected_database(), Some("b"));
+        assert_eq!(tree.table_names("a"), vec!["c", "d", "e"]);
+    }
+
     #[test]
     fn test_expand() {
         let items = vec![Database::new(
@@ -466,6 +754,116 @@ mod test {
         assert_eq!(tree.selection, Some(2));
     }
 
+    #[test]
+    fn test_expand_nodes_and_select_table() {
+        let items = vec![
+            Database::new(
+                "a".to_string(),
+                vec![Schema {
+                    name: "b".to_string(),
+                    tables: vec![Table::new_with_schema("c".to_string(), "b".to_string())],
+                }
+                .into()],
+            ),
+            Database::new("d".to_string(), vec![Table::new("e".to_string()).into()]),
+        ];
+
+        // a
+        //   b
+        //     c
+        // d
+        //   e
+
+        let mut tree = DatabaseTree::new(&items, &BTreeSet::new()).unwrap();
+        assert!(tree.expanded_nodes().is_empty());
+
+        tree.expand_nodes(&["a".to_string(), "a/b".to_string()]);
+        assert_eq!(tree.expanded_nodes(), vec!["a", "a/b"]);
+
+        assert!(tree.select_table("d", None, "e"));
+        assert_eq!(tree.selection, Some(4));
+        assert_eq!(tree.expanded_nodes(), vec!["a", "a/b", "d"]);
+        assert!(!tree.select_table("d", None, "f"));
+
+        assert!(tree.select_table_path("b.c"));
+        assert_eq!(tree.selection, Some(2));
+        assert!(!tree.select_table_path("d.c"));
+
+        assert!(tree.select_node("a", Some("b")));
+        assert_eq!(tree.selection, Some(1));
+    }
+
+    #[test]
+    fn test_partitions() {
+        let partition = |name: &str| Table {
+            partition_of: Some("orders".to_string()),
+            ..Table::new_with_schema(name.to_string(), "b".to_string())
+        };
+        let items = vec![Database::new(
+            "a".to_string(),
+            vec![Schema {
+                name: "b".to_string(),
+                tables: vec![
+                    partition("orders_2023"),
+                    Table::new_with_schema("orders".to_string(), "b".to_string()),
+                    Table::new_with_schema("users".to_string(), "b".to_string()),
+                    partition("orders_2024"),
+                ],
+            }
+            .into()],
+        )];
+
+        // a
+        //   b
+        //     orders
+        //       orders_2023
+        //       orders_2024
+        //     users
+
+        let mut tree = DatabaseTree::new(&items, &BTreeSet::new()).unwrap();
+        assert_eq!(tree.items.tree_items[3].kind().name(), "orders_2023");
+        assert_eq!(tree.items.tree_items[3].info().indent(), 3);
+        assert_eq!(tree.items.tree_items[2].children(), 2);
+
+        assert!(tree.move_selection(MoveSelection::Enter));
+        assert!(tree.move_selection(MoveSelection::Down));
+        assert!(tree.move_selection(MoveSelection::Enter));
+        assert!(tree.move_selection(MoveSelection::Down));
+        assert_eq!(tree.selection, Some(2));
+        // the partitions start collapsed
+        assert!(tree.move_selection(MoveSelection::Down));
+        assert_eq!(tree.selection, Some(5));
+
+        assert!(tree.move_selection(MoveSelection::Up));
+        assert!(tree.move_selection(MoveSelection::Right));
+        assert_eq!(tree.selection, Some(2));
+        assert!(tree.move_selection(MoveSelection::Down));
+        assert_eq!(tree.selection, Some(3));
+        assert!(tree.move_selection(MoveSelection::Left));
+        assert_eq!(tree.selection, Some(2));
+        assert!(tree.move_selection(MoveSelection::Left));
+        assert!(tree.items.tree_items[2].is_partitions_collapsed());
+        assert!(tree.move_selection(MoveSelection::Down));
+        assert_eq!(tree.selection, Some(5));
+
+        // selecting a partition shows it
+        assert!(tree.select_table("a", Some("b"), "orders_2024"));
+        assert_eq!(tree.selection, Some(4));
+        assert!(tree.items.tree_items[4].info().is_visible());
+        assert!(!tree.items.tree_items[2].is_partitions_collapsed());
+
+        // a diagram leaves the partitions out
+        assert_eq!(tree.selected_database(), Some("a"));
+        let tables = tree.tables("a");
+        assert_eq!(
+            tables
+                .iter()
+                .map(|table| table.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["orders", "users"]
+        );
+    }
+
     #[test]
     fn test_selection_multiple_up_down() {
         let items = vec![Database::new(
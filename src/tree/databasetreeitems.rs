@@ -1,7 +1,8 @@
 use crate::tree::{error::Result, treeitems_iter::TreeItemsIterator};
 use crate::tree::{item::DatabaseTreeItemKind, DatabaseTreeItem};
-use crate::tree::{Child, Database};
+use crate::tree::{Child, Database, Table};
 use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
 
 #[derive(Default)]
 pub struct DatabaseTreeItems {
@@ -49,13 +50,30 @@ impl DatabaseTreeItems {
             {
                 Self::push_databases(e, &mut items, &mut items_added, collapsed)?;
             }
+            let tables = e
+                .children
+                .iter()
+                .filter_map(|child| match child {
+                    Child::Table(table) => Some(table),
+                    Child::Schema(_) => None,
+                })
+                .collect::<Vec<&Table>>();
+            let partitions = Self::partitions(&tables);
             for child in &e.children {
                 match child {
-                    Child::Table(table) => items.push(DatabaseTreeItem::new_table(e, table)),
+                    Child::Table(table) => {
+                        if !Self::is_nested(table, &partitions) {
+                            Self::push_table(e, table, &partitions, 0, &mut items);
+                        }
+                    }
                     Child::Schema(schema) => {
                         items.push(DatabaseTreeItem::new_schema(e, schema, true));
-                        for table in &schema.tables {
-                            items.push(DatabaseTreeItem::new_table(e, table))
+                        let tables = schema.tables.iter().collect::<Vec<&Table>>();
+                        let partitions = Self::partitions(&tables);
+                        for table in tables {
+                            if !Self::is_nested(table, &partitions) {
+                                Self::push_table(e, table, &partitions, 0, &mut items);
+                            }
                         }
                     }
                 }
@@ -65,6 +83,82 @@ impl DatabaseTreeItems {
         Ok(items)
     }
 
+    /// Returns the partitions of each partitioned table among `tables`, by its name.
+    fn partitions<'a>(tables: &[&'a Table]) -> HashMap<&'a str, Vec<&'a Table>> {
+        let names = tables
+            .iter()
+            .map(|table| table.name.as_str())
+            .collect::<BTreeSet<&str>>();
+        let mut partitions: HashMap<&str, Vec<&Table>> = HashMap::new();
+        for table in tables {
+            if let Some(parent) = table.partition_of.as_deref() {
+                if let Some(parent) = names.get(parent).filter(|parent| **parent != table.name) {
+                    partitions.entry(parent).or_default().push(table);
+                }
+            }
+        }
+        partitions
+    }
+
+    /// Returns true if `table` is shown under its partitioned table rather than on its own.
+    fn is_nested(table: &Table, partitions: &HashMap<&str, Vec<&Table>>) -> bool {
+        table
+            .partition_of
+            .as_deref()
+            .and_then(|parent| partitions.get(parent))
+            .is_some_and(|siblings| siblings.iter().any(|sibling| sibling.name == table.name))
+    }
+
+    /// Pushes `table`, then its partitions one level deeper.
+    fn push_table(
+        database: &Database,
+        table: &Table,
+        partitions: &HashMap<&str, Vec<&Table>>,
+        depth: u8,
+        items: &mut Vec<DatabaseTreeItem>,
+    ) {
+        let children = partitions
+            .get(table.name.as_str())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        items.push(DatabaseTreeItem::new_partitioned_table(
+            database,
+            table,
+            children.len(),
+            depth,
+        ));
+        for partition in children {
+            Self::push_table(database, partition, partitions, depth + 1, items);
+        }
+    }
+
+    /// Replaces the schemas and tables under the database named like `database` with its
+    /// children, keeping it expanded if it was. Returns the range of the old items, or `None`
+    /// if the database is not in the list.
+    pub fn set_children(&mut self, database: &Database) -> Result<Option<Range<usize>>> {
+        let index = match self
+            .tree_items
+            .iter()
+            .position(|item| item.is_database() && item.kind().name() == database.name)
+        {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let end = self.tree_items[index + 1..]
+            .iter()
+            .position(DatabaseTreeItem::is_database)
+            .map_or(self.tree_items.len(), |offset| index + 1 + offset);
+        let expanded = !self.tree_items[index].kind().is_database_collapsed();
+        let visible = self.tree_items[index].info().is_visible();
+        let mut items = Self::create_items(std::slice::from_ref(database), &BTreeSet::new())?;
+        items[0].info_mut().set_visible(visible);
+        self.tree_items.splice(index..end, items);
+        if expanded {
+            self.expand(index, false);
+        }
+        Ok(Some(index..end))
+    }
+
     /// how many individual items are in the list
     pub fn len(&self) -> usize {
         self.tree_items.len()
@@ -100,6 +194,14 @@ impl DatabaseTreeItems {
     }
 
     pub fn collapse(&mut self, index: usize, recursive: bool) {
+        if self.tree_items[index].is_partitioned() {
+            self.tree_items[index].set_partitions_collapsed(true);
+            for i in self.partitions_range(index) {
+                self.tree_items[i].hide();
+            }
+            return;
+        }
+
         if self.tree_items[index].kind().is_database() {
             self.tree_items[index].collapse_database();
 
@@ -146,6 +248,16 @@ impl DatabaseTreeItems {
     }
 
     pub fn expand(&mut self, index: usize, recursive: bool) {
+        if self.tree_items[index].is_partitioned() {
+            self.tree_items[index].set_partitions_collapsed(false);
+            let range = self.partitions_range(index);
+            for i in range.clone() {
+                self.tree_items[i].show();
+            }
+            self.hide_collapsed_partitions(range);
+            return;
+        }
+
         if self.tree_items[index].kind().is_database() {
             self.tree_items[index].expand_database();
 
@@ -199,6 +311,35 @@ impl DatabaseTreeItems {
         }
     }
 
+    /// Returns the indices of the partitions under the partitioned table at `index`, including
+    /// the partitions of its partitions.
+    fn partitions_range(&self, index: usize) -> std::ops::Range<usize> {
+        let indent = self.tree_items[index].info().indent();
+        let end = self.tree_items[index + 1..]
+            .iter()
+            .position(|item| !item.kind().is_table() || item.info().indent() <= indent)
+            .map_or(self.tree_items.len(), |offset| index + 1 + offset);
+        index + 1..end
+    }
+
+    /// Hides the partitions of the collapsed partitioned tables in `range`.
+    fn hide_collapsed_partitions(&mut self, range: std::ops::Range<usize>) {
+        let mut hidden_below: Option<u8> = None;
+        for i in range {
+            let item = &mut self.tree_items[i];
+            if let Some(indent) = hidden_below {
+                if item.kind().is_table() && item.info().indent() > indent {
+                    item.hide();
+                    continue;
+                }
+                hidden_below = None;
+            }
+            if item.is_partitioned() && item.is_partitions_collapsed() {
+                hidden_below = Some(item.info().indent());
+            }
+        }
+    }
+
     fn update_visibility(&mut self, prefix: &DatabaseTreeItemKind, start_idx: usize) {
         let mut inner_collapsed: Option<DatabaseTreeItemKind> = None;
 
@@ -269,5 +410,6 @@ impl DatabaseTreeItems {
                 _ => (),
             }
         }
+        self.hide_collapsed_partitions(start_idx..self.tree_items.len());
     }
 }
@@ -101,6 +101,9 @@ impl DatabaseTreeItemKind {
 pub struct DatabaseTreeItem {
     info: TreeItemInfo,
     kind: DatabaseTreeItemKind,
+    children: usize,
+    /// True while the partitions of a partitioned table are hidden.
+    partitions_collapsed: bool,
 }
 
 impl DatabaseTreeItem {
@@ -111,9 +114,26 @@ impl DatabaseTreeItem {
                 database: database.clone(),
                 table: table.clone(),
             },
+            children: 0,
+            partitions_collapsed: false,
         }
     }
 
+    /// A table with `partitions` right under it, `depth` levels below its schema or database
+    /// if it is a partition itself.
+    pub fn new_partitioned_table(
+        database: &Database,
+        table: &Table,
+        partitions: usize,
+        depth: u8,
+    ) -> Self {
+        let mut item = Self::new_table(database, table);
+        item.info.indent += depth;
+        item.children = partitions;
+        item.partitions_collapsed = partitions > 0;
+        item
+    }
+
     pub fn new_schema(database: &Database, schema: &Schema, _collapsed: bool) -> Self {
         Self {
             info: TreeItemInfo::new(1, false),
@@ -122,6 +142,8 @@ impl DatabaseTreeItem {
                 schema: schema.clone(),
                 collapsed: true,
             },
+            children: schema.tables.len(),
+            partitions_collapsed: false,
         }
     }
 
@@ -132,6 +154,8 @@ impl DatabaseTreeItem {
                 name: database.name.to_string(),
                 collapsed: true,
             },
+            children: database.children.len(),
+            partitions_collapsed: false,
         }
     }
 
@@ -156,6 +180,26 @@ impl DatabaseTreeItem {
         &self.kind
     }
 
+    /// Returns the number of schemas or tables right under a database or schema, or of the
+    /// partitions right under a partitioned table.
+    pub const fn children(&self) -> usize {
+        self.children
+    }
+
+    pub const fn is_partitioned(&self) -> bool {
+        self.kind.is_table() && self.children > 0
+    }
+
+    pub const fn is_partitions_collapsed(&self) -> bool {
+        self.partitions_collapsed
+    }
+
+    pub fn set_partitions_collapsed(&mut self, collapsed: bool) {
+        if self.is_partitioned() {
+            self.partitions_collapsed = collapsed;
+        }
+    }
+
     pub fn collapse_database(&mut self) {
         if let DatabaseTreeItemKind::Database { name, .. } = &self.kind {
             self.kind = DatabaseTreeItemKind::Database {
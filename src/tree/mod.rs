@@ -7,8 +7,33 @@ mod treeitems_iter;
 
 pub use crate::tree::{
     databasetree::DatabaseTree, databasetree::MoveSelection, item::DatabaseTreeItem,
+    item::DatabaseTreeItemKind,
 };
 
+use crate::database::Dialect;
+use serde::{Deserialize, Serialize};
+
+/// The databases which the servers of `dialect` keep for themselves, compared in lower case.
+fn system_databases(dialect: Dialect) -> &'static [&'static str] {
+    match dialect {
+        Dialect::MySql => &["information_schema", "performance_schema", "mysql", "sys"],
+        Dialect::Postgres => &["template0", "template1"],
+        Dialect::ClickHouse => &["information_schema", "system"],
+        Dialect::Sqlite => &[],
+    }
+}
+
+/// The schemas which PostgreSQL and the servers speaking its SQL keep for themselves.
+const SYSTEM_SCHEMAS: &[&str] = &[
+    "information_schema",
+    "pg_catalog",
+    "pg_toast",
+    "pg_internal",
+    "pg_automv",
+    "crdb_internal",
+    "pg_extension",
+];
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Database {
     pub name: String,
@@ -40,6 +65,31 @@ impl Database {
             children,
         }
     }
+
+    /// Returns true for a database of the server itself, e.g. `information_schema` of MySQL.
+    pub fn is_system(&self, dialect: Dialect) -> bool {
+        system_databases(dialect).contains(&self.name.to_ascii_lowercase().as_str())
+    }
+
+    /// Drops the system schemas and tables, e.g. `pg_catalog` of PostgreSQL or
+    /// `sqlite_sequence` of SQLite.
+    pub fn retain_user_objects(&mut self, dialect: Dialect) {
+        self.children.retain_mut(|child| match child {
+            Child::Schema(schema) => {
+                schema.tables.retain(|table| !table.is_system(dialect));
+                !schema.is_system(dialect)
+            }
+            Child::Table(table) => !table.is_system(dialect),
+        });
+    }
+
+    /// Drops the schemas other than `schema`, keeping the tables outside of schemas.
+    pub fn retain_schema(&mut self, schema: &str) {
+        self.children.retain(|child| match child {
+            Child::Schema(s) => s.name == schema,
+            Child::Table(_) => true,
+        });
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -48,6 +98,53 @@ pub struct Schema {
     pub tables: Vec<Table>,
 }
 
+impl Schema {
+    pub fn is_system(&self, dialect: Dialect) -> bool {
+        dialect == Dialect::Postgres
+            && (SYSTEM_SCHEMAS.contains(&self.name.as_str())
+                || self.name.starts_with("pg_temp_")
+                || self.name.starts_with("pg_toast_temp_"))
+    }
+}
+
+/// What a relation in the tree is, which decides its icon and how its definition is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TableKind {
+    #[default]
+    Table,
+    View,
+    MaterializedView,
+    Function,
+    Procedure,
+    /// A sequence of PostgreSQL, MariaDB or CockroachDB, which reads as a table of one row with
+    /// its state.
+    Sequence,
+}
+
+impl TableKind {
+    pub const fn is_view(self) -> bool {
+        matches!(self, Self::View | Self::MaterializedView)
+    }
+
+    /// Returns true for a stored function or procedure, which has a source instead of rows.
+    pub const fn is_routine(self) -> bool {
+        matches!(self, Self::Function | Self::Procedure)
+    }
+}
+
+impl std::fmt::Display for TableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Table => write!(f, "table"),
+            Self::View => write!(f, "view"),
+            Self::MaterializedView => write!(f, "materialized view"),
+            Self::Function => write!(f, "function"),
+            Self::Procedure => write!(f, "procedure"),
+            Self::Sequence => write!(f, "sequence"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Table {
     pub name: String,
@@ -55,4 +152,99 @@ pub struct Table {
     pub update_time: Option<chrono::DateTime<chrono::Utc>>,
     pub engine: Option<String>,
     pub schema: Option<String>,
+    pub kind: TableKind,
+    /// The partitioned table this one is a partition of, under which the tree shows it.
+    pub partition_of: Option<String>,
+}
+
+impl Table {
+    /// Returns the name with its schema, e.g. `audit.users`, which tells apart the tables of the
+    /// same name in different schemas of a database.
+    pub fn qualified_name(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", schema, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Returns true for a table which SQLite keeps for itself, e.g. `sqlite_sequence`.
+    pub fn is_system(&self, dialect: Dialect) -> bool {
+        dialect == Dialect::Sqlite && self.name.starts_with("sqlite_")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Child, Database, Dialect, Schema, Table, TableKind};
+
+    fn table(name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+            create_time: None,
+            update_time: None,
+            engine: None,
+            schema: None,
+            kind: TableKind::Table,
+            partition_of: None,
+        }
+    }
+
+    #[test]
+    fn test_qualified_name() {
+        assert_eq!(table("users").qualified_name(), "users");
+        let audit = Table {
+            schema: Some("audit".to_string()),
+            ..table("users")
+        };
+        assert_eq!(audit.qualified_name(), "audit.users");
+    }
+
+    #[test]
+    fn test_retain_user_objects() {
+        let empty = |name: &str| Database::new(name.to_string(), vec![]);
+        assert!(empty("INFORMATION_SCHEMA").is_system(Dialect::MySql));
+        assert!(!empty("shop").is_system(Dialect::MySql));
+        // the names are only kept by the server of one dialect
+        assert!(empty("system").is_system(Dialect::ClickHouse));
+        assert!(!empty("system").is_system(Dialect::MySql));
+        assert!(!empty("mysql").is_system(Dialect::Postgres));
+
+        let schema = |name: &str| -> Child {
+            Schema {
+                name: name.to_string(),
+                tables: vec![table("orders")],
+            }
+            .into()
+        };
+        let mut database = Database::new(
+            "shop".to_string(),
+            vec![
+                schema("pg_catalog"),
+                schema("pg_toast_temp_1"),
+                schema("public"),
+                table("sqlite_sequence").into(),
+                table("users").into(),
+            ],
+        );
+        let mut sqlite = database.clone();
+        sqlite.retain_user_objects(Dialect::Sqlite);
+        assert_eq!(
+            sqlite.children,
+            vec![
+                schema("pg_catalog"),
+                schema("pg_toast_temp_1"),
+                schema("public"),
+                table("users").into()
+            ]
+        );
+        database.retain_user_objects(Dialect::Postgres);
+        assert_eq!(
+            database.children,
+            vec![
+                schema("public"),
+                table("sqlite_sequence").into(),
+                table("users").into()
+            ]
+        );
+    }
 }
@@ -0,0 +1,69 @@
+use crate::config::{Config, Connection};
+use crate::database::script::split_statements;
+use crate::database::{Pool, SqlitePool};
+use std::collections::BTreeMap;
+
+/// The sample database of `zhobo --tutorial`.
+const SAMPLE: &str = "
+CREATE TABLE departments (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    location TEXT
+);
+CREATE TABLE employees (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    email TEXT UNIQUE,
+    department_id INTEGER REFERENCES departments (id),
+    salary INTEGER NOT NULL,
+    hired_on TEXT
+);
+CREATE INDEX employees_department_id ON employees (department_id);
+CREATE VIEW department_salaries AS
+    SELECT d.name AS department, COUNT(*) AS employees, AVG(e.salary) AS average_salary
+    FROM employees e JOIN departments d ON d.id = e.department_id
+    GROUP BY d.name;
+INSERT INTO departments (id, name, location) VALUES
+    (1, 'Engineering', 'Tokyo'),
+    (2, 'Sales', 'Osaka'),
+    (3, 'Support', 'Remote'),
+    (4, 'Finance', 'Tokyo');
+INSERT INTO employees (name, email, department_id, salary, hired_on) VALUES
+    ('Aiko Tanaka', 'aiko@example.com', 1, 8200, '2019-04-01'),
+    ('Ben Carter', 'ben@example.com', 1, 7400, '2020-10-15'),
+    ('Chen Wei', 'chen@example.com', 1, 6900, '2021-01-11'),
+    ('Dana Silva', 'dana@example.com', 2, 5600, '2018-06-20'),
+    ('Emil Novak', 'emil@example.com', 2, 6100, '2022-03-07'),
+    ('Fatima Khan', 'fatima@example.com', 3, 4800, '2023-02-01'),
+    ('Goro Sato', 'goro@example.com', 3, 4500, '2023-09-18'),
+    ('Hana Kim', 'hana@example.com', 4, 7100, '2017-11-30'),
+    ('Ivan Petrov', 'ivan@example.com', 1, 9100, '2016-05-23'),
+    ('Julia Rossi', 'julia@example.com', 2, 5900, '2024-01-08'),
+    ('Kenji Mori', NULL, 3, 5200, '2021-07-12'),
+    ('Lena Braun', 'lena@example.com', 4, 6600, '2020-02-17');
+";
+
+/// Creates the sample database in the temporary directory and returns a config which only
+/// connects to it.
+pub async fn config(mut config: Config) -> anyhow::Result<Config> {
+    let path = std::env::temp_dir().join("zhobo-tutorial.db");
+    // an empty file is an empty database
+    std::fs::write(&path, "")?;
+    let connection = Connection::sqlite("tutorial", path);
+    let pool = SqlitePool::new(
+        &connection.database_url()?,
+        connection.limit_size,
+        connection.timeout_second,
+        connection.memory_budget(config.memory_budget()),
+        &BTreeMap::new(),
+        None,
+    )
+    .await?;
+    for statement in split_statements(SAMPLE) {
+        pool.execute(&statement).await?;
+    }
+    pool.close().await;
+    config.conn = vec![connection];
+    config.restore_session = false;
+    Ok(config)
+}
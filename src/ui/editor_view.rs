@@ -0,0 +1,272 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, StatefulWidget, Widget},
+};
+use std::ops::Range;
+use unicode_width::UnicodeWidthChar;
+
+fn char_width(c: char) -> u16 {
+    c.width().unwrap_or(0) as u16
+}
+
+/// A row of the editor, which is a line or a part of a wrapped line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Row {
+    line: usize,
+    /// Whether the row starts its line, which the line number is shown next to.
+    first: bool,
+    /// The indices of the characters of the row in the text.
+    start: usize,
+    end: usize,
+}
+
+/// Splits the text into its lines, wrapping them at `width` if `wrap` is set.
+fn layout(text: &[char], width: u16, wrap: bool) -> Vec<Row> {
+    let mut rows = Vec::new();
+    let mut line_start = 0;
+    for (line, chars) in text.split(|c| *c == '\n').enumerate() {
+        let mut start = line_start;
+        let mut row_width = 0;
+        let mut first = true;
+        for (offset, c) in chars.iter().enumerate() {
+            let c_width = char_width(*c);
+            if wrap && row_width > 0 && row_width + c_width > width {
+                rows.push(Row {
+                    line,
+                    first,
+                    start,
+                    end: line_start + offset,
+                });
+                first = false;
+                start = line_start + offset;
+                row_width = 0;
+            }
+            row_width += c_width;
+        }
+        rows.push(Row {
+            line,
+            first,
+            start,
+            end: line_start + chars.len(),
+        });
+        line_start += chars.len() + 1;
+    }
+    rows
+}
+
+/// Returns the row of the cursor and its column in the row.
+fn cursor_position(text: &[char], rows: &[Row], cursor: usize) -> (usize, u16) {
+    let row = rows
+        .iter()
+        .rposition(|row| row.start <= cursor)
+        .unwrap_or(0);
+    let column = text[rows[row].start..cursor.min(text.len())]
+        .iter()
+        .copied()
+        .map(char_width)
+        .sum();
+    (row, column)
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EditorViewState {
+    /// The first row which is shown.
+    top: usize,
+    /// The first column which is shown if the lines are not wrapped.
+    left: u16,
+    cursor: (u16, u16),
+}
+
+impl EditorViewState {
+    /// Returns where the cursor was drawn last.
+    pub fn cursor(&self) -> (u16, u16) {
+        self.cursor
+    }
+}
+
+/// The text of the SQL editor with line numbers, the line of the cursor highlighted and the
+/// long lines wrapped or scrolled sideways.
+pub struct EditorView<'a> {
+    text: &'a [char],
+    cursor: usize,
+    block: Option<Block<'a>>,
+    line_numbers: bool,
+    highlight_line: bool,
+    wrap: bool,
+    selection: Option<Range<usize>>,
+}
+
+impl<'a> EditorView<'a> {
+    pub fn new(text: &'a [char], cursor: usize) -> Self {
+        Self {
+            text,
+            cursor,
+            block: None,
+            line_numbers: false,
+            highlight_line: false,
+            wrap: true,
+            selection: None,
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    pub fn highlight_line(mut self, highlight_line: bool) -> Self {
+        self.highlight_line = highlight_line;
+        self
+    }
+
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Highlights the characters of `selection`.
+    pub fn selection(mut self, selection: Option<Range<usize>>) -> Self {
+        self.selection = selection;
+        self
+    }
+}
+
+impl<'a> StatefulWidget for EditorView<'a> {
+    type State = EditorViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut EditorViewState) {
+        let area = match self.block.take() {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+        let lines = self.text.iter().filter(|c| **c == '\n').count() + 1;
+        let gutter = if self.line_numbers {
+            (lines.to_string().len() as u16 + 1).min(area.width)
+        } else {
+            0
+        };
+        let width = area.width - gutter;
+        if width == 0 || area.height == 0 {
+            return;
+        }
+
+        let rows = layout(self.text, width, self.wrap);
+        let (cursor_row, cursor_column) = cursor_position(self.text, &rows, self.cursor);
+        let height = area.height as usize;
+        if cursor_row < state.top {
+            state.top = cursor_row;
+        } else if cursor_row >= state.top + height {
+            state.top = cursor_row + 1 - height;
+        }
+        state.top = state.top.min(rows.len() - 1);
+        if self.wrap {
+            state.left = 0;
+        } else if cursor_column < state.left {
+            state.left = cursor_column;
+        } else if cursor_column >= state.left + width {
+            state.left = cursor_column + 1 - width;
+        }
+
+        let cursor_line = rows[cursor_row].line;
+        for (y, row) in rows[state.top..].iter().take(height).enumerate() {
+            let y = area.y + y as u16;
+            let current = row.line == cursor_line;
+            if self.highlight_line && current {
+                buf.set_style(
+                    Rect::new(area.x, y, area.width, 1),
+                    Style::default().bg(Color::DarkGray),
+                );
+            }
+            if gutter > 0 && row.first {
+                buf.set_stringn(
+                    area.x,
+                    y,
+                    format!("{:>width$}", row.line + 1, width = gutter as usize - 1),
+                    gutter as usize - 1,
+                    Style::default().fg(if current {
+                        Color::Yellow
+                    } else {
+                        Color::DarkGray
+                    }),
+                );
+            }
+            let text_row = Rect::new(area.x + gutter, y, width, 1);
+            let mut column = 0;
+            let mut visible = String::new();
+            for (index, c) in (row.start..row.end).zip(&self.text[row.start..row.end]) {
+                let c_width = char_width(*c);
+                if column >= state.left {
+                    visible.push(*c);
+                    if self
+                        .selection
+                        .as_ref()
+                        .is_some_and(|selection| selection.contains(&index))
+                    {
+                        buf.set_style(
+                            Rect::new(text_row.x + column - state.left, y, c_width, 1)
+                                .intersection(text_row),
+                            Style::default().add_modifier(Modifier::REVERSED),
+                        );
+                    }
+                }
+                column += c_width;
+            }
+            buf.set_stringn(text_row.x, y, visible, width as usize, Style::default());
+        }
+
+        state.cursor = (
+            area.x + gutter + (cursor_column - state.left).min(width - 1),
+            area.y + (cursor_row - state.top) as u16,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cursor_position, layout, Row};
+
+    #[test]
+    fn test_layout() {
+        let text = "SELECT 1\n\nFROM t".chars().collect::<Vec<char>>();
+        let rows = layout(&text, 4, true);
+        assert_eq!(
+            rows.iter()
+                .map(|row| (row.line, row.first, row.start, row.end))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, true, 0, 4),
+                (0, false, 4, 8),
+                (1, true, 9, 9),
+                (2, true, 10, 14),
+                (2, false, 14, 16),
+            ]
+        );
+        assert_eq!(cursor_position(&text, &rows, 8), (1, 4));
+        assert_eq!(cursor_position(&text, &rows, 9), (2, 0));
+        assert_eq!(cursor_position(&text, &rows, 16), (4, 2));
+
+        let rows = layout(&text, 4, false);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows[2],
+            Row {
+                line: 2,
+                first: true,
+                start: 10,
+                end: 16
+            }
+        );
+        assert_eq!(cursor_position(&text, &rows, 16), (2, 6));
+    }
+}
@@ -2,6 +2,7 @@ use crate::config::KeyConfig;
 use crate::event::Key;
 use crate::tree::MoveSelection;
 
+pub mod editor_view;
 pub mod reflow;
 pub mod scrollbar;
 pub mod scrolllist;
@@ -1,12 +1,59 @@
+use crate::config::expand_path;
+use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
 use ratatui::text::{Line, Span};
 use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
 use syntect::{
     highlighting::{
-        FontStyle, HighlightState, Highlighter, RangedHighlightIterator, Style, ThemeSet,
+        FontStyle, HighlightState, Highlighter, RangedHighlightIterator, Style, Theme, ThemeSet,
     },
     parsing::{ParseState, ScopeStack, SyntaxSet},
 };
 
+const DEFAULT_THEME: &str = "base16-eighties.dark";
+
+static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Sets the theme from the config: the name of a theme bundled with syntect, or the path of a
+/// `.tmTheme` file. It can only be set once, and the default theme is used if it fails.
+pub fn set_theme(theme: &str) -> Result<()> {
+    let _ = THEME.set(load_theme(theme)?);
+    Ok(())
+}
+
+fn load_theme(theme: &str) -> Result<Theme> {
+    if theme.ends_with(".tmTheme") {
+        let path = expand_path(Path::new(theme)).unwrap_or_else(|| theme.into());
+        return ThemeSet::get_theme(&path)
+            .with_context(|| format!("Failed to load the syntax theme {}", path.display()));
+    }
+    let mut themes = ThemeSet::load_defaults().themes;
+    themes.remove(theme).ok_or_else(|| {
+        anyhow!(
+            "Unknown syntax theme `{}`, use one of {} or the path of a .tmTheme file",
+            theme,
+            themes.keys().join(", ")
+        )
+    })
+}
+
+/// The syntaxes and the theme are loaded once, on the first highlighted text.
+fn syntax_set() -> &'static SyntaxSet {
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_nonewlines)
+}
+
+fn theme() -> &'static Theme {
+    THEME.get_or_init(|| {
+        ThemeSet::load_defaults()
+            .themes
+            .remove(DEFAULT_THEME)
+            .unwrap_or_default()
+    })
+}
+
 struct SyntaxLine {
     items: Vec<(Style, usize, Range<usize>)>,
 }
@@ -18,16 +65,15 @@ pub struct SyntaxText {
 
 impl SyntaxText {
     pub fn new(text: String) -> Self {
-        let syntax_set: SyntaxSet = SyntaxSet::load_defaults_nonewlines();
-        let theme_set: ThemeSet = ThemeSet::load_defaults();
+        let syntax_set = syntax_set();
 
         let mut state = ParseState::new(syntax_set.find_syntax_by_extension("sql").unwrap());
-        let highlighter = Highlighter::new(&theme_set.themes["base16-eighties.dark"]);
+        let highlighter = Highlighter::new(theme());
         let mut syntax_lines: Vec<SyntaxLine> = Vec::new();
         let mut highlight_state = HighlightState::new(&highlighter, ScopeStack::new());
 
         for (number, line) in text.lines().enumerate() {
-            let ops = state.parse_line(line, &syntax_set);
+            let ops = state.parse_line(line, syntax_set);
             if let Ok(vec) = ops {
                 let iter =
                     RangedHighlightIterator::new(&mut highlight_state, &vec, line, &highlighter);
@@ -105,3 +151,16 @@ fn syntact_style_to_tui(style: &Style) -> ratatui::style::Style {
 
     res
 }
+
+#[cfg(test)]
+mod test {
+    use super::load_theme;
+
+    #[test]
+    fn test_load_theme() {
+        assert!(load_theme("InspiredGitHub").is_ok());
+        let error = load_theme("missing").unwrap_err().to_string();
+        assert!(error.contains("base16-ocean.dark"), "{}", error);
+        assert!(load_theme("/nonexistent/missing.tmTheme").is_err());
+    }
+}
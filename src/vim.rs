@@ -0,0 +1,683 @@
+use crate::event::Key;
+use std::ops::Range;
+
+/// The mode of the vim emulation of the SQL editor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+impl Mode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "NORMAL",
+            Self::Insert => "INSERT",
+            Self::Visual => "VISUAL",
+            Self::VisualLine => "VISUAL LINE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Delete,
+    Yank,
+    Change,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'd' => Some(Self::Delete),
+            'y' => Some(Self::Yank),
+            'c' => Some(Self::Change),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Motion {
+    Left,
+    Right,
+    Down,
+    Up,
+    WordForward,
+    WordBackward,
+    WordEnd,
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    /// `gg`, or the line of the count.
+    Top,
+    /// `G`, or the line of the count.
+    Bottom,
+}
+
+impl Motion {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'h' => Some(Self::Left),
+            'l' | ' ' => Some(Self::Right),
+            'j' => Some(Self::Down),
+            'k' => Some(Self::Up),
+            'w' => Some(Self::WordForward),
+            'b' => Some(Self::WordBackward),
+            'e' => Some(Self::WordEnd),
+            '0' => Some(Self::LineStart),
+            '^' => Some(Self::FirstNonBlank),
+            '$' => Some(Self::LineEnd),
+            'G' => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+
+    /// Whether an operator with the motion works on whole lines.
+    fn is_linewise(&self) -> bool {
+        matches!(self, Self::Down | Self::Up | Self::Top | Self::Bottom)
+    }
+}
+
+/// Text which was deleted or yanked, to be put back with `p`.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Register {
+    text: Vec<char>,
+    /// Whether whole lines were taken, which are put on lines of their own.
+    linewise: bool,
+}
+
+/// Vim-like modal editing of the text of the SQL editor: normal, insert and visual modes, the
+/// basic motions, the `d`, `y` and `c` operators, `p` and `u`.
+pub struct Vim {
+    mode: Mode,
+    count: Option<usize>,
+    /// The operator waiting for its motion and its count.
+    operator: Option<(Operator, Option<usize>)>,
+    /// Whether `g` waits for the second `g` of `gg`.
+    g: bool,
+    /// Where the selection of the visual mode started.
+    anchor: usize,
+    register: Register,
+    /// The text and the cursor before each change, the newest last.
+    undo: Vec<(Vec<char>, usize)>,
+}
+
+impl Default for Vim {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Normal,
+            count: None,
+            operator: None,
+            g: false,
+            anchor: 0,
+            register: Register::default(),
+            undo: Vec::new(),
+        }
+    }
+}
+
+impl Vim {
+    const MAX_UNDO: usize = 100;
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the characters selected in the visual mode.
+    pub fn selection(&self, text: &[char], cursor: usize) -> Option<Range<usize>> {
+        let (from, to) = (self.anchor.min(cursor), self.anchor.max(cursor));
+        match self.mode {
+            Mode::Visual => Some(from..(to + 1).min(text.len())),
+            Mode::VisualLine => Some(line_start(text, from)..line_end(text, to)),
+            Mode::Normal | Mode::Insert => None,
+        }
+    }
+
+    /// Handles `key` on the text and the cursor of the editor. Returns false for the keys
+    /// which the editor handles itself, e.g. the characters typed in the insert mode.
+    pub fn event(&mut self, key: Key, text: &mut Vec<char>, cursor: &mut usize) -> bool {
+        if self.mode == Mode::Insert {
+            if key != Key::Esc {
+                return false;
+            }
+            self.mode = Mode::Normal;
+            if *cursor > line_start(text, *cursor) {
+                *cursor -= 1;
+            }
+            return true;
+        }
+        let c = match key {
+            Key::Char(c) => c,
+            Key::Backspace => 'h',
+            Key::Delete => 'x',
+            Key::Esc if self.mode != Mode::Normal || self.is_pending() => {
+                self.reset();
+                self.mode = Mode::Normal;
+                *cursor = clamp(text, *cursor);
+                return true;
+            }
+            _ => return false,
+        };
+        self.command(c, text, cursor);
+        if self.mode != Mode::Insert {
+            *cursor = clamp(text, *cursor);
+        }
+        true
+    }
+
+    fn is_pending(&self) -> bool {
+        self.count.is_some() || self.operator.is_some() || self.g
+    }
+
+    fn reset(&mut self) {
+        self.count = None;
+        self.operator = None;
+        self.g = false;
+    }
+
+    fn command(&mut self, c: char, text: &mut Vec<char>, cursor: &mut usize) {
+        let motion = if std::mem::take(&mut self.g) {
+            if c != 'g' {
+                self.reset();
+                return;
+            }
+            Some(Motion::Top)
+        } else if c == 'g' {
+            self.g = true;
+            return;
+        } else if c.is_ascii_digit() && (c != '0' || self.count.is_some()) {
+            self.count = Some(self.count.unwrap_or(0) * 10 + c.to_digit(10).unwrap() as usize);
+            return;
+        } else {
+            Motion::from_char(c)
+        };
+        let count = self.count.take();
+        if let Some(motion) = motion {
+            match self.operator.take() {
+                Some((operator, operator_count)) => {
+                    let count = match (operator_count, count) {
+                        (None, None) => None,
+                        (a, b) => Some(a.unwrap_or(1) * b.unwrap_or(1)),
+                    };
+                    let target = target(motion, text, *cursor, count, true);
+                    let (range, linewise) = operator_range(motion, text, *cursor, target);
+                    self.apply(operator, range, linewise, text, cursor);
+                }
+                None => *cursor = target(motion, text, *cursor, count, false),
+            }
+            return;
+        }
+        let operator = Operator::from_char(c);
+        if let Some(selection) = self.selection(text, *cursor) {
+            let linewise = self.mode == Mode::VisualLine;
+            let operator = match c {
+                'x' => Some(Operator::Delete),
+                'Y' => Some(Operator::Yank),
+                _ => operator,
+            };
+            match (operator, c) {
+                (Some(operator), _) => {
+                    self.mode = Mode::Normal;
+                    self.apply(operator, selection, linewise, text, cursor)
+                }
+                (None, 'v') if self.mode == Mode::Visual => self.mode = Mode::Normal,
+                (None, 'V') if self.mode == Mode::VisualLine => self.mode = Mode::Normal,
+                (None, 'v') => self.mode = Mode::Visual,
+                (None, 'V') => self.mode = Mode::VisualLine,
+                _ => (),
+            }
+            return;
+        }
+        if let Some((pending, operator_count)) = self.operator.take() {
+            // `dd`, `yy` and `cc` work on the line of the cursor and the lines below it
+            if operator == Some(pending) {
+                let lines = operator_count.unwrap_or(1) * count.unwrap_or(1);
+                let last = target(Motion::Down, text, *cursor, Some(lines - 1), true);
+                self.apply(pending, *cursor..last, true, text, cursor);
+            }
+            return;
+        }
+        if let Some(operator) = operator {
+            self.operator = Some((operator, count));
+            return;
+        }
+        let count = count.unwrap_or(1);
+        match c {
+            'i' => self.insert(text, *cursor),
+            'a' => {
+                self.insert(text, *cursor);
+                if *cursor < line_end(text, *cursor) {
+                    *cursor += 1;
+                }
+            }
+            'I' => {
+                self.insert(text, *cursor);
+                *cursor = first_non_blank(text, *cursor);
+            }
+            'A' => {
+                self.insert(text, *cursor);
+                *cursor = line_end(text, *cursor);
+            }
+            'o' | 'O' => {
+                self.insert(text, *cursor);
+                *cursor = if c == 'o' {
+                    line_end(text, *cursor)
+                } else {
+                    line_start(text, *cursor)
+                };
+                text.insert(*cursor, '\n');
+                if c == 'o' {
+                    *cursor += 1;
+                }
+            }
+            'x' => {
+                let end = (*cursor + count).min(line_end(text, *cursor));
+                if end > *cursor {
+                    self.apply(Operator::Delete, *cursor..end, false, text, cursor);
+                }
+            }
+            'D' | 'C' => {
+                let operator = if c == 'D' {
+                    Operator::Delete
+                } else {
+                    Operator::Change
+                };
+                let end = line_end(text, *cursor);
+                self.apply(operator, *cursor..end, false, text, cursor);
+            }
+            'Y' => {
+                let last = target(Motion::Down, text, *cursor, Some(count - 1), true);
+                self.apply(Operator::Yank, *cursor..last, true, text, cursor);
+            }
+            'p' | 'P' => self.put(c == 'p', count, text, cursor),
+            'v' | 'V' => {
+                self.anchor = *cursor;
+                self.mode = if c == 'v' {
+                    Mode::Visual
+                } else {
+                    Mode::VisualLine
+                };
+            }
+            'u' => {
+                if let Some((previous, previous_cursor)) = self.undo.pop() {
+                    *text = previous;
+                    *cursor = previous_cursor;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn save_undo(&mut self, text: &[char], cursor: usize) {
+        self.undo.push((text.to_vec(), cursor));
+        if self.undo.len() > Self::MAX_UNDO {
+            self.undo.remove(0);
+        }
+    }
+
+    fn insert(&mut self, text: &[char], cursor: usize) {
+        self.save_undo(text, cursor);
+        self.mode = Mode::Insert;
+    }
+
+    /// Runs `operator` on `range`, or on the lines which it spans if `linewise`.
+    fn apply(
+        &mut self,
+        operator: Operator,
+        range: Range<usize>,
+        linewise: bool,
+        text: &mut Vec<char>,
+        cursor: &mut usize,
+    ) {
+        let (start, end) = if linewise {
+            (
+                line_start(text, range.start),
+                line_end(text, range.end.max(range.start)),
+            )
+        } else {
+            (range.start, range.end.min(text.len()))
+        };
+        self.register = Register {
+            text: text[start..end].to_vec(),
+            linewise,
+        };
+        match operator {
+            Operator::Yank => *cursor = start,
+            Operator::Change => {
+                self.insert(text, *cursor);
+                text.drain(start..end);
+                *cursor = start;
+            }
+            Operator::Delete => {
+                self.save_undo(text, *cursor);
+                if linewise {
+                    // the line break after the lines, or before them if they are the last
+                    if end < text.len() {
+                        text.drain(start..=end);
+                    } else {
+                        text.drain(start.saturating_sub(1)..end);
+                    }
+                    *cursor = line_start(text, start.min(text.len()));
+                } else {
+                    text.drain(start..end);
+                    *cursor = start;
+                }
+            }
+        }
+    }
+
+    /// Puts the register after the cursor, or before it for `P`.
+    fn put(&mut self, after: bool, count: usize, text: &mut Vec<char>, cursor: &mut usize) {
+        if self.register.text.is_empty() && !self.register.linewise {
+            return;
+        }
+        self.save_undo(text, *cursor);
+        let mut put = Vec::new();
+        for index in 0..count {
+            if index > 0 && self.register.linewise {
+                put.push('\n');
+            }
+            put.extend(&self.register.text);
+        }
+        let position = if self.register.linewise {
+            if after {
+                put.insert(0, '\n');
+                line_end(text, *cursor)
+            } else {
+                put.push('\n');
+                line_start(text, *cursor)
+            }
+        } else if after && *cursor < line_end(text, *cursor) {
+            *cursor + 1
+        } else {
+            *cursor
+        };
+        let length = put.len();
+        text.splice(position..position, put);
+        *cursor = match (self.register.linewise, after) {
+            (true, true) => position + 1,
+            (true, false) => position,
+            (false, _) => position + length - 1,
+        };
+    }
+}
+
+fn line_start(text: &[char], cursor: usize) -> usize {
+    text[..cursor.min(text.len())]
+        .iter()
+        .rposition(|c| *c == '\n')
+        .map_or(0, |index| index + 1)
+}
+
+fn line_end(text: &[char], cursor: usize) -> usize {
+    let cursor = cursor.min(text.len());
+    text[cursor..]
+        .iter()
+        .position(|c| *c == '\n')
+        .map_or(text.len(), |index| cursor + index)
+}
+
+fn first_non_blank(text: &[char], cursor: usize) -> usize {
+    let end = line_end(text, cursor);
+    let mut index = line_start(text, cursor);
+    while index < end && matches!(text[index], ' ' | '\t') {
+        index += 1;
+    }
+    index
+}
+
+/// Keeps the cursor of the normal mode on a character rather than after the end of its line.
+fn clamp(text: &[char], cursor: usize) -> usize {
+    let cursor = cursor.min(text.len());
+    let (start, end) = (line_start(text, cursor), line_end(text, cursor));
+    if end > start && cursor >= end {
+        end - 1
+    } else {
+        cursor
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+fn word_forward(text: &[char], mut index: usize) -> usize {
+    if index >= text.len() {
+        return text.len();
+    }
+    let class = char_class(text[index]);
+    if class != CharClass::Space {
+        while index < text.len() && char_class(text[index]) == class {
+            index += 1;
+        }
+    }
+    while index < text.len() && char_class(text[index]) == CharClass::Space {
+        index += 1;
+    }
+    index
+}
+
+fn word_backward(text: &[char], mut index: usize) -> usize {
+    while index > 0 && char_class(text[index - 1]) == CharClass::Space {
+        index -= 1;
+    }
+    if index == 0 {
+        return 0;
+    }
+    let class = char_class(text[index - 1]);
+    while index > 0 && char_class(text[index - 1]) == class {
+        index -= 1;
+    }
+    index
+}
+
+fn word_end(text: &[char], index: usize) -> usize {
+    let mut index = index + 1;
+    while index < text.len() && char_class(text[index]) == CharClass::Space {
+        index += 1;
+    }
+    if index >= text.len() {
+        return text.len().saturating_sub(1);
+    }
+    let class = char_class(text[index]);
+    while index + 1 < text.len() && char_class(text[index + 1]) == class {
+        index += 1;
+    }
+    index
+}
+
+/// Returns the start of the line `number`, counted from 1, or of the last line.
+fn line_at(text: &[char], number: usize) -> usize {
+    let mut start = 0;
+    for _ in 1..number {
+        let end = line_end(text, start);
+        if end == text.len() {
+            break;
+        }
+        start = end + 1;
+    }
+    start
+}
+
+/// Returns where `motion` moves the cursor `count` times. An operator may move the cursor
+/// right after the end of the line, e.g. for `dl` at the last character.
+fn target(
+    motion: Motion,
+    text: &[char],
+    cursor: usize,
+    count: Option<usize>,
+    operator: bool,
+) -> usize {
+    let repeat = |step: &dyn Fn(usize) -> usize| {
+        (0..count.unwrap_or(1)).fold(cursor, |index, _| step(index))
+    };
+    match motion {
+        Motion::Left => repeat(&|index| index.max(line_start(text, cursor) + 1) - 1),
+        Motion::Right => {
+            let end = line_end(text, cursor);
+            let last = if operator { end } else { end.saturating_sub(1) };
+            repeat(&|index| (index + 1).min(last.max(cursor)))
+        }
+        Motion::Down | Motion::Up => {
+            let column = cursor - line_start(text, cursor);
+            let start =
+                (0..count.unwrap_or(1)).fold(line_start(text, cursor), |start, _| match motion {
+                    Motion::Down if line_end(text, start) < text.len() => line_end(text, start) + 1,
+                    Motion::Up if start > 0 => line_start(text, start - 1),
+                    _ => start,
+                });
+            (start + column).min(line_end(text, start))
+        }
+        Motion::WordForward => repeat(&|index| word_forward(text, index)),
+        Motion::WordBackward => repeat(&|index| word_backward(text, index)),
+        Motion::WordEnd => repeat(&|index| word_end(text, index)),
+        Motion::LineStart => line_start(text, cursor),
+        Motion::FirstNonBlank => first_non_blank(text, cursor),
+        Motion::LineEnd => line_end(text, cursor).saturating_sub(1),
+        Motion::Top => line_at(text, count.unwrap_or(1)),
+        Motion::Bottom => line_at(text, count.unwrap_or(usize::MAX)),
+    }
+}
+
+/// Returns the text which an operator with `motion` works on and whether it is whole lines.
+fn operator_range(
+    motion: Motion,
+    text: &[char],
+    cursor: usize,
+    target: usize,
+) -> (Range<usize>, bool) {
+    let (from, to) = (cursor.min(target), cursor.max(target));
+    if motion.is_linewise() {
+        return (from..to, true);
+    }
+    let range = match motion {
+        Motion::WordEnd => from..(to + 1).min(text.len()),
+        Motion::LineEnd => cursor..line_end(text, cursor),
+        // `dw` at the last word of a line keeps the line break
+        Motion::WordForward if line_end(text, cursor) > cursor => {
+            cursor..target.min(line_end(text, cursor))
+        }
+        _ => from..to,
+    };
+    (range, false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Mode, Vim};
+    use crate::event::Key;
+
+    /// Types `keys` in the normal mode at `cursor` and returns the text and the cursor.
+    fn run(vim: &mut Vim, text: &str, cursor: usize, keys: &str) -> (String, usize) {
+        let mut text = text.chars().collect::<Vec<char>>();
+        let mut cursor = cursor;
+        for c in keys.chars() {
+            let key = if c == '\u{1b}' {
+                Key::Esc
+            } else {
+                Key::Char(c)
+            };
+            if !vim.event(key, &mut text, &mut cursor) {
+                text.insert(cursor, c);
+                cursor += 1;
+            }
+        }
+        (text.into_iter().collect(), cursor)
+    }
+
+    #[test]
+    fn test_motions() {
+        let text = "SELECT a, b\n  FROM t\nWHERE x = 1";
+        let mut vim = Vim::default();
+        assert_eq!(run(&mut vim, text, 0, "w").1, 7);
+        assert_eq!(run(&mut vim, text, 0, "2w").1, 8);
+        assert_eq!(run(&mut vim, text, 0, "e").1, 5);
+        assert_eq!(run(&mut vim, text, 8, "b").1, 7);
+        assert_eq!(run(&mut vim, text, 0, "$").1, 10);
+        assert_eq!(run(&mut vim, text, 10, "j").1, 19);
+        assert_eq!(run(&mut vim, text, 3, "j^").1, 14);
+        assert_eq!(run(&mut vim, text, 3, "G").1, 21);
+        assert_eq!(run(&mut vim, text, 25, "gg").1, 0);
+        assert_eq!(run(&mut vim, text, 0, "2G").1, 12);
+        assert_eq!(run(&mut vim, text, 4, "10l").1, 10);
+    }
+
+    #[test]
+    fn test_operators() {
+        let text = "SELECT a, b\n  FROM t\nWHERE x = 1";
+        let mut vim = Vim::default();
+        assert_eq!(
+            run(&mut vim, text, 0, "dw"),
+            ("a, b\n  FROM t\nWHERE x = 1".to_string(), 0)
+        );
+        assert_eq!(
+            run(&mut vim, text, 14, "dd"),
+            ("SELECT a, b\nWHERE x = 1".to_string(), 12)
+        );
+        assert_eq!(
+            run(&mut vim, text, 25, "dd"),
+            ("SELECT a, b\n  FROM t".to_string(), 12)
+        );
+        assert_eq!(
+            run(&mut vim, text, 0, "yyjp"),
+            (
+                "SELECT a, b\n  FROM t\nSELECT a, b\nWHERE x = 1".to_string(),
+                21
+            )
+        );
+        assert_eq!(
+            run(&mut vim, text, 7, "cwc\u{1b}"),
+            ("SELECT c, b\n  FROM t\nWHERE x = 1".to_string(), 7)
+        );
+        assert_eq!(
+            run(&mut vim, text, 7, "d$"),
+            ("SELECT \n  FROM t\nWHERE x = 1".to_string(), 6)
+        );
+        assert_eq!(run(&mut vim, text, 7, "xxxuuu").0, text);
+    }
+
+    #[test]
+    fn test_modes() {
+        let mut vim = Vim::default();
+        assert_eq!(
+            run(&mut vim, "SELECT 1", 7, "A, 2\u{1b}"),
+            ("SELECT 1, 2".to_string(), 10)
+        );
+        assert_eq!(vim.mode(), Mode::Normal);
+        assert_eq!(
+            run(&mut vim, "SELECT 1", 0, "oFROM t"),
+            ("SELECT 1\nFROM t".to_string(), 15)
+        );
+        assert_eq!(vim.mode(), Mode::Insert);
+
+        let mut vim = Vim::default();
+        let text = "SELECT a\nFROM t";
+        let (selected, cursor) = run(&mut vim, text, 7, "vj");
+        assert_eq!(selected, text);
+        assert_eq!(
+            vim.selection(&text.chars().collect::<Vec<char>>(), cursor),
+            Some(7..15)
+        );
+        assert_eq!(run(&mut vim, text, cursor, "d").0, "SELECT ");
+        assert_eq!(
+            run(&mut vim, text, 0, "VjyP").0,
+            "SELECT a\nFROM t\nSELECT a\nFROM t"
+        );
+    }
+}